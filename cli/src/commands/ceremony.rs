@@ -0,0 +1,347 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node::bft::helpers::{RemoteSigner, RemoteSignerConfig};
+use snarkvm::console::{
+    account::{Address, PrivateKey, Signature},
+    network::{CanaryV0, MainnetV0, Network, TestnetV0},
+};
+
+use anyhow::{anyhow, bail, ensure, Result};
+use clap::Parser;
+use core::str::FromStr;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// The file name of the private key, as written by the key ceremony.
+const PRIVATE_KEY_FILE: &str = "validator.key";
+/// The file name of the bonding manifest, as written by the key ceremony.
+const MANIFEST_FILE: &str = "bonding.json";
+/// The domain separator of the bonding manifest signature.
+const MANIFEST_DOMAIN: &str = "snarkos-bonding-manifest";
+/// The maximum commission rate of a validator, in percent.
+const MAX_COMMISSION: u8 = 100;
+
+/// Commands to run the key ceremony of a validator, so that its private key never exists on the production node.
+#[derive(Debug, Parser)]
+pub enum Ceremony {
+    /// Generates the validator account on an offline machine, and exports the bonding manifest
+    Generate {
+        /// Specify the network of the validator
+        #[clap(default_value = "0", long = "network")]
+        network: u16,
+        /// Specify the directory to write the private key and the bonding manifest to
+        #[clap(long = "output", value_name = "DIR")]
+        output: PathBuf,
+        /// Specify the withdrawal address of the validator
+        #[clap(long = "withdrawal-address")]
+        withdrawal_address: String,
+        /// Specify the commission rate of the validator, in percent
+        #[clap(default_value = "0", long = "commission")]
+        commission: u8,
+    },
+    /// Verifies the bonding manifest, and imports the remote signer configuration on the production node
+    Import {
+        /// Specify the path to the bonding manifest, as exported by 'generate'
+        #[clap(long = "manifest", value_name = "PATH")]
+        manifest: PathBuf,
+        /// Specify the HTTPS endpoint of the remote signer that holds the private key
+        #[clap(long = "remote-signer", value_name = "URL")]
+        remote_signer: String,
+        /// Specify the path to the PEM-encoded CA certificate of the remote signer
+        #[clap(long = "remote-signer-ca", value_name = "PATH")]
+        remote_signer_ca: PathBuf,
+        /// Specify the path to the PEM-encoded client certificate for the remote signer
+        #[clap(long = "remote-signer-cert", value_name = "PATH")]
+        remote_signer_cert: PathBuf,
+        /// Specify the path to the PEM-encoded (PKCS #8) client key for the remote signer
+        #[clap(long = "remote-signer-key", value_name = "PATH")]
+        remote_signer_key: PathBuf,
+        /// Specify the path to write the remote signer configuration to
+        #[clap(long = "output", value_name = "PATH")]
+        output: PathBuf,
+        /// Skips the check that the remote signer holds the key of the manifest address
+        #[clap(long = "skip-connect")]
+        skip_connect: bool,
+    },
+}
+
+/// The material needed to bond the validator, which is safe to carry off the offline machine.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BondingManifest {
+    /// The network ID.
+    pub network: u16,
+    /// The address of the validator.
+    pub address: String,
+    /// The withdrawal address of the validator.
+    pub withdrawal_address: String,
+    /// The commission rate of the validator, in percent.
+    pub commission: u8,
+    /// The signature of the validator over the manifest, which proves possession of the private key.
+    pub signature: String,
+}
+
+impl BondingManifest {
+    /// Returns the message that is signed by the validator.
+    fn message(network: u16, address: &str, withdrawal_address: &str, commission: u8) -> String {
+        format!("{MANIFEST_DOMAIN}:{network}:{address}:{withdrawal_address}:{commission}")
+    }
+
+    /// Signs a new bonding manifest with the given private key.
+    pub fn sign<N: Network>(
+        private_key: &PrivateKey<N>,
+        withdrawal_address: Address<N>,
+        commission: u8,
+    ) -> Result<Self> {
+        ensure!(commission <= MAX_COMMISSION, "The commission rate must be at most {MAX_COMMISSION}%");
+        let address = Address::try_from(private_key)?.to_string();
+        let withdrawal_address = withdrawal_address.to_string();
+        let message = Self::message(N::ID, &address, &withdrawal_address, commission);
+        let signature = private_key.sign_bytes(message.as_bytes(), &mut rand::thread_rng())?.to_string();
+        Ok(Self { network: N::ID, address, withdrawal_address, commission, signature })
+    }
+
+    /// Verifies the bonding manifest, and returns the address of the validator.
+    pub fn verify<N: Network>(&self) -> Result<Address<N>> {
+        ensure!(self.network == N::ID, "The bonding manifest is for network {}, not {}", self.network, N::ID);
+        ensure!(self.commission <= MAX_COMMISSION, "The commission rate must be at most {MAX_COMMISSION}%");
+        let address = Address::<N>::from_str(&self.address)?;
+        let _ = Address::<N>::from_str(&self.withdrawal_address)?;
+        let signature = Signature::<N>::from_str(&self.signature)?;
+        let message = Self::message(self.network, &self.address, &self.withdrawal_address, self.commission);
+        match signature.verify_bytes(&address, message.as_bytes()) {
+            true => Ok(address),
+            false => bail!("The signature of the bonding manifest is invalid"),
+        }
+    }
+
+    /// Loads the bonding manifest from the given path.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes =
+            fs::read(path).map_err(|e| anyhow!("Couldn't read the bonding manifest at {} - {e}", path.display()))?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+}
+
+impl Ceremony {
+    pub fn parse(self) -> Result<String> {
+        match self {
+            Self::Generate { network, output, withdrawal_address, commission } => match network {
+                MainnetV0::ID => Self::generate::<MainnetV0>(&output, &withdrawal_address, commission),
+                TestnetV0::ID => Self::generate::<TestnetV0>(&output, &withdrawal_address, commission),
+                CanaryV0::ID => Self::generate::<CanaryV0>(&output, &withdrawal_address, commission),
+                unknown_id => bail!("Unknown network ID ({unknown_id})"),
+            },
+            Self::Import {
+                manifest,
+                remote_signer,
+                remote_signer_ca,
+                remote_signer_cert,
+                remote_signer_key,
+                output,
+                skip_connect,
+            } => {
+                let manifest = BondingManifest::load(&manifest)?;
+                let config = RemoteSignerConfig {
+                    endpoint: remote_signer,
+                    address: manifest.address.clone(),
+                    ca_certificate: remote_signer_ca,
+                    certificate: remote_signer_cert,
+                    key: remote_signer_key,
+                };
+                match manifest.network {
+                    MainnetV0::ID => Self::import::<MainnetV0>(&manifest, &config, &output, skip_connect),
+                    TestnetV0::ID => Self::import::<TestnetV0>(&manifest, &config, &output, skip_connect),
+                    CanaryV0::ID => Self::import::<CanaryV0>(&manifest, &config, &output, skip_connect),
+                    unknown_id => bail!("Unknown network ID ({unknown_id})"),
+                }
+            }
+        }
+    }
+
+    /// Generates the validator account, and writes the private key and the bonding manifest to the given directory.
+    fn generate<N: Network>(output: &Path, withdrawal_address: &str, commission: u8) -> Result<String> {
+        let withdrawal_address = Address::<N>::from_str(withdrawal_address)
+            .map_err(|_| anyhow!("Failed to parse a valid withdrawal address"))?;
+        let key_path = output.join(PRIVATE_KEY_FILE);
+        // Ensure an existing private key is never overwritten.
+        ensure!(!key_path.exists(), "The private key at {} already exists", key_path.display());
+
+        // Sample the private key, and sign the bonding manifest.
+        let private_key = PrivateKey::<N>::new(&mut rand::thread_rng())?;
+        let manifest = BondingManifest::sign(&private_key, withdrawal_address, commission)?;
+
+        // Write the private key, readable only by the owner.
+        create_private_dir(output)?;
+        write_private_file(&key_path, private_key.to_string().as_bytes())?;
+        // Write the bonding manifest.
+        let manifest_path = output.join(MANIFEST_FILE);
+        fs::write(&manifest_path, serde_json::to_vec_pretty(&manifest)?)?;
+
+        Ok(format!(
+            "✅ Generated the validator {} offline.\n\n\
+             Keep '{}' on this machine, and load it only into the remote signer.\n\
+             Carry '{}' to the production node, and bond the validator with commission {}% and withdrawal address {}.",
+            manifest.address,
+            key_path.display(),
+            manifest_path.display(),
+            manifest.commission,
+            manifest.withdrawal_address,
+        ))
+    }
+
+    /// Verifies the bonding manifest, and writes the remote signer configuration to the given path.
+    fn import<N: Network>(
+        manifest: &BondingManifest,
+        config: &RemoteSignerConfig,
+        output: &Path,
+        skip_connect: bool,
+    ) -> Result<String> {
+        // Ensure the manifest was signed by the validator.
+        let address = manifest.verify::<N>()?;
+        // Ensure the remote signer holds the key of the validator.
+        if !skip_connect {
+            let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+            runtime
+                .block_on(RemoteSigner::<N>::connect_with_config(config))
+                .map_err(|e| anyhow!("Failed to verify the remote signer at '{}' - {e}", config.endpoint))?;
+        }
+        // Write the remote signer configuration.
+        config.save(output)?;
+        Ok(format!(
+            "✅ Imported the remote signer for the validator {address}.\n\n\
+             Start the validator with '--remote-signer-config {}'.",
+            output.display()
+        ))
+    }
+}
+
+/// Creates the given directory, readable only by the owner.
+fn create_private_dir(path: &Path) -> Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(target_family = "unix")]
+    {
+        use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+        builder.mode(0o700);
+        builder.create(path)?;
+        // Ensure an existing directory is also restricted to the owner.
+        fs::set_permissions(path, fs::Permissions::from_mode(0o700))?;
+    }
+    #[cfg(not(target_family = "unix"))]
+    builder.create(path)?;
+    Ok(())
+}
+
+/// Writes the given bytes to a new file, readable only by the owner.
+fn write_private_file(path: &Path, bytes: &[u8]) -> Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(target_family = "unix")]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    Ok(options.open(path)?.write_all(bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::TestRng;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_bonding_manifest() {
+        let rng = &mut TestRng::default();
+
+        let private_key = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        let withdrawal_address = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        let manifest = BondingManifest::sign(&private_key, withdrawal_address, 10).unwrap();
+        assert_eq!(manifest.verify::<CurrentNetwork>().unwrap(), Address::try_from(&private_key).unwrap());
+
+        // Ensure a tampered manifest is rejected.
+        let mut tampered = manifest.clone();
+        tampered.commission = 0;
+        assert!(tampered.verify::<CurrentNetwork>().is_err());
+        let mut tampered = manifest.clone();
+        tampered.withdrawal_address = manifest.address.clone();
+        assert!(tampered.verify::<CurrentNetwork>().is_err());
+        // Ensure the manifest is bound to its network.
+        assert!(manifest.verify::<TestnetV0>().is_err());
+        // Ensure the commission rate is bounded.
+        assert!(BondingManifest::sign(&private_key, withdrawal_address, MAX_COMMISSION + 1).is_err());
+    }
+
+    #[test]
+    fn test_generate_and_import() {
+        let rng = &mut TestRng::default();
+        let withdrawal_address = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+
+        let dir = std::env::temp_dir().join(format!("snarkos-ceremony-{}", rand::random::<u64>()));
+        let generate = Ceremony::Generate {
+            network: CurrentNetwork::ID,
+            output: dir.clone(),
+            withdrawal_address: withdrawal_address.to_string(),
+            commission: 5,
+        };
+        assert!(generate.parse().is_ok());
+
+        // Ensure the private key is written for the owner only.
+        #[cfg(target_family = "unix")]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&dir).unwrap().permissions().mode() & 0o777, 0o700);
+            assert_eq!(fs::metadata(dir.join(PRIVATE_KEY_FILE)).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+        // Ensure the manifest matches the private key.
+        let private_key =
+            PrivateKey::<CurrentNetwork>::from_str(&fs::read_to_string(dir.join(PRIVATE_KEY_FILE)).unwrap()).unwrap();
+        let manifest = BondingManifest::load(dir.join(MANIFEST_FILE)).unwrap();
+        assert_eq!(manifest.verify::<CurrentNetwork>().unwrap(), Address::try_from(&private_key).unwrap());
+
+        // Ensure the private key is never overwritten.
+        let generate = Ceremony::Generate {
+            network: CurrentNetwork::ID,
+            output: dir.clone(),
+            withdrawal_address: withdrawal_address.to_string(),
+            commission: 5,
+        };
+        assert!(generate.parse().is_err());
+
+        // Import the remote signer configuration.
+        let output = dir.join("remote-signer.json");
+        let import = Ceremony::Import {
+            manifest: dir.join(MANIFEST_FILE),
+            remote_signer: "https://signer:8443".to_string(),
+            remote_signer_ca: PathBuf::from("ca.pem"),
+            remote_signer_cert: PathBuf::from("cert.pem"),
+            remote_signer_key: PathBuf::from("key.pem"),
+            output: output.clone(),
+            skip_connect: true,
+        };
+        assert!(import.parse().is_ok());
+        let config = RemoteSignerConfig::load(&output).unwrap();
+        assert_eq!(config.address, manifest.address);
+        assert_eq!(config.endpoint, "https://signer:8443");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
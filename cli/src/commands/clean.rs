@@ -12,7 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use snarkos_node::bft::helpers::proposal_cache_path;
+use snarkos_node::{
+    analytics_index_path,
+    bft::{
+        helpers::proposal_cache_path,
+        storage_service::{BFTPersistentStorage, StorageService},
+    },
+    invalid_blocks_path,
+    light_headers_path,
+    mempool_path,
+    peer_cache_path,
+    pending_blocks_path,
+    pruned_height_path,
+    snapshot_path,
+};
+use snarkvm::console::network::{CanaryV0, MainnetV0, Network, TestnetV0};
 
 use aleo_std::StorageMode;
 use anyhow::{bail, Result};
@@ -32,23 +46,82 @@ pub struct Clean {
     /// Specify the path to a directory containing the ledger
     #[clap(long = "path")]
     pub path: Option<PathBuf>,
+    /// Remove only the BFT history, i.e. the transmissions of the past rounds, and keep the ledger
+    #[clap(long = "bft-history")]
+    pub bft_history: bool,
 }
 
 impl Clean {
     /// Cleans the snarkOS node storage.
     pub fn parse(self) -> Result<String> {
-        // Remove the current proposal cache file, if it exists.
-        let proposal_cache_path = proposal_cache_path(self.network, self.dev);
-        if proposal_cache_path.exists() {
-            if let Err(err) = std::fs::remove_file(&proposal_cache_path) {
-                bail!("Failed to remove the current proposal cache file at {}: {err}", proposal_cache_path.display());
-            }
+        // If only the BFT history is removed, keep the rest of the node storage.
+        if self.bft_history {
+            let storage_mode = match self.path {
+                Some(path) => StorageMode::Custom(path),
+                None => StorageMode::from(self.dev),
+            };
+            return match self.network {
+                MainnetV0::ID => Self::remove_bft_history::<MainnetV0>(storage_mode),
+                TestnetV0::ID => Self::remove_bft_history::<TestnetV0>(storage_mode),
+                CanaryV0::ID => Self::remove_bft_history::<CanaryV0>(storage_mode),
+                unknown_id => bail!("Unknown network ID ({unknown_id})"),
+            };
         }
-        // Remove the specified ledger from storage.
-        Self::remove_ledger(self.network, match self.path {
+        // Parse the storage mode.
+        let storage_mode = match self.path {
             Some(path) => StorageMode::Custom(path),
             None => StorageMode::from(self.dev),
-        })
+        };
+        // Remove the files that the node keeps next to its ledger, if they exist.
+        let paths = [
+            ("current proposal cache file", proposal_cache_path(self.network, self.dev)),
+            ("peer cache file", peer_cache_path(self.network, &storage_mode)),
+            ("memory pool file", mempool_path(self.network, &storage_mode)),
+            ("pruned height file", pruned_height_path(self.network, &storage_mode)),
+            ("invalid blocks file", invalid_blocks_path(self.network, &storage_mode)),
+            ("pending blocks file", pending_blocks_path(self.network, &storage_mode)),
+            ("analytics index", analytics_index_path(self.network, &storage_mode)),
+            ("state snapshot", snapshot_path(self.network, &storage_mode)),
+            ("light client headers", light_headers_path(self.network, &storage_mode)),
+        ];
+        for (name, path) in paths {
+            if !path.exists() {
+                continue;
+            }
+            let result = match path.is_dir() {
+                true => std::fs::remove_dir_all(&path),
+                false => std::fs::remove_file(&path),
+            };
+            if let Err(err) = result {
+                bail!("Failed to remove the {name} at {}: {err}", path.display());
+            }
+        }
+        // Remove the specified ledger from storage.
+        Self::remove_ledger(self.network, storage_mode)
+    }
+
+    /// Removes the BFT history, i.e. the stored transmissions of the past rounds, from the specified ledger.
+    fn remove_bft_history<N: Network>(mode: StorageMode) -> Result<String> {
+        // Construct the path to the ledger in storage.
+        let path = aleo_std::aleo_ledger_dir(N::ID, mode.clone());
+
+        // Prepare the path string.
+        let path_string = format!("(in \"{}\")", path.display()).dimmed();
+
+        // Check if the path to the ledger exists in storage.
+        if !path.exists() {
+            return Ok(format!("✅ No snarkOS node storage was found {path_string}"));
+        }
+        // Open the BFT storage, which fails if the node is running.
+        let storage = match BFTPersistentStorage::<N>::open(mode) {
+            Ok(storage) => storage,
+            Err(error) => bail!("Failed to open the BFT storage {path_string} - is the node running?\n{error}"),
+        };
+        // Remove all of the transmissions, as no certificate references them while the node is stopped.
+        // Note: On startup, the node restores the transmissions of the recent certificates from the ledger.
+        let transmission_ids = storage.find_unreferenced_transmissions(&|_| false);
+        let num_removed = storage.remove_unreferenced_transmissions(&transmission_ids, &|_| false);
+        Ok(format!("✅ Removed {num_removed} transmission(s) from the BFT history {path_string}"))
     }
 
     /// Removes the specified ledger from storage.
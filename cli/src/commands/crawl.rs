@@ -0,0 +1,159 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_account::Account;
+use snarkos_node::Crawler;
+use snarkvm::{
+    console::network::{CanaryV0, MainnetV0, Network, TestnetV0},
+    ledger::block::Block,
+    prelude::FromBytes,
+};
+
+use anyhow::{bail, Result};
+use clap::{Parser, ValueEnum};
+use colored::Colorize;
+use std::{net::SocketAddr, path::PathBuf, time::Duration};
+use tokio::runtime;
+
+/// The format of the topology report.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    /// A JSON report of the crawled peers.
+    Json,
+    /// A GraphML graph of the crawled peers and their connections.
+    Graphml,
+}
+
+/// Crawls the network from the given seed peers, and emits a topology report.
+#[derive(Clone, Debug, Parser)]
+pub struct Crawl {
+    /// Specify the network ID to crawl.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Specify the seed peer(s) to start crawling from, i.e. `--seed "ip1:port,ip2:port"`
+    #[clap(long = "seed", required = true)]
+    pub seed: String,
+    /// Specify the IP address and port for the crawler to listen on (defaults to a random port)
+    #[clap(default_value = "0.0.0.0:0", long = "node")]
+    pub node: SocketAddr,
+    /// Specify the maximum number of concurrent connections
+    #[clap(default_value = "50", long = "max-connections")]
+    pub max_connections: u16,
+    /// Specify the maximum duration of the crawl in seconds
+    #[clap(default_value = "120", long = "timeout")]
+    pub timeout: u64,
+    /// Specify the format of the topology report
+    #[clap(value_enum, default_value = "json", long = "format")]
+    pub format: ReportFormat,
+    /// Specify the path to write the topology report to (defaults to stdout)
+    #[clap(long = "output")]
+    pub output: Option<PathBuf>,
+    /// Enables development mode, relaxing the validity requirements for peer IPs.
+    #[clap(long)]
+    pub dev: bool,
+}
+
+impl Crawl {
+    /// Crawls the network, and emits a topology report.
+    pub fn parse(self) -> Result<String> {
+        // Crawl the given network.
+        match self.network {
+            MainnetV0::ID => self.crawl::<MainnetV0>(),
+            TestnetV0::ID => self.crawl::<TestnetV0>(),
+            CanaryV0::ID => self.crawl::<CanaryV0>(),
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        }
+    }
+
+    /// Returns the seed peers to start crawling from.
+    fn parse_seeds(&self) -> Result<Vec<SocketAddr>> {
+        let mut seeds = Vec::new();
+        for ip in self.seed.split(',').map(str::trim).filter(|ip| !ip.is_empty()) {
+            match ip.parse::<SocketAddr>() {
+                Ok(ip) => seeds.push(ip),
+                Err(e) => bail!("The seed IP '{ip}' is malformed: {e}"),
+            }
+        }
+        if seeds.is_empty() {
+            bail!("At least one seed peer must be provided");
+        }
+        Ok(seeds)
+    }
+
+    /// Crawls the network, and emits a topology report.
+    fn crawl<N: Network>(&self) -> Result<String> {
+        // Parse the seed peers.
+        let seeds = self.parse_seeds()?;
+        // Initialize an ephemeral account for the handshake.
+        let account = Account::<N>::new(&mut rand::thread_rng())?;
+        // Load the genesis block.
+        let genesis = Block::<N>::from_bytes_le(N::genesis_bytes())?;
+
+        // Initialize the runtime.
+        let runtime = runtime::Builder::new_multi_thread().enable_all().build()?;
+        // Crawl the network.
+        let topology = runtime.block_on(async move {
+            let crawler = Crawler::new(self.node, account, &seeds, genesis, self.max_connections, self.dev).await?;
+            Ok::<_, anyhow::Error>(crawler.crawl(Duration::from_secs(self.timeout)).await)
+        })?;
+
+        // Prepare the topology report.
+        let report = match self.format {
+            ReportFormat::Json => serde_json::to_string_pretty(&topology.to_json())?,
+            ReportFormat::Graphml => topology.to_graphml(),
+        };
+
+        // Prepare the summary.
+        let summary = format!(
+            "🕸️  Crawled {} peers ({} reachable)",
+            topology.peers().len().to_string().bold(),
+            topology.num_reachable().to_string().bold()
+        );
+
+        // Write the topology report.
+        match &self.output {
+            Some(path) => {
+                std::fs::write(path, report)?;
+                Ok(format!("{summary}, and wrote the report to {}", path.display()))
+            }
+            None => {
+                println!("{report}");
+                Ok(summary)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_seeds() {
+        let crawl = Crawl::try_parse_from(["snarkos", "--seed", "1.2.3.4:4130, 5.6.7.8:4130"]).unwrap();
+        assert_eq!(crawl.parse_seeds().unwrap(), vec![
+            SocketAddr::from(([1, 2, 3, 4], 4130)),
+            SocketAddr::from(([5, 6, 7, 8], 4130))
+        ]);
+        assert_eq!(crawl.format, ReportFormat::Json);
+
+        let crawl = Crawl::try_parse_from(["snarkos", "--seed", "1.2.3.4"]).unwrap();
+        assert!(crawl.parse_seeds().is_err());
+
+        let crawl = Crawl::try_parse_from(["snarkos", "--seed", "1.2.3.4:4130", "--format", "graphml"]).unwrap();
+        assert_eq!(crawl.format, ReportFormat::Graphml);
+
+        assert!(Crawl::try_parse_from(["snarkos"]).is_err());
+    }
+}
@@ -0,0 +1,458 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::commands::ledger::load_genesis;
+use snarkos_node::bft::ledger_service::committee_lookback_round;
+use snarkvm::{
+    console::network::{CanaryV0, MainnetV0, Network, TestnetV0},
+    ledger::{
+        authority::Authority,
+        narwhal::{BatchCertificate, BatchHeader},
+        store::helpers::rocksdb::ConsensusDB,
+        Ledger,
+    },
+};
+
+use aleo_std::StorageMode;
+use anyhow::{bail, ensure, Result};
+use clap::{Parser, ValueEnum};
+use indexmap::{IndexMap, IndexSet};
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt::Write, path::PathBuf};
+
+/// The format of the replayed DAG.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ReplayFormat {
+    /// A Graphviz graph, with the anchors and anomalies highlighted.
+    Dot,
+    /// A JSON object, with the certificates, the commit sequence, and the anomalies.
+    Json,
+}
+
+/// A committed certificate of the replayed DAG.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ReplayCertificate {
+    /// The certificate ID.
+    pub id: String,
+    /// The round of the certificate.
+    pub round: u64,
+    /// The author of the certificate.
+    pub author: String,
+    /// The IDs of the certificates of the previous round.
+    pub previous_certificate_ids: Vec<String>,
+    /// The number of transmissions in the batch.
+    pub num_transmissions: usize,
+    /// The number of signatures of the certificate, excluding the author.
+    pub num_signatures: usize,
+    /// The height of the block committing the certificate.
+    pub height: u32,
+}
+
+impl ReplayCertificate {
+    /// Initializes the replayed certificate from the given certificate, committed at the given height.
+    fn new<N: Network>(certificate: &BatchCertificate<N>, height: u32) -> Self {
+        Self {
+            id: certificate.id().to_string(),
+            round: certificate.round(),
+            author: certificate.author().to_string(),
+            previous_certificate_ids: certificate.previous_certificate_ids().iter().map(|id| id.to_string()).collect(),
+            num_transmissions: certificate.transmission_ids().len(),
+            num_signatures: certificate.signatures().count(),
+            height,
+        }
+    }
+}
+
+/// A commit of the replayed DAG, i.e. a block.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ReplayCommit {
+    /// The height of the block.
+    pub height: u32,
+    /// The round of the anchor, i.e. the leader certificate.
+    pub anchor_round: u64,
+    /// The ID of the anchor.
+    pub anchor_id: String,
+    /// The author of the anchor.
+    pub leader: String,
+    /// The number of committed certificates.
+    pub num_certificates: usize,
+}
+
+/// An anomaly of the replayed DAG.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReplayAnomaly {
+    /// An author has more than one committed certificate in a round.
+    Equivocation { round: u64, author: String, certificate_ids: Vec<String> },
+    /// A certificate references a previous certificate that was never committed, within the GC rounds.
+    MissingParent { certificate_id: String, parent_id: String },
+    /// A certificate is committed by more than one block.
+    DuplicateCommit { certificate_id: String, heights: Vec<u32> },
+    /// The anchor is not authored by the leader of its round.
+    UnexpectedLeader { height: u32, round: u64, expected: String, found: String },
+    /// The anchor round does not increase from the previous block.
+    NonIncreasingAnchor { height: u32, round: u64, previous_round: u64 },
+}
+
+impl ReplayAnomaly {
+    /// Returns the certificate IDs involved in the anomaly.
+    fn certificate_ids(&self) -> Vec<&str> {
+        match self {
+            Self::Equivocation { certificate_ids, .. } => certificate_ids.iter().map(String::as_str).collect(),
+            Self::MissingParent { certificate_id, .. } | Self::DuplicateCommit { certificate_id, .. } => {
+                vec![certificate_id.as_str()]
+            }
+            Self::UnexpectedLeader { .. } | Self::NonIncreasingAnchor { .. } => vec![],
+        }
+    }
+}
+
+/// The DAG reconstructed from the committed subdags of the blocks.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ReplayDag {
+    /// The committed certificates, by certificate ID.
+    pub certificates: IndexMap<String, ReplayCertificate>,
+    /// The commit sequence, in ascending height order.
+    pub commits: Vec<ReplayCommit>,
+    /// The anomalies of the DAG.
+    pub anomalies: Vec<ReplayAnomaly>,
+}
+
+impl ReplayDag {
+    /// Accounts for the next commit, in ascending height order, with the expected leader of its anchor round, if known.
+    fn add_commit(
+        &mut self,
+        commit: ReplayCommit,
+        certificates: Vec<ReplayCertificate>,
+        expected_leader: Option<String>,
+    ) {
+        // Ensure the anchor is authored by the leader of its round.
+        if let Some(expected) = expected_leader.filter(|expected| *expected != commit.leader) {
+            self.anomalies.push(ReplayAnomaly::UnexpectedLeader {
+                height: commit.height,
+                round: commit.anchor_round,
+                expected,
+                found: commit.leader.clone(),
+            });
+        }
+        // Ensure the anchor round increases from the previous block.
+        if let Some(previous) = self.commits.last().filter(|previous| previous.anchor_round >= commit.anchor_round) {
+            self.anomalies.push(ReplayAnomaly::NonIncreasingAnchor {
+                height: commit.height,
+                round: commit.anchor_round,
+                previous_round: previous.anchor_round,
+            });
+        }
+        // Insert the certificates, ensuring each one is committed only once.
+        for certificate in certificates {
+            match self.certificates.get(&certificate.id) {
+                Some(existing) => self.anomalies.push(ReplayAnomaly::DuplicateCommit {
+                    certificate_id: certificate.id.clone(),
+                    heights: vec![existing.height, certificate.height],
+                }),
+                None => {
+                    self.certificates.insert(certificate.id.clone(), certificate);
+                }
+            }
+        }
+        self.commits.push(commit);
+    }
+
+    /// Checks the complete DAG for equivocations, and for missing parents within the given GC rounds.
+    fn finish(&mut self, gc_rounds: u64) {
+        // Note: The parents up to the anchor round of the first replayed block may be committed by the earlier blocks,
+        // so they are only checked when the replay starts from the first block.
+        let first_round = match self.commits.first() {
+            Some(commit) if commit.height > 1 => commit.anchor_round,
+            _ => 0,
+        };
+        // Retrieve the anchor round of the commit of each height.
+        let anchor_rounds: BTreeMap<u32, u64> =
+            self.commits.iter().map(|commit| (commit.height, commit.anchor_round)).collect();
+
+        // Find the certificates of the same author in the same round.
+        let mut authored: IndexMap<(u64, &str), Vec<String>> = IndexMap::new();
+        for certificate in self.certificates.values() {
+            authored.entry((certificate.round, &certificate.author)).or_default().push(certificate.id.clone());
+        }
+        for ((round, author), certificate_ids) in authored {
+            if certificate_ids.len() > 1 {
+                let author = author.to_string();
+                self.anomalies.push(ReplayAnomaly::Equivocation { round, author, certificate_ids });
+            }
+        }
+
+        // Find the previous certificates that were never committed.
+        let mut missing_parents = Vec::new();
+        for certificate in self.certificates.values() {
+            let parent_round = certificate.round.saturating_sub(1);
+            // Skip the parents before the first replayed round, or beyond the GC rounds of the commit.
+            let anchor_round = anchor_rounds.get(&certificate.height).copied().unwrap_or_default();
+            if parent_round <= first_round || parent_round.saturating_add(gc_rounds) <= anchor_round {
+                continue;
+            }
+            for parent_id in &certificate.previous_certificate_ids {
+                if !self.certificates.contains_key(parent_id) {
+                    missing_parents.push(ReplayAnomaly::MissingParent {
+                        certificate_id: certificate.id.clone(),
+                        parent_id: parent_id.clone(),
+                    });
+                }
+            }
+        }
+        self.anomalies.extend(missing_parents);
+    }
+
+    /// Renders the DAG as a Graphviz graph, with the certificates ranked by round,
+    /// the anchors highlighted in gold, and the certificates of an anomaly in red.
+    pub fn to_dot(&self) -> String {
+        let anchors: IndexSet<&str> = self.commits.iter().map(|commit| commit.anchor_id.as_str()).collect();
+        let anomalous: IndexSet<&str> = self.anomalies.iter().flat_map(|anomaly| anomaly.certificate_ids()).collect();
+
+        let mut dot = String::from("digraph bft {\n    rankdir=BT;\n    node [shape=box, style=filled];\n");
+        // Group the certificates by round.
+        let mut rounds: BTreeMap<u64, Vec<&ReplayCertificate>> = BTreeMap::new();
+        for certificate in self.certificates.values() {
+            rounds.entry(certificate.round).or_default().push(certificate);
+        }
+        for (round, certificates) in rounds {
+            let _ = writeln!(dot, "    subgraph round_{round} {{\n        rank=same;");
+            for certificate in certificates {
+                let color =
+                    match (anomalous.contains(certificate.id.as_str()), anchors.contains(certificate.id.as_str())) {
+                        (true, _) => "red",
+                        (false, true) => "gold",
+                        (false, false) => "white",
+                    };
+                let _ = writeln!(
+                    dot,
+                    "        \"{}\" [label=\"round {round}\\n{}\\nheight {}\", fillcolor={color}];",
+                    certificate.id,
+                    short_id(&certificate.author),
+                    certificate.height
+                );
+            }
+            dot.push_str("    }\n");
+        }
+        // Link the certificates to their previous certificates, including the ones that were never committed.
+        for certificate in self.certificates.values() {
+            for parent_id in &certificate.previous_certificate_ids {
+                let _ = writeln!(dot, "    \"{}\" -> \"{parent_id}\";", certificate.id);
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Returns the truncated form of the given ID, for the labels of the graph.
+fn short_id(id: &str) -> String {
+    match id.char_indices().nth(16) {
+        Some((index, _)) => format!("{}..", &id[..index]),
+        None => id.to_string(),
+    }
+}
+
+/// Replays the committed DAG of a local ledger, to analyze consensus incidents offline; the node must not be running.
+#[derive(Debug, Parser)]
+pub struct BftReplay {
+    /// Specify the path to a directory containing the ledger
+    pub path: PathBuf,
+    /// Specify the network of the ledger.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Enables development mode, specify the unique ID of the local node.
+    #[clap(long)]
+    pub dev: Option<u16>,
+    /// If development mode is enabled, specify the number of genesis validators (default: 4)
+    #[clap(long)]
+    pub dev_num_validators: Option<u16>,
+    /// Specify the height of the first block to replay (default: 100 blocks before the end)
+    #[clap(long = "start")]
+    pub start: Option<u32>,
+    /// Specify the height of the last block to replay (default: the latest block)
+    #[clap(long = "end")]
+    pub end: Option<u32>,
+    /// Specify the format of the replayed DAG
+    #[clap(value_enum, default_value = "dot", long = "format")]
+    pub format: ReplayFormat,
+}
+
+impl BftReplay {
+    /// The number of blocks replayed by default.
+    const DEFAULT_NUM_BLOCKS: u32 = 100;
+
+    /// Replays the committed DAG of the ledger.
+    pub fn parse(self) -> Result<String> {
+        match self.network {
+            MainnetV0::ID => self.replay::<MainnetV0>(),
+            TestnetV0::ID => self.replay::<TestnetV0>(),
+            CanaryV0::ID => self.replay::<CanaryV0>(),
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        }
+    }
+
+    /// Reconstructs the DAG from the committed subdags of the blocks, and renders it.
+    fn replay<N: Network>(&self) -> Result<String> {
+        // Ensure the ledger exists, as loading it would otherwise initialize a new one.
+        let storage_mode = StorageMode::Custom(self.path.clone());
+        let ledger_dir = aleo_std::aleo_ledger_dir(self.network, storage_mode.clone());
+        ensure!(ledger_dir.exists(), "No snarkOS node storage was found in {}", ledger_dir.display());
+
+        // Load the ledger.
+        let genesis = load_genesis::<N>(self.dev, self.dev_num_validators)?;
+        let ledger = Ledger::<N, ConsensusDB<N>>::load(genesis, storage_mode)?;
+
+        // Determine the range of blocks to replay.
+        let end = self.end.unwrap_or(ledger.latest_height()).min(ledger.latest_height());
+        let start = self.start.unwrap_or(end.saturating_sub(Self::DEFAULT_NUM_BLOCKS - 1));
+        ensure!(start <= end, "The start height ({start}) must not exceed the end height ({end})");
+
+        // Replay the committed subdags.
+        let mut dag = ReplayDag::default();
+        for height in start..=end {
+            let block = ledger.get_block(height)?;
+            // Note: The genesis block has no subdag.
+            let Authority::Quorum(subdag) = block.authority() else {
+                continue;
+            };
+            let anchor = subdag.leader_certificate();
+            let commit = ReplayCommit {
+                height,
+                anchor_round: anchor.round(),
+                anchor_id: anchor.id().to_string(),
+                leader: anchor.author().to_string(),
+                num_certificates: subdag.values().map(|certificates| certificates.len()).sum(),
+            };
+            let certificates = subdag.values().flatten().map(|c| ReplayCertificate::new(c, height)).collect();
+            // Compute the expected leader of the anchor round, from the committee lookback.
+            let expected_leader = ledger
+                .get_committee_for_round(committee_lookback_round::<N>(anchor.round()))
+                .ok()
+                .flatten()
+                .and_then(|committee| committee.get_leader(anchor.round()).ok())
+                .map(|leader| leader.to_string());
+            dag.add_commit(commit, certificates, expected_leader);
+        }
+        dag.finish(BatchHeader::<N>::MAX_GC_ROUNDS as u64);
+
+        match self.format {
+            ReplayFormat::Dot => Ok(dag.to_dot()),
+            ReplayFormat::Json => Ok(serde_json::to_string_pretty(&dag)?),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn certificate(id: &str, round: u64, author: &str, previous: &[&str], height: u32) -> ReplayCertificate {
+        ReplayCertificate {
+            id: id.to_string(),
+            round,
+            author: author.to_string(),
+            previous_certificate_ids: previous.iter().map(|id| id.to_string()).collect(),
+            num_transmissions: 0,
+            num_signatures: 0,
+            height,
+        }
+    }
+
+    fn commit(height: u32, anchor_round: u64, anchor_id: &str, leader: &str) -> ReplayCommit {
+        ReplayCommit {
+            height,
+            anchor_round,
+            anchor_id: anchor_id.to_string(),
+            leader: leader.to_string(),
+            num_certificates: 0,
+        }
+    }
+
+    #[test]
+    fn test_replay_dag() {
+        // Replay a healthy DAG.
+        let mut dag = ReplayDag::default();
+        dag.add_commit(
+            commit(1, 2, "b2", "bob"),
+            vec![certificate("a1", 1, "alice", &[], 1), certificate("b2", 2, "bob", &["a1"], 1)],
+            Some("bob".to_string()),
+        );
+        dag.add_commit(
+            commit(2, 4, "a4", "alice"),
+            vec![certificate("a3", 3, "alice", &["b2"], 2), certificate("a4", 4, "alice", &["a3"], 2)],
+            Some("alice".to_string()),
+        );
+        dag.finish(50);
+        assert_eq!(dag.certificates.len(), 4);
+        assert_eq!(dag.commits.len(), 2);
+        assert!(dag.anomalies.is_empty());
+
+        // Ensure the anchors are highlighted.
+        let dot = dag.to_dot();
+        assert!(dot.starts_with("digraph bft {"));
+        assert!(dot.contains("\"b2\" -> \"a1\";"));
+        assert!(dot.contains("fillcolor=gold"));
+        assert!(!dot.contains("fillcolor=red"));
+    }
+
+    #[test]
+    fn test_replay_anomalies() {
+        let mut dag = ReplayDag::default();
+        dag.add_commit(
+            commit(1, 2, "b2", "bob"),
+            vec![certificate("a1", 1, "alice", &[], 1), certificate("b2", 2, "bob", &["a1"], 1)],
+            Some("alice".to_string()),
+        );
+        dag.add_commit(
+            commit(2, 2, "c3", "carol"),
+            vec![
+                certificate("b2", 2, "bob", &["a1"], 2),
+                certificate("c3", 3, "carol", &["b2", "x2"], 2),
+                certificate("d3", 3, "carol", &["b2"], 2),
+            ],
+            None,
+        );
+        dag.finish(50);
+
+        assert_eq!(dag.anomalies.len(), 5);
+        assert!(dag.anomalies.contains(&ReplayAnomaly::UnexpectedLeader {
+            height: 1,
+            round: 2,
+            expected: "alice".to_string(),
+            found: "bob".to_string()
+        }));
+        assert!(dag.anomalies.contains(&ReplayAnomaly::NonIncreasingAnchor { height: 2, round: 2, previous_round: 2 }));
+        assert!(dag
+            .anomalies
+            .contains(&ReplayAnomaly::DuplicateCommit { certificate_id: "b2".to_string(), heights: vec![1, 2] }));
+        assert!(dag.anomalies.contains(&ReplayAnomaly::Equivocation {
+            round: 3,
+            author: "carol".to_string(),
+            certificate_ids: vec!["c3".to_string(), "d3".to_string()]
+        }));
+        assert!(dag
+            .anomalies
+            .contains(&ReplayAnomaly::MissingParent { certificate_id: "c3".to_string(), parent_id: "x2".to_string() }));
+
+        // Ensure the certificates of the anomalies are highlighted.
+        assert!(dag.to_dot().contains("fillcolor=red"));
+    }
+
+    #[test]
+    fn test_short_id() {
+        assert_eq!(short_id("aleo1abc"), "aleo1abc");
+        assert_eq!(short_id("aleo1abcdefghijklmnopqrstuvwxyz"), "aleo1abcdefghijk..");
+    }
+}
@@ -0,0 +1,270 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::Developer;
+use snarkvm::{
+    console::network::{CanaryV0, MainnetV0, Network, TestnetV0},
+    ledger::committee::{Committee, MIN_DELEGATOR_STAKE},
+    prelude::{Address, Identifier, Literal, Locator, Plaintext, PrivateKey, Value},
+};
+
+use anyhow::{bail, ensure, Result};
+use clap::Parser;
+use std::{path::PathBuf, str::FromStr};
+use zeroize::Zeroize;
+
+/// Bonds microcredits to a validator, by executing the `bond_public` function in the `credits.aleo` program.
+#[derive(Debug, Parser)]
+pub struct Bond {
+    /// Specify the network to create a `bond_public` for.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// The address of the validator to bond to.
+    #[clap(long)]
+    validator: String,
+    /// The address that the microcredits are withdrawn to once unbonded. Defaults to the address of the staker.
+    #[clap(long)]
+    withdrawal: Option<String>,
+    /// The number of microcredits to bond.
+    #[clap(long)]
+    amount: u64,
+    /// The private key of the staker, used to generate the execution.
+    #[clap(short, long)]
+    private_key: String,
+    /// The endpoint to query node state from.
+    #[clap(short, long)]
+    query: String,
+    /// The priority fee in microcredits.
+    #[clap(long)]
+    priority_fee: Option<u64>,
+    /// The record to spend the fee from. Defaults to paying the fee from the public balance.
+    #[clap(short, long)]
+    record: Option<String>,
+    /// The endpoint used to broadcast the generated transaction.
+    #[clap(short, long, conflicts_with = "dry_run")]
+    broadcast: Option<String>,
+    /// Performs a dry-run of transaction generation.
+    #[clap(short, long, conflicts_with = "broadcast")]
+    dry_run: bool,
+    /// Store generated deployment transaction to a local file.
+    #[clap(long)]
+    store: Option<String>,
+    /// Specify the path to a directory containing the ledger
+    #[clap(long = "storage_path")]
+    pub storage_path: Option<PathBuf>,
+}
+
+impl Drop for Bond {
+    /// Zeroize the private key when the `Bond` struct goes out of scope.
+    fn drop(&mut self) {
+        self.private_key.zeroize();
+    }
+}
+
+impl Bond {
+    /// Creates a bond with the provided inputs.
+    pub fn parse(self) -> Result<String> {
+        // Ensure that the user has specified an action.
+        if !self.dry_run && self.broadcast.is_none() && self.store.is_none() {
+            bail!("❌ Please specify one of the following actions: --broadcast, --dry-run, --store");
+        }
+
+        // Construct the bond for the specified network.
+        match self.network {
+            MainnetV0::ID => self.construct_bond::<MainnetV0>(),
+            TestnetV0::ID => self.construct_bond::<TestnetV0>(),
+            CanaryV0::ID => self.construct_bond::<CanaryV0>(),
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        }
+    }
+
+    /// Construct and process the `bond_public` transaction.
+    fn construct_bond<N: Network>(&self) -> Result<String> {
+        // Retrieve the private key and the staker.
+        let private_key = PrivateKey::<N>::from_str(&self.private_key)?;
+        let staker = Address::try_from(&private_key)?;
+
+        // Retrieve the validator and the withdrawal address.
+        let validator = Address::<N>::from_str(&self.validator)?;
+        let withdrawal = match &self.withdrawal {
+            Some(withdrawal) => Address::<N>::from_str(withdrawal)?,
+            None => staker,
+        };
+
+        // Ensure the bond is valid for the current committee and the current bond of the staker.
+        let committee = Developer::get_latest_committee::<N>(&self.query)?;
+        let current_bond = match Developer::get_credits_mapping_value("bonded", &staker, &self.query)? {
+            Some(bond_state) => Some(parse_bond_state(&bond_state)?),
+            None => None,
+        };
+        let public_balance = Developer::get_public_balance(&staker, &self.query)?;
+        check_bond(&committee, staker, validator, current_bond, self.amount, public_balance)?;
+
+        println!("📦 Creating bond of {} microcredits to {}...\n", self.amount, validator);
+
+        // Generate the bond_public transaction.
+        let inputs = vec![
+            Value::from_str(&validator.to_string())?,
+            Value::from_str(&withdrawal.to_string())?,
+            Value::from_str(&format!("{}u64", self.amount))?,
+        ];
+        let transaction = Developer::execute_credits(
+            &private_key,
+            "bond_public",
+            inputs,
+            &self.record,
+            self.priority_fee.unwrap_or(0),
+            &self.query,
+            &self.storage_path,
+        )?;
+        let locator = Locator::<N>::from_str("credits.aleo/bond_public")?;
+        println!("✅ Created bond of {} microcredits to {}\n", self.amount, validator);
+
+        // Determine if the transaction should be broadcast, stored, or displayed to the user.
+        Developer::handle_transaction(&self.broadcast, self.dry_run, &self.store, transaction, locator.to_string())
+    }
+}
+
+/// Parses the validator and the microcredits of the given `bond_state` of the `bonded` mapping.
+pub(super) fn parse_bond_state<N: Network>(bond_state: &Plaintext<N>) -> Result<(Address<N>, u64)> {
+    let validator = match bond_state.find(&[Identifier::from_str("validator")?])? {
+        Plaintext::Literal(Literal::Address(validator), _) => validator,
+        _ => bail!("The member 'validator' is not an address"),
+    };
+    Ok((validator, Developer::get_u64_member(bond_state, "microcredits")?))
+}
+
+/// Ensures the staker may bond the given amount to the given validator, given its current bond and public balance.
+fn check_bond<N: Network>(
+    committee: &Committee<N>,
+    staker: Address<N>,
+    validator: Address<N>,
+    current_bond: Option<(Address<N>, u64)>,
+    amount: u64,
+    public_balance: u64,
+) -> Result<()> {
+    ensure!(amount > 0, "The amount to bond must be positive");
+    ensure!(!committee.is_committee_member(staker), "The staker {staker} is a validator, and cannot bond to another");
+    // Ensure the validator is in the committee, and accepts new stakers.
+    let Some((_, is_open, _)) = committee.members().get(&validator) else {
+        bail!("The validator {validator} is not in the committee");
+    };
+    // Ensure the staker only adds to its bond with the same validator.
+    let current_amount = match current_bond {
+        Some((bonded_validator, current_amount)) => {
+            ensure!(
+                bonded_validator == validator,
+                "The staker is already bonded to {bonded_validator}, and must unbond before bonding to another"
+            );
+            current_amount
+        }
+        None => {
+            ensure!(*is_open, "The validator {validator} does not accept new stakers");
+            0
+        }
+    };
+    ensure!(
+        current_amount.saturating_add(amount) >= MIN_DELEGATOR_STAKE,
+        "The bond must total at least {MIN_DELEGATOR_STAKE} microcredits"
+    );
+    ensure!(
+        public_balance >= amount,
+        "The public balance of {public_balance} microcredits is insufficient to bond {amount} microcredits"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Command, CLI};
+    use snarkvm::{ledger::committee::MIN_VALIDATOR_STAKE, prelude::MainnetV0};
+
+    use indexmap::IndexMap;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn clap_snarkos_bond() {
+        let arg_vec = vec![
+            "snarkos",
+            "developer",
+            "bond",
+            "--private-key",
+            "PRIVATE_KEY",
+            "--query",
+            "QUERY",
+            "--validator",
+            "VALIDATOR",
+            "--amount",
+            "10000000000",
+            "--dry-run",
+        ];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::Bond(bond)) = cli.command {
+            assert_eq!(bond.network, 0);
+            assert_eq!(bond.private_key, "PRIVATE_KEY");
+            assert_eq!(bond.query, "QUERY");
+            assert_eq!(bond.validator, "VALIDATOR");
+            assert_eq!(bond.withdrawal, None);
+            assert_eq!(bond.amount, 10_000_000_000);
+            assert!(bond.dry_run);
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+    }
+
+    #[test]
+    fn test_check_bond() {
+        let address =
+            || Address::try_from(PrivateKey::<CurrentNetwork>::new(&mut rand::thread_rng()).unwrap()).unwrap();
+
+        // Initialize a committee, whose first validator accepts new stakers.
+        let validators = (0..4).map(|_| address()).collect::<Vec<_>>();
+        let members = validators
+            .iter()
+            .enumerate()
+            .map(|(i, validator)| (*validator, (MIN_VALIDATOR_STAKE, i == 0, 0)))
+            .collect::<IndexMap<_, _>>();
+        let committee = Committee::new(0, members).unwrap();
+        let staker = address();
+        let balance = MIN_DELEGATOR_STAKE;
+
+        // Ensure a new bond to an open validator is valid.
+        assert!(check_bond(&committee, staker, validators[0], None, MIN_DELEGATOR_STAKE, balance).is_ok());
+        // Ensure the amount must be positive, and covered by the public balance.
+        assert!(check_bond(&committee, staker, validators[0], None, 0, balance).is_err());
+        assert!(check_bond(&committee, staker, validators[0], None, MIN_DELEGATOR_STAKE, balance - 1).is_err());
+        // Ensure the bond must total at least the minimum.
+        assert!(check_bond(&committee, staker, validators[0], None, MIN_DELEGATOR_STAKE - 1, balance).is_err());
+        assert!(check_bond(
+            &committee,
+            staker,
+            validators[0],
+            Some((validators[0], 1)),
+            MIN_DELEGATOR_STAKE - 1,
+            balance
+        )
+        .is_ok());
+        // Ensure a new bond to a closed validator, or to a non-member, is invalid.
+        assert!(check_bond(&committee, staker, validators[1], None, MIN_DELEGATOR_STAKE, balance).is_err());
+        assert!(check_bond(&committee, staker, address(), None, MIN_DELEGATOR_STAKE, balance).is_err());
+        // Ensure a staker cannot bond to a second validator.
+        assert!(check_bond(&committee, staker, validators[0], Some((validators[1], MIN_DELEGATOR_STAKE)), 1, balance)
+            .is_err());
+        // Ensure a validator cannot bond to another.
+        assert!(check_bond(&committee, validators[1], validators[0], None, MIN_DELEGATOR_STAKE, balance).is_err());
+    }
+}
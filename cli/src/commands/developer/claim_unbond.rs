@@ -0,0 +1,184 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::Developer;
+use snarkvm::{
+    console::network::{CanaryV0, MainnetV0, Network, TestnetV0},
+    prelude::{Address, Identifier, Literal, Locator, Plaintext, PrivateKey, Value},
+};
+
+use anyhow::{bail, ensure, Result};
+use clap::Parser;
+use std::{path::PathBuf, str::FromStr};
+use zeroize::Zeroize;
+
+/// Claims the unbonded microcredits, by executing the `claim_unbond_public` function in the `credits.aleo` program.
+#[derive(Debug, Parser)]
+pub struct ClaimUnbond {
+    /// Specify the network to create a `claim_unbond_public` for.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// The address of the staker to claim for. Defaults to the address of the private key.
+    #[clap(long)]
+    staker: Option<String>,
+    /// The private key used to generate the execution.
+    #[clap(short, long)]
+    private_key: String,
+    /// The endpoint to query node state from.
+    #[clap(short, long)]
+    query: String,
+    /// The priority fee in microcredits.
+    #[clap(long)]
+    priority_fee: Option<u64>,
+    /// The record to spend the fee from. Defaults to paying the fee from the public balance.
+    #[clap(short, long)]
+    record: Option<String>,
+    /// The endpoint used to broadcast the generated transaction.
+    #[clap(short, long, conflicts_with = "dry_run")]
+    broadcast: Option<String>,
+    /// Performs a dry-run of transaction generation.
+    #[clap(short, long, conflicts_with = "broadcast")]
+    dry_run: bool,
+    /// Store generated deployment transaction to a local file.
+    #[clap(long)]
+    store: Option<String>,
+    /// Specify the path to a directory containing the ledger
+    #[clap(long = "storage_path")]
+    pub storage_path: Option<PathBuf>,
+}
+
+impl Drop for ClaimUnbond {
+    /// Zeroize the private key when the `ClaimUnbond` struct goes out of scope.
+    fn drop(&mut self) {
+        self.private_key.zeroize();
+    }
+}
+
+impl ClaimUnbond {
+    /// Creates a claim of the unbonded microcredits with the provided inputs.
+    pub fn parse(self) -> Result<String> {
+        // Ensure that the user has specified an action.
+        if !self.dry_run && self.broadcast.is_none() && self.store.is_none() {
+            bail!("❌ Please specify one of the following actions: --broadcast, --dry-run, --store");
+        }
+
+        // Construct the claim for the specified network.
+        match self.network {
+            MainnetV0::ID => self.construct_claim_unbond::<MainnetV0>(),
+            TestnetV0::ID => self.construct_claim_unbond::<TestnetV0>(),
+            CanaryV0::ID => self.construct_claim_unbond::<CanaryV0>(),
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        }
+    }
+
+    /// Construct and process the `claim_unbond_public` transaction.
+    fn construct_claim_unbond<N: Network>(&self) -> Result<String> {
+        // Retrieve the private key and the staker.
+        let private_key = PrivateKey::<N>::from_str(&self.private_key)?;
+        let staker = match &self.staker {
+            Some(staker) => Address::<N>::from_str(staker)?,
+            None => Address::try_from(&private_key)?,
+        };
+
+        // Ensure the staker has an unbonding request that can be claimed.
+        let unbonding = match Developer::get_credits_mapping_value("unbonding", &staker, &self.query)? {
+            Some(unbond_state) => Some(parse_unbond_state(&unbond_state)?),
+            None => None,
+        };
+        let latest_height = Developer::get_latest_height::<N>(&self.query)?;
+        let amount = check_claim_unbond(unbonding, latest_height)?;
+
+        println!("📦 Creating claim of {amount} unbonded microcredits for {staker}...\n");
+
+        // Generate the claim_unbond_public transaction.
+        let inputs = vec![Value::from_str(&staker.to_string())?];
+        let transaction = Developer::execute_credits(
+            &private_key,
+            "claim_unbond_public",
+            inputs,
+            &self.record,
+            self.priority_fee.unwrap_or(0),
+            &self.query,
+            &self.storage_path,
+        )?;
+        let locator = Locator::<N>::from_str("credits.aleo/claim_unbond_public")?;
+        println!("✅ Created claim of {amount} unbonded microcredits for {staker}\n");
+
+        // Determine if the transaction should be broadcast, stored, or displayed to the user.
+        Developer::handle_transaction(&self.broadcast, self.dry_run, &self.store, transaction, locator.to_string())
+    }
+}
+
+/// Parses the microcredits and the unlock height of the given `unbond_state` of the `unbonding` mapping.
+fn parse_unbond_state<N: Network>(unbond_state: &Plaintext<N>) -> Result<(u64, u32)> {
+    let height = match unbond_state.find(&[Identifier::from_str("height")?])? {
+        Plaintext::Literal(Literal::U32(height), _) => *height,
+        _ => bail!("The member 'height' is not a u32"),
+    };
+    Ok((Developer::get_u64_member(unbond_state, "microcredits")?, height))
+}
+
+/// Ensures the given unbonding request can be claimed at the given height, and returns the amount to claim.
+fn check_claim_unbond(unbonding: Option<(u64, u32)>, latest_height: u32) -> Result<u64> {
+    let Some((amount, unlock_height)) = unbonding else {
+        bail!("The staker has no unbonded microcredits to claim");
+    };
+    ensure!(
+        latest_height >= unlock_height,
+        "The unbonded microcredits can only be claimed from block {unlock_height} (currently at block {latest_height})"
+    );
+    Ok(amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Command, CLI};
+
+    #[test]
+    fn clap_snarkos_claim_unbond() {
+        let arg_vec = vec![
+            "snarkos",
+            "developer",
+            "claim-unbond",
+            "--private-key",
+            "PRIVATE_KEY",
+            "--query",
+            "QUERY",
+            "--staker",
+            "STAKER",
+            "--broadcast",
+            "BROADCAST",
+        ];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::ClaimUnbond(claim_unbond)) = cli.command {
+            assert_eq!(claim_unbond.network, 0);
+            assert_eq!(claim_unbond.private_key, "PRIVATE_KEY");
+            assert_eq!(claim_unbond.staker, Some("STAKER".into()));
+            assert_eq!(claim_unbond.broadcast, Some("BROADCAST".into()));
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+    }
+
+    #[test]
+    fn test_check_claim_unbond() {
+        assert!(check_claim_unbond(None, 100).is_err());
+        // Ensure the unbonded microcredits can only be claimed from the unlock height.
+        assert!(check_claim_unbond(Some((5, 101)), 100).is_err());
+        assert_eq!(check_claim_unbond(Some((5, 100)), 100).unwrap(), 5);
+        assert_eq!(check_claim_unbond(Some((5, 100)), 200).unwrap(), 5);
+    }
+}
@@ -0,0 +1,246 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::Developer;
+use snarkvm::{
+    console::network::{CanaryV0, MainnetV0, Network, TestnetV0},
+    ledger::committee::Committee as LedgerCommittee,
+    prelude::Address,
+};
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use colored::Colorize;
+use serde_json::json;
+use std::str::FromStr;
+
+/// Fetches and displays the committee.
+#[derive(Debug, Parser)]
+pub struct Committee {
+    /// Specify the network of the committee.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// The endpoint to fetch the committee from.
+    #[clap(long)]
+    endpoint: String,
+    /// The block height to fetch the committee at. Defaults to the latest block.
+    #[clap(long = "at-height")]
+    at_height: Option<u32>,
+    /// The address of your validator, to display its position and share of the stake.
+    #[clap(long)]
+    address: Option<String>,
+    /// Outputs the committee as JSON.
+    #[clap(long)]
+    json: bool,
+}
+
+impl Committee {
+    /// Fetches and displays the committee.
+    pub fn parse(self) -> Result<String> {
+        // Fetch the committee on the given network.
+        match self.network {
+            MainnetV0::ID => self.display_committee::<MainnetV0>(),
+            TestnetV0::ID => self.display_committee::<TestnetV0>(),
+            CanaryV0::ID => self.display_committee::<CanaryV0>(),
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        }
+    }
+
+    /// Fetches the committee, and returns it as a table or as JSON.
+    fn display_committee<N: Network>(&self) -> Result<String> {
+        // Retrieve the address of the validator, if any.
+        let address = self.address.as_deref().map(Address::<N>::from_str).transpose()?;
+
+        // Fetch the committee.
+        let committee: LedgerCommittee<N> = match self.at_height {
+            Some(height) => Developer::get_json::<N, _>(&self.endpoint, &format!("committee/{height}"))?,
+            None => Developer::get_latest_committee::<N>(&self.endpoint)?,
+        };
+
+        match self.json {
+            true => Ok(serde_json::to_string_pretty(&committee_to_json(&committee, address))?),
+            false => Ok(committee_to_table(&committee, address)),
+        }
+    }
+}
+
+/// Returns the members of the committee, with their stake, open status, and commission, by descending stake.
+fn sorted_members<N: Network>(committee: &LedgerCommittee<N>) -> Vec<(Address<N>, u64, bool, u8)> {
+    let mut members = committee
+        .members()
+        .iter()
+        .map(|(address, (stake, is_open, commission))| (*address, *stake, *is_open, *commission))
+        .collect::<Vec<_>>();
+    members.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.to_string().cmp(&b.0.to_string())));
+    members
+}
+
+/// Returns the share of the total stake of the given stake, in percent.
+fn share(stake: u64, total_stake: u64) -> f64 {
+    match total_stake {
+        0 => 0.0,
+        total_stake => stake as f64 * 100.0 / total_stake as f64,
+    }
+}
+
+/// Returns the committee as JSON, including the position of the given validator, if any.
+fn committee_to_json<N: Network>(committee: &LedgerCommittee<N>, address: Option<Address<N>>) -> serde_json::Value {
+    let total_stake = committee.total_stake();
+    let members = sorted_members(committee);
+    let validator = address.map(|address| match members.iter().position(|(member, ..)| *member == address) {
+        Some(index) => json!({
+            "address": address.to_string(),
+            "rank": index + 1,
+            "stake": members[index].1,
+            "share": share(members[index].1, total_stake),
+        }),
+        None => json!({ "address": address.to_string(), "rank": null }),
+    });
+    let members_json = members
+        .iter()
+        .enumerate()
+        .map(|(index, (address, stake, is_open, commission))| {
+            json!({
+                "rank": index + 1,
+                "address": address.to_string(),
+                "stake": stake,
+                "share": share(*stake, total_stake),
+                "is_open": is_open,
+                "commission": commission,
+            })
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "starting_round": committee.starting_round(),
+        "total_stake": total_stake,
+        "num_members": members.len(),
+        "members": members_json,
+        "validator": validator,
+    })
+}
+
+/// Returns the committee as a table, followed by the position of the given validator, if any.
+fn committee_to_table<N: Network>(committee: &LedgerCommittee<N>, address: Option<Address<N>>) -> String {
+    const MICROCREDITS_PER_CREDIT: u64 = 1_000_000;
+
+    let total_stake = committee.total_stake();
+    let members = sorted_members(committee);
+
+    let mut table = format!(
+        "🏛️  Committee from round {} with {} members and {} credits staked\n\n",
+        committee.starting_round(),
+        members.len(),
+        total_stake / MICROCREDITS_PER_CREDIT
+    );
+    table.push_str(&format!(
+        "{:>4}  {:<63}  {:>16}  {:>7}  {:<6}  {:>10}\n",
+        "Rank", "Address", "Stake (credits)", "Share", "Status", "Commission"
+    ));
+    for (index, (member, stake, is_open, commission)) in members.iter().enumerate() {
+        let row = format!(
+            "{:>4}  {:<63}  {:>16}  {:>6.2}%  {:<6}  {:>9}%",
+            index + 1,
+            member.to_string(),
+            stake / MICROCREDITS_PER_CREDIT,
+            share(*stake, total_stake),
+            if *is_open { "open" } else { "closed" },
+            commission
+        );
+        // Highlight the given validator.
+        match Some(*member) == address {
+            true => table.push_str(&format!("{}\n", row.bold())),
+            false => table.push_str(&format!("{row}\n")),
+        }
+    }
+
+    if let Some(address) = address {
+        match members.iter().position(|(member, ..)| *member == address) {
+            Some(index) => table.push_str(&format!(
+                "\n✅ Your validator is ranked {} of {}, with {:.2}% of the stake",
+                index + 1,
+                members.len(),
+                share(members[index].1, total_stake)
+            )),
+            None => table.push_str(&format!("\n⚠️  Your validator {address} is not in the committee")),
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Command, CLI};
+    use snarkvm::{
+        ledger::committee::MIN_VALIDATOR_STAKE,
+        prelude::{MainnetV0, PrivateKey},
+    };
+
+    use indexmap::IndexMap;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn clap_snarkos_committee() {
+        let arg_vec =
+            vec!["snarkos", "developer", "committee", "--endpoint", "ENDPOINT", "--at-height", "100", "--json"];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::Committee(committee)) = cli.command {
+            assert_eq!(committee.network, 0);
+            assert_eq!(committee.endpoint, "ENDPOINT");
+            assert_eq!(committee.at_height, Some(100));
+            assert_eq!(committee.address, None);
+            assert!(committee.json);
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+    }
+
+    #[test]
+    fn test_committee_to_json() {
+        let address =
+            || Address::try_from(PrivateKey::<CurrentNetwork>::new(&mut rand::thread_rng()).unwrap()).unwrap();
+
+        // Initialize a committee, whose last validator holds half of the stake.
+        let validators = (0..4).map(|_| address()).collect::<Vec<_>>();
+        let members = validators
+            .iter()
+            .enumerate()
+            .map(|(i, validator)| {
+                let stake = if i == 3 { 3 * MIN_VALIDATOR_STAKE } else { MIN_VALIDATOR_STAKE };
+                (*validator, (stake, i % 2 == 0, i as u8))
+            })
+            .collect::<IndexMap<_, _>>();
+        let committee = LedgerCommittee::new(0, members).unwrap();
+
+        // Ensure the members are sorted by descending stake.
+        let json = committee_to_json(&committee, Some(validators[3]));
+        assert_eq!(json["num_members"], 4);
+        assert_eq!(json["total_stake"], 6 * MIN_VALIDATOR_STAKE);
+        assert_eq!(json["members"][0]["address"], validators[3].to_string());
+        assert_eq!(json["members"][0]["share"], 50.0);
+        assert_eq!(json["members"][0]["is_open"], false);
+        assert_eq!(json["members"][0]["commission"], 3);
+        // Ensure the position of the given validator is reported.
+        assert_eq!(json["validator"]["rank"], 1);
+        assert_eq!(json["validator"]["share"], 50.0);
+
+        // Ensure a validator outside of the committee is reported as such.
+        let json = committee_to_json(&committee, Some(address()));
+        assert!(json["validator"]["rank"].is_null());
+        let json = committee_to_json(&committee, None);
+        assert!(json["validator"].is_null());
+    }
+}
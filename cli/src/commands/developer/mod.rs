@@ -12,6 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod bft_replay;
+pub use bft_replay::*;
+
+mod bond;
+pub use bond::*;
+
+mod claim_unbond;
+pub use claim_unbond::*;
+
+mod committee;
+pub use committee::*;
+
 mod decrypt;
 pub use decrypt::*;
 
@@ -27,11 +39,17 @@ pub use scan::*;
 mod transfer_private;
 pub use transfer_private::*;
 
+mod unbond;
+pub use unbond::*;
+
 use snarkvm::{
     console::network::Network,
+    ledger::committee::Committee as LedgerCommittee,
     package::Package,
     prelude::{
         block::Transaction,
+        query::Query,
+        store::{helpers::memory::ConsensusMemory, ConsensusStore},
         Address,
         Ciphertext,
         Identifier,
@@ -44,17 +62,28 @@ use snarkvm::{
         ToBytes,
         Value,
         ViewKey,
+        VM,
     },
 };
 
+use aleo_std::StorageMode;
 use anyhow::{bail, ensure, Result};
 use clap::Parser;
 use colored::Colorize;
+use serde::de::DeserializeOwned;
 use std::{path::PathBuf, str::FromStr};
 
 /// Commands to deploy and execute transactions
 #[derive(Debug, Parser)]
 pub enum Developer {
+    /// Replay the committed DAG of a local ledger, to analyze consensus incidents offline.
+    BftReplay(BftReplay),
+    /// Bond microcredits to a validator, with `credits.aleo/bond_public`.
+    Bond(Bond),
+    /// Claim the unbonded microcredits, with `credits.aleo/claim_unbond_public`.
+    ClaimUnbond(ClaimUnbond),
+    /// Display the committee.
+    Committee(Committee),
     /// Decrypt a ciphertext.
     Decrypt(Decrypt),
     /// Deploy a program.
@@ -65,16 +94,23 @@ pub enum Developer {
     Scan(Scan),
     /// Execute the `credits.aleo/transfer_private` function.
     TransferPrivate(TransferPrivate),
+    /// Unbond microcredits from a validator, with `credits.aleo/unbond_public`.
+    Unbond(Unbond),
 }
 
 impl Developer {
     pub fn parse(self) -> Result<String> {
         match self {
+            Self::BftReplay(bft_replay) => bft_replay.parse(),
+            Self::Bond(bond) => bond.parse(),
+            Self::ClaimUnbond(claim_unbond) => claim_unbond.parse(),
+            Self::Committee(committee) => committee.parse(),
             Self::Decrypt(decrypt) => decrypt.parse(),
             Self::Deploy(deploy) => deploy.parse(),
             Self::Execute(execute) => execute.parse(),
             Self::Scan(scan) => scan.parse(),
             Self::TransferPrivate(transfer_private) => transfer_private.parse(),
+            Self::Unbond(unbond) => unbond.parse(),
         }
     }
 
@@ -176,6 +212,100 @@ impl Developer {
         }
     }
 
+    /// Fetch the JSON response of the given route from the given endpoint.
+    fn get_json<N: Network, T: DeserializeOwned>(endpoint: &str, route: &str) -> Result<T> {
+        // Get the network being used.
+        let network = match N::ID {
+            snarkvm::console::network::MainnetV0::ID => "mainnet",
+            snarkvm::console::network::TestnetV0::ID => "testnet",
+            snarkvm::console::network::CanaryV0::ID => "canary",
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        };
+
+        // Send a request to the query node.
+        match ureq::get(&format!("{endpoint}/{network}/{route}")).call() {
+            Ok(response) => response.into_json().map_err(|err| err.into()),
+            Err(ureq::Error::Status(_status, response)) => {
+                bail!(response.into_string().unwrap_or("Response too large!".to_owned()))
+            }
+            Err(err) => bail!(err),
+        }
+    }
+
+    /// Fetch the value of the given key in the given `credits.aleo` mapping from the given endpoint.
+    fn get_credits_mapping_value<N: Network>(
+        mapping: &str,
+        key: &Address<N>,
+        endpoint: &str,
+    ) -> Result<Option<Plaintext<N>>> {
+        match Self::get_json::<N, Option<Value<N>>>(endpoint, &format!("program/credits.aleo/mapping/{mapping}/{key}"))?
+        {
+            Some(Value::Plaintext(plaintext)) => Ok(Some(plaintext)),
+            Some(..) => bail!("Failed to deserialize the '{mapping}' entry of {key}"),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the `u64` member with the given name of the given struct.
+    fn get_u64_member<N: Network>(plaintext: &Plaintext<N>, name: &str) -> Result<u64> {
+        match plaintext.find(&[Identifier::from_str(name)?])? {
+            Plaintext::Literal(Literal::U64(value), _) => Ok(*value),
+            _ => bail!("The member '{name}' is not a u64"),
+        }
+    }
+
+    /// Fetch the latest committee from the given endpoint.
+    fn get_latest_committee<N: Network>(endpoint: &str) -> Result<LedgerCommittee<N>> {
+        Self::get_json::<N, _>(endpoint, "committee/latest")
+    }
+
+    /// Fetch the latest block height from the given endpoint.
+    fn get_latest_height<N: Network>(endpoint: &str) -> Result<u32> {
+        Self::get_json::<N, _>(endpoint, "block/height/latest")
+    }
+
+    /// Construct an execution of the given `credits.aleo` function.
+    /// The fee is paid from the given record, if any, and from the public balance otherwise.
+    fn execute_credits<N: Network>(
+        private_key: &PrivateKey<N>,
+        function: &str,
+        inputs: Vec<Value<N>>,
+        fee_record: &Option<String>,
+        priority_fee: u64,
+        query: &str,
+        storage_path: &Option<PathBuf>,
+    ) -> Result<Transaction<N>> {
+        // Initialize an RNG.
+        let rng = &mut rand::thread_rng();
+
+        // Initialize the storage.
+        let storage_mode = match storage_path {
+            Some(path) => StorageMode::Custom(path.clone()),
+            None => StorageMode::Production,
+        };
+        let store = ConsensusStore::<N, ConsensusMemory<N>>::open(storage_mode)?;
+
+        // Initialize the VM.
+        let vm = VM::from(store)?;
+
+        // Prepare the fee.
+        let fee_record = match fee_record {
+            Some(record_string) => Some(Self::parse_record(private_key, record_string)?),
+            None => None,
+        };
+
+        // Create a new transaction.
+        vm.execute(
+            private_key,
+            ("credits.aleo", function),
+            inputs.iter(),
+            fee_record,
+            priority_fee,
+            Some(Query::from(query)),
+            rng,
+        )
+    }
+
     /// Determine if the transaction should be broadcast or displayed to user.
     fn handle_transaction<N: Network>(
         broadcast: &Option<String>,
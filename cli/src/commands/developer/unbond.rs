@@ -0,0 +1,191 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{bond::parse_bond_state, Developer};
+use snarkvm::{
+    console::network::{CanaryV0, MainnetV0, Network, TestnetV0},
+    ledger::committee::MIN_DELEGATOR_STAKE,
+    prelude::{Address, Locator, PrivateKey, Value},
+};
+
+use anyhow::{bail, ensure, Result};
+use clap::Parser;
+use std::{path::PathBuf, str::FromStr};
+use zeroize::Zeroize;
+
+/// Unbonds microcredits from a validator, by executing the `unbond_public` function in the `credits.aleo` program.
+#[derive(Debug, Parser)]
+pub struct Unbond {
+    /// Specify the network to create an `unbond_public` for.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// The address of the staker to unbond. Defaults to the address of the private key.
+    #[clap(long)]
+    staker: Option<String>,
+    /// The number of microcredits to unbond.
+    #[clap(long)]
+    amount: u64,
+    /// The private key of the staker or of its withdrawal address, used to generate the execution.
+    #[clap(short, long)]
+    private_key: String,
+    /// The endpoint to query node state from.
+    #[clap(short, long)]
+    query: String,
+    /// The priority fee in microcredits.
+    #[clap(long)]
+    priority_fee: Option<u64>,
+    /// The record to spend the fee from. Defaults to paying the fee from the public balance.
+    #[clap(short, long)]
+    record: Option<String>,
+    /// The endpoint used to broadcast the generated transaction.
+    #[clap(short, long, conflicts_with = "dry_run")]
+    broadcast: Option<String>,
+    /// Performs a dry-run of transaction generation.
+    #[clap(short, long, conflicts_with = "broadcast")]
+    dry_run: bool,
+    /// Store generated deployment transaction to a local file.
+    #[clap(long)]
+    store: Option<String>,
+    /// Specify the path to a directory containing the ledger
+    #[clap(long = "storage_path")]
+    pub storage_path: Option<PathBuf>,
+}
+
+impl Drop for Unbond {
+    /// Zeroize the private key when the `Unbond` struct goes out of scope.
+    fn drop(&mut self) {
+        self.private_key.zeroize();
+    }
+}
+
+impl Unbond {
+    /// Creates an unbond with the provided inputs.
+    pub fn parse(self) -> Result<String> {
+        // Ensure that the user has specified an action.
+        if !self.dry_run && self.broadcast.is_none() && self.store.is_none() {
+            bail!("❌ Please specify one of the following actions: --broadcast, --dry-run, --store");
+        }
+
+        // Construct the unbond for the specified network.
+        match self.network {
+            MainnetV0::ID => self.construct_unbond::<MainnetV0>(),
+            TestnetV0::ID => self.construct_unbond::<TestnetV0>(),
+            CanaryV0::ID => self.construct_unbond::<CanaryV0>(),
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        }
+    }
+
+    /// Construct and process the `unbond_public` transaction.
+    fn construct_unbond<N: Network>(&self) -> Result<String> {
+        // Retrieve the private key and the staker.
+        let private_key = PrivateKey::<N>::from_str(&self.private_key)?;
+        let staker = match &self.staker {
+            Some(staker) => Address::<N>::from_str(staker)?,
+            None => Address::try_from(&private_key)?,
+        };
+
+        // Ensure the staker has a bond to unbond the amount from.
+        let current_bond = match Developer::get_credits_mapping_value("bonded", &staker, &self.query)? {
+            Some(bond_state) => Some(parse_bond_state(&bond_state)?),
+            None => None,
+        };
+        let unbonded_amount = check_unbond(current_bond, self.amount)?;
+        if unbonded_amount > self.amount {
+            println!("⚠️  The remaining bond would be below the minimum, so {unbonded_amount} microcredits will be unbonded.\n");
+        }
+
+        println!("📦 Creating unbond of {} microcredits for {}...\n", self.amount, staker);
+
+        // Generate the unbond_public transaction.
+        let inputs = vec![Value::from_str(&staker.to_string())?, Value::from_str(&format!("{}u64", self.amount))?];
+        let transaction = Developer::execute_credits(
+            &private_key,
+            "unbond_public",
+            inputs,
+            &self.record,
+            self.priority_fee.unwrap_or(0),
+            &self.query,
+            &self.storage_path,
+        )?;
+        let locator = Locator::<N>::from_str("credits.aleo/unbond_public")?;
+        println!("✅ Created unbond of {} microcredits for {}\n", self.amount, staker);
+
+        // Determine if the transaction should be broadcast, stored, or displayed to the user.
+        Developer::handle_transaction(&self.broadcast, self.dry_run, &self.store, transaction, locator.to_string())
+    }
+}
+
+/// Ensures the given amount can be unbonded from the current bond, and returns the amount that will be unbonded.
+/// Note: If the remaining bond of a delegator would fall below the minimum, the entire bond is unbonded.
+fn check_unbond<N: Network>(current_bond: Option<(Address<N>, u64)>, amount: u64) -> Result<u64> {
+    ensure!(amount > 0, "The amount to unbond must be positive");
+    let Some((_, current_amount)) = current_bond else {
+        bail!("The staker is not bonded to any validator");
+    };
+    ensure!(amount <= current_amount, "The amount to unbond exceeds the bond of {current_amount} microcredits");
+    match current_amount - amount < MIN_DELEGATOR_STAKE {
+        true => Ok(current_amount),
+        false => Ok(amount),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Command, CLI};
+    use snarkvm::prelude::MainnetV0;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn clap_snarkos_unbond() {
+        let arg_vec = vec![
+            "snarkos",
+            "developer",
+            "unbond",
+            "--private-key",
+            "PRIVATE_KEY",
+            "--query",
+            "QUERY",
+            "--amount",
+            "5",
+            "--dry-run",
+        ];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Developer(Developer::Unbond(unbond)) = cli.command {
+            assert_eq!(unbond.network, 0);
+            assert_eq!(unbond.private_key, "PRIVATE_KEY");
+            assert_eq!(unbond.staker, None);
+            assert_eq!(unbond.amount, 5);
+            assert!(unbond.dry_run);
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+    }
+
+    #[test]
+    fn test_check_unbond() {
+        let validator = Address::try_from(PrivateKey::<CurrentNetwork>::new(&mut rand::thread_rng()).unwrap()).unwrap();
+        let bond = Some((validator, 2 * MIN_DELEGATOR_STAKE));
+
+        // Ensure the amount must be positive, and within the bond.
+        assert!(check_unbond(bond, 0).is_err());
+        assert!(check_unbond(bond, 2 * MIN_DELEGATOR_STAKE + 1).is_err());
+        assert!(check_unbond::<CurrentNetwork>(None, 1).is_err());
+        // Ensure the remaining bond must stay above the minimum, or the entire bond is unbonded.
+        assert_eq!(check_unbond(bond, MIN_DELEGATOR_STAKE).unwrap(), MIN_DELEGATOR_STAKE);
+        assert_eq!(check_unbond(bond, MIN_DELEGATOR_STAKE + 1).unwrap(), 2 * MIN_DELEGATOR_STAKE);
+    }
+}
@@ -0,0 +1,257 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::load_genesis;
+use snarkos_node::{copy_entries, rest::StateDigest, write_backup, BackupManifest};
+use snarkvm::{
+    console::network::{CanaryV0, MainnetV0, Network, TestnetV0},
+    ledger::store::helpers::rocksdb::ConsensusDB,
+    prelude::Ledger,
+};
+
+use aleo_std::StorageMode;
+use anyhow::{anyhow, bail, ensure, Result};
+use clap::Parser;
+use colored::Colorize;
+use rocksdb::{IteratorMode, Options, DB};
+use std::{
+    fs::{self, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
+use time::OffsetDateTime;
+
+/// Takes a consistent backup of the ledger, while the node keeps running.
+#[derive(Debug, Parser)]
+pub struct Backup {
+    /// Specify the path to write the backup archive to; it must not exist yet.
+    pub archive: PathBuf,
+    /// Specify the network of the ledger to back up.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Enables development mode, specify the unique ID of the local node to back up.
+    #[clap(long)]
+    pub dev: Option<u16>,
+    /// If development mode is enabled, specify the number of genesis validators (default: 4)
+    #[clap(long)]
+    pub dev_num_validators: Option<u16>,
+    /// Specify the path to a directory containing the ledger
+    #[clap(long = "path")]
+    pub path: Option<PathBuf>,
+}
+
+impl Backup {
+    /// Takes a consistent backup of the ledger.
+    pub fn parse(self) -> Result<String> {
+        match self.network {
+            MainnetV0::ID => self.backup::<MainnetV0>(),
+            TestnetV0::ID => self.backup::<TestnetV0>(),
+            CanaryV0::ID => self.backup::<CanaryV0>(),
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        }
+    }
+
+    /// Snapshots the ledger into a staging directory, and packages it with its manifest into the backup archive.
+    fn backup<N: Network>(&self) -> Result<String> {
+        ensure!(!self.archive.exists(), "The backup archive {} already exists", self.archive.display());
+
+        // Parse the storage mode.
+        let storage_mode = match &self.path {
+            Some(path) => StorageMode::Custom(path.clone()),
+            None => StorageMode::from(self.dev),
+        };
+        // Ensure the ledger exists.
+        let ledger_dir = aleo_std::aleo_ledger_dir(self.network, storage_mode);
+        ensure!(ledger_dir.exists(), "No snarkOS node storage was found in {}", ledger_dir.display());
+
+        // Stage the snapshot right next to the archive, as it is as large as the ledger.
+        let mut staging_dir = self.archive.clone().into_os_string();
+        staging_dir.push(".staging");
+        let staging_dir = PathBuf::from(staging_dir);
+        ensure!(!staging_dir.exists(), "The staging directory {} already exists", staging_dir.display());
+
+        let result = snapshot(&ledger_dir, &staging_dir).and_then(|_| {
+            // Load the snapshot, to ensure it is usable and to determine its latest block.
+            let genesis = load_genesis::<N>(self.dev, self.dev_num_validators)?;
+            let ledger = Ledger::<N, ConsensusDB<N>>::load(genesis, StorageMode::Custom(staging_dir.clone()))?;
+            let manifest = BackupManifest::<N> {
+                network: self.network,
+                height: ledger.latest_height(),
+                hash: ledger.latest_hash(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                timestamp: OffsetDateTime::now_utc().unix_timestamp(),
+                digest: Some(StateDigest::new(&ledger)?.digest),
+            };
+            // Package the snapshot.
+            let database = DB::open_for_read_only(&Options::default(), &staging_dir, false)?;
+            write_archive(&database, &manifest, &self.archive)?;
+            Ok(manifest)
+        });
+        let _ = fs::remove_dir_all(&staging_dir);
+        let manifest = match result {
+            Ok(manifest) => manifest,
+            Err(error) => {
+                let _ = fs::remove_file(&self.archive);
+                return Err(error);
+            }
+        };
+
+        Ok(format!(
+            "✅ Backed up the ledger at block {} ({}) {}",
+            manifest.height.to_string().bold(),
+            manifest.hash,
+            format!("(in \"{}\")", self.archive.display()).dimmed()
+        ))
+    }
+}
+
+/// Creates a consistent snapshot of the given (possibly live) ledger in the given directory.
+fn snapshot(ledger_dir: &Path, snapshot_dir: &Path) -> Result<()> {
+    // Open the ledger as a secondary instance, as the node holds the lock on the primary one.
+    let secondary_dir = std::env::temp_dir().join(format!("snarkos-backup-{}", rand::random::<u64>()));
+    let result = (|| {
+        let database = DB::open_as_secondary(&Options::default(), ledger_dir, &secondary_dir)
+            .map_err(|err| anyhow!("Couldn't open the ledger at {} - {err}", ledger_dir.display()))?;
+        // Catch up with the writes of the node, so that the snapshot is as recent as possible.
+        database.try_catch_up_with_primary()?;
+        // Copy the caught-up view of the ledger, which is consistent as it is read from a single implicit snapshot.
+        // Note: A checkpoint of a secondary instance would miss the writes it replayed from the log of the node,
+        // as they only live in its memtables.
+        copy_entries(database.iterator(IteratorMode::Start).map(|entry| Ok(entry?)), snapshot_dir, Options::default())
+    })();
+    let _ = fs::remove_dir_all(&secondary_dir);
+    result
+}
+
+/// Writes the manifest and then the entries of the given database to a new backup archive at the given path.
+fn write_archive<N: Network>(database: &DB, manifest: &BackupManifest<N>, path: &Path) -> Result<()> {
+    let file = File::options()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|err| anyhow!("Couldn't create the backup archive at {} - {err}", path.display()))?;
+    write_backup(database.iterator(IteratorMode::Start).map(|entry| Ok(entry?)), manifest, BufWriter::new(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Command, CLI};
+    use snarkos_node::BackupReader;
+    use snarkvm::prelude::{Block, FromBytes};
+
+    use rand::Rng;
+    use std::{
+        sync::{
+            atomic::{AtomicBool, AtomicU64, Ordering},
+            Arc,
+        },
+        thread,
+        time::Duration,
+    };
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Returns the keys of the database in the given directory.
+    fn read_keys(dir: &Path) -> Vec<u64> {
+        let database = DB::open_for_read_only(&Options::default(), dir, false).unwrap();
+        let keys = database
+            .iterator(IteratorMode::Start)
+            .map(|entry| u64::from_be_bytes((*entry.unwrap().0).try_into().unwrap()));
+        keys.collect()
+    }
+
+    #[test]
+    fn test_parse_backup() {
+        let cli = CLI::parse_from(["snarkos", "ledger", "backup", "/tmp/backup", "--network", "1", "--dev", "0"]);
+        let Command::Ledger(crate::commands::Ledger::Backup(backup)) = cli.command else {
+            panic!("Unexpected result of clap parsing!");
+        };
+        assert_eq!(backup.archive, PathBuf::from("/tmp/backup"));
+        assert_eq!(backup.network, 1);
+        assert_eq!(backup.dev, Some(0));
+        assert!(backup.path.is_none());
+    }
+
+    #[test]
+    fn test_backup_requires_new_archive() {
+        // Ensure an existing file is never overwritten.
+        let dir = std::env::temp_dir();
+        let cli = CLI::parse_from(["snarkos", "ledger", "backup", dir.to_str().unwrap()]);
+        let Command::Ledger(crate::commands::Ledger::Backup(backup)) = cli.command else {
+            panic!("Unexpected result of clap parsing!");
+        };
+        assert!(backup.parse().is_err());
+        assert!(dir.exists());
+    }
+
+    #[test]
+    fn test_backup_of_live_database() {
+        let root = std::env::temp_dir().join(format!("snarkos-backup-test-{}", rand::thread_rng().gen::<u64>()));
+        let primary_dir = root.join("primary");
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        let database = Arc::new(DB::open(&options, &primary_dir).unwrap());
+
+        // Write ascending keys in the background without flushing them, as a running node would.
+        let num_written = Arc::new(AtomicU64::new(0));
+        let is_done = Arc::new(AtomicBool::new(false));
+        let writer = {
+            let (database, num_written, is_done) = (database.clone(), num_written.clone(), is_done.clone());
+            thread::spawn(move || {
+                while !is_done.load(Ordering::SeqCst) {
+                    let key = num_written.load(Ordering::SeqCst);
+                    database.put(key.to_be_bytes(), [1u8; 64]).unwrap();
+                    num_written.store(key + 1, Ordering::SeqCst);
+                }
+            })
+        };
+        while num_written.load(Ordering::SeqCst) < 1_000 {
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        // Snapshot the database while it is being written.
+        let num_before = num_written.load(Ordering::SeqCst);
+        let snapshot_dir = root.join("snapshot");
+        snapshot(&primary_dir, &snapshot_dir).unwrap();
+        is_done.store(true, Ordering::SeqCst);
+        writer.join().unwrap();
+
+        // Ensure the snapshot contains every write before it was taken, and no gaps.
+        let keys = read_keys(&snapshot_dir);
+        assert!(keys.len() as u64 >= num_before);
+        assert!(keys.iter().enumerate().all(|(index, key)| *key == index as u64));
+
+        // Ensure the snapshot survives a round trip through an archive.
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let manifest = BackupManifest::<CurrentNetwork> {
+            network: CurrentNetwork::ID,
+            height: 0,
+            hash: genesis.hash(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            timestamp: 0,
+            digest: None,
+        };
+        let archive = root.join("backup.zst");
+        write_archive(&DB::open_for_read_only(&Options::default(), &snapshot_dir, false).unwrap(), &manifest, &archive)
+            .unwrap();
+        let reader = BackupReader::<CurrentNetwork>::open(&archive).unwrap();
+        assert_eq!(reader.manifest(), &manifest);
+        reader.unpack(&root.join("restored"), Options::default()).unwrap();
+        assert_eq!(read_keys(&root.join("restored")), keys);
+
+        drop(database);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,111 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::column_family_sizes;
+
+use aleo_std::StorageMode;
+use anyhow::{anyhow, ensure, Result};
+use clap::Parser;
+use colored::Colorize;
+use rocksdb::{Options, DB};
+use std::{fmt::Write, path::PathBuf, time::Instant};
+
+/// Compacts the local ledger database, discarding its tombstones; the node must not be running.
+#[derive(Debug, Parser)]
+pub struct Compact {
+    /// Specify the network of the ledger to compact.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Enables development mode, specify the unique ID of the local node to compact.
+    #[clap(long)]
+    pub dev: Option<u16>,
+    /// Specify the path to a directory containing the ledger
+    #[clap(long = "path")]
+    pub path: Option<PathBuf>,
+}
+
+impl Compact {
+    /// Compacts every column family of the ledger database, and reports the sizes before and after.
+    pub fn parse(self) -> Result<String> {
+        // Parse the storage mode.
+        let storage_mode = match self.path {
+            Some(path) => StorageMode::Custom(path),
+            None => StorageMode::from(self.dev),
+        };
+        let ledger_dir = aleo_std::aleo_ledger_dir(self.network, storage_mode);
+        ensure!(ledger_dir.exists(), "No snarkOS node storage was found in {}", ledger_dir.display());
+
+        // Open the database; this fails if the node is running, as it holds the lock on it.
+        let options = Options::default();
+        let column_families = DB::list_cf(&options, &ledger_dir)?;
+        let database = DB::open_cf(&options, &ledger_dir, &column_families).map_err(|err| {
+            anyhow!("Couldn't open the ledger at {} (is the node running?) - {err}", ledger_dir.display())
+        })?;
+
+        // Compact the full key range of each column family.
+        let sizes_before = column_family_sizes(&database, &column_families)?;
+        let timer = Instant::now();
+        for name in &column_families {
+            let column_family = database.cf_handle(name).ok_or_else(|| anyhow!("Missing column family '{name}'"))?;
+            database.compact_range_cf(column_family, None::<&[u8]>, None::<&[u8]>);
+        }
+        let elapsed = timer.elapsed();
+        let sizes_after = column_family_sizes(&database, &column_families)?;
+
+        // Prepare the compaction stats.
+        let mut summary = format!(
+            "✅ Compacted the ledger from {} to {} bytes in {:.1} seconds {}",
+            sizes_before.values().sum::<u64>(),
+            sizes_after.values().sum::<u64>().to_string().bold(),
+            elapsed.as_secs_f64(),
+            format!("(in \"{}\")", ledger_dir.display()).dimmed()
+        );
+        for (name, before) in &sizes_before {
+            let after = sizes_after.get(name).copied().unwrap_or_default();
+            write!(summary, "\n  {name}: {before} -> {after} bytes")?;
+        }
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::Rng;
+
+    #[test]
+    fn test_compact() {
+        let path = std::env::temp_dir().join(format!("snarkos-compact-{}", rand::thread_rng().gen::<u64>()));
+        // Write and delete some keys, to leave tombstones behind.
+        {
+            let mut options = Options::default();
+            options.create_if_missing(true);
+            let database = DB::open(&options, &path).unwrap();
+            for i in 0u32..1000 {
+                database.put(i.to_le_bytes(), [0u8; 100]).unwrap();
+            }
+            database.flush().unwrap();
+            for i in 0u32..1000 {
+                database.delete(i.to_le_bytes()).unwrap();
+            }
+            database.flush().unwrap();
+        }
+
+        let compact = Compact { network: 0, dev: None, path: Some(path.clone()) };
+        assert!(compact.parse().is_ok());
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}
@@ -0,0 +1,136 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::load_genesis;
+use snarkos_node::cdn::{ArchiveHeader, ArchiveIndex, ArchiveIndexEntry, ArchiveWriter, ARCHIVE_INDEX_FILE};
+use snarkvm::{
+    console::network::{CanaryV0, MainnetV0, Network, TestnetV0},
+    ledger::store::helpers::rocksdb::ConsensusDB,
+    prelude::{Ledger, ToBytes},
+};
+
+use aleo_std::StorageMode;
+use anyhow::{bail, ensure, Result};
+use clap::Parser;
+use colored::Colorize;
+use std::{fs::File, io::BufWriter, path::PathBuf};
+
+/// Exports a range of canonical blocks from the local ledger to a compressed, checksummed archive.
+#[derive(Debug, Parser)]
+pub struct Export {
+    /// Specify the network of the ledger to export from.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Enables development mode, specify the unique ID of the local node to export from.
+    #[clap(long)]
+    pub dev: Option<u16>,
+    /// If development mode is enabled, specify the number of genesis validators (default: 4)
+    #[clap(long)]
+    pub dev_num_validators: Option<u16>,
+    /// Specify the path to a directory containing the ledger
+    #[clap(long = "path")]
+    pub path: Option<PathBuf>,
+    /// Specify the height of the first block to export
+    #[clap(default_value = "0", long = "start")]
+    pub start: u32,
+    /// Specify the height after the last block to export (defaults to the latest height, inclusive)
+    #[clap(long = "end")]
+    pub end: Option<u32>,
+    /// Specify the path to write the archive to, i.e. `chain.aleo.zst`
+    #[clap(long = "output")]
+    pub output: PathBuf,
+    /// Appends the archive to the `index.json` in its directory, to serve the directory as a static block archive
+    #[clap(long = "index")]
+    pub index: bool,
+}
+
+impl Export {
+    /// The number of blocks between progress updates.
+    const PROGRESS_INTERVAL: u32 = 10_000;
+
+    /// Exports the blocks.
+    pub fn parse(self) -> Result<String> {
+        match self.network {
+            MainnetV0::ID => self.export::<MainnetV0>(),
+            TestnetV0::ID => self.export::<TestnetV0>(),
+            CanaryV0::ID => self.export::<CanaryV0>(),
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        }
+    }
+
+    /// Writes the requested blocks to the archive.
+    fn export<N: Network>(&self) -> Result<String> {
+        // Parse the storage mode.
+        let storage_mode = match &self.path {
+            Some(path) => StorageMode::Custom(path.clone()),
+            None => StorageMode::from(self.dev),
+        };
+        // Ensure the ledger exists, as loading it would otherwise initialize a new one.
+        let ledger_dir = aleo_std::aleo_ledger_dir(self.network, storage_mode.clone());
+        ensure!(ledger_dir.exists(), "No snarkOS node storage was found in {}", ledger_dir.display());
+
+        // Load the ledger.
+        let genesis = load_genesis::<N>(self.dev, self.dev_num_validators)?;
+        let ledger = Ledger::<N, ConsensusDB<N>>::load(genesis, storage_mode)?;
+        // Determine the range of blocks to export.
+        let end = self.end.unwrap_or(ledger.latest_height() + 1);
+        ensure!(self.start < end, "The start height must be less than the end height");
+        ensure!(end <= ledger.latest_height() + 1, "The end height exceeds the ledger height");
+
+        // Prepare the updated index of the static block archive, if requested.
+        let index = match self.index {
+            true => Some(self.append_to_index(end)?),
+            false => None,
+        };
+
+        // Write to a temporary file first, so that a failed export never leaves a truncated archive behind.
+        let temp_path = self.output.with_extension("tmp");
+        let header = ArchiveHeader { network: self.network, start_height: self.start, end_height: end };
+        let mut writer = ArchiveWriter::new(BufWriter::new(File::create(&temp_path)?), header)?;
+        for height in self.start..end {
+            writer.write_block(&ledger.get_block(height)?.to_bytes_le()?)?;
+            if (height + 1 - self.start) % Self::PROGRESS_INTERVAL == 0 {
+                println!("Exported blocks {} to {height}", self.start);
+            }
+        }
+        writer.finish()?.into_inner().map_err(|error| error.into_error())?.sync_all()?;
+        std::fs::rename(&temp_path, &self.output)?;
+        // Write the updated index, now that the archive is in place.
+        if let Some((index_path, index)) = index {
+            std::fs::write(index_path, serde_json::to_string_pretty(&index)?)?;
+        }
+
+        Ok(format!(
+            "✅ Exported blocks {} to {} {}",
+            self.start,
+            (end - 1).to_string().bold(),
+            format!("(in \"{}\")", self.output.display()).dimmed()
+        ))
+    }
+
+    /// Returns the path and contents of the index in the output directory, with the archive appended to it.
+    fn append_to_index(&self, end: u32) -> Result<(PathBuf, ArchiveIndex)> {
+        let index_path = self.output.with_file_name(ARCHIVE_INDEX_FILE);
+        let mut index = match index_path.exists() {
+            true => serde_json::from_slice::<ArchiveIndex>(&std::fs::read(&index_path)?)?,
+            false => ArchiveIndex::new(self.network),
+        };
+        ensure!(index.network == self.network, "The archive index is for network {}", index.network);
+        let Some(file) = self.output.file_name().and_then(|name| name.to_str()) else {
+            bail!("Invalid archive file name '{}'", self.output.display())
+        };
+        index.append(ArchiveIndexEntry { file: file.to_string(), start_height: self.start, end_height: end })?;
+        Ok((index_path, index))
+    }
+}
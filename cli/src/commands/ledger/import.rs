@@ -0,0 +1,153 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::load_genesis;
+use snarkos_node::cdn::ArchiveReader;
+use snarkvm::{
+    console::network::{CanaryV0, MainnetV0, Network, TestnetV0},
+    ledger::store::helpers::rocksdb::ConsensusDB,
+    prelude::{Block, FromBytes, Ledger},
+};
+
+use aleo_std::StorageMode;
+use anyhow::{anyhow, bail, ensure, Result};
+use clap::Parser;
+use colored::Colorize;
+use std::{fs::File, io::BufReader, path::PathBuf};
+
+/// Imports the blocks of an archive into the local ledger; the node must not be running.
+#[derive(Debug, Parser)]
+pub struct Import {
+    /// Specify the path of the archive to import.
+    pub archive: PathBuf,
+    /// Specify the network of the ledger to import into.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Enables development mode, specify the unique ID of the local node to import into.
+    #[clap(long)]
+    pub dev: Option<u16>,
+    /// If development mode is enabled, specify the number of genesis validators (default: 4)
+    #[clap(long)]
+    pub dev_num_validators: Option<u16>,
+    /// Specify the path to a directory containing the ledger
+    #[clap(long = "path")]
+    pub path: Option<PathBuf>,
+}
+
+impl Import {
+    /// The number of blocks between progress updates.
+    const PROGRESS_INTERVAL: u32 = 10_000;
+
+    /// Imports the blocks.
+    pub fn parse(self) -> Result<String> {
+        match self.network {
+            MainnetV0::ID => self.import::<MainnetV0>(),
+            TestnetV0::ID => self.import::<TestnetV0>(),
+            CanaryV0::ID => self.import::<CanaryV0>(),
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        }
+    }
+
+    /// Verifies each block of the archive against the ledger, and advances the ledger with the new ones.
+    fn import<N: Network>(&self) -> Result<String> {
+        // Read the archive header.
+        let mut reader = ArchiveReader::new(BufReader::new(File::open(&self.archive)?))?;
+        let header = reader.header();
+        ensure!(
+            header.network == self.network,
+            "The archive is of network {}, but network {} was specified",
+            header.network,
+            self.network
+        );
+
+        // Parse the storage mode.
+        let storage_mode = match &self.path {
+            Some(path) => StorageMode::Custom(path.clone()),
+            None => StorageMode::from(self.dev),
+        };
+        // Load the ledger, initializing a new one if it does not exist yet.
+        let genesis = load_genesis::<N>(self.dev, self.dev_num_validators)?;
+        let ledger = Ledger::<N, ConsensusDB<N>>::load(genesis, storage_mode)?;
+        let start_height = ledger.latest_height();
+        ensure!(
+            header.start_height <= start_height + 1,
+            "The archive starts at block {}, but the ledger ends at block {start_height}",
+            header.start_height
+        );
+
+        // Ingest the blocks, in order.
+        let mut expected_height = header.start_height;
+        while let Some(bytes) = reader.read_block()? {
+            let block = Block::<N>::from_bytes_le(&bytes)?;
+            ensure!(block.height() == expected_height, "Expected block {expected_height} in the archive");
+            // Ensure the blocks the ledger already has are the same, and advance with the new ones.
+            if block.height() <= ledger.latest_height() {
+                ensure!(
+                    ledger.get_hash(block.height())? == block.hash(),
+                    "Block {} of the archive conflicts with the ledger",
+                    block.height()
+                );
+            } else {
+                ledger
+                    .check_next_block(&block)
+                    .and_then(|_| ledger.advance_to_next_block(&block))
+                    .map_err(|error| anyhow!("Failed to import block {} - {error}", block.height()))?;
+            }
+            if (expected_height + 1 - header.start_height) % Self::PROGRESS_INTERVAL == 0 {
+                println!("Imported blocks {} to {expected_height}", header.start_height);
+            }
+            expected_height += 1;
+        }
+
+        Ok(format!(
+            "✅ Imported blocks {} to {} {}",
+            start_height + 1,
+            ledger.latest_height().to_string().bold(),
+            format!("(from \"{}\")", self.archive.display()).dimmed()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node::cdn::{ArchiveHeader, ArchiveWriter};
+    use snarkvm::prelude::MainnetV0;
+
+    use rand::Rng;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_import_rejects_other_network() {
+        let path = std::env::temp_dir().join(format!("snarkos-archive-{}", rand::thread_rng().gen::<u64>()));
+        let header = ArchiveHeader { network: CurrentNetwork::ID + 1, start_height: 0, end_height: 1 };
+        let mut writer = ArchiveWriter::new(File::create(&path).unwrap(), header).unwrap();
+        writer.write_block(&[0u8; 10]).unwrap();
+        writer.finish().unwrap();
+
+        // Ensure the archive is rejected before the ledger is touched.
+        let import = Import {
+            archive: path.clone(),
+            network: CurrentNetwork::ID,
+            dev: None,
+            dev_num_validators: None,
+            path: Some(std::env::temp_dir().join("snarkos-import-unused")),
+        };
+        assert!(import.parse().is_err());
+        assert!(!std::env::temp_dir().join("snarkos-import-unused").exists());
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{load_genesis, replay};
+use snarkos_node::{bft::helpers::proposal_cache_path, invalid_blocks_path, sync::InvalidBlocks};
+use snarkvm::{
+    console::network::{CanaryV0, MainnetV0, Network, TestnetV0},
+    ledger::store::helpers::rocksdb::ConsensusDB,
+    prelude::Ledger,
+};
+
+use aleo_std::StorageMode;
+use anyhow::{bail, ensure, Result};
+use clap::Parser;
+use colored::Colorize;
+use std::path::PathBuf;
+
+/// Marks a block as invalid, rolling the local ledger back below it if needed, so that the node
+/// refuses to sync it and reorganizes to an alternative branch; the node must not be running.
+#[derive(Debug, Parser)]
+pub struct Invalidate {
+    /// Specify the hash of the block to mark as invalid
+    pub hash: String,
+    /// Specify the network of the ledger.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Enables development mode, specify the unique ID of the local node.
+    #[clap(long)]
+    pub dev: Option<u16>,
+    /// If development mode is enabled, specify the number of genesis validators (default: 4)
+    #[clap(long)]
+    pub dev_num_validators: Option<u16>,
+    /// Specify the path to a directory containing the ledger
+    #[clap(long = "path")]
+    pub path: Option<PathBuf>,
+}
+
+impl Invalidate {
+    /// Marks the block as invalid.
+    pub fn parse(self) -> Result<String> {
+        match self.network {
+            MainnetV0::ID => self.invalidate::<MainnetV0>(),
+            TestnetV0::ID => self.invalidate::<TestnetV0>(),
+            CanaryV0::ID => self.invalidate::<CanaryV0>(),
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        }
+    }
+
+    /// Records the block as invalid, and rolls the ledger back to the block before it, if the ledger contains it.
+    fn invalidate<N: Network>(&self) -> Result<String> {
+        let hash = match self.hash.parse::<N::BlockHash>() {
+            Ok(hash) => hash,
+            Err(error) => bail!("Invalid block hash '{}' - {error}", self.hash),
+        };
+
+        // Parse the storage mode.
+        let storage_mode = match &self.path {
+            Some(path) => StorageMode::Custom(path.clone()),
+            None => StorageMode::from(self.dev),
+        };
+        // Ensure the ledger exists, as loading it would otherwise initialize a new one.
+        let ledger_dir = aleo_std::aleo_ledger_dir(self.network, storage_mode.clone());
+        ensure!(ledger_dir.exists(), "No snarkOS node storage was found in {}", ledger_dir.display());
+
+        // Load the ledger.
+        let genesis = load_genesis::<N>(self.dev, self.dev_num_validators)?;
+        ensure!(hash != genesis.hash(), "The genesis block cannot be marked as invalid");
+        let ledger = Ledger::<N, ConsensusDB<N>>::load(genesis.clone(), storage_mode.clone())?;
+
+        // Record the block as invalid first, so that the node never syncs it again, even if the rollback fails.
+        let invalid_blocks_path = invalid_blocks_path(self.network, &storage_mode);
+        let mut invalid_blocks = InvalidBlocks::<N>::load(&invalid_blocks_path)?;
+        invalid_blocks.insert(hash);
+        invalid_blocks.save(&invalid_blocks_path)?;
+
+        // If the ledger does not contain the block, there is nothing to roll back.
+        let Ok(height) = ledger.get_height(&hash) else {
+            return Ok(format!("✅ Marked block {hash} as invalid {}", "(not in the ledger)".dimmed()));
+        };
+
+        // Replay the blocks below the invalid block.
+        replay(ledger, genesis, &ledger_dir, height - 1)?;
+
+        // Remove the proposal cache, as it refers to rounds beyond the new tip.
+        let proposal_cache_path = proposal_cache_path(self.network, self.dev);
+        if proposal_cache_path.exists() {
+            if let Err(err) = std::fs::remove_file(&proposal_cache_path) {
+                bail!("Failed to remove the proposal cache file at {}: {err}", proposal_cache_path.display());
+            }
+        }
+
+        Ok(format!(
+            "✅ Marked block {height} ({hash}) as invalid, and rolled back the ledger to block {} {}",
+            (height - 1).to_string().bold(),
+            format!("(in \"{}\")", ledger_dir.display()).dimmed()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::{Command, Ledger, CLI};
+
+    use clap::Parser;
+
+    #[test]
+    fn test_parse_invalidate() {
+        let cli = CLI::parse_from(["snarkos", "ledger", "invalidate", "ab1hash", "--dev", "0"]);
+        let Command::Ledger(Ledger::Invalidate(invalidate)) = cli.command else {
+            panic!("Unexpected result of clap parsing!");
+        };
+        assert_eq!(invalidate.hash, "ab1hash");
+        assert_eq!(invalidate.dev, Some(0));
+
+        // Ensure the hash is required.
+        assert!(CLI::try_parse_from(["snarkos", "ledger", "invalidate"]).is_err());
+    }
+}
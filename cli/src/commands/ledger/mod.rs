@@ -0,0 +1,105 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod backup;
+pub use backup::*;
+
+mod compact;
+pub use compact::*;
+
+mod export;
+pub use export::*;
+
+mod import;
+pub use import::*;
+
+mod invalidate;
+pub use invalidate::*;
+
+mod query;
+pub use query::*;
+
+mod reindex;
+pub use reindex::*;
+
+mod restore;
+pub use restore::*;
+
+mod rollback;
+pub use rollback::*;
+
+mod stats;
+pub use stats::*;
+
+mod verify;
+pub use verify::*;
+
+use crate::commands::Start;
+use snarkvm::{console::network::Network, prelude::Block};
+
+use anyhow::Result;
+use clap::Parser;
+
+/// Commands to manage the ledger storage
+#[derive(Debug, Parser)]
+pub enum Ledger {
+    /// Take a consistent backup of the ledger, while the node keeps running.
+    Backup(Backup),
+    /// Compact the ledger database, discarding its tombstones.
+    Compact(Compact),
+    /// Export a range of blocks to a compressed, checksummed archive.
+    Export(Export),
+    /// Import the blocks of an archive into the ledger.
+    Import(Import),
+    /// Mark a block as invalid, rolling the ledger back below it, so that the node syncs an alternative branch.
+    Invalidate(Invalidate),
+    /// Print a block or a transaction from the local ledger, by height, hash, or transaction ID.
+    Query(Query),
+    /// Rebuild the derived indexes of the ledger from its blocks.
+    Reindex(Reindex),
+    /// Restore the ledger from a backup, leaving the node ready to start.
+    Restore(Restore),
+    /// Roll back the ledger to the given height.
+    Rollback(Rollback),
+    /// Report the statistics of the ledger, as text or JSON.
+    Stats(Stats),
+    /// Verify the integrity of the local ledger, reporting the first corrupt block.
+    Verify(Verify),
+}
+
+impl Ledger {
+    pub fn parse(self) -> Result<String> {
+        match self {
+            Self::Backup(backup) => backup.parse(),
+            Self::Compact(compact) => compact.parse(),
+            Self::Export(export) => export.parse(),
+            Self::Import(import) => import.parse(),
+            Self::Invalidate(invalidate) => invalidate.parse(),
+            Self::Query(query) => query.parse(),
+            Self::Reindex(reindex) => reindex.parse(),
+            Self::Restore(restore) => restore.parse(),
+            Self::Rollback(rollback) => rollback.parse(),
+            Self::Stats(stats) => stats.parse(),
+            Self::Verify(verify) => verify.parse(),
+        }
+    }
+}
+
+/// Returns the genesis block of the network, as a node started with the given development flags would use.
+pub(crate) fn load_genesis<N: Network>(dev: Option<u16>, dev_num_validators: Option<u16>) -> Result<Block<N>> {
+    let mut start = Start::try_parse_from(["snarkos"].iter())?;
+    start.dev = dev;
+    start.dev_num_validators = dev_num_validators;
+    start.parse_genesis::<N>()
+}
@@ -0,0 +1,142 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::load_genesis;
+use snarkvm::{
+    console::network::{CanaryV0, MainnetV0, Network, TestnetV0},
+    ledger::store::helpers::rocksdb::ConsensusDB,
+    prelude::Ledger,
+};
+
+use aleo_std::StorageMode;
+use anyhow::{bail, ensure, Result};
+use clap::Parser;
+use std::{path::PathBuf, str::FromStr};
+
+/// An object to look up in the ledger.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LedgerQuery<N: Network> {
+    /// The block at the given height.
+    Height(u32),
+    /// The block with the given hash.
+    Hash(N::BlockHash),
+    /// The transaction with the given ID.
+    Transaction(N::TransactionID),
+}
+
+impl<N: Network> FromStr for LedgerQuery<N> {
+    type Err = anyhow::Error;
+
+    /// Parses a block height, a block hash, or a transaction ID.
+    fn from_str(query: &str) -> Result<Self> {
+        if let Ok(height) = query.parse::<u32>() {
+            return Ok(Self::Height(height));
+        }
+        if let Ok(hash) = query.parse::<N::BlockHash>() {
+            return Ok(Self::Hash(hash));
+        }
+        if let Ok(transaction_id) = query.parse::<N::TransactionID>() {
+            return Ok(Self::Transaction(transaction_id));
+        }
+        bail!("'{query}' is not a block height, a block hash, or a transaction ID")
+    }
+}
+
+/// Prints a block or a transaction from the local ledger as JSON; the node must not be running.
+#[derive(Debug, Parser)]
+pub struct Query {
+    /// Specify the block height, block hash, or transaction ID to look up
+    pub query: String,
+    /// Specify the network of the ledger.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Enables development mode, specify the unique ID of the local node.
+    #[clap(long)]
+    pub dev: Option<u16>,
+    /// If development mode is enabled, specify the number of genesis validators (default: 4)
+    #[clap(long)]
+    pub dev_num_validators: Option<u16>,
+    /// Specify the path to a directory containing the ledger
+    #[clap(long = "path")]
+    pub path: Option<PathBuf>,
+}
+
+impl Query {
+    /// Looks up the requested object.
+    pub fn parse(self) -> Result<String> {
+        match self.network {
+            MainnetV0::ID => self.query::<MainnetV0>(),
+            TestnetV0::ID => self.query::<TestnetV0>(),
+            CanaryV0::ID => self.query::<CanaryV0>(),
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        }
+    }
+
+    /// Reads the requested object from the ledger, and returns it as JSON.
+    fn query<N: Network>(&self) -> Result<String> {
+        // Parse the query before opening the ledger.
+        let query = self.query.parse::<LedgerQuery<N>>()?;
+
+        // Parse the storage mode.
+        let storage_mode = match &self.path {
+            Some(path) => StorageMode::Custom(path.clone()),
+            None => StorageMode::from(self.dev),
+        };
+        // Ensure the ledger exists, as loading it would otherwise initialize a new one.
+        let ledger_dir = aleo_std::aleo_ledger_dir(self.network, storage_mode.clone());
+        ensure!(ledger_dir.exists(), "No snarkOS node storage was found in {}", ledger_dir.display());
+
+        // Load the ledger.
+        let genesis = load_genesis::<N>(self.dev, self.dev_num_validators)?;
+        let ledger = Ledger::<N, ConsensusDB<N>>::load(genesis, storage_mode)?;
+        // Look up the object.
+        match query {
+            LedgerQuery::Height(height) => {
+                ensure!(height <= ledger.latest_height(), "Block {height} is not in the ledger");
+                Ok(serde_json::to_string_pretty(&ledger.get_block(height)?)?)
+            }
+            LedgerQuery::Hash(hash) => match ledger.get_height(&hash).and_then(|height| ledger.get_block(height)) {
+                Ok(block) => Ok(serde_json::to_string_pretty(&block)?),
+                Err(_) => bail!("Block '{hash}' is not in the ledger"),
+            },
+            LedgerQuery::Transaction(transaction_id) => match ledger.get_transaction(transaction_id) {
+                Ok(transaction) => Ok(serde_json::to_string_pretty(&transaction)?),
+                Err(_) => bail!("Transaction '{transaction_id}' is not in the ledger"),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{block::Block, FromBytes};
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_parse_query() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let transaction_id = *genesis.transaction_ids().next().unwrap();
+
+        assert_eq!("0".parse::<LedgerQuery<CurrentNetwork>>().unwrap(), LedgerQuery::Height(0));
+        assert_eq!(genesis.hash().to_string().parse::<LedgerQuery<_>>().unwrap(), LedgerQuery::Hash(genesis.hash()));
+        assert_eq!(
+            transaction_id.to_string().parse::<LedgerQuery<_>>().unwrap(),
+            LedgerQuery::Transaction(transaction_id)
+        );
+        assert!("-1".parse::<LedgerQuery<CurrentNetwork>>().is_err());
+        assert!("ab1invalid".parse::<LedgerQuery<CurrentNetwork>>().is_err());
+    }
+}
@@ -0,0 +1,145 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::load_genesis;
+use snarkvm::{
+    console::network::{CanaryV0, MainnetV0, Network, TestnetV0},
+    ledger::store::helpers::rocksdb::ConsensusDB,
+    prelude::{Block, Ledger},
+};
+
+use aleo_std::StorageMode;
+use anyhow::{anyhow, bail, ensure, Result};
+use clap::Parser;
+use colored::Colorize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Rebuilds the derived indexes of the local ledger by replaying its blocks into a fresh ledger;
+/// the node must not be running.
+#[derive(Debug, Parser)]
+pub struct Reindex {
+    /// Specify the network of the ledger to reindex.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Enables development mode, specify the unique ID of the local node to reindex.
+    #[clap(long)]
+    pub dev: Option<u16>,
+    /// If development mode is enabled, specify the number of genesis validators (default: 4)
+    #[clap(long)]
+    pub dev_num_validators: Option<u16>,
+    /// Specify the path to a directory containing the ledger
+    #[clap(long = "path")]
+    pub path: Option<PathBuf>,
+}
+
+impl Reindex {
+    /// Reindexes the ledger.
+    pub fn parse(self) -> Result<String> {
+        match self.network {
+            MainnetV0::ID => self.reindex::<MainnetV0>(),
+            TestnetV0::ID => self.reindex::<TestnetV0>(),
+            CanaryV0::ID => self.reindex::<CanaryV0>(),
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        }
+    }
+
+    /// Replays the blocks of the ledger into a fresh ledger, and swaps it in once it is complete.
+    fn reindex<N: Network>(&self) -> Result<String> {
+        // Parse the storage mode.
+        let storage_mode = match &self.path {
+            Some(path) => StorageMode::Custom(path.clone()),
+            None => StorageMode::from(self.dev),
+        };
+        // Ensure the ledger exists, as loading it would otherwise initialize a new one.
+        let ledger_dir = aleo_std::aleo_ledger_dir(self.network, storage_mode.clone());
+        ensure!(ledger_dir.exists(), "No snarkOS node storage was found in {}", ledger_dir.display());
+
+        // Load the ledger, without checking its integrity, as its indexes may be corrupt.
+        let genesis = load_genesis::<N>(self.dev, self.dev_num_validators)?;
+        let ledger = Ledger::<N, ConsensusDB<N>>::load_unchecked(genesis.clone(), storage_mode)?;
+        let latest_height = ledger.latest_height();
+
+        // Replay the blocks; advancing a fresh ledger rebuilds all of the derived indexes.
+        replay(ledger, genesis, &ledger_dir, latest_height)?;
+
+        Ok(format!(
+            "✅ Reindexed blocks 0 to {} {}",
+            latest_height.to_string().bold(),
+            format!("(in \"{}\")", ledger_dir.display()).dimmed()
+        ))
+    }
+}
+
+/// The number of blocks between progress updates.
+const PROGRESS_INTERVAL: u32 = 10_000;
+
+/// Replays the blocks of the given ledger up to the given height into a fresh ledger,
+/// and swaps it in for the ledger in the given directory once it is complete.
+pub(super) fn replay<N: Network>(
+    ledger: Ledger<N, ConsensusDB<N>>,
+    genesis: Block<N>,
+    ledger_dir: &Path,
+    end_height: u32,
+) -> Result<()> {
+    // Initialize a fresh ledger next to the current one, discarding the leftovers of an interrupted replay.
+    let replay_dir = sibling_dir(ledger_dir, "replay")?;
+    if replay_dir.exists() {
+        fs::remove_dir_all(&replay_dir)?;
+    }
+    let replayed = Ledger::<N, ConsensusDB<N>>::load(genesis, StorageMode::Custom(replay_dir.clone()))?;
+
+    // Replay the blocks, in order.
+    for height in 1..=end_height {
+        let block = ledger.get_block(height).map_err(|error| anyhow!("Failed to read block {height} - {error}"))?;
+        replayed.advance_to_next_block(&block).map_err(|error| anyhow!("Failed to replay block {height} - {error}"))?;
+        if height % PROGRESS_INTERVAL == 0 {
+            println!("Replayed blocks 0 to {height}");
+        }
+    }
+    ensure!(replayed.latest_hash() == ledger.get_hash(end_height)?, "The replayed ledger does not match the ledger");
+    drop((ledger, replayed));
+
+    // Swap in the replayed ledger, and remove the previous one.
+    let previous_dir = sibling_dir(ledger_dir, "previous")?;
+    fs::rename(ledger_dir, &previous_dir)?;
+    fs::rename(&replay_dir, ledger_dir)?;
+    fs::remove_dir_all(&previous_dir)?;
+    Ok(())
+}
+
+/// Returns the path of a directory next to the given ledger directory, with the given suffix.
+fn sibling_dir(ledger_dir: &Path, suffix: &str) -> Result<PathBuf> {
+    let name = ledger_dir.file_name().ok_or_else(|| anyhow!("Invalid ledger path {}", ledger_dir.display()))?;
+    let mut name = name.to_os_string();
+    name.push(format!(".{suffix}"));
+    Ok(ledger_dir.with_file_name(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sibling_dir() {
+        let ledger_dir = PathBuf::from("/home/aleo/.aleo/storage/ledger-0");
+        assert_eq!(
+            sibling_dir(&ledger_dir, "reindex").unwrap(),
+            PathBuf::from("/home/aleo/.aleo/storage/ledger-0.reindex")
+        );
+        assert!(sibling_dir(Path::new("/"), "reindex").is_err());
+    }
+}
@@ -0,0 +1,236 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{load_genesis, verify::verify_block};
+use crate::helpers::{unlock_ledger_dir, DatabaseOptions, LedgerKey};
+use snarkos_node::{rest::StateDigest, BackupReader};
+use snarkvm::{
+    circuit::{Aleo, AleoCanaryV0, AleoTestnetV0, AleoV0},
+    console::network::{CanaryV0, MainnetV0, Network, TestnetV0},
+    ledger::store::helpers::rocksdb::ConsensusDB,
+    prelude::Ledger,
+};
+
+use aleo_std::StorageMode;
+use anyhow::{anyhow, bail, ensure, Result};
+use clap::Parser;
+use colored::Colorize;
+use std::{
+    fs::{self, File},
+    io::Read,
+    path::PathBuf,
+};
+
+/// Restores the ledger from a backup or a state snapshot, leaving the node ready to start.
+///
+/// The restored ledger is verified from its latest block down through the most recent blocks, which must form
+/// a valid chain ending with the trusted block hash, if one is given. Note that no block commits to the finalize
+/// state, so the restored mappings are only as trustworthy as the node that produced the archive.
+#[derive(Debug, Parser)]
+pub struct Restore {
+    /// Specify the backup archive or state snapshot to restore, as a path or an http(s) URL.
+    pub archive: String,
+    /// Specify the hash of the latest block the archive must hold, which is required for URLs
+    #[clap(long = "trusted-hash")]
+    pub trusted_hash: Option<String>,
+    /// Specify the network of the ledger to restore.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Enables development mode, specify the unique ID of the local node to restore.
+    #[clap(long)]
+    pub dev: Option<u16>,
+    /// If development mode is enabled, specify the number of genesis validators (default: 4)
+    #[clap(long)]
+    pub dev_num_validators: Option<u16>,
+    /// Specify the path to a directory to restore the ledger to
+    #[clap(long = "path")]
+    pub path: Option<PathBuf>,
+    /// The tuning options of the restored ledger database
+    #[clap(flatten)]
+    pub database: DatabaseOptions,
+    /// Encrypt the restored ledger at rest (Linux only), with the key in `SNARKOS_LEDGER_KEY` or the OS keyring
+    #[clap(long = "encrypt")]
+    pub encrypt: bool,
+}
+
+impl Restore {
+    /// The number of most recent blocks that are verified after the restore.
+    const NUM_VERIFIED_BLOCKS: u32 = 100;
+
+    /// Restores the ledger from a backup.
+    pub fn parse(self) -> Result<String> {
+        match self.network {
+            MainnetV0::ID => self.restore::<MainnetV0, AleoV0>(),
+            TestnetV0::ID => self.restore::<TestnetV0, AleoTestnetV0>(),
+            CanaryV0::ID => self.restore::<CanaryV0, AleoCanaryV0>(),
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        }
+    }
+
+    /// Validates the backup, unpacks it into the ledger directory, and verifies the restored blocks.
+    fn restore<N: Network, A: Aleo<Network = N>>(&self) -> Result<String> {
+        // Parse the trusted block hash.
+        let trusted_hash = match &self.trusted_hash {
+            Some(hash) => match hash.parse::<N::BlockHash>() {
+                Ok(hash) => Some(hash),
+                Err(error) => bail!("Invalid block hash '{hash}' - {error}"),
+            },
+            None => None,
+        };
+        // Open the archive, downloading it if needed.
+        let is_remote = self.archive.starts_with("http://") || self.archive.starts_with("https://");
+        let archive: Box<dyn Read> = match is_remote {
+            // Ensure a downloaded archive is anchored to a block hash the operator trusts.
+            true if trusted_hash.is_none() => bail!("The '--trusted-hash' flag is required to restore from a URL"),
+            true => Box::new(
+                ureq::get(&self.archive)
+                    .call()
+                    .map_err(|err| anyhow!("Couldn't download the archive from {} - {err}", self.archive))?
+                    .into_reader(),
+            ),
+            false => Box::new(
+                File::open(&self.archive)
+                    .map_err(|err| anyhow!("Couldn't open the archive at {} - {err}", self.archive))?,
+            ),
+        };
+
+        // Validate the manifest.
+        let reader = BackupReader::<N, _>::new(archive)?;
+        let manifest = reader.manifest().clone();
+        ensure!(
+            manifest.network == self.network,
+            "The backup is of network {}, but network {} was specified",
+            manifest.network,
+            self.network
+        );
+        if let Some(trusted_hash) = trusted_hash {
+            ensure!(
+                manifest.hash == trusted_hash,
+                "The backup ends with block {}, not with the trusted block {trusted_hash}",
+                manifest.hash
+            );
+        }
+        // Parse the storage mode.
+        let storage_mode = match &self.path {
+            Some(path) => StorageMode::Custom(path.clone()),
+            None => StorageMode::from(self.dev),
+        };
+        // Ensure an existing ledger is never overwritten.
+        let ledger_dir = aleo_std::aleo_ledger_dir(self.network, storage_mode.clone());
+        ensure!(
+            ledger_dir.read_dir().map_or(true, |mut entries| entries.next().is_none()),
+            "A ledger already exists in {}; run `snarkos clean` first to replace it",
+            ledger_dir.display()
+        );
+        // Unlock the encrypted ledger directory, which is encrypted if it does not exist yet.
+        if self.encrypt {
+            unlock_ledger_dir(&ledger_dir, &LedgerKey::load()?)?;
+        }
+
+        // Unpack the ledger, and verify its tip against the genesis block and the manifest.
+        // Note: The manifest hash is trusted, or checked against the trusted block hash above.
+        let result = reader.unpack(&ledger_dir, self.database.to_options()).and_then(|_| {
+            let genesis = load_genesis::<N>(self.dev, self.dev_num_validators)?;
+            let ledger = Ledger::<N, ConsensusDB<N>>::load(genesis, storage_mode)?;
+            ensure!(
+                ledger.latest_height() == manifest.height && ledger.latest_hash() == manifest.hash,
+                "The restored tip (block {} - {}) does not match the manifest (block {} - {})",
+                ledger.latest_height(),
+                ledger.latest_hash(),
+                manifest.height,
+                manifest.hash
+            );
+            // Ensure the restored state matches the state digest of the backup, if it has one.
+            if let Some(digest) = &manifest.digest {
+                let restored = StateDigest::new(&ledger)?;
+                ensure!(
+                    &restored.digest == digest,
+                    "The restored state digest ({}) does not match the manifest ({digest})",
+                    restored.digest
+                );
+            }
+            // Verify the most recent blocks, which form a chain of hashes that ends with the manifest hash.
+            let start_height = manifest.height.saturating_sub(Self::NUM_VERIFIED_BLOCKS - 1);
+            let mut previous_hash = match start_height {
+                0 => ledger.get_block(0)?.previous_hash(),
+                height => ledger.get_hash(height - 1)?,
+            };
+            for height in start_height..=manifest.height {
+                previous_hash = verify_block::<N, A>(&ledger, height, previous_hash, false)
+                    .map_err(|err| anyhow!("Block {height} of the backup is invalid - {err}"))?;
+            }
+            Ok(())
+        });
+        // If the backup is invalid, remove the partially restored ledger.
+        if let Err(error) = result {
+            let _ = fs::remove_dir_all(&ledger_dir);
+            return Err(error);
+        }
+
+        Ok(format!(
+            "✅ Restored the ledger at block {} ({}) {}",
+            manifest.height.to_string().bold(),
+            manifest.hash,
+            format!("(in \"{}\")", ledger_dir.display()).dimmed()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::MainnetV0;
+
+    use rand::Rng;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_restore_requires_archive() {
+        let root = std::env::temp_dir().join(format!("snarkos-restore-{}", rand::thread_rng().gen::<u64>()));
+        fs::create_dir_all(&root).unwrap();
+        let archive = root.join("backup.zst");
+        fs::write(&archive, zstd::encode_all(&b"not a backup"[..], 3).unwrap()).unwrap();
+        let restore = Restore {
+            archive: archive.to_str().unwrap().to_string(),
+            trusted_hash: None,
+            network: CurrentNetwork::ID,
+            dev: None,
+            dev_num_validators: None,
+            path: Some(root.join("ledger-restored")),
+            database: Default::default(),
+            encrypt: false,
+        };
+        // Ensure a file that is not a backup archive is rejected, and nothing is restored.
+        assert!(restore.parse().is_err());
+        assert!(!root.join("ledger-restored").exists());
+
+        // Ensure a download is never restored without a trusted block hash.
+        let restore = Restore {
+            archive: "http://127.0.0.1:3030/mainnet/snapshot/latest".to_string(),
+            trusted_hash: None,
+            network: CurrentNetwork::ID,
+            dev: None,
+            dev_num_validators: None,
+            path: Some(root.join("ledger-restored")),
+            database: Default::default(),
+            encrypt: false,
+        };
+        let error = restore.parse().unwrap_err();
+        assert!(error.to_string().contains("--trusted-hash"));
+        assert!(!root.join("ledger-restored").exists());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{load_genesis, replay};
+use snarkos_node::bft::helpers::proposal_cache_path;
+use snarkvm::{
+    console::network::{CanaryV0, MainnetV0, Network, TestnetV0},
+    ledger::store::helpers::rocksdb::ConsensusDB,
+    prelude::Ledger,
+};
+
+use aleo_std::StorageMode;
+use anyhow::{bail, ensure, Result};
+use clap::Parser;
+use colored::Colorize;
+use std::path::PathBuf;
+
+/// Unwinds the blocks above the given height from the local ledger, restoring the corresponding finalize state;
+/// the node must not be running.
+#[derive(Debug, Parser)]
+pub struct Rollback {
+    /// Specify the height of the block to roll back to.
+    #[clap(long = "height")]
+    pub height: u32,
+    /// Specify the network of the ledger to roll back.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Enables development mode, specify the unique ID of the local node to roll back.
+    #[clap(long)]
+    pub dev: Option<u16>,
+    /// If development mode is enabled, specify the number of genesis validators (default: 4)
+    #[clap(long)]
+    pub dev_num_validators: Option<u16>,
+    /// Specify the path to a directory containing the ledger
+    #[clap(long = "path")]
+    pub path: Option<PathBuf>,
+}
+
+impl Rollback {
+    /// Rolls back the ledger.
+    pub fn parse(self) -> Result<String> {
+        match self.network {
+            MainnetV0::ID => self.rollback::<MainnetV0>(),
+            TestnetV0::ID => self.rollback::<TestnetV0>(),
+            CanaryV0::ID => self.rollback::<CanaryV0>(),
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        }
+    }
+
+    /// Replays the blocks up to the given height into a fresh ledger, and swaps it in once it is complete.
+    /// Note: The finalize state cannot be unwound in place, so it is rebuilt by replaying the retained blocks.
+    fn rollback<N: Network>(&self) -> Result<String> {
+        // Parse the storage mode.
+        let storage_mode = match &self.path {
+            Some(path) => StorageMode::Custom(path.clone()),
+            None => StorageMode::from(self.dev),
+        };
+        // Ensure the ledger exists, as loading it would otherwise initialize a new one.
+        let ledger_dir = aleo_std::aleo_ledger_dir(self.network, storage_mode.clone());
+        ensure!(ledger_dir.exists(), "No snarkOS node storage was found in {}", ledger_dir.display());
+
+        // Load the ledger.
+        let genesis = load_genesis::<N>(self.dev, self.dev_num_validators)?;
+        let ledger = Ledger::<N, ConsensusDB<N>>::load(genesis.clone(), storage_mode)?;
+        let latest_height = ledger.latest_height();
+        ensure!(self.height < latest_height, "The ledger is already at block {latest_height}");
+
+        // Replay the retained blocks.
+        replay(ledger, genesis, &ledger_dir, self.height)?;
+
+        // Remove the proposal cache, as it refers to rounds beyond the new tip.
+        let proposal_cache_path = proposal_cache_path(self.network, self.dev);
+        if proposal_cache_path.exists() {
+            if let Err(err) = std::fs::remove_file(&proposal_cache_path) {
+                bail!("Failed to remove the proposal cache file at {}: {err}", proposal_cache_path.display());
+            }
+        }
+
+        Ok(format!(
+            "✅ Rolled back the ledger from block {latest_height} to block {} {}",
+            self.height.to_string().bold(),
+            format!("(in \"{}\")", ledger_dir.display()).dimmed()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::{Command, Ledger, CLI};
+
+    use clap::Parser;
+
+    #[test]
+    fn test_parse_rollback() {
+        let cli = CLI::parse_from(["snarkos", "ledger", "rollback", "--height", "100", "--dev", "0"]);
+        let Command::Ledger(Ledger::Rollback(rollback)) = cli.command else {
+            panic!("Unexpected result of clap parsing!");
+        };
+        assert_eq!(rollback.height, 100);
+        assert_eq!(rollback.dev, Some(0));
+
+        // Ensure the height is required.
+        assert!(CLI::try_parse_from(["snarkos", "ledger", "rollback"]).is_err());
+    }
+}
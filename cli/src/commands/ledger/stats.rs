@@ -0,0 +1,309 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::load_genesis;
+use snarkos_node::{
+    analytics_index_path,
+    rest::{AnalyticsIndex, ChainStatistics},
+};
+use snarkvm::{
+    console::network::{CanaryV0, MainnetV0, Network, TestnetV0},
+    ledger::store::helpers::rocksdb::ConsensusDB,
+    prelude::{Ledger, ToBytes},
+};
+
+use aleo_std::StorageMode;
+use anyhow::{anyhow, bail, ensure, Result};
+use clap::{Parser, ValueEnum};
+use indexmap::IndexMap;
+use rocksdb::{IteratorMode, Options, DB};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// The length of the key prefix of the ledger database, i.e. the network ID followed by the map ID.
+const KEY_PREFIX_LENGTH: usize = 4;
+
+/// The format of the ledger statistics.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum StatsFormat {
+    /// A human-readable summary.
+    Text,
+    /// A JSON object, for monitoring pipelines.
+    Json,
+}
+
+/// The statistics of a ledger.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct LedgerStats {
+    /// The height of the latest block.
+    pub height: u32,
+    /// The number of confirmed transactions, including the rejected ones.
+    pub num_transactions: u64,
+    /// The number of transitions.
+    pub num_transitions: u64,
+    /// The number of accepted program deployments.
+    pub num_deployments: u64,
+    /// The average size of a block, in bytes.
+    pub average_block_size: u64,
+    /// The average interval between blocks, in seconds.
+    pub average_block_interval: f64,
+    /// The size of the database on disk, in bytes.
+    pub database_size: u64,
+    /// The size of the entries of the database, in bytes, by map (i.e. key prefix).
+    pub map_sizes: IndexMap<String, u64>,
+    /// The total size of the blocks, in bytes.
+    #[serde(skip)]
+    total_block_size: u64,
+    /// The timestamp of the first block.
+    #[serde(skip)]
+    first_timestamp: Option<i64>,
+}
+
+impl LedgerStats {
+    /// Accounts for the blocks up to the latest one of the given cumulative statistics.
+    fn add_totals(&mut self, totals: &ChainStatistics) {
+        self.height = totals.height;
+        self.num_transactions = totals.num_transactions;
+        self.num_transitions = totals.num_transitions;
+        self.num_deployments = totals.num_deployments;
+        self.total_block_size = totals.total_size;
+        self.first_timestamp = Some(totals.first_timestamp);
+        self.update_averages(totals.latest_timestamp);
+    }
+
+    /// Accounts for the next block, in ascending height order.
+    fn add_block(
+        &mut self,
+        height: u32,
+        size: usize,
+        timestamp: i64,
+        num_transactions: usize,
+        num_transitions: usize,
+        num_deployments: usize,
+    ) {
+        self.height = height;
+        self.num_transactions += num_transactions as u64;
+        self.num_transitions += num_transitions as u64;
+        self.num_deployments += num_deployments as u64;
+        self.total_block_size += size as u64;
+        self.first_timestamp.get_or_insert(timestamp);
+        self.update_averages(timestamp);
+    }
+
+    /// Updates the averages, given the timestamp of the latest block.
+    fn update_averages(&mut self, latest_timestamp: i64) {
+        let num_blocks = self.height as u64 + 1;
+        self.average_block_size = self.total_block_size / num_blocks;
+        if let (Some(first), true) = (self.first_timestamp, self.height > 0) {
+            self.average_block_interval = (latest_timestamp - first) as f64 / self.height as f64;
+        }
+    }
+}
+
+impl fmt::Display for LedgerStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Height: {}", self.height)?;
+        writeln!(f, "Transactions: {}", self.num_transactions)?;
+        writeln!(f, "Transitions: {}", self.num_transitions)?;
+        writeln!(f, "Deployments: {}", self.num_deployments)?;
+        writeln!(f, "Average block size: {} bytes", self.average_block_size)?;
+        writeln!(f, "Average block interval: {:.2} seconds", self.average_block_interval)?;
+        writeln!(f, "Database size: {} bytes", self.database_size)?;
+        for (map, size) in &self.map_sizes {
+            writeln!(f, "  {map}: {size} bytes")?;
+        }
+        Ok(())
+    }
+}
+
+/// Reports the statistics of the local ledger; the node must not be running.
+#[derive(Debug, Parser)]
+pub struct Stats {
+    /// Specify the network of the ledger.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Enables development mode, specify the unique ID of the local node.
+    #[clap(long)]
+    pub dev: Option<u16>,
+    /// If development mode is enabled, specify the number of genesis validators (default: 4)
+    #[clap(long)]
+    pub dev_num_validators: Option<u16>,
+    /// Specify the path to a directory containing the ledger
+    #[clap(long = "path")]
+    pub path: Option<PathBuf>,
+    /// Specify the format of the statistics
+    #[clap(value_enum, default_value = "text", long = "format")]
+    pub format: StatsFormat,
+}
+
+impl Stats {
+    /// Reports the statistics of the ledger.
+    pub fn parse(self) -> Result<String> {
+        match self.network {
+            MainnetV0::ID => self.stats::<MainnetV0>(),
+            TestnetV0::ID => self.stats::<TestnetV0>(),
+            CanaryV0::ID => self.stats::<CanaryV0>(),
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        }
+    }
+
+    /// Walks the ledger, and gathers its statistics; the blocks covered by the analytics index are not walked.
+    fn stats<N: Network>(&self) -> Result<String> {
+        // Parse the storage mode.
+        let storage_mode = match &self.path {
+            Some(path) => StorageMode::Custom(path.clone()),
+            None => StorageMode::from(self.dev),
+        };
+        // Ensure the ledger exists, as loading it would otherwise initialize a new one.
+        let ledger_dir = aleo_std::aleo_ledger_dir(self.network, storage_mode.clone());
+        ensure!(ledger_dir.exists(), "No snarkOS node storage was found in {}", ledger_dir.display());
+
+        // Measure the database, before the ledger takes the lock on it.
+        let (database_size, map_sizes) = database_size(&ledger_dir)?;
+        let mut stats = LedgerStats { database_size, map_sizes, ..Default::default() };
+
+        // Load the ledger.
+        let genesis = load_genesis::<N>(self.dev, self.dev_num_validators)?;
+        let ledger = Ledger::<N, ConsensusDB<N>>::load(genesis, storage_mode.clone())?;
+        // Start from the totals of the analytics index, if the node keeps one and it matches the ledger.
+        let mut start_height = 0;
+        let index_path = analytics_index_path(self.network, &storage_mode);
+        if index_path.exists() {
+            if let Some(totals) = AnalyticsIndex::open_read_only(&index_path)?.canonical_totals(&ledger)? {
+                stats.add_totals(&totals);
+                start_height = totals.height + 1;
+            }
+        }
+        // Walk the remaining blocks.
+        for height in start_height..=ledger.latest_height() {
+            let block = ledger.get_block(height)?;
+            let transactions = block.transactions();
+            stats.add_block(
+                height,
+                block.to_bytes_le()?.len(),
+                block.timestamp(),
+                transactions.len(),
+                transactions.iter().map(|tx| tx.transaction().transitions().count()).sum(),
+                transactions.iter().filter(|tx| tx.is_accepted() && tx.transaction().is_deploy()).count(),
+            );
+        }
+
+        match self.format {
+            StatsFormat::Text => Ok(stats.to_string()),
+            StatsFormat::Json => Ok(serde_json::to_string_pretty(&stats)?),
+        }
+    }
+}
+
+/// Returns the size of the SST files of the database in the given directory,
+/// and the size of its entries by map, as the ledger keeps all of its maps in a single column family.
+fn database_size(ledger_dir: &Path) -> Result<(u64, IndexMap<String, u64>)> {
+    let options = Options::default();
+    let column_families = DB::list_cf(&options, ledger_dir)?;
+    let database = DB::open_cf_for_read_only(&options, ledger_dir, &column_families, false)
+        .map_err(|err| anyhow!("Couldn't open the ledger at {} - {err}", ledger_dir.display()))?;
+    let size = column_family_sizes(&database, &column_families)?.values().sum();
+
+    // Sum up the sizes of the keys and values, by the map ID in their key prefix.
+    let mut map_sizes = BTreeMap::<Option<u16>, u64>::new();
+    for entry in database.iterator(IteratorMode::Start) {
+        let (key, value) = entry?;
+        *map_sizes.entry(map_id(&key)).or_default() += (key.len() + value.len()) as u64;
+    }
+    let map_sizes = map_sizes.into_iter().map(|(map_id, size)| match map_id {
+        Some(map_id) => (format!("map {map_id}"), size),
+        None => ("unprefixed".to_string(), size),
+    });
+    Ok((size, map_sizes.collect()))
+}
+
+/// Returns the ID of the map of the given key, which follows the network ID in its prefix.
+fn map_id(key: &[u8]) -> Option<u16> {
+    key.get(2..KEY_PREFIX_LENGTH).map(|map_id| u16::from_le_bytes([map_id[0], map_id[1]]))
+}
+
+/// Returns the size of the SST files of the given column families of the given database.
+pub(super) fn column_family_sizes(database: &DB, column_families: &[String]) -> Result<IndexMap<String, u64>> {
+    let mut sizes = IndexMap::new();
+    for name in column_families {
+        let column_family = database.cf_handle(name).ok_or_else(|| anyhow!("Missing column family '{name}'"))?;
+        let size = database.property_int_value_cf(column_family, "rocksdb.total-sst-files-size")?;
+        sizes.insert(name.clone(), size.unwrap_or_default());
+    }
+    Ok(sizes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ledger_stats() {
+        let mut stats = LedgerStats::default();
+        stats.add_block(0, 100, 1_000, 0, 0, 0);
+        assert_eq!(stats.average_block_size, 100);
+        assert_eq!(stats.average_block_interval, 0.0);
+
+        stats.add_block(1, 300, 1_010, 2, 5, 1);
+        stats.add_block(2, 200, 1_030, 1, 1, 0);
+        assert_eq!(stats.num_transactions, 3);
+        assert_eq!(stats.num_transitions, 6);
+        assert_eq!(stats.num_deployments, 1);
+        assert_eq!(stats.average_block_size, 200);
+        assert_eq!(stats.average_block_interval, 15.0);
+
+        // Ensure starting from the totals of the analytics index yields the same statistics.
+        let totals = ChainStatistics {
+            height: 1,
+            first_timestamp: 1_000,
+            latest_timestamp: 1_010,
+            total_size: 400,
+            num_transactions: 2,
+            num_transitions: 5,
+            num_deployments: 1,
+            total_fees: 0,
+        };
+        let mut indexed = LedgerStats::default();
+        indexed.add_totals(&totals);
+        indexed.add_block(2, 200, 1_030, 1, 1, 0);
+        assert_eq!(indexed, stats);
+    }
+
+    #[test]
+    fn test_map_sizes() {
+        let path = std::env::temp_dir().join(format!("snarkos-stats-{}", rand::random::<u64>()));
+        {
+            let mut options = Options::default();
+            options.create_if_missing(true);
+            let database = DB::open(&options, &path).unwrap();
+            // Write the entries of two maps of network 1, and an unprefixed one.
+            database.put([1, 0, 7, 0, 1], [0u8; 10]).unwrap();
+            database.put([1, 0, 7, 0, 2], [0u8; 20]).unwrap();
+            database.put([1, 0, 0, 1, 1], [0u8; 5]).unwrap();
+            database.put([9], [0u8; 3]).unwrap();
+            database.flush().unwrap();
+        }
+
+        let (_, map_sizes) = database_size(&path).unwrap();
+        assert_eq!(map_sizes.get("map 7"), Some(&40));
+        assert_eq!(map_sizes.get("map 256"), Some(&10));
+        assert_eq!(map_sizes.get("unprefixed"), Some(&4));
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}
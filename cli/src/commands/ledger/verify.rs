@@ -0,0 +1,182 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::load_genesis;
+use snarkvm::{
+    circuit::{Aleo, AleoCanaryV0, AleoTestnetV0, AleoV0},
+    console::network::{CanaryV0, MainnetV0, Network, TestnetV0},
+    ledger::store::helpers::rocksdb::ConsensusDB,
+    prelude::{block::Transaction, Block, FromBytes, Ledger, ToBits, ToBytes},
+    synthesizer::process::Stack,
+};
+
+use aleo_std::StorageMode;
+use anyhow::{anyhow, bail, ensure, Result};
+use clap::Parser;
+use colored::Colorize;
+use std::path::PathBuf;
+
+/// Walks the local ledger, and reports the first corrupt block.
+#[derive(Debug, Parser)]
+pub struct Verify {
+    /// Specify the network of the ledger to verify.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Enables development mode, specify the unique ID of the local node to verify.
+    #[clap(long)]
+    pub dev: Option<u16>,
+    /// If development mode is enabled, specify the number of genesis validators (default: 4)
+    #[clap(long)]
+    pub dev_num_validators: Option<u16>,
+    /// Specify the path to a directory containing the ledger
+    #[clap(long = "path")]
+    pub path: Option<PathBuf>,
+    /// Specify the height to start verifying from
+    #[clap(default_value = "0", long = "start")]
+    pub start: u32,
+    /// If the flag is set, the signatures and proofs of every block are re-verified as well
+    #[clap(long)]
+    pub deep: bool,
+}
+
+impl Verify {
+    /// The number of blocks between progress updates.
+    const PROGRESS_INTERVAL: u32 = 10_000;
+
+    /// Verifies the local ledger.
+    pub fn parse(self) -> Result<String> {
+        match self.network {
+            MainnetV0::ID => self.verify::<MainnetV0, AleoV0>(),
+            TestnetV0::ID => self.verify::<TestnetV0, AleoTestnetV0>(),
+            CanaryV0::ID => self.verify::<CanaryV0, AleoCanaryV0>(),
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        }
+    }
+
+    /// Walks the ledger from the start height up to its tip, stopping at the first corrupt block.
+    fn verify<N: Network, A: Aleo<Network = N>>(&self) -> Result<String> {
+        // Parse the storage mode.
+        let storage_mode = match &self.path {
+            Some(path) => StorageMode::Custom(path.clone()),
+            None => StorageMode::from(self.dev),
+        };
+        // Ensure the ledger exists, as loading it would otherwise initialize a new one.
+        let ledger_dir = aleo_std::aleo_ledger_dir(self.network, storage_mode.clone());
+        ensure!(ledger_dir.exists(), "No snarkOS node storage was found in {}", ledger_dir.display());
+
+        // Load the ledger.
+        let genesis = load_genesis::<N>(self.dev, self.dev_num_validators)?;
+        let ledger = Ledger::<N, ConsensusDB<N>>::load(genesis, storage_mode)?;
+        let latest_height = ledger.latest_height();
+        ensure!(self.start <= latest_height, "The start height exceeds the ledger height ({latest_height})");
+
+        // Walk the ledger, starting from the hash preceding the start height.
+        let mut previous_hash = match self.start {
+            0 => N::BlockHash::default(),
+            height => ledger.get_hash(height - 1)?,
+        };
+        for height in self.start..=latest_height {
+            previous_hash = verify_block::<N, A>(&ledger, height, previous_hash, self.deep)
+                .map_err(|error| anyhow!("The ledger is corrupt at block {height} - {error}"))?;
+            if height > self.start && height % Self::PROGRESS_INTERVAL == 0 {
+                println!("Verified blocks {} to {height}", self.start);
+            }
+        }
+
+        Ok(format!(
+            "✅ Verified blocks {} to {} {}",
+            self.start,
+            latest_height.to_string().bold(),
+            format!("(in \"{}\")", ledger_dir.display()).dimmed()
+        ))
+    }
+}
+
+/// Verifies the block at the given height against the indexes of the ledger and the given previous block hash,
+/// and returns its hash.
+pub(super) fn verify_block<N: Network, A: Aleo<Network = N>>(
+    ledger: &Ledger<N, ConsensusDB<N>>,
+    height: u32,
+    previous_hash: N::BlockHash,
+    deep: bool,
+) -> Result<N::BlockHash> {
+    // Ensure the indexes are complete and consistent.
+    let hash = ledger.get_hash(height)?;
+    ensure!(ledger.get_height(&hash)? == height, "The height index is inconsistent with the hash index");
+    let block = ledger.get_block(height)?;
+    ensure!(block.height() == height, "The block has height {}", block.height());
+    ensure!(block.hash() == hash, "The block has hash {}, but is indexed as {hash}", block.hash());
+
+    // Ensure the block links to the previous block.
+    ensure!(block.previous_hash() == previous_hash, "The block does not link to the previous block");
+    // Ensure the block hash commits to the header.
+    let header_root = block.header().to_root()?;
+    let expected_hash = N::hash_bhp1024(&[previous_hash.to_bits_le(), header_root.to_bits_le()].concat())?;
+    ensure!(*hash == expected_hash, "The block hash does not commit to the header");
+    // Ensure the header is consistent with the block contents and the state.
+    ensure!(block.header().is_valid(), "The header is malformed");
+    ensure!(
+        block.header().transactions_root() == block.transactions().to_transactions_root()?,
+        "The transactions root does not match the transactions"
+    );
+    ensure!(
+        ledger.contains_state_root(&block.header().previous_state_root())?,
+        "The previous state root is missing from the state"
+    );
+
+    if deep {
+        // Re-verify the authority and certificate signatures, which are checked when a block is deserialized.
+        let block = Block::<N>::from_bytes_le(&block.to_bytes_le()?)?;
+        // Re-verify the proofs of the accepted transactions.
+        let process = ledger.vm().process();
+        let process = process.read();
+        for transaction in block.transactions().iter().filter(|tx| tx.is_accepted()).map(|tx| tx.transaction()) {
+            match transaction {
+                Transaction::Deploy(_, _, deployment, fee) => {
+                    // Note: The process already contains the deployed program, so a new stack is verified instead.
+                    Stack::new(&process, deployment.program())?
+                        .verify_deployment::<A, _>(deployment, &mut rand::thread_rng())?;
+                    process.verify_fee(fee, deployment.to_deployment_id()?)?;
+                }
+                Transaction::Execute(_, execution, fee) => {
+                    process.verify_execution(execution)?;
+                    if let Some(fee) = fee {
+                        process.verify_fee(fee, execution.to_execution_id()?)?;
+                    }
+                }
+                Transaction::Fee(..) => bail!("Transaction '{}' is an accepted fee transaction", transaction.id()),
+            }
+        }
+    }
+    Ok(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::{Command, Ledger, CLI};
+
+    use clap::Parser;
+
+    #[test]
+    fn test_parse_verify() {
+        let cli = CLI::parse_from(["snarkos", "ledger", "verify", "--dev", "1", "--start", "100", "--deep"]);
+        let Command::Ledger(Ledger::Verify(verify)) = cli.command else {
+            panic!("Unexpected result of clap parsing!");
+        };
+        assert_eq!(verify.network, 0);
+        assert_eq!(verify.dev, Some(1));
+        assert_eq!(verify.start, 100);
+        assert!(verify.deep);
+    }
+}
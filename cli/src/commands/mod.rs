@@ -15,12 +15,27 @@
 mod account;
 pub use account::*;
 
+mod ceremony;
+pub use ceremony::*;
+
 mod clean;
 pub use clean::*;
 
+mod crawl;
+pub use crawl::*;
+
 mod developer;
 pub use developer::*;
 
+mod ledger;
+pub use ledger::*;
+
+mod node;
+pub use node::*;
+
+mod prover;
+pub use prover::*;
+
 mod start;
 pub use start::*;
 
@@ -53,10 +68,20 @@ pub struct CLI {
 pub enum Command {
     #[clap(subcommand)]
     Account(Account),
+    #[clap(subcommand)]
+    Ceremony(Ceremony),
     #[clap(name = "clean")]
     Clean(Clean),
+    #[clap(name = "crawl")]
+    Crawl(Crawl),
     #[clap(subcommand)]
     Developer(Developer),
+    #[clap(subcommand)]
+    Ledger(Ledger),
+    #[clap(subcommand)]
+    Node(Node),
+    #[clap(subcommand)]
+    Prover(Prover),
     #[clap(name = "start")]
     Start(Box<Start>),
     #[clap(name = "update")]
@@ -68,8 +93,13 @@ impl Command {
     pub fn parse(self) -> Result<String> {
         match self {
             Self::Account(command) => command.parse(),
+            Self::Ceremony(command) => command.parse(),
             Self::Clean(command) => command.parse(),
+            Self::Crawl(command) => command.parse(),
             Self::Developer(command) => command.parse(),
+            Self::Ledger(command) => command.parse(),
+            Self::Node(command) => command.parse(),
+            Self::Prover(command) => command.parse(),
             Self::Start(command) => command.parse(),
             Self::Update(command) => command.parse(),
         }
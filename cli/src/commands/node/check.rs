@@ -0,0 +1,329 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::get_json;
+use snarkos_node::bft::MAX_TIMESTAMP_DELTA_IN_SECS;
+use snarkvm::{
+    console::network::{CanaryV0, MainnetV0, Network, TestnetV0},
+    ledger::{authority::Authority, block::Block, committee::Committee},
+    prelude::Address,
+};
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use serde::de::DeserializeOwned;
+use std::{
+    fmt,
+    net::{SocketAddr, TcpStream},
+    str::FromStr,
+    time::Duration,
+};
+use time::OffsetDateTime;
+
+/// The time to wait for a port to accept a connection.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+/// The age of the latest block, in seconds, beyond which the node is likely not synced.
+const STALE_BLOCK_IN_SECS: i64 = 300;
+
+/// Diagnoses the configuration of a running node.
+///
+/// The ports are probed from this machine: to verify they are reachable by the other validators,
+/// run the command from another machine with the public addresses of the node.
+#[derive(Debug, Parser)]
+pub struct Check {
+    /// Run the diagnostics of a validator: the BFT port, the committee membership, and the signing participation
+    #[clap(long = "validator")]
+    pub validator: bool,
+    /// Specify the network of the node
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Specify the REST endpoint of the node
+    #[clap(default_value = "http://127.0.0.1:3030", long = "endpoint")]
+    pub endpoint: String,
+    /// Specify the JWT token that the node printed on startup, to fetch the address of the node
+    #[clap(long = "jwt")]
+    pub jwt: Option<String>,
+    /// Specify the IP address and port of the node server
+    #[clap(default_value = "127.0.0.1:4130", long = "node")]
+    pub node: SocketAddr,
+    /// Specify the IP address and port of the BFT
+    #[clap(default_value = "127.0.0.1:5000", long = "bft")]
+    pub bft: SocketAddr,
+    /// Specify the expected address of the validator, defaults to the address of the node
+    #[clap(long = "address")]
+    pub address: Option<String>,
+    /// Specify the expected stake of the validator, in microcredits
+    #[clap(long = "expected-stake", value_name = "MICROCREDITS")]
+    pub expected_stake: Option<u64>,
+    /// Specify the number of recent blocks in which to check the signing participation of the validator
+    #[clap(default_value = "10", long = "blocks", value_parser = clap::value_parser!(u32).range(1..=50))]
+    pub blocks: u32,
+}
+
+impl Check {
+    /// Runs the diagnostics, and returns the report, as an error if any of the diagnostics failed.
+    pub fn parse(self) -> Result<String> {
+        let report = match self.network {
+            MainnetV0::ID => self.diagnose::<MainnetV0>(),
+            TestnetV0::ID => self.diagnose::<TestnetV0>(),
+            CanaryV0::ID => self.diagnose::<CanaryV0>(),
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        };
+        match report.num_failures() {
+            0 => Ok(report.to_string()),
+            num_failures => bail!("{report}\n\n{num_failures} diagnostic(s) failed"),
+        }
+    }
+
+    /// Runs the diagnostics on the given network.
+    fn diagnose<N: Network>(&self) -> Report {
+        let mut report = Report::default();
+
+        // Ensure the REST server is reachable, as the remaining diagnostics depend on it.
+        let latest_block = match self.get_json::<Block<N>>(None, "block/latest") {
+            Ok(block) => {
+                report.pass(format!("The REST server at {} is reachable (block {})", self.endpoint, block.height()));
+                Some(block)
+            }
+            Err(error) => {
+                report.fail(format!("The REST server at {} is unreachable - {error}", self.endpoint));
+                None
+            }
+        };
+        // Ensure the node server accepts connections.
+        report.probe("node server", "--node", self.node);
+        // Ensure the clock of this machine agrees with the latest block.
+        if let Some(block) = &latest_block {
+            let (outcome, message) = assess_clock(block.timestamp(), OffsetDateTime::now_utc().unix_timestamp());
+            report.push(outcome, message);
+        }
+
+        // Run the diagnostics of a validator, if requested.
+        if self.validator {
+            report.probe("BFT", "--bft", self.bft);
+            self.diagnose_validator(&mut report, latest_block.as_ref());
+        }
+        report
+    }
+
+    /// Runs the diagnostics of the committee membership and signing participation of the validator.
+    fn diagnose_validator<N: Network>(&self, report: &mut Report, latest_block: Option<&Block<N>>) {
+        // Determine the address of the validator, from the expected one and the one of the node.
+        let expected_address = self.address.as_deref().map(Address::<N>::from_str);
+        let node_address = self.jwt.as_deref().map(|jwt| self.get_json::<Address<N>>(Some(jwt), "node/address"));
+        let address = match (expected_address, node_address) {
+            (Some(Err(error)), _) => return report.fail(format!("The given address is invalid - {error}")),
+            (_, Some(Err(error))) => return report.fail(format!("Failed to fetch the address of the node - {error}")),
+            (Some(Ok(expected)), Some(Ok(actual))) if expected != actual => {
+                report.warn(format!(
+                    "The node runs as {actual} instead of {expected} - check the private key of the node (expected with a remote signer)"
+                ));
+                expected
+            }
+            (Some(Ok(address)), _) | (None, Some(Ok(address))) => address,
+            (None, None) => {
+                return report.fail("The address of the validator is unknown, please specify '--address' or '--jwt'");
+            }
+        };
+
+        // Ensure the validator is a member of the current committee, with the expected stake.
+        let committee = match self.get_json::<Committee<N>>(None, "committee/latest") {
+            Ok(committee) => committee,
+            Err(error) => return report.fail(format!("Failed to fetch the current committee - {error}")),
+        };
+        let Some((stake, ..)) = committee.members().get(&address) else {
+            return report.fail(format!(
+                "{address} is not a member of the current committee - check the private key of the node, and its bond"
+            ));
+        };
+        report.pass(format!("{address} is a member of the current committee, with {stake} microcredits staked"));
+        if let Some(expected_stake) = self.expected_stake {
+            if *stake != expected_stake {
+                report.warn(format!("The stake of {address} is {stake} microcredits, instead of {expected_stake}"));
+            }
+        }
+
+        // Ensure the validator authored certificates in the recent blocks.
+        let Some(latest_block) = latest_block else {
+            return;
+        };
+        let end = latest_block.height() + 1;
+        let start = end.saturating_sub(self.blocks);
+        match self.get_json::<Vec<Block<N>>>(None, &format!("blocks?start={start}&end={end}")) {
+            Ok(blocks) => {
+                let (outcome, message) = assess_participation(&blocks, address);
+                report.push(outcome, message);
+            }
+            Err(error) => report.fail(format!("Failed to fetch the recent blocks - {error}")),
+        }
+    }
+
+    /// Fetches the JSON response of the given route from the node.
+    fn get_json<T: DeserializeOwned>(&self, jwt: Option<&str>, route: &str) -> Result<T> {
+        get_json(self.network, &self.endpoint, jwt, route)
+    }
+}
+
+/// Assesses the clock of this machine, given the timestamp of the latest block.
+fn assess_clock(block_timestamp: i64, now: i64) -> (Outcome, String) {
+    let age = now.saturating_sub(block_timestamp);
+    if age < -MAX_TIMESTAMP_DELTA_IN_SECS {
+        (
+            Outcome::Fail,
+            format!(
+                "The clock of this machine is {}s behind the latest block, so the node rejects the batches of its peers - check its time synchronization",
+                -age
+            ),
+        )
+    } else if age > STALE_BLOCK_IN_SECS {
+        (
+            Outcome::Warn,
+            format!("The latest block is {age}s old - the node is not synced, or the clock of this machine is ahead"),
+        )
+    } else {
+        (Outcome::Pass, format!("The clock of this machine agrees with the latest block ({age}s old)"))
+    }
+}
+
+/// Assesses the signing participation of the given validator in the given blocks.
+fn assess_participation<N: Network>(blocks: &[Block<N>], address: Address<N>) -> (Outcome, String) {
+    // Count the blocks of the BFT, and the ones with a certificate authored by the validator.
+    let (num_blocks, num_signed) = blocks
+        .iter()
+        .filter_map(|block| match block.authority() {
+            Authority::Quorum(subdag) => Some(subdag),
+            Authority::Beacon(_) => None,
+        })
+        .fold((0, 0), |(num_blocks, num_signed), subdag| {
+            let is_signed = subdag.values().flatten().any(|certificate| certificate.author() == address);
+            (num_blocks + 1, num_signed + usize::from(is_signed))
+        });
+    match (num_blocks, num_signed) {
+        (0, _) => (Outcome::Warn, "The recent blocks were not produced by the BFT, so participation is unknown".into()),
+        (num_blocks, 0) => (
+            Outcome::Fail,
+            format!("{address} authored no certificate in the last {num_blocks} blocks - check the BFT port and peers"),
+        ),
+        (num_blocks, num_signed) if num_signed * 2 < num_blocks => (
+            Outcome::Warn,
+            format!("{address} authored certificates in only {num_signed} of the last {num_blocks} blocks"),
+        ),
+        (num_blocks, num_signed) => {
+            (Outcome::Pass, format!("{address} authored certificates in {num_signed} of the last {num_blocks} blocks"))
+        }
+    }
+}
+
+/// The outcome of a diagnostic.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Outcome {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// The outcomes of the diagnostics, in the order they ran.
+#[derive(Debug, Default)]
+struct Report {
+    findings: Vec<(Outcome, String)>,
+}
+
+impl Report {
+    /// Records the outcome of a diagnostic.
+    fn push(&mut self, outcome: Outcome, message: impl Into<String>) {
+        self.findings.push((outcome, message.into()));
+    }
+
+    /// Records a passed diagnostic.
+    fn pass(&mut self, message: impl Into<String>) {
+        self.push(Outcome::Pass, message)
+    }
+
+    /// Records a likely misconfiguration.
+    fn warn(&mut self, message: impl Into<String>) {
+        self.push(Outcome::Warn, message)
+    }
+
+    /// Records a failed diagnostic.
+    fn fail(&mut self, message: impl Into<String>) {
+        self.push(Outcome::Fail, message)
+    }
+
+    /// Records whether the given port accepts connections.
+    fn probe(&mut self, name: &str, flag: &str, addr: SocketAddr) {
+        match TcpStream::connect_timeout(&addr, PROBE_TIMEOUT) {
+            Ok(_) => self.pass(format!("The {name} port at {addr} is reachable")),
+            Err(error) => self.fail(format!(
+                "The {name} port at {addr} is unreachable ({error}) - check the '{flag}' flag of the node and the firewall"
+            )),
+        }
+    }
+
+    /// Returns the number of failed diagnostics.
+    fn num_failures(&self) -> usize {
+        self.findings.iter().filter(|(outcome, _)| *outcome == Outcome::Fail).count()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let lines = self.findings.iter().map(|(outcome, message)| match outcome {
+            Outcome::Pass => format!("✅ {message}"),
+            Outcome::Warn => format!("⚠️  {message}"),
+            Outcome::Fail => format!("❌ {message}"),
+        });
+        write!(f, "{}", lines.collect::<Vec<_>>().join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Command, Node, CLI};
+
+    #[test]
+    fn test_parse_check() {
+        let cli = CLI::parse_from(["snarkos", "node", "check", "--validator", "--bft", "10.0.0.1:5000"]);
+        let Command::Node(Node::Check(check)) = cli.command else {
+            panic!("Unexpected result of clap parsing!");
+        };
+        assert!(check.validator);
+        assert_eq!(check.bft, SocketAddr::from_str("10.0.0.1:5000").unwrap());
+        assert_eq!(check.node, SocketAddr::from_str("127.0.0.1:4130").unwrap());
+        assert_eq!(check.blocks, 10);
+
+        // Ensure the number of blocks is bounded by the REST server.
+        assert!(CLI::try_parse_from(["snarkos", "node", "check", "--blocks", "0"]).is_err());
+        assert!(CLI::try_parse_from(["snarkos", "node", "check", "--blocks", "51"]).is_err());
+    }
+
+    #[test]
+    fn test_assess_clock() {
+        let now = 1_700_000_000;
+        assert_eq!(assess_clock(now - 5, now).0, Outcome::Pass);
+        assert_eq!(assess_clock(now + MAX_TIMESTAMP_DELTA_IN_SECS, now).0, Outcome::Pass);
+        assert_eq!(assess_clock(now + MAX_TIMESTAMP_DELTA_IN_SECS + 1, now).0, Outcome::Fail);
+        assert_eq!(assess_clock(now - STALE_BLOCK_IN_SECS - 1, now).0, Outcome::Warn);
+    }
+
+    #[test]
+    fn test_report() {
+        let mut report = Report::default();
+        report.pass("first");
+        report.warn("second");
+        report.fail("third");
+        assert_eq!(report.num_failures(), 1);
+        assert_eq!(report.to_string(), "✅ first\n⚠️  second\n❌ third");
+    }
+}
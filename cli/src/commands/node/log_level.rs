@@ -0,0 +1,94 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{get_json, post_json};
+use snarkos_node_rest::{LogFilter, LogFilterRequest};
+
+use anyhow::{bail, Result};
+use clap::Parser;
+
+/// Changes the log filter of a running node, through its JWT-protected REST endpoint.
+#[derive(Debug, Parser)]
+pub struct LogLevel {
+    /// The filter directives to apply, i.e. `snarkos_node_bft=debug` (prints the log filter, if none is given)
+    pub directives: Vec<String>,
+    /// If the flag is set, the directives that were applied at runtime are discarded first
+    #[clap(long)]
+    pub reset: bool,
+    /// Specify the network of the node
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Specify the REST endpoint of the node
+    #[clap(default_value = "http://127.0.0.1:3030", long = "endpoint")]
+    pub endpoint: String,
+    /// Specify the JWT token that the node printed on startup
+    #[clap(long = "jwt")]
+    pub jwt: Option<String>,
+}
+
+impl LogLevel {
+    /// Prints or changes the log filter of the node.
+    pub fn parse(self) -> Result<String> {
+        let Some(jwt) = &self.jwt else {
+            bail!("The log level endpoint requires the JWT token of the node, please specify '--jwt'");
+        };
+        let log_filter: LogFilter = match self.directives.is_empty() && !self.reset {
+            true => get_json(self.network, &self.endpoint, Some(jwt), "node/logLevel")?,
+            false => {
+                let request = LogFilterRequest { directives: self.directives, reset: self.reset };
+                post_json(self.network, &self.endpoint, Some(jwt), "node/logLevel", &request)?
+            }
+        };
+        Ok(format_log_filter(&log_filter))
+    }
+}
+
+/// Formats the log filter of the node.
+fn format_log_filter(log_filter: &LogFilter) -> String {
+    let directives = match log_filter.directives.is_empty() {
+        true => "-".to_string(),
+        false => log_filter.directives.join(","),
+    };
+    format!("Log filter: {}\nRuntime directives: {directives}", log_filter.filter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Command, Node, CLI};
+
+    #[test]
+    fn test_parse_log_level() {
+        let cli = CLI::parse_from(["snarkos", "node", "log-level", "snarkos_node_bft=debug", "--jwt", "token"]);
+        let Command::Node(Node::LogLevel(log_level)) = cli.command else {
+            panic!("Unexpected result of clap parsing!");
+        };
+        assert_eq!(log_level.directives, vec!["snarkos_node_bft=debug"]);
+        assert!(!log_level.reset);
+        assert_eq!(log_level.endpoint, "http://127.0.0.1:3030");
+
+        // Ensure the JWT is required.
+        let cli = CLI::parse_from(["snarkos", "node", "log-level", "--reset"]);
+        let Command::Node(Node::LogLevel(log_level)) = cli.command else {
+            panic!("Unexpected result of clap parsing!");
+        };
+        assert!(log_level.parse().is_err());
+    }
+
+    #[test]
+    fn test_format_log_filter() {
+        let log_filter = LogFilter { filter: "debug,snarkos_node_bft=trace".to_string(), directives: vec![] };
+        assert!(format_log_filter(&log_filter).contains("Runtime directives: -"));
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::get_json;
+use snarkos_node_rest::{MempoolEntry, MempoolStats};
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use serde::de::DeserializeOwned;
+use std::fmt::Write;
+
+/// The commands to inspect the memory pool.
+#[derive(Debug, Parser)]
+pub enum MempoolCommand {
+    /// List the unconfirmed transactions and solutions, with their fees, sizes, and ages.
+    List,
+    /// Print an unconfirmed transaction or solution, by its ID.
+    Get {
+        /// The ID of the transaction or solution.
+        id: String,
+    },
+    /// Print the statistics of the memory pool.
+    Stats,
+}
+
+/// Inspects the memory pool of a running validator.
+#[derive(Debug, Parser)]
+pub struct Mempool {
+    /// Specify the network of the node
+    #[clap(default_value = "0", long = "network", global = true)]
+    pub network: u16,
+    /// Specify the REST endpoint of the node
+    #[clap(default_value = "http://127.0.0.1:3030", long = "endpoint", global = true)]
+    pub endpoint: String,
+    /// Specify the JWT token that the node printed on startup
+    #[clap(long = "jwt", global = true)]
+    pub jwt: Option<String>,
+    /// The command to run.
+    #[clap(subcommand)]
+    pub command: MempoolCommand,
+}
+
+impl Mempool {
+    /// Runs the command against the node.
+    pub fn parse(self) -> Result<String> {
+        match &self.command {
+            MempoolCommand::List => {
+                let entries: Vec<MempoolEntry> = self.get_json("node/mempool")?;
+                Ok(format_entries(&entries))
+            }
+            MempoolCommand::Get { id } => {
+                let transmission: serde_json::Value = self.get_json(&format!("node/mempool/{id}"))?;
+                Ok(serde_json::to_string_pretty(&transmission)?)
+            }
+            MempoolCommand::Stats => {
+                let stats: MempoolStats = self.get_json("node/mempool/stats")?;
+                Ok(format_stats(&stats))
+            }
+        }
+    }
+
+    /// Fetches the JSON response of the given route from the node.
+    fn get_json<T: DeserializeOwned>(&self, route: &str) -> Result<T> {
+        let Some(jwt) = &self.jwt else {
+            bail!("The memory pool endpoints require the JWT token of the node, please specify '--jwt'");
+        };
+        get_json(self.network, &self.endpoint, Some(jwt), route)
+    }
+}
+
+/// Formats an optional value, with `-` if it is absent.
+fn or_dash(value: Option<impl ToString>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_else(|| "-".to_string())
+}
+
+/// Formats the given entries as a table.
+fn format_entries(entries: &[MempoolEntry]) -> String {
+    let mut output =
+        format!("{:<64} {:<12} {:>14} {:>14} {:>10} {:>8}\n", "ID", "KIND", "FEE", "PRIORITY FEE", "SIZE", "AGE");
+    for entry in entries {
+        let _ = writeln!(
+            output,
+            "{:<64} {:<12} {:>14} {:>14} {:>10} {:>8}",
+            entry.id,
+            entry.kind,
+            or_dash(entry.fee),
+            or_dash(entry.priority_fee),
+            entry.size_in_bytes,
+            or_dash(entry.age_in_secs.map(|age| format!("{age}s"))),
+        );
+    }
+    let _ = write!(output, "{} unconfirmed transmission(s)", entries.len());
+    output
+}
+
+/// Formats the given statistics.
+fn format_stats(stats: &MempoolStats) -> String {
+    format!(
+        "Solutions: {}\nTransactions: {}\nTotal size: {} bytes\nTotal fees: {} microcredits\nOldest: {}",
+        stats.num_solutions,
+        stats.num_transactions,
+        stats.total_size_in_bytes,
+        stats.total_fees,
+        or_dash(stats.oldest_age_in_secs.map(|age| format!("{age} seconds"))),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Command, Node, CLI};
+
+    #[test]
+    fn test_parse_mempool() {
+        let cli = CLI::parse_from(["snarkos", "node", "mempool", "get", "at1abc", "--jwt", "token"]);
+        let Command::Node(Node::Mempool(mempool)) = cli.command else {
+            panic!("Unexpected result of clap parsing!");
+        };
+        assert_eq!(mempool.network, 0);
+        assert_eq!(mempool.endpoint, "http://127.0.0.1:3030");
+        assert_eq!(mempool.jwt, Some("token".to_string()));
+        assert!(matches!(mempool.command, MempoolCommand::Get { id } if id == "at1abc"));
+    }
+
+    #[test]
+    fn test_format_stats() {
+        let stats = MempoolStats {
+            num_solutions: 1,
+            num_transactions: 2,
+            total_size_in_bytes: 3600,
+            total_fees: 1500,
+            oldest_age_in_secs: None,
+        };
+        let output = format_stats(&stats);
+        assert!(output.contains("Transactions: 2"));
+        assert!(output.contains("Oldest: -"));
+    }
+}
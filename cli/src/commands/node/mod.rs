@@ -0,0 +1,103 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod check;
+pub use check::*;
+
+mod log_level;
+pub use log_level::*;
+
+mod mempool;
+pub use mempool::*;
+
+mod promote;
+pub use promote::*;
+
+use snarkvm::console::network::{CanaryV0, MainnetV0, Network, TestnetV0};
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Commands to inspect and operate a running node
+#[derive(Debug, Parser)]
+pub enum Node {
+    /// Diagnose the configuration of a running node, and report the likely misconfigurations.
+    Check(Check),
+    /// Change the log filter of a running node, i.e. to enable the debug logs of one module during an incident.
+    LogLevel(LogLevel),
+    /// Inspect the memory pool of a running validator, through its JWT-protected REST endpoints.
+    Mempool(Mempool),
+    /// Promote a standby instance of a validator, by transferring the signing lease to it.
+    Promote(Promote),
+}
+
+impl Node {
+    pub fn parse(self) -> Result<String> {
+        match self {
+            Self::Check(check) => check.parse(),
+            Self::LogLevel(log_level) => log_level.parse(),
+            Self::Mempool(mempool) => mempool.parse(),
+            Self::Promote(promote) => promote.parse(),
+        }
+    }
+}
+
+/// Fetches the JSON response of the given route from the REST server of a node, authenticated with the given JWT.
+pub(crate) fn get_json<T: DeserializeOwned>(network: u16, endpoint: &str, jwt: Option<&str>, route: &str) -> Result<T> {
+    // Send the request to the node.
+    into_json(request("GET", network, endpoint, jwt, route)?.call())
+}
+
+/// Posts the given JSON body to the given route of the REST server of a node, authenticated with the given JWT,
+/// and returns the JSON response.
+pub(crate) fn post_json<B: Serialize, T: DeserializeOwned>(
+    network: u16,
+    endpoint: &str,
+    jwt: Option<&str>,
+    route: &str,
+    body: &B,
+) -> Result<T> {
+    // Send the request to the node.
+    into_json(request("POST", network, endpoint, jwt, route)?.send_json(body))
+}
+
+/// Prepares a request to the given route of the REST server of a node, with the JWT if one is given.
+fn request(method: &str, network: u16, endpoint: &str, jwt: Option<&str>, route: &str) -> Result<ureq::Request> {
+    // Get the network being used.
+    let network = match network {
+        MainnetV0::ID => "mainnet",
+        TestnetV0::ID => "testnet",
+        CanaryV0::ID => "canary",
+        unknown_id => bail!("Unknown network ID ({unknown_id})"),
+    };
+    // Prepare the request, with the JWT if one is given.
+    let endpoint = endpoint.trim_end_matches('/');
+    let mut request = ureq::request(method, &format!("{endpoint}/{network}/{route}"));
+    if let Some(jwt) = jwt {
+        request = request.set("Authorization", &format!("Bearer {jwt}"));
+    }
+    Ok(request)
+}
+
+/// Returns the JSON response of the given request.
+fn into_json<T: DeserializeOwned>(response: Result<ureq::Response, ureq::Error>) -> Result<T> {
+    match response {
+        Ok(response) => response.into_json().map_err(|err| err.into()),
+        Err(ureq::Error::Status(_status, response)) => {
+            bail!(response.into_string().unwrap_or("Response too large!".to_owned()))
+        }
+        Err(err) => bail!(err),
+    }
+}
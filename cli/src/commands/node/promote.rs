@@ -0,0 +1,58 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node::bft::helpers::{read_lease, signing_journal_path, signing_lease_path, transfer_lease, Failover};
+use snarkvm::console::network::MainnetV0;
+
+use anyhow::{bail, ensure, Result};
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Promotes a standby instance of a validator, by transferring the signing lease to it, which fences the active instance.
+#[derive(Debug, Parser)]
+pub struct Promote {
+    /// Specify the name of the standby instance to promote
+    #[clap(long = "instance", value_name = "NAME")]
+    pub instance: String,
+    /// Specify the network of the validator.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Enables development mode, specify the unique ID of the local node.
+    #[clap(long)]
+    pub dev: Option<u16>,
+    /// Specify the path to the signing journal shared by the instances, next to which the signing lease is kept
+    #[clap(long = "signing-journal", value_name = "PATH")]
+    pub signing_journal: Option<PathBuf>,
+}
+
+impl Promote {
+    /// Transfers the signing lease to the standby instance.
+    pub fn parse(self) -> Result<String> {
+        let signing_journal = self.signing_journal.unwrap_or_else(|| signing_journal_path(self.network, self.dev));
+        let lease_path = signing_lease_path(&signing_journal);
+        // Ensure the lease exists, as it is created by the active instance.
+        let Some(previous) = read_lease(&lease_path)? else {
+            bail!("No signing lease was found at {}", lease_path.display());
+        };
+        ensure!(previous.holder != self.instance, "The instance '{}' already holds the signing lease", self.instance);
+
+        let lease = transfer_lease(&lease_path, &self.instance)?;
+        // Note: The grace period does not depend on the network.
+        let grace_period = Failover::<MainnetV0>::GRACE_PERIOD_IN_SECS;
+        Ok(format!(
+            "Transferred the signing lease from '{}' to '{}' (epoch {}).\nThe instance '{}' stops signing right away, and should be stopped; the instance '{}' starts signing in about {grace_period} seconds.",
+            previous.holder, lease.holder, lease.epoch, previous.holder, lease.holder
+        ))
+    }
+}
@@ -0,0 +1,280 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::commands::node::get_json;
+use snarkvm::{
+    console::network::{CanaryV0, MainnetV0, Network, TestnetV0},
+    ledger::{
+        block::{Block, Ratify},
+        store::helpers::memory::ConsensusMemory,
+    },
+    prelude::{Address, PrivateKey},
+    synthesizer::VM,
+};
+
+use anyhow::{bail, ensure, Result};
+use clap::{Parser, ValueEnum};
+use rand::Rng;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// The number of seconds in a day.
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// The format of the benchmark report.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum BenchmarkFormat {
+    /// A human-readable summary.
+    Text,
+    /// A JSON object, for sizing scripts.
+    Json,
+}
+
+/// The measurements of the puzzle with a given number of threads.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BenchmarkRun {
+    /// The number of threads.
+    pub threads: usize,
+    /// The number of proofs.
+    pub proofs: u64,
+    /// The number of proofs that met the proof target.
+    pub solutions: u64,
+    /// The duration of the run, in seconds.
+    pub seconds: f64,
+    /// The number of proofs per second.
+    pub proofs_per_sec: f64,
+    /// The estimated number of solutions per second, at the proof target.
+    pub solutions_per_sec: f64,
+    /// The proof rate, relative to the first run.
+    pub speedup: f64,
+}
+
+/// The report of a benchmark of the puzzle.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BenchmarkReport {
+    /// The proof target that the solutions are measured against.
+    pub proof_target: u64,
+    /// The coinbase target of the network.
+    pub coinbase_target: u64,
+    /// The puzzle reward of the latest block, in microcredits, if known.
+    pub puzzle_reward: Option<u64>,
+    /// The measurements of each run.
+    pub runs: Vec<BenchmarkRun>,
+    /// The estimated puzzle rewards per day of the fastest run, in microcredits, if the puzzle reward is known.
+    pub estimated_rewards_per_day: Option<f64>,
+}
+
+impl BenchmarkReport {
+    /// Initializes a new report, for the given difficulty.
+    fn new(proof_target: u64, coinbase_target: u64, puzzle_reward: Option<u64>) -> Self {
+        Self { proof_target, coinbase_target, puzzle_reward, runs: Vec::new(), estimated_rewards_per_day: None }
+    }
+
+    /// Adds the measurements of a run.
+    fn add_run(&mut self, threads: usize, proofs: u64, solutions: u64, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64().max(f64::EPSILON);
+        let proofs_per_sec = proofs as f64 / seconds;
+        // Note: A proof meets the proof target with a probability of about `1 / proof_target`.
+        let solutions_per_sec = proofs_per_sec / self.proof_target.max(1) as f64;
+        let speedup = match self.runs.first() {
+            Some(first) if first.proofs_per_sec > 0.0 => proofs_per_sec / first.proofs_per_sec,
+            _ => 1.0,
+        };
+        self.runs.push(BenchmarkRun {
+            threads,
+            proofs,
+            solutions,
+            seconds,
+            proofs_per_sec,
+            solutions_per_sec,
+            speedup,
+        });
+
+        // Estimate the rewards of the fastest run.
+        // Note: The puzzle reward of a block is split in proportion to the proof targets of its solutions,
+        // which add up to about the coinbase target, so the reward per proof is `puzzle_reward / coinbase_target`.
+        let fastest = self.runs.iter().map(|run| run.proofs_per_sec).fold(0.0, f64::max);
+        self.estimated_rewards_per_day = self
+            .puzzle_reward
+            .map(|puzzle_reward| fastest * SECONDS_PER_DAY * puzzle_reward as f64 / self.coinbase_target.max(1) as f64);
+    }
+}
+
+impl fmt::Display for BenchmarkReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Proof target: {}", self.proof_target)?;
+        writeln!(f, "Coinbase target: {}", self.coinbase_target)?;
+        writeln!(f, "{:>8} {:>12} {:>14} {:>8}", "Threads", "Proofs/sec", "Solutions/day", "Speedup")?;
+        for run in &self.runs {
+            writeln!(
+                f,
+                "{:>8} {:>12.2} {:>14.2} {:>7.2}x",
+                run.threads,
+                run.proofs_per_sec,
+                run.solutions_per_sec * SECONDS_PER_DAY,
+                run.speedup
+            )?;
+        }
+        match self.estimated_rewards_per_day {
+            Some(rewards) => write!(f, "Estimated rewards: {:.6} credits/day", rewards / 1_000_000.0),
+            None => write!(f, "Estimated rewards: unknown (specify '--endpoint' to fetch the current puzzle reward)"),
+        }
+    }
+}
+
+/// Benchmarks the puzzle on the local hardware, for sizing a prover.
+///
+/// The proofs are computed for a random epoch, so the benchmark does not need a synced node;
+/// the current difficulty and puzzle reward are fetched from the REST endpoint, if one is given.
+#[derive(Debug, Parser)]
+pub struct Benchmark {
+    /// Specify the network to benchmark the puzzle of
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Specify the REST endpoint of a node, to measure against the current difficulty (defaults to the genesis difficulty)
+    #[clap(long = "endpoint")]
+    pub endpoint: Option<String>,
+    /// Specify the numbers of threads to benchmark, i.e. `--threads 1,4,8` (defaults to powers of two up to all cores)
+    #[clap(long = "threads", value_delimiter = ',')]
+    pub threads: Vec<usize>,
+    /// Specify the duration of each run, in seconds
+    #[clap(default_value = "30", long = "duration")]
+    pub duration: u64,
+    /// Specify the format of the report
+    #[clap(value_enum, default_value = "text", long = "format")]
+    pub format: BenchmarkFormat,
+}
+
+impl Benchmark {
+    /// Benchmarks the puzzle, and returns the report.
+    pub fn parse(self) -> Result<String> {
+        match self.network {
+            MainnetV0::ID => self.benchmark::<MainnetV0>(),
+            TestnetV0::ID => self.benchmark::<TestnetV0>(),
+            CanaryV0::ID => self.benchmark::<CanaryV0>(),
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        }
+    }
+
+    /// Returns the numbers of threads to benchmark.
+    fn thread_counts(&self, num_cores: usize) -> Result<Vec<usize>> {
+        // Default to the powers of two up to the number of cores, and all cores.
+        if self.threads.is_empty() {
+            let mut counts: Vec<_> = (0..).map(|i| 1 << i).take_while(|count| *count < num_cores).collect();
+            counts.push(num_cores.max(1));
+            return Ok(counts);
+        }
+        ensure!(self.threads.iter().all(|threads| *threads > 0), "The numbers of threads must be at least 1");
+        Ok(self.threads.clone())
+    }
+
+    /// Runs the puzzle with each number of threads, and reports the measurements.
+    fn benchmark<N: Network>(&self) -> Result<String> {
+        ensure!(self.duration > 0, "The duration must be at least 1 second");
+        let thread_counts = self.thread_counts(num_cpus::get())?;
+
+        // Fetch the current difficulty and puzzle reward, if an endpoint is given.
+        let mut report = match &self.endpoint {
+            Some(endpoint) => {
+                let block = get_json::<Block<N>>(self.network, endpoint, None, "block/latest")?;
+                let puzzle_reward = block.ratifications().iter().find_map(|ratify| match ratify {
+                    Ratify::PuzzleReward(reward) => Some(*reward),
+                    _ => None,
+                });
+                BenchmarkReport::new(block.header().proof_target(), block.header().coinbase_target(), puzzle_reward)
+            }
+            None => BenchmarkReport::new(N::GENESIS_PROOF_TARGET, N::GENESIS_COINBASE_TARGET, None),
+        };
+
+        // Prepare the puzzle, for a random address and epoch.
+        let puzzle = VM::<N, ConsensusMemory<N>>::new_puzzle()?;
+        let rng = &mut rand::thread_rng();
+        let address = Address::try_from(PrivateKey::<N>::new(rng)?)?;
+        let epoch_hash: N::BlockHash = rng.gen();
+
+        for threads in thread_counts {
+            println!("Benchmarking the puzzle with {threads} threads for {} seconds...", self.duration);
+            let pool = rayon::ThreadPoolBuilder::new().stack_size(8 * 1024 * 1024).num_threads(threads).build()?;
+            let timer = Instant::now();
+            let deadline = timer + Duration::from_secs(self.duration);
+            // Run one puzzle instance per thread, as the prover does.
+            let (proofs, solutions) = pool.install(|| {
+                (0..threads)
+                    .into_par_iter()
+                    .map(|_| {
+                        let (mut proofs, mut solutions) = (0u64, 0u64);
+                        while Instant::now() < deadline {
+                            let Ok(solution) = puzzle.prove(epoch_hash, address, rand::thread_rng().gen(), None) else {
+                                continue;
+                            };
+                            proofs += 1;
+                            if puzzle.get_proof_target(&solution).is_ok_and(|target| target >= report.proof_target) {
+                                solutions += 1;
+                            }
+                        }
+                        (proofs, solutions)
+                    })
+                    .reduce(|| (0, 0), |a, b| (a.0 + b.0, a.1 + b.1))
+            });
+            report.add_run(threads, proofs, solutions, timer.elapsed());
+        }
+
+        match self.format {
+            BenchmarkFormat::Text => Ok(report.to_string()),
+            BenchmarkFormat::Json => Ok(serde_json::to_string_pretty(&report)?),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thread_counts() {
+        let benchmark = Benchmark::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(benchmark.thread_counts(1).unwrap(), vec![1]);
+        assert_eq!(benchmark.thread_counts(6).unwrap(), vec![1, 2, 4, 6]);
+        assert_eq!(benchmark.thread_counts(8).unwrap(), vec![1, 2, 4, 8]);
+
+        let benchmark = Benchmark::try_parse_from(["snarkos", "--threads", "2,3"].iter()).unwrap();
+        assert_eq!(benchmark.thread_counts(8).unwrap(), vec![2, 3]);
+        let benchmark = Benchmark::try_parse_from(["snarkos", "--threads", "0"].iter()).unwrap();
+        assert!(benchmark.thread_counts(8).is_err());
+    }
+
+    #[test]
+    fn test_report() {
+        let mut report = BenchmarkReport::new(100, 1_000, Some(5_000_000));
+        report.add_run(1, 50, 1, Duration::from_secs(10));
+        report.add_run(2, 90, 0, Duration::from_secs(10));
+
+        assert_eq!(report.runs[0].proofs_per_sec, 5.0);
+        assert_eq!(report.runs[0].solutions_per_sec, 0.05);
+        assert_eq!(report.runs[1].speedup, 1.8);
+        // Ensure the rewards are estimated from the fastest run.
+        assert_eq!(report.estimated_rewards_per_day, Some(9.0 * SECONDS_PER_DAY * 5_000.0));
+        assert!(report.to_string().contains("credits/day"));
+
+        // Ensure the rewards are unknown without the puzzle reward.
+        let mut report = BenchmarkReport::new(100, 1_000, None);
+        report.add_run(1, 50, 1, Duration::from_secs(10));
+        assert_eq!(report.estimated_rewards_per_day, None);
+        assert!(report.to_string().contains("unknown"));
+    }
+}
@@ -0,0 +1,34 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod benchmark;
+pub use benchmark::*;
+
+use anyhow::Result;
+use clap::Parser;
+
+/// Commands to size and operate a prover
+#[derive(Debug, Parser)]
+pub enum Prover {
+    /// Benchmark the puzzle on the local hardware, and estimate the solution rate and rewards at the current difficulty.
+    Benchmark(Benchmark),
+}
+
+impl Prover {
+    pub fn parse(self) -> Result<String> {
+        match self {
+            Self::Benchmark(benchmark) => benchmark.parse(),
+        }
+    }
+}
@@ -12,9 +12,56 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::{
+    commands::Restore,
+    helpers::{
+        unlock_ledger_dir,
+        CrashReporter,
+        DatabaseOptions,
+        LedgerKey,
+        LogFormat,
+        LogRotation,
+        LogRotationInterval,
+    },
+};
 use snarkos_account::Account;
 use snarkos_display::Display;
-use snarkos_node::{bft::MEMORY_POOL_PORT, router::messages::NodeType, Node};
+use snarkos_node::{
+    bft::{
+        helpers::{
+            signing_journal_path,
+            AuditLog,
+            Failover,
+            OperatorLane,
+            ParticipationConfig,
+            ProposalConfig,
+            RemoteSigner,
+            RemoteSignerConfig,
+            TimeoutConfig,
+            TransactionFilter,
+            TransmissionOrdering,
+        },
+        MEMORY_POOL_PORT,
+    },
+    consensus::AdmissionPolicy,
+    light_headers_path,
+    router::{messages::NodeType, KeepAlive, ListenAddr, RelayMode, SolutionRateLimit},
+    sync::SyncCheckpoint,
+    tcp::AcceptRateLimit,
+    AlertConfig,
+    CompactionSchedule,
+    CpuAffinity,
+    DiskWatchdog,
+    HeadlessProver,
+    LightClient,
+    Node,
+    PoolClient,
+    PoolConfig,
+    ProverDevices,
+    ProverThrottle,
+    SolutionSubmitter,
+    SubmitMode,
+};
 use snarkvm::{
     console::{
         account::{Address, PrivateKey},
@@ -24,7 +71,11 @@ use snarkvm::{
     ledger::{
         block::Block,
         committee::{Committee, MIN_DELEGATOR_STAKE, MIN_VALIDATOR_STAKE},
-        store::{helpers::memory::ConsensusMemory, ConsensusStore},
+        store::{
+            helpers::{memory::ConsensusMemory, rocksdb::ConsensusDB},
+            ConsensusStorage,
+            ConsensusStore,
+        },
     },
     prelude::{FromBytes, ToBits, ToBytes},
     synthesizer::VM,
@@ -32,8 +83,8 @@ use snarkvm::{
 };
 
 use aleo_std::StorageMode;
-use anyhow::{bail, ensure, Result};
-use clap::Parser;
+use anyhow::{anyhow, bail, ensure, Result};
+use clap::{Parser, ValueEnum};
 use colored::Colorize;
 use core::str::FromStr;
 use indexmap::IndexMap;
@@ -43,9 +94,16 @@ use serde::{Deserialize, Serialize};
 use std::{
     net::SocketAddr,
     path::PathBuf,
-    sync::{atomic::AtomicBool, Arc},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::{
+    runtime::{self, Runtime},
+    sync::mpsc::Receiver,
 };
-use tokio::runtime::{self, Runtime};
 
 /// The recommended minimum number of 'open files' limit for a validator.
 /// Validators should be able to handle at least 1000 concurrent connections, each requiring 2 sockets.
@@ -69,6 +127,42 @@ impl FromStr for BondedBalances {
     }
 }
 
+/// The storage backend of the ledger.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum StorageBackend {
+    /// A persistent RocksDB database.
+    #[default]
+    Rocksdb,
+    /// An ephemeral in-memory store, which is discarded when the node shuts down.
+    Memory,
+}
+
+/// An optional index maintained by the node.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum IndexKind {
+    /// The per-block counts and chain totals, and the program call counts.
+    Analytics,
+}
+
+/// The order in which a validator selects the transmissions for its batch proposals.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum BatchOrdering {
+    /// The transmissions are selected in the order they were received.
+    #[default]
+    Arrival,
+    /// The transactions are selected by their priority fee and fee per byte.
+    FeePriority,
+}
+
+impl From<BatchOrdering> for TransmissionOrdering {
+    fn from(ordering: BatchOrdering) -> Self {
+        match ordering {
+            BatchOrdering::Arrival => Self::Arrival,
+            BatchOrdering::FeePriority => Self::FeePriority,
+        }
+    }
+}
+
 /// Starts the snarkOS node.
 #[derive(Clone, Debug, Parser)]
 pub struct Start {
@@ -82,9 +176,67 @@ pub struct Start {
     /// Specify this node as a prover
     #[clap(long = "prover")]
     pub prover: bool,
+    /// If the flag is set, the node switches between a client and a validator as it is bonded in or out of the committee
+    #[clap(long = "auto-role", conflicts_with = "prover")]
+    pub auto_role: bool,
+    /// Specify the CUDA devices to split the puzzle across, i.e. `--cuda-devices 0,2` (provers only)
+    #[clap(long = "cuda-devices", value_name = "IDS", requires = "prover")]
+    pub cuda_devices: Option<String>,
+    /// Specify the maximum number of threads that the prover uses (provers only)
+    #[clap(long = "prover-threads", value_name = "THREADS", requires = "prover")]
+    pub prover_threads: Option<usize>,
+    /// Specify the cores to pin the prover threads to, i.e. `--prover-cores 0-15,32-47` (provers only)
+    #[clap(long = "prover-cores", value_name = "CORES", requires = "prover", conflicts_with = "prover_numa_nodes")]
+    pub prover_cores: Option<String>,
+    /// Specify the NUMA nodes to pin the prover threads to, i.e. `--prover-numa-nodes 1` (provers only)
+    #[clap(long = "prover-numa-nodes", value_name = "NODES", requires = "prover")]
+    pub prover_numa_nodes: Option<String>,
+    /// Specify the target percentage of the time that the prover spends proving, i.e. `--prover-utilization 50` (provers only)
+    #[clap(long = "prover-utilization", value_name = "PERCENT", requires = "prover")]
+    pub prover_utilization: Option<u8>,
+    /// Specify the URL of a pool server to prove for, i.e. `--pool tcp://pool.example.com:4140` (provers only)
+    #[clap(long = "pool", value_name = "URL", requires_all = ["prover", "worker"])]
+    pub pool: Option<String>,
+    /// Specify the name of this worker in the pool (provers only)
+    #[clap(long = "worker", value_name = "NAME", requires = "pool")]
+    pub worker: Option<String>,
+    /// Specify the REST endpoints to submit the solutions to, i.e. `--submit-endpoints "url1,url2"` (provers only)
+    #[clap(
+        long = "submit-endpoints",
+        value_name = "URLS",
+        value_delimiter = ',',
+        requires = "prover",
+        conflicts_with = "pool"
+    )]
+    pub submit_endpoints: Vec<String>,
+    /// Run the prover without the P2P network, proving the puzzle of the given node(s), i.e. `--headless "url1,url2"`
+    #[clap(
+        long = "headless",
+        value_name = "REST_URLS",
+        value_delimiter = ',',
+        requires = "prover",
+        conflicts_with_all = ["pool", "submit_endpoints"]
+    )]
+    pub headless: Vec<String>,
+    /// Specify the IP address and port to serve the prover statistics on, at `/status` (provers only)
+    #[clap(long = "prover-status", value_name = "IP:PORT", requires = "prover")]
+    pub prover_status: Option<SocketAddr>,
+    /// Specify whether the solutions are submitted to the first reachable endpoint, or to all endpoints [options: failover, all]
+    #[clap(default_value = "failover", long = "submit-mode", value_name = "MODE")]
+    pub submit_mode: String,
     /// Specify this node as a client
     #[clap(long = "client")]
     pub client: bool,
+    /// Specify the IP address and port to coordinate a pool for the workers on, i.e. `--pool-server 0.0.0.0:4140` (clients only)
+    #[clap(long = "pool-server", value_name = "IP:PORT", requires = "client")]
+    pub pool_server: Option<SocketAddr>,
+    /// Specify the divisor of the proof target that determines the share target of the workers (clients only)
+    #[clap(long = "pool-share-divisor", value_name = "DIVISOR", requires = "pool_server")]
+    pub pool_share_divisor: Option<u64>,
+    /// Run a light client that tracks the block headers via the REST API of the given full node(s), i.e. `--light "url1,url2"`,
+    /// and serves the verified headers and transaction inclusion proofs at `--rest`
+    #[clap(long = "light", value_name = "REST_URLS", conflicts_with_all = ["validator", "prover", "client"])]
+    pub light: Option<String>,
 
     /// Specify the account private key of the node
     #[clap(long = "private-key")]
@@ -92,22 +244,215 @@ pub struct Start {
     /// Specify the path to a file containing the account private key of the node
     #[clap(long = "private-key-file")]
     pub private_key_file: Option<PathBuf>,
+    /// Specify the HTTPS endpoint of a remote signer that holds the account private key (validators only)
+    #[clap(
+        long = "remote-signer",
+        value_name = "URL",
+        requires_all = ["remote_signer_ca", "remote_signer_cert", "remote_signer_key"],
+        conflicts_with_all = ["private_key", "private_key_file", "dev"]
+    )]
+    pub remote_signer: Option<String>,
+    /// Specify the path to the PEM-encoded CA certificate of the remote signer
+    #[clap(long = "remote-signer-ca", value_name = "PATH", requires = "remote_signer")]
+    pub remote_signer_ca: Option<PathBuf>,
+    /// Specify the path to the PEM-encoded client certificate for the remote signer
+    #[clap(long = "remote-signer-cert", value_name = "PATH", requires = "remote_signer")]
+    pub remote_signer_cert: Option<PathBuf>,
+    /// Specify the path to the PEM-encoded (PKCS #8) client key for the remote signer
+    #[clap(long = "remote-signer-key", value_name = "PATH", requires = "remote_signer")]
+    pub remote_signer_key: Option<PathBuf>,
+    /// Specify the path to a remote signer configuration, as imported by 'snarkos ceremony import' (validators only)
+    #[clap(
+        long = "remote-signer-config",
+        value_name = "PATH",
+        conflicts_with_all = ["remote_signer", "private_key", "private_key_file", "dev"]
+    )]
+    pub remote_signer_config: Option<PathBuf>,
 
     /// Specify the IP address and port for the node server
     #[clap(long = "node")]
     pub node: Option<SocketAddr>,
+    /// Specify an additional IP address and port to listen on, optionally followed by `=<advertised port>` (repeatable)
+    #[clap(long = "listen")]
+    pub listen: Vec<ListenAddr>,
     /// Specify the IP address and port for the BFT
     #[clap(long = "bft")]
     pub bft: Option<SocketAddr>,
+    /// Specify the number of BFT workers the transmissions are split between (validators only, default: 1)
+    #[clap(long = "bft-workers", value_name = "COUNT")]
+    pub bft_workers: Option<u8>,
+    /// Specify an offset to add to the default ports of the node (4130), BFT (5000), REST (3030) and metrics (9000)
+    #[clap(long = "port-offset", value_name = "OFFSET", conflicts_with = "dev")]
+    pub port_offset: Option<u16>,
     /// Specify the IP address and port of the peer(s) to connect to
     #[clap(default_value = "", long = "peers")]
     pub peers: String,
+    /// Specify the IP address and port of the peer(s) to always stay connected to
+    #[clap(default_value = "", long = "pinned-peers")]
+    pub pinned_peers: String,
+    /// Specify the IP address and port of the peer(s) to exclusively sync blocks from (clients only)
+    #[clap(default_value = "", long = "sync-peers")]
+    pub sync_peers: String,
+    /// Specify a trusted '<height>:<hash>' checkpoint to sync up to without full verification (clients only)
+    #[clap(long = "sync-checkpoint")]
+    pub sync_checkpoint: Option<String>,
+    /// Specify the URL of a state snapshot to bootstrap the ledger from, if it does not exist yet (clients only)
+    #[clap(long = "bootstrap", value_name = "URL", requires = "bootstrap_hash")]
+    pub bootstrap: Option<String>,
+    /// Specify the hash of the trusted block the state snapshot must end with
+    #[clap(long = "bootstrap-hash", value_name = "HASH", requires = "bootstrap")]
+    pub bootstrap_hash: Option<String>,
+    /// Specify the number of recent blocks to keep the proofs and records of, discarding older ones (clients only)
+    #[clap(long = "prune", value_name = "BLOCKS_TO_KEEP")]
+    pub prune: Option<u32>,
+    /// Specify the maximum size (in GB) of the ledger, beyond which the oldest blocks are pruned (clients only)
+    #[clap(long = "max-ledger-size", value_name = "GB", value_parser = clap::value_parser!(u64).range(1..))]
+    pub max_ledger_size: Option<u64>,
+    /// Specify the path to migrate the pruned blocks to, from where they are still served (clients only)
+    #[clap(long = "cold-storage", requires = "prune")]
+    pub cold_storage: Option<PathBuf>,
+    /// Specify the number of blocks between the state snapshots the node produces and serves (clients only)
+    #[clap(long = "snapshot-interval", value_name = "BLOCKS", value_parser = clap::value_parser!(u32).range(1..))]
+    pub snapshot_interval: Option<u32>,
+    /// Specify the optional indexes to maintain and serve over REST (clients only)
+    #[clap(value_enum, long = "index", value_delimiter = ',')]
+    pub index: Vec<IndexKind>,
+    /// Specify the number of blocks between the state digests the node logs and serves, to compare with other nodes
+    #[clap(
+        long = "state-digest-interval",
+        value_name = "BLOCKS",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub state_digest_interval: Option<u32>,
+    /// Specify the path to append the finalize state diff of every block to, as JSON lines, for external indexers
+    #[clap(long = "state-diffs", value_name = "PATH")]
+    pub state_diffs: Option<PathBuf>,
     /// Specify the IP address and port of the validator(s) to connect to
     #[clap(default_value = "", long = "validators")]
     pub validators: String,
+    /// Specify the interval in milliseconds between the batch proposals (validators only)
+    #[clap(long = "batch-interval", value_name = "MS")]
+    pub batch_interval: Option<u64>,
+    /// Specify the maximum number of transmissions in a batch proposal (validators only)
+    #[clap(long = "max-batch-transmissions", value_name = "TRANSMISSIONS")]
+    pub max_batch_transmissions: Option<usize>,
+    /// Specify the maximum size in bytes of the transmissions in a batch proposal (validators only)
+    #[clap(long = "max-batch-size", value_name = "BYTES")]
+    pub max_batch_size: Option<usize>,
+    /// Specify the maximum size in bytes of a single transmission in a batch proposal (validators only)
+    #[clap(long = "max-transmission-size", value_name = "BYTES")]
+    pub max_transmission_size: Option<usize>,
+    /// Specify the maximum number of solutions in a batch proposal (validators only)
+    #[clap(long = "max-batch-solutions", value_name = "SOLUTIONS")]
+    pub max_batch_solutions: Option<usize>,
+    /// Specify the order in which the transmissions are selected for a batch proposal (validators only)
+    #[clap(long = "batch-ordering", value_name = "ORDERING")]
+    pub batch_ordering: Option<BatchOrdering>,
+    /// If the flag is set, the validator speculates the finalize execution of a transaction, and skips proposing it if it would be rejected (validators only)
+    #[clap(long = "speculate-proposals")]
+    pub speculate_proposals: bool,
+    /// Specify the interval in milliseconds at which the BFT tries to advance to the next round (validators only)
+    #[clap(long = "round-interval", value_name = "MS")]
+    pub round_interval: Option<u64>,
+    /// Specify the time in seconds the BFT waits for the certificate of the round leader (validators only)
+    #[clap(long = "leader-timeout", value_name = "SECS")]
+    pub leader_timeout: Option<i64>,
+    /// Specify the number of committed rounds over which the participation of the validator is measured (validators only)
+    #[clap(long = "participation-window", value_name = "ROUNDS")]
+    pub participation_window: Option<u64>,
+    /// Specify the participation in percent below which the validator raises an alert (validators only)
+    #[clap(long = "participation-threshold", value_name = "PERCENT")]
+    pub participation_threshold: Option<u8>,
+    /// Specify the minimum fee in microcredits of a transaction admitted into the memory pool (validators only)
+    #[clap(long = "mempool-min-fee", value_name = "MICROCREDITS")]
+    pub mempool_min_fee: Option<u64>,
+    /// Specify the minimum fee in microcredits per byte of a transaction admitted into the memory pool (validators only)
+    #[clap(long = "mempool-min-fee-per-byte", value_name = "MICROCREDITS")]
+    pub mempool_min_fee_per_byte: Option<u64>,
+    /// Specify the maximum size in bytes of a transaction admitted into the memory pool (validators only)
+    #[clap(long = "mempool-max-transaction-size", value_name = "BYTES")]
+    pub mempool_max_transaction_size: Option<usize>,
+    /// Specify the maximum nested depth (in transitions) of an execution admitted into the memory pool (validators only)
+    #[clap(long = "mempool-max-execution-depth", value_name = "TRANSITIONS")]
+    pub mempool_max_execution_depth: Option<usize>,
+    /// Specify the maximum number of pending transactions of a fee payer in the memory pool (validators only)
+    #[clap(long = "mempool-max-pending-per-account", value_name = "TRANSACTIONS")]
+    pub mempool_max_pending_per_account: Option<usize>,
+    /// Specify the maximum total size in bytes of the pending transactions of a fee payer in the memory pool (validators only)
+    #[clap(long = "mempool-max-pending-bytes-per-account", value_name = "BYTES")]
+    pub mempool_max_pending_bytes_per_account: Option<usize>,
+    /// Specify the program IDs whose transactions are rejected from the memory pool and batch proposals (validators only)
+    #[clap(long = "mempool-deny-programs", value_name = "PROGRAM_IDS", value_delimiter = ',')]
+    pub mempool_deny_programs: Vec<String>,
+    /// Specify the minimum fee in microcredits of a transaction calling a program, as 'PROGRAM=MICROCREDITS' (validators only)
+    #[clap(long = "mempool-program-min-fee", value_name = "PROGRAM=MICROCREDITS", value_delimiter = ',')]
+    pub mempool_program_min_fees: Vec<String>,
+    /// Specify the maximum number of unconfirmed solutions accepted from a single peer per minute (validators only)
+    #[clap(long = "mempool-max-solutions-per-peer", value_name = "SOLUTIONS")]
+    pub mempool_max_solutions_per_peer: Option<usize>,
+    /// Specify the maximum number of unconfirmed solutions accepted from all peers per minute (validators only)
+    #[clap(long = "mempool-max-solutions", value_name = "SOLUTIONS")]
+    pub mempool_max_solutions: Option<usize>,
+    /// If the flag is set, the validator saves its memory pool on shutdown, and re-validates it on startup (validators only)
+    #[clap(long = "mempool-persist")]
+    pub mempool_persist: bool,
+    /// Specify the operator addresses whose transactions are proposed ahead of the fee ordering (validators only)
+    #[clap(long = "operator-addresses", value_name = "ADDRESSES", value_delimiter = ',')]
+    pub operator_addresses: Vec<String>,
+    /// Specify the maximum number of operator transactions proposed ahead of the fee ordering per batch (validators only)
+    #[clap(long = "operator-quota", value_name = "TRANSACTIONS", requires = "operator_addresses")]
+    pub operator_quota: Option<usize>,
+    /// Specify the number of past rounds of certificates the BFT storage retains, at least the protocol GC rounds (validators only)
+    #[clap(long = "bft-retention-rounds", value_name = "ROUNDS")]
+    pub bft_retention_rounds: Option<u64>,
+    /// Specify the path to the journal of the signed batches, which protects against double-signing (validators only)
+    #[clap(long = "signing-journal", value_name = "PATH")]
+    pub signing_journal: Option<PathBuf>,
+    /// Specify the name of this instance of the validator, which enables the active/standby failover with a signing lease (validators only)
+    #[clap(long = "failover-instance", value_name = "NAME")]
+    pub failover_instance: Option<String>,
+    /// If the flag is set, the validator starts on standby, and neither proposes nor signs until it is promoted
+    #[clap(long = "standby", requires = "failover_instance")]
+    pub standby: bool,
+    /// Specify the path of the audit log of the consensus events, as JSON lines (validators only)
+    #[clap(long = "audit-log", value_name = "PATH")]
+    pub audit_log: Option<PathBuf>,
+    /// Specify the size in megabytes at which the audit log is rotated
+    #[clap(default_value = "100", long = "audit-log-max-size", value_name = "MB", value_parser = clap::value_parser!(u64).range(1..))]
+    pub audit_log_max_size: u64,
+    /// Specify the number of rotated audit log files to keep
+    #[clap(default_value = "10", long = "audit-log-max-files", value_name = "FILES", value_parser = clap::value_parser!(u32).range(1..))]
+    pub audit_log_max_files: u32,
     /// If the flag is set, a node will allow untrusted peers to connect
     #[clap(long = "allow-external-peers")]
     pub allow_external_peers: bool,
+    /// If the flag is set, a node will discover peers on the local network via mDNS (intended for devnets)
+    #[clap(long = "local-discovery")]
+    pub local_discovery: bool,
+    /// Specify the interval in seconds between heartbeats, which maintain the connected peers
+    #[clap(default_value = "25", long = "heartbeat-interval")]
+    pub heartbeat_interval: u64,
+    /// Specify the interval in seconds between pings to each connected peer
+    #[clap(default_value = "20", long = "ping-interval")]
+    pub ping_interval: u64,
+    /// Specify the duration in seconds after which a silent peer is disconnected
+    #[clap(default_value = "150", long = "idle-timeout")]
+    pub idle_timeout: u64,
+    /// Specify the idle time in seconds after which TCP keep-alive probes are sent (disabled by default)
+    #[clap(long = "tcp-keepalive")]
+    pub tcp_keepalive: Option<u64>,
+    /// Specify the maximum number of inbound connection attempts accepted per second, from all sources combined
+    #[clap(default_value = "50", long = "max-accept-rate")]
+    pub max_accept_rate: u32,
+    /// Specify the maximum number of inbound connection attempts accepted per minute, from a single IP address
+    #[clap(default_value = "10", long = "max-accept-rate-per-ip")]
+    pub max_accept_rate_per_ip: u32,
+    /// If the flag is set, a node will introduce its peers that are behind a NAT to each other
+    #[clap(long = "relay")]
+    pub relay: bool,
+    /// If the flag is set, a node behind a NAT will ask a relay to introduce it to the peers it cannot reach
+    #[clap(long = "hole-punching")]
+    pub hole_punching: bool,
 
     /// Specify the IP address and port for the REST server
     #[clap(long = "rest")]
@@ -115,6 +460,9 @@ pub struct Start {
     /// Specify the requests per second (RPS) rate limit per IP for the REST server
     #[clap(default_value = "10", long = "rest-rps")]
     pub rest_rps: u32,
+    /// Specify the number of recently requested blocks and transactions the REST server caches (0 to disable)
+    #[clap(default_value = "256", long = "rest-cache-size")]
+    pub rest_cache_size: usize,
     /// If the flag is set, the node will not initialize the REST server
     #[clap(long)]
     pub norest: bool,
@@ -128,14 +476,62 @@ pub struct Start {
     /// Specify the path to the file where logs will be stored
     #[clap(default_value_os_t = std::env::temp_dir().join("snarkos.log"), long = "logfile")]
     pub logfile: PathBuf,
+    /// Specify the size (in MB) above which the logfile is rotated
+    #[clap(long = "log-max-size", value_name = "MB", value_parser = clap::value_parser!(u64).range(1..))]
+    pub log_max_size: Option<u64>,
+    /// Specify the interval at which the logfile is rotated
+    #[clap(value_enum, long = "log-rotation")]
+    pub log_rotation: Option<LogRotationInterval>,
+    /// Specify the number of rotated (and zstd-compressed) logfiles to keep
+    #[clap(default_value = "10", long = "log-retention", value_name = "COUNT")]
+    pub log_retention: usize,
+    /// Specify the format of the logs, i.e. `--log-format json` for log collectors (disables the display)
+    #[clap(value_enum, default_value = "pretty", long = "log-format")]
+    pub log_format: LogFormat,
+    /// Specify the OTLP (gRPC) endpoint to export the tracing spans to, i.e. `--otlp-endpoint http://localhost:4317`
+    #[clap(long = "otlp-endpoint", value_name = "URL")]
+    pub otlp_endpoint: Option<String>,
+    /// Specify the directory where the crash reports are written to (default: the directory of the logfile)
+    #[clap(long = "crash-report-dir", value_name = "PATH")]
+    pub crash_report_dir: Option<PathBuf>,
     /// Enables the metrics exporter
     #[clap(default_value = "false", long = "metrics")]
     pub metrics: bool,
+    /// Specify the IP address and port for the Prometheus exporter, which serves the metrics at `/metrics`
+    #[clap(long = "metrics-ip", value_name = "IP:PORT", requires = "metrics")]
+    pub metrics_ip: Option<SocketAddr>,
 
     /// Specify the path to a directory containing the storage database for the ledger
     #[clap(long = "storage")]
     pub storage: Option<PathBuf>,
-    /// Enables the node to prefetch initial blocks from a CDN
+    /// The tuning options of the ledger database
+    #[clap(flatten)]
+    pub database: DatabaseOptions,
+    /// Encrypt the ledger directory at rest (Linux only), with the key in `SNARKOS_LEDGER_KEY` or the OS keyring
+    #[clap(long = "encrypt-ledger")]
+    pub encrypt_ledger: bool,
+    /// Specify the free space (in GB) on the ledger volume below which the node warns and pauses non-essential writes
+    #[clap(default_value = "10", long = "disk-warning-threshold", value_name = "GB")]
+    pub disk_warning_threshold: u64,
+    /// Specify the free space (in GB) on the ledger volume below which the node shuts down gracefully
+    #[clap(long = "disk-shutdown-threshold", value_name = "GB")]
+    pub disk_shutdown_threshold: Option<u64>,
+    /// Specify the webhooks to post the health alerts to, i.e. `--alert-webhooks "url1,url2"` (Slack, Discord, or PagerDuty)
+    #[clap(long = "alert-webhooks", value_name = "URLS", value_delimiter = ',')]
+    pub alert_webhooks: Vec<String>,
+    /// Specify the time in seconds without a new block, after which the node raises a sync stall alert
+    #[clap(long = "alert-sync-stall", value_name = "SECS", requires = "alert_webhooks")]
+    pub alert_sync_stall: Option<u64>,
+    /// Specify the number of connected peers below which the node raises an alert
+    #[clap(long = "alert-min-peers", value_name = "COUNT", requires = "alert_webhooks")]
+    pub alert_min_peers: Option<usize>,
+    /// Specify the storage backend of the ledger
+    #[clap(value_enum, default_value = "rocksdb", long = "storage-backend")]
+    pub storage_backend: StorageBackend,
+    /// Specify a daily window of UTC hours in which the ledger database is compacted, i.e. `--compaction-schedule 2-5`
+    #[clap(long = "compaction-schedule", value_name = "HOURS")]
+    pub compaction_schedule: Option<CompactionSchedule>,
+    /// Enables the node to prefetch initial blocks from a CDN, or from the `index.json` URL of a static block archive
     #[clap(default_value = "https://s3.us-west-1.amazonaws.com/testnet3.blocks/phase3", long = "cdn")]
     pub cdn: String,
     /// If the flag is set, the node will not prefetch from a CDN
@@ -154,50 +550,66 @@ pub struct Start {
     /// If development mode is enabled, specify the custom bonded balances as a JSON object (default: None)
     #[clap(long)]
     pub dev_bonded_balances: Option<BondedBalances>,
+
+    /// The role the node was last switched to, if any, which overrides its configured role.
+    #[clap(skip)]
+    role: Option<NodeType>,
 }
 
 impl Start {
     /// Starts the snarkOS node.
-    pub fn parse(self) -> Result<String> {
+    pub fn parse(mut self) -> Result<String> {
         // Prepare the shutdown flag.
         let shutdown: Arc<AtomicBool> = Default::default();
 
-        // Initialize the logger.
-        let log_receiver =
-            crate::helpers::initialize_logger(self.verbosity, self.nodisplay, self.logfile.clone(), shutdown.clone());
-        // Initialize the runtime.
-        Self::runtime().block_on(async move {
+        // JSON logs are meant for log collectors, so they are never rendered in the display.
+        self.nodisplay |= self.log_format == LogFormat::Json;
+
+        // Restrict the proving backend to the selected devices, before any thread is started.
+        let devices = self.parse_prover_devices()?;
+        if let Some(visible_devices) = devices.cuda_visible_devices() {
+            devices.ensure_available(&ProverDevices::list_cuda()?)?;
+            // Number the devices as 'nvidia-smi' does, which the device IDs refer to.
+            std::env::set_var("CUDA_DEVICE_ORDER", "PCI_BUS_ID");
+            std::env::set_var("CUDA_VISIBLE_DEVICES", visible_devices);
+        }
+
+        // Initialize the runtime, capping the thread pool of the proving backend, if the prover is throttled,
+        // and pinning it to the prover cores, if any.
+        let affinity = self.parse_cpu_affinity()?;
+        let runtime = Self::runtime(self.parse_prover_throttle()?.threads(), affinity);
+
+        // Initialize the logger, within the runtime, as the OTLP exporter runs on it.
+        // Note: The light client and the headless prover have no display, so their logs are always printed.
+        let nodisplay = self.nodisplay || self.light.is_some() || !self.headless.is_empty();
+        let log_receiver = {
+            let _guard = runtime.enter();
+            crate::helpers::initialize_logger(
+                self.verbosity,
+                nodisplay,
+                self.logfile.clone(),
+                self.parse_log_rotation(),
+                self.log_format,
+                self.otlp_endpoint.as_deref(),
+                shutdown.clone(),
+            )?
+        };
+
+        // Write a crash report if the node panics, next to the logfile, unless specified otherwise.
+        let crash_report_dir = match &self.crash_report_dir {
+            Some(directory) => directory.clone(),
+            None => self.logfile.parent().map(PathBuf::from).unwrap_or_else(std::env::temp_dir),
+        };
+        let crash_reporter = CrashReporter::new(crash_report_dir, std::env::args()).install();
+
+        runtime.block_on(async move {
             // Clone the configurations.
             let mut cli = self.clone();
             // Parse the network.
             match cli.network {
-                MainnetV0::ID => {
-                    // Parse the node from the configurations.
-                    let node = cli.parse_node::<MainnetV0>(shutdown.clone()).await.expect("Failed to parse the node");
-                    // If the display is enabled, render the display.
-                    if !cli.nodisplay {
-                        // Initialize the display.
-                        Display::start(node, log_receiver).expect("Failed to initialize the display");
-                    }
-                }
-                TestnetV0::ID => {
-                    // Parse the node from the configurations.
-                    let node = cli.parse_node::<TestnetV0>(shutdown.clone()).await.expect("Failed to parse the node");
-                    // If the display is enabled, render the display.
-                    if !cli.nodisplay {
-                        // Initialize the display.
-                        Display::start(node, log_receiver).expect("Failed to initialize the display");
-                    }
-                }
-                CanaryV0::ID => {
-                    // Parse the node from the configurations.
-                    let node = cli.parse_node::<CanaryV0>(shutdown.clone()).await.expect("Failed to parse the node");
-                    // If the display is enabled, render the display.
-                    if !cli.nodisplay {
-                        // Initialize the display.
-                        Display::start(node, log_receiver).expect("Failed to initialize the display");
-                    }
-                }
+                MainnetV0::ID => cli.start_node::<MainnetV0>(shutdown.clone(), log_receiver, crash_reporter).await,
+                TestnetV0::ID => cli.start_node::<TestnetV0>(shutdown.clone(), log_receiver, crash_reporter).await,
+                CanaryV0::ID => cli.start_node::<CanaryV0>(shutdown.clone(), log_receiver, crash_reporter).await,
                 _ => panic!("Invalid network ID specified"),
             };
             // Note: Do not move this. The pending await must be here otherwise
@@ -207,9 +619,149 @@ impl Start {
 
         Ok(String::new())
     }
+
+    /// Starts the node with the selected storage backend, and renders the display if it is enabled.
+    async fn start_node<N: Network>(
+        &mut self,
+        shutdown: Arc<AtomicBool>,
+        log_receiver: Receiver<Vec<u8>>,
+        crash_reporter: Arc<CrashReporter>,
+    ) {
+        // If the light mode is enabled, track the block headers instead of running a node.
+        if self.light.is_some() {
+            let light_client = self.parse_light_client::<N>(shutdown).expect("Failed to parse the light client");
+            // Serve the tracked headers and the verified transactions, unless the REST server is disabled.
+            if !self.norest {
+                let rest_ip = self.rest.unwrap_or_else(|| "0.0.0.0:3030".parse().unwrap());
+                tokio::spawn(light_client.clone().serve(rest_ip));
+            }
+            tokio::spawn(async move { light_client.run().await });
+            return;
+        }
+        // If the headless mode is enabled, prove the puzzle of the given node(s) instead of running a node.
+        if !self.headless.is_empty() {
+            let prover = self.parse_headless_prover::<N>(shutdown).expect("Failed to parse the headless prover");
+            self.initialize_metrics::<N>(NodeType::Prover);
+            prover.start(self.prover_status).await.expect("Failed to start the headless prover");
+            return;
+        }
+        match self.storage_backend {
+            StorageBackend::Rocksdb => {
+                // Parse the node from the configurations.
+                let node =
+                    self.parse_node::<N, ConsensusDB<N>>(shutdown.clone()).await.expect("Failed to parse the node");
+                Self::track_crash_context(&node, &crash_reporter);
+                // Switch the node between the client and validator roles, if enabled.
+                if self.auto_role {
+                    tokio::spawn(self.clone().switch_roles(node.clone(), shutdown, crash_reporter));
+                }
+                self.start_display(node, log_receiver);
+            }
+            StorageBackend::Memory => {
+                eprintln!("The ledger is stored in memory, and will be discarded when the node shuts down");
+                // Parse the node from the configurations.
+                let node = self.parse_node::<N, ConsensusMemory<N>>(shutdown).await.expect("Failed to parse the node");
+                Self::track_crash_context(&node, &crash_reporter);
+                self.start_display(node, log_receiver);
+            }
+        }
+    }
+
+    /// Starts the node again in the role it is requested to switch to, on the same ledger, whenever its address
+    /// is bonded in or out of the committee.
+    async fn switch_roles<N: Network>(
+        mut self,
+        mut node: Node<N, ConsensusDB<N>>,
+        shutdown: Arc<AtomicBool>,
+        crash_reporter: Arc<CrashReporter>,
+    ) {
+        // Note: The given shutdown flag is shared with the logger, which dims the logs once it is set,
+        // so every node that is started again has its own flag, which is mirrored once it shuts down for good.
+        let mut node_shutdown = shutdown.clone();
+        loop {
+            let Some(role_transitions) = node.enable_role_transitions() else { return };
+            let role = tokio::select! {
+                role = role_transitions.wait() => role,
+                _ = wait_for_shutdown(&node_shutdown) => {
+                    shutdown.store(true, Ordering::Relaxed);
+                    return;
+                }
+            };
+            println!("🔄 Switching from {} to {}...\n", node.node_type().description(), role.description());
+            node.shut_down().await;
+            shutdown.store(false, Ordering::Relaxed);
+
+            // Start the node in its new role.
+            self.role = Some(role);
+            node_shutdown = Default::default();
+            node = match self.parse_node::<N, ConsensusDB<N>>(node_shutdown.clone()).await {
+                Ok(node) => node,
+                Err(error) => {
+                    eprintln!("Failed to start {} - {error}", role.description());
+                    std::process::exit(1);
+                }
+            };
+            Self::track_crash_context(&node, &crash_reporter);
+            println!("🔄 Switched to {}.\n", role.description());
+        }
+    }
+
+    /// Records the recent heights and rounds of the given node, for the crash report.
+    fn track_crash_context<N: Network, C: ConsensusStorage<N>>(node: &Node<N, C>, crash_reporter: &Arc<CrashReporter>) {
+        let node = node.clone();
+        crash_reporter.track(move || (node.latest_height(), node.current_round()));
+    }
+
+    /// Renders the display, if it is enabled.
+    fn start_display<N: Network, C: ConsensusStorage<N>>(&self, node: Node<N, C>, log_receiver: Receiver<Vec<u8>>) {
+        if !self.nodisplay {
+            // Initialize the display.
+            Display::start(node, log_receiver).expect("Failed to initialize the display");
+        }
+    }
 }
 
 impl Start {
+    /// Returns the rotation policy of the logfile.
+    fn parse_log_rotation(&self) -> LogRotation {
+        LogRotation {
+            max_size: self.log_max_size.map(|max_size| max_size * 1024 * 1024),
+            interval: self.log_rotation,
+            retention: self.log_retention,
+        }
+    }
+
+    /// Returns the light client, tracking the block headers of the given full node(s).
+    fn parse_light_client<N: Network>(&self, shutdown: Arc<AtomicBool>) -> Result<LightClient<N>> {
+        let sources = match &self.light {
+            Some(sources) => sources.split(',').map(str::trim).filter(|url| !url.is_empty()).map(String::from),
+            None => bail!("The light mode is not enabled"),
+        };
+        let storage_mode = match &self.storage {
+            Some(path) => StorageMode::Custom(path.clone()),
+            None => StorageMode::from(self.dev),
+        };
+        LightClient::new(self.parse_genesis::<N>()?, sources.collect(), shutdown)?
+            .with_storage(light_headers_path(N::ID, &storage_mode))
+    }
+
+    /// Initializes the metrics, if they are enabled, labeled with the given node type and the network.
+    fn initialize_metrics<N: Network>(&self, node_type: NodeType) {
+        if self.metrics {
+            let metrics_ip = self.metrics_ip.unwrap_or_else(|| metrics::DEFAULT_METRICS_IP.parse().unwrap());
+            let labels = [("node_type", node_type.to_string().to_lowercase()), ("network", N::NAME.to_string())];
+            metrics::initialize_metrics(metrics_ip, &labels);
+        }
+    }
+
+    /// Returns the headless prover, proving the puzzle of the given node(s).
+    fn parse_headless_prover<N: Network>(&self, shutdown: Arc<AtomicBool>) -> Result<HeadlessProver<N>> {
+        let nodes: Vec<_> =
+            self.headless.iter().map(|url| url.trim().to_string()).filter(|url| !url.is_empty()).collect();
+        let address = self.parse_private_key::<N>()?.address();
+        HeadlessProver::new(address, &nodes, &self.parse_prover_devices()?, self.parse_prover_throttle()?, shutdown)
+    }
+
     /// Returns the initial peer(s) to connect to, from the given configurations.
     fn parse_trusted_peers(&self) -> Result<Vec<SocketAddr>> {
         match self.peers.is_empty() {
@@ -228,6 +780,70 @@ impl Start {
         }
     }
 
+    /// Returns the pinned peer(s) to stay connected to, from the given configurations.
+    fn parse_pinned_peers(&self) -> Result<Vec<SocketAddr>> {
+        match self.pinned_peers.is_empty() {
+            true => Ok(vec![]),
+            false => Ok(self
+                .pinned_peers
+                .split(',')
+                .flat_map(|ip| match ip.parse::<SocketAddr>() {
+                    Ok(ip) => Some(ip),
+                    Err(e) => {
+                        eprintln!("The IP supplied to --pinned-peers ('{ip}') is malformed: {e}");
+                        None
+                    }
+                })
+                .collect()),
+        }
+    }
+
+    /// Returns the peer(s) to exclusively sync blocks from, from the given configurations.
+    /// Unlike the other peer lists, a malformed address is an error, as dropping it would change the set of sync sources.
+    fn parse_sync_peers(&self) -> Result<Vec<SocketAddr>> {
+        match self.sync_peers.is_empty() {
+            true => Ok(vec![]),
+            false => self
+                .sync_peers
+                .split(',')
+                .map(|ip| {
+                    ip.parse::<SocketAddr>()
+                        .map_err(|e| anyhow!("The IP supplied to --sync-peers ('{ip}') is malformed: {e}"))
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the trusted checkpoint to sync blocks up to without full verification, from the given configurations.
+    fn parse_sync_checkpoint<N: Network>(&self) -> Result<Option<SyncCheckpoint<N>>> {
+        self.sync_checkpoint.as_deref().map(SyncCheckpoint::from_str).transpose()
+    }
+
+    /// Returns the settings that keep the connections to the peers alive, from the given configurations.
+    fn parse_keep_alive(&self) -> Result<KeepAlive> {
+        KeepAlive::new(
+            Duration::from_secs(self.heartbeat_interval),
+            Duration::from_secs(self.ping_interval),
+            Duration::from_secs(self.idle_timeout),
+            self.tcp_keepalive.map(Duration::from_secs),
+        )
+    }
+
+    /// Returns the limits on the rate of inbound connection attempts, from the given configurations.
+    /// In development mode, the local nodes share an IP address, so the rate is not limited.
+    fn parse_accept_rate_limit(&self) -> Result<Option<AcceptRateLimit>> {
+        ensure!(self.max_accept_rate > 0, "The '--max-accept-rate' must be greater than 0");
+        ensure!(self.max_accept_rate_per_ip > 0, "The '--max-accept-rate-per-ip' must be greater than 0");
+        match self.dev {
+            Some(_) => Ok(None),
+            None => Ok(Some(AcceptRateLimit {
+                global_per_sec: self.max_accept_rate,
+                per_ip_per_min: self.max_accept_rate_per_ip,
+                ..Default::default()
+            })),
+        }
+    }
+
     /// Returns the initial validator(s) to connect to, from the given configurations.
     fn parse_trusted_validators(&self) -> Result<Vec<SocketAddr>> {
         match self.validators.is_empty() {
@@ -277,11 +893,14 @@ impl Start {
                     check_permissions(path)?;
                     Account::from_str(std::fs::read_to_string(path)?.trim())
                 }
-                // Ensure the private key is provided to the CLI, except for clients or nodes in development mode.
-                (None, None) => match self.client {
-                    true => Account::new(&mut rand::thread_rng()),
-                    false => bail!("Missing the '--private-key' or '--private-key-file' argument"),
-                },
+                // Ensure the private key is provided to the CLI, except for clients,
+                // or validators with a remote signer (whose router uses an ephemeral account).
+                (None, None) => {
+                    match self.client || self.remote_signer.is_some() || self.remote_signer_config.is_some() {
+                        true => Account::new(&mut rand::thread_rng()),
+                        false => bail!("Missing the '--private-key' or '--private-key-file' argument"),
+                    }
+                }
                 // Ensure only one private key flag is provided to the CLI.
                 (Some(_), Some(_)) => {
                     bail!("Cannot use '--private-key' and '--private-key-file' simultaneously, please use only one")
@@ -304,6 +923,85 @@ impl Start {
         }
     }
 
+    /// Returns the devices that the prover splits the puzzle across.
+    fn parse_prover_devices(&self) -> Result<ProverDevices> {
+        match &self.cuda_devices {
+            None => Ok(ProverDevices::default()),
+            Some(devices) => {
+                ensure!(cfg!(feature = "cuda"), "The '--cuda-devices' flag requires snarkOS to be built with 'cuda'");
+                ProverDevices::parse_cuda(devices)
+            }
+        }
+    }
+
+    /// Returns the limits on the resources that the prover uses.
+    fn parse_prover_throttle(&self) -> Result<ProverThrottle> {
+        ProverThrottle::new(self.prover_threads, self.prover_utilization)
+    }
+
+    /// Returns the CPU affinity of the prover, if its threads are pinned to cores or NUMA nodes.
+    fn parse_cpu_affinity(&self) -> Result<Option<CpuAffinity>> {
+        let prover_cores = match (&self.prover_cores, &self.prover_numa_nodes) {
+            (Some(cores), _) => CpuAffinity::parse_cores(cores)?,
+            (None, Some(nodes)) => CpuAffinity::numa_node_cores(nodes)?,
+            (None, None) => return Ok(None),
+        };
+        Ok(Some(CpuAffinity::new(prover_cores, num_cpus::get())?))
+    }
+
+    /// Returns the pool client of the prover, if a pool is specified.
+    fn parse_pool<N: Network>(&self) -> Result<Option<PoolClient<N>>> {
+        match (&self.pool, &self.worker) {
+            (Some(pool), Some(worker)) => Ok(Some(PoolClient::new(pool, worker)?)),
+            (None, None) => Ok(None),
+            _ => bail!("The '--pool' and '--worker' flags must be specified together"),
+        }
+    }
+
+    /// Returns the solution submitter of the prover.
+    fn parse_submitter<N: Network>(&self) -> Result<SolutionSubmitter<N>> {
+        SolutionSubmitter::new(&self.submit_endpoints, SubmitMode::from_str(&self.submit_mode)?)
+    }
+
+    /// Returns the pool coordinator configuration of the client, if a pool server is specified.
+    fn parse_pool_server(&self) -> Result<Option<PoolConfig>> {
+        self.pool_server.map(|listen| PoolConfig::new(listen, self.pool_share_divisor)).transpose()
+    }
+
+    /// Returns the remote signer of the validator, if one is specified.
+    async fn parse_remote_signer<N: Network>(&self) -> Result<Option<RemoteSigner<N>>> {
+        // Connect to the remote signer from its configuration, if one is specified.
+        if let Some(path) = &self.remote_signer_config {
+            // Ensure the remote signer is only used by validators.
+            ensure!(self.validator, "The '--remote-signer-config' flag is only supported by validators");
+            let config = RemoteSignerConfig::load(path)?;
+            check_permissions(&config.key)?;
+            let remote_signer = RemoteSigner::connect_with_config(&config)
+                .await
+                .map_err(|e| anyhow!("Failed to connect to the remote signer at '{}' - {e}", config.endpoint))?;
+            return Ok(Some(remote_signer));
+        }
+        match (&self.remote_signer, &self.remote_signer_ca, &self.remote_signer_cert, &self.remote_signer_key) {
+            (None, ..) => Ok(None),
+            (Some(endpoint), Some(ca_certificate), Some(certificate), Some(key)) => {
+                // Ensure the remote signer is only used by validators.
+                ensure!(self.validator, "The '--remote-signer' flag is only supported by validators");
+                check_permissions(key)?;
+                // Connect to the remote signer.
+                let remote_signer = RemoteSigner::connect(
+                    endpoint,
+                    &std::fs::read(ca_certificate)?,
+                    &std::fs::read(certificate)?,
+                    &std::fs::read(key)?,
+                )
+                .await
+                .map_err(|e| anyhow!("Failed to connect to the remote signer at '{endpoint}' - {e}"))?;
+                Ok(Some(remote_signer))
+            }
+            _ => bail!("The '--remote-signer' flag requires the CA certificate, client certificate, and client key"),
+        }
+    }
+
     /// Updates the configurations if the node is in development mode.
     fn parse_development(
         &mut self,
@@ -342,13 +1040,39 @@ impl Start {
             if !self.norest && self.rest.is_none() {
                 self.rest = Some(SocketAddr::from_str(&format!("0.0.0.0:{}", 3030 + dev)).unwrap());
             }
+            // If the metrics are enabled and the metrics IP is not already specified, set the metrics IP to `9000 + dev`.
+            if self.metrics && self.metrics_ip.is_none() {
+                self.metrics_ip = Some(SocketAddr::from_str(&format!("0.0.0.0:{}", 9000 + dev))?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Offsets the default ports of the node, the BFT, the REST server and the metrics, unless they are specified.
+    fn parse_port_offset(&mut self) -> Result<()> {
+        let Some(offset) = self.port_offset else { return Ok(()) };
+        let address = |port: u16| match port.checked_add(offset) {
+            Some(port) => Ok(SocketAddr::from(([0, 0, 0, 0], port))),
+            None => bail!("The port offset {offset} is too large"),
+        };
+        if self.node.is_none() {
+            self.node = Some(address(4130)?);
+        }
+        if self.bft.is_none() {
+            self.bft = Some(address(MEMORY_POOL_PORT)?);
+        }
+        if !self.norest && self.rest.is_none() {
+            self.rest = Some(address(3030)?);
+        }
+        if self.metrics && self.metrics_ip.is_none() {
+            self.metrics_ip = Some(address(9000)?);
         }
         Ok(())
     }
 
     /// Returns an alternative genesis block if the node is in development mode.
     /// Otherwise, returns the actual genesis block.
-    fn parse_genesis<N: Network>(&self) -> Result<Block<N>> {
+    pub(crate) fn parse_genesis<N: Network>(&self) -> Result<Block<N>> {
         if self.dev.is_some() {
             // Determine the number of genesis committee members.
             let num_committee_members = match self.dev_num_validators {
@@ -491,16 +1215,28 @@ impl Start {
 
     /// Returns the node type corresponding to the given configurations.
     #[rustfmt::skip]
-    async fn parse_node<N: Network>(&mut self, shutdown: Arc<AtomicBool>) -> Result<Node<N>> {
+    async fn parse_node<N: Network, C: ConsensusStorage<N>>(&mut self, shutdown: Arc<AtomicBool>) -> Result<Node<N, C>> {
         // Print the welcome.
         println!("{}", crate::helpers::welcome_message());
 
         // Parse the trusted peers to connect to.
         let mut trusted_peers = self.parse_trusted_peers()?;
+        // Parse the pinned peers to stay connected to.
+        let pinned_peers = self.parse_pinned_peers()?;
+        // Parse the peers to exclusively sync blocks from.
+        let sync_peers = self.parse_sync_peers()?;
+        // Parse the keep-alive settings.
+        let keep_alive = self.parse_keep_alive()?;
+        // Parse the relay settings.
+        let relay_mode = RelayMode::new(self.relay, self.hole_punching);
+        // Parse the limits on the rate of inbound connection attempts.
+        let accept_rate_limit = self.parse_accept_rate_limit()?;
         // Parse the trusted validators to connect to.
         let mut trusted_validators = self.parse_trusted_validators()?;
         // Parse the development configurations.
         self.parse_development(&mut trusted_peers, &mut trusted_validators)?;
+        // Parse the port offset.
+        self.parse_port_offset()?;
 
         // Parse the CDN.
         let cdn = self.parse_cdn();
@@ -509,8 +1245,191 @@ impl Start {
         let genesis = self.parse_genesis::<N>()?;
         // Parse the private key of the node.
         let account = self.parse_private_key::<N>()?;
+        // Parse the remote signer of the validator.
+        let remote_signer = self.parse_remote_signer::<N>().await?;
+        // Determine the address of the node, which is held by the remote signer, if one is specified.
+        let address = remote_signer.as_ref().map_or_else(|| account.address(), |signer| signer.address());
         // Parse the node type.
         let node_type = self.parse_node_type();
+        // Ensure the role transitions are only enabled for the nodes that can be started again in their new role.
+        ensure!(
+            !self.auto_role || (!node_type.is_prover() && self.storage_backend == StorageBackend::Rocksdb),
+            "The '--auto-role' flag is only supported by clients and validators that store the ledger on disk"
+        );
+        ensure!(
+            !self.auto_role || (self.dev.is_some() || self.private_key.is_some() || self.private_key_file.is_some()),
+            "The '--auto-role' flag requires the '--private-key' or '--private-key-file' argument"
+        );
+        ensure!(
+            !self.auto_role || (remote_signer.is_none() && self.failover_instance.is_none() && self.nodisplay),
+            "The '--auto-role' flag is not supported with a remote signer, a failover instance, or the display"
+        );
+        // Determine the roles the node may run in, as the flags of both roles apply if it switches between them.
+        let (may_be_client, may_be_validator) = match self.auto_role {
+            true => (true, true),
+            false => (node_type.is_client(), node_type.is_validator()),
+        };
+        // Ensure the sync peers are only set for clients, as validators sync through the BFT.
+        ensure!(sync_peers.is_empty() || may_be_client, "The '--sync-peers' flag is only supported by clients");
+        // Parse the trusted checkpoint to sync blocks up to.
+        let sync_checkpoint = self.parse_sync_checkpoint::<N>()?;
+        // Ensure the sync checkpoint is only set for clients, as validators must verify every block.
+        // Note: The same holds for the pruning and the bootstrapping, so they are not supported with '--auto-role'.
+        let is_client_only = node_type.is_client() && !self.auto_role;
+        ensure!(
+            sync_checkpoint.is_none() || is_client_only,
+            "The '--sync-checkpoint' flag is only supported by clients, without '--auto-role'"
+        );
+        // Ensure pruning is only enabled for clients, as validators must serve the full history.
+        ensure!(
+            self.prune.is_none() || is_client_only,
+            "The '--prune' flag is only supported by clients, without '--auto-role'"
+        );
+        ensure!(
+            self.max_ledger_size.is_none() || is_client_only,
+            "The '--max-ledger-size' flag is only supported by clients, without '--auto-role'"
+        );
+        // Ensure the maximum ledger size is only set for a ledger on disk.
+        ensure!(
+            self.max_ledger_size.is_none() || self.storage_backend != StorageBackend::Memory,
+            "The '--max-ledger-size' flag requires the ledger to be stored on disk"
+        );
+        // Ensure the compaction is only scheduled for a ledger on disk.
+        ensure!(
+            self.compaction_schedule.is_none() || (!node_type.is_prover() && self.storage_backend != StorageBackend::Memory),
+            "The '--compaction-schedule' flag requires the ledger to be stored on disk"
+        );
+        // Ensure state snapshots are only produced by clients, as they serve the network's sync requests.
+        ensure!(
+            self.snapshot_interval.is_none() || may_be_client,
+            "The '--snapshot-interval' flag is only supported by clients"
+        );
+        // Ensure state snapshots are only produced from a ledger on disk, as they are archives of its database.
+        ensure!(
+            self.snapshot_interval.is_none() || self.storage_backend != StorageBackend::Memory,
+            "The '--snapshot-interval' flag requires the ledger to be stored on disk"
+        );
+        // Ensure the ledger is only bootstrapped from a state snapshot by clients, as validators must execute every block.
+        ensure!(
+            self.bootstrap.is_none() || (is_client_only && self.storage_backend != StorageBackend::Memory),
+            "The '--bootstrap' flag is only supported by clients that store the ledger on disk, without '--auto-role'"
+        );
+        // Ensure the ledger is only encrypted by the nodes that store it on disk.
+        ensure!(
+            !self.encrypt_ledger || (!node_type.is_prover() && self.storage_backend != StorageBackend::Memory),
+            "The '--encrypt-ledger' flag requires the ledger to be stored on disk"
+        );
+        // Ensure the optional indexes are only maintained by clients, as they serve the statistics.
+        ensure!(self.index.is_empty() || may_be_client, "The '--index' flag is only supported by clients");
+        // Ensure the batch proposals are only configured for validators, and within the safe bounds.
+        ensure!(
+            may_be_validator
+                || (self.batch_interval.is_none()
+                    && self.max_batch_transmissions.is_none()
+                    && self.max_batch_size.is_none()
+                    && self.max_transmission_size.is_none()
+                    && self.max_batch_solutions.is_none()
+                    && self.batch_ordering.is_none()
+                    && !self.speculate_proposals),
+            "The batch proposal flags are only supported by validators"
+        );
+        // Ensure the BFT workers are only configured for validators.
+        ensure!(
+            self.bft_workers.is_none() || may_be_validator,
+            "The '--bft-workers' flag is only supported by validators"
+        );
+        let proposal_config =
+            ProposalConfig::<N>::new(self.batch_interval, self.max_batch_transmissions, self.max_batch_size)?
+                .with_num_workers(self.bft_workers)?
+                .with_transmission_limits(self.max_transmission_size, self.max_batch_solutions)?
+                .with_ordering(self.batch_ordering.unwrap_or_default().into())
+                .with_speculation(self.speculate_proposals);
+        // Ensure the round timeouts are only configured for validators, and within the safe bounds.
+        ensure!(
+            may_be_validator || (self.round_interval.is_none() && self.leader_timeout.is_none()),
+            "The '--round-interval' and '--leader-timeout' flags are only supported by validators"
+        );
+        let timeout_config = TimeoutConfig::new(self.round_interval, self.leader_timeout)?;
+        // Ensure the participation alerts are only configured for validators, and within the bounds.
+        ensure!(
+            may_be_validator
+                || (self.participation_window.is_none() && self.participation_threshold.is_none()),
+            "The participation flags are only supported by validators"
+        );
+        let participation_config = ParticipationConfig::new(self.participation_window, self.participation_threshold)?;
+        // Ensure the admission policy of the memory pool is only configured for validators, and within the limits.
+        ensure!(
+            may_be_validator
+                || (self.mempool_min_fee.is_none()
+                    && self.mempool_min_fee_per_byte.is_none()
+                    && self.mempool_max_transaction_size.is_none()
+                    && self.mempool_max_execution_depth.is_none()
+                    && self.mempool_max_pending_per_account.is_none()
+                    && self.mempool_max_pending_bytes_per_account.is_none()
+                    && self.mempool_deny_programs.is_empty()
+                    && self.mempool_program_min_fees.is_empty()
+                    && self.mempool_max_solutions_per_peer.is_none()
+                    && self.mempool_max_solutions.is_none()
+                    && !self.mempool_persist),
+            "The memory pool flags are only supported by validators"
+        );
+        let admission_policy = AdmissionPolicy::<N>::new(
+            self.mempool_min_fee,
+            self.mempool_min_fee_per_byte,
+            self.mempool_max_transaction_size,
+            self.mempool_max_execution_depth,
+            self.mempool_max_pending_per_account,
+            self.mempool_max_pending_bytes_per_account,
+        )?
+        .with_transaction_filter(TransactionFilter::parse(&self.mempool_deny_programs, &self.mempool_program_min_fees)?);
+        // Parse the rate limits on the unconfirmed solutions from peers, if enabled.
+        let solution_rate_limit = match (self.mempool_max_solutions_per_peer, self.mempool_max_solutions) {
+            (None, None) => None,
+            (max_per_peer, max_total) => Some(SolutionRateLimit::new(max_per_peer, max_total)?),
+        };
+        // Ensure the operator lane is only configured for validators.
+        ensure!(
+            self.operator_addresses.is_empty() || may_be_validator,
+            "The '--operator-addresses' flag is only supported by validators"
+        );
+        let operator_lane = OperatorLane::<N>::parse(&self.operator_addresses, self.operator_quota)?;
+        // Ensure the BFT retention is only configured for validators.
+        ensure!(
+            self.bft_retention_rounds.is_none() || may_be_validator,
+            "The '--bft-retention-rounds' flag is only supported by validators"
+        );
+        // Ensure the signing journal is only configured for validators.
+        ensure!(
+            self.signing_journal.is_none() || may_be_validator,
+            "The '--signing-journal' flag is only supported by validators"
+        );
+        // Parse the signing journal, which validators always keep.
+        let signing_journal = self.signing_journal.clone().unwrap_or_else(|| signing_journal_path(N::ID, self.dev));
+        // Ensure the failover is only configured for validators.
+        ensure!(
+            self.failover_instance.is_none() || may_be_validator,
+            "The '--failover-instance' flag is only supported by validators"
+        );
+        // Initialize the failover, if enabled, in which case the signing journal is only opened while holding the lease.
+        let failover = match &self.failover_instance {
+            Some(instance) => Some(Failover::<N>::new(instance, &signing_journal, self.standby)?),
+            None => None,
+        };
+        let signing_journal = match failover {
+            Some(_) => None,
+            None => Some(signing_journal),
+        };
+        // Ensure the audit log is only configured for validators.
+        ensure!(self.audit_log.is_none() || may_be_validator, "The '--audit-log' flag is only supported by validators");
+        // Open the audit log, if enabled.
+        let audit_log = match &self.audit_log {
+            Some(path) => Some(
+                AuditLog::<N>::open(path)?.with_rotation(self.audit_log_max_size * 1024 * 1024, self.audit_log_max_files)?,
+            ),
+            None => None,
+        };
+        // Start the node in the role it was last switched to, if any.
+        let node_type = self.role.unwrap_or(node_type);
 
         // Parse the node IP.
         let node_ip = match self.node {
@@ -527,7 +1446,7 @@ impl Start {
         // If the display is not enabled, render the welcome message.
         if self.nodisplay {
             // Print the Aleo address.
-            println!("👛 Your Aleo address is {}.\n", account.address().to_string().bold());
+            println!("👛 Your Aleo address is {}.\n", address.to_string().bold());
             // Print the node type and network.
             println!(
                 "🧭 Starting {} on {} at {}.\n",
@@ -541,7 +1460,7 @@ impl Start {
                 if let Some(rest_ip) = rest_ip {
                     println!("🌐 Starting the REST server at {}.\n", rest_ip.to_string().bold());
 
-                    if let Ok(jwt_token) = snarkos_node_rest::Claims::new(account.address()).to_jwt_string() {
+                    if let Ok(jwt_token) = snarkos_node_rest::Claims::new(address).to_jwt_string() {
                         println!("🔑 Your one-time JWT token is {}\n", jwt_token.dimmed());
                     }
                 }
@@ -556,9 +1475,9 @@ impl Start {
         // Check if the machine meets the minimum requirements for a validator.
         crate::helpers::check_validator_machine(node_type);
 
-        // Initialize the metrics.
-        if self.metrics {
-            metrics::initialize_metrics();
+        // Initialize the metrics, unless the node is started again in another role, as they are already exported.
+        if self.role.is_none() {
+            self.initialize_metrics::<N>(node_type);
         }
 
         // Initialize the storage mode.
@@ -567,6 +1486,62 @@ impl Start {
             None => StorageMode::from(self.dev),
         };
 
+        // Unlock the encrypted ledger directory, which is encrypted if the node has no ledger yet.
+        if self.encrypt_ledger {
+            let ledger_dir = aleo_std::aleo_ledger_dir(N::ID, storage_mode.clone());
+            unlock_ledger_dir(&ledger_dir, &LedgerKey::load()?)?;
+        }
+
+        // Bootstrap the ledger from a state snapshot, unless the node already has a ledger.
+        // Note: The snapshot is verified against the trusted block hash, and the node then syncs the blocks after it.
+        if let Some(url) = &self.bootstrap {
+            let ledger_dir = aleo_std::aleo_ledger_dir(N::ID, storage_mode.clone());
+            match ledger_dir.read_dir().map_or(false, |mut entries| entries.next().is_some()) {
+                true => eprintln!("The '--bootstrap' flag is ignored, as the ledger already exists"),
+                false => {
+                    let restore = Restore {
+                        archive: url.clone(),
+                        trusted_hash: self.bootstrap_hash.clone(),
+                        network: N::ID,
+                        dev: self.dev,
+                        dev_num_validators: self.dev_num_validators,
+                        path: self.storage.clone(),
+                        database: self.database.clone(),
+                        encrypt: false,
+                    };
+                    println!("{}", tokio::task::spawn_blocking(move || restore.parse()).await??);
+                }
+            }
+        }
+
+        // Initialize the disk watchdog, for the nodes that persist their ledger.
+        let disk_watchdog = match node_type.is_prover() || self.storage_backend == StorageBackend::Memory {
+            true => None,
+            false => {
+                const GB: u64 = 1024 * 1024 * 1024;
+                let ledger_dir = aleo_std::aleo_ledger_dir(N::ID, storage_mode.clone());
+                let shutdown_threshold = self.disk_shutdown_threshold.map(|threshold| threshold * GB);
+                Some(DiskWatchdog::new(ledger_dir, self.disk_warning_threshold * GB, shutdown_threshold)?)
+            }
+        };
+
+        // Initialize the health alerts, if any webhooks are given.
+        let alert_config = match self.alert_webhooks.is_empty() {
+            true => None,
+            false => {
+                let source = format!("{node_type} {address}");
+                Some(AlertConfig::new(&self.alert_webhooks, source, self.alert_sync_stall, self.alert_min_peers)?)
+            }
+        };
+
+        // Prepare the scheduled compaction of the ledger database, if any.
+        let compaction = self.compaction_schedule.map(|schedule| (schedule, storage_mode.clone()));
+
+        // Tune the ledger database, unless the node keeps no ledger on disk.
+        if !node_type.is_prover() && self.storage_backend == StorageBackend::Rocksdb {
+            self.database.apply_to_ledger::<N>(storage_mode.clone())?;
+        }
+
         // Determine whether to generate background transactions in dev mode.
         let dev_txs = match self.dev {
             Some(_) => !self.no_dev_txs,
@@ -580,15 +1555,57 @@ impl Start {
         };
 
         // Initialize the node.
-        match node_type {
-            NodeType::Validator => Node::new_validator(node_ip, self.bft, rest_ip, self.rest_rps, account, &trusted_peers, &trusted_validators, genesis, cdn, storage_mode, self.allow_external_peers, dev_txs, shutdown.clone()).await,
-            NodeType::Prover => Node::new_prover(node_ip, account, &trusted_peers, genesis, storage_mode, shutdown.clone()).await,
-            NodeType::Client => Node::new_client(node_ip, rest_ip, self.rest_rps, account, &trusted_peers, genesis, cdn, storage_mode, shutdown).await,
+        let node = match node_type {
+            NodeType::Validator => Node::new_validator(node_ip, &self.listen, self.bft, rest_ip, self.rest_rps, self.rest_cache_size, account, remote_signer, &trusted_peers, &pinned_peers, keep_alive, relay_mode, &trusted_validators, proposal_config, timeout_config, participation_config, admission_policy, solution_rate_limit, self.mempool_persist, operator_lane, self.bft_retention_rounds, signing_journal, failover, audit_log, genesis, cdn, storage_mode, self.allow_external_peers, dev_txs, shutdown.clone()).await,
+            NodeType::Prover => Node::new_prover(node_ip, &self.listen, account, &trusted_peers, &pinned_peers, keep_alive, relay_mode, self.parse_prover_devices()?, self.parse_prover_throttle()?, self.parse_pool()?, self.parse_submitter()?, self.prover_status, genesis, storage_mode, shutdown.clone()).await,
+            NodeType::Client => Node::new_client(node_ip, &self.listen, rest_ip, self.rest_rps, self.rest_cache_size, account, &trusted_peers, &pinned_peers, &sync_peers, sync_checkpoint, self.prune, self.max_ledger_size.map(|size| size * 1024 * 1024 * 1024), self.cold_storage.clone(), self.snapshot_interval, self.index.contains(&IndexKind::Analytics), keep_alive, relay_mode, self.parse_pool_server()?, genesis, cdn, storage_mode, shutdown.clone()).await,
+        }?;
+
+        // Limit the rate of inbound connection attempts, if enabled.
+        if let Some(limit) = accept_rate_limit {
+            node.set_accept_rate_limit(limit);
+        }
+        // Enable the discovery of peers on the local network, if requested.
+        if self.local_discovery {
+            node.enable_local_discovery()?;
+        }
+        // Post the health alerts to the webhooks, if requested.
+        if let Some(config) = alert_config {
+            node.enable_alerts(config, disk_watchdog.clone());
+        }
+        // Compact the ledger database on a schedule, if requested.
+        if let Some((schedule, storage_mode)) = compaction {
+            node.enable_scheduled_compaction(schedule, storage_mode);
+        }
+        // Monitor the free space on the ledger volume, if the ledger is persisted.
+        if let Some(disk_watchdog) = disk_watchdog {
+            node.enable_disk_watchdog(disk_watchdog.clone());
+            // Stop the node once the ledger volume is almost full, and exit with an error.
+            let (node, shutdown) = (node.clone(), shutdown.clone());
+            tokio::spawn(async move {
+                tokio::select! {
+                    _ = disk_watchdog.wait_until_critical() => (),
+                    // Release the node once it shuts down, as it may be started again in another role.
+                    _ = wait_for_shutdown(&shutdown) => return,
+                }
+                node.shut_down().await;
+                std::process::exit(1);
+            });
         }
+        // Log the state digests, if requested.
+        if let Some(interval) = self.state_digest_interval {
+            node.enable_state_digests(interval);
+        }
+        // Export the state diffs, if requested.
+        if let Some(path) = &self.state_diffs {
+            node.enable_state_diffs(path);
+        }
+
+        Ok(node)
     }
 
-    /// Returns a runtime for the node.
-    fn runtime() -> Runtime {
+    /// Returns a runtime for the node, with the given number of rayon threads, if capped.
+    fn runtime(num_rayon_threads: Option<usize>, affinity: Option<CpuAffinity>) -> Runtime {
         // Retrieve the number of cores.
         let num_cores = num_cpus::get();
 
@@ -596,21 +1613,38 @@ impl Start {
         // Note: We intentionally set the number of tokio worker threads and number of rayon cores to be
         // more than the number of physical cores, because the node is expected to be I/O-bound.
         let (num_tokio_worker_threads, max_tokio_blocking_threads, num_rayon_cores_global) =
-            (2 * num_cores, 512, num_cores);
+            (2 * num_cores, 512, num_rayon_threads.unwrap_or(num_cores));
+        // If the prover threads are pinned, run one rayon thread per prover core, unless the prover is throttled.
+        let num_rayon_cores_global = match &affinity {
+            Some(affinity) if num_rayon_threads.is_none() => affinity.prover_cores().len(),
+            _ => num_rayon_cores_global,
+        };
 
-        // Initialize the parallelization parameters.
+        // Initialize the parallelization parameters, pinning the rayon threads to the prover cores, if any.
+        let rayon_affinity = affinity.clone();
         rayon::ThreadPoolBuilder::new()
             .stack_size(8 * 1024 * 1024)
             .num_threads(num_rayon_cores_global)
+            .start_handler(move |index| {
+                if let Some(affinity) = &rayon_affinity {
+                    affinity.pin_prover_thread(index);
+                }
+            })
             .build_global()
             .unwrap();
 
-        // Initialize the runtime configuration.
+        // Initialize the runtime configuration, pinning the tokio threads to the other cores, if any.
+        let num_tokio_threads = AtomicUsize::new(0);
         runtime::Builder::new_multi_thread()
             .enable_all()
             .thread_stack_size(8 * 1024 * 1024)
             .worker_threads(num_tokio_worker_threads)
             .max_blocking_threads(max_tokio_blocking_threads)
+            .on_thread_start(move || {
+                if let Some(affinity) = &affinity {
+                    affinity.pin_other_thread(num_tokio_threads.fetch_add(1, Ordering::Relaxed));
+                }
+            })
             .build()
             .expect("Failed to initialize a runtime for the router")
     }
@@ -746,10 +1780,20 @@ fn load_or_compute_genesis<N: Network>(
     Ok(block)
 }
 
+/// Waits until the given shutdown flag is set.
+async fn wait_for_shutdown(shutdown: &AtomicBool) {
+    while !shutdown.load(Ordering::Relaxed) {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::commands::{Command, CLI};
+    use crate::{
+        commands::{Command, CLI},
+        helpers::DatabaseProfile,
+    };
     use snarkvm::prelude::MainnetV0;
 
     type CurrentNetwork = MainnetV0;
@@ -772,6 +1816,736 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn test_parse_listen() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(config.listen.is_empty());
+
+        let config =
+            Start::try_parse_from(["snarkos", "--listen", "10.0.0.5:4130", "--listen", "0.0.0.0:4131=4141"].iter())
+                .unwrap();
+        assert_eq!(config.listen, vec![
+            ListenAddr::from_str("10.0.0.5:4130").unwrap(),
+            ListenAddr::new(SocketAddr::from_str("0.0.0.0:4131").unwrap(), 4141)
+        ]);
+
+        assert!(Start::try_parse_from(["snarkos", "--listen", "10.0.0.5"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_pinned_peers() {
+        let config = Start::try_parse_from(["snarkos", "--pinned-peers", ""].iter()).unwrap();
+        assert!(config.parse_pinned_peers().is_ok());
+        assert!(config.parse_pinned_peers().unwrap().is_empty());
+
+        let config = Start::try_parse_from(["snarkos", "--pinned-peers", "1.2.3.4:5,6.7.8.9:0"].iter()).unwrap();
+        assert!(config.parse_pinned_peers().is_ok());
+        assert_eq!(config.parse_pinned_peers().unwrap(), vec![
+            SocketAddr::from_str("1.2.3.4:5").unwrap(),
+            SocketAddr::from_str("6.7.8.9:0").unwrap()
+        ]);
+    }
+
+    #[test]
+    fn test_parse_sync_peers() {
+        let config = Start::try_parse_from(["snarkos", "--sync-peers", ""].iter()).unwrap();
+        assert!(config.parse_sync_peers().unwrap().is_empty());
+
+        let config = Start::try_parse_from(["snarkos", "--sync-peers", "1.2.3.4:5,6.7.8.9:0"].iter()).unwrap();
+        assert_eq!(config.parse_sync_peers().unwrap(), vec![
+            SocketAddr::from_str("1.2.3.4:5").unwrap(),
+            SocketAddr::from_str("6.7.8.9:0").unwrap()
+        ]);
+
+        // A malformed address must not silently change the set of sync peers.
+        let config = Start::try_parse_from(["snarkos", "--sync-peers", "1.2.3.4:5,1.2.3.4"].iter()).unwrap();
+        assert!(config.parse_sync_peers().is_err());
+    }
+
+    #[test]
+    fn test_parse_max_ledger_size() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.max_ledger_size, None);
+        let config = Start::try_parse_from(["snarkos", "--max-ledger-size", "500"].iter()).unwrap();
+        assert_eq!(config.max_ledger_size, Some(500));
+
+        // Ensure the maximum size must be positive.
+        assert!(Start::try_parse_from(["snarkos", "--max-ledger-size", "0"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_cold_storage() {
+        let config =
+            Start::try_parse_from(["snarkos", "--prune", "1000", "--cold-storage", "/mnt/cold"].iter()).unwrap();
+        assert_eq!(config.cold_storage, Some(PathBuf::from("/mnt/cold")));
+
+        // Ensure the cold storage is only used together with pruning.
+        assert!(Start::try_parse_from(["snarkos", "--cold-storage", "/mnt/cold"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_bootstrap() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(config.bootstrap.is_none());
+
+        let url = "https://example.com/mainnet/snapshot/latest";
+        let hash = "ab1mkz7u5s4g5dy3kgu5snmchv4vsv9v5hyyaq2yqtfs9a6fkrj8vzsykkl7h";
+        let config = Start::try_parse_from(["snarkos", "--bootstrap", url, "--bootstrap-hash", hash].iter()).unwrap();
+        assert_eq!(config.bootstrap.as_deref(), Some(url));
+        assert_eq!(config.bootstrap_hash.as_deref(), Some(hash));
+
+        // Ensure a snapshot is never bootstrapped from without a trusted block hash.
+        assert!(Start::try_parse_from(["snarkos", "--bootstrap", url].iter()).is_err());
+        assert!(Start::try_parse_from(["snarkos", "--bootstrap-hash", hash].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_snapshot_interval() {
+        let config = Start::try_parse_from(["snarkos", "--snapshot-interval", "1000"].iter()).unwrap();
+        assert_eq!(config.snapshot_interval, Some(1000));
+
+        // Ensure the interval must be positive.
+        assert!(Start::try_parse_from(["snarkos", "--snapshot-interval", "0"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_state_digest_interval() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.state_digest_interval, None);
+        let config = Start::try_parse_from(["snarkos", "--state-digest-interval", "100"].iter()).unwrap();
+        assert_eq!(config.state_digest_interval, Some(100));
+
+        // Ensure the interval must be positive.
+        assert!(Start::try_parse_from(["snarkos", "--state-digest-interval", "0"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_batch_proposals() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.batch_interval, None);
+        assert_eq!(config.max_batch_transmissions, None);
+        assert_eq!(config.max_batch_size, None);
+
+        let config = Start::try_parse_from(
+            ["snarkos", "--batch-interval", "1500", "--max-batch-transmissions", "20", "--max-batch-size", "1000000"]
+                .iter(),
+        )
+        .unwrap();
+        assert_eq!(config.batch_interval, Some(1500));
+        assert_eq!(config.max_batch_transmissions, Some(20));
+        assert_eq!(config.max_batch_size, Some(1_000_000));
+        assert_eq!(config.batch_ordering, None);
+
+        let config = Start::try_parse_from(["snarkos", "--batch-ordering", "fee-priority"].iter()).unwrap();
+        assert_eq!(config.batch_ordering, Some(BatchOrdering::FeePriority));
+        assert!(Start::try_parse_from(["snarkos", "--batch-ordering", "random"].iter()).is_err());
+
+        assert!(!config.speculate_proposals);
+        let config = Start::try_parse_from(["snarkos", "--speculate-proposals"].iter()).unwrap();
+        assert!(config.speculate_proposals);
+
+        assert_eq!(config.max_transmission_size, None);
+        assert_eq!(config.max_batch_solutions, None);
+        let config =
+            Start::try_parse_from(["snarkos", "--max-transmission-size", "65536", "--max-batch-solutions", "4"].iter())
+                .unwrap();
+        assert_eq!(config.max_transmission_size, Some(65536));
+        assert_eq!(config.max_batch_solutions, Some(4));
+    }
+
+    #[test]
+    fn test_parse_round_timeouts() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.round_interval, None);
+        assert_eq!(config.leader_timeout, None);
+
+        let config =
+            Start::try_parse_from(["snarkos", "--round-interval", "1000", "--leader-timeout", "20"].iter()).unwrap();
+        assert_eq!(config.round_interval, Some(1000));
+        assert_eq!(config.leader_timeout, Some(20));
+    }
+
+    #[test]
+    fn test_parse_participation_alerts() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.participation_window, None);
+        assert_eq!(config.participation_threshold, None);
+
+        let config = Start::try_parse_from(
+            ["snarkos", "--participation-window", "200", "--participation-threshold", "90"].iter(),
+        )
+        .unwrap();
+        assert_eq!(config.participation_window, Some(200));
+        assert_eq!(config.participation_threshold, Some(90));
+    }
+
+    #[test]
+    fn test_parse_mempool_admission() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.mempool_min_fee, None);
+        assert_eq!(config.mempool_min_fee_per_byte, None);
+        assert_eq!(config.mempool_max_transaction_size, None);
+        assert_eq!(config.mempool_max_execution_depth, None);
+        assert_eq!(config.mempool_max_pending_per_account, None);
+        assert_eq!(config.mempool_max_pending_bytes_per_account, None);
+        assert!(config.mempool_deny_programs.is_empty());
+        assert!(config.mempool_program_min_fees.is_empty());
+        assert_eq!(config.mempool_max_solutions_per_peer, None);
+        assert_eq!(config.mempool_max_solutions, None);
+        assert!(!config.mempool_persist);
+
+        let config = Start::try_parse_from(
+            [
+                "snarkos",
+                "--mempool-min-fee",
+                "10000",
+                "--mempool-min-fee-per-byte",
+                "5",
+                "--mempool-max-transaction-size",
+                "50000",
+                "--mempool-max-execution-depth",
+                "8",
+                "--mempool-max-pending-per-account",
+                "20",
+                "--mempool-max-pending-bytes-per-account",
+                "2000000",
+                "--mempool-deny-programs",
+                "spam.aleo,flood.aleo",
+                "--mempool-program-min-fee",
+                "token.aleo=5000",
+                "--mempool-max-solutions-per-peer",
+                "30",
+                "--mempool-max-solutions",
+                "600",
+                "--mempool-persist",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        assert_eq!(config.mempool_min_fee, Some(10_000));
+        assert_eq!(config.mempool_min_fee_per_byte, Some(5));
+        assert_eq!(config.mempool_max_transaction_size, Some(50_000));
+        assert_eq!(config.mempool_max_execution_depth, Some(8));
+        assert_eq!(config.mempool_max_pending_per_account, Some(20));
+        assert_eq!(config.mempool_max_pending_bytes_per_account, Some(2_000_000));
+        assert_eq!(config.mempool_deny_programs, vec!["spam.aleo", "flood.aleo"]);
+        assert_eq!(config.mempool_program_min_fees, vec!["token.aleo=5000"]);
+        assert_eq!(config.mempool_max_solutions_per_peer, Some(30));
+        assert_eq!(config.mempool_max_solutions, Some(600));
+        assert!(config.mempool_persist);
+    }
+
+    #[test]
+    fn test_parse_operator_lane() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(config.operator_addresses.is_empty());
+        assert_eq!(config.operator_quota, None);
+
+        let operator = "aleo1rhgdu77hgyqd3xjj8ucu3jj9r2krwz6mnzyd80gncr5fxcwlh5rsvzp9px";
+        let config =
+            Start::try_parse_from(["snarkos", "--operator-addresses", operator, "--operator-quota", "3"].iter())
+                .unwrap();
+        assert_eq!(config.operator_addresses, vec![operator]);
+        assert_eq!(config.operator_quota, Some(3));
+
+        // Ensure the quota requires the operator addresses.
+        assert!(Start::try_parse_from(["snarkos", "--operator-quota", "3"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_bft_retention_rounds() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.bft_retention_rounds, None);
+        let config = Start::try_parse_from(["snarkos", "--bft-retention-rounds", "1000"].iter()).unwrap();
+        assert_eq!(config.bft_retention_rounds, Some(1000));
+    }
+
+    #[test]
+    fn test_parse_signing_journal() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.signing_journal, None);
+        let config = Start::try_parse_from(["snarkos", "--signing-journal", "/mnt/journal"].iter()).unwrap();
+        assert_eq!(config.signing_journal, Some(PathBuf::from("/mnt/journal")));
+    }
+
+    #[test]
+    fn test_parse_failover() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.failover_instance, None);
+        assert!(!config.standby);
+
+        let config =
+            Start::try_parse_from(["snarkos", "--failover-instance", "standby-b", "--standby"].iter()).unwrap();
+        assert_eq!(config.failover_instance, Some("standby-b".to_string()));
+        assert!(config.standby);
+
+        // Ensure the standby requires the name of the instance.
+        assert!(Start::try_parse_from(["snarkos", "--standby"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_auto_role() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(!config.auto_role);
+        assert!(config.role.is_none());
+
+        let config = Start::try_parse_from(["snarkos", "--validator", "--auto-role"].iter()).unwrap();
+        assert!(config.auto_role);
+        // Ensure a prover never switches roles.
+        assert!(Start::try_parse_from(["snarkos", "--prover", "--auto-role"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_audit_log() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.audit_log, None);
+        assert_eq!(config.audit_log_max_size, 100);
+        assert_eq!(config.audit_log_max_files, 10);
+
+        let config = Start::try_parse_from(
+            [
+                "snarkos",
+                "--audit-log",
+                "/var/log/audit.jsonl",
+                "--audit-log-max-size",
+                "50",
+                "--audit-log-max-files",
+                "3",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        assert_eq!(config.audit_log, Some(PathBuf::from("/var/log/audit.jsonl")));
+        assert_eq!(config.audit_log_max_size, 50);
+        assert_eq!(config.audit_log_max_files, 3);
+        assert!(Start::try_parse_from(["snarkos", "--audit-log-max-files", "0"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_remote_signer() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.remote_signer, None);
+
+        let config = Start::try_parse_from(
+            [
+                "snarkos",
+                "--validator",
+                "--remote-signer",
+                "https://signer:8443",
+                "--remote-signer-ca",
+                "ca.pem",
+                "--remote-signer-cert",
+                "cert.pem",
+                "--remote-signer-key",
+                "key.pem",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        assert_eq!(config.remote_signer, Some("https://signer:8443".to_string()));
+        assert_eq!(config.remote_signer_key, Some(PathBuf::from("key.pem")));
+
+        // Ensure the TLS files are required.
+        assert!(Start::try_parse_from(["snarkos", "--remote-signer", "https://signer:8443"].iter()).is_err());
+        // Ensure the remote signer cannot be combined with a private key.
+        assert!(Start::try_parse_from(
+            [
+                "snarkos",
+                "--remote-signer",
+                "https://signer:8443",
+                "--remote-signer-ca",
+                "ca.pem",
+                "--remote-signer-cert",
+                "cert.pem",
+                "--remote-signer-key",
+                "key.pem",
+                "--private-key",
+                "APrivateKey1",
+            ]
+            .iter()
+        )
+        .is_err());
+
+        // Ensure the remote signer config is parsed, and cannot be combined with a private key.
+        let config =
+            Start::try_parse_from(["snarkos", "--validator", "--remote-signer-config", "signer.json"].iter()).unwrap();
+        assert_eq!(config.remote_signer_config, Some(PathBuf::from("signer.json")));
+        assert!(Start::try_parse_from(
+            ["snarkos", "--remote-signer-config", "signer.json", "--private-key", "APrivateKey1"].iter()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_cuda_devices() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.cuda_devices, None);
+        assert_eq!(config.parse_prover_devices().unwrap(), ProverDevices::default());
+
+        let config = Start::try_parse_from(["snarkos", "--prover", "--cuda-devices", "0,2"].iter()).unwrap();
+        assert_eq!(config.cuda_devices, Some("0,2".to_string()));
+        match cfg!(feature = "cuda") {
+            true => assert_eq!(config.parse_prover_devices().unwrap(), ProverDevices::Cuda(vec![0, 2])),
+            false => assert!(config.parse_prover_devices().is_err()),
+        }
+
+        // Ensure the CUDA devices are only supported by provers.
+        assert!(Start::try_parse_from(["snarkos", "--cuda-devices", "0"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_prover_throttle() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.parse_prover_throttle().unwrap(), ProverThrottle::default());
+
+        let args = ["snarkos", "--prover", "--prover-threads", "4", "--prover-utilization", "50"];
+        let throttle = Start::try_parse_from(args.iter()).unwrap().parse_prover_throttle().unwrap();
+        assert_eq!(throttle.threads(), Some(4));
+        assert_eq!(throttle.utilization(), 50);
+
+        // Ensure the throttle is only supported by provers, and is bounded.
+        assert!(Start::try_parse_from(["snarkos", "--prover-threads", "4"].iter()).is_err());
+        assert!(Start::try_parse_from(["snarkos", "--prover-utilization", "50"].iter()).is_err());
+        let config = Start::try_parse_from(["snarkos", "--prover", "--prover-threads", "0"].iter()).unwrap();
+        assert!(config.parse_prover_throttle().is_err());
+        let config = Start::try_parse_from(["snarkos", "--prover", "--prover-utilization", "101"].iter()).unwrap();
+        assert!(config.parse_prover_throttle().is_err());
+    }
+
+    #[test]
+    fn test_parse_cpu_affinity() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(config.parse_cpu_affinity().unwrap().is_none());
+
+        // Ensure the prover cores are not shared with the networking and ledger threads.
+        if num_cpus::get() >= 2 {
+            let config = Start::try_parse_from(["snarkos", "--prover", "--prover-cores", "0"].iter()).unwrap();
+            let affinity = config.parse_cpu_affinity().unwrap().unwrap();
+            assert_eq!(affinity.prover_cores(), &[0]);
+            assert!(!affinity.other_cores().contains(&0));
+        }
+        let config = Start::try_parse_from(["snarkos", "--prover", "--prover-cores", "100000"].iter()).unwrap();
+        assert!(config.parse_cpu_affinity().is_err());
+
+        // Ensure the affinity is only supported by provers, and is specified with either cores or NUMA nodes.
+        assert!(Start::try_parse_from(["snarkos", "--prover-cores", "0"].iter()).is_err());
+        assert!(Start::try_parse_from(["snarkos", "--prover-numa-nodes", "0"].iter()).is_err());
+        let args = ["snarkos", "--prover", "--prover-cores", "0", "--prover-numa-nodes", "0"];
+        assert!(Start::try_parse_from(args.iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_pool() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(config.parse_pool::<CurrentNetwork>().unwrap().is_none());
+
+        let config = Start::try_parse_from(
+            ["snarkos", "--prover", "--pool", "tcp://pool.example.com:4140", "--worker", "rig-1"].iter(),
+        )
+        .unwrap();
+        let pool = config.parse_pool::<CurrentNetwork>().unwrap().unwrap();
+        assert_eq!(pool.server(), "pool.example.com:4140");
+        assert_eq!(pool.worker(), "rig-1");
+
+        // Ensure the pool is only supported by provers, and requires a worker name.
+        assert!(Start::try_parse_from(["snarkos", "--pool", "pool:4140", "--worker", "rig"].iter()).is_err());
+        assert!(Start::try_parse_from(["snarkos", "--prover", "--pool", "pool.example.com:4140"].iter()).is_err());
+        assert!(Start::try_parse_from(["snarkos", "--worker", "rig"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_headless_prover() {
+        let args = ["snarkos", "--prover", "--dev", "0", "--headless", "http://a:3030, http://b:3030"];
+        let config = Start::try_parse_from(args.iter()).unwrap();
+        let prover = config.parse_headless_prover::<CurrentNetwork>(Default::default()).unwrap();
+        assert_eq!(prover.nodes(), ["http://a:3030", "http://b:3030"]);
+
+        // Ensure the headless mode is only supported by solo provers, and requires an HTTP(S) URL.
+        assert!(Start::try_parse_from(["snarkos", "--headless", "http://a:3030"].iter()).is_err());
+        let args = ["snarkos", "--prover", "--headless", "http://a:3030", "--submit-endpoints", "http://b:3030"];
+        assert!(Start::try_parse_from(args.iter()).is_err());
+        let args = ["snarkos", "--prover", "--dev", "0", "--headless", "a:3030"];
+        let config = Start::try_parse_from(args.iter()).unwrap();
+        assert!(config.parse_headless_prover::<CurrentNetwork>(Default::default()).is_err());
+    }
+
+    #[test]
+    fn test_parse_log_rotation() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(!config.parse_log_rotation().is_enabled());
+
+        let args = ["snarkos", "--log-max-size", "100", "--log-rotation", "daily", "--log-retention", "3"];
+        let config = Start::try_parse_from(args.iter()).unwrap();
+        let rotation = config.parse_log_rotation();
+        assert!(rotation.is_enabled());
+        assert_eq!(rotation.max_size, Some(100 * 1024 * 1024));
+        assert_eq!(rotation.interval, Some(LogRotationInterval::Daily));
+        assert_eq!(rotation.retention, 3);
+
+        // Ensure the maximum size is positive.
+        assert!(Start::try_parse_from(["snarkos", "--log-max-size", "0"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_log_format() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.log_format, LogFormat::Pretty);
+        let config = Start::try_parse_from(["snarkos", "--log-format", "json"].iter()).unwrap();
+        assert_eq!(config.log_format, LogFormat::Json);
+        assert!(Start::try_parse_from(["snarkos", "--log-format", "xml"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_otlp_endpoint() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.otlp_endpoint, None);
+        let config = Start::try_parse_from(["snarkos", "--otlp-endpoint", "http://localhost:4317"].iter()).unwrap();
+        assert_eq!(config.otlp_endpoint.as_deref(), Some("http://localhost:4317"));
+    }
+
+    #[test]
+    fn test_parse_metrics_ip() {
+        let config = Start::try_parse_from(["snarkos", "--metrics", "--metrics-ip", "127.0.0.1:9100"].iter()).unwrap();
+        assert_eq!(config.metrics_ip, Some(SocketAddr::from_str("127.0.0.1:9100").unwrap()));
+        // Ensure the metrics IP requires the metrics.
+        assert!(Start::try_parse_from(["snarkos", "--metrics-ip", "127.0.0.1:9100"].iter()).is_err());
+
+        // Ensure the metrics IP of a development node is offset by its index, unless it is specified.
+        let (mut trusted_peers, mut trusted_validators) = (vec![], vec![]);
+        let mut config = Start::try_parse_from(["snarkos", "--dev", "2", "--metrics"].iter()).unwrap();
+        config.parse_development(&mut trusted_peers, &mut trusted_validators).unwrap();
+        assert_eq!(config.metrics_ip, Some(SocketAddr::from_str("0.0.0.0:9002").unwrap()));
+        let mut config = Start::try_parse_from(["snarkos", "--dev", "2"].iter()).unwrap();
+        config.parse_development(&mut trusted_peers, &mut trusted_validators).unwrap();
+        assert_eq!(config.metrics_ip, None);
+    }
+
+    #[test]
+    fn test_parse_prover_status() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.prover_status, None);
+        let config =
+            Start::try_parse_from(["snarkos", "--prover", "--prover-status", "127.0.0.1:4140"].iter()).unwrap();
+        assert_eq!(config.prover_status, Some(SocketAddr::from(([127, 0, 0, 1], 4140))));
+        // Ensure the status endpoint is only supported by provers.
+        assert!(Start::try_parse_from(["snarkos", "--prover-status", "127.0.0.1:4140"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_submitter() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        let submitter = config.parse_submitter::<CurrentNetwork>().unwrap();
+        assert!(submitter.endpoints().is_empty());
+        assert_eq!(submitter.mode(), SubmitMode::Failover);
+
+        let args = ["snarkos", "--prover", "--submit-endpoints", "http://a:3030,http://b:3030", "--submit-mode", "all"];
+        let submitter = Start::try_parse_from(args.iter()).unwrap().parse_submitter::<CurrentNetwork>().unwrap();
+        assert_eq!(submitter.endpoints(), ["http://a:3030", "http://b:3030"]);
+        assert_eq!(submitter.mode(), SubmitMode::All);
+
+        // Ensure the submission endpoints are only supported by solo provers, and the mode is valid.
+        assert!(Start::try_parse_from(["snarkos", "--submit-endpoints", "http://a:3030"].iter()).is_err());
+        let args =
+            ["snarkos", "--prover", "--submit-endpoints", "http://a:3030", "--pool", "pool:4140", "--worker", "rig"];
+        assert!(Start::try_parse_from(args.iter()).is_err());
+        let config = Start::try_parse_from(["snarkos", "--submit-mode", "first"].iter()).unwrap();
+        assert!(config.parse_submitter::<CurrentNetwork>().is_err());
+    }
+
+    #[test]
+    fn test_parse_pool_server() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(config.parse_pool_server().unwrap().is_none());
+
+        let config = Start::try_parse_from(["snarkos", "--client", "--pool-server", "0.0.0.0:4140"].iter()).unwrap();
+        let pool = config.parse_pool_server().unwrap().unwrap();
+        assert_eq!(pool.listen(), SocketAddr::from(([0, 0, 0, 0], 4140)));
+        assert_eq!(pool.share_divisor(), PoolConfig::DEFAULT_SHARE_DIVISOR);
+
+        let args = ["snarkos", "--client", "--pool-server", "0.0.0.0:4140", "--pool-share-divisor", "64"];
+        let config = Start::try_parse_from(args.iter()).unwrap();
+        assert_eq!(config.parse_pool_server().unwrap().unwrap().share_divisor(), 64);
+
+        // Ensure the pool server is only supported by clients, and the share divisor is bounded.
+        assert!(Start::try_parse_from(["snarkos", "--pool-server", "0.0.0.0:4140"].iter()).is_err());
+        assert!(Start::try_parse_from(["snarkos", "--client", "--pool-share-divisor", "64"].iter()).is_err());
+        let args = ["snarkos", "--client", "--pool-server", "0.0.0.0:4140", "--pool-share-divisor", "0"];
+        assert!(Start::try_parse_from(args.iter()).unwrap().parse_pool_server().is_err());
+    }
+
+    #[test]
+    fn test_parse_state_diffs() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.state_diffs, None);
+        let config = Start::try_parse_from(["snarkos", "--state-diffs", "diffs.jsonl"].iter()).unwrap();
+        assert_eq!(config.state_diffs, Some(PathBuf::from("diffs.jsonl")));
+    }
+
+    #[test]
+    fn test_parse_index() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(config.index.is_empty());
+        let config = Start::try_parse_from(["snarkos", "--index", "analytics"].iter()).unwrap();
+        assert_eq!(config.index, vec![IndexKind::Analytics]);
+
+        // Ensure unknown indexes are rejected.
+        assert!(Start::try_parse_from(["snarkos", "--index", "unknown"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_disk_thresholds() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.disk_warning_threshold, 10);
+        assert_eq!(config.disk_shutdown_threshold, None);
+
+        let config = Start::try_parse_from(
+            ["snarkos", "--disk-warning-threshold", "20", "--disk-shutdown-threshold", "5"].iter(),
+        )
+        .unwrap();
+        assert_eq!(config.disk_warning_threshold, 20);
+        assert_eq!(config.disk_shutdown_threshold, Some(5));
+    }
+
+    #[test]
+    fn test_parse_alerts() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(config.alert_webhooks.is_empty());
+        assert_eq!(config.alert_sync_stall, None);
+        assert_eq!(config.alert_min_peers, None);
+
+        let config = Start::try_parse_from(
+            [
+                "snarkos",
+                "--alert-webhooks",
+                "https://hooks.slack.com/services/T0/B0/X,https://alerts.example.com/hook",
+                "--alert-sync-stall",
+                "600",
+                "--alert-min-peers",
+                "5",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        assert_eq!(config.alert_webhooks, vec![
+            "https://hooks.slack.com/services/T0/B0/X".to_string(),
+            "https://alerts.example.com/hook".to_string()
+        ]);
+        assert_eq!(config.alert_sync_stall, Some(600));
+        assert_eq!(config.alert_min_peers, Some(5));
+
+        // Ensure the thresholds require a webhook.
+        assert!(Start::try_parse_from(["snarkos", "--alert-min-peers", "5"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_crash_report_dir() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.crash_report_dir, None);
+
+        let config =
+            Start::try_parse_from(["snarkos", "--crash-report-dir", "/var/lib/snarkos/crashes"].iter()).unwrap();
+        assert_eq!(config.crash_report_dir, Some(PathBuf::from("/var/lib/snarkos/crashes")));
+    }
+
+    #[test]
+    fn test_parse_light() {
+        let config = Start::try_parse_from(["snarkos", "--light", "http://1.2.3.4:3030, http://5.6.7.8:3030"].iter());
+        let client = config.unwrap().parse_light_client::<CurrentNetwork>(Default::default()).unwrap();
+        assert_eq!(client.latest_height(), 0);
+
+        // Ensure the light mode cannot be combined with a node type.
+        assert!(Start::try_parse_from(["snarkos", "--light", "http://1.2.3.4:3030", "--client"].iter()).is_err());
+        // Ensure the light mode requires a source.
+        let config = Start::try_parse_from(["snarkos", "--light", ""].iter()).unwrap();
+        assert!(config.parse_light_client::<CurrentNetwork>(Default::default()).is_err());
+    }
+
+    #[test]
+    fn test_parse_database_options() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.database, DatabaseOptions::default());
+
+        let config =
+            Start::try_parse_from(["snarkos", "--db-profile", "low-memory", "--db-write-buffer", "32"].iter()).unwrap();
+        assert_eq!(config.database.db_profile, DatabaseProfile::LowMemory);
+        assert_eq!(config.database.db_write_buffer, Some(32));
+
+        // Ensure an unknown profile is rejected.
+        assert!(Start::try_parse_from(["snarkos", "--db-profile", "fastest"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_storage_backend() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.storage_backend, StorageBackend::Rocksdb);
+
+        let config = Start::try_parse_from(["snarkos", "--storage-backend", "memory"].iter()).unwrap();
+        assert_eq!(config.storage_backend, StorageBackend::Memory);
+
+        // Ensure an unknown backend is rejected.
+        assert!(Start::try_parse_from(["snarkos", "--storage-backend", "s3"].iter()).is_err());
+
+        // Ensure the compaction schedule is parsed, and a malformed one is rejected.
+        let config = Start::try_parse_from(["snarkos", "--compaction-schedule", "2-5"].iter()).unwrap();
+        assert_eq!(config.compaction_schedule, Some(CompactionSchedule::from_str("2-5").unwrap()));
+        assert!(Start::try_parse_from(["snarkos", "--compaction-schedule", "2-25"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_sync_checkpoint() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(config.parse_sync_checkpoint::<CurrentNetwork>().unwrap().is_none());
+
+        let checkpoint = SyncCheckpoint::<CurrentNetwork>::new(10_000, Default::default()).unwrap();
+        let config = Start::try_parse_from(["snarkos", "--sync-checkpoint", &checkpoint.to_string()].iter()).unwrap();
+        assert_eq!(config.parse_sync_checkpoint::<CurrentNetwork>().unwrap(), Some(checkpoint));
+
+        // Ensure a malformed checkpoint is rejected.
+        let config = Start::try_parse_from(["snarkos", "--sync-checkpoint", "10000"].iter()).unwrap();
+        assert!(config.parse_sync_checkpoint::<CurrentNetwork>().is_err());
+    }
+
+    #[test]
+    fn test_parse_keep_alive() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.parse_keep_alive().unwrap(), KeepAlive::default());
+
+        let args =
+            ["--heartbeat-interval", "10", "--ping-interval", "5", "--idle-timeout", "30", "--tcp-keepalive", "15"];
+        let config = Start::try_parse_from(["snarkos"].iter().chain(args.iter())).unwrap();
+        let keep_alive = config.parse_keep_alive().unwrap();
+        assert_eq!(keep_alive.heartbeat_interval(), Duration::from_secs(10));
+        assert_eq!(keep_alive.ping_interval(), Duration::from_secs(5));
+        assert_eq!(keep_alive.idle_timeout(), Duration::from_secs(30));
+        assert_eq!(keep_alive.tcp_keepalive(), Some(Duration::from_secs(15)));
+
+        // Ensure a peer cannot be considered idle before it is pinged.
+        let config =
+            Start::try_parse_from(["snarkos", "--ping-interval", "60", "--idle-timeout", "30"].iter()).unwrap();
+        assert!(config.parse_keep_alive().is_err());
+    }
+
+    #[test]
+    fn test_parse_accept_rate_limit() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        let limit = config.parse_accept_rate_limit().unwrap().unwrap();
+        assert_eq!(limit.global_per_sec, 50);
+        assert_eq!(limit.per_ip_per_min, 10);
+
+        let args = ["snarkos", "--max-accept-rate", "5", "--max-accept-rate-per-ip", "2"];
+        let limit = Start::try_parse_from(args.iter()).unwrap().parse_accept_rate_limit().unwrap().unwrap();
+        assert_eq!(limit.global_per_sec, 5);
+        assert_eq!(limit.per_ip_per_min, 2);
+
+        // Ensure the rate is not limited in development mode.
+        let config = Start::try_parse_from(["snarkos", "--dev", "0"].iter()).unwrap();
+        assert!(config.parse_accept_rate_limit().unwrap().is_none());
+
+        // Ensure the rates must be positive.
+        let config = Start::try_parse_from(["snarkos", "--max-accept-rate", "0"].iter()).unwrap();
+        assert!(config.parse_accept_rate_limit().is_err());
+    }
+
     #[test]
     fn test_parse_trusted_validators() {
         let config = Start::try_parse_from(["snarkos", "--validators", ""].iter()).unwrap();
@@ -885,6 +2659,32 @@ mod tests {
         assert!(config.parse_cdn().is_none());
     }
 
+    #[test]
+    fn test_parse_bft_workers() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.bft_workers, None);
+        let config = Start::try_parse_from(["snarkos", "--bft-workers", "4"].iter()).unwrap();
+        assert_eq!(config.bft_workers, Some(4));
+    }
+
+    #[test]
+    fn test_parse_port_offset() {
+        // Ensure the default ports are offset, unless they are specified.
+        let args = ["snarkos", "--port-offset", "100", "--rest", "127.0.0.1:8080", "--metrics"];
+        let mut config = Start::try_parse_from(args.iter()).unwrap();
+        config.parse_port_offset().unwrap();
+        assert_eq!(config.metrics_ip, Some(SocketAddr::from_str("0.0.0.0:9100").unwrap()));
+        assert_eq!(config.node, Some(SocketAddr::from_str("0.0.0.0:4230").unwrap()));
+        assert_eq!(config.bft, Some(SocketAddr::from_str("0.0.0.0:5100").unwrap()));
+        assert_eq!(config.rest, Some(SocketAddr::from_str("127.0.0.1:8080").unwrap()));
+
+        // Ensure the ports do not overflow.
+        let mut config = Start::try_parse_from(["snarkos", "--port-offset", "65000"].iter()).unwrap();
+        assert!(config.parse_port_offset().is_err());
+        // Ensure the offset conflicts with the development mode, which offsets the ports by the node index.
+        assert!(Start::try_parse_from(["snarkos", "--port-offset", "100", "--dev", "0"].iter()).is_err());
+    }
+
     #[test]
     fn test_parse_development_and_genesis() {
         let prod_genesis = Block::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
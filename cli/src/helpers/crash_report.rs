@@ -0,0 +1,248 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::{
+    backtrace::Backtrace,
+    collections::VecDeque,
+    fs,
+    io,
+    panic,
+    path::PathBuf,
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+use time::OffsetDateTime;
+
+/// The number of recent heights and rounds that are kept for the crash report.
+const NUM_RECENT_PROGRESS: usize = 12;
+/// The interval at which the height and round of the node are recorded, in seconds.
+const PROGRESS_INTERVAL_IN_SECS: u64 = 10;
+/// The words that mark a flag whose value may be a secret, and is redacted from the crash report.
+const SECRET_FLAG_WORDS: [&str; 6] = ["key", "secret", "token", "password", "jwt", "webhook"];
+
+/// The height and round of the node at a point in time.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Progress {
+    /// The UNIX timestamp of the record.
+    timestamp: i64,
+    /// The latest block height, if the node keeps a ledger.
+    height: Option<u32>,
+    /// The current round of the BFT, if the node is a validator.
+    round: Option<u64>,
+}
+
+impl Progress {
+    /// Returns the progress as JSON.
+    fn to_json(&self) -> Value {
+        json!({ "timestamp": self.timestamp, "height": self.height, "round": self.round })
+    }
+}
+
+/// Writes a crash report into a local file when the node panics, so the context of the crash survives a restart.
+pub struct CrashReporter {
+    /// The directory that the crash reports are written to.
+    directory: PathBuf,
+    /// The command line of the node, with the secrets redacted.
+    command: String,
+    /// The recent heights and rounds of the node, oldest first.
+    recent: Mutex<VecDeque<Progress>>,
+}
+
+impl CrashReporter {
+    /// Initializes a new crash reporter, writing into the given directory, for the given command line.
+    pub fn new(directory: PathBuf, args: impl IntoIterator<Item = String>) -> Self {
+        Self { directory, command: redact_args(args), recent: Default::default() }
+    }
+
+    /// Installs the crash reporter as the panic hook, ahead of the existing one.
+    pub fn install(self) -> Arc<Self> {
+        let reporter = Arc::new(self);
+        let previous_hook = panic::take_hook();
+        let hook_reporter = reporter.clone();
+        panic::set_hook(Box::new(move |info| {
+            // Retrieve the message of the panic.
+            let message = match (info.payload().downcast_ref::<&str>(), info.payload().downcast_ref::<String>()) {
+                (Some(message), _) => message.to_string(),
+                (None, Some(message)) => message.clone(),
+                (None, None) => "Box<dyn Any>".to_string(),
+            };
+            let location = info.location().map(|location| location.to_string()).unwrap_or_default();
+            // Write the crash report, and point to it in the logs and on stderr, as the display may hide the latter.
+            let report = hook_reporter.report(&message, &location, &Backtrace::force_capture());
+            match hook_reporter.write(&report) {
+                Ok(path) => {
+                    tracing::error!("The node panicked - the crash report is written to '{}'", path.display());
+                    eprintln!("\nThe node panicked - the crash report is written to '{}'\n", path.display());
+                }
+                Err(error) => eprintln!("\nThe node panicked, and failed to write the crash report - {error}\n"),
+            }
+            previous_hook(info);
+        }));
+        reporter
+    }
+
+    /// Records the given height and round of the node.
+    pub fn record(&self, height: Option<u32>, round: Option<u64>) {
+        let mut recent = self.recent.lock();
+        if recent.len() == NUM_RECENT_PROGRESS {
+            recent.pop_front();
+        }
+        recent.push_back(Progress { timestamp: OffsetDateTime::now_utc().unix_timestamp(), height, round });
+    }
+
+    /// Starts recording the height and round of the node periodically, with the given probe.
+    pub fn track<F: 'static + Send + Fn() -> (Option<u32>, Option<u64>)>(self: &Arc<Self>, probe: F) {
+        let reporter = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let (height, round) = probe();
+                reporter.record(height, round);
+                tokio::time::sleep(Duration::from_secs(PROGRESS_INTERVAL_IN_SECS)).await;
+            }
+        });
+    }
+
+    /// Returns the crash report for the given panic.
+    fn report(&self, message: &str, location: &str, backtrace: &Backtrace) -> Value {
+        // Note: The lock is only awaited briefly, as the panic may have occurred while it was held.
+        let recent = match self.recent.try_lock_for(Duration::from_millis(100)) {
+            Some(recent) => recent.iter().map(Progress::to_json).collect(),
+            None => vec![],
+        };
+        json!({
+            "timestamp": OffsetDateTime::now_utc().unix_timestamp(),
+            "version": env!("CARGO_PKG_VERSION"),
+            "os": std::env::consts::OS,
+            "arch": std::env::consts::ARCH,
+            "thread": thread::current().name().unwrap_or("<unnamed>"),
+            "message": message,
+            "location": location,
+            "recent_progress": recent,
+            "command": self.command,
+            "backtrace": backtrace.to_string().lines().map(str::trim_end).collect::<Vec<_>>(),
+        })
+    }
+
+    /// Writes the given crash report into a new file, i.e. `snarkos-crash-20240101T000000.000.json`,
+    /// and returns its path.
+    fn write(&self, report: &Value) -> io::Result<PathBuf> {
+        let now = OffsetDateTime::now_utc();
+        let path = self.directory.join(format!(
+            "snarkos-crash-{:04}{:02}{:02}T{:02}{:02}{:02}.{:03}.json",
+            now.year(),
+            u8::from(now.month()),
+            now.day(),
+            now.hour(),
+            now.minute(),
+            now.second(),
+            now.millisecond()
+        ));
+        fs::create_dir_all(&self.directory)?;
+        fs::write(&path, serde_json::to_vec_pretty(report)?)?;
+        Ok(path)
+    }
+}
+
+/// Returns the given command line, with the values of the flags that may be secrets redacted.
+pub fn redact_args(args: impl IntoIterator<Item = String>) -> String {
+    let is_secret = |flag: &str| flag.starts_with('-') && SECRET_FLAG_WORDS.iter().any(|word| flag.contains(word));
+    let mut redacted = Vec::new();
+    let mut redact_next = false;
+    for arg in args {
+        let arg = match arg.split_once('=') {
+            // Redact the value of a secret flag, i.e. `--private-key=<value>`.
+            Some((flag, _)) if is_secret(flag) => format!("{flag}=<redacted>"),
+            // Redact the value following a secret flag, i.e. `--private-key <value>`.
+            _ if redact_next && !arg.starts_with('-') => "<redacted>".to_string(),
+            _ => arg,
+        };
+        redact_next = is_secret(&arg) && !arg.contains('=');
+        redacted.push(arg);
+    }
+    redacted.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::Rng;
+
+    /// Returns the given arguments as a command line.
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_redact_args() {
+        let command = redact_args(args(&[
+            "snarkos",
+            "start",
+            "--validator",
+            "--private-key",
+            "APrivateKey1zkp",
+            "--jwt-secret=c2VjcmV0",
+            "--alert-webhooks",
+            "https://hooks.slack.com/services/T0/B0/X",
+            "--peers",
+            "1.2.3.4:4130",
+        ]));
+        assert_eq!(
+            command,
+            "snarkos start --validator --private-key <redacted> --jwt-secret=<redacted> --alert-webhooks <redacted> \
+             --peers 1.2.3.4:4130"
+        );
+
+        // Ensure a secret flag without a value does not redact the following flag.
+        assert_eq!(redact_args(args(&["snarkos", "--private-key", "--dev", "0"])), "snarkos --private-key --dev 0");
+    }
+
+    #[test]
+    fn test_recent_progress() {
+        let reporter = CrashReporter::new(std::env::temp_dir(), args(&["snarkos"]));
+        for height in 0..20 {
+            reporter.record(Some(height), Some(height as u64 * 2));
+        }
+        // Ensure only the most recent progress is kept.
+        let recent = reporter.recent.lock();
+        assert_eq!(recent.len(), NUM_RECENT_PROGRESS);
+        assert_eq!(recent.front().unwrap().height, Some(8));
+        assert_eq!(recent.back().unwrap().round, Some(38));
+    }
+
+    #[test]
+    fn test_write_report() {
+        let directory = std::env::temp_dir().join(format!("snarkos-crash-{}", rand::thread_rng().gen::<u64>()));
+        let reporter = CrashReporter::new(directory.clone(), args(&["snarkos", "start", "--private-key", "key"]));
+        reporter.record(Some(10), None);
+
+        let report = reporter.report("boom", "node/src/lib.rs:1:1", &Backtrace::force_capture());
+        let path = reporter.write(&report).unwrap();
+        assert!(path.starts_with(&directory));
+
+        // Ensure the crash report is readable, and holds the context of the crash.
+        let report: Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        assert_eq!(report["message"], "boom");
+        assert_eq!(report["location"], "node/src/lib.rs:1:1");
+        assert_eq!(report["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(report["command"], "snarkos start --private-key <redacted>");
+        assert_eq!(report["recent_progress"][0]["height"], 10);
+        assert!(report["recent_progress"][0]["round"].is_null());
+        assert!(report["backtrace"].is_array());
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,207 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    console::network::Network,
+    ledger::store::helpers::rocksdb::internal::{self, Database},
+};
+
+use aleo_std::StorageMode;
+use anyhow::{ensure, Result};
+use clap::{Args, ValueEnum};
+use rocksdb::{BlockBasedOptions, Cache, DBCompactionStyle, DBCompressionType, Options};
+
+/// The number of bytes in a megabyte.
+const MB: usize = 1024 * 1024;
+
+/// A tuning profile of the ledger database.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum DatabaseProfile {
+    /// The defaults of RocksDB.
+    #[default]
+    Default,
+    /// Large caches and write buffers, for servers with plenty of memory.
+    Throughput,
+    /// Small caches and write buffers, for machines with a few GB of memory.
+    LowMemory,
+}
+
+/// The compaction style of the ledger database.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CompactionStyle {
+    /// Leveled compaction, which favors reads and space.
+    Level,
+    /// Universal compaction, which favors writes.
+    Universal,
+}
+
+/// The compression codec of the ledger database.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Compression {
+    /// No compression.
+    None,
+    /// LZ4, which is fast.
+    Lz4,
+    /// Zstandard, which is compact.
+    Zstd,
+}
+
+impl Compression {
+    /// Returns the name of the codec in the RocksDB options.
+    const fn option_value(self) -> &'static str {
+        match self {
+            Self::None => "kNoCompression",
+            Self::Lz4 => "kLZ4Compression",
+            Self::Zstd => "kZSTD",
+        }
+    }
+}
+
+/// The tuning options of the ledger database; the explicit options override the ones of the profile.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Args)]
+pub struct DatabaseOptions {
+    /// Specify the tuning profile of the ledger database
+    #[clap(value_enum, default_value = "default", long = "db-profile")]
+    pub db_profile: DatabaseProfile,
+    /// Specify the size (in MB) of the block cache of the ledger database
+    #[clap(long = "db-block-cache", value_name = "MB", value_parser = clap::value_parser!(u64).range(1..))]
+    pub db_block_cache: Option<u64>,
+    /// Specify the size (in MB) of the write buffer of the ledger database
+    #[clap(long = "db-write-buffer", value_name = "MB", value_parser = clap::value_parser!(u64).range(1..))]
+    pub db_write_buffer: Option<u64>,
+    /// Specify the compaction style of the ledger database
+    #[clap(value_enum, long = "db-compaction-style")]
+    pub db_compaction_style: Option<CompactionStyle>,
+    /// Specify the maximum number of files the ledger database keeps open
+    #[clap(long = "db-max-open-files", value_parser = clap::value_parser!(u32).range(64..))]
+    pub db_max_open_files: Option<u32>,
+    /// Specify the compression codec of the ledger database
+    #[clap(value_enum, long = "db-compression")]
+    pub db_compression: Option<Compression>,
+}
+
+impl DatabaseOptions {
+    /// Returns the options, with the unset ones taken from the profile.
+    pub fn resolve(&self) -> Self {
+        let profile = match self.db_profile {
+            DatabaseProfile::Default => Self::default(),
+            DatabaseProfile::Throughput => Self {
+                db_profile: self.db_profile,
+                db_block_cache: Some(4096),
+                db_write_buffer: Some(256),
+                db_compaction_style: Some(CompactionStyle::Level),
+                db_max_open_files: None,
+                db_compression: Some(Compression::Lz4),
+            },
+            DatabaseProfile::LowMemory => Self {
+                db_profile: self.db_profile,
+                db_block_cache: Some(64),
+                db_write_buffer: Some(16),
+                db_compaction_style: Some(CompactionStyle::Level),
+                db_max_open_files: Some(256),
+                db_compression: Some(Compression::Zstd),
+            },
+        };
+        Self {
+            db_profile: self.db_profile,
+            db_block_cache: self.db_block_cache.or(profile.db_block_cache),
+            db_write_buffer: self.db_write_buffer.or(profile.db_write_buffer),
+            db_compaction_style: self.db_compaction_style.or(profile.db_compaction_style),
+            db_max_open_files: self.db_max_open_files.or(profile.db_max_open_files),
+            db_compression: self.db_compression.or(profile.db_compression),
+        }
+    }
+
+    /// Returns the RocksDB options of a database that snarkOS opens itself.
+    pub fn to_options(&self) -> Options {
+        let resolved = self.resolve();
+        let mut options = Options::default();
+        if let Some(block_cache) = resolved.db_block_cache {
+            let mut table_options = BlockBasedOptions::default();
+            table_options.set_block_cache(&Cache::new_lru_cache(block_cache as usize * MB));
+            options.set_block_based_table_factory(&table_options);
+        }
+        if let Some(write_buffer) = resolved.db_write_buffer {
+            options.set_write_buffer_size(write_buffer as usize * MB);
+        }
+        if let Some(compaction_style) = resolved.db_compaction_style {
+            options.set_compaction_style(match compaction_style {
+                CompactionStyle::Level => DBCompactionStyle::Level,
+                CompactionStyle::Universal => DBCompactionStyle::Universal,
+            });
+        }
+        if let Some(max_open_files) = resolved.db_max_open_files {
+            options.set_max_open_files(max_open_files as i32);
+        }
+        if let Some(compression) = resolved.db_compression {
+            options.set_compression_type(match compression {
+                Compression::None => DBCompressionType::None,
+                Compression::Lz4 => DBCompressionType::Lz4,
+                Compression::Zstd => DBCompressionType::Zstd,
+            });
+        }
+        options
+    }
+
+    /// Applies the options to the ledger database of a running node.
+    /// Note: The ledger of a running node is opened by snarkVM with its own options, so only those that RocksDB
+    /// changes on an open database (the write buffer size and the compression codec) are applied; the block cache,
+    /// the compaction style and the open files limit apply to the databases that snarkOS opens itself,
+    /// i.e. in `snarkos ledger restore`.
+    pub fn apply_to_ledger<N: Network>(&self, storage_mode: StorageMode) -> Result<()> {
+        ensure!(
+            self.db_block_cache.is_none() && self.db_compaction_style.is_none() && self.db_max_open_files.is_none(),
+            "The block cache, compaction style and open files limit of a running ledger cannot be changed"
+        );
+        let resolved = self.resolve();
+        let mut options = Vec::new();
+        if let Some(write_buffer) = resolved.db_write_buffer {
+            options.push(("write_buffer_size", (write_buffer as usize * MB).to_string()));
+        }
+        if let Some(compression) = resolved.db_compression {
+            options.push(("compression", compression.option_value().to_string()));
+        }
+        if options.is_empty() {
+            return Ok(());
+        }
+        // Note: The database is opened once per process, so this is the same instance that the ledger uses.
+        let database = internal::RocksDB::open(N::ID, storage_mode)?;
+        let options = options.iter().map(|(key, value)| (*key, value.as_str())).collect::<Vec<_>>();
+        Ok(database.set_options(&options)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        // Ensure the default profile leaves every option to RocksDB.
+        assert_eq!(DatabaseOptions::default().resolve(), DatabaseOptions::default());
+
+        // Ensure a profile fills in the unset options, and the explicit ones take precedence.
+        let options = DatabaseOptions {
+            db_profile: DatabaseProfile::LowMemory,
+            db_block_cache: Some(128),
+            db_compression: Some(Compression::None),
+            ..Default::default()
+        };
+        let resolved = options.resolve();
+        assert_eq!(resolved.db_block_cache, Some(128));
+        assert_eq!(resolved.db_write_buffer, Some(16));
+        assert_eq!(resolved.db_max_open_files, Some(256));
+        assert_eq!(resolved.db_compression, Some(Compression::None));
+    }
+}
@@ -0,0 +1,224 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{bail, ensure, Result};
+use std::path::Path;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// The environment variable holding the ledger encryption key, as hex.
+pub const LEDGER_KEY_ENV: &str = "SNARKOS_LEDGER_KEY";
+/// The service of the ledger encryption key in the OS keyring.
+const KEYRING_SERVICE: &str = "snarkos";
+/// The user of the ledger encryption key in the OS keyring.
+const KEYRING_USER: &str = "ledger";
+/// The size of the ledger encryption key, as required by AES-256-XTS.
+const KEY_SIZE: usize = 64;
+
+/// The key of the encrypted ledger directory.
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct LedgerKey([u8; KEY_SIZE]);
+
+impl LedgerKey {
+    /// Loads the key from the `SNARKOS_LEDGER_KEY` environment variable, or else from the OS keyring.
+    /// The variable is removed once it is read, so the key is not inherited by child processes.
+    pub fn load() -> Result<Self> {
+        let mut hex = match std::env::var(LEDGER_KEY_ENV) {
+            Ok(hex) => {
+                std::env::remove_var(LEDGER_KEY_ENV);
+                hex
+            }
+            Err(_) => match keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER).and_then(|entry| entry.get_password()) {
+                Ok(hex) => hex,
+                Err(error) => bail!(
+                    "Failed to load the ledger key from '{LEDGER_KEY_ENV}' or the OS keyring \
+                     (service '{KEYRING_SERVICE}', user '{KEYRING_USER}') - {error}"
+                ),
+            },
+        };
+        let key = Self::from_hex(hex.trim());
+        hex.zeroize();
+        key
+    }
+
+    /// Parses the key from the given hex string.
+    fn from_hex(hex: &str) -> Result<Self> {
+        ensure!(hex.len() == 2 * KEY_SIZE, "The ledger key must be {KEY_SIZE} bytes, i.e. {} hex digits", 2 * KEY_SIZE);
+        ensure!(hex.bytes().all(|digit| digit.is_ascii_hexdigit()), "The ledger key is not valid hex");
+        let mut key = Self([0u8; KEY_SIZE]);
+        for (byte, digits) in key.0.iter_mut().zip(hex.as_bytes().chunks(2)) {
+            *byte = u8::from_str_radix(std::str::from_utf8(digits)?, 16)?;
+        }
+        Ok(key)
+    }
+}
+
+/// Unlocks the given ledger directory with the given key, and encrypts it if it does not exist yet.
+///
+/// The directory is encrypted by the filesystem (`fscrypt`, e.g. on ext4 or f2fs), which covers the ledger
+/// database and the BFT storage within it, without relying on full-disk encryption. The key is added to
+/// the keyring of the filesystem, so the directory stays unlocked until a reboot, or until the key is removed
+/// (e.g. with `fscryptctl remove_key`). Note: An existing ledger cannot be encrypted in place, but can be
+/// moved into an encrypted directory with `snarkos ledger backup` and `snarkos ledger restore --encrypt`.
+pub fn unlock_ledger_dir(dir: &Path, key: &LedgerKey) -> Result<()> {
+    #[cfg(target_os = "linux")]
+    {
+        fscrypt::unlock_dir(dir, key)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = key;
+        bail!("Failed to encrypt {} - the ledger can only be encrypted on Linux", dir.display())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod fscrypt {
+    use super::{LedgerKey, KEY_SIZE};
+
+    use anyhow::{bail, Result};
+    use nix::errno::Errno;
+    use std::{fs, fs::File, os::fd::AsRawFd, path::Path};
+    use zeroize::Zeroize;
+
+    /// The size of a key identifier.
+    const IDENTIFIER_SIZE: usize = 16;
+    /// The version of the encryption policies.
+    const POLICY_V2: u8 = 2;
+    /// The encryption mode of the file contents.
+    const MODE_AES_256_XTS: u8 = 1;
+    /// The encryption mode of the file names.
+    const MODE_AES_256_CTS: u8 = 4;
+    /// The padding of the file names to 32 bytes.
+    const POLICY_FLAGS_PAD_32: u8 = 0x03;
+    /// The type of the key specifiers that refer to a key by its identifier.
+    const KEY_SPEC_TYPE_IDENTIFIER: u32 = 2;
+
+    /// The encryption policy of a directory (`struct fscrypt_policy_v2`).
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct PolicyV2 {
+        version: u8,
+        contents_encryption_mode: u8,
+        filenames_encryption_mode: u8,
+        flags: u8,
+        reserved: [u8; 4],
+        master_key_identifier: [u8; IDENTIFIER_SIZE],
+    }
+
+    /// The argument of `FS_IOC_GET_ENCRYPTION_POLICY_EX` (`struct fscrypt_get_policy_ex_arg`).
+    #[repr(C)]
+    pub struct GetPolicyExArg {
+        policy_size: u64,
+        policy: PolicyV2,
+    }
+
+    /// The argument of `FS_IOC_ADD_ENCRYPTION_KEY` (`struct fscrypt_add_key_arg`), followed by the raw key.
+    #[repr(C)]
+    #[derive(Zeroize)]
+    pub struct AddKeyArg {
+        key_spec_type: u32,
+        key_spec_reserved: u32,
+        key_spec_identifier: [u8; 32],
+        raw_size: u32,
+        key_id: u32,
+        reserved: [u32; 8],
+        raw: [u8; KEY_SIZE],
+    }
+
+    nix::ioctl_write_ptr_bad!(set_encryption_policy, nix::request_code_read!(b'f', 19, 12), PolicyV2);
+    nix::ioctl_readwrite_bad!(get_encryption_policy_ex, nix::request_code_readwrite!(b'f', 22, 9), GetPolicyExArg);
+    nix::ioctl_readwrite_bad!(
+        add_encryption_key,
+        nix::request_code_readwrite!(b'f', 23, std::mem::size_of::<AddKeyArg>() - KEY_SIZE),
+        AddKeyArg
+    );
+
+    /// Unlocks the given directory with the given key, and encrypts it if it does not exist yet.
+    pub(super) fn unlock_dir(dir: &Path, key: &LedgerKey) -> Result<()> {
+        // Add the key to the keyring of the filesystem, through the parent directory.
+        let Some(parent) = dir.parent() else { bail!("Failed to encrypt {} - it has no parent", dir.display()) };
+        fs::create_dir_all(parent)?;
+        let identifier = add_key(&File::open(parent)?, key)
+            .map_err(|error| anyhow::anyhow!("Failed to add the ledger key to the filesystem - {error}"))?;
+
+        // Encrypt a new directory, which must still be empty.
+        if !dir.exists() {
+            fs::create_dir(dir)?;
+            let policy = PolicyV2 {
+                version: POLICY_V2,
+                contents_encryption_mode: MODE_AES_256_XTS,
+                filenames_encryption_mode: MODE_AES_256_CTS,
+                flags: POLICY_FLAGS_PAD_32,
+                master_key_identifier: identifier,
+                ..Default::default()
+            };
+            // Safety: The policy is a valid `struct fscrypt_policy_v2`, which outlives the call.
+            if let Err(error) = unsafe { set_encryption_policy(File::open(dir)?.as_raw_fd(), &policy) } {
+                let _ = fs::remove_dir(dir);
+                bail!("Failed to encrypt {} - {error} (does the filesystem support encryption?)", dir.display());
+            }
+            return Ok(());
+        }
+
+        // Ensure an existing directory is encrypted with the given key.
+        let mut arg =
+            GetPolicyExArg { policy_size: std::mem::size_of::<PolicyV2>() as u64, policy: Default::default() };
+        // Safety: The argument is a valid `struct fscrypt_get_policy_ex_arg`, which outlives the call.
+        match unsafe { get_encryption_policy_ex(File::open(dir)?.as_raw_fd(), &mut arg) } {
+            Ok(_) if arg.policy.version == POLICY_V2 && arg.policy.master_key_identifier == identifier => Ok(()),
+            Ok(_) => bail!("{} is encrypted with a different key", dir.display()),
+            Err(Errno::ENODATA) => bail!(
+                "{} is not encrypted - move the ledger into an encrypted directory with \
+                 `snarkos ledger backup` and `snarkos ledger restore --encrypt`",
+                dir.display()
+            ),
+            Err(error) => bail!("Failed to read the encryption policy of {} - {error}", dir.display()),
+        }
+    }
+
+    /// Adds the given key to the keyring of the filesystem of the given file, and returns its identifier.
+    fn add_key(file: &File, key: &LedgerKey) -> Result<[u8; IDENTIFIER_SIZE], Errno> {
+        let mut arg = AddKeyArg {
+            key_spec_type: KEY_SPEC_TYPE_IDENTIFIER,
+            key_spec_reserved: 0,
+            key_spec_identifier: [0; 32],
+            raw_size: KEY_SIZE as u32,
+            key_id: 0,
+            reserved: [0; 8],
+            raw: key.0,
+        };
+        // Safety: The argument is a valid `struct fscrypt_add_key_arg` followed by the raw key,
+        // and outlives the call.
+        let result = unsafe { add_encryption_key(file.as_raw_fd(), &mut arg) };
+        let mut identifier = [0; IDENTIFIER_SIZE];
+        identifier.copy_from_slice(&arg.key_spec_identifier[..IDENTIFIER_SIZE]);
+        arg.zeroize();
+        result.map(|_| identifier)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ledger_key_from_hex() {
+        let hex = "ab".repeat(KEY_SIZE);
+        assert_eq!(LedgerKey::from_hex(&hex).unwrap().0, [0xab; KEY_SIZE]);
+
+        // Ensure the keys of the wrong size or with invalid digits are rejected.
+        assert!(LedgerKey::from_hex(&hex[2..]).is_err());
+        assert!(LedgerKey::from_hex(&"zz".repeat(KEY_SIZE)).is_err());
+    }
+}
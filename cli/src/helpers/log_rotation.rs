@@ -0,0 +1,309 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap::ValueEnum;
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use time::OffsetDateTime;
+
+/// The zstd compression level of the rotated logfiles.
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// The interval at which the logfile is rotated.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum LogRotationInterval {
+    /// At the start of every hour (UTC).
+    Hourly,
+    /// At the start of every day (UTC).
+    Daily,
+}
+
+impl LogRotationInterval {
+    /// Returns the length of the interval in seconds.
+    const fn as_secs(&self) -> u64 {
+        match self {
+            Self::Hourly => 3600,
+            Self::Daily => 86400,
+        }
+    }
+
+    /// Returns the UNIX timestamp (in seconds) of the first rotation after the given timestamp.
+    const fn next_rotation(&self, timestamp: u64) -> u64 {
+        (timestamp / self.as_secs() + 1) * self.as_secs()
+    }
+}
+
+/// The rotation policy of the logfile.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LogRotation {
+    /// The size (in bytes) above which the logfile is rotated, if any.
+    pub max_size: Option<u64>,
+    /// The interval at which the logfile is rotated, if any.
+    pub interval: Option<LogRotationInterval>,
+    /// The number of rotated logfiles to keep.
+    pub retention: usize,
+}
+
+impl LogRotation {
+    /// Returns `true` if the logfile is rotated.
+    pub const fn is_enabled(&self) -> bool {
+        self.max_size.is_some() || self.interval.is_some()
+    }
+}
+
+/// A logfile that is rotated by size and/or time.
+///
+/// A rotated logfile is renamed to `<logfile>.<timestamp>`, compressed with zstd in the background,
+/// and the oldest rotated logfiles beyond the retention are removed.
+pub struct RotatingLogFile {
+    /// The path of the logfile.
+    path: PathBuf,
+    /// The rotation policy.
+    rotation: LogRotation,
+    /// The open logfile.
+    file: File,
+    /// The size (in bytes) of the open logfile.
+    size: u64,
+    /// The UNIX timestamp (in seconds) of the next time-based rotation, if any.
+    next_rotation: Option<u64>,
+}
+
+impl RotatingLogFile {
+    /// Opens (or creates) the logfile at the given path, which is rotated with the given policy.
+    pub fn open<P: AsRef<Path>>(path: P, rotation: LogRotation) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = Self::open_file(&path)?;
+        let size = file.metadata()?.len();
+        let next_rotation = rotation.interval.map(|interval| interval.next_rotation(now()));
+        Ok(Self { path, rotation, file, size, next_rotation })
+    }
+
+    /// Opens the logfile at the given path in append mode.
+    fn open_file(path: &Path) -> io::Result<File> {
+        File::options().append(true).create(true).open(path)
+    }
+
+    /// Returns `true` if the logfile must be rotated before writing the given number of bytes.
+    fn must_rotate(&self, num_bytes: usize) -> bool {
+        // Never rotate an empty logfile.
+        if self.size == 0 {
+            return false;
+        }
+        let exceeds_size = self.rotation.max_size.map_or(false, |max_size| self.size + num_bytes as u64 > max_size);
+        let is_due = self.next_rotation.map_or(false, |next_rotation| now() >= next_rotation);
+        exceeds_size || is_due
+    }
+
+    /// Moves the logfile aside, reopens it, and compresses and prunes the rotated logfiles in the background.
+    fn rotate(&mut self) -> io::Result<()> {
+        // Reset the triggers first, so a failed rotation is not retried on every write.
+        self.size = 0;
+        self.next_rotation = self.rotation.interval.map(|interval| interval.next_rotation(now()));
+
+        // Move the logfile aside, and reopen it.
+        self.file.flush()?;
+        let rotated_path = Self::rotated_path(&self.path);
+        fs::rename(&self.path, &rotated_path)?;
+        self.file = Self::open_file(&self.path)?;
+        self.size = self.file.metadata()?.len();
+
+        // Compress the rotated logfile, and remove the oldest ones beyond the retention.
+        let (path, retention) = (self.path.clone(), self.rotation.retention);
+        std::thread::spawn(move || {
+            if let Err(error) = compress(&rotated_path) {
+                eprintln!("Failed to compress the rotated logfile '{}': {error}", rotated_path.display());
+            }
+            if let Err(error) = prune(&path, retention) {
+                eprintln!("Failed to remove the old logfiles of '{}': {error}", path.display());
+            }
+        });
+        Ok(())
+    }
+
+    /// Returns the path of the logfile, rotated at the current time, i.e. `snarkos.log.20240101T000000.000`.
+    fn rotated_path(path: &Path) -> PathBuf {
+        let now = OffsetDateTime::now_utc();
+        let mut rotated_path = path.as_os_str().to_owned();
+        rotated_path.push(format!(
+            ".{:04}{:02}{:02}T{:02}{:02}{:02}.{:03}",
+            now.year(),
+            u8::from(now.month()),
+            now.day(),
+            now.hour(),
+            now.minute(),
+            now.second(),
+            now.millisecond()
+        ));
+        rotated_path.into()
+    }
+}
+
+impl Write for RotatingLogFile {
+    /// Writes the given buffer into the logfile, rotating it first, if needed.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.must_rotate(buf.len()) {
+            // Keep writing into the current logfile, if the rotation fails.
+            if let Err(error) = self.rotate() {
+                eprintln!("Failed to rotate the logfile '{}': {error}", self.path.display());
+            }
+        }
+        let num_bytes = self.file.write(buf)?;
+        self.size += num_bytes as u64;
+        Ok(num_bytes)
+    }
+
+    /// Flushes the logfile.
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Returns the current UNIX timestamp (in seconds).
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs())
+}
+
+/// Compresses the given file into `<file>.zst`, and removes the original.
+fn compress(path: &Path) -> io::Result<()> {
+    let mut compressed_path = path.as_os_str().to_owned();
+    compressed_path.push(".zst");
+    zstd::stream::copy_encode(File::open(path)?, File::create(&compressed_path)?, COMPRESSION_LEVEL)?;
+    fs::remove_file(path)
+}
+
+/// Removes the oldest rotated logfiles of the given logfile, keeping the latest `retention` ones.
+fn prune(path: &Path, retention: usize) -> io::Result<()> {
+    let (Some(directory), Some(file_name)) = (path.parent(), path.file_name()) else {
+        return Ok(());
+    };
+    // Note: The rotated logfiles are named `<logfile>.<timestamp>[.zst]`, so they are sorted by age.
+    let prefix = format!("{}.", file_name.to_string_lossy());
+    let mut rotated_paths = Vec::new();
+    for entry in fs::read_dir(if directory.as_os_str().is_empty() { Path::new(".") } else { directory })? {
+        let entry = entry?;
+        if entry.file_name().to_str().map_or(false, |file_name| is_rotated(file_name, &prefix)) {
+            rotated_paths.push(entry.path());
+        }
+    }
+    rotated_paths.sort();
+    let num_removed = rotated_paths.len().saturating_sub(retention);
+    for rotated_path in &rotated_paths[..num_removed] {
+        fs::remove_file(rotated_path)?;
+    }
+    Ok(())
+}
+
+/// Returns `true` if the given file name is a rotated logfile with the given prefix.
+fn is_rotated(file_name: &str, prefix: &str) -> bool {
+    file_name.strip_prefix(prefix).and_then(|suffix| suffix.chars().next()).map_or(false, |c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::Rng;
+
+    /// Returns a logfile path in a new temporary directory.
+    fn sample_logfile() -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("snarkos-logs-{}", rand::thread_rng().gen::<u64>()));
+        fs::create_dir_all(&directory).unwrap();
+        directory.join("snarkos.log")
+    }
+
+    /// Returns the sorted file names in the directory of the given logfile.
+    fn file_names(path: &Path) -> Vec<String> {
+        let mut file_names: Vec<_> = fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        file_names.sort();
+        file_names
+    }
+
+    #[test]
+    fn test_next_rotation() {
+        assert_eq!(LogRotationInterval::Hourly.next_rotation(0), 3600);
+        assert_eq!(LogRotationInterval::Hourly.next_rotation(3599), 3600);
+        assert_eq!(LogRotationInterval::Hourly.next_rotation(3600), 7200);
+        assert_eq!(LogRotationInterval::Daily.next_rotation(86399), 86400);
+    }
+
+    #[test]
+    fn test_rotate_by_size() {
+        let path = sample_logfile();
+        let rotation = LogRotation { max_size: Some(10), interval: None, retention: 5 };
+        let mut logfile = RotatingLogFile::open(&path, rotation).unwrap();
+
+        // Ensure the logfile is not rotated below the maximum size.
+        logfile.write_all(b"0123456789").unwrap();
+        assert_eq!(file_names(&path), vec!["snarkos.log"]);
+
+        // Ensure the logfile is rotated before exceeding the maximum size.
+        logfile.write_all(b"abc").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"abc");
+
+        // Ensure the rotated logfile is compressed in the background.
+        for _ in 0..50 {
+            if file_names(&path).len() == 2 && file_names(&path)[1].ends_with(".zst") {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        let file_names = file_names(&path);
+        assert_eq!(file_names.len(), 2);
+        assert!(file_names[1].starts_with("snarkos.log.2") && file_names[1].ends_with(".zst"));
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_compress() {
+        let path = sample_logfile();
+        fs::write(&path, b"hello world").unwrap();
+        compress(&path).unwrap();
+
+        // Ensure the original is replaced by the compressed file.
+        assert_eq!(file_names(&path), vec!["snarkos.log.zst"]);
+        let compressed = fs::read(path.with_file_name("snarkos.log.zst")).unwrap();
+        assert_eq!(zstd::decode_all(&compressed[..]).unwrap(), b"hello world");
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_prune() {
+        let path = sample_logfile();
+        fs::write(&path, b"").unwrap();
+        fs::write(path.with_file_name("snarkos.log.20240101T000000.000.zst"), b"").unwrap();
+        fs::write(path.with_file_name("snarkos.log.20240102T000000.000.zst"), b"").unwrap();
+        fs::write(path.with_file_name("snarkos.log.20240103T000000.000"), b"").unwrap();
+        fs::write(path.with_file_name("snarkos.log.backup"), b"").unwrap();
+
+        // Ensure only the oldest rotated logfiles are removed.
+        prune(&path, 2).unwrap();
+        assert_eq!(file_names(&path), vec![
+            "snarkos.log",
+            "snarkos.log.20240102T000000.000.zst",
+            "snarkos.log.20240103T000000.000",
+            "snarkos.log.backup"
+        ]);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
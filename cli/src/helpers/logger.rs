@@ -12,22 +12,41 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::helpers::{DynamicFormatter, LogWriter};
+use crate::helpers::{DynamicFormatter, LogRotation, LogWriter, RotatingLogFile};
+use snarkos_node::set_exit_hook;
+use snarkos_node_rest::{set_log_filter_controller, LogFilter, LogFilterController, LogFilterRequest};
 
+use anyhow::{bail, Result};
+use clap::ValueEnum;
 use crossterm::tty::IsTty;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{runtime, trace::Tracer, Resource};
 use std::{
-    fs::File,
     io,
     path::Path,
-    sync::{atomic::AtomicBool, Arc},
+    sync::{atomic::AtomicBool, Arc, Mutex},
 };
 use tokio::sync::mpsc;
+use tracing::Level;
 use tracing_subscriber::{
+    filter::{filter_fn, Directive},
     layer::{Layer, SubscriberExt},
+    reload,
     util::SubscriberInitExt,
     EnvFilter,
 };
 
+/// The format of the logs written to stdout and to the logfile.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable lines.
+    #[default]
+    Pretty,
+    /// One JSON object per line, with the timestamp, level, target, fields, and enclosing spans of the event.
+    Json,
+}
+
 /// Initializes the logger.
 ///
 /// ```ignore
@@ -39,60 +58,32 @@ use tracing_subscriber::{
 /// 5 => info, debug, trace, snarkos_node_router=trace
 /// 6 => info, debug, trace, snarkos_node_tcp=trace
 /// ```
+///
+/// If the log rotation is enabled, the logfile is rotated by size and/or time, and the rotated logfiles are compressed.
+/// If the log format is JSON, the fields of the event and of its spans (i.e. `height`, `peer`, `round`)
+/// are emitted as JSON fields.
+/// The filters of the stdout and the logfile can be changed at runtime, through the `node/logLevel` endpoint.
+/// If an OTLP endpoint is given, the spans are also exported to it, regardless of the verbosity,
+/// and the pending spans are exported once the node shuts down.
+/// Note: The OTLP exporter must be initialized within a Tokio runtime.
 pub fn initialize_logger<P: AsRef<Path>>(
     verbosity: u8,
     nodisplay: bool,
     logfile: P,
+    log_rotation: LogRotation,
+    log_format: LogFormat,
+    otlp_endpoint: Option<&str>,
     shutdown: Arc<AtomicBool>,
-) -> mpsc::Receiver<Vec<u8>> {
+) -> Result<mpsc::Receiver<Vec<u8>>> {
     match verbosity {
         0 => std::env::set_var("RUST_LOG", "info"),
         1 => std::env::set_var("RUST_LOG", "debug"),
         2.. => std::env::set_var("RUST_LOG", "trace"),
     };
 
-    // Filter out undesirable logs. (unfortunately EnvFilter cannot be cloned)
-    let [filter, filter2] = std::array::from_fn(|_| {
-        let filter = EnvFilter::from_default_env()
-            .add_directive("mio=off".parse().unwrap())
-            .add_directive("tokio_util=off".parse().unwrap())
-            .add_directive("hyper=off".parse().unwrap())
-            .add_directive("reqwest=off".parse().unwrap())
-            .add_directive("want=off".parse().unwrap())
-            .add_directive("warp=off".parse().unwrap());
-
-        let filter = if verbosity >= 2 {
-            filter.add_directive("snarkos_node_sync=trace".parse().unwrap())
-        } else {
-            filter.add_directive("snarkos_node_sync=debug".parse().unwrap())
-        };
-
-        let filter = if verbosity >= 3 {
-            filter
-                .add_directive("snarkos_node_bft=trace".parse().unwrap())
-                .add_directive("snarkos_node_bft::gateway=debug".parse().unwrap())
-        } else {
-            filter.add_directive("snarkos_node_bft=debug".parse().unwrap())
-        };
-
-        let filter = if verbosity >= 4 {
-            filter.add_directive("snarkos_node_bft::gateway=trace".parse().unwrap())
-        } else {
-            filter.add_directive("snarkos_node_bft::gateway=debug".parse().unwrap())
-        };
-
-        let filter = if verbosity >= 5 {
-            filter.add_directive("snarkos_node_router=trace".parse().unwrap())
-        } else {
-            filter.add_directive("snarkos_node_router=debug".parse().unwrap())
-        };
-
-        if verbosity >= 6 {
-            filter.add_directive("snarkos_node_tcp=trace".parse().unwrap())
-        } else {
-            filter.add_directive("snarkos_node_tcp=off".parse().unwrap())
-        }
-    });
+    // Filter out undesirable logs, and make the filters reloadable. (unfortunately EnvFilter cannot be cloned)
+    let (filter, filter_handle) = reload::Layer::new(build_filter(verbosity));
+    let (filter2, filter2_handle) = reload::Layer::new(build_filter(verbosity));
 
     // Create the directories tree for a logfile if it doesn't exist.
     let logfile_dir = logfile.as_ref().parent().expect("Root directory passed as a logfile");
@@ -100,9 +91,9 @@ pub fn initialize_logger<P: AsRef<Path>>(
         std::fs::create_dir_all(logfile_dir)
             .expect("Failed to create a directories: '{logfile_dir}', please check if user has permissions");
     }
-    // Create a file to write logs to.
+    // Create a file to write logs to, which is rotated with the given policy.
     let logfile =
-        File::options().append(true).create(true).open(logfile).expect("Failed to open the file for writing logs");
+        Mutex::new(RotatingLogFile::open(logfile, log_rotation).expect("Failed to open the file for writing logs"));
 
     // Initialize the log channel.
     let (log_sender, log_receiver) = mpsc::channel(1024);
@@ -113,28 +104,183 @@ pub fn initialize_logger<P: AsRef<Path>>(
         false => Some(log_sender),
     };
 
+    // Initialize the OTLP exporter, which exports the spans of the node and of the REST server.
+    let otlp_layer = match otlp_endpoint {
+        Some(endpoint) => {
+            let otlp_filter = filter_fn(|metadata| {
+                let target = metadata.target();
+                (target.starts_with("snarkos") || target.starts_with("tower_http")) && *metadata.level() <= Level::INFO
+            });
+            let tracer = initialize_otlp_tracer(endpoint)?;
+            // Export the pending spans before the process exits.
+            set_exit_hook(opentelemetry::global::shutdown_tracer_provider)?;
+            Some(tracing_opentelemetry::layer().with_tracer(tracer).with_filter(otlp_filter))
+        }
+        None => None,
+    };
+
     // Initialize tracing.
     let _ = tracing_subscriber::registry()
-        .with(
+        .with(match log_format {
             // Add layer using LogWriter for stdout / terminal
-            tracing_subscriber::fmt::Layer::default()
+            LogFormat::Pretty => tracing_subscriber::fmt::Layer::default()
                 .with_ansi(log_sender.is_none() && io::stdout().is_tty())
                 .with_writer(move || LogWriter::new(&log_sender))
                 .with_target(verbosity > 2)
                 .event_format(DynamicFormatter::new(shutdown))
-                .with_filter(filter),
-        )
-        .with(
+                .with_filter(filter)
+                .boxed(),
+            // Add layer writing JSON logs to stdout / terminal
+            LogFormat::Json => tracing_subscriber::fmt::Layer::default()
+                .json()
+                .flatten_event(true)
+                .with_span_list(true)
+                .with_writer(move || LogWriter::new(&log_sender))
+                .with_filter(filter)
+                .boxed(),
+        })
+        .with(match log_format {
             // Add layer redirecting logs to the file
-            tracing_subscriber::fmt::Layer::default()
+            LogFormat::Pretty => tracing_subscriber::fmt::Layer::default()
                 .with_ansi(false)
                 .with_writer(logfile)
                 .with_target(verbosity > 2)
-                .with_filter(filter2),
-        )
+                .with_filter(filter2)
+                .boxed(),
+            // Add layer redirecting JSON logs to the file
+            LogFormat::Json => tracing_subscriber::fmt::Layer::default()
+                .json()
+                .flatten_event(true)
+                .with_span_list(true)
+                .with_writer(logfile)
+                .with_filter(filter2)
+                .boxed(),
+        })
+        .with(otlp_layer)
         .try_init();
 
-    log_receiver
+    // Register the filters, so they can be changed at runtime through the REST server.
+    set_log_filter_controller(LogFilters {
+        verbosity,
+        directives: Default::default(),
+        reload: Box::new(move |filter, filter2| {
+            filter_handle.reload(filter)?;
+            filter2_handle.reload(filter2)?;
+            Ok(())
+        }),
+    })?;
+
+    Ok(log_receiver)
+}
+
+/// The filters of the logger, which are changed at runtime through the REST server.
+struct LogFilters {
+    /// The verbosity of the logger.
+    verbosity: u8,
+    /// The filter directives that were applied at runtime.
+    directives: parking_lot::Mutex<Vec<String>>,
+    /// Reloads the filters of the stdout and the logfile layers.
+    reload: Box<dyn Fn(EnvFilter, EnvFilter) -> Result<()> + Send + Sync>,
+}
+
+impl LogFilters {
+    /// Returns the filter of the logger for the given directives, which are applied on top of the verbosity.
+    fn filter(&self, directives: &[String]) -> Result<EnvFilter> {
+        let mut filter = build_filter(self.verbosity);
+        for directive in directives {
+            filter = filter.add_directive(directive.parse()?);
+        }
+        Ok(filter)
+    }
+}
+
+impl LogFilterController for LogFilters {
+    /// Returns the log filter.
+    fn log_filter(&self) -> LogFilter {
+        let directives = self.directives.lock().clone();
+        let filter = self.filter(&directives).map(|filter| filter.to_string()).unwrap_or_default();
+        LogFilter { filter, directives }
+    }
+
+    /// Applies the given directives, replacing the previous ones for the same targets, and reloads the filters.
+    fn set_log_filter(&self, request: &LogFilterRequest) -> Result<LogFilter> {
+        let mut directives = self.directives.lock();
+        let mut new_directives = if request.reset { Vec::new() } else { directives.clone() };
+        for directive in &request.directives {
+            // Ensure the directive is valid, i.e. `snarkos_node_bft=debug`.
+            if let Err(error) = directive.parse::<Directive>() {
+                bail!("Invalid log directive '{directive}' - {error}");
+            }
+            new_directives.retain(|previous| directive_target(previous) != directive_target(directive));
+            new_directives.push(directive.clone());
+        }
+        // Reload the filters.
+        (self.reload)(self.filter(&new_directives)?, self.filter(&new_directives)?)?;
+        *directives = new_directives;
+        Ok(LogFilter { filter: self.filter(&directives)?.to_string(), directives: directives.clone() })
+    }
+}
+
+/// Returns the target of the given directive, i.e. `snarkos_node_bft` for `snarkos_node_bft=debug`.
+fn directive_target(directive: &str) -> &str {
+    directive.split_once('=').map_or("", |(target, _)| target)
+}
+
+/// Returns the filter of the logger for the given verbosity, which filters out the undesirable logs.
+fn build_filter(verbosity: u8) -> EnvFilter {
+    let filter = EnvFilter::from_default_env()
+        .add_directive("mio=off".parse().unwrap())
+        .add_directive("tokio_util=off".parse().unwrap())
+        .add_directive("hyper=off".parse().unwrap())
+        .add_directive("reqwest=off".parse().unwrap())
+        .add_directive("want=off".parse().unwrap())
+        .add_directive("warp=off".parse().unwrap());
+
+    let filter = if verbosity >= 2 {
+        filter.add_directive("snarkos_node_sync=trace".parse().unwrap())
+    } else {
+        filter.add_directive("snarkos_node_sync=debug".parse().unwrap())
+    };
+
+    let filter = if verbosity >= 3 {
+        filter
+            .add_directive("snarkos_node_bft=trace".parse().unwrap())
+            .add_directive("snarkos_node_bft::gateway=debug".parse().unwrap())
+    } else {
+        filter.add_directive("snarkos_node_bft=debug".parse().unwrap())
+    };
+
+    let filter = if verbosity >= 4 {
+        filter.add_directive("snarkos_node_bft::gateway=trace".parse().unwrap())
+    } else {
+        filter.add_directive("snarkos_node_bft::gateway=debug".parse().unwrap())
+    };
+
+    let filter = if verbosity >= 5 {
+        filter.add_directive("snarkos_node_router=trace".parse().unwrap())
+    } else {
+        filter.add_directive("snarkos_node_router=debug".parse().unwrap())
+    };
+
+    if verbosity >= 6 {
+        filter.add_directive("snarkos_node_tcp=trace".parse().unwrap())
+    } else {
+        filter.add_directive("snarkos_node_tcp=off".parse().unwrap())
+    }
+}
+
+/// Returns a tracer that exports the spans in batches to the given OTLP (gRPC) endpoint, i.e. `http://localhost:4317`.
+fn initialize_otlp_tracer(endpoint: &str) -> Result<Tracer> {
+    let exporter = opentelemetry_otlp::new_exporter().tonic().with_endpoint(endpoint);
+    let config = opentelemetry_sdk::trace::config().with_resource(Resource::new([
+        KeyValue::new("service.name", "snarkos"),
+        KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+    ]));
+    Ok(opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(exporter)
+        .with_trace_config(config)
+        .install_batch(runtime::Tokio)?)
 }
 
 /// Returns the welcome message as a string.
@@ -162,3 +308,47 @@ pub fn welcome_message() -> String {
     output += &"👋 Welcome to Aleo! We thank you for running a node and supporting privacy.\n".bold();
     output
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the filters of a logger, which are not installed.
+    fn sample_log_filters() -> LogFilters {
+        LogFilters { verbosity: 1, directives: Default::default(), reload: Box::new(|_, _| Ok(())) }
+    }
+
+    #[test]
+    fn test_set_log_filter() {
+        let log_filters = sample_log_filters();
+        let request = |directives: &[&str], reset| LogFilterRequest {
+            directives: directives.iter().map(|directive| directive.to_string()).collect(),
+            reset,
+        };
+
+        // Apply a directive.
+        let log_filter = log_filters.set_log_filter(&request(&["snarkos_node_bft=trace"], false)).unwrap();
+        assert_eq!(log_filter.directives, vec!["snarkos_node_bft=trace"]);
+        assert!(log_filter.filter.contains("snarkos_node_bft=trace"));
+
+        // Ensure a directive for the same target replaces the previous one.
+        let log_filter =
+            log_filters.set_log_filter(&request(&["snarkos_node_sync=trace", "snarkos_node_bft=info"], false)).unwrap();
+        assert_eq!(log_filter.directives, vec!["snarkos_node_sync=trace", "snarkos_node_bft=info"]);
+
+        // Ensure an invalid directive is rejected, and leaves the filter unchanged.
+        assert!(log_filters.set_log_filter(&request(&["snarkos_node_bft=loud"], false)).is_err());
+        assert_eq!(log_filters.log_filter().directives, vec!["snarkos_node_sync=trace", "snarkos_node_bft=info"]);
+
+        // Ensure the directives are discarded on reset.
+        let log_filter = log_filters.set_log_filter(&request(&[], true)).unwrap();
+        assert!(log_filter.directives.is_empty());
+    }
+
+    #[test]
+    fn test_directive_target() {
+        assert_eq!(directive_target("snarkos_node_bft=debug"), "snarkos_node_bft");
+        assert_eq!(directive_target("snarkos_node_bft::gateway=trace"), "snarkos_node_bft::gateway");
+        assert_eq!(directive_target("debug"), "");
+    }
+}
@@ -15,6 +15,18 @@
 mod bech32m;
 pub use bech32m::*;
 
+mod crash_report;
+pub use crash_report::*;
+
+mod database;
+pub use database::*;
+
+mod encryption;
+pub use encryption::*;
+
+mod log_rotation;
+pub use log_rotation::*;
+
 mod log_writer;
 use log_writer::*;
 
@@ -21,7 +21,10 @@ mod tabs;
 use tabs::Tabs;
 
 use snarkos_node::Node;
-use snarkvm::prelude::Network;
+use snarkvm::prelude::{
+    store::{helpers::rocksdb::ConsensusDB, ConsensusStorage},
+    Network,
+};
 
 use anyhow::Result;
 use crossterm::{
@@ -45,9 +48,9 @@ use std::{
 };
 use tokio::sync::mpsc::Receiver;
 
-pub struct Display<N: Network> {
+pub struct Display<N: Network, C: ConsensusStorage<N> = ConsensusDB<N>> {
     /// An instance of the node.
-    node: Node<N>,
+    node: Node<N, C>,
     /// The tick rate of the display.
     tick_rate: Duration,
     /// The state of the tabs.
@@ -56,9 +59,9 @@ pub struct Display<N: Network> {
     logs: Logs,
 }
 
-impl<N: Network> Display<N> {
+impl<N: Network, C: ConsensusStorage<N>> Display<N, C> {
     /// Initializes a new display.
-    pub fn start(node: Node<N>, log_receiver: Receiver<Vec<u8>>) -> Result<()> {
+    pub fn start(node: Node<N, C>, log_receiver: Receiver<Vec<u8>>) -> Result<()> {
         // Initialize the display.
         enable_raw_mode()?;
         let mut stdout = io::stdout();
@@ -91,7 +94,7 @@ impl<N: Network> Display<N> {
     }
 }
 
-impl<N: Network> Display<N> {
+impl<N: Network, C: ConsensusStorage<N>> Display<N, C> {
     /// Renders the display.
     fn render<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> io::Result<()> {
         let mut last_tick = Instant::now();
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use snarkos_node::Node;
-use snarkvm::prelude::Network;
+use snarkvm::prelude::{store::ConsensusStorage, Network};
 
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
@@ -26,7 +26,7 @@ use ratatui::{
 pub(crate) struct Overview;
 
 impl Overview {
-    pub(crate) fn draw<N: Network>(&self, f: &mut Frame, area: Rect, _node: &Node<N>) {
+    pub(crate) fn draw<N: Network, C: ConsensusStorage<N>>(&self, f: &mut Frame, area: Rect, _node: &Node<N, C>) {
         // Initialize the layout of the page.
         let chunks = Layout::default()
             .direction(Direction::Vertical)
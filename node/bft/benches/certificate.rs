@@ -0,0 +1,58 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_bft::helpers::VerifiedCertificates;
+use snarkvm::{
+    console::{account::PrivateKey, network::MainnetV0},
+    ledger::narwhal::{batch_header::test_helpers::sample_batch_header, BatchCertificate, Data},
+    prelude::{FromBytes, TestRng, ToBytes},
+};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use indexmap::IndexSet;
+
+type CurrentNetwork = MainnetV0;
+
+/// The number of signatures in the sampled batch certificate, as in a large committee.
+const NUM_SIGNATURES: usize = 100;
+
+/// Compares deserializing a batch certificate received from a peer, which verifies all of its signatures,
+/// with deserializing the same certificate again, as when it is re-broadcast in the primary pings.
+fn deserialize_certificate(c: &mut Criterion) {
+    let rng = &mut TestRng::default();
+    let batch_header = sample_batch_header(rng);
+    let batch_id = batch_header.batch_id();
+    let signatures = (0..NUM_SIGNATURES)
+        .map(|_| PrivateKey::<CurrentNetwork>::new(rng).unwrap().sign(&[batch_id], rng).unwrap())
+        .collect::<IndexSet<_>>();
+    let certificate = BatchCertificate::from(batch_header, signatures).unwrap();
+    let bytes = certificate.to_bytes_le().unwrap();
+
+    c.bench_function("certificate_read_le", |b| {
+        b.iter(|| BatchCertificate::<CurrentNetwork>::from_bytes_le(&bytes).unwrap())
+    });
+
+    let verified = VerifiedCertificates::<CurrentNetwork>::default();
+    verified.deserialize(Data::Buffer(bytes.clone().into()), |_| None).unwrap();
+    c.bench_function("certificate_verified", |b| {
+        b.iter(|| verified.deserialize(Data::Buffer(bytes.clone().into()), |_| Some(certificate.clone())).unwrap())
+    });
+}
+
+criterion_group! {
+    name = certificate;
+    config = Criterion::default().sample_size(10);
+    targets = deserialize_certificate
+}
+criterion_main!(certificate);
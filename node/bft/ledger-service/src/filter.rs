@@ -0,0 +1,304 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::{block::Block, narwhal::TransmissionID, store::ConsensusStorage, Ledger},
+    prelude::{Field, Network},
+};
+
+use parking_lot::{Mutex, RwLock};
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// The target false positive rate of the existence filters.
+const FALSE_POSITIVE_RATE: f64 = 0.001;
+/// The minimum number of items the existence filters are sized for.
+const MINIMUM_CAPACITY: usize = 1 << 16;
+
+/// A Bloom filter, which answers "definitely not present" for the items that were never inserted.
+pub struct BloomFilter {
+    /// The bits of the filter.
+    bits: Vec<u64>,
+    /// The number of bits set per item.
+    num_hashes: u64,
+    /// The number of items inserted into the filter.
+    num_items: usize,
+    /// The number of items the filter is sized for.
+    capacity: usize,
+}
+
+impl BloomFilter {
+    /// Initializes a filter sized for the given number of items, at the given false positive rate.
+    pub fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        let num_items = capacity.max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let num_bits = (-num_items * false_positive_rate.ln() / (ln2 * ln2)).ceil().max(64.0) as usize;
+        let num_hashes = (num_bits as f64 / num_items * ln2).round().clamp(1.0, 16.0) as u64;
+        Self { bits: vec![0; num_bits.div_ceil(64)], num_hashes, num_items: 0, capacity }
+    }
+
+    /// Initializes a filter with the given items, and room for as many more.
+    fn with_items<T: Hash>(items: &[T]) -> Self {
+        let mut filter = Self::new((2 * items.len()).max(MINIMUM_CAPACITY), FALSE_POSITIVE_RATE);
+        items.iter().for_each(|item| filter.insert(item));
+        filter
+    }
+
+    /// Returns `true` if more items were inserted than the filter is sized for.
+    pub fn is_full(&self) -> bool {
+        self.num_items > self.capacity
+    }
+
+    /// Inserts the given item.
+    pub fn insert<T: Hash>(&mut self, item: &T) {
+        for index in self.indices(item) {
+            self.bits[index / 64] |= 1 << (index % 64);
+        }
+        self.num_items += 1;
+    }
+
+    /// Returns `false` if the given item was definitely never inserted.
+    pub fn may_contain<T: Hash>(&self, item: &T) -> bool {
+        self.indices(item).all(|index| self.bits[index / 64] & (1 << (index % 64)) != 0)
+    }
+
+    /// Returns the bit indices of the given item, using double hashing.
+    fn indices<T: Hash>(&self, item: &T) -> impl Iterator<Item = usize> {
+        let mut hasher = DefaultHasher::new();
+        item.hash(&mut hasher);
+        let h1 = hasher.finish();
+        hasher.write_u8(u8::MAX);
+        let h2 = hasher.finish() | 1;
+        let num_bits = self.bits.len() as u64 * 64;
+        (0..self.num_hashes).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % num_bits) as usize)
+    }
+}
+
+/// The existence filters, as of a given block height.
+struct FilterState {
+    /// The filter of the confirmed transaction IDs.
+    transaction_ids: BloomFilter,
+    /// The filter of the confirmed solution IDs.
+    solution_ids: BloomFilter,
+    /// The filter of the serial numbers of the spent records.
+    serial_numbers: BloomFilter,
+    /// The filter of the commitments of the created records.
+    commitments: BloomFilter,
+    /// The height of the next block to insert.
+    next_height: u32,
+}
+
+impl FilterState {
+    /// Inserts the IDs, serial numbers and commitments of the given block.
+    fn insert_block<N: Network>(&mut self, block: &Block<N>) {
+        for transaction in block.transactions().iter() {
+            self.transaction_ids.insert(&transaction.id());
+            transaction.serial_numbers().for_each(|serial_number| self.serial_numbers.insert(serial_number));
+            transaction.commitments().for_each(|commitment| self.commitments.insert(commitment));
+        }
+        block.solutions().solution_ids().for_each(|solution_id| self.solution_ids.insert(solution_id));
+        self.next_height = block.height() + 1;
+    }
+
+    /// Returns `true` if any of the filters outgrew its capacity.
+    fn is_full(&self) -> bool {
+        [&self.transaction_ids, &self.solution_ids, &self.serial_numbers, &self.commitments]
+            .iter()
+            .any(|filter| filter.is_full())
+    }
+}
+
+/// Probabilistic filters of the transaction IDs, solution IDs, serial numbers and commitments in the ledger,
+/// which answer the common "not present" case of the existence checks without a disk read.
+///
+/// Note: The filters sit in front of the checks made by this service, i.e. the transmission existence checks
+/// of the workers and the primary, and the double spend checks that run before a transaction or block is verified.
+/// They do not reduce the reads of `Ledger::check_transaction_basic` and `Ledger::check_next_block`,
+/// which look up the same items in the ledger storage again. What they save is the reads of the former checks,
+/// and the proof verification of the transactions and blocks they reject early. The savings are reported
+/// by the `existence_filter_hits` and `existence_filter_misses` metrics.
+pub struct ExistenceFilter<N: Network> {
+    /// The filters, once they are built.
+    state: RwLock<Option<FilterState>>,
+    /// The blocks added to the ledger while the filters are being built.
+    pending: Mutex<Vec<Block<N>>>,
+    /// Whether the filters are being built.
+    is_building: AtomicBool,
+}
+
+impl<N: Network> Default for ExistenceFilter<N> {
+    /// Initializes the filters, which are not built yet.
+    fn default() -> Self {
+        Self { state: Default::default(), pending: Default::default(), is_building: Default::default() }
+    }
+}
+
+impl<N: Network> ExistenceFilter<N> {
+    /// Returns `true` if the filters are built.
+    pub fn is_ready(&self) -> bool {
+        self.state.read().is_some()
+    }
+
+    /// Builds the filters from the ledger, unless they are built or being built already.
+    pub fn build<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>) {
+        if self.is_ready() || self.is_building.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        self.build_inner(ledger);
+        self.is_building.store(false, Ordering::Release);
+    }
+
+    /// Builds the filters from the ID indices of the ledger, which are retained by the pruned nodes,
+    /// unlike the full blocks.
+    fn build_inner<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>) {
+        // Note: The blocks added from here on may be missing from the indices, and are buffered by `advance`.
+        let next_height = ledger.latest_height() + 1;
+        // Collect the items first, to size the filters for them.
+        let transaction_ids: Vec<N::TransactionID> =
+            ledger.vm().transaction_store().transaction_ids().map(|id| *id).collect();
+        let solution_ids: Vec<_> = ledger.vm().block_store().solution_ids().map(|id| *id).collect();
+        let serial_numbers: Vec<Field<N>> = ledger.vm().transition_store().serial_numbers().map(|sn| *sn).collect();
+        let commitments: Vec<Field<N>> = ledger.vm().transition_store().commitments().map(|cm| *cm).collect();
+        let mut filters = FilterState {
+            transaction_ids: BloomFilter::with_items(&transaction_ids),
+            solution_ids: BloomFilter::with_items(&solution_ids),
+            serial_numbers: BloomFilter::with_items(&serial_numbers),
+            commitments: BloomFilter::with_items(&commitments),
+            next_height,
+        };
+
+        // Insert the blocks that were added meanwhile, and track the next ones from here on.
+        let mut state = self.state.write();
+        let mut pending = std::mem::take(&mut *self.pending.lock());
+        pending.sort_unstable_by_key(|block| block.height());
+        for block in pending.iter().filter(|block| block.height() == filters.next_height) {
+            filters.insert_block(block);
+        }
+        *state = Some(filters);
+    }
+
+    /// Inserts the items of the given block, which was just added to the ledger.
+    ///
+    /// If the block does not follow the filters, or they outgrew their capacity, they are dropped to be rebuilt.
+    pub fn advance(&self, block: &Block<N>) {
+        let mut state = self.state.write();
+        match state.as_mut() {
+            Some(filters) if filters.next_height == block.height() => {
+                filters.insert_block(block);
+                if filters.is_full() {
+                    *state = None;
+                }
+            }
+            Some(_) => *state = None,
+            None if self.is_building.load(Ordering::Acquire) => self.pending.lock().push(block.clone()),
+            None => (),
+        }
+    }
+
+    /// Returns `Some(false)` if the given transmission is definitely not confirmed in the ledger,
+    /// or `None` if the ledger has to be checked.
+    ///
+    /// Note: The rejected and aborted transaction IDs are not covered, as the ledger does not index them.
+    pub fn contains_transmission<C: ConsensusStorage<N>>(
+        &self,
+        ledger: &Ledger<N, C>,
+        transmission_id: &TransmissionID<N>,
+    ) -> Option<bool> {
+        self.check(ledger, |filters| match transmission_id {
+            TransmissionID::Ratification => true,
+            TransmissionID::Solution(solution_id) => filters.solution_ids.may_contain(solution_id),
+            TransmissionID::Transaction(transaction_id) => filters.transaction_ids.may_contain(transaction_id),
+        })
+    }
+
+    /// Returns `Some(false)` if the given serial number is definitely not in the ledger,
+    /// or `None` if the ledger has to be checked.
+    pub fn contains_serial_number<C: ConsensusStorage<N>>(
+        &self,
+        ledger: &Ledger<N, C>,
+        serial_number: &Field<N>,
+    ) -> Option<bool> {
+        self.check(ledger, |filters| filters.serial_numbers.may_contain(serial_number))
+    }
+
+    /// Returns `Some(false)` if the given commitment is definitely not in the ledger,
+    /// or `None` if the ledger has to be checked.
+    pub fn contains_commitment<C: ConsensusStorage<N>>(
+        &self,
+        ledger: &Ledger<N, C>,
+        commitment: &Field<N>,
+    ) -> Option<bool> {
+        self.check(ledger, |filters| filters.commitments.may_contain(commitment))
+    }
+
+    /// Returns `Some(false)` if the given filter query rules out the item, or `None` if the ledger has to be checked,
+    /// including while the filters are not built or not up to date with the ledger.
+    fn check<C: ConsensusStorage<N>>(
+        &self,
+        ledger: &Ledger<N, C>,
+        may_contain: impl FnOnce(&FilterState) -> bool,
+    ) -> Option<bool> {
+        let state = self.state.read();
+        let is_ruled_out = match state.as_ref() {
+            Some(filters) if filters.next_height == ledger.latest_height() + 1 => !may_contain(filters),
+            _ => false,
+        };
+        // Count the disk reads that were skipped, and the ones that were not.
+        #[cfg(feature = "metrics")]
+        match is_ruled_out {
+            true => metrics::increment_counter(metrics::bft::EXISTENCE_FILTER_HITS),
+            false => metrics::increment_counter(metrics::bft::EXISTENCE_FILTER_MISSES),
+        }
+        match is_ruled_out {
+            true => Some(false),
+            false => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bloom_filter() {
+        let mut filter = BloomFilter::new(1000, FALSE_POSITIVE_RATE);
+        for item in 0..1000u64 {
+            filter.insert(&item);
+        }
+
+        // Ensure there are no false negatives.
+        assert!((0..1000u64).all(|item| filter.may_contain(&item)));
+        // Ensure the false positive rate is close to the target.
+        let num_false_positives = (1000..101_000u64).filter(|item| filter.may_contain(item)).count();
+        assert!(num_false_positives < 300, "Found {num_false_positives} false positives");
+    }
+
+    #[test]
+    fn test_bloom_filter_capacity() {
+        let items = (0..1000u64).collect::<Vec<_>>();
+        let mut filter = BloomFilter::with_items(&items);
+        assert!(items.iter().all(|item| filter.may_contain(item)));
+        assert!(!filter.is_full());
+
+        // Ensure the filter reports when it outgrew its capacity.
+        for item in 1000..MINIMUM_CAPACITY as u64 + 1 {
+            filter.insert(&item);
+        }
+        assert!(filter.is_full());
+    }
+}
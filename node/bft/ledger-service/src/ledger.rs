@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{fmt_id, spawn_blocking, LedgerService};
+use crate::{fmt_id, spawn_blocking, ExistenceFilter, LedgerService};
 use snarkvm::{
     ledger::{
         block::{Block, Transaction},
@@ -22,12 +22,13 @@ use snarkvm::{
         store::ConsensusStorage,
         Ledger,
     },
-    prelude::{bail, Address, Field, FromBytes, Network, Result},
+    prelude::{bail, Address, Field, FinalizeGlobalState, FromBytes, Network, Result},
 };
 
 use indexmap::IndexMap;
 use lru::LruCache;
 use parking_lot::{Mutex, RwLock};
+use rayon::prelude::*;
 use std::{
     fmt,
     io::Read,
@@ -41,12 +42,17 @@ use std::{
 /// The capacity of the LRU holding the recently queried committees.
 const COMMITTEE_CACHE_SIZE: usize = 16;
 
+/// A callback invoked with each block the ledger advances to.
+type AdvanceHook<N> = Box<dyn Fn(&Block<N>) + Send + Sync>;
+
 /// A core ledger service.
 #[allow(clippy::type_complexity)]
 pub struct CoreLedgerService<N: Network, C: ConsensusStorage<N>> {
     ledger: Ledger<N, C>,
     committee_cache: Arc<Mutex<LruCache<u64, Committee<N>>>>,
     latest_leader: Arc<RwLock<Option<(u64, Address<N>)>>>,
+    existence_filter: Arc<ExistenceFilter<N>>,
+    advance_hooks: Arc<RwLock<Vec<AdvanceHook<N>>>>,
     shutdown: Arc<AtomicBool>,
 }
 
@@ -54,7 +60,53 @@ impl<N: Network, C: ConsensusStorage<N>> CoreLedgerService<N, C> {
     /// Initializes a new core ledger service.
     pub fn new(ledger: Ledger<N, C>, shutdown: Arc<AtomicBool>) -> Self {
         let committee_cache = Arc::new(Mutex::new(LruCache::new(COMMITTEE_CACHE_SIZE.try_into().unwrap())));
-        Self { ledger, committee_cache, latest_leader: Default::default(), shutdown }
+        let service = Self {
+            ledger,
+            committee_cache,
+            latest_leader: Default::default(),
+            existence_filter: Default::default(),
+            advance_hooks: Default::default(),
+            shutdown,
+        };
+        // Build the existence filters in the background; until then, the existence checks read from disk.
+        service.build_existence_filter();
+        service
+    }
+
+    /// Builds the existence filters on a separate thread, unless they are built or being built already.
+    fn build_existence_filter(&self) {
+        if self.existence_filter.is_ready() {
+            return;
+        }
+        let ledger = self.ledger.clone();
+        let existence_filter = self.existence_filter.clone();
+        std::thread::spawn(move || existence_filter.build(&ledger));
+    }
+
+    /// Registers the given callback, which is invoked with each block the ledger advances to,
+    /// before the ledger advances to the next block.
+    pub fn on_advance(&self, hook: impl Fn(&Block<N>) + Send + Sync + 'static) {
+        self.advance_hooks.write().push(Box::new(hook));
+    }
+
+    /// Ensures the serial numbers of the given transaction are not spent and its commitments are new,
+    /// skipping the disk reads for the ones the existence filters rule out.
+    fn ensure_transaction_is_unspent(&self, transaction: &Transaction<N>) -> Result<()> {
+        for serial_number in transaction.serial_numbers() {
+            if self.existence_filter.contains_serial_number(&self.ledger, serial_number).is_none()
+                && self.ledger.contains_serial_number(serial_number)?
+            {
+                bail!("Transaction '{}' spends an existing serial number", fmt_id(transaction.id()));
+            }
+        }
+        for commitment in transaction.commitments() {
+            if self.existence_filter.contains_commitment(&self.ledger, commitment).is_none()
+                && self.ledger.contains_commitment(commitment)?
+            {
+                bail!("Transaction '{}' creates an existing commitment", fmt_id(transaction.id()));
+            }
+        }
+        Ok(())
     }
 }
 
@@ -182,18 +234,8 @@ impl<N: Network, C: ConsensusStorage<N>> LedgerService<N> for CoreLedgerService<
 
     /// Returns the committee lookback for the given round.
     fn get_committee_lookback_for_round(&self, round: u64) -> Result<Committee<N>> {
-        // Get the round number for the previous committee. Note, we subtract 2 from odd rounds,
-        // because committees are updated in even rounds.
-        let previous_round = match round % 2 == 0 {
-            true => round.saturating_sub(1),
-            false => round.saturating_sub(2),
-        };
-
-        // Get the committee lookback round.
-        let committee_lookback_round = previous_round.saturating_sub(Committee::<N>::COMMITTEE_LOOKBACK_RANGE);
-
         // Retrieve the committee for the committee lookback round.
-        self.get_committee_for_round(committee_lookback_round)
+        self.get_committee_for_round(crate::committee_lookback_round::<N>(round))
     }
 
     /// Returns `true` if the ledger contains the given certificate ID in block history.
@@ -203,6 +245,16 @@ impl<N: Network, C: ConsensusStorage<N>> LedgerService<N> for CoreLedgerService<
 
     /// Returns `true` if the transmission exists in the ledger.
     fn contains_transmission(&self, transmission_id: &TransmissionID<N>) -> Result<bool> {
+        // Skip the disk read if the existence filters rule out the transmission.
+        if self.existence_filter.contains_transmission(&self.ledger, transmission_id) == Some(false) {
+            return match transmission_id {
+                // Note: The existence filters do not cover the rejected and aborted transaction IDs.
+                TransmissionID::Transaction(transaction_id) => {
+                    self.ledger.vm().block_store().contains_rejected_or_aborted_transaction_id(transaction_id)
+                }
+                _ => Ok(false),
+            };
+        }
         match transmission_id {
             TransmissionID::Ratification => Ok(false),
             TransmissionID::Solution(solution_id) => self.ledger.contains_solution_id(solution_id),
@@ -310,14 +362,75 @@ impl<N: Network, C: ConsensusStorage<N>> LedgerService<N> for CoreLedgerService<
         if transaction.is_fee() {
             bail!("Invalid transaction - 'Transaction::fee' type is not valid at this stage ({})", transaction.id());
         }
+        // Reject the double spends before verifying the transaction.
+        self.ensure_transaction_is_unspent(&transaction)?;
         // Check the transaction is well-formed.
         let ledger = self.ledger.clone();
         spawn_blocking!(ledger.check_transaction_basic(&transaction, None, &mut rand::thread_rng()))
     }
 
+    /// Speculates the finalize execution of the given transaction against the latest state,
+    /// and returns an error if the transaction would be rejected or aborted.
+    async fn check_transaction_speculative(&self, transaction: Transaction<N>) -> Result<()> {
+        let ledger = self.ledger.clone();
+        spawn_blocking!({
+            // Construct the global state of the next block.
+            let latest_block = ledger.latest_block();
+            let state = FinalizeGlobalState::new::<N>(
+                latest_block.round().saturating_add(1),
+                latest_block.height().saturating_add(1),
+                latest_block.cumulative_weight(),
+                latest_block.cumulative_proof_target(),
+                latest_block.hash(),
+            )?;
+            // Speculate the transaction on its own.
+            // Note: The speculation does not modify the state, as it is rolled back.
+            let (_, confirmed_transactions, aborted_transaction_ids, _) = ledger.vm().speculate(
+                state,
+                N::BLOCK_TIME as i64,
+                None,
+                vec![],
+                &None.into(),
+                [&transaction].into_iter(),
+                &mut rand::thread_rng(),
+            )?;
+            // Ensure the transaction is not aborted.
+            if !aborted_transaction_ids.is_empty() {
+                bail!("Transaction '{}' would be aborted", fmt_id(transaction.id()));
+            }
+            // Ensure the transaction is not rejected.
+            if confirmed_transactions.iter().any(|confirmed| confirmed.is_rejected()) {
+                bail!("Transaction '{}' would be rejected", fmt_id(transaction.id()));
+            }
+            Ok(())
+        })
+    }
+
     /// Checks the given block is valid next block.
     fn check_next_block(&self, block: &Block<N>) -> Result<()> {
-        self.ledger.check_next_block(block, &mut rand::thread_rng())
+        #[cfg(feature = "metrics")]
+        let start = std::time::Instant::now();
+        // Reject the double spends before verifying the block.
+        for transaction in block.transactions().iter() {
+            self.ensure_transaction_is_unspent(transaction)?;
+        }
+        self.ledger.check_next_block(block, &mut rand::thread_rng())?;
+        #[cfg(feature = "metrics")]
+        metrics::histogram(metrics::blocks::VERIFICATION_LATENCY, start.elapsed().as_secs_f64());
+        Ok(())
+    }
+
+    /// Verifies the transactions of the given block, ahead of checking it as the next block.
+    ///
+    /// The transactions are verified as one batch, spread over the cores, and the VM caches the ones it verified,
+    /// so `check_next_block` does not verify their proofs again. Note: snarkVM has no verifier that aggregates
+    /// the proofs of separate transactions, so each proof is still verified on its own.
+    fn verify_transactions(&self, block: &Block<N>) -> Result<()> {
+        let transactions = block.transactions().iter().collect::<Vec<_>>();
+        transactions.into_par_iter().try_for_each(|transaction| {
+            let rejected_id = transaction.to_rejected_id()?;
+            self.ledger.check_transaction_basic(transaction.transaction(), rejected_id, &mut rand::thread_rng())
+        })
     }
 
     /// Returns a candidate for the next block in the ledger, using a committed subdag and its transmissions.
@@ -337,8 +450,17 @@ impl<N: Network, C: ConsensusStorage<N>> LedgerService<N> for CoreLedgerService<
         if self.shutdown.load(Ordering::Relaxed) {
             bail!("Skipping advancing to block {} - The node is shutting down", block.height());
         }
-        // Advance to the next block.
+        // Advance to the next block, which finalizes its transactions.
+        #[cfg(feature = "metrics")]
+        let start = std::time::Instant::now();
         self.ledger.advance_to_next_block(block)?;
+        #[cfg(feature = "metrics")]
+        metrics::histogram(metrics::blocks::FINALIZE_LATENCY, start.elapsed().as_secs_f64());
+        // Update the existence filters, and rebuild them if they were dropped.
+        self.existence_filter.advance(block);
+        self.build_existence_filter();
+        // Invoke the callbacks, while the ledger is still at the block.
+        self.advance_hooks.read().iter().for_each(|hook| hook(block));
         // Update BFT metrics.
         #[cfg(feature = "metrics")]
         {
@@ -17,6 +17,11 @@
 #[macro_use]
 extern crate async_trait;
 
+#[cfg(feature = "ledger")]
+pub mod filter;
+#[cfg(feature = "ledger")]
+pub use filter::*;
+
 #[cfg(feature = "ledger")]
 pub mod ledger;
 #[cfg(feature = "ledger")]
@@ -50,6 +55,18 @@ pub fn fmt_id(id: impl ToString) -> String {
     formatted_id
 }
 
+/// Returns the round of the committee lookback for the given round.
+pub fn committee_lookback_round<N: snarkvm::prelude::Network>(round: u64) -> u64 {
+    // Get the round number for the previous committee. Note, we subtract 2 from odd rounds,
+    // because committees are updated in even rounds.
+    let previous_round = match round % 2 == 0 {
+        true => round.saturating_sub(1),
+        false => round.saturating_sub(2),
+    };
+    // Get the committee lookback round.
+    previous_round.saturating_sub(snarkvm::ledger::committee::Committee::<N>::COMMITTEE_LOOKBACK_RANGE)
+}
+
 /// A helper macro to spawn a blocking task.
 #[macro_export]
 macro_rules! spawn_blocking {
@@ -199,11 +199,22 @@ impl<N: Network> LedgerService<N> for MockLedgerService<N> {
         Ok(())
     }
 
+    /// Speculates the finalize execution of the given transaction.
+    async fn check_transaction_speculative(&self, transaction: Transaction<N>) -> Result<()> {
+        trace!("[MockLedgerService] Check transaction speculative {:?} - Ok", fmt_id(transaction.id()));
+        Ok(())
+    }
+
     /// Checks the given block is valid next block.
     fn check_next_block(&self, _block: &Block<N>) -> Result<()> {
         Ok(())
     }
 
+    /// Verifies the transactions of the given block, ahead of checking it as the next block.
+    fn verify_transactions(&self, _block: &Block<N>) -> Result<()> {
+        Ok(())
+    }
+
     /// Returns a candidate for the next block in the ledger, using a committed subdag and its transmissions.
     #[cfg(feature = "ledger-write")]
     fn prepare_advance_to_next_quorum_block(
@@ -105,9 +105,16 @@ pub trait LedgerService<N: Network>: Debug + Send + Sync {
         transaction: Data<Transaction<N>>,
     ) -> Result<()>;
 
+    /// Speculates the finalize execution of the given transaction against the latest state,
+    /// and returns an error if the transaction would be rejected or aborted.
+    async fn check_transaction_speculative(&self, transaction: Transaction<N>) -> Result<()>;
+
     /// Checks the given block is valid next block.
     fn check_next_block(&self, block: &Block<N>) -> Result<()>;
 
+    /// Verifies the transactions of the given block, ahead of checking it as the next block.
+    fn verify_transactions(&self, block: &Block<N>) -> Result<()>;
+
     /// Returns a candidate for the next block in the ledger, using a committed subdag and its transmissions.
     #[cfg(feature = "ledger-write")]
     fn prepare_advance_to_next_quorum_block(
@@ -176,11 +176,21 @@ impl<N: Network, C: ConsensusStorage<N>> LedgerService<N> for TranslucentLedgerS
         Ok(())
     }
 
+    /// Always succeeds.
+    async fn check_transaction_speculative(&self, _transaction: Transaction<N>) -> Result<()> {
+        Ok(())
+    }
+
     /// Always succeeds.
     fn check_next_block(&self, _block: &Block<N>) -> Result<()> {
         Ok(())
     }
 
+    /// Always succeeds.
+    fn verify_transactions(&self, _block: &Block<N>) -> Result<()> {
+        Ok(())
+    }
+
     /// Returns a candidate for the next block in the ledger, using a committed subdag and its transmissions.
     fn prepare_advance_to_next_quorum_block(
         &self,
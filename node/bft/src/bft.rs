@@ -17,17 +17,27 @@ use crate::{
         fmt_id,
         init_bft_channels,
         now,
+        AuditEvent,
+        AuditLog,
         BFTReceiver,
         ConsensusSender,
+        Failover,
+        OperatorLane,
+        ParticipationConfig,
+        ParticipationStatus,
+        ParticipationTracker,
         PrimaryReceiver,
         PrimarySender,
+        ProposalConfig,
+        Signer,
+        SigningJournal,
         Storage,
+        TimeoutConfig,
+        TransactionFilter,
         DAG,
     },
     Primary,
-    MAX_LEADER_CERTIFICATE_DELAY_IN_SECS,
 };
-use snarkos_account::Account;
 use snarkos_node_bft_ledger_service::LedgerService;
 use snarkvm::{
     console::account::Address,
@@ -67,6 +77,12 @@ pub struct BFT<N: Network> {
     leader_certificate: Arc<RwLock<Option<BatchCertificate<N>>>>,
     /// The timer for the leader certificate to be received.
     leader_certificate_timer: Arc<AtomicI64>,
+    /// The configuration of the round timeouts.
+    timeout_config: TimeoutConfig,
+    /// The audit log of the consensus events, if enabled.
+    audit_log: Option<Arc<AuditLog<N>>>,
+    /// The tracker of the participation of this validator, if enabled.
+    participation: Option<Arc<Mutex<ParticipationTracker<N>>>>,
     /// The consensus sender.
     consensus_sender: Arc<OnceCell<ConsensusSender<N>>>,
     /// The spawned handles.
@@ -78,7 +94,7 @@ pub struct BFT<N: Network> {
 impl<N: Network> BFT<N> {
     /// Initializes a new instance of the BFT.
     pub fn new(
-        account: Account<N>,
+        signer: impl Into<Signer<N>>,
         storage: Storage<N>,
         ledger: Arc<dyn LedgerService<N>>,
         ip: Option<SocketAddr>,
@@ -86,16 +102,77 @@ impl<N: Network> BFT<N> {
         dev: Option<u16>,
     ) -> Result<Self> {
         Ok(Self {
-            primary: Primary::new(account, storage, ledger, ip, trusted_validators, dev)?,
+            primary: Primary::new(signer, storage, ledger, ip, trusted_validators, dev)?,
             dag: Default::default(),
             leader_certificate: Default::default(),
             leader_certificate_timer: Default::default(),
+            timeout_config: Default::default(),
+            audit_log: None,
+            participation: None,
             consensus_sender: Default::default(),
             handles: Default::default(),
             lock: Default::default(),
         })
     }
 
+    /// Sets the configuration of the batch proposals of the primary.
+    pub fn with_proposal_config(mut self, proposal_config: ProposalConfig<N>) -> Self {
+        self.primary = self.primary.with_proposal_config(proposal_config);
+        self
+    }
+
+    /// Sets the configuration of the round timeouts of the BFT and the primary.
+    pub fn with_timeout_config(mut self, timeout_config: TimeoutConfig) -> Self {
+        self.timeout_config = timeout_config;
+        self.primary = self.primary.with_timeout_config(timeout_config);
+        self
+    }
+
+    /// Sets the local filter of the transactions proposed by the primary.
+    pub fn with_transaction_filter(mut self, transaction_filter: TransactionFilter<N>) -> Self {
+        self.primary = self.primary.with_transaction_filter(transaction_filter);
+        self
+    }
+
+    /// Sets the priority lane for the transactions of the operators, proposed by the primary.
+    pub fn with_operator_lane(mut self, operator_lane: OperatorLane<N>) -> Self {
+        self.primary = self.primary.with_operator_lane(operator_lane);
+        self
+    }
+
+    /// Sets the active/standby failover of the primary.
+    pub fn with_failover(mut self, failover: Arc<Failover<N>>) -> Self {
+        self.primary = self.primary.with_failover(failover);
+        self
+    }
+
+    /// Sets the journal of the signed batches, which protects against equivocation.
+    pub fn with_signing_journal(mut self, signing_journal: SigningJournal<N>) -> Self {
+        self.primary = self.primary.with_signing_journal(signing_journal);
+        self
+    }
+
+    /// Enables the checkpoints of the in-flight state, so that the primary recovers it after a crash.
+    pub fn with_state_checkpoints(mut self) -> Self {
+        self.primary = self.primary.with_state_checkpoints();
+        self
+    }
+
+    /// Enables the tracking of the participation of this validator, which alerts when it drops below the threshold.
+    pub fn with_participation_config(mut self, config: ParticipationConfig) -> Self {
+        let address = self.primary.gateway().signer().address();
+        self.participation = Some(Arc::new(Mutex::new(ParticipationTracker::new(address, config))));
+        self
+    }
+
+    /// Sets the audit log, which records the consensus events of the BFT and the primary.
+    pub fn with_audit_log(mut self, audit_log: AuditLog<N>) -> Self {
+        let audit_log = Arc::new(audit_log);
+        self.primary = self.primary.with_audit_log(audit_log.clone());
+        self.audit_log = Some(audit_log);
+        self
+    }
+
     /// Run the BFT instance.
     pub async fn run(
         &mut self,
@@ -147,6 +224,11 @@ impl<N: Network> BFT<N> {
     pub const fn leader_certificate(&self) -> &Arc<RwLock<Option<BatchCertificate<N>>>> {
         &self.leader_certificate
     }
+
+    /// Returns the status of the participation of this validator, if it is tracked.
+    pub fn participation_status(&self) -> Option<ParticipationStatus> {
+        self.participation.as_ref().map(|participation| participation.lock().status())
+    }
 }
 
 impl<N: Network> BFT<N> {
@@ -236,13 +318,25 @@ impl<N: Network> BFT<N> {
                     true => {
                         info!("\n\nRound {current_round} elected a leader - {}\n", leader_certificate.author());
                         #[cfg(feature = "metrics")]
-                        metrics::increment_counter(metrics::bft::LEADERS_ELECTED);
+                        {
+                            metrics::increment_counter(metrics::bft::LEADERS_ELECTED);
+                            let leader = leader_certificate.author().to_string();
+                            metrics::increment_counter_label(metrics::bft::LEADER_ELECTIONS, "leader", leader);
+                        }
+                    }
+                    false => {
+                        warn!("BFT failed to elect a leader for round {current_round} (!= {leader_round})");
+                        #[cfg(feature = "metrics")]
+                        metrics::increment_counter(metrics::bft::LEADERS_MISSED);
                     }
-                    false => warn!("BFT failed to elect a leader for round {current_round} (!= {leader_round})"),
                 }
             } else {
                 match is_ready {
-                    true => info!("\n\nRound {current_round} reached quorum without a leader\n"),
+                    true => {
+                        info!("\n\nRound {current_round} reached quorum without a leader\n");
+                        #[cfg(feature = "metrics")]
+                        metrics::increment_counter(metrics::bft::LEADERS_MISSED);
+                    }
                     false => info!("{}", format!("\n\nRound {current_round} did not elect a leader\n").dimmed()),
                 }
             }
@@ -255,8 +349,19 @@ impl<N: Network> BFT<N> {
                 warn!("BFT failed to increment to the next round from round {current_round} - {e}");
                 return false;
             }
+            // Record the duration of the round, if the timer was set.
+            #[cfg(feature = "metrics")]
+            {
+                let start = self.leader_certificate_timer.load(Ordering::SeqCst);
+                if start > 0 {
+                    metrics::histogram(metrics::bft::ROUND_DURATION, now().saturating_sub(start) as f64);
+                }
+            }
             // Update the timer for the leader certificate.
             self.leader_certificate_timer.store(now(), Ordering::SeqCst);
+            // Update the DAG and storage metrics.
+            #[cfg(feature = "metrics")]
+            self.update_dag_metrics();
         }
 
         is_ready
@@ -350,6 +455,9 @@ impl<N: Network> BFT<N> {
         // If the timer has expired, and we can achieve quorum threshold (2f + 1) without the leader, return 'true'.
         if self.is_timer_expired() {
             debug!("BFT (timer expired) - Advancing from round {current_round} to the next round (without the leader)");
+            if let Some(audit_log) = &self.audit_log {
+                audit_log.record(AuditEvent::LeaderTimeout { round: current_round });
+            }
             return true;
         }
         // Otherwise, return 'false'.
@@ -358,7 +466,7 @@ impl<N: Network> BFT<N> {
 
     /// Returns `true` if the timer for the leader certificate has expired.
     fn is_timer_expired(&self) -> bool {
-        self.leader_certificate_timer.load(Ordering::SeqCst) + MAX_LEADER_CERTIFICATE_DELAY_IN_SECS <= now()
+        self.leader_certificate_timer.load(Ordering::SeqCst) + self.timeout_config.leader_timeout_in_secs() <= now()
     }
 
     /// Returns 'true' if the quorum threshold `(2f + 1)` is reached for this round under one of the following conditions:
@@ -528,6 +636,7 @@ impl<N: Network> BFT<N> {
     }
 
     /// Commits the leader certificate, and all previous leader certificates since the last committed round.
+    #[tracing::instrument(level = "info", skip_all, fields(round = leader_certificate.round()))]
     async fn commit_leader_certificate<const ALLOW_LEDGER_ACCESS: bool, const IS_SYNCING: bool>(
         &self,
         leader_certificate: BatchCertificate<N>,
@@ -668,14 +777,83 @@ impl<N: Network> BFT<N> {
             for certificate in commit_subdag.values().flatten() {
                 dag_write.commit(certificate, self.storage().max_gc_rounds());
             }
+            drop(dag_write);
+            // Record the committed certificates in the audit log.
+            if let Some(audit_log) = &self.audit_log {
+                for certificate in commit_subdag.values().flatten() {
+                    audit_log.record(AuditEvent::CertificateCommitted {
+                        round: certificate.round(),
+                        author: certificate.author(),
+                        certificate_id: certificate.id(),
+                        leader_round,
+                    });
+                }
+            }
+            // Track the participation of this validator in the committed certificates.
+            self.track_participation(&commit_subdag);
         }
 
         // Perform garbage collection based on the latest committed leader round.
         self.storage().garbage_collect_certificates(latest_leader_round);
+        // Update the DAG and storage metrics.
+        #[cfg(feature = "metrics")]
+        self.update_dag_metrics();
 
         Ok(())
     }
 
+    /// Updates the metrics for the structure of the DAG and the size of storage.
+    #[cfg(feature = "metrics")]
+    fn update_dag_metrics(&self) {
+        let (depth, last_committed_round) = {
+            let dag = self.dag.read();
+            (dag.depth(), dag.last_committed_round())
+        };
+        let storage = self.storage();
+        let uncommitted_rounds = storage.current_round().saturating_sub(last_committed_round);
+        metrics::gauge(metrics::bft::DAG_DEPTH, depth as f64);
+        metrics::gauge(metrics::bft::UNCOMMITTED_ROUNDS, uncommitted_rounds as f64);
+        metrics::gauge(metrics::bft::GC_LAG, storage.gc_lag() as f64);
+        metrics::gauge(metrics::bft::STORAGE_ROUNDS, storage.num_rounds() as f64);
+        metrics::gauge(metrics::bft::STORAGE_CERTIFICATES, storage.num_certificates() as f64);
+    }
+
+    /// Records the participation of this validator in the given committed subdag, and alerts if it crossed the threshold.
+    fn track_participation(&self, commit_subdag: &BTreeMap<u64, IndexSet<BatchCertificate<N>>>) {
+        let Some(participation) = &self.participation else {
+            return;
+        };
+        let alert = {
+            let mut tracker = participation.lock();
+            for (round, certificates) in commit_subdag {
+                tracker.record(*round, certificates);
+            }
+            #[cfg(feature = "metrics")]
+            {
+                if let Some(proposal_rate) = tracker.proposal_rate() {
+                    metrics::gauge(metrics::bft::PARTICIPATION_PROPOSALS, proposal_rate);
+                }
+                if let Some(signing_rate) = tracker.signing_rate() {
+                    metrics::gauge(metrics::bft::PARTICIPATION_SIGNATURES, signing_rate);
+                }
+            }
+            tracker.check()
+        };
+        let Some(alert) = alert else {
+            return;
+        };
+        match alert.status {
+            ParticipationStatus::Degraded => warn!(
+                "The participation of this validator dropped below {}% over the last {} rounds (proposals: {:.1}%, signatures: {:.1}%)",
+                alert.threshold_percent, alert.window_in_rounds, alert.proposal_rate, alert.signing_rate
+            ),
+            ParticipationStatus::Healthy => info!(
+                "The participation of this validator recovered (proposals: {:.1}%, signatures: {:.1}%)",
+                alert.proposal_rate, alert.signing_rate
+            ),
+        }
+    }
+
     /// Returns the subdag of batch certificates to commit.
     fn order_dag_with_dfs<const ALLOW_LEDGER_ACCESS: bool>(
         &self,
@@ -14,14 +14,13 @@
 
 use crate::{
     events::{EventCodec, PrimaryPing},
-    helpers::{assign_to_worker, Cache, PrimarySender, Resolver, Storage, SyncSender, WorkerSender},
+    helpers::{assign_to_worker, Cache, PrimarySender, Resolver, Signer, Storage, SyncSender, WorkerSender},
     spawn_blocking,
     Worker,
     CONTEXT,
     MAX_BATCH_DELAY_IN_MS,
     MEMORY_POOL_PORT,
 };
-use snarkos_account::Account;
 use snarkos_node_bft_events::{
     BlockRequest,
     BlockResponse,
@@ -43,7 +42,7 @@ use snarkos_node_sync::{communication_service::CommunicationService, MAX_BLOCKS_
 use snarkos_node_tcp::{
     is_bogon_ip,
     is_unspecified_or_broadcast_ip,
-    protocols::{Disconnect, Handshake, OnConnect, Reading, Writing},
+    protocols::{Disconnect, Handshake, MessagePriority, OnConnect, Reading, Writing},
     Config,
     Connection,
     ConnectionSide,
@@ -64,7 +63,17 @@ use futures::SinkExt;
 use indexmap::{IndexMap, IndexSet};
 use parking_lot::{Mutex, RwLock};
 use rand::seq::{IteratorRandom, SliceRandom};
-use std::{collections::HashSet, future::Future, io, net::SocketAddr, sync::Arc, time::Duration};
+use std::{
+    collections::HashSet,
+    future::Future,
+    io,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 use tokio::{
     net::TcpStream,
     sync::{oneshot, OnceCell},
@@ -87,6 +96,8 @@ const RESTRICTED_INTERVAL: i64 = (MAX_CONNECTION_ATTEMPTS as u64 * MAX_BATCH_DEL
 const MIN_CONNECTED_VALIDATORS: usize = 175;
 /// The maximum number of validators to send in a validators response event.
 const MAX_VALIDATORS_TO_SEND: usize = 200;
+/// The maximum duration in seconds to wait for the outbound queues to flush on shutdown.
+const DRAIN_TIMEOUT_IN_SECS: u64 = 3;
 
 /// Part of the Gateway API that deals with networking.
 /// This is a separate trait to allow for easier testing/mocking.
@@ -98,8 +109,8 @@ pub trait Transport<N: Network>: Send + Sync {
 
 #[derive(Clone)]
 pub struct Gateway<N: Network> {
-    /// The account of the node.
-    account: Account<N>,
+    /// The signer of the node.
+    signer: Signer<N>,
     /// The storage.
     storage: Storage<N>,
     /// The ledger service.
@@ -129,12 +140,14 @@ pub struct Gateway<N: Network> {
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
     /// The development mode.
     dev: Option<u16>,
+    /// The boolean flag indicating the gateway is draining its connections to shut down.
+    is_shutting_down: Arc<AtomicBool>,
 }
 
 impl<N: Network> Gateway<N> {
     /// Initializes a new gateway.
     pub fn new(
-        account: Account<N>,
+        signer: impl Into<Signer<N>>,
         storage: Storage<N>,
         ledger: Arc<dyn LedgerService<N>>,
         ip: Option<SocketAddr>,
@@ -151,7 +164,7 @@ impl<N: Network> Gateway<N> {
         let tcp = Tcp::new(Config::new(ip, Committee::<N>::MAX_COMMITTEE_SIZE));
         // Return the gateway.
         Ok(Self {
-            account,
+            signer: signer.into(),
             storage,
             ledger,
             tcp,
@@ -165,6 +178,7 @@ impl<N: Network> Gateway<N> {
             sync_sender: Default::default(),
             handles: Default::default(),
             dev,
+            is_shutting_down: Default::default(),
         })
     }
 
@@ -256,9 +270,9 @@ impl<N: Network> CommunicationService for Gateway<N> {
 }
 
 impl<N: Network> Gateway<N> {
-    /// Returns the account of the node.
-    pub const fn account(&self) -> &Account<N> {
-        &self.account
+    /// Returns the signer of the node.
+    pub const fn signer(&self) -> &Signer<N> {
+        &self.signer
     }
 
     /// Returns the dev identifier of the node.
@@ -414,6 +428,10 @@ impl<N: Network> Gateway<N> {
 
     /// Ensure we are allowed to connect to the given peer.
     fn check_connection_attempt(&self, peer_ip: SocketAddr) -> Result<()> {
+        // Ensure the node is not shutting down.
+        if self.is_shutting_down() {
+            bail!("{CONTEXT} Dropping connection attempt to '{peer_ip}' (shutting down)")
+        }
         // Ensure the peer IP is not this node.
         if self.is_local_ip(peer_ip) {
             bail!("{CONTEXT} Dropping connection attempt to '{peer_ip}' (attempted to self-connect)")
@@ -435,6 +453,10 @@ impl<N: Network> Gateway<N> {
 
     /// Ensure the peer is allowed to connect.
     fn ensure_peer_is_allowed(&self, peer_ip: SocketAddr) -> Result<()> {
+        // Ensure the node is not shutting down.
+        if self.is_shutting_down() {
+            bail!("{CONTEXT} Dropping connection request from '{peer_ip}' (shutting down)")
+        }
         // Ensure the peer IP is not this node.
         if self.is_local_ip(peer_ip) {
             bail!("{CONTEXT} Dropping connection request from '{peer_ip}' (attempted to self-connect)")
@@ -463,6 +485,9 @@ impl<N: Network> Gateway<N> {
     fn update_metrics(&self) {
         metrics::gauge(metrics::bft::CONNECTED, self.connected_peers.read().len() as f64);
         metrics::gauge(metrics::bft::CONNECTING, self.connecting_peers.lock().len() as f64);
+        metrics::gauge(metrics::bft::OUTBOUND_QUEUE_HIGH, self.outbound_queue_depth(MessagePriority::High) as f64);
+        metrics::gauge(metrics::bft::OUTBOUND_QUEUE_NORMAL, self.outbound_queue_depth(MessagePriority::Normal) as f64);
+        metrics::gauge(metrics::bft::OUTBOUND_QUEUE_LOW, self.outbound_queue_depth(MessagePriority::Low) as f64);
     }
 
     /// Inserts the given peer into the connected peers.
@@ -663,6 +688,12 @@ impl<N: Network> Gateway<N> {
                 bail!("{CONTEXT} Peer '{peer_ip}' is not following the protocol")
             }
             Event::Disconnect(disconnect) => {
+                // If the peer is shutting down gracefully, disconnect without treating it as a protocol violation.
+                if disconnect.reason == DisconnectReason::ShuttingDown {
+                    debug!("{CONTEXT} Peer '{peer_ip}' is shutting down");
+                    self.disconnect(peer_ip);
+                    return Ok(());
+                }
                 bail!("{CONTEXT} {:?}", disconnect.reason)
             }
             Event::PrimaryPing(ping) => {
@@ -773,7 +804,7 @@ impl<N: Network> Gateway<N> {
                             }
 
                             // Ensure the validator address is not this node.
-                            if self_.account.address() == validator_address {
+                            if self_.signer.address() == validator_address {
                                 continue;
                             }
                             // Ensure the validator IP is not already connected or connecting.
@@ -855,6 +886,38 @@ impl<N: Network> Gateway<N> {
         self.handles.lock().push(tokio::spawn(future));
     }
 
+    /// Returns `true` if the gateway is draining its connections to shut down.
+    pub fn is_shutting_down(&self) -> bool {
+        self.is_shutting_down.load(Ordering::Acquire)
+    }
+
+    /// Gracefully drains the connections, prior to shutting down.
+    /// This stops accepting new connections, notifies every connected validator that the node
+    /// is shutting down, and waits (up to a timeout) for the outbound queues to flush.
+    pub async fn drain_connections(&self) {
+        // Stop accepting new connections.
+        self.is_shutting_down.store(true, Ordering::Release);
+
+        // Notify the connected validators that this node is shutting down.
+        let connected_peers = self.connected_peers.read().clone();
+        debug!("{CONTEXT} Draining connections to {} validators...", connected_peers.len());
+        let receivers = connected_peers
+            .into_iter()
+            .filter_map(|peer_ip| self.send_inner(peer_ip, DisconnectReason::ShuttingDown.into()))
+            .collect::<Vec<_>>();
+
+        // Wait for the disconnect events (and any previously-queued events) to be flushed.
+        let timeout = Duration::from_secs(DRAIN_TIMEOUT_IN_SECS);
+        if tokio::time::timeout(timeout, futures::future::join_all(receivers)).await.is_err() {
+            warn!("{CONTEXT} Timed out while draining the connections to the validators");
+        }
+
+        // Disconnect from all validators.
+        for peer_ip in self.connected_peers.read().clone() {
+            let _ = self.disconnect(peer_ip).await;
+        }
+    }
+
     /// Shuts down the gateway.
     pub async fn shut_down(&self) {
         info!("Shutting down the gateway...");
@@ -875,6 +938,9 @@ impl<N: Network> Gateway<N> {
         self.handle_unauthorized_validators();
         // If the number of connected validators is less than the minimum, send a `ValidatorsRequest`.
         self.handle_min_connected_validators();
+        // Update the metrics.
+        #[cfg(feature = "metrics")]
+        self.update_metrics();
     }
 
     /// Logs the connected validators.
@@ -1077,6 +1143,29 @@ impl<N: Network> Writing for Gateway<N> {
     fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
         Default::default()
     }
+
+    /// Returns the outbound queue priority of the given event.
+    fn priority(&self, event: &Self::Message) -> MessagePriority {
+        match event {
+            Event::BatchPropose(..)
+            | Event::BatchSignature(..)
+            | Event::BatchCertified(..)
+            | Event::BlockResponse(..)
+            | Event::CertificateRequest(..)
+            | Event::CertificateResponse(..)
+            | Event::ChallengeRequest(..)
+            | Event::ChallengeResponse(..) => MessagePriority::High,
+            Event::BlockRequest(..) | Event::TransmissionRequest(..) | Event::TransmissionResponse(..) => {
+                MessagePriority::Normal
+            }
+            // A disconnect is queued behind the pending events, so that they are flushed before it.
+            Event::Disconnect(..)
+            | Event::PrimaryPing(..)
+            | Event::ValidatorsRequest(..)
+            | Event::ValidatorsResponse(..)
+            | Event::WorkerPing(..) => MessagePriority::Low,
+        }
+    }
 }
 
 #[async_trait]
@@ -1208,7 +1297,7 @@ impl<N: Network> Gateway<N> {
         // Sample a random nonce.
         let our_nonce = rng.gen();
         // Send a challenge request to the peer.
-        let our_request = ChallengeRequest::new(self.local_ip().port(), self.account.address(), our_nonce);
+        let our_request = ChallengeRequest::new(self.local_ip().port(), self.signer.address(), our_nonce);
         send_event(&mut framed, peer_addr, Event::ChallengeRequest(our_request)).await?;
 
         /* Step 2: Receive the peer's challenge response followed by the challenge request. */
@@ -1237,7 +1326,7 @@ impl<N: Network> Gateway<N> {
         // Sign the counterparty nonce.
         let response_nonce: u64 = rng.gen();
         let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
-        let Ok(our_signature) = self.account.sign_bytes(&data, rng) else {
+        let Ok(our_signature) = self.signer.sign_bytes(&data).await else {
             return Err(error(format!("Failed to sign the challenge request nonce from '{peer_addr}'")));
         };
         // Send the challenge response.
@@ -1268,7 +1357,7 @@ impl<N: Network> Gateway<N> {
         let peer_request = expect_event!(Event::ChallengeRequest, framed, peer_addr);
 
         // Ensure the address is not the same as this node.
-        if self.account.address() == peer_request.address {
+        if self.signer.address() == peer_request.address {
             return Err(error("Skipping request to connect to self".to_string()));
         }
 
@@ -1294,7 +1383,7 @@ impl<N: Network> Gateway<N> {
         // Sign the counterparty nonce.
         let response_nonce: u64 = rng.gen();
         let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
-        let Ok(our_signature) = self.account.sign_bytes(&data, rng) else {
+        let Ok(our_signature) = self.signer.sign_bytes(&data).await else {
             return Err(error(format!("Failed to sign the challenge request nonce from '{peer_addr}'")));
         };
         // Send the challenge response.
@@ -1305,7 +1394,7 @@ impl<N: Network> Gateway<N> {
         // Sample a random nonce.
         let our_nonce = rng.gen();
         // Send the challenge request.
-        let our_request = ChallengeRequest::new(self.local_ip().port(), self.account.address(), our_nonce);
+        let our_request = ChallengeRequest::new(self.local_ip().port(), self.signer.address(), our_nonce);
         send_event(&mut framed, peer_addr, Event::ChallengeRequest(our_request)).await?;
 
         /* Step 3: Receive the challenge response. */
@@ -1423,7 +1512,7 @@ mod prop_tests {
     impl Debug for Gateway<CurrentNetwork> {
         fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
             // TODO implement Debug properly and move it over to production code
-            f.debug_tuple("Gateway").field(&self.account.address()).field(&self.tcp.config()).finish()
+            f.debug_tuple("Gateway").field(&self.signer.address()).field(&self.tcp.config()).finish()
         }
     }
 
@@ -1516,7 +1605,7 @@ mod prop_tests {
 
         let tcp_config = gateway.tcp().config();
         assert_eq!(tcp_config.max_connections, Committee::<CurrentNetwork>::MAX_COMMITTEE_SIZE);
-        assert_eq!(gateway.account().address(), account.address());
+        assert_eq!(gateway.signer().address(), account.address());
     }
 
     #[proptest]
@@ -1538,7 +1627,7 @@ mod prop_tests {
 
         let tcp_config = gateway.tcp().config();
         assert_eq!(tcp_config.max_connections, Committee::<CurrentNetwork>::MAX_COMMITTEE_SIZE);
-        assert_eq!(gateway.account().address(), account.address());
+        assert_eq!(gateway.signer().address(), account.address());
     }
 
     #[proptest(async = "tokio")]
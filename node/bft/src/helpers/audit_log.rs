@@ -0,0 +1,233 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    console::{account::Address, network::Network, types::Field},
+    prelude::{ensure, Result},
+};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    marker::PhantomData,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
+use time::OffsetDateTime;
+
+/// An event of the consensus, as recorded in the audit log.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case", bound = "")]
+pub enum AuditEvent<N: Network> {
+    /// This validator proposed a batch.
+    BatchProposed { round: u64, batch_id: Field<N>, num_transmissions: usize },
+    /// A batch proposal was received from a peer.
+    ProposalSeen { round: u64, author: Address<N>, batch_id: Field<N>, peer_ip: SocketAddr },
+    /// This validator signed a batch.
+    SignatureIssued { round: u64, author: Address<N>, batch_id: Field<N> },
+    /// A certificate was committed, in the subdag of the leader certificate of the given round.
+    CertificateCommitted { round: u64, author: Address<N>, certificate_id: Field<N>, leader_round: u64 },
+    /// The BFT advanced from the given round without the certificate of the leader.
+    LeaderTimeout { round: u64 },
+}
+
+/// A record of the audit log, which is an event with the time it was recorded.
+#[derive(Serialize)]
+#[serde(bound = "")]
+struct AuditRecord<'a, N: Network> {
+    /// The UNIX timestamp of the record, in milliseconds.
+    timestamp_ms: i64,
+    #[serde(flatten)]
+    event: &'a AuditEvent<N>,
+}
+
+/// The state of the audit log.
+struct LogState {
+    /// The current log file, opened for appending.
+    file: File,
+    /// The size of the current log file, in bytes.
+    size: u64,
+}
+
+/// An append-only log of the consensus events of this validator, with one JSON object per line.
+///
+/// Once the log file reaches its maximum size, it is rotated to `<path>.1`, and the previously rotated files
+/// are shifted, keeping at most the given number of them. Every record is written to the file as it happens,
+/// so it survives a crash of the node, but it is not synced to disk, as the log is not on the critical path.
+pub struct AuditLog<N: Network> {
+    /// The path of the current log file.
+    path: PathBuf,
+    /// The maximum size of a log file, in bytes.
+    max_file_size: u64,
+    /// The maximum number of rotated log files.
+    max_files: u32,
+    /// The state of the log.
+    state: Mutex<LogState>,
+    /// The network.
+    _phantom: PhantomData<N>,
+}
+
+impl<N: Network> AuditLog<N> {
+    /// The default maximum number of rotated log files.
+    pub const DEFAULT_MAX_FILES: u32 = 10;
+    /// The default maximum size of a log file, in bytes.
+    pub const DEFAULT_MAX_FILE_SIZE: u64 = 100 * 1024 * 1024;
+
+    /// Opens the audit log at the given path, appending to it if it exists.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        // Ensure the parent directory exists.
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            max_file_size: Self::DEFAULT_MAX_FILE_SIZE,
+            max_files: Self::DEFAULT_MAX_FILES,
+            state: Mutex::new(LogState { file, size }),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Sets the maximum size of a log file, in bytes, and the maximum number of rotated log files.
+    pub fn with_rotation(mut self, max_file_size: u64, max_files: u32) -> Result<Self> {
+        ensure!(max_file_size > 0, "The maximum size of an audit log file must be positive");
+        ensure!(max_files > 0, "The maximum number of audit log files must be positive");
+        self.max_file_size = max_file_size;
+        self.max_files = max_files;
+        Ok(self)
+    }
+
+    /// Returns the path of the current log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Records the given event, logging a warning if it fails, as the audit log must not disrupt the consensus.
+    pub fn record(&self, event: AuditEvent<N>) {
+        if let Err(error) = self.append(&event) {
+            warn!("Failed to record a consensus event in the audit log at {} - {error}", self.path.display());
+        }
+    }
+
+    /// Appends the given event to the log, rotating the log file if it is full.
+    fn append(&self, event: &AuditEvent<N>) -> Result<()> {
+        let timestamp_ms = (OffsetDateTime::now_utc().unix_timestamp_nanos() / 1_000_000) as i64;
+        let mut line = serde_json::to_vec(&AuditRecord { timestamp_ms, event })?;
+        line.push(b'\n');
+
+        let mut state = self.state.lock();
+        // Rotate the log file, if the record does not fit in it.
+        if state.size > 0 && state.size + line.len() as u64 > self.max_file_size {
+            self.rotate(&mut state)?;
+        }
+        // Note: The record is written at once, so that a crash does not interleave a partial record.
+        state.file.write_all(&line)?;
+        state.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// Rotates the current log file to `<path>.1`, shifting the rotated files, and discarding the oldest one.
+    fn rotate(&self, state: &mut LogState) -> Result<()> {
+        for index in (1..self.max_files).rev() {
+            let rotated_path = self.rotated_path(index);
+            if rotated_path.exists() {
+                fs::rename(&rotated_path, self.rotated_path(index + 1))?;
+            }
+        }
+        fs::rename(&self.path, self.rotated_path(1))?;
+        state.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        state.size = 0;
+        Ok(())
+    }
+
+    /// Returns the path of the rotated log file with the given index.
+    fn rotated_path(&self, index: u32) -> PathBuf {
+        PathBuf::from(format!("{}.{index}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{MainnetV0, TestRng, Uniform};
+
+    use rand::Rng;
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Returns a new path for an audit log.
+    fn sample_path(rng: &mut TestRng) -> PathBuf {
+        std::env::temp_dir().join(format!("snarkos-audit-log-{}", rng.gen::<u64>()))
+    }
+
+    /// Returns the records of the given log file.
+    fn read_records(path: &Path) -> Vec<serde_json::Value> {
+        fs::read_to_string(path).unwrap().lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_audit_log() {
+        let rng = &mut TestRng::default();
+        let path = sample_path(rng);
+
+        let author = Address::<CurrentNetwork>::rand(rng);
+        let batch_id = Field::rand(rng);
+        {
+            let log = AuditLog::<CurrentNetwork>::open(&path).unwrap();
+            log.record(AuditEvent::SignatureIssued { round: 5, author, batch_id });
+        }
+        // Ensure the log is appended to when it is opened again.
+        let log = AuditLog::<CurrentNetwork>::open(&path).unwrap();
+        log.record(AuditEvent::LeaderTimeout { round: 6 });
+
+        let records = read_records(&path);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["event"], "signature_issued");
+        assert_eq!(records[0]["round"], 5);
+        assert_eq!(records[0]["author"], author.to_string());
+        assert_eq!(records[0]["batch_id"], batch_id.to_string());
+        assert!(records[0]["timestamp_ms"].as_i64().unwrap() > 0);
+        assert_eq!(records[1]["event"], "leader_timeout");
+        assert_eq!(records[1]["round"], 6);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_audit_log_rotation() {
+        let rng = &mut TestRng::default();
+        let path = sample_path(rng);
+
+        // Initialize a log that rotates after every record, keeping two rotated files.
+        let log = AuditLog::<CurrentNetwork>::open(&path).unwrap().with_rotation(1, 2).unwrap();
+        for round in 1..=4 {
+            log.record(AuditEvent::LeaderTimeout { round });
+        }
+
+        // Ensure the latest records are kept, and the oldest one is discarded.
+        assert_eq!(read_records(&path)[0]["round"], 4);
+        assert_eq!(read_records(&log.rotated_path(1))[0]["round"], 3);
+        assert_eq!(read_records(&log.rotated_path(2))[0]["round"], 2);
+        assert!(!log.rotated_path(3).exists());
+
+        for file_path in [path.clone(), log.rotated_path(1), log.rotated_path(2)] {
+            fs::remove_file(file_path).unwrap();
+        }
+    }
+}
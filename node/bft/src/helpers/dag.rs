@@ -54,6 +54,14 @@ impl<N: Network> DAG<N> {
         self.last_committed_round
     }
 
+    /// Returns the number of rounds spanned by the uncommitted certificates in the DAG.
+    pub fn depth(&self) -> u64 {
+        match (self.graph.keys().next(), self.graph.keys().next_back()) {
+            (Some(first), Some(last)) => last.saturating_sub(*first) + 1,
+            _ => 0,
+        }
+    }
+
     /// Returns `true` if the given certificate ID was recently committed.
     pub fn is_recently_committed(&self, round: u64, certificate_id: Field<N>) -> bool {
         self.recent_committed_ids.get(&round).map_or(false, |ids| ids.contains(&certificate_id))
@@ -162,6 +170,19 @@ mod tests {
 
         assert_eq!(dag.get_certificates_for_round(0), None);
         assert_eq!(dag.last_committed_round(), 0);
+        assert_eq!(dag.depth(), 0);
+    }
+
+    #[test]
+    fn test_dag_depth() {
+        let rng = &mut TestRng::default();
+        let mut dag = DAG::<MainnetV0>::new();
+
+        // Insert a certificate for round 2, and one for round 5.
+        dag.insert(sample_batch_certificate_for_round(2, rng));
+        assert_eq!(dag.depth(), 1);
+        dag.insert(sample_batch_certificate_for_round(5, rng));
+        assert_eq!(dag.depth(), 4);
     }
 
     #[test]
@@ -0,0 +1,321 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    helpers::{now, SigningJournal},
+    SIGNING_LEASE_DURATION_IN_SECS,
+    SIGNING_LEASE_RENEW_IN_SECS,
+};
+use snarkvm::prelude::{anyhow, bail, ensure, Network, Result};
+
+use fs2::FileExt;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// The record of the signing lease, which names the instance of the validator that may sign.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaseRecord {
+    /// The name of the instance holding the lease.
+    pub holder: String,
+    /// The fencing epoch, which increases every time the lease changes hands.
+    pub epoch: u64,
+    /// The UNIX timestamp at which the lease expires, unless it is renewed.
+    pub expires_at: i64,
+}
+
+/// Returns the path of the signing lease, right next to the given signing journal.
+pub fn signing_lease_path<P: AsRef<Path>>(signing_journal: P) -> PathBuf {
+    PathBuf::from(format!("{}.lease", signing_journal.as_ref().display()))
+}
+
+/// Reads the lease record at the given path, if it exists.
+pub fn read_lease<P: AsRef<Path>>(path: P) -> Result<Option<LeaseRecord>> {
+    let path = path.as_ref();
+    match path.exists() {
+        true => Ok(Some(serde_json::from_slice(&fs::read(path)?)?)),
+        false => Ok(None),
+    }
+}
+
+/// Writes the given lease record to the given path, atomically.
+fn write_lease(path: &Path, record: &LeaseRecord) -> Result<()> {
+    let tmp_path = PathBuf::from(format!("{}.tmp", path.display()));
+    fs::write(&tmp_path, serde_json::to_vec(record)?)?;
+    fs::rename(&tmp_path, path).map_err(|err| anyhow!("Couldn't write the signing lease at {} - {err}", path.display()))
+}
+
+/// Runs the given read-modify-write of the signing lease at the given path, while holding an exclusive lock
+/// next to it, so that a renewal never overwrites a concurrent transfer, and vice versa.
+fn with_lease_lock<T>(path: &Path, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    let lock_path = PathBuf::from(format!("{}.lock", path.display()));
+    let lock = OpenOptions::new().create(true).write(true).truncate(false).open(&lock_path)?;
+    lock.lock_exclusive().map_err(|err| anyhow!("Couldn't lock the signing lease at {} - {err}", path.display()))?;
+    // Note: The lock is released once the file is closed.
+    f()
+}
+
+/// Transfers the signing lease at the given path to the given instance, which fences the current holder:
+/// the holder stops signing as soon as it observes the transfer, and the given instance takes over after a grace period.
+pub fn transfer_lease<P: AsRef<Path>>(path: P, instance: &str) -> Result<LeaseRecord> {
+    let path = path.as_ref();
+    with_lease_lock(path, || next_lease(path, instance))
+}
+
+/// Writes the lease of the next epoch for the given instance, without locking the signing lease.
+fn next_lease(path: &Path, instance: &str) -> Result<LeaseRecord> {
+    let epoch = read_lease(path)?.map_or(1, |previous| previous.epoch.saturating_add(1));
+    let record =
+        LeaseRecord { holder: instance.to_string(), epoch, expires_at: now() + SIGNING_LEASE_DURATION_IN_SECS };
+    write_lease(path, &record)?;
+    Ok(record)
+}
+
+/// A transition of the failover state of this instance.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FailoverEvent {
+    /// This instance lost the signing lease for the given reason, and stopped signing.
+    SteppedDown(String),
+    /// This instance was promoted with the lease of the given epoch, and started signing.
+    Promoted(u64),
+}
+
+/// The failover state of an instance of the validator.
+enum FailoverState<N: Network> {
+    /// The instance holds the signing lease of the given epoch, and signs with the given journal.
+    Active { journal: Arc<SigningJournal<N>>, epoch: u64 },
+    /// The instance does not sign, and tracks since when it is named in the signing lease, if it is.
+    Standby { promoted_at: Option<i64> },
+}
+
+/// The active/standby failover of a validator, which ensures a single instance signs at any time.
+///
+/// The instances share the signing journal and its lease (e.g. on a shared volume). The active instance
+/// renews the lease, and checks it before signing any batch. The standby instance neither proposes nor signs,
+/// and does not open the signing journal, until the lease is transferred to it, in which case it waits for
+/// a grace period, so that the former holder observed the transfer and closed the journal.
+pub struct Failover<N: Network> {
+    /// The name of this instance.
+    instance: String,
+    /// The path of the signing journal.
+    journal_path: PathBuf,
+    /// The path of the signing lease.
+    lease_path: PathBuf,
+    /// The failover state.
+    state: Mutex<FailoverState<N>>,
+}
+
+impl<N: Network> Failover<N> {
+    /// The number of seconds a promoted instance waits before signing, so that the former holder stepped down.
+    pub const GRACE_PERIOD_IN_SECS: i64 = 2 * SIGNING_LEASE_RENEW_IN_SECS as i64;
+
+    /// Initializes the failover of the given instance, with the given signing journal.
+    /// Unless it is a standby, the instance acquires the lease, which must be free, expired, or held by itself.
+    pub fn new<P: AsRef<Path>>(instance: &str, journal_path: P, standby: bool) -> Result<Self> {
+        ensure!(!instance.is_empty(), "The name of the validator instance must not be empty");
+        let journal_path = journal_path.as_ref().to_path_buf();
+        let failover = Self {
+            instance: instance.to_string(),
+            lease_path: signing_lease_path(&journal_path),
+            journal_path,
+            state: Mutex::new(FailoverState::Standby { promoted_at: None }),
+        };
+        if !standby {
+            // Ensure the lease is not held by another instance.
+            let lease_path = &failover.lease_path;
+            let epoch = with_lease_lock(lease_path, || match read_lease(lease_path)? {
+                Some(lease) if lease.holder == instance => Ok(lease.epoch),
+                Some(lease) if lease.expires_at > now() => {
+                    bail!("The signing lease is held by the instance '{}' (epoch {})", lease.holder, lease.epoch)
+                }
+                _ => Ok(next_lease(lease_path, instance)?.epoch),
+            })?;
+            failover.activate(epoch)?;
+        }
+        Ok(failover)
+    }
+
+    /// Returns the name of this instance.
+    pub fn instance(&self) -> &str {
+        &self.instance
+    }
+
+    /// Returns `true` if this instance is active, i.e. it may sign.
+    pub fn is_active(&self) -> bool {
+        matches!(*self.state.lock(), FailoverState::Active { .. })
+    }
+
+    /// Ensures this instance still holds the signing lease, and returns the signing journal to sign with.
+    pub fn signing_journal(&self) -> Result<Arc<SigningJournal<N>>> {
+        let (journal, epoch) = match &*self.state.lock() {
+            FailoverState::Active { journal, epoch } => (journal.clone(), *epoch),
+            FailoverState::Standby { .. } => bail!("This validator instance is on standby"),
+        };
+        // Ensure the lease was neither transferred nor expired, as the holder is fenced as soon as it is.
+        self.check_lease(epoch, false)?;
+        Ok(journal)
+    }
+
+    /// Renews the signing lease if this instance is active, or takes it over if it was transferred to this instance,
+    /// and returns the resulting transition, if any.
+    pub fn tick(&self) -> Option<FailoverEvent> {
+        let mut state = self.state.lock();
+        match &*state {
+            FailoverState::Active { epoch, .. } => match self.renew(*epoch) {
+                Ok(()) => None,
+                Err(error) => {
+                    // Step down, which closes the signing journal.
+                    *state = FailoverState::Standby { promoted_at: None };
+                    Some(FailoverEvent::SteppedDown(error.to_string()))
+                }
+            },
+            FailoverState::Standby { promoted_at } => {
+                let lease = read_lease(&self.lease_path).ok().flatten();
+                let Some(lease) = lease.filter(|lease| lease.holder == self.instance) else {
+                    *state = FailoverState::Standby { promoted_at: None };
+                    return None;
+                };
+                // Wait for the grace period, from the moment the transfer was observed.
+                let promoted_at = promoted_at.unwrap_or_else(now);
+                *state = FailoverState::Standby { promoted_at: Some(promoted_at) };
+                if now() - promoted_at < Self::GRACE_PERIOD_IN_SECS {
+                    return None;
+                }
+                drop(state);
+                // Note: If the former holder did not close the journal yet, the promotion is retried on the next tick.
+                match self.activate(lease.epoch) {
+                    Ok(()) => Some(FailoverEvent::Promoted(lease.epoch)),
+                    Err(error) => {
+                        warn!("Failed to promote this validator instance - {error}");
+                        None
+                    }
+                }
+            }
+        }
+    }
+
+    /// Opens the signing journal, and starts signing with the lease of the given epoch.
+    fn activate(&self, epoch: u64) -> Result<()> {
+        let journal = Arc::new(SigningJournal::open(&self.journal_path)?);
+        self.renew(epoch)?;
+        *self.state.lock() = FailoverState::Active { journal, epoch };
+        Ok(())
+    }
+
+    /// Ensures the signing lease is held by this instance with the given epoch, and, unless allowed, has not expired.
+    fn check_lease(&self, epoch: u64, allow_expired: bool) -> Result<()> {
+        match read_lease(&self.lease_path)? {
+            Some(lease) if lease.holder != self.instance || lease.epoch != epoch => {
+                bail!("The signing lease is held by the instance '{}' (epoch {})", lease.holder, lease.epoch)
+            }
+            Some(lease) => {
+                ensure!(allow_expired || lease.expires_at > now(), "The signing lease expired");
+                Ok(())
+            }
+            None => bail!("The signing lease at {} is missing", self.lease_path.display()),
+        }
+    }
+
+    /// Extends the signing lease held by this instance with the given epoch.
+    /// Note: An expired lease may be renewed, as long as it was not transferred, since no other instance signed.
+    fn renew(&self, epoch: u64) -> Result<()> {
+        with_lease_lock(&self.lease_path, || {
+            self.check_lease(epoch, true)?;
+            let expires_at = now() + SIGNING_LEASE_DURATION_IN_SECS;
+            write_lease(&self.lease_path, &LeaseRecord { holder: self.instance.clone(), epoch, expires_at })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{MainnetV0, TestRng};
+
+    use rand::Rng;
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Returns a new path for a signing journal.
+    fn sample_path(rng: &mut TestRng) -> PathBuf {
+        std::env::temp_dir().join(format!("snarkos-failover-{}", rng.gen::<u64>()))
+    }
+
+    #[test]
+    fn test_failover() {
+        let rng = &mut TestRng::default();
+        let path = sample_path(rng);
+
+        // Ensure the active instance acquires the lease.
+        let active = Failover::<CurrentNetwork>::new("a", &path, false).unwrap();
+        assert!(active.is_active());
+        assert!(active.signing_journal().is_ok());
+        assert_eq!(read_lease(signing_lease_path(&path)).unwrap().unwrap().epoch, 1);
+        assert_eq!(active.tick(), None);
+
+        // Ensure another instance cannot start as active, but can start as a standby.
+        assert!(Failover::<CurrentNetwork>::new("b", &path, false).is_err());
+        let standby = Failover::<CurrentNetwork>::new("b", &path, true).unwrap();
+        assert!(!standby.is_active());
+        assert!(standby.signing_journal().is_err());
+        assert_eq!(standby.tick(), None);
+
+        // Transfer the lease, and ensure the active instance is fenced right away.
+        assert_eq!(transfer_lease(signing_lease_path(&path), "b").unwrap().epoch, 2);
+        assert!(active.signing_journal().is_err());
+        assert!(matches!(active.tick(), Some(FailoverEvent::SteppedDown(_))));
+        assert!(!active.is_active());
+
+        // Ensure the standby waits for the grace period before it is promoted.
+        assert_eq!(standby.tick(), None);
+        assert!(!standby.is_active());
+        *standby.state.lock() =
+            FailoverState::Standby { promoted_at: Some(now() - Failover::<CurrentNetwork>::GRACE_PERIOD_IN_SECS) };
+        assert_eq!(standby.tick(), Some(FailoverEvent::Promoted(2)));
+        assert!(standby.is_active());
+        assert!(standby.signing_journal().is_ok());
+
+        // Ensure the former active instance stays on standby.
+        assert_eq!(active.tick(), None);
+        assert!(!active.is_active());
+    }
+
+    #[test]
+    fn test_transfer_waits_for_renewal() {
+        let rng = &mut TestRng::default();
+        let path = sample_path(rng);
+        let lease_path = signing_lease_path(&path);
+        let active = Failover::<CurrentNetwork>::new("a", &path, false).unwrap();
+
+        // Hold the lock, as a renewal in progress would.
+        let lock_path = format!("{}.lock", lease_path.display());
+        let lock = OpenOptions::new().create(true).write(true).truncate(false).open(lock_path).unwrap();
+        lock.lock_exclusive().unwrap();
+        let transfer = {
+            let lease_path = lease_path.clone();
+            std::thread::spawn(move || transfer_lease(lease_path, "b").unwrap())
+        };
+        // Ensure the transfer waits until the renewal is done, so that it is never overwritten.
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        assert_eq!(read_lease(&lease_path).unwrap().unwrap().holder, "a");
+        drop(lock);
+        assert_eq!(transfer.join().unwrap().epoch, 2);
+        assert!(matches!(active.tick(), Some(FailoverEvent::SteppedDown(_))));
+        assert_eq!(read_lease(&lease_path).unwrap().unwrap().holder, "b");
+    }
+}
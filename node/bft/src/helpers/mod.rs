@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod audit_log;
+pub use audit_log::*;
+
 pub mod cache;
 pub use cache::*;
 
@@ -21,6 +24,15 @@ pub use channels::*;
 pub mod dag;
 pub use dag::*;
 
+pub mod failover;
+pub use failover::*;
+
+pub mod operator_lane;
+pub use operator_lane::*;
+
+pub mod participation;
+pub use participation::*;
+
 pub mod partition;
 pub use partition::*;
 
@@ -33,6 +45,9 @@ pub use proposal::*;
 pub mod proposal_cache;
 pub use proposal_cache::*;
 
+pub mod proposal_config;
+pub use proposal_config::*;
+
 pub mod ready;
 pub use ready::*;
 
@@ -42,12 +57,27 @@ pub use resolver::*;
 pub mod signed_proposals;
 pub use signed_proposals::*;
 
+pub mod signer;
+pub use signer::*;
+
+pub mod signing_journal;
+pub use signing_journal::*;
+
 pub mod storage;
 pub use storage::*;
 
+pub mod timeout_config;
+pub use timeout_config::*;
+
 pub mod timestamp;
 pub use timestamp::*;
 
+pub mod transaction_filter;
+pub use transaction_filter::*;
+
+pub mod verified_certificates;
+pub use verified_certificates::*;
+
 /// Formats an ID into a truncated identifier (for logging purposes).
 pub fn fmt_id(id: impl ToString) -> String {
     let id = id.to_string();
@@ -0,0 +1,111 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::block::Transaction,
+    prelude::{ensure, Address, Network, Result},
+};
+
+use indexmap::IndexSet;
+use std::str::FromStr;
+
+/// The priority lane of a validator for the transactions of its own operators (e.g. to manage its bond),
+/// which are included in the next batch proposal ahead of the fee ordering, up to a small quota per batch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OperatorLane<N: Network> {
+    /// The addresses of the operators, whose transactions are identified by their public fee payer.
+    operators: IndexSet<Address<N>>,
+    /// The maximum number of operator transactions prioritized in a batch proposal.
+    quota: usize,
+}
+
+impl<N: Network> Default for OperatorLane<N> {
+    /// Returns the empty lane, which prioritizes no transactions.
+    fn default() -> Self {
+        Self { operators: Default::default(), quota: Self::DEFAULT_QUOTA }
+    }
+}
+
+impl<N: Network> OperatorLane<N> {
+    /// The default number of operator transactions prioritized in a batch proposal.
+    pub const DEFAULT_QUOTA: usize = 2;
+    /// The maximum number of operator transactions prioritized in a batch proposal, so that the lane stays small.
+    pub const MAX_QUOTA: usize = 10;
+
+    /// Initializes a new lane, from the given operator addresses, and the given quota or the default.
+    pub fn parse(operators: &[String], quota: Option<usize>) -> Result<Self> {
+        let operators =
+            operators.iter().map(|operator| Address::from_str(operator)).collect::<Result<IndexSet<_>>>()?;
+        let quota = quota.unwrap_or(Self::DEFAULT_QUOTA);
+        ensure!(
+            (1..=Self::MAX_QUOTA).contains(&quota),
+            "The operator quota must be between 1 and {} transactions per batch",
+            Self::MAX_QUOTA
+        );
+        Ok(Self { operators, quota })
+    }
+
+    /// Returns `true` if the lane prioritizes no transactions.
+    pub fn is_empty(&self) -> bool {
+        self.operators.is_empty()
+    }
+
+    /// Returns the addresses of the operators.
+    pub const fn operators(&self) -> &IndexSet<Address<N>> {
+        &self.operators
+    }
+
+    /// Returns the maximum number of operator transactions prioritized in a batch proposal.
+    pub const fn quota(&self) -> usize {
+        self.quota
+    }
+
+    /// Returns `true` if the fee of the given transaction is paid publicly by one of the operators.
+    pub fn is_operator_transaction(&self, transaction: &Transaction<N>) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        transaction.fee_transition().and_then(|fee| fee.payer()).map_or(false, |payer| self.operators.contains(&payer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{MainnetV0, PrivateKey, TestRng};
+
+    type CurrentNetwork = MainnetV0;
+    type Lane = OperatorLane<CurrentNetwork>;
+
+    #[test]
+    fn test_parse() {
+        let rng = &mut TestRng::default();
+
+        // Ensure the empty lane is the default.
+        let lane = Lane::parse(&[], None).unwrap();
+        assert!(lane.is_empty());
+        assert_eq!(lane, Lane::default());
+
+        // Ensure the operators and the quota are parsed.
+        let operator = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        let lane = Lane::parse(&[operator.to_string()], Some(Lane::MAX_QUOTA)).unwrap();
+        assert!(lane.operators().contains(&operator));
+        assert_eq!(lane.quota(), Lane::MAX_QUOTA);
+
+        // Ensure the invalid operators and quotas are rejected.
+        assert!(Lane::parse(&["aleo1invalid".to_string()], None).is_err());
+        assert!(Lane::parse(&[operator.to_string()], Some(0)).is_err());
+        assert!(Lane::parse(&[operator.to_string()], Some(Lane::MAX_QUOTA + 1)).is_err());
+    }
+}
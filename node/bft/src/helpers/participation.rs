@@ -0,0 +1,268 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    console::{account::Address, network::Network},
+    ledger::narwhal::BatchCertificate,
+    prelude::{ensure, Result},
+};
+
+use std::collections::BTreeMap;
+
+/// The configuration of the participation alerts of a validator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParticipationConfig {
+    /// The number of committed rounds over which the participation is measured.
+    window_in_rounds: u64,
+    /// The participation, in percent, below which an alert is raised.
+    threshold_percent: u8,
+}
+
+impl Default for ParticipationConfig {
+    /// Returns the default configuration, which alerts below 80% participation over 100 rounds.
+    fn default() -> Self {
+        Self { window_in_rounds: 100, threshold_percent: 80 }
+    }
+}
+
+impl ParticipationConfig {
+    /// The maximum number of rounds over which the participation is measured.
+    pub const MAX_WINDOW_IN_ROUNDS: u64 = 10_000;
+    /// The minimum number of rounds over which the participation is measured.
+    pub const MIN_WINDOW_IN_ROUNDS: u64 = 10;
+
+    /// Initializes a new configuration, with the given values or the defaults, ensuring they are within bounds.
+    pub fn new(window_in_rounds: Option<u64>, threshold_percent: Option<u8>) -> Result<Self> {
+        let default = Self::default();
+        let window_in_rounds = window_in_rounds.unwrap_or(default.window_in_rounds);
+        ensure!(
+            (Self::MIN_WINDOW_IN_ROUNDS..=Self::MAX_WINDOW_IN_ROUNDS).contains(&window_in_rounds),
+            "The participation window must be between {} and {} rounds",
+            Self::MIN_WINDOW_IN_ROUNDS,
+            Self::MAX_WINDOW_IN_ROUNDS
+        );
+        let threshold_percent = threshold_percent.unwrap_or(default.threshold_percent);
+        ensure!(
+            (1..=100).contains(&threshold_percent),
+            "The participation threshold must be between 1 and 100 percent"
+        );
+        Ok(Self { window_in_rounds, threshold_percent })
+    }
+
+    /// Returns the number of committed rounds over which the participation is measured.
+    pub const fn window_in_rounds(&self) -> u64 {
+        self.window_in_rounds
+    }
+
+    /// Returns the participation, in percent, below which an alert is raised.
+    pub const fn threshold_percent(&self) -> u8 {
+        self.threshold_percent
+    }
+}
+
+/// The participation of the validator in the committed certificates of a round.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+struct RoundParticipation {
+    /// Whether the certificate of the validator was committed.
+    proposed: bool,
+    /// The number of committed certificates of the other validators.
+    num_certificates: usize,
+    /// The number of committed certificates of the other validators signed by the validator.
+    num_signed: usize,
+}
+
+/// The status of the participation of the validator.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParticipationStatus {
+    /// The participation is at or above the threshold.
+    Healthy,
+    /// The participation is below the threshold.
+    Degraded,
+}
+
+/// An alert raised when the participation of the validator crosses the threshold.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParticipationAlert {
+    /// The address of the validator.
+    pub validator: String,
+    /// The new status of the participation.
+    pub status: ParticipationStatus,
+    /// The share of the rounds with a committed certificate of the validator, in percent.
+    pub proposal_rate: f64,
+    /// The share of the committed certificates of the other validators signed by the validator, in percent.
+    pub signing_rate: f64,
+    /// The participation, in percent, below which an alert is raised.
+    pub threshold_percent: u8,
+    /// The number of committed rounds over which the participation is measured.
+    pub window_in_rounds: u64,
+    /// The latest committed round.
+    pub round: u64,
+}
+
+/// Tracks the participation of a validator in the committed certificates, over a sliding window of rounds.
+///
+/// A round counts as proposed if the certificate of the validator for that round was committed, and
+/// a certificate of another validator counts as signed if the validator is among its signers. As a
+/// certificate may be committed in the subdag of a later leader, the rounds are updated as they are committed.
+pub struct ParticipationTracker<N: Network> {
+    /// The address of the validator.
+    address: Address<N>,
+    /// The configuration of the alerts.
+    config: ParticipationConfig,
+    /// The participation of the validator in the rounds of the window.
+    rounds: BTreeMap<u64, RoundParticipation>,
+    /// The current status of the participation.
+    status: ParticipationStatus,
+}
+
+impl<N: Network> ParticipationTracker<N> {
+    /// Initializes a new tracker for the given validator.
+    pub fn new(address: Address<N>, config: ParticipationConfig) -> Self {
+        Self { address, config, rounds: Default::default(), status: ParticipationStatus::Healthy }
+    }
+
+    /// Returns the configuration of the alerts.
+    pub const fn config(&self) -> &ParticipationConfig {
+        &self.config
+    }
+
+    /// Returns the status of the participation, as of the latest check.
+    pub const fn status(&self) -> ParticipationStatus {
+        self.status
+    }
+
+    /// Records the given committed certificates of a round.
+    pub fn record<'a>(&mut self, round: u64, certificates: impl IntoIterator<Item = &'a BatchCertificate<N>>) {
+        let entry = self.rounds.entry(round).or_default();
+        for certificate in certificates {
+            match certificate.author() == self.address {
+                true => entry.proposed = true,
+                false => {
+                    entry.num_certificates += 1;
+                    if certificate.signatures().any(|signature| signature.to_address() == self.address) {
+                        entry.num_signed += 1;
+                    }
+                }
+            }
+        }
+        // Remove the rounds that fell out of the window.
+        if let Some(latest_round) = self.rounds.keys().next_back().copied() {
+            let start_round = latest_round.saturating_sub(self.config.window_in_rounds - 1);
+            self.rounds = self.rounds.split_off(&start_round);
+        }
+    }
+
+    /// Returns the share of the rounds with a committed certificate of the validator, in percent.
+    pub fn proposal_rate(&self) -> Option<f64> {
+        let num_proposed = self.rounds.values().filter(|participation| participation.proposed).count();
+        match self.rounds.len() {
+            0 => None,
+            num_rounds => Some(num_proposed as f64 * 100.0 / num_rounds as f64),
+        }
+    }
+
+    /// Returns the share of the committed certificates of the other validators signed by the validator, in percent.
+    pub fn signing_rate(&self) -> Option<f64> {
+        let num_certificates = self.rounds.values().map(|participation| participation.num_certificates).sum::<usize>();
+        let num_signed = self.rounds.values().map(|participation| participation.num_signed).sum::<usize>();
+        match num_certificates {
+            0 => None,
+            num_certificates => Some(num_signed as f64 * 100.0 / num_certificates as f64),
+        }
+    }
+
+    /// Returns an alert if the participation crossed the threshold since the previous check.
+    /// Note: The participation is only assessed once half of the window has been recorded, to avoid alerting on startup.
+    pub fn check(&mut self) -> Option<ParticipationAlert> {
+        if (self.rounds.len() as u64) * 2 < self.config.window_in_rounds {
+            return None;
+        }
+        let proposal_rate = self.proposal_rate().unwrap_or(0.0);
+        let signing_rate = self.signing_rate().unwrap_or(100.0);
+        let threshold = self.config.threshold_percent as f64;
+        let status = match proposal_rate < threshold || signing_rate < threshold {
+            true => ParticipationStatus::Degraded,
+            false => ParticipationStatus::Healthy,
+        };
+        if status == self.status {
+            return None;
+        }
+        self.status = status;
+        Some(ParticipationAlert {
+            validator: self.address.to_string(),
+            status,
+            proposal_rate,
+            signing_rate,
+            threshold_percent: self.config.threshold_percent,
+            window_in_rounds: self.config.window_in_rounds,
+            round: self.rounds.keys().next_back().copied().unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        ledger::narwhal::batch_certificate::test_helpers::sample_batch_certificate_for_round,
+        prelude::{MainnetV0, TestRng, Uniform},
+    };
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_participation_config() {
+        assert_eq!(ParticipationConfig::new(None, None).unwrap(), ParticipationConfig::default());
+        assert!(ParticipationConfig::new(Some(ParticipationConfig::MIN_WINDOW_IN_ROUNDS - 1), None).is_err());
+        assert!(ParticipationConfig::new(Some(ParticipationConfig::MAX_WINDOW_IN_ROUNDS + 1), None).is_err());
+        assert!(ParticipationConfig::new(None, Some(0)).is_err());
+        assert!(ParticipationConfig::new(None, Some(101)).is_err());
+        let config = ParticipationConfig::new(Some(20), Some(50)).unwrap();
+        assert_eq!(config.window_in_rounds(), 20);
+        assert_eq!(config.threshold_percent(), 50);
+    }
+
+    #[test]
+    fn test_participation_tracker() {
+        let rng = &mut TestRng::default();
+
+        // Track a validator that authored none of the sampled certificates, nor signed them.
+        let address = Address::<CurrentNetwork>::rand(rng);
+        let config = ParticipationConfig::new(Some(10), Some(80)).unwrap();
+        let mut tracker = ParticipationTracker::new(address, config);
+        assert_eq!(tracker.proposal_rate(), None);
+        assert_eq!(tracker.signing_rate(), None);
+
+        // Ensure no alert is raised before half of the window is recorded.
+        for round in 1..=4 {
+            tracker.record(round, &[sample_batch_certificate_for_round(round, rng)]);
+            assert_eq!(tracker.check(), None);
+        }
+        // Ensure an alert is raised once, when the participation is assessed.
+        tracker.record(5, &[sample_batch_certificate_for_round(5, rng)]);
+        let alert = tracker.check().unwrap();
+        assert_eq!(alert.status, ParticipationStatus::Degraded);
+        assert_eq!(alert.proposal_rate, 0.0);
+        assert_eq!(alert.signing_rate, 0.0);
+        assert_eq!(alert.round, 5);
+        assert_eq!(tracker.check(), None);
+
+        // Ensure the window slides.
+        for round in 6..=30 {
+            tracker.record(round, &[sample_batch_certificate_for_round(round, rng)]);
+        }
+        assert_eq!(tracker.rounds.len(), 10);
+        assert_eq!(tracker.rounds.keys().next(), Some(&21));
+    }
+}
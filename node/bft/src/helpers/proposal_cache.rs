@@ -108,12 +108,18 @@ impl<N: Network> ProposalCache<N> {
     /// Store the proposal cache to the file system.
     pub fn store(&self, dev: Option<u16>) -> Result<()> {
         let path = proposal_cache_path(N::ID, dev);
-        info!("Storing the proposal cache to {}...", path.display());
+        debug!("Storing the proposal cache to {}...", path.display());
 
         // Serialize the proposal cache.
         let bytes = self.to_bytes_le()?;
-        // Store the proposal cache to the file system.
-        fs::write(&path, bytes)
+        // Store the proposal cache to a temporary file, and then replace the proposal cache with it,
+        // so that a crash while storing never leaves a partial proposal cache behind.
+        let tmp_path = path.with_extension("tmp");
+        let mut file = fs::File::create(&tmp_path)
+            .map_err(|err| anyhow!("Couldn't write the proposal cache to {} - {err}", tmp_path.display()))?;
+        file.write_all(&bytes)?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, &path)
             .map_err(|err| anyhow!("Couldn't write the proposal cache to {} - {err}", path.display()))?;
 
         Ok(())
@@ -0,0 +1,297 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{MAX_BATCH_DELAY_IN_MS, MAX_WORKERS, MIN_BATCH_DELAY_IN_SECS};
+use snarkvm::{
+    console::network::Network,
+    ledger::narwhal::BatchHeader,
+    prelude::{ensure, Result},
+};
+
+use std::marker::PhantomData;
+
+/// The order in which the primary selects the transmissions for a batch proposal.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TransmissionOrdering {
+    /// The transmissions are selected in the order they were received.
+    #[default]
+    Arrival,
+    /// The solutions are selected first, and then the transactions by their priority fee and fee per byte.
+    FeePriority,
+}
+
+/// The configuration of the batch proposals of the primary, which trades off throughput and latency.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ProposalConfig<N: Network> {
+    /// The interval between the batch proposals, in milliseconds.
+    interval_in_ms: u64,
+    /// The maximum number of transmissions in a batch.
+    max_transmissions: usize,
+    /// The maximum total size of the transmissions in a batch, in bytes.
+    max_size_in_bytes: usize,
+    /// The maximum size of a single transmission in a batch, in bytes.
+    max_transmission_size: usize,
+    /// The maximum number of solutions in a batch.
+    max_solutions: usize,
+    /// The order in which the transmissions are selected.
+    ordering: TransmissionOrdering,
+    /// If `true`, the finalize execution of a transaction is speculated before it is proposed.
+    speculate: bool,
+    /// The number of workers the transmissions are split between.
+    num_workers: u8,
+    /// PhantomData.
+    _phantom: PhantomData<N>,
+}
+
+impl<N: Network> Default for ProposalConfig<N> {
+    /// Returns the default configuration, which matches the protocol limits.
+    fn default() -> Self {
+        Self {
+            interval_in_ms: Self::MAX_INTERVAL_IN_MS,
+            max_transmissions: Self::MAX_TRANSMISSIONS,
+            max_size_in_bytes: Self::MAX_SIZE_IN_BYTES,
+            max_transmission_size: Self::MAX_TRANSMISSION_SIZE_IN_BYTES,
+            max_solutions: Self::MAX_SOLUTIONS,
+            ordering: TransmissionOrdering::Arrival,
+            speculate: false,
+            num_workers: 1,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<N: Network> ProposalConfig<N> {
+    /// The maximum interval between the batch proposals, as the protocol timeouts are derived from it.
+    pub const MAX_INTERVAL_IN_MS: u64 = MAX_BATCH_DELAY_IN_MS;
+    /// The maximum size of a batch, so that a batch fits the maximum number of the largest transactions.
+    pub const MAX_SIZE_IN_BYTES: usize = Self::MAX_TRANSMISSIONS * N::MAX_TRANSACTION_SIZE;
+    /// The maximum number of solutions in a batch, as a block does not include more solutions.
+    pub const MAX_SOLUTIONS: usize = N::MAX_SOLUTIONS;
+    /// The maximum number of transmissions in a batch, as the peers reject larger batches.
+    pub const MAX_TRANSMISSIONS: usize = BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH;
+    /// The maximum size of a transmission, as the peers reject larger transactions.
+    pub const MAX_TRANSMISSION_SIZE_IN_BYTES: usize = N::MAX_TRANSACTION_SIZE;
+    /// The minimum interval between the batch proposals, as the peers reject proposals that are more frequent.
+    pub const MIN_INTERVAL_IN_MS: u64 = MIN_BATCH_DELAY_IN_SECS * 1000;
+    /// The minimum size of a batch, so that a batch fits the largest transaction.
+    pub const MIN_SIZE_IN_BYTES: usize = N::MAX_TRANSACTION_SIZE;
+    /// The minimum size of a transmission, so that the common executions still fit.
+    pub const MIN_TRANSMISSION_SIZE_IN_BYTES: usize = 32 * 1024;
+
+    /// Initializes a new configuration, with the given values or the defaults, ensuring they are within bounds.
+    pub fn new(
+        interval_in_ms: Option<u64>,
+        max_transmissions: Option<usize>,
+        max_size_in_bytes: Option<usize>,
+    ) -> Result<Self> {
+        let interval_in_ms = interval_in_ms.unwrap_or(Self::MAX_INTERVAL_IN_MS);
+        ensure!(
+            (Self::MIN_INTERVAL_IN_MS..=Self::MAX_INTERVAL_IN_MS).contains(&interval_in_ms),
+            "The batch proposal interval must be between {} and {} ms",
+            Self::MIN_INTERVAL_IN_MS,
+            Self::MAX_INTERVAL_IN_MS
+        );
+        let max_transmissions = max_transmissions.unwrap_or(Self::MAX_TRANSMISSIONS);
+        ensure!(
+            (1..=Self::MAX_TRANSMISSIONS).contains(&max_transmissions),
+            "The maximum number of transmissions per batch must be between 1 and {}",
+            Self::MAX_TRANSMISSIONS
+        );
+        let max_size_in_bytes = max_size_in_bytes.unwrap_or(Self::MAX_SIZE_IN_BYTES);
+        ensure!(
+            (Self::MIN_SIZE_IN_BYTES..=Self::MAX_SIZE_IN_BYTES).contains(&max_size_in_bytes),
+            "The maximum batch size must be between {} and {} bytes",
+            Self::MIN_SIZE_IN_BYTES,
+            Self::MAX_SIZE_IN_BYTES
+        );
+        Ok(Self {
+            interval_in_ms,
+            max_transmissions,
+            max_size_in_bytes,
+            max_transmission_size: Self::MAX_TRANSMISSION_SIZE_IN_BYTES,
+            max_solutions: Self::MAX_SOLUTIONS,
+            ordering: TransmissionOrdering::Arrival,
+            speculate: false,
+            num_workers: 1,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Sets the maximum size of a single transmission and the maximum number of solutions in a batch,
+    /// with the given values or the defaults, ensuring they are within bounds.
+    pub fn with_transmission_limits(
+        mut self,
+        max_transmission_size: Option<usize>,
+        max_solutions: Option<usize>,
+    ) -> Result<Self> {
+        let max_transmission_size = max_transmission_size.unwrap_or(Self::MAX_TRANSMISSION_SIZE_IN_BYTES);
+        ensure!(
+            (Self::MIN_TRANSMISSION_SIZE_IN_BYTES..=Self::MAX_TRANSMISSION_SIZE_IN_BYTES)
+                .contains(&max_transmission_size),
+            "The maximum transmission size must be between {} and {} bytes",
+            Self::MIN_TRANSMISSION_SIZE_IN_BYTES,
+            Self::MAX_TRANSMISSION_SIZE_IN_BYTES
+        );
+        let max_solutions = max_solutions.unwrap_or(Self::MAX_SOLUTIONS);
+        ensure!(
+            (1..=Self::MAX_SOLUTIONS).contains(&max_solutions),
+            "The maximum number of solutions per batch must be between 1 and {}",
+            Self::MAX_SOLUTIONS
+        );
+        self.max_transmission_size = max_transmission_size;
+        self.max_solutions = max_solutions;
+        Ok(self)
+    }
+
+    /// Sets the order in which the transmissions are selected.
+    pub fn with_ordering(mut self, ordering: TransmissionOrdering) -> Self {
+        self.ordering = ordering;
+        self
+    }
+
+    /// Sets whether the finalize execution of a transaction is speculated before it is proposed,
+    /// so that the transactions that would be rejected are skipped.
+    pub fn with_speculation(mut self, speculate: bool) -> Self {
+        self.speculate = speculate;
+        self
+    }
+
+    /// Sets the number of workers the transmissions are split between, with the given value or the default (1),
+    /// ensuring it is within bounds. The workers share the connections of the gateway, and open no ports.
+    pub fn with_num_workers(mut self, num_workers: Option<u8>) -> Result<Self> {
+        let num_workers = num_workers.unwrap_or(1);
+        ensure!((1..=MAX_WORKERS).contains(&num_workers), "The number of workers must be between 1 and {MAX_WORKERS}");
+        // Ensure every worker contributes to the batch proposals.
+        ensure!(
+            num_workers as usize <= self.max_transmissions,
+            "The number of workers must not exceed the maximum number of transmissions per batch"
+        );
+        self.num_workers = num_workers;
+        Ok(self)
+    }
+
+    /// Returns the interval between the batch proposals, in milliseconds.
+    pub const fn interval_in_ms(&self) -> u64 {
+        self.interval_in_ms
+    }
+
+    /// Returns the maximum number of transmissions in a batch.
+    pub const fn max_transmissions(&self) -> usize {
+        self.max_transmissions
+    }
+
+    /// Returns the maximum total size of the transmissions in a batch, in bytes.
+    pub const fn max_size_in_bytes(&self) -> usize {
+        self.max_size_in_bytes
+    }
+
+    /// Returns the maximum size of a single transmission in a batch, in bytes.
+    pub const fn max_transmission_size(&self) -> usize {
+        self.max_transmission_size
+    }
+
+    /// Returns the maximum number of solutions in a batch.
+    pub const fn max_solutions(&self) -> usize {
+        self.max_solutions
+    }
+
+    /// Returns the order in which the transmissions are selected.
+    pub const fn ordering(&self) -> TransmissionOrdering {
+        self.ordering
+    }
+
+    /// Returns `true` if the finalize execution of a transaction is speculated before it is proposed.
+    pub const fn speculate(&self) -> bool {
+        self.speculate
+    }
+
+    /// Returns the number of workers the transmissions are split between.
+    pub const fn num_workers(&self) -> u8 {
+        self.num_workers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::MainnetV0;
+
+    type CurrentNetwork = MainnetV0;
+    type Config = ProposalConfig<CurrentNetwork>;
+
+    #[test]
+    fn test_proposal_config() {
+        // Ensure the defaults match the protocol limits.
+        assert_eq!(Config::new(None, None, None).unwrap(), Config::default());
+        assert_eq!(Config::default().interval_in_ms(), MAX_BATCH_DELAY_IN_MS);
+        assert_eq!(Config::default().max_transmissions(), BatchHeader::<CurrentNetwork>::MAX_TRANSMISSIONS_PER_BATCH);
+        assert_eq!(Config::default().ordering(), TransmissionOrdering::Arrival);
+
+        // Ensure the ordering can be set.
+        let config = Config::default().with_ordering(TransmissionOrdering::FeePriority);
+        assert_eq!(config.ordering(), TransmissionOrdering::FeePriority);
+
+        // Ensure the speculation is disabled by default, and can be enabled.
+        assert!(!Config::default().speculate());
+        assert!(Config::default().with_speculation(true).speculate());
+
+        // Ensure the values within bounds are accepted.
+        let config = Config::new(Some(Config::MIN_INTERVAL_IN_MS), Some(1), Some(Config::MIN_SIZE_IN_BYTES)).unwrap();
+        assert_eq!(config.interval_in_ms(), Config::MIN_INTERVAL_IN_MS);
+        assert_eq!(config.max_transmissions(), 1);
+        assert_eq!(config.max_size_in_bytes(), Config::MIN_SIZE_IN_BYTES);
+
+        // Ensure the values out of bounds are rejected.
+        assert!(Config::new(Some(Config::MIN_INTERVAL_IN_MS - 1), None, None).is_err());
+        assert!(Config::new(Some(Config::MAX_INTERVAL_IN_MS + 1), None, None).is_err());
+        assert!(Config::new(None, Some(0), None).is_err());
+        assert!(Config::new(None, Some(Config::MAX_TRANSMISSIONS + 1), None).is_err());
+        assert!(Config::new(None, None, Some(Config::MIN_SIZE_IN_BYTES - 1)).is_err());
+        assert!(Config::new(None, None, Some(Config::MAX_SIZE_IN_BYTES + 1)).is_err());
+    }
+
+    #[test]
+    fn test_transmission_limits() {
+        // Ensure the defaults match the protocol limits.
+        assert_eq!(Config::default().with_transmission_limits(None, None).unwrap(), Config::default());
+        assert_eq!(Config::default().max_transmission_size(), CurrentNetwork::MAX_TRANSACTION_SIZE);
+        assert_eq!(Config::default().max_solutions(), CurrentNetwork::MAX_SOLUTIONS);
+
+        // Ensure the values within bounds are accepted.
+        let config =
+            Config::default().with_transmission_limits(Some(Config::MIN_TRANSMISSION_SIZE_IN_BYTES), Some(1)).unwrap();
+        assert_eq!(config.max_transmission_size(), Config::MIN_TRANSMISSION_SIZE_IN_BYTES);
+        assert_eq!(config.max_solutions(), 1);
+
+        // Ensure the values out of bounds are rejected.
+        let limits = |size, solutions| Config::default().with_transmission_limits(size, solutions);
+        assert!(limits(Some(Config::MIN_TRANSMISSION_SIZE_IN_BYTES - 1), None).is_err());
+        assert!(limits(Some(Config::MAX_TRANSMISSION_SIZE_IN_BYTES + 1), None).is_err());
+        assert!(limits(None, Some(0)).is_err());
+        assert!(limits(None, Some(Config::MAX_SOLUTIONS + 1)).is_err());
+    }
+
+    #[test]
+    fn test_num_workers() {
+        // Ensure there is a single worker by default.
+        assert_eq!(Config::default().num_workers(), 1);
+        assert_eq!(Config::default().with_num_workers(None).unwrap(), Config::default());
+
+        // Ensure the values within bounds are accepted, and the values out of bounds are rejected.
+        assert_eq!(Config::default().with_num_workers(Some(MAX_WORKERS)).unwrap().num_workers(), MAX_WORKERS);
+        assert!(Config::default().with_num_workers(Some(0)).is_err());
+        assert!(Config::default().with_num_workers(Some(MAX_WORKERS + 1)).is_err());
+        assert!(Config::new(None, Some(1), None).unwrap().with_num_workers(Some(2)).is_err());
+    }
+}
@@ -23,7 +23,7 @@ use snarkvm::{
 
 use indexmap::{IndexMap, IndexSet};
 use parking_lot::RwLock;
-use std::sync::Arc;
+use std::{cmp::Reverse, sync::Arc};
 
 #[derive(Clone, Debug)]
 pub struct Ready<N: Network> {
@@ -127,6 +127,30 @@ impl<N: Network> Ready<N> {
         transmissions.drain(range).collect::<IndexMap<_, _>>()
     }
 
+    /// Orders the transmissions by fee priority: the solutions first, and then the transactions by their
+    /// priority fee and fee per byte, in descending order. Ties are kept in the order they were received.
+    /// Note: This method deserializes the transactions, and should be called from a blocking task.
+    pub fn sort_by_fee_priority(&self) {
+        self.transmissions.write().sort_by_cached_key(|id, transmission| fee_priority(id, transmission));
+    }
+
+    /// Moves up to the specified number of transmissions that match the given predicate to the front of the queue,
+    /// keeping their relative order, and returns the number of moved transmissions.
+    pub fn prioritize(&self, num_transmissions: usize, predicate: impl Fn(&Transmission<N>) -> bool) -> usize {
+        // Acquire the write lock.
+        let mut transmissions = self.transmissions.write();
+        // Determine the transmissions to prioritize.
+        let prioritized = transmissions
+            .iter()
+            .filter(|(_, transmission)| predicate(transmission))
+            .take(num_transmissions)
+            .map(|(id, _)| *id)
+            .collect::<IndexSet<_>>();
+        // Move the prioritized transmissions to the front, as the sort is stable.
+        transmissions.sort_by_cached_key(|id, _| !prioritized.contains(id));
+        prioritized.len()
+    }
+
     /// Clears all solutions from the ready queue.
     pub(crate) fn clear_solutions(&self) {
         // Acquire the write lock.
@@ -136,6 +160,31 @@ impl<N: Network> Ready<N> {
     }
 }
 
+/// Returns the sort key of the given transmission for the fee-priority ordering, where lower keys come first.
+/// Note: The transactions whose fees cannot be determined are placed last.
+fn fee_priority<N: Network>(
+    id: &TransmissionID<N>,
+    transmission: &Transmission<N>,
+) -> (u8, Reverse<u64>, Reverse<u64>) {
+    // Retrieve the priority fee and the fee per byte of the transaction.
+    let fees = || -> Result<(u64, u64)> {
+        let Transmission::Transaction(data) = transmission else {
+            bail!("Transmission '{id}' is not a transaction");
+        };
+        let num_bytes = transmission.to_bytes_le()?.len().max(1) as u64;
+        let transaction = data.clone().deserialize_blocking()?;
+        Ok((*transaction.priority_fee_amount()?, *transaction.fee_amount()? / num_bytes))
+    };
+    match id {
+        TransmissionID::Solution(..) => (0, Reverse(0), Reverse(0)),
+        TransmissionID::Transaction(..) => match fees() {
+            Ok((priority_fee, fee_per_byte)) => (1, Reverse(priority_fee), Reverse(fee_per_byte)),
+            Err(_) => (2, Reverse(0), Reverse(0)),
+        },
+        TransmissionID::Ratification => (2, Reverse(0), Reverse(0)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -231,4 +280,64 @@ mod tests {
         // Check the number of transmissions.
         assert_eq!(ready.num_transmissions(), 1);
     }
+
+    #[test]
+    fn test_ready_sort_by_fee_priority() {
+        let rng = &mut TestRng::default();
+
+        // Sample random fake bytes.
+        let data = |rng: &mut TestRng| Data::Buffer(Bytes::from((0..512).map(|_| rng.gen::<u8>()).collect::<Vec<_>>()));
+
+        // Initialize the ready queue.
+        let ready = Ready::<CurrentNetwork>::new();
+
+        // Initialize a transaction whose fees cannot be determined, and two solutions.
+        let transaction_id = TransmissionID::Transaction(Default::default());
+        let solution_id_1 = TransmissionID::Solution(rng.gen::<u64>().into());
+        let solution_id_2 = TransmissionID::Solution(rng.gen::<u64>().into());
+
+        // Insert the transaction before the solutions.
+        assert!(ready.insert(transaction_id, Transmission::Transaction(data(rng))));
+        assert!(ready.insert(solution_id_1, Transmission::Solution(data(rng))));
+        assert!(ready.insert(solution_id_2, Transmission::Solution(data(rng))));
+
+        // Sort the ready queue.
+        ready.sort_by_fee_priority();
+
+        // Ensure the solutions come first, in the order they were received, and the transaction last.
+        let transmission_ids = vec![solution_id_1, solution_id_2, transaction_id].into_iter().collect::<IndexSet<_>>();
+        assert_eq!(ready.transmission_ids(), transmission_ids);
+    }
+
+    #[test]
+    fn test_ready_prioritize() {
+        let rng = &mut TestRng::default();
+
+        // Sample random fake bytes.
+        let data = |rng: &mut TestRng| Data::Buffer(Bytes::from((0..512).map(|_| rng.gen::<u8>()).collect::<Vec<_>>()));
+
+        // Initialize the ready queue.
+        let ready = Ready::<CurrentNetwork>::new();
+
+        // Insert a transaction, followed by two solutions.
+        let transaction_id = TransmissionID::Transaction(Default::default());
+        let solution_id_1 = TransmissionID::Solution(rng.gen::<u64>().into());
+        let solution_id_2 = TransmissionID::Solution(rng.gen::<u64>().into());
+        assert!(ready.insert(transaction_id, Transmission::Transaction(data(rng))));
+        assert!(ready.insert(solution_id_1, Transmission::Solution(data(rng))));
+        assert!(ready.insert(solution_id_2, Transmission::Solution(data(rng))));
+
+        // Ensure only up to the given number of matching transmissions are moved to the front.
+        let is_solution =
+            |transmission: &Transmission<CurrentNetwork>| matches!(transmission, Transmission::Solution(..));
+        assert_eq!(ready.prioritize(1, is_solution), 1);
+        let transmission_ids = vec![solution_id_1, transaction_id, solution_id_2].into_iter().collect::<IndexSet<_>>();
+        assert_eq!(ready.transmission_ids(), transmission_ids);
+
+        // Ensure the other transmissions keep their order.
+        let is_transaction = |transmission: &Transmission<CurrentNetwork>| !is_solution(transmission);
+        assert_eq!(ready.prioritize(5, is_transaction), 1);
+        let transmission_ids = vec![transaction_id, solution_id_1, solution_id_2].into_iter().collect::<IndexSet<_>>();
+        assert_eq!(ready.transmission_ids(), transmission_ids);
+    }
 }
@@ -0,0 +1,294 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::spawn_blocking;
+use snarkos_account::Account;
+use snarkvm::{
+    console::network::Network,
+    prelude::{anyhow, bail, ensure, Address, Field, FromStr, Result, Signature},
+};
+
+use reqwest::{Certificate, Client, Identity};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// The signer of the validator, which holds (or has access to) the account key of the validator.
+#[derive(Clone)]
+pub enum Signer<N: Network> {
+    /// The account key resides on this node.
+    Local(Account<N>),
+    /// The account key resides on an external signer service.
+    Remote(RemoteSigner<N>),
+}
+
+impl<N: Network> From<Account<N>> for Signer<N> {
+    /// Initializes a local signer from the given account.
+    fn from(account: Account<N>) -> Self {
+        Self::Local(account)
+    }
+}
+
+impl<N: Network> From<RemoteSigner<N>> for Signer<N> {
+    /// Initializes a signer from the given remote signer.
+    fn from(remote_signer: RemoteSigner<N>) -> Self {
+        Self::Remote(remote_signer)
+    }
+}
+
+impl<N: Network> Signer<N> {
+    /// Returns the address of the signer.
+    pub fn address(&self) -> Address<N> {
+        match self {
+            Self::Local(account) => account.address(),
+            Self::Remote(remote_signer) => remote_signer.address(),
+        }
+    }
+
+    /// Returns `true` if the account key resides on an external signer service.
+    pub const fn is_remote(&self) -> bool {
+        matches!(self, Self::Remote(_))
+    }
+
+    /// Returns a signature for the given message (as field elements).
+    pub async fn sign(&self, message: &[Field<N>]) -> Result<Signature<N>> {
+        match self {
+            Self::Local(account) => {
+                let (account, message) = (account.clone(), message.to_vec());
+                spawn_blocking!(account.sign(&message, &mut rand::thread_rng()))
+            }
+            Self::Remote(remote_signer) => remote_signer.sign(message).await,
+        }
+    }
+
+    /// Returns a signature for the given message (as bytes).
+    pub async fn sign_bytes(&self, message: &[u8]) -> Result<Signature<N>> {
+        match self {
+            Self::Local(account) => account.sign_bytes(message, &mut rand::thread_rng()),
+            Self::Remote(remote_signer) => remote_signer.sign_bytes(message).await,
+        }
+    }
+}
+
+/// The message of a signing request to the remote signer.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum SignMessage {
+    /// The message as field elements.
+    Fields(Vec<String>),
+    /// The message as bytes.
+    Bytes(Vec<u8>),
+}
+
+/// The signing request to the remote signer.
+#[derive(Serialize)]
+struct SignRequest {
+    /// The address of the expected signer.
+    address: String,
+    /// The message to sign.
+    message: SignMessage,
+}
+
+/// The signing response from the remote signer.
+#[derive(Deserialize)]
+struct SignResponse {
+    /// The signature.
+    signature: String,
+}
+
+/// The address response from the remote signer.
+#[derive(Deserialize)]
+struct AddressResponse {
+    /// The address of the signer.
+    address: String,
+}
+
+/// The configuration of the remote signer of a validator, as imported after an offline key ceremony.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteSignerConfig {
+    /// The HTTPS endpoint of the signer service.
+    pub endpoint: String,
+    /// The expected address of the signer.
+    pub address: String,
+    /// The path to the PEM-encoded CA certificate of the signer service.
+    pub ca_certificate: PathBuf,
+    /// The path to the PEM-encoded client certificate.
+    pub certificate: PathBuf,
+    /// The path to the PEM-encoded (PKCS #8) client key.
+    pub key: PathBuf,
+}
+
+impl RemoteSignerConfig {
+    /// Loads the remote signer configuration from the given path.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)
+            .map_err(|e| anyhow!("Couldn't read the remote signer config at {} - {e}", path.display()))?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// Saves the remote signer configuration to the given path.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        Ok(fs::write(path, serde_json::to_vec_pretty(self)?)?)
+    }
+}
+
+/// A client to an external signer service, which holds the account key of the validator,
+/// so that the key never resides on the internet-facing node.
+///
+/// The signer service is reached over HTTPS with mutual TLS, and exposes two endpoints:
+/// - `GET /address`, which returns `{ "address": "aleo1..." }`.
+/// - `POST /sign`, which takes `{ "address": "aleo1...", "message": { "fields": ["..."] } }`
+///   (or `{ "bytes": [...] }`), and returns `{ "signature": "sign1..." }`.
+///
+/// Every returned signature is verified against the signer address before it is used.
+#[derive(Clone)]
+pub struct RemoteSigner<N: Network> {
+    /// The base URL of the signer service.
+    endpoint: String,
+    /// The address of the signer.
+    address: Address<N>,
+    /// The HTTP client.
+    client: Client,
+}
+
+impl<N: Network> RemoteSigner<N> {
+    /// The timeout for a request to the signer service.
+    const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Connects to the signer service at the given endpoint, authenticating with mutual TLS,
+    /// where the CA certificate, client certificate, and client key are PEM-encoded.
+    pub async fn connect(endpoint: &str, ca_certificate: &[u8], certificate: &[u8], key: &[u8]) -> Result<Self> {
+        // Ensure the endpoint is served over TLS.
+        ensure!(endpoint.starts_with("https://"), "The remote signer endpoint must use 'https://'");
+        // Initialize the HTTP client, which only trusts the given CA, and presents the given identity.
+        let client = Client::builder()
+            .add_root_certificate(Certificate::from_pem(ca_certificate)?)
+            .tls_built_in_root_certs(false)
+            .identity(Identity::from_pkcs8_pem(certificate, key)?)
+            .https_only(true)
+            .timeout(Self::REQUEST_TIMEOUT)
+            .build()?;
+        let endpoint = endpoint.trim_end_matches('/').to_string();
+        // Retrieve the address of the signer.
+        let response = client.get(format!("{endpoint}/address")).send().await?.error_for_status()?;
+        let address = Address::from_str(&response.json::<AddressResponse>().await?.address)?;
+        Ok(Self { endpoint, address, client })
+    }
+
+    /// Connects to the signer service with the given configuration,
+    /// and ensures the signer holds the key of the expected address.
+    pub async fn connect_with_config(config: &RemoteSignerConfig) -> Result<Self> {
+        let expected = Address::<N>::from_str(&config.address)?;
+        let remote_signer = Self::connect(
+            &config.endpoint,
+            &fs::read(&config.ca_certificate)?,
+            &fs::read(&config.certificate)?,
+            &fs::read(&config.key)?,
+        )
+        .await?;
+        // Ensure the signer holds the key of the expected address.
+        ensure!(
+            remote_signer.address == expected,
+            "The remote signer holds the key of '{}', instead of '{expected}'",
+            remote_signer.address
+        );
+        Ok(remote_signer)
+    }
+
+    /// Returns the address of the signer.
+    pub const fn address(&self) -> Address<N> {
+        self.address
+    }
+
+    /// Returns a signature for the given message (as field elements), from the signer service.
+    pub async fn sign(&self, message: &[Field<N>]) -> Result<Signature<N>> {
+        let signature =
+            self.request(SignMessage::Fields(message.iter().map(|field| field.to_string()).collect())).await?;
+        // Ensure the signature is valid.
+        match signature.verify(&self.address, message) {
+            true => Ok(signature),
+            false => bail!("The remote signer returned an invalid signature"),
+        }
+    }
+
+    /// Returns a signature for the given message (as bytes), from the signer service.
+    pub async fn sign_bytes(&self, message: &[u8]) -> Result<Signature<N>> {
+        let signature = self.request(SignMessage::Bytes(message.to_vec())).await?;
+        // Ensure the signature is valid.
+        match signature.verify_bytes(&self.address, message) {
+            true => Ok(signature),
+            false => bail!("The remote signer returned an invalid signature"),
+        }
+    }
+
+    /// Sends a signing request to the signer service, and returns the signature.
+    async fn request(&self, message: SignMessage) -> Result<Signature<N>> {
+        let request = SignRequest { address: self.address.to_string(), message };
+        let response =
+            self.client.post(format!("{}/sign", self.endpoint)).json(&request).send().await?.error_for_status()?;
+        Signature::from_str(&response.json::<SignResponse>().await?.signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{MainnetV0, TestRng, Uniform};
+
+    type CurrentNetwork = MainnetV0;
+
+    #[tokio::test]
+    async fn test_local_signer() {
+        let rng = &mut TestRng::default();
+
+        let account = Account::<CurrentNetwork>::new(rng).unwrap();
+        let signer = Signer::from(account.clone());
+        assert_eq!(signer.address(), account.address());
+        assert!(!signer.is_remote());
+
+        // Ensure the signatures verify against the address.
+        let message = [Field::rand(rng), Field::rand(rng)];
+        assert!(signer.sign(&message).await.unwrap().verify(&account.address(), &message));
+        let message = b"hello world";
+        assert!(signer.sign_bytes(message).await.unwrap().verify_bytes(&account.address(), message));
+    }
+
+    #[tokio::test]
+    async fn test_remote_signer_requires_tls() {
+        let result = RemoteSigner::<CurrentNetwork>::connect("http://127.0.0.1:4000", b"", b"", b"").await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_remote_signer_config() {
+        let config = RemoteSignerConfig {
+            endpoint: "https://signer:8443".to_string(),
+            address: "aleo1rhgdu77hgyqd3xjj8ucu3jj9r2krwz6mnzyd80gncr5fxcwlh5rsvzp9px".to_string(),
+            ca_certificate: PathBuf::from("ca.pem"),
+            certificate: PathBuf::from("cert.pem"),
+            key: PathBuf::from("key.pem"),
+        };
+        let path = std::env::temp_dir().join(format!("snarkos-remote-signer-{}.json", rand::random::<u64>()));
+        config.save(&path).unwrap();
+        assert_eq!(RemoteSignerConfig::load(&path).unwrap(), config);
+        fs::remove_file(&path).unwrap();
+
+        // Ensure a missing config is rejected.
+        assert!(RemoteSignerConfig::load(&path).is_err());
+    }
+}
@@ -0,0 +1,277 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    console::{account::Address, network::Network, types::Field},
+    prelude::{anyhow, bail, ensure, FromBytes, Result, ToBytes},
+    utilities::to_bytes_le,
+};
+
+use aleo_std::{aleo_ledger_dir, StorageMode};
+use fs2::FileExt;
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// Returns the path where the signing journal may be stored.
+pub fn signing_journal_path(network: u16, dev: Option<u16>) -> PathBuf {
+    const SIGNING_JOURNAL_FILE_NAME: &str = "signing-journal";
+
+    // Obtain the path to the ledger.
+    let mut path = aleo_ledger_dir(network, StorageMode::from(dev));
+    // Go to the folder right above the ledger.
+    path.pop();
+    // Append the signing journal's file name.
+    match dev {
+        Some(id) => path.push(&format!(".{SIGNING_JOURNAL_FILE_NAME}-{}-{}", network, id)),
+        None => path.push(&format!("{SIGNING_JOURNAL_FILE_NAME}-{}", network)),
+    }
+
+    path
+}
+
+/// The state of the signing journal.
+struct JournalState<N: Network> {
+    /// The journal file, opened for appending.
+    file: File,
+    /// A map of `author` to the latest (`round`, `batch ID`) signed for the author.
+    entries: HashMap<Address<N>, (u64, Field<N>)>,
+    /// The number of records in the journal file.
+    num_records: usize,
+}
+
+/// A persistent journal of the batches signed by this validator, which protects against equivocation.
+///
+/// For every batch author (including this validator), the journal records the latest round and batch ID signed,
+/// and refuses to sign a different batch for that round, or any batch for an earlier round. Every record is
+/// synced to disk before the signature is released, so the protection holds across restarts. The journal
+/// is locked while it is open, so a duplicate instance sharing the journal refuses to start.
+pub struct SigningJournal<N: Network> {
+    /// The path of the journal file.
+    path: PathBuf,
+    /// The lock file, which is held exclusively while the journal is open.
+    _lock: File,
+    /// The state of the journal.
+    state: Mutex<JournalState<N>>,
+}
+
+impl<N: Network> SigningJournal<N> {
+    /// The minimum number of records in the journal file before it is compacted.
+    const MIN_RECORDS_TO_COMPACT: usize = 10_000;
+
+    /// Opens the signing journal at the given path, creating it if it does not exist.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        // Ensure the parent directory exists.
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Acquire the lock of the journal, to ensure no other instance is signing with it.
+        let lock_path = PathBuf::from(format!("{}.lock", path.display()));
+        let lock = OpenOptions::new().create(true).write(true).truncate(false).open(&lock_path)?;
+        if lock.try_lock_exclusive().is_err() {
+            bail!("The signing journal at {} is in use by another instance of the validator", path.display());
+        }
+
+        // Read the records of the journal.
+        let bytes = match path.exists() {
+            true => fs::read(&path)?,
+            false => vec![],
+        };
+        let mut entries = HashMap::new();
+        let mut num_records = 0;
+        let mut reader = &bytes[..];
+        let mut num_valid_bytes = 0;
+        while !reader.is_empty() {
+            // Note: A partial record may be left at the end of the journal if the node crashed while appending it,
+            // in which case its signature was never released, and the partial record is discarded.
+            let Ok(record) = Self::read_record(&mut reader) else {
+                warn!("Discarding a partial record at the end of the signing journal at {}", path.display());
+                break;
+            };
+            let (author, round, batch_id) = record;
+            // Note: The records of an author are appended in increasing order of rounds.
+            entries.insert(author, (round, batch_id));
+            num_records += 1;
+            num_valid_bytes = bytes.len() - reader.len();
+        }
+
+        // Open the journal for appending, discarding a partial record at its end.
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        file.set_len(num_valid_bytes as u64)?;
+
+        info!("Loaded the signing journal from {} with {} authors", path.display(), entries.len());
+        Ok(Self { path, _lock: lock, state: Mutex::new(JournalState { file, entries, num_records }) })
+    }
+
+    /// Returns the latest round and batch ID signed for the given author, if any.
+    pub fn get(&self, author: &Address<N>) -> Option<(u64, Field<N>)> {
+        self.state.lock().entries.get(author).copied()
+    }
+
+    /// Ensures the given batch does not conflict with a batch signed before, and records it in the journal.
+    /// This method must be called (and succeed) before the signature of the batch is released.
+    pub fn check_and_record(&self, author: Address<N>, round: u64, batch_id: Field<N>) -> Result<()> {
+        let mut state = self.state.lock();
+        match state.entries.get(&author) {
+            // If the batch was signed before, there is nothing to record.
+            Some((signed_round, signed_batch_id)) if *signed_round == round => {
+                ensure!(
+                    *signed_batch_id == batch_id,
+                    "Refusing to sign a conflicting batch from '{author}' for round {round}"
+                );
+                return Ok(());
+            }
+            // If a batch for a later round was signed before, refuse to sign.
+            Some((signed_round, _)) if *signed_round > round => {
+                bail!("Refusing to sign a batch from '{author}' for round {round}, as round {signed_round} was signed")
+            }
+            _ => (),
+        }
+
+        // Append the record to the journal, and sync it to disk.
+        state.file.write_all(&to_bytes_le![author, round, batch_id]?)?;
+        state.file.sync_data()?;
+        state.entries.insert(author, (round, batch_id));
+        state.num_records += 1;
+
+        // If the journal holds mostly superseded records, compact it.
+        if state.num_records > Self::MIN_RECORDS_TO_COMPACT.max(state.entries.len() * 2) {
+            self.compact(&mut state)?;
+        }
+        Ok(())
+    }
+
+    /// Rewrites the journal with only the latest record of each author.
+    fn compact(&self, state: &mut JournalState<N>) -> Result<()> {
+        // Write the latest records to a temporary file.
+        let tmp_path = PathBuf::from(format!("{}.tmp", self.path.display()));
+        let mut tmp_file = File::create(&tmp_path)?;
+        for (author, (round, batch_id)) in &state.entries {
+            tmp_file.write_all(&to_bytes_le![author, round, batch_id]?)?;
+        }
+        tmp_file.sync_all()?;
+        // Replace the journal with the temporary file.
+        fs::rename(&tmp_path, &self.path)
+            .map_err(|err| anyhow!("Couldn't compact the signing journal at {} - {err}", self.path.display()))?;
+        state.file = OpenOptions::new().append(true).open(&self.path)?;
+        state.num_records = state.entries.len();
+        Ok(())
+    }
+
+    /// Reads a record from the given reader.
+    fn read_record(reader: &mut &[u8]) -> Result<(Address<N>, u64, Field<N>)> {
+        Ok((Address::read_le(&mut *reader)?, u64::read_le(&mut *reader)?, Field::read_le(&mut *reader)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{MainnetV0, TestRng, Uniform};
+
+    use rand::Rng;
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Returns a new path for a signing journal.
+    fn sample_path(rng: &mut TestRng) -> PathBuf {
+        std::env::temp_dir().join(format!("snarkos-signing-journal-{}", rng.gen::<u64>()))
+    }
+
+    #[test]
+    fn test_signing_journal() {
+        let rng = &mut TestRng::default();
+        let path = sample_path(rng);
+
+        let author = Address::<CurrentNetwork>::rand(rng);
+        let (batch_id, other_batch_id) = (Field::rand(rng), Field::rand(rng));
+        {
+            let journal = SigningJournal::<CurrentNetwork>::open(&path).unwrap();
+            journal.check_and_record(author, 5, batch_id).unwrap();
+            // Ensure the same batch may be signed again.
+            journal.check_and_record(author, 5, batch_id).unwrap();
+            // Ensure a conflicting batch for the same round is refused.
+            assert!(journal.check_and_record(author, 5, other_batch_id).is_err());
+            // Ensure a batch for an earlier round is refused.
+            assert!(journal.check_and_record(author, 4, other_batch_id).is_err());
+            // Ensure a duplicate instance cannot open the journal.
+            assert!(SigningJournal::<CurrentNetwork>::open(&path).is_err());
+        }
+
+        // Ensure the journal persists across restarts.
+        let journal = SigningJournal::<CurrentNetwork>::open(&path).unwrap();
+        assert_eq!(journal.get(&author), Some((5, batch_id)));
+        assert!(journal.check_and_record(author, 5, other_batch_id).is_err());
+        journal.check_and_record(author, 6, other_batch_id).unwrap();
+        assert_eq!(journal.get(&author), Some((6, other_batch_id)));
+    }
+
+    #[test]
+    fn test_signing_journal_partial_record() {
+        let rng = &mut TestRng::default();
+        let path = sample_path(rng);
+
+        let author = Address::<CurrentNetwork>::rand(rng);
+        let batch_id = Field::rand(rng);
+        {
+            let journal = SigningJournal::<CurrentNetwork>::open(&path).unwrap();
+            journal.check_and_record(author, 1, batch_id).unwrap();
+        }
+        // Append a partial record, as if the node crashed while appending it.
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[1, 2, 3]).unwrap();
+        drop(file);
+
+        // Ensure the partial record is discarded.
+        let journal = SigningJournal::<CurrentNetwork>::open(&path).unwrap();
+        assert_eq!(journal.get(&author), Some((1, batch_id)));
+        journal.check_and_record(author, 2, batch_id).unwrap();
+        drop(journal);
+        let journal = SigningJournal::<CurrentNetwork>::open(&path).unwrap();
+        assert_eq!(journal.get(&author), Some((2, batch_id)));
+    }
+
+    #[test]
+    fn test_signing_journal_compaction() {
+        let rng = &mut TestRng::default();
+        let path = sample_path(rng);
+
+        let (author, other_author) = (Address::<CurrentNetwork>::rand(rng), Address::<CurrentNetwork>::rand(rng));
+        let batch_id = Field::rand(rng);
+        {
+            let journal = SigningJournal::<CurrentNetwork>::open(&path).unwrap();
+            for round in 1..=10 {
+                journal.check_and_record(author, round, batch_id).unwrap();
+            }
+            journal.check_and_record(other_author, 3, batch_id).unwrap();
+            assert_eq!(journal.state.lock().num_records, 11);
+
+            // Compact the journal.
+            let mut state = journal.state.lock();
+            journal.compact(&mut state).unwrap();
+            assert_eq!(state.num_records, 2);
+        }
+        // Ensure the compacted journal retains the latest records.
+        let journal = SigningJournal::<CurrentNetwork>::open(&path).unwrap();
+        assert_eq!(journal.get(&author), Some((10, batch_id)));
+        assert_eq!(journal.get(&other_author), Some((3, batch_id)));
+        assert!(journal.check_and_record(author, 9, batch_id).is_err());
+        assert_eq!(journal.state.lock().num_records, 2);
+    }
+}
@@ -33,6 +33,9 @@ use std::{
     },
 };
 
+/// The maximum number of rounds of certificates to retain in storage, as a multiple of the maximum number of GC rounds.
+const MAX_RETENTION_MULTIPLE: u64 = 100;
+
 #[derive(Clone, Debug)]
 pub struct Storage<N: Network>(Arc<StorageInner<N>>);
 
@@ -77,6 +80,10 @@ pub struct StorageInner<N: Network> {
     gc_round: AtomicU64,
     /// The maximum number of rounds to keep in storage.
     max_gc_rounds: u64,
+    /// The number of rounds of certificates to retain in storage, which is at least the maximum number of GC rounds.
+    retention_rounds: AtomicU64,
+    /// The lock that pauses the insertion of certificates while the unreferenced transmissions are removed.
+    gc_lock: RwLock<()>,
     /* Once per batch */
     /// The map of `round` to a list of `(certificate ID, batch ID, author)` entries.
     rounds: RwLock<IndexMap<u64, IndexSet<(Field<N>, Field<N>, Address<N>)>>>,
@@ -107,6 +114,8 @@ impl<N: Network> Storage<N> {
             current_round: Default::default(),
             gc_round: Default::default(),
             max_gc_rounds,
+            retention_rounds: AtomicU64::new(max_gc_rounds),
+            gc_lock: Default::default(),
             rounds: Default::default(),
             certificates: Default::default(),
             batch_ids: Default::default(),
@@ -142,11 +151,44 @@ impl<N: Network> Storage<N> {
         self.gc_round.load(Ordering::SeqCst)
     }
 
+    /// Returns the number of rounds that garbage collection is behind the current round.
+    pub fn gc_lag(&self) -> u64 {
+        self.current_round().saturating_sub(self.max_gc_rounds).saturating_sub(self.gc_round())
+    }
+
+    /// Returns the number of rounds in storage.
+    pub fn num_rounds(&self) -> usize {
+        self.rounds.read().len()
+    }
+
+    /// Returns the number of certificates in storage.
+    pub fn num_certificates(&self) -> usize {
+        self.certificates.read().len()
+    }
+
     /// Returns the maximum number of rounds to keep in storage.
     pub fn max_gc_rounds(&self) -> u64 {
         self.max_gc_rounds
     }
 
+    /// Returns the number of rounds of certificates to retain in storage.
+    pub fn retention_rounds(&self) -> u64 {
+        self.retention_rounds.load(Ordering::SeqCst)
+    }
+
+    /// Sets the number of rounds of certificates to retain in storage, so that the node can serve
+    /// a longer history of the DAG to its peers. The GC round, which the protocol depends on, is unaffected.
+    pub fn set_retention_rounds(&self, retention_rounds: u64) -> Result<()> {
+        let max_retention_rounds = self.max_gc_rounds.saturating_mul(MAX_RETENTION_MULTIPLE);
+        ensure!(
+            (self.max_gc_rounds..=max_retention_rounds).contains(&retention_rounds),
+            "The number of retained rounds must be between {} and {max_retention_rounds}",
+            self.max_gc_rounds
+        );
+        self.retention_rounds.store(retention_rounds, Ordering::SeqCst);
+        Ok(())
+    }
+
     /// Increments storage to the next round, updating the current round.
     /// Note: This method is only called once per round, upon certification of the primary's batch.
     pub fn increment_to_next_round(&self, current_round: u64) -> Result<u64> {
@@ -215,8 +257,12 @@ impl<N: Network> Storage<N> {
         let next_gc_round = next_round.saturating_sub(self.max_gc_rounds);
         // Check if storage needs to be garbage collected.
         if next_gc_round > current_gc_round {
-            // Remove the GC round(s) from storage.
-            for gc_round in current_gc_round..=next_gc_round {
+            // Determine the number of rounds that are retained beyond the GC round.
+            let num_retained_rounds = self.retention_rounds().saturating_sub(self.max_gc_rounds);
+            // Remove the round(s) beyond the retention from storage.
+            for gc_round in current_gc_round.saturating_sub(num_retained_rounds)
+                ..=next_gc_round.saturating_sub(num_retained_rounds)
+            {
                 // Iterate over the certificates for the GC round.
                 for certificate in self.get_certificates_for_round(gc_round).iter() {
                     // Remove the certificate from storage.
@@ -567,6 +613,8 @@ impl<N: Network> Storage<N> {
         // Retrieve the author of the batch.
         let author = certificate.author();
 
+        // Ensure the unreferenced transmissions are not being removed.
+        let _gc_guard = self.gc_lock.read();
         // Insert the round to certificate ID entry.
         self.rounds.write().entry(round).or_default().insert((certificate_id, batch_id, author));
         // Obtain the certificate's transmission ids.
@@ -584,6 +632,22 @@ impl<N: Network> Storage<N> {
         );
     }
 
+    /// Removes the transmissions that are no longer referenced by any certificate in storage,
+    /// such as the ones left behind by the certificates of a previous run, and returns the number of removed entries.
+    ///
+    /// Note: This method scans the transmissions in storage, and should be called from a blocking task.
+    pub fn garbage_collect_transmissions(&self) -> usize {
+        let is_referenced = |certificate_id: &Field<N>| self.contains_certificate(*certificate_id);
+        // Find the unreferenced transmissions, without pausing the insertion of certificates.
+        let transmission_ids = self.transmissions.find_unreferenced_transmissions(&is_referenced);
+        if transmission_ids.is_empty() {
+            return 0;
+        }
+        // Remove the transmissions that are still unreferenced, while no certificate is being inserted.
+        let _gc_guard = self.gc_lock.write();
+        self.transmissions.remove_unreferenced_transmissions(&transmission_ids, &is_referenced)
+    }
+
     /// Removes the given `certificate ID` from storage.
     ///
     /// This method triggers updates to the `rounds`, `certificates`, `batch_ids`, and `transmissions` maps.
@@ -932,6 +996,8 @@ pub(crate) mod tests {
         storage.insert_certificate_atomic(certificate.clone(), Default::default(), missing_transmissions);
         // Ensure the certificate exists in storage.
         assert!(storage.contains_certificate(certificate_id));
+        assert_eq!(storage.num_certificates(), 1);
+        assert_eq!(storage.num_rounds(), 1);
         // Ensure the certificate is stored in the correct round.
         assert_eq!(storage.get_certificates_for_round(round), indexset! { certificate.clone() });
         // Ensure the certificate is stored for the correct round and author.
@@ -966,6 +1032,60 @@ pub(crate) mod tests {
         assert_storage(&storage, &[], &[], &[], &Default::default());
     }
 
+    #[test]
+    fn test_garbage_collect_transmissions() {
+        let rng = &mut TestRng::default();
+
+        // Sample a committee.
+        let committee = snarkvm::ledger::committee::test_helpers::sample_committee(rng);
+        // Initialize the ledger.
+        let ledger = Arc::new(MockLedgerService::new(committee));
+        // Initialize the transmissions, with the transmissions of a certificate from a previous run.
+        let transmissions = Arc::new(BFTMemoryService::new());
+        let orphan = snarkvm::ledger::narwhal::batch_certificate::test_helpers::sample_batch_certificate(rng);
+        let (orphan_transmissions, _) = sample_transmissions(&orphan, rng);
+        transmissions.insert_transmissions(
+            orphan.id(),
+            orphan.transmission_ids().clone(),
+            Default::default(),
+            orphan_transmissions,
+        );
+        // Initialize the storage.
+        let storage = Storage::<CurrentNetwork>::new(ledger, transmissions, 1);
+
+        // Insert a certificate.
+        let certificate = snarkvm::ledger::narwhal::batch_certificate::test_helpers::sample_batch_certificate(rng);
+        let (missing_transmissions, transmissions) = sample_transmissions(&certificate, rng);
+        storage.insert_certificate_atomic(certificate.clone(), Default::default(), missing_transmissions);
+
+        // Ensure only the transmissions of the orphaned certificate are removed.
+        assert_eq!(storage.garbage_collect_transmissions(), orphan.transmission_ids().len());
+        assert_eq!(storage.transmissions_iter().collect::<HashMap<_, _>>(), transmissions);
+        // Ensure a second pass removes nothing.
+        assert_eq!(storage.garbage_collect_transmissions(), 0);
+    }
+
+    #[test]
+    fn test_retention_rounds() {
+        let rng = &mut TestRng::default();
+
+        // Sample a committee.
+        let committee = snarkvm::ledger::committee::test_helpers::sample_committee(rng);
+        // Initialize the ledger.
+        let ledger = Arc::new(MockLedgerService::new(committee));
+        // Initialize the storage.
+        let storage = Storage::<CurrentNetwork>::new(ledger, Arc::new(BFTMemoryService::new()), 10);
+
+        // Ensure the retention defaults to the maximum number of GC rounds.
+        assert_eq!(storage.retention_rounds(), 10);
+        // Ensure the retention cannot be below the maximum number of GC rounds, or unbounded.
+        assert!(storage.set_retention_rounds(9).is_err());
+        assert!(storage.set_retention_rounds(10 * MAX_RETENTION_MULTIPLE + 1).is_err());
+        // Ensure the retention can be extended.
+        storage.set_retention_rounds(20).unwrap();
+        assert_eq!(storage.retention_rounds(), 20);
+    }
+
     #[test]
     fn test_certificate_duplicate() {
         let rng = &mut TestRng::default();
@@ -0,0 +1,104 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{MAX_BATCH_DELAY_IN_MS, MAX_LEADER_CERTIFICATE_DELAY_IN_SECS, MIN_BATCH_DELAY_IN_SECS};
+use snarkvm::prelude::{ensure, Result};
+
+/// The configuration of the round timeouts of the BFT, which trades off the round latency and spurious timeouts.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TimeoutConfig {
+    /// The interval at which the primary tries to advance to the next round, in milliseconds.
+    round_interval_in_ms: u64,
+    /// The time the BFT waits for the certificate of the leader, in seconds.
+    leader_timeout_in_secs: i64,
+}
+
+impl Default for TimeoutConfig {
+    /// Returns the default configuration, which matches the protocol defaults.
+    fn default() -> Self {
+        Self {
+            round_interval_in_ms: MAX_BATCH_DELAY_IN_MS,
+            leader_timeout_in_secs: MAX_LEADER_CERTIFICATE_DELAY_IN_SECS,
+        }
+    }
+}
+
+impl TimeoutConfig {
+    /// The maximum time the BFT waits for the certificate of the leader.
+    pub const MAX_LEADER_TIMEOUT_IN_SECS: i64 = 12 * MAX_LEADER_CERTIFICATE_DELAY_IN_SECS;
+    /// The maximum interval at which the primary tries to advance to the next round.
+    pub const MAX_ROUND_INTERVAL_IN_MS: u64 = 10 * MAX_BATCH_DELAY_IN_MS;
+    /// The minimum time the BFT waits for the certificate of the leader, so that the leader has the time
+    /// to propose and certify its batch after the minimum batch delay.
+    pub const MIN_LEADER_TIMEOUT_IN_SECS: i64 = 2 * MIN_BATCH_DELAY_IN_SECS as i64;
+    /// The minimum interval at which the primary tries to advance to the next round,
+    /// as the primary can not propose more frequently than the minimum batch delay.
+    pub const MIN_ROUND_INTERVAL_IN_MS: u64 = MIN_BATCH_DELAY_IN_SECS * 1000;
+
+    /// Initializes a new configuration, with the given values or the defaults, ensuring they are within bounds.
+    pub fn new(round_interval_in_ms: Option<u64>, leader_timeout_in_secs: Option<i64>) -> Result<Self> {
+        let round_interval_in_ms = round_interval_in_ms.unwrap_or(MAX_BATCH_DELAY_IN_MS);
+        ensure!(
+            (Self::MIN_ROUND_INTERVAL_IN_MS..=Self::MAX_ROUND_INTERVAL_IN_MS).contains(&round_interval_in_ms),
+            "The round interval must be between {} and {} ms",
+            Self::MIN_ROUND_INTERVAL_IN_MS,
+            Self::MAX_ROUND_INTERVAL_IN_MS
+        );
+        let leader_timeout_in_secs = leader_timeout_in_secs.unwrap_or(MAX_LEADER_CERTIFICATE_DELAY_IN_SECS);
+        ensure!(
+            (Self::MIN_LEADER_TIMEOUT_IN_SECS..=Self::MAX_LEADER_TIMEOUT_IN_SECS).contains(&leader_timeout_in_secs),
+            "The leader timeout must be between {} and {} seconds",
+            Self::MIN_LEADER_TIMEOUT_IN_SECS,
+            Self::MAX_LEADER_TIMEOUT_IN_SECS
+        );
+        Ok(Self { round_interval_in_ms, leader_timeout_in_secs })
+    }
+
+    /// Returns the interval at which the primary tries to advance to the next round, in milliseconds.
+    pub const fn round_interval_in_ms(&self) -> u64 {
+        self.round_interval_in_ms
+    }
+
+    /// Returns the time the BFT waits for the certificate of the leader, in seconds.
+    pub const fn leader_timeout_in_secs(&self) -> i64 {
+        self.leader_timeout_in_secs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Config = TimeoutConfig;
+
+    #[test]
+    fn test_timeout_config() {
+        // Ensure the defaults match the protocol defaults.
+        assert_eq!(Config::new(None, None).unwrap(), Config::default());
+        assert_eq!(Config::default().round_interval_in_ms(), MAX_BATCH_DELAY_IN_MS);
+        assert_eq!(Config::default().leader_timeout_in_secs(), MAX_LEADER_CERTIFICATE_DELAY_IN_SECS);
+
+        // Ensure the values within bounds are accepted.
+        let config =
+            Config::new(Some(Config::MIN_ROUND_INTERVAL_IN_MS), Some(Config::MAX_LEADER_TIMEOUT_IN_SECS)).unwrap();
+        assert_eq!(config.round_interval_in_ms(), Config::MIN_ROUND_INTERVAL_IN_MS);
+        assert_eq!(config.leader_timeout_in_secs(), Config::MAX_LEADER_TIMEOUT_IN_SECS);
+
+        // Ensure the values out of bounds are rejected.
+        assert!(Config::new(Some(Config::MIN_ROUND_INTERVAL_IN_MS - 1), None).is_err());
+        assert!(Config::new(Some(Config::MAX_ROUND_INTERVAL_IN_MS + 1), None).is_err());
+        assert!(Config::new(None, Some(Config::MIN_LEADER_TIMEOUT_IN_SECS - 1)).is_err());
+        assert!(Config::new(None, Some(Config::MAX_LEADER_TIMEOUT_IN_SECS + 1)).is_err());
+    }
+}
@@ -0,0 +1,136 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::helpers::fmt_id;
+use snarkvm::{
+    ledger::block::Transaction,
+    prelude::{bail, ensure, Network, ProgramID, Result},
+};
+
+use indexmap::{IndexMap, IndexSet};
+use std::str::FromStr;
+
+/// The local transaction filter of a validator, which rejects the transactions of the denied programs,
+/// or below the minimum fee of a program. It only applies to the memory pool and the batch proposals
+/// of this validator, and does not affect the validity of a block.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransactionFilter<N: Network> {
+    /// The programs whose deployments and executions are rejected.
+    denied_programs: IndexSet<ProgramID<N>>,
+    /// The minimum fee of a transaction calling a program, in microcredits.
+    program_min_fees: IndexMap<ProgramID<N>, u64>,
+}
+
+impl<N: Network> TransactionFilter<N> {
+    /// Initializes a new filter, from the given program IDs, and the given `PROGRAM=MICROCREDITS` minimum fees.
+    pub fn parse(denied_programs: &[String], program_min_fees: &[String]) -> Result<Self> {
+        let denied_programs = denied_programs
+            .iter()
+            .map(|program_id| ProgramID::from_str(program_id))
+            .collect::<Result<IndexSet<_>>>()?;
+        let program_min_fees = program_min_fees
+            .iter()
+            .map(|entry| {
+                let Some((program_id, min_fee)) = entry.split_once('=') else {
+                    bail!("Invalid minimum fee '{entry}' - expected 'PROGRAM=MICROCREDITS'");
+                };
+                Ok((ProgramID::from_str(program_id)?, min_fee.parse::<u64>()?))
+            })
+            .collect::<Result<IndexMap<_, _>>>()?;
+        // Ensure a program is not both denied and given a minimum fee, as it is ambiguous.
+        if let Some(program_id) = program_min_fees.keys().find(|program_id| denied_programs.contains(*program_id)) {
+            bail!("Program '{program_id}' is both denied and given a minimum fee");
+        }
+        Ok(Self { denied_programs, program_min_fees })
+    }
+
+    /// Returns `true` if the filter admits every transaction.
+    pub fn is_empty(&self) -> bool {
+        self.denied_programs.is_empty() && self.program_min_fees.is_empty()
+    }
+
+    /// Returns the programs whose transactions are rejected.
+    pub const fn denied_programs(&self) -> &IndexSet<ProgramID<N>> {
+        &self.denied_programs
+    }
+
+    /// Returns the minimum fees of the transactions calling a program, in microcredits.
+    pub const fn program_min_fees(&self) -> &IndexMap<ProgramID<N>, u64> {
+        &self.program_min_fees
+    }
+
+    /// Ensures the given transaction is admitted by the filter.
+    pub fn check(&self, transaction: &Transaction<N>) -> Result<()> {
+        // If the filter is empty, every transaction is admitted, so skip the checks.
+        if self.is_empty() {
+            return Ok(());
+        }
+        // Retrieve the programs of the transaction.
+        // Note: The fee transition is excluded, as every transaction pays its fee with 'credits.aleo'.
+        let program_ids: Vec<ProgramID<N>> = match (transaction.deployment(), transaction.execution()) {
+            (Some(deployment), _) => vec![*deployment.program_id()],
+            (_, Some(execution)) => execution.transitions().map(|transition| *transition.program_id()).collect(),
+            _ => vec![],
+        };
+        // Ensure the transaction does not call a denied program.
+        if let Some(program_id) = program_ids.iter().find(|program_id| self.denied_programs.contains(*program_id)) {
+            bail!("Transaction '{}' calls the denied program '{program_id}'", fmt_id(transaction.id()));
+        }
+        // Ensure the fee meets the highest minimum fee of the called programs.
+        let min_fee = program_ids.iter().filter_map(|program_id| self.program_min_fees.get(program_id)).max();
+        if let Some(min_fee) = min_fee {
+            let fee = *transaction.fee_amount()?;
+            ensure!(
+                fee >= *min_fee,
+                "Transaction '{}' has a fee of {fee} microcredits, below the program minimum of {min_fee} microcredits",
+                fmt_id(transaction.id())
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::MainnetV0;
+
+    type CurrentNetwork = MainnetV0;
+    type Filter = TransactionFilter<CurrentNetwork>;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        // Ensure the empty filter is the default.
+        let filter = Filter::parse(&[], &[]).unwrap();
+        assert!(filter.is_empty());
+        assert_eq!(filter, Filter::default());
+
+        // Ensure the rules are parsed.
+        let filter = Filter::parse(&strings(&["spam.aleo"]), &strings(&["token.aleo=5000", "swap.aleo=10"])).unwrap();
+        assert!(!filter.is_empty());
+        assert!(filter.denied_programs().contains(&ProgramID::from_str("spam.aleo").unwrap()));
+        assert_eq!(filter.program_min_fees().get(&ProgramID::from_str("token.aleo").unwrap()), Some(&5000));
+        assert_eq!(filter.program_min_fees().get(&ProgramID::from_str("swap.aleo").unwrap()), Some(&10));
+
+        // Ensure the invalid rules are rejected.
+        assert!(Filter::parse(&strings(&["spam"]), &[]).is_err());
+        assert!(Filter::parse(&[], &strings(&["token.aleo"])).is_err());
+        assert!(Filter::parse(&[], &strings(&["token.aleo=-1"])).is_err());
+        assert!(Filter::parse(&strings(&["token.aleo"]), &strings(&["token.aleo=5000"])).is_err());
+    }
+}
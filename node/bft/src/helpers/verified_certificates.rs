@@ -0,0 +1,121 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    console::{network::Network, types::Field},
+    ledger::narwhal::{BatchCertificate, Data},
+    prelude::{FromBytes, Result},
+};
+
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
+
+/// The maximum number of verified certificates to remember.
+const MAX_VERIFIED_CERTIFICATES: usize = 1024;
+
+/// The recently-verified batch certificates, keyed by the digest of their bytes.
+///
+/// Every validator re-broadcasts its latest certificate in each primary ping, so the same bytes arrive many
+/// times per round, and deserializing them verifies every signature in the certificate each time. Instead,
+/// the signatures are only verified the first time, and the stored certificate is returned afterwards.
+///
+/// Note: An Aleo signature only commits to its nonce through the challenge, so the signatures cannot be verified
+/// as a batch. The certificates from different peers are still deserialized concurrently, in blocking tasks.
+#[derive(Debug, Default)]
+pub struct VerifiedCertificates<N: Network> {
+    /// The map of the digest of the certificate bytes to the certificate ID.
+    digests: Mutex<IndexMap<[u8; 32], Field<N>>>,
+}
+
+impl<N: Network> VerifiedCertificates<N> {
+    /// Deserializes the given batch certificate. If the same bytes were verified before, and `get_certificate`
+    /// returns the certificate for their ID, that certificate is returned without verifying its signatures again.
+    pub fn deserialize(
+        &self,
+        certificate: Data<BatchCertificate<N>>,
+        get_certificate: impl FnOnce(Field<N>) -> Option<BatchCertificate<N>>,
+    ) -> Result<BatchCertificate<N>> {
+        let bytes = match certificate {
+            Data::Object(certificate) => return Ok(certificate),
+            Data::Buffer(bytes) => bytes,
+        };
+        let digest: [u8; 32] = Sha256::digest(&bytes).into();
+        // If the bytes were verified already, return the known certificate.
+        let certificate_id = self.digests.lock().get(&digest).copied();
+        if let Some(certificate) = certificate_id.and_then(get_certificate) {
+            return Ok(certificate);
+        }
+        // Deserialize the certificate, which verifies its signatures.
+        let certificate = BatchCertificate::<N>::from_bytes_le(&bytes)?;
+        // Remember the certificate, evicting the oldest one if the limit is reached.
+        let mut digests = self.digests.lock();
+        digests.insert(digest, certificate.id());
+        if digests.len() > MAX_VERIFIED_CERTIFICATES {
+            digests.shift_remove_index(0);
+        }
+        Ok(certificate)
+    }
+
+    /// Returns the number of verified certificates that are remembered.
+    pub fn len(&self) -> usize {
+        self.digests.lock().len()
+    }
+
+    /// Returns `true` if no verified certificates are remembered.
+    pub fn is_empty(&self) -> bool {
+        self.digests.lock().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        console::network::MainnetV0,
+        ledger::narwhal::batch_certificate::test_helpers::sample_batch_certificate,
+        prelude::{TestRng, ToBytes},
+    };
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_deserialize() {
+        let rng = &mut TestRng::default();
+        let certificate = sample_batch_certificate(rng);
+        let bytes = certificate.to_bytes_le().unwrap();
+        let verified = VerifiedCertificates::<CurrentNetwork>::default();
+
+        // Ensure the certificate is verified the first time.
+        let result = verified.deserialize(Data::Buffer(bytes.clone().into()), |_| unreachable!());
+        assert_eq!(result.unwrap(), certificate);
+        assert_eq!(verified.len(), 1);
+        // Ensure the known certificate is returned afterwards.
+        let result = verified.deserialize(Data::Buffer(bytes.clone().into()), |id| {
+            assert_eq!(id, certificate.id());
+            Some(certificate.clone())
+        });
+        assert_eq!(result.unwrap(), certificate);
+        // Ensure the certificate is verified again, if it is no longer known.
+        let result = verified.deserialize(Data::Buffer(bytes.clone().into()), |_| None);
+        assert_eq!(result.unwrap(), certificate);
+        assert_eq!(verified.len(), 1);
+
+        // Ensure modified bytes are not mistaken for the known certificate.
+        let mut modified = bytes;
+        *modified.last_mut().unwrap() ^= 1;
+        assert!(verified.deserialize(Data::Buffer(modified.into()), |_| Some(certificate.clone())).is_err());
+        assert_eq!(verified.len(), 1);
+    }
+}
@@ -58,11 +58,19 @@ pub const MAX_LEADER_CERTIFICATE_DELAY_IN_SECS: i64 = 2 * MAX_BATCH_DELAY_IN_MS
 /// The maximum number of seconds before the timestamp is considered expired.
 pub const MAX_TIMESTAMP_DELTA_IN_SECS: i64 = 10; // seconds
 /// The maximum number of workers that can be spawned.
-pub const MAX_WORKERS: u8 = 1; // worker(s)
+pub const MAX_WORKERS: u8 = 8; // worker(s)
+/// The number of seconds a signing lease is valid for, unless it is renewed.
+pub const SIGNING_LEASE_DURATION_IN_SECS: i64 = 30; // seconds
 
 /// The frequency at which each primary broadcasts a ping to every other node.
 /// Note: If this is updated, be sure to update `MAX_BLOCKS_BEHIND` to correspond properly.
 pub const PRIMARY_PING_IN_MS: u64 = 2 * MAX_BATCH_DELAY_IN_MS; // ms
+/// The frequency at which each primary renews its signing lease, or checks whether it was promoted, if enabled.
+pub const SIGNING_LEASE_RENEW_IN_SECS: u64 = 5; // seconds
+/// The frequency at which each primary checkpoints its in-flight state, if enabled.
+pub const STATE_CHECKPOINT_IN_MS: u64 = MAX_BATCH_DELAY_IN_MS / 2; // ms
+/// The frequency at which each primary removes the unreferenced transmissions from storage.
+pub const TRANSMISSIONS_GC_IN_SECS: u64 = 10 * 60; // seconds
 /// The frequency at which each worker broadcasts a ping to every other node.
 pub const WORKER_PING_IN_MS: u64 = 4 * MAX_BATCH_DELAY_IN_MS; // ms
 
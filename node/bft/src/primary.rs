@@ -21,26 +21,38 @@ use crate::{
         init_sync_channels,
         init_worker_channels,
         now,
+        AuditEvent,
+        AuditLog,
         BFTSender,
+        Failover,
+        FailoverEvent,
+        OperatorLane,
         PrimaryReceiver,
         PrimarySender,
         Proposal,
         ProposalCache,
+        ProposalConfig,
         SignedProposals,
+        Signer,
+        SigningJournal,
         Storage,
+        TimeoutConfig,
+        TransactionFilter,
+        TransmissionOrdering,
+        VerifiedCertificates,
     },
     spawn_blocking,
     Gateway,
     Sync,
     Transport,
     Worker,
-    MAX_BATCH_DELAY_IN_MS,
-    MAX_WORKERS,
     MIN_BATCH_DELAY_IN_SECS,
     PRIMARY_PING_IN_MS,
+    SIGNING_LEASE_RENEW_IN_SECS,
+    STATE_CHECKPOINT_IN_MS,
+    TRANSMISSIONS_GC_IN_SECS,
     WORKER_PING_IN_MS,
 };
-use snarkos_account::Account;
 use snarkos_node_bft_events::PrimaryPing;
 use snarkos_node_bft_ledger_service::LedgerService;
 use snarkos_node_sync::DUMMY_SELF_IP;
@@ -66,7 +78,10 @@ use std::{
     collections::{HashMap, HashSet},
     future::Future,
     net::SocketAddr,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     time::Duration,
 };
 use tokio::{
@@ -97,10 +112,30 @@ pub struct Primary<N: Network> {
     latest_proposed_batch_timestamp: Arc<RwLock<i64>>,
     /// The recently-signed batch proposals.
     signed_proposals: Arc<RwLock<SignedProposals<N>>>,
+    /// The recently-verified batch certificates from peers.
+    verified_certificates: Arc<VerifiedCertificates<N>>,
     /// The spawned handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
     /// The lock for propose_batch.
     propose_lock: Arc<TMutex<u64>>,
+    /// The configuration of the batch proposals.
+    proposal_config: ProposalConfig<N>,
+    /// The configuration of the round timeouts.
+    timeout_config: TimeoutConfig,
+    /// The local filter of the transactions proposed in a batch.
+    transaction_filter: Arc<TransactionFilter<N>>,
+    /// The priority lane for the transactions of the operators of the validator.
+    operator_lane: Arc<OperatorLane<N>>,
+    /// The journal of the signed batches, if enabled.
+    signing_journal: Option<Arc<SigningJournal<N>>>,
+    /// The active/standby failover of the validator, if enabled, which supersedes the signing journal.
+    failover: Option<Arc<Failover<N>>>,
+    /// The audit log of the consensus events, if enabled.
+    audit_log: Option<Arc<AuditLog<N>>>,
+    /// If `true`, the in-flight state is periodically checkpointed to the proposal cache.
+    state_checkpoints: bool,
+    /// If `true`, the in-flight state has changed since the latest checkpoint.
+    state_changed: Arc<AtomicBool>,
 }
 
 impl<N: Network> Primary<N> {
@@ -109,7 +144,7 @@ impl<N: Network> Primary<N> {
 
     /// Initializes a new primary instance.
     pub fn new(
-        account: Account<N>,
+        signer: impl Into<Signer<N>>,
         storage: Storage<N>,
         ledger: Arc<dyn LedgerService<N>>,
         ip: Option<SocketAddr>,
@@ -117,7 +152,7 @@ impl<N: Network> Primary<N> {
         dev: Option<u16>,
     ) -> Result<Self> {
         // Initialize the gateway.
-        let gateway = Gateway::new(account, storage.clone(), ledger.clone(), ip, trusted_validators, dev)?;
+        let gateway = Gateway::new(signer, storage.clone(), ledger.clone(), ip, trusted_validators, dev)?;
         // Initialize the sync module.
         let sync = Sync::new(gateway.clone(), storage.clone(), ledger.clone());
 
@@ -132,17 +167,76 @@ impl<N: Network> Primary<N> {
             proposed_batch: Default::default(),
             latest_proposed_batch_timestamp: Default::default(),
             signed_proposals: Default::default(),
+            verified_certificates: Default::default(),
             handles: Default::default(),
             propose_lock: Default::default(),
+            proposal_config: Default::default(),
+            timeout_config: Default::default(),
+            transaction_filter: Default::default(),
+            operator_lane: Default::default(),
+            signing_journal: None,
+            failover: None,
+            audit_log: None,
+            state_checkpoints: false,
+            state_changed: Default::default(),
         })
     }
 
+    /// Sets the configuration of the batch proposals.
+    pub fn with_proposal_config(mut self, proposal_config: ProposalConfig<N>) -> Self {
+        self.proposal_config = proposal_config;
+        self
+    }
+
+    /// Sets the configuration of the round timeouts.
+    pub fn with_timeout_config(mut self, timeout_config: TimeoutConfig) -> Self {
+        self.timeout_config = timeout_config;
+        self
+    }
+
+    /// Sets the local filter of the transactions, which skips the rejected transactions when proposing a batch.
+    pub fn with_transaction_filter(mut self, transaction_filter: TransactionFilter<N>) -> Self {
+        self.transaction_filter = Arc::new(transaction_filter);
+        self
+    }
+
+    /// Sets the priority lane for the transactions of the operators, which are proposed ahead of the fee ordering.
+    pub fn with_operator_lane(mut self, operator_lane: OperatorLane<N>) -> Self {
+        self.operator_lane = Arc::new(operator_lane);
+        self
+    }
+
+    /// Sets the journal of the signed batches, which protects against equivocation.
+    pub fn with_signing_journal(mut self, signing_journal: SigningJournal<N>) -> Self {
+        self.signing_journal = Some(Arc::new(signing_journal));
+        self
+    }
+
+    /// Sets the active/standby failover, which only lets the holder of the signing lease propose and sign.
+    pub fn with_failover(mut self, failover: Arc<Failover<N>>) -> Self {
+        self.failover = Some(failover);
+        self
+    }
+
+    /// Sets the audit log, which records the consensus events of the primary.
+    pub fn with_audit_log(mut self, audit_log: Arc<AuditLog<N>>) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    /// Enables the checkpoints of the in-flight state (i.e. the proposal cache), so that the primary
+    /// recovers its current round, proposed batch, and received signatures after a crash.
+    pub fn with_state_checkpoints(mut self) -> Self {
+        self.state_checkpoints = true;
+        self
+    }
+
     /// Load the proposal cache file and update the Primary state with the stored data.
     async fn load_proposal_cache(&self) -> Result<()> {
         // Fetch the signed proposals from the file system if it exists.
         match ProposalCache::<N>::exists(self.gateway.dev()) {
             // If the proposal cache exists, then process the proposal cache.
-            true => match ProposalCache::<N>::load(self.gateway.account().address(), self.gateway.dev()) {
+            true => match ProposalCache::<N>::load(self.gateway.signer().address(), self.gateway.dev()) {
                 Ok(proposal_cache) => {
                     // Extract the proposal and signed proposals.
                     let (latest_certificate_round, proposed_batch, signed_proposals, pending_certificates) =
@@ -197,7 +291,8 @@ impl<N: Network> Primary<N> {
         // Construct a map for the workers.
         let mut workers = Vec::new();
         // Initialize the workers.
-        for id in 0..MAX_WORKERS {
+        let num_workers = self.proposal_config.num_workers();
+        for id in 0..num_workers {
             // Construct the worker channels.
             let (tx_worker, rx_worker) = init_worker_channels();
             // Construct the worker instance.
@@ -207,7 +302,8 @@ impl<N: Network> Primary<N> {
                 self.storage.clone(),
                 self.ledger.clone(),
                 self.proposed_batch.clone(),
-            )?;
+            )?
+            .with_num_workers(num_workers);
             // Run the worker instance.
             worker.run(rx_worker);
             // Add the worker to the list of workers.
@@ -335,7 +431,14 @@ impl<N: Network> Primary<N> {
     /// 2. Sign the batch.
     /// 3. Set the batch proposal in the primary.
     /// 4. Broadcast the batch header to all validators for signing.
+    #[tracing::instrument(level = "info", skip_all, fields(round = self.current_round()))]
     pub async fn propose_batch(&self) -> Result<()> {
+        // Check if this instance is on standby, as it only proposes once it is promoted.
+        if !self.is_signing_enabled() {
+            debug!("Primary is safely skipping a batch proposal {}", "(on standby)".dimmed());
+            return Ok(());
+        }
+
         // This function isn't re-entrant.
         let mut lock_guard = self.propose_lock.lock().await;
 
@@ -402,16 +505,22 @@ impl<N: Network> Primary<N> {
         }
 
         #[cfg(feature = "metrics")]
-        metrics::gauge(metrics::bft::PROPOSAL_ROUND, round as f64);
+        {
+            metrics::gauge(metrics::bft::PROPOSAL_ROUND, round as f64);
+            // Update the depths of the transmission queues of the workers.
+            metrics::gauge(metrics::bft::UNCONFIRMED_SOLUTIONS, self.num_unconfirmed_solutions() as f64);
+            metrics::gauge(metrics::bft::UNCONFIRMED_TRANSACTIONS, self.num_unconfirmed_transactions() as f64);
+            metrics::gauge(metrics::bft::UNCONFIRMED_RATIFICATIONS, self.num_unconfirmed_ratifications() as f64);
+        }
 
         // Ensure that the primary does not create a new proposal too quickly.
-        if let Err(e) = self.check_proposal_timestamp(previous_round, self.gateway.account().address(), now()) {
+        if let Err(e) = self.check_proposal_timestamp(previous_round, self.gateway.signer().address(), now()) {
             debug!("Primary is safely skipping a batch proposal - {}", format!("{e}").dimmed());
             return Ok(());
         }
 
         // Ensure the primary has not proposed a batch for this round before.
-        if self.storage.contains_certificate_in_round_from(round, self.gateway.account().address()) {
+        if self.storage.contains_certificate_in_round_from(round, self.gateway.signer().address()) {
             // If a BFT sender was provided, attempt to advance the current round.
             if let Some(bft_sender) = self.bft_sender.get() {
                 match bft_sender.send_primary_round_to_bft(self.current_round()).await {
@@ -442,12 +551,17 @@ impl<N: Network> Primary<N> {
 
         // Retrieve the committee to check against.
         let committee_lookback = self.ledger.get_committee_lookback_for_round(round)?;
+        // Check if the primary is a member of the committee, as it resumes proposing once it is bonded again.
+        if !committee_lookback.is_committee_member(self.gateway.signer().address()) {
+            debug!("Primary is safely skipping a batch proposal {}", "(not a committee member)".dimmed());
+            return Ok(());
+        }
         // Check if the primary is connected to enough validators to reach quorum threshold.
         {
             // Retrieve the connected validator addresses.
             let mut connected_validators = self.gateway.connected_addresses();
             // Append the primary to the set.
-            connected_validators.insert(self.gateway.account().address());
+            connected_validators.insert(self.gateway.signer().address());
             // If quorum threshold is not reached, return early.
             if !committee_lookback.is_quorum_threshold_reached(&connected_validators) {
                 debug!(
@@ -487,12 +601,50 @@ impl<N: Network> Primary<N> {
             return Ok(());
         }
 
+        // If the fee-priority ordering is enabled, order the ready queues of the workers by fee priority.
+        if self.proposal_config.ordering() == TransmissionOrdering::FeePriority {
+            for worker in self.workers.iter() {
+                let worker = worker.clone();
+                spawn_blocking!({
+                    worker.sort_by_fee_priority();
+                    Ok(())
+                })?;
+            }
+        }
+        // If the operator lane is enabled, move the transactions of the operators to the front, up to the quota.
+        if !self.operator_lane.is_empty() {
+            let mut num_remaining = self.operator_lane.quota();
+            for worker in self.workers.iter() {
+                if num_remaining == 0 {
+                    break;
+                }
+                let worker = worker.clone();
+                let operator_lane = self.operator_lane.clone();
+                num_remaining -= spawn_blocking!({
+                    Ok(worker.prioritize(num_remaining, |transmission| match transmission {
+                        Transmission::Transaction(data) => data
+                            .clone()
+                            .deserialize_blocking()
+                            .map_or(false, |transaction| operator_lane.is_operator_transaction(&transaction)),
+                        _ => false,
+                    }))
+                })?;
+            }
+        }
+
         // Determined the required number of transmissions per worker.
-        let num_transmissions_per_worker = BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH / self.num_workers() as usize;
+        let num_transmissions_per_worker = self.proposal_config.max_transmissions() / self.num_workers() as usize;
         // Initialize the map of transmissions.
         let mut transmissions: IndexMap<_, _> = Default::default();
+        // Initialize the total size of the transmissions, in bytes.
+        let mut num_bytes = 0;
+        // Initialize the map of transmissions that did not fit the maximum batch size.
+        let mut deferred_transmissions: IndexMap<_, _> = Default::default();
+        // Initialize the number of solutions, and the map of solutions beyond the maximum number of solutions.
+        let mut num_solutions = 0;
+        let mut deferred_solutions: IndexMap<_, _> = Default::default();
         // Take the transmissions from the workers.
-        for worker in self.workers.iter() {
+        'workers: for worker in self.workers.iter() {
             // Initialize a tracker for included transmissions for the current worker.
             let mut num_transmissions_included_for_worker = 0;
             // Keep draining the worker until the desired number of transmissions is reached or the worker is empty.
@@ -520,6 +672,14 @@ impl<N: Network> Primary<N> {
                         trace!("Proposing - Skipping transmission '{}' - Already in storage", fmt_id(id));
                         continue 'inner;
                     }
+                    // Check if the batch has the maximum number of solutions, and defer the solution otherwise.
+                    if matches!(id, TransmissionID::Solution(..))
+                        && num_solutions >= self.proposal_config.max_solutions()
+                    {
+                        trace!("Proposing - Deferring solution '{}' - The batch has enough solutions", fmt_id(id));
+                        deferred_solutions.insert(id, transmission);
+                        continue 'inner;
+                    }
                     // Check the transmission is still valid.
                     match (id, transmission.clone()) {
                         (TransmissionID::Solution(solution_id), Transmission::Solution(solution)) => {
@@ -530,11 +690,25 @@ impl<N: Network> Primary<N> {
                             }
                         }
                         (TransmissionID::Transaction(transaction_id), Transmission::Transaction(transaction)) => {
+                            // Check if the transaction is admitted by the local transaction filter.
+                            if let Err(e) = self.check_transaction_filter(transaction.clone()).await {
+                                trace!("Proposing - Skipping transaction '{}' - {e}", fmt_id(transaction_id));
+                                continue 'inner;
+                            }
                             // Check if the transaction is still valid.
-                            if let Err(e) = self.ledger.check_transaction_basic(transaction_id, transaction).await {
+                            if let Err(e) =
+                                self.ledger.check_transaction_basic(transaction_id, transaction.clone()).await
+                            {
                                 trace!("Proposing - Skipping transaction '{}' - {e}", fmt_id(transaction_id));
                                 continue 'inner;
                             }
+                            // Check if the transaction would be accepted, if the speculation is enabled.
+                            if let Err(e) = self.check_transaction_speculative(transaction).await {
+                                debug!("Proposing - Skipping transaction '{}' - {e}", fmt_id(transaction_id));
+                                #[cfg(feature = "metrics")]
+                                metrics::increment_counter(metrics::bft::SPECULATION_SKIPPED_TRANSACTIONS);
+                                continue 'inner;
+                            }
                         }
                         // Note: We explicitly forbid including ratifications,
                         // as the protocol currently does not support ratifications.
@@ -542,12 +716,40 @@ impl<N: Network> Primary<N> {
                         // All other combinations are clearly invalid.
                         _ => continue 'inner,
                     }
+                    // Check if the transmission fits the maximum batch size, and defer it to a later batch otherwise.
+                    // Note: The first transmission always fits, as the maximum batch size fits the largest transaction.
+                    let Ok(num_transmission_bytes) = transmission.to_bytes_le().map(|bytes| bytes.len()) else {
+                        continue 'inner;
+                    };
+                    // Check if the transmission fits the maximum transmission size, and skip it otherwise.
+                    if num_transmission_bytes > self.proposal_config.max_transmission_size() {
+                        trace!("Proposing - Skipping transmission '{}' - Exceeds the maximum size", fmt_id(id));
+                        continue 'inner;
+                    }
+                    if num_bytes + num_transmission_bytes > self.proposal_config.max_size_in_bytes() {
+                        trace!("Proposing - Deferring transmission '{}' - The batch is full", fmt_id(id));
+                        deferred_transmissions.insert(id, transmission);
+                        continue 'inner;
+                    }
                     // Insert the transmission into the map.
+                    if matches!(id, TransmissionID::Solution(..)) {
+                        num_solutions += 1;
+                    }
                     transmissions.insert(id, transmission);
                     num_transmissions_included_for_worker += 1;
+                    num_bytes += num_transmission_bytes;
+                }
+                // If the batch is full, stop draining the workers.
+                if !deferred_transmissions.is_empty() {
+                    break 'workers;
                 }
             }
         }
+        // Re-insert the deferred transmissions and solutions into the workers.
+        deferred_transmissions.extend(deferred_solutions);
+        if let Err(e) = self.reinsert_transmissions_into_workers(deferred_transmissions) {
+            error!("Failed to reinsert the deferred transmissions: {e:?}");
+        }
 
         // Determine the current timestamp.
         let current_timestamp = now();
@@ -557,8 +759,6 @@ impl<N: Network> Primary<N> {
         /* Proceeding to sign & propose the batch. */
         info!("Proposing a batch with {} transmissions for round {round}...", transmissions.len());
 
-        // Retrieve the private key.
-        let private_key = *self.gateway.account().private_key();
         // Retrieve the committee ID.
         let committee_id = committee_lookback.id();
         // Prepare the transmission IDs.
@@ -566,32 +766,35 @@ impl<N: Network> Primary<N> {
         // Prepare the previous batch certificate IDs.
         let previous_certificate_ids = previous_certificates.into_iter().map(|c| c.id()).collect();
         // Sign the batch header and construct the proposal.
-        let (batch_header, proposal) = spawn_blocking!(BatchHeader::new(
-            &private_key,
-            round,
-            current_timestamp,
-            committee_id,
-            transmission_ids,
-            previous_certificate_ids,
-            &mut rand::thread_rng()
-        ))
-        .and_then(|batch_header| {
-            Proposal::new(committee_lookback, batch_header.clone(), transmissions.clone())
-                .map(|proposal| (batch_header, proposal))
-        })
-        .map_err(|err| {
-            // On error, reinsert the transmissions and then propagate the error.
-            if let Err(e) = self.reinsert_transmissions_into_workers(transmissions) {
-                error!("Failed to reinsert transmissions: {e:?}");
-            }
-            err
-        })?;
-        // Broadcast the batch to all validators for signing.
-        self.gateway.broadcast(Event::BatchPropose(batch_header.into()));
+        let (batch_header, proposal) = self
+            .sign_batch_header(round, current_timestamp, committee_id, transmission_ids, previous_certificate_ids)
+            .await
+            .and_then(|batch_header| {
+                Proposal::new(committee_lookback, batch_header.clone(), transmissions.clone())
+                    .map(|proposal| (batch_header, proposal))
+            })
+            .map_err(|err| {
+                // On error, reinsert the transmissions and then propagate the error.
+                if let Err(e) = self.reinsert_transmissions_into_workers(transmissions) {
+                    error!("Failed to reinsert transmissions: {e:?}");
+                }
+                err
+            })?;
         // Set the timestamp of the latest proposed batch.
         *self.latest_proposed_batch_timestamp.write() = proposal.timestamp();
         // Set the proposed batch.
         *self.proposed_batch.write() = Some(proposal);
+        // Checkpoint the in-flight state, so that the proposed batch is recovered after a crash.
+        self.checkpoint_state().await;
+        self.audit(AuditEvent::BatchProposed {
+            round,
+            batch_id: batch_header.batch_id(),
+            num_transmissions: batch_header.transmission_ids().len(),
+        });
+        // Broadcast the batch to all validators for signing.
+        self.gateway.broadcast(Event::BatchPropose(batch_header.into()));
+        #[cfg(feature = "metrics")]
+        metrics::increment_counter(metrics::bft::BATCHES_PROPOSED);
         Ok(())
     }
 
@@ -605,6 +808,10 @@ impl<N: Network> Primary<N> {
     /// If our primary is ahead of the peer, we will not sign the batch.
     /// If our primary is behind the peer, but within GC range, we will sync up to the peer's round, and then sign the batch.
     async fn process_batch_propose_from_peer(&self, peer_ip: SocketAddr, batch_propose: BatchPropose<N>) -> Result<()> {
+        // If this instance is on standby, it does not sign.
+        if !self.is_signing_enabled() {
+            return Ok(());
+        }
         let BatchPropose { round: batch_round, batch_header } = batch_propose;
 
         // Deserialize the batch header.
@@ -638,9 +845,15 @@ impl<N: Network> Primary<N> {
             bail!("Malicious peer - proposed batch from a non-committee member ({batch_author})");
         }
         // Ensure the batch proposal is not from the current primary.
-        if self.gateway.account().address() == batch_author {
+        if self.gateway.signer().address() == batch_author {
             bail!("Invalid peer - proposed batch from myself ({batch_author})");
         }
+        self.audit(AuditEvent::ProposalSeen {
+            round: batch_round,
+            author: batch_author,
+            batch_id: batch_header.batch_id(),
+            peer_ip,
+        });
 
         // Ensure that the batch proposal's committee ID matches the expected committee ID.
         let expected_committee_id = self.ledger.get_committee_lookback_for_round(batch_round)?.id();
@@ -734,9 +947,10 @@ impl<N: Network> Primary<N> {
 
         // Retrieve the batch ID.
         let batch_id = batch_header.batch_id();
+        // Ensure the batch does not conflict with a batch signed before.
+        self.check_and_record_signing(batch_author, batch_round, batch_id).await?;
         // Sign the batch ID.
-        let account = self.gateway.account().clone();
-        let signature = spawn_blocking!(account.sign(&[batch_id], &mut rand::thread_rng()))?;
+        let signature = self.gateway.signer().sign(&[batch_id]).await?;
 
         // Ensure the proposal has not already been signed.
         //
@@ -761,6 +975,8 @@ impl<N: Network> Primary<N> {
                 entry.insert((batch_round, batch_id, signature));
             }
         };
+        self.mark_state_changed();
+        self.audit(AuditEvent::SignatureIssued { round: batch_round, author: batch_author, batch_id });
 
         // Broadcast the signature back to the validator.
         let self_ = self.clone();
@@ -769,6 +985,8 @@ impl<N: Network> Primary<N> {
             // Send the batch signature to the peer.
             if self_.gateway.send(peer_ip, event).await.is_some() {
                 debug!("Signed a batch for round {batch_round} from '{peer_ip}'");
+                #[cfg(feature = "metrics")]
+                metrics::increment_counter(metrics::bft::BATCHES_SIGNED);
             }
         });
         Ok(())
@@ -803,7 +1021,7 @@ impl<N: Network> Primary<N> {
             bail!("Malicious peer - batch signature is from a different validator ({signer})");
         }
         // Ensure the batch signature is not from the current primary.
-        if self.gateway.account().address() == signer {
+        if self.gateway.signer().address() == signer {
             bail!("Invalid peer - received a batch signature from myself ({signer})");
         }
 
@@ -841,6 +1059,7 @@ impl<N: Network> Primary<N> {
                     };
                     // Add the signature to the batch.
                     proposal.add_signature(signer, signature, &committee_lookback)?;
+                    self_.mark_state_changed();
                     info!("Received a batch signature for round {} from '{peer_ip}'", proposal.round());
                     // Check if the batch is ready to be certified.
                     if !proposal.is_quorum_threshold_reached(&committee_lookback) {
@@ -910,12 +1129,14 @@ impl<N: Network> Primary<N> {
             bail!("Malicious peer - Received a batch certificate from an unauthorized validator IP ({peer_ip})");
         }
         // Ensure the batch certificate is not from the current primary.
-        if self.gateway.account().address() == author {
+        if self.gateway.signer().address() == author {
             bail!("Received a batch certificate for myself ({author})");
         }
 
         // Store the certificate, after ensuring it is valid.
         self.sync_with_certificate_from_peer::<false>(peer_ip, certificate).await?;
+        #[cfg(feature = "metrics")]
+        metrics::increment_counter_label(metrics::bft::CERTIFICATES_RECEIVED, "author", author.to_string());
 
         // If there are enough certificates to reach quorum threshold for the certificate round,
         // then proceed to advance to the next round.
@@ -971,6 +1192,71 @@ impl<N: Network> Primary<N> {
             mut rx_unconfirmed_transaction,
         } = primary_receiver;
 
+        // Periodically renew the signing lease, or take it over once it is transferred to this instance, if enabled.
+        if let Some(failover) = self.failover.clone() {
+            let self_ = self.clone();
+            self.spawn(async move {
+                loop {
+                    // Sleep briefly.
+                    tokio::time::sleep(Duration::from_secs(SIGNING_LEASE_RENEW_IN_SECS)).await;
+                    let failover_ = failover.clone();
+                    match tokio::task::spawn_blocking(move || failover_.tick()).await {
+                        Ok(Some(FailoverEvent::SteppedDown(reason))) => {
+                            error!("This validator instance stepped down, and stopped signing - {reason}")
+                        }
+                        Ok(Some(FailoverEvent::Promoted(epoch))) => {
+                            info!("This validator instance was promoted (epoch {epoch}), and started signing");
+                            // Sync the storage with the blocks the standby synced from its router peers.
+                            if let Err(error) = self_.sync.sync_storage_with_ledger_at_bootup().await {
+                                error!("Failed to sync the storage with the ledger after the promotion - {error}");
+                            }
+                        }
+                        Ok(None) => (),
+                        Err(error) => error!("Failed to check the signing lease - {error}"),
+                    }
+                }
+            });
+        }
+
+        // Periodically checkpoint the in-flight state, if enabled.
+        if self.state_checkpoints {
+            let self_ = self.clone();
+            self.spawn(async move {
+                loop {
+                    // Sleep briefly.
+                    tokio::time::sleep(Duration::from_millis(STATE_CHECKPOINT_IN_MS)).await;
+                    // If the in-flight state has changed, checkpoint it.
+                    if self_.state_changed.load(Ordering::SeqCst) {
+                        self_.checkpoint_state().await;
+                    }
+                }
+            });
+        }
+
+        // Periodically remove the unreferenced transmissions from storage.
+        let self_ = self.clone();
+        self.spawn(async move {
+            loop {
+                // Sleep briefly.
+                tokio::time::sleep(Duration::from_secs(TRANSMISSIONS_GC_IN_SECS)).await;
+                // If the primary is not synced, then do not remove the transmissions, as the storage is incomplete.
+                if !self_.sync.is_synced() {
+                    continue;
+                }
+                // Remove the unreferenced transmissions.
+                let storage = self_.storage.clone();
+                match spawn_blocking!(Ok(storage.garbage_collect_transmissions())) {
+                    Ok(0) => trace!("Found no unreferenced transmissions in storage"),
+                    Ok(num_removed) => {
+                        info!("Removed {num_removed} unreferenced transmission(s) from storage");
+                        #[cfg(feature = "metrics")]
+                        metrics::counter(metrics::bft::GC_TRANSMISSIONS, num_removed as u64);
+                    }
+                    Err(e) => warn!("Failed to remove the unreferenced transmissions from storage - {e}"),
+                }
+            }
+        });
+
         // Start the primary ping.
         if self.sync.is_gateway_mode() {
             let self_ = self.clone();
@@ -992,7 +1278,7 @@ impl<N: Network> Primary<N> {
                     // Retrieve the latest certificate of the primary.
                     let primary_certificate = {
                         // Retrieve the primary address.
-                        let primary_address = self_.gateway.account().address();
+                        let primary_address = self_.gateway.signer().address();
 
                         // Iterate backwards from the latest round to find the primary certificate.
                         let mut certificate = None;
@@ -1043,8 +1329,11 @@ impl<N: Network> Primary<N> {
                 {
                     let self_ = self_.clone();
                     tokio::spawn(async move {
-                        // Deserialize the primary certificate in the primary ping.
-                        let Ok(primary_certificate) = spawn_blocking!(primary_certificate.deserialize_blocking())
+                        // Deserialize the primary certificate, unless it was verified and stored already.
+                        let (verified, storage) = (self_.verified_certificates.clone(), self_.storage.clone());
+                        let get_certificate = move |id| storage.get_certificate(id);
+                        let Ok(primary_certificate) =
+                            spawn_blocking!(verified.deserialize(primary_certificate, get_certificate))
                         else {
                             warn!("Failed to deserialize primary certificate in 'PrimaryPing' from '{peer_ip}'");
                             return;
@@ -1082,7 +1371,7 @@ impl<N: Network> Primary<N> {
         self.spawn(async move {
             loop {
                 // Sleep briefly, but longer than if there were no batch.
-                tokio::time::sleep(Duration::from_millis(MAX_BATCH_DELAY_IN_MS)).await;
+                tokio::time::sleep(Duration::from_millis(self_.proposal_config.interval_in_ms())).await;
                 // If the primary is not synced, then do not propose a batch.
                 if !self_.sync.is_synced() {
                     debug!("Skipping batch proposal {}", "(node is syncing)".dimmed());
@@ -1155,8 +1444,12 @@ impl<N: Network> Primary<N> {
                 // Spawn a task to process the batch certificate.
                 let self_ = self_.clone();
                 tokio::spawn(async move {
-                    // Deserialize the batch certificate.
-                    let Ok(batch_certificate) = spawn_blocking!(batch_certificate.deserialize_blocking()) else {
+                    // Deserialize the batch certificate, unless it was verified and stored already.
+                    let (verified, storage) = (self_.verified_certificates.clone(), self_.storage.clone());
+                    let get_certificate = move |id| storage.get_certificate(id);
+                    let Ok(batch_certificate) =
+                        spawn_blocking!(verified.deserialize(batch_certificate, get_certificate))
+                    else {
                         warn!("Failed to deserialize the batch certificate from '{peer_ip}'");
                         return;
                     };
@@ -1175,7 +1468,7 @@ impl<N: Network> Primary<N> {
         self.spawn(async move {
             loop {
                 // Sleep briefly.
-                tokio::time::sleep(Duration::from_millis(MAX_BATCH_DELAY_IN_MS)).await;
+                tokio::time::sleep(Duration::from_millis(self_.timeout_config.round_interval_in_ms())).await;
                 // If the primary is not synced, then do not increment to the next round.
                 if !self_.sync.is_synced() {
                     trace!("Skipping round increment {}", "(node is syncing)".dimmed());
@@ -1265,6 +1558,7 @@ impl<N: Network> Primary<N> {
             let proposal = self.proposed_batch.write().take();
             if let Some(proposal) = proposal {
                 debug!("Cleared expired proposal for round {}", proposal.round());
+                self.mark_state_changed();
                 self.reinsert_transmissions_into_workers(proposal.into_transmissions())?;
             }
         }
@@ -1272,6 +1566,7 @@ impl<N: Network> Primary<N> {
     }
 
     /// Increments to the next round.
+    #[tracing::instrument(level = "info", skip(self))]
     async fn try_increment_to_the_next_round(&self, next_round: u64) -> Result<()> {
         // If the next round is within GC range, then iterate to the penultimate round.
         if self.current_round() + self.storage.max_gc_rounds() >= next_round {
@@ -1353,7 +1648,7 @@ impl<N: Network> Primary<N> {
         let previous_timestamp = match self.storage.get_certificate_for_round_with_author(previous_round, author) {
             // Ensure that the previous certificate was created at least `MIN_BATCH_DELAY_IN_MS` seconds ago.
             Some(certificate) => certificate.timestamp(),
-            None => match self.gateway.account().address() == author {
+            None => match self.gateway.signer().address() == author {
                 // If we are the author, then ensure the previous proposal was created at least `MIN_BATCH_DELAY_IN_MS` seconds ago.
                 true => *self.latest_proposed_batch_timestamp.read(),
                 // If we do not see a previous certificate for the author, then proceed optimistically.
@@ -1382,6 +1677,7 @@ impl<N: Network> Primary<N> {
         // Store the certified batch.
         let (storage, certificate_) = (self.storage.clone(), certificate.clone());
         spawn_blocking!(storage.insert_certificate(certificate_, transmissions, Default::default()))?;
+        self.mark_state_changed();
         debug!("Stored a batch certificate for round {}", certificate.round());
         // If a BFT sender was provided, send the certificate to the BFT.
         if let Some(bft_sender) = self.bft_sender.get() {
@@ -1413,6 +1709,137 @@ impl<N: Network> Primary<N> {
         })
     }
 
+    /// Signs a batch header for the given round, with the signer of the primary.
+    async fn sign_batch_header(
+        &self,
+        round: u64,
+        timestamp: i64,
+        committee_id: Field<N>,
+        transmission_ids: IndexSet<TransmissionID<N>>,
+        previous_certificate_ids: IndexSet<Field<N>>,
+    ) -> Result<BatchHeader<N>> {
+        let batch_header = match self.gateway.signer() {
+            // If the account key is local, sign the batch header directly.
+            Signer::Local(account) => {
+                let private_key = *account.private_key();
+                spawn_blocking!(BatchHeader::new(
+                    &private_key,
+                    round,
+                    timestamp,
+                    committee_id,
+                    transmission_ids,
+                    previous_certificate_ids,
+                    &mut rand::thread_rng()
+                ))
+            }
+            // Otherwise, compute the batch ID, and request the signature over it from the remote signer.
+            Signer::Remote(remote_signer) => {
+                let author = remote_signer.address();
+                let (batch_id, transmission_ids, previous_certificate_ids) = spawn_blocking!({
+                    BatchHeader::compute_batch_id(
+                        author,
+                        round,
+                        timestamp,
+                        committee_id,
+                        &transmission_ids,
+                        &previous_certificate_ids,
+                    )
+                    .map(|batch_id| (batch_id, transmission_ids, previous_certificate_ids))
+                })?;
+                let signature = remote_signer.sign(&[batch_id]).await?;
+                spawn_blocking!(BatchHeader::from(
+                    author,
+                    round,
+                    timestamp,
+                    committee_id,
+                    transmission_ids,
+                    previous_certificate_ids,
+                    signature
+                ))
+            }
+        }?;
+        // Ensure the batch does not conflict with a batch proposed before, before the batch header is released.
+        self.check_and_record_signing(batch_header.author(), round, batch_header.batch_id()).await?;
+        Ok(batch_header)
+    }
+
+    /// Ensures the given batch does not conflict with a batch signed before, and records it in the signing journal.
+    async fn check_and_record_signing(&self, author: Address<N>, round: u64, batch_id: Field<N>) -> Result<()> {
+        // If the failover is enabled, ensure this instance holds the signing lease, and sign with its journal.
+        if let Some(failover) = &self.failover {
+            let failover = failover.clone();
+            return spawn_blocking!(failover.signing_journal()?.check_and_record(author, round, batch_id));
+        }
+        match &self.signing_journal {
+            Some(signing_journal) => {
+                let signing_journal = signing_journal.clone();
+                spawn_blocking!(signing_journal.check_and_record(author, round, batch_id))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Ensures the given transaction is admitted by the local transaction filter.
+    async fn check_transaction_filter(&self, transaction: Data<Transaction<N>>) -> Result<()> {
+        // If the filter is empty, skip deserializing the transaction.
+        if self.transaction_filter.is_empty() {
+            return Ok(());
+        }
+        self.transaction_filter.check(&transaction.deserialize().await?)
+    }
+
+    /// Ensures the given transaction would be accepted, by speculating its finalize execution, if enabled.
+    async fn check_transaction_speculative(&self, transaction: Data<Transaction<N>>) -> Result<()> {
+        // If the speculation is disabled, skip deserializing the transaction.
+        if !self.proposal_config.speculate() {
+            return Ok(());
+        }
+        #[cfg(feature = "metrics")]
+        metrics::increment_counter(metrics::bft::SPECULATED_TRANSACTIONS);
+        self.ledger.check_transaction_speculative(transaction.deserialize().await?).await
+    }
+
+    /// Returns `false` if this instance is on standby, in which case it neither proposes nor signs.
+    fn is_signing_enabled(&self) -> bool {
+        self.failover.as_ref().map_or(true, |failover| failover.is_active())
+    }
+
+    /// Records the given consensus event in the audit log, if enabled.
+    fn audit(&self, event: AuditEvent<N>) {
+        if let Some(audit_log) = &self.audit_log {
+            audit_log.record(event);
+        }
+    }
+
+    /// Marks the in-flight state as changed since the latest checkpoint.
+    fn mark_state_changed(&self) {
+        self.state_changed.store(true, Ordering::SeqCst);
+    }
+
+    /// Stores a checkpoint of the in-flight state to the proposal cache, if enabled.
+    async fn checkpoint_state(&self) {
+        if !self.state_checkpoints {
+            return;
+        }
+        self.state_changed.store(false, Ordering::SeqCst);
+        // Construct the proposal cache, without clearing the proposed batch.
+        let proposal = self.proposed_batch.read().clone();
+        let signed_proposals = self.signed_proposals.read().clone();
+        // Note: The propose lock is only acquired without a proposed batch, as it is held while proposing.
+        let latest_round = match &proposal {
+            Some(proposal) => proposal.round(),
+            None => *self.propose_lock.lock().await,
+        };
+        let pending_certificates = self.storage.get_pending_certificates();
+        let proposal_cache = ProposalCache::new(latest_round, proposal, signed_proposals, pending_certificates);
+        // Store the proposal cache.
+        let dev = self.gateway.dev();
+        if let Err(err) = spawn_blocking!(proposal_cache.store(dev)) {
+            error!("Failed to checkpoint the proposal cache: {err}");
+            self.mark_state_changed();
+        }
+    }
+
     /// Re-inserts the transmissions from the proposal into the workers.
     fn reinsert_transmissions_into_workers(
         &self,
@@ -1469,6 +1896,7 @@ impl<N: Network> Primary<N> {
             // Store the batch certificate.
             let (storage, certificate_) = (self.storage.clone(), certificate.clone());
             spawn_blocking!(storage.insert_certificate(certificate_, missing_transmissions, Default::default()))?;
+            self.mark_state_changed();
             debug!("Stored a batch certificate for round {batch_round} from '{peer_ip}'");
             // If a BFT sender was provided, send the round and certificate to the BFT.
             if let Some(bft_sender) = self.bft_sender.get() {
@@ -1678,6 +2106,8 @@ impl<N: Network> Primary<N> {
     /// Shuts down the primary.
     pub async fn shut_down(&self) {
         info!("Shutting down the primary...");
+        // Drain the connections to the validators.
+        self.gateway.drain_connections().await;
         // Shut down the workers.
         self.workers.iter().for_each(|worker| worker.shut_down());
         // Abort the tasks.
@@ -1696,11 +2126,24 @@ impl<N: Network> Primary<N> {
         // Close the gateway.
         self.gateway.shut_down().await;
     }
+
+    /// Stops the primary abruptly, without storing the proposal cache, as in a crash.
+    /// Note: This is intended for testing the recovery of the in-flight state.
+    pub async fn abort(&self) {
+        info!("Aborting the primary...");
+        // Shut down the workers.
+        self.workers.iter().for_each(|worker| worker.shut_down());
+        // Abort the tasks.
+        self.handles.lock().iter().for_each(|handle| handle.abort());
+        // Close the gateway.
+        self.gateway.shut_down().await;
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use snarkos_account::Account;
     use snarkos_node_bft_ledger_service::MockLedgerService;
     use snarkos_node_bft_storage_service::BFTMemoryService;
     use snarkvm::{
@@ -1833,7 +2276,7 @@ mod tests {
         // Each committee member signs the batch.
         let mut signatures = Vec::with_capacity(accounts.len() - 1);
         for (socket_addr, account) in accounts {
-            if account.address() == primary.gateway.account().address() {
+            if account.address() == primary.gateway.signer().address() {
                 continue;
             }
             let batch_id = primary.proposed_batch.read().as_ref().unwrap().batch_id();
@@ -2378,7 +2821,7 @@ mod tests {
         // Create a valid proposal.
         let timestamp = now();
         let proposal = create_test_proposal(
-            primary.gateway.account(),
+            &accounts[0].1,
             primary.ledger.current_committee().unwrap(),
             round + 1,
             previous_certificates,
@@ -2405,7 +2848,7 @@ mod tests {
         let round = 1;
         let timestamp = now() + MIN_BATCH_DELAY_IN_SECS as i64;
         let proposal = create_test_proposal(
-            primary.gateway.account(),
+            &accounts[0].1,
             primary.ledger.current_committee().unwrap(),
             round,
             Default::default(),
@@ -2425,7 +2868,7 @@ mod tests {
         }
 
         // Check the certificate was created and stored by the primary.
-        assert!(primary.storage.contains_certificate_in_round_from(round, primary.gateway.account().address()));
+        assert!(primary.storage.contains_certificate_in_round_from(round, primary.gateway.signer().address()));
         // Check the round was incremented.
         assert_eq!(primary.current_round(), round + 1);
     }
@@ -2443,7 +2886,7 @@ mod tests {
         // Create a valid proposal.
         let timestamp = now();
         let proposal = create_test_proposal(
-            primary.gateway.account(),
+            &accounts[0].1,
             primary.ledger.current_committee().unwrap(),
             round,
             previous_certificates,
@@ -2463,7 +2906,7 @@ mod tests {
         }
 
         // Check the certificate was created and stored by the primary.
-        assert!(primary.storage.contains_certificate_in_round_from(round, primary.gateway.account().address()));
+        assert!(primary.storage.contains_certificate_in_round_from(round, primary.gateway.signer().address()));
         // Check the round was incremented.
         assert_eq!(primary.current_round(), round + 1);
     }
@@ -2478,7 +2921,7 @@ mod tests {
         let round = 1;
         let timestamp = now() + MIN_BATCH_DELAY_IN_SECS as i64;
         let proposal = create_test_proposal(
-            primary.gateway.account(),
+            &accounts[0].1,
             primary.ledger.current_committee().unwrap(),
             round,
             Default::default(),
@@ -2497,7 +2940,7 @@ mod tests {
         primary.process_batch_signature_from_peer(*socket_addr, *signature).await.unwrap();
 
         // Check the certificate was not created and stored by the primary.
-        assert!(!primary.storage.contains_certificate_in_round_from(round, primary.gateway.account().address()));
+        assert!(!primary.storage.contains_certificate_in_round_from(round, primary.gateway.signer().address()));
         // Check the round was incremented.
         assert_eq!(primary.current_round(), round);
     }
@@ -2515,7 +2958,7 @@ mod tests {
         // Create a valid proposal.
         let timestamp = now() + MIN_BATCH_DELAY_IN_SECS as i64;
         let proposal = create_test_proposal(
-            primary.gateway.account(),
+            &accounts[0].1,
             primary.ledger.current_committee().unwrap(),
             round,
             previous_certificates,
@@ -2534,7 +2977,7 @@ mod tests {
         primary.process_batch_signature_from_peer(*socket_addr, *signature).await.unwrap();
 
         // Check the certificate was not created and stored by the primary.
-        assert!(!primary.storage.contains_certificate_in_round_from(round, primary.gateway.account().address()));
+        assert!(!primary.storage.contains_certificate_in_round_from(round, primary.gateway.signer().address()));
         // Check the round was incremented.
         assert_eq!(primary.current_round(), round);
     }
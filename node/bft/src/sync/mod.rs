@@ -66,7 +66,7 @@ impl<N: Network> Sync<N> {
     /// Initializes a new sync instance.
     pub fn new(gateway: Gateway<N>, storage: Storage<N>, ledger: Arc<dyn LedgerService<N>>) -> Self {
         // Initialize the block sync module.
-        let block_sync = BlockSync::new(BlockSyncMode::Gateway, ledger.clone());
+        let block_sync = BlockSync::new(BlockSyncMode::Gateway, ledger.clone(), &[]);
         // Return the sync instance.
         Self {
             gateway,
@@ -53,6 +53,8 @@ pub struct Worker<N: Network> {
     ready: Ready<N>,
     /// The pending transmissions queue.
     pending: Arc<Pending<TransmissionID<N>, Transmission<N>>>,
+    /// The maximum number of transmissions in the ready queue, beyond which the pinged transmissions are skipped.
+    max_transmissions: usize,
     /// The spawned handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
 }
@@ -77,10 +79,17 @@ impl<N: Network> Worker<N> {
             proposed_batch,
             ready: Default::default(),
             pending: Default::default(),
+            max_transmissions: BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH,
             handles: Default::default(),
         })
     }
 
+    /// Sets the number of workers the transmissions are split between, which divides the ready queue between them.
+    pub fn with_num_workers(mut self, num_workers: u8) -> Self {
+        self.max_transmissions = BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH / num_workers.max(1) as usize;
+        self
+    }
+
     /// Run the worker instance.
     pub fn run(&self, receiver: WorkerReceiver<N>) {
         info!("Starting worker instance {} of the memory pool...", self.id);
@@ -100,9 +109,6 @@ impl<N: Network> Worker<N> {
 }
 
 impl<N: Network> Worker<N> {
-    /// The maximum number of transmissions allowed in a worker.
-    pub const MAX_TRANSMISSIONS_PER_WORKER: usize =
-        BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH / MAX_WORKERS as usize;
     /// The maximum number of transmissions allowed in a worker ping.
     pub const MAX_TRANSMISSIONS_PER_WORKER_PING: usize = BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH / 10;
 
@@ -214,6 +220,17 @@ impl<N: Network> Worker<N> {
         self.ready.drain(num_transmissions).into_iter()
     }
 
+    /// Orders the ready queue by fee priority, so that the highest-paying transactions are drained first.
+    pub(crate) fn sort_by_fee_priority(&self) {
+        self.ready.sort_by_fee_priority()
+    }
+
+    /// Moves up to the specified number of transmissions that match the given predicate to the front of
+    /// the ready queue, so that they are drained first, and returns the number of moved transmissions.
+    pub(crate) fn prioritize(&self, num_transmissions: usize, predicate: impl Fn(&Transmission<N>) -> bool) -> usize {
+        self.ready.prioritize(num_transmissions, predicate)
+    }
+
     /// Reinserts the specified transmission into the ready queue.
     pub(crate) fn reinsert(&self, transmission_id: TransmissionID<N>, transmission: Transmission<N>) -> bool {
         // Check if the transmission ID exists.
@@ -251,7 +268,7 @@ impl<N: Network> Worker<N> {
         }
         // If the ready queue is full, then skip this transmission.
         // Note: We must prioritize the unconfirmed solutions and unconfirmed transactions, not transmissions.
-        if self.ready.num_transmissions() > Self::MAX_TRANSMISSIONS_PER_WORKER {
+        if self.ready.num_transmissions() > self.max_transmissions {
             return;
         }
         // Attempt to fetch the transmission from the peer.
@@ -413,6 +430,8 @@ impl<N: Network> Worker<N> {
         peer_ip: SocketAddr,
         transmission_id: TransmissionID<N>,
     ) -> Result<(TransmissionID<N>, Transmission<N>)> {
+        #[cfg(feature = "metrics")]
+        let start = std::time::Instant::now();
         // Initialize a oneshot channel.
         let (callback_sender, callback_receiver) = oneshot::channel();
         // Determine how many sent requests are pending.
@@ -443,7 +462,12 @@ impl<N: Network> Worker<N> {
         // Wait for the transmission to be fetched.
         match timeout(Duration::from_millis(MAX_FETCH_TIMEOUT_IN_MS), callback_receiver).await {
             // If the transmission was fetched, return it.
-            Ok(result) => Ok((transmission_id, result?)),
+            Ok(result) => {
+                let transmission = result?;
+                #[cfg(feature = "metrics")]
+                metrics::histogram(metrics::bft::TRANSMISSION_FETCH_LATENCY, start.elapsed().as_secs_f64());
+                Ok((transmission_id, transmission))
+            }
             // If the transmission was not fetched, return an error.
             Err(e) => bail!("Unable to fetch transmission - (timeout) {e}"),
         }
@@ -568,6 +592,7 @@ mod tests {
                 transaction_id: N::TransactionID,
                 transaction: Data<Transaction<N>>,
             ) -> Result<()>;
+            async fn check_transaction_speculative(&self, transaction: Transaction<N>) -> Result<()>;
             fn check_next_block(&self, block: &Block<N>) -> Result<()>;
             fn prepare_advance_to_next_quorum_block(
                 &self,
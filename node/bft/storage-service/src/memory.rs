@@ -194,6 +194,59 @@ impl<N: Network> StorageService<N> for BFTMemoryService<N> {
         }
     }
 
+    /// Returns the IDs of the transmissions and aborted transmission IDs in storage
+    /// that no longer reference any certificate ID for which `is_referenced` returns `true`.
+    fn find_unreferenced_transmissions(&self, is_referenced: &dyn Fn(&Field<N>) -> bool) -> Vec<TransmissionID<N>> {
+        // Find the unreferenced transmissions.
+        let mut transmission_ids: Vec<_> = self
+            .transmissions
+            .read()
+            .iter()
+            .filter(|(_, (_, certificate_ids))| !certificate_ids.iter().any(is_referenced))
+            .map(|(transmission_id, _)| *transmission_id)
+            .collect();
+        // Find the unreferenced aborted transmission IDs.
+        transmission_ids.extend(
+            self.aborted_transmission_ids
+                .read()
+                .iter()
+                .filter(|(_, certificate_ids)| !certificate_ids.iter().any(is_referenced))
+                .map(|(transmission_id, _)| *transmission_id),
+        );
+        transmission_ids
+    }
+
+    /// Removes the given transmissions and aborted transmission IDs from storage, if they still do not reference
+    /// any certificate ID for which `is_referenced` returns `true`, and returns the number of removed entries.
+    fn remove_unreferenced_transmissions(
+        &self,
+        transmission_ids: &[TransmissionID<N>],
+        is_referenced: &dyn Fn(&Field<N>) -> bool,
+    ) -> usize {
+        // Acquire the transmissions write lock.
+        let mut transmissions = self.transmissions.write();
+        // Acquire the aborted transmission IDs write lock.
+        let mut aborted_transmission_ids = self.aborted_transmission_ids.write();
+        let mut num_removed = 0;
+        for transmission_id in transmission_ids {
+            // Remove the transmission, if it is still unreferenced.
+            if let Entry::Occupied(occupied_entry) = transmissions.entry(*transmission_id) {
+                if !occupied_entry.get().1.iter().any(is_referenced) {
+                    occupied_entry.shift_remove();
+                    num_removed += 1;
+                }
+            }
+            // Remove the aborted transmission ID, if it is still unreferenced.
+            if let Entry::Occupied(occupied_entry) = aborted_transmission_ids.entry(*transmission_id) {
+                if !occupied_entry.get().iter().any(is_referenced) {
+                    occupied_entry.shift_remove();
+                    num_removed += 1;
+                }
+            }
+        }
+        num_removed
+    }
+
     /// Returns a HashMap over the `(transmission ID, (transmission, certificate IDs))` entries.
     #[cfg(any(test, feature = "test"))]
     fn as_hashmap(&self) -> HashMap<TransmissionID<N>, (Transmission<N>, IndexSet<Field<N>>)> {
@@ -291,6 +291,67 @@ impl<N: Network> StorageService<N> for BFTPersistentStorage<N> {
         }
     }
 
+    /// Returns the IDs of the transmissions and aborted transmission IDs in storage
+    /// that no longer reference any certificate ID for which `is_referenced` returns `true`.
+    fn find_unreferenced_transmissions(&self, is_referenced: &dyn Fn(&Field<N>) -> bool) -> Vec<TransmissionID<N>> {
+        // Find the unreferenced transmissions.
+        let mut transmission_ids: Vec<_> = self
+            .transmissions
+            .iter_confirmed()
+            .filter(|(_, entry)| !entry.1.iter().any(is_referenced))
+            .map(|(transmission_id, _)| *transmission_id)
+            .collect();
+        // Find the unreferenced aborted transmission IDs.
+        transmission_ids.extend(
+            self.aborted_transmission_ids
+                .iter_confirmed()
+                .filter(|(_, certificate_ids)| !certificate_ids.iter().any(is_referenced))
+                .map(|(transmission_id, _)| *transmission_id),
+        );
+        transmission_ids
+    }
+
+    /// Removes the given transmissions and aborted transmission IDs from storage, if they still do not reference
+    /// any certificate ID for which `is_referenced` returns `true`, and returns the number of removed entries.
+    fn remove_unreferenced_transmissions(
+        &self,
+        transmission_ids: &[TransmissionID<N>],
+        is_referenced: &dyn Fn(&Field<N>) -> bool,
+    ) -> usize {
+        let mut num_removed = 0;
+        for transmission_id in transmission_ids {
+            // Remove the transmission, if it is still unreferenced.
+            match self.transmissions.get_confirmed(transmission_id) {
+                Ok(Some(entry)) if !entry.1.iter().any(is_referenced) => {
+                    match self.transmissions.remove(transmission_id) {
+                        Ok(()) => num_removed += 1,
+                        Err(e) => error!("Failed to remove unreferenced transmission {transmission_id} - {e}"),
+                    }
+                }
+                Ok(_) => (),
+                Err(e) => {
+                    error!("Failed to process the 'remove' for transmission {transmission_id} from storage - {e}")
+                }
+            }
+            // Remove the aborted transmission ID, if it is still unreferenced.
+            match self.aborted_transmission_ids.get_confirmed(transmission_id) {
+                Ok(Some(certificate_ids)) if !certificate_ids.iter().any(is_referenced) => {
+                    match self.aborted_transmission_ids.remove(transmission_id) {
+                        Ok(()) => num_removed += 1,
+                        Err(e) => {
+                            error!("Failed to remove unreferenced aborted transmission ID {transmission_id} - {e}")
+                        }
+                    }
+                }
+                Ok(_) => (),
+                Err(e) => error!(
+                    "Failed to process the 'remove' for aborted transmission ID {transmission_id} from storage - {e}"
+                ),
+            }
+        }
+        num_removed
+    }
+
     /// Returns a HashMap over the `(transmission ID, (transmission, certificate IDs))` entries.
     #[cfg(any(test, feature = "test"))]
     fn as_hashmap(&self) -> HashMap<TransmissionID<N>, (Transmission<N>, IndexSet<Field<N>>)> {
@@ -53,6 +53,18 @@ pub trait StorageService<N: Network>: Debug + Send + Sync {
     /// If the transmission no longer references any certificate IDs, the entry is removed from storage.
     fn remove_transmissions(&self, certificate_id: &Field<N>, transmission_ids: &IndexSet<TransmissionID<N>>);
 
+    /// Returns the IDs of the transmissions and aborted transmission IDs in storage
+    /// that no longer reference any certificate ID for which `is_referenced` returns `true`.
+    fn find_unreferenced_transmissions(&self, is_referenced: &dyn Fn(&Field<N>) -> bool) -> Vec<TransmissionID<N>>;
+
+    /// Removes the given transmissions and aborted transmission IDs from storage, if they still do not reference
+    /// any certificate ID for which `is_referenced` returns `true`, and returns the number of removed entries.
+    fn remove_unreferenced_transmissions(
+        &self,
+        transmission_ids: &[TransmissionID<N>],
+        is_referenced: &dyn Fn(&Field<N>) -> bool,
+    ) -> usize;
+
     /// Returns a HashMap over the `(transmission ID, (transmission, certificate IDs))` entries.
     #[cfg(any(test, feature = "test"))]
     fn as_hashmap(&self) -> HashMap<TransmissionID<N>, (Transmission<N>, IndexSet<Field<N>>)>;
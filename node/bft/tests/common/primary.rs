@@ -19,7 +19,7 @@ use crate::common::{
 };
 use snarkos_account::Account;
 use snarkos_node_bft::{
-    helpers::{init_primary_channels, PrimarySender, Storage},
+    helpers::{init_primary_channels, PrimarySender, Signer, Storage},
     Primary,
     BFT,
     MAX_BATCH_DELAY_IN_MS,
@@ -216,6 +216,53 @@ impl TestNetwork {
         }
     }
 
+    // Enables the checkpoints of the in-flight state for a specific node (only without the BFT).
+    pub fn enable_state_checkpoints(&mut self, id: u16) {
+        let validator = self.validators.get_mut(&id).unwrap();
+        assert!(validator.bft.get().is_none(), "The checkpoints are only enabled on primaries");
+        validator.primary = validator.primary.clone().with_state_checkpoints();
+    }
+
+    // Crashes a specific node, stopping it abruptly without storing its state.
+    pub async fn crash_one(&self, id: u16) {
+        let validator = self.validators.get(&id).unwrap();
+        validator.handles.lock().iter().for_each(|handle| handle.abort());
+        validator.primary.abort().await;
+    }
+
+    // Restarts a crashed node from its checkpointed state (only without the BFT), and reconnects it.
+    pub async fn restart_one(&mut self, id: u16) {
+        let fire_transmissions = self.config.fire_transmissions;
+        let validator = self.validators.get_mut(&id).unwrap();
+        assert!(validator.bft.get().is_none(), "The checkpoints are only enabled on primaries");
+
+        // Initialize a new primary with the same account and ledger, and an empty storage, as it is kept in memory.
+        let Signer::Local(account) = validator.primary.gateway().signer().clone() else {
+            panic!("The test validators use a local signer");
+        };
+        let ledger = validator.primary.ledger().clone();
+        let storage = Storage::new(
+            ledger.clone(),
+            Arc::new(BFTMemoryService::new()),
+            BatchHeader::<CurrentNetwork>::MAX_GC_ROUNDS as u64,
+        );
+        validator.primary = Primary::<CurrentNetwork>::new(account, storage, ledger, None, &[], Some(id))
+            .unwrap()
+            .with_state_checkpoints();
+        validator.handles = Default::default();
+
+        // Start the primary, which recovers the checkpointed state.
+        let (primary_sender, primary_receiver) = init_primary_channels();
+        validator.primary_sender = Some(primary_sender.clone());
+        validator.primary.run(None, primary_sender, primary_receiver).await.unwrap();
+        if let Some(interval_ms) = fire_transmissions {
+            validator.fire_transmissions(interval_ms);
+        }
+
+        // Reconnect the node to the network.
+        self.connect_one(id).await;
+    }
+
     // Starts the solution and transaction cannons for node.
     pub fn fire_transmissions_at(&mut self, id: u16, interval_ms: u64) {
         self.validators.get_mut(&id).unwrap().fire_transmissions(interval_ms);
@@ -269,3 +269,26 @@ async fn handshake_responder_side_invalid_challenge_response() {
     assert!(gateway.connected_peers().read().is_empty());
     assert_eq!(gateway.tcp().num_connected(), 0);
 }
+
+// The gateway drains its connections; it should refuse any new ones afterwards.
+#[tokio::test(flavor = "multi_thread")]
+async fn drain_connections_refuses_new_connections() {
+    const NUM_NODES: u16 = 4;
+
+    let mut rng = TestRng::default();
+    let (_accounts, gateway) = new_test_gateway(NUM_NODES, &mut rng).await;
+    let test_peer = TestPeer::new().await;
+
+    // Drain the connections of the gateway.
+    gateway.drain_connections().await;
+    assert!(gateway.is_shutting_down());
+
+    // Initiate a connection with the gateway, which should be dropped during its handshake.
+    let _ = test_peer.connect(gateway.local_ip()).await;
+
+    // Check the test peer hasn't been added to the gateway's connected peers.
+    let gateway_clone = gateway.clone();
+    deadline!(Duration::from_secs(1), move || gateway_clone.tcp().num_connecting() == 0);
+    assert!(gateway.connected_peers().read().is_empty());
+    assert_eq!(gateway.tcp().num_connected(), 0);
+}
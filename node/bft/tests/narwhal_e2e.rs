@@ -15,8 +15,12 @@
 #[allow(dead_code)]
 mod common;
 
-use crate::common::primary::{TestNetwork, TestNetworkConfig};
-use snarkos_node_bft::MAX_FETCH_TIMEOUT_IN_MS;
+use crate::common::{
+    primary::{TestNetwork, TestNetworkConfig},
+    CurrentNetwork,
+};
+use snarkos_node_bft::{helpers::proposal_cache_path, MAX_FETCH_TIMEOUT_IN_MS};
+use snarkvm::console::network::Network;
 
 use std::time::Duration;
 
@@ -162,3 +166,51 @@ async fn test_storage_coherence() {
     // the nodes have completed the round.
     assert!(network.is_certificate_round_coherent(1..TARGET_ROUND - 1));
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_crash_recovery() {
+    // Start N nodes, connect them and start the cannons for each.
+    const N: u16 = 4;
+    const TRANSMISSION_INTERVAL_MS: u64 = 10;
+    let mut network = TestNetwork::new(TestNetworkConfig {
+        num_nodes: N,
+        bft: false,
+        connect_all: true,
+        fire_transmissions: Some(TRANSMISSION_INTERVAL_MS),
+        // Set this to Some(0..=4) to see the logs.
+        log_level: None,
+        log_connections: false,
+    });
+
+    // Enable the checkpoints of the in-flight state on the node to crash.
+    const CRASHED_ID: u16 = 0;
+    let proposal_cache_path = proposal_cache_path(CurrentNetwork::ID, Some(CRASHED_ID));
+    let _ = std::fs::remove_file(&proposal_cache_path);
+    network.enable_state_checkpoints(CRASHED_ID);
+    network.start().await;
+
+    // Check the nodes have started advancing through the rounds under load.
+    const TARGET_ROUND: u64 = 6;
+    // Note: cloning the network is fine because the primaries it wraps are `Arc`ed.
+    let network_clone = network.clone();
+    deadline!(Duration::from_secs(20), move || { network_clone.is_round_reached(TARGET_ROUND) });
+
+    // Crash the node mid-round, and check its in-flight state was checkpointed.
+    network.crash_one(CRASHED_ID).await;
+    let crashed_round = network.validators[&CRASHED_ID].primary.current_round();
+    assert!(proposal_cache_path.exists());
+
+    // Restart the node from its checkpointed state.
+    network.restart_one(CRASHED_ID).await;
+
+    // Check the restarted node rejoins consensus, as the network keeps advancing through the rounds.
+    let network_clone = network.clone();
+    deadline!(Duration::from_secs(60), move || {
+        network_clone.validators[&CRASHED_ID].primary.current_round() > crashed_round + 2
+            && network_clone.is_round_reached(crashed_round + 4)
+    });
+
+    // Clean up the checkpointed state.
+    network.crash_one(CRASHED_ID).await;
+    let _ = std::fs::remove_file(&proposal_cache_path);
+}
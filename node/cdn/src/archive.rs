@@ -0,0 +1,305 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{Deserialize, Serialize};
+
+use anyhow::{bail, ensure, Result};
+use sha2::{Digest, Sha256};
+use std::io::{BufReader, Read, Write};
+use zstd::stream::{read::Decoder, write::Encoder};
+
+/// The file name of the index of a static block archive.
+pub const ARCHIVE_INDEX_FILE: &str = "index.json";
+
+/// The magic bytes at the start of every block archive.
+const ARCHIVE_MAGIC: &[u8; 8] = b"ALEOBLKS";
+/// The version of the block archive format.
+const ARCHIVE_VERSION: u8 = 1;
+/// The zstd compression level of block archives.
+const COMPRESSION_LEVEL: i32 = 3;
+/// The maximum size of a single block in an archive, in bytes.
+const MAXIMUM_BLOCK_SIZE: u32 = 256 * 1024 * 1024;
+
+/// The header of a block archive.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ArchiveHeader {
+    /// The network ID of the blocks.
+    pub network: u16,
+    /// The height of the first block in the archive.
+    pub start_height: u32,
+    /// The height after the last block in the archive.
+    pub end_height: u32,
+}
+
+impl ArchiveHeader {
+    /// Returns the number of blocks in the archive.
+    pub const fn num_blocks(&self) -> u32 {
+        self.end_height - self.start_height
+    }
+
+    /// Returns the bytes of the header.
+    fn to_bytes(self) -> Vec<u8> {
+        let mut bytes = ARCHIVE_MAGIC.to_vec();
+        bytes.push(ARCHIVE_VERSION);
+        bytes.extend(self.network.to_le_bytes());
+        bytes.extend(self.start_height.to_le_bytes());
+        bytes.extend(self.end_height.to_le_bytes());
+        bytes
+    }
+}
+
+/// An archive file of a static block archive.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ArchiveIndexEntry {
+    /// The file name of the archive, relative to the index.
+    pub file: String,
+    /// The height of the first block in the archive.
+    pub start_height: u32,
+    /// The height after the last block in the archive.
+    pub end_height: u32,
+}
+
+/// The index of a static block archive, i.e. a directory of archives served over HTTPS,
+/// which lists the archives in ascending height order.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ArchiveIndex {
+    /// The network ID of the blocks.
+    pub network: u16,
+    /// The archives, in ascending height order.
+    pub archives: Vec<ArchiveIndexEntry>,
+}
+
+impl ArchiveIndex {
+    /// Initializes a new empty index for the given network.
+    pub const fn new(network: u16) -> Self {
+        Self { network, archives: Vec::new() }
+    }
+
+    /// Returns the height after the last block in the index.
+    pub fn end_height(&self) -> u32 {
+        self.archives.last().map(|entry| entry.end_height).unwrap_or_default()
+    }
+
+    /// Appends the given archive, which must continue from the last one.
+    pub fn append(&mut self, entry: ArchiveIndexEntry) -> Result<()> {
+        ensure!(entry.start_height < entry.end_height, "The archive '{}' must contain a block", entry.file);
+        ensure!(
+            entry.start_height == self.end_height(),
+            "The archive '{}' starts at block {}, but the index ends at block {}",
+            entry.file,
+            entry.start_height,
+            self.end_height()
+        );
+        ensure!(
+            !self.archives.iter().any(|other| other.file == entry.file),
+            "The index already lists '{}'",
+            entry.file
+        );
+        self.archives.push(entry);
+        Ok(())
+    }
+
+    /// Ensures the archives are contiguous, starting from genesis.
+    pub fn check(&self) -> Result<()> {
+        let mut index = Self::new(self.network);
+        for entry in &self.archives {
+            index.append(entry.clone())?;
+        }
+        Ok(())
+    }
+}
+
+/// A writer of a compressed block archive; each block is length-prefixed,
+/// and the archive ends with a SHA-256 checksum of everything before it.
+pub struct ArchiveWriter<W: Write> {
+    /// The compressed output.
+    encoder: Encoder<'static, W>,
+    /// The running checksum.
+    hasher: Sha256,
+    /// The header of the archive.
+    header: ArchiveHeader,
+    /// The number of blocks written so far.
+    num_written: u32,
+}
+
+impl<W: Write> ArchiveWriter<W> {
+    /// Initializes a new archive writer, and writes the header.
+    pub fn new(output: W, header: ArchiveHeader) -> Result<Self> {
+        ensure!(header.start_height < header.end_height, "The archive must contain at least one block");
+        let encoder = Encoder::new(output, COMPRESSION_LEVEL)?;
+        let mut writer = Self { encoder, hasher: Sha256::new(), header, num_written: 0 };
+        writer.write(&header.to_bytes())?;
+        Ok(writer)
+    }
+
+    /// Writes the given block bytes, in ascending height order.
+    pub fn write_block(&mut self, block_bytes: &[u8]) -> Result<()> {
+        ensure!(self.num_written < self.header.num_blocks(), "The archive already contains all of its blocks");
+        ensure!(block_bytes.len() <= MAXIMUM_BLOCK_SIZE as usize, "The block is too large to archive");
+        self.write(&(block_bytes.len() as u32).to_le_bytes())?;
+        self.write(block_bytes)?;
+        self.num_written += 1;
+        Ok(())
+    }
+
+    /// Writes the checksum, and returns the output.
+    pub fn finish(mut self) -> Result<W> {
+        ensure!(self.num_written == self.header.num_blocks(), "The archive is missing some of its blocks");
+        let checksum = self.hasher.finalize();
+        self.encoder.write_all(&checksum)?;
+        Ok(self.encoder.finish()?)
+    }
+
+    /// Writes the given bytes, and updates the checksum.
+    fn write(&mut self, bytes: &[u8]) -> Result<()> {
+        self.hasher.update(bytes);
+        Ok(self.encoder.write_all(bytes)?)
+    }
+}
+
+/// A reader of a compressed block archive; the checksum is verified once the last block is read.
+pub struct ArchiveReader<R: Read> {
+    /// The decompressed input.
+    decoder: Decoder<'static, BufReader<R>>,
+    /// The running checksum.
+    hasher: Sha256,
+    /// The header of the archive.
+    header: ArchiveHeader,
+    /// The number of blocks read so far.
+    num_read: u32,
+}
+
+impl<R: Read> ArchiveReader<R> {
+    /// Initializes a new archive reader, and reads the header.
+    pub fn new(input: R) -> Result<Self> {
+        let mut decoder = Decoder::new(input)?;
+        let mut bytes = [0u8; 19];
+        decoder.read_exact(&mut bytes)?;
+        if &bytes[..8] != ARCHIVE_MAGIC {
+            bail!("The file is not a block archive");
+        }
+        ensure!(bytes[8] == ARCHIVE_VERSION, "Unsupported block archive version ({})", bytes[8]);
+        let header = ArchiveHeader {
+            network: u16::from_le_bytes([bytes[9], bytes[10]]),
+            start_height: u32::from_le_bytes([bytes[11], bytes[12], bytes[13], bytes[14]]),
+            end_height: u32::from_le_bytes([bytes[15], bytes[16], bytes[17], bytes[18]]),
+        };
+        ensure!(header.start_height < header.end_height, "The block archive has an invalid height range");
+
+        let mut hasher = Sha256::new();
+        hasher.update(bytes);
+        Ok(Self { decoder, hasher, header, num_read: 0 })
+    }
+
+    /// Returns the header of the archive.
+    pub const fn header(&self) -> ArchiveHeader {
+        self.header
+    }
+
+    /// Reads the next block bytes, or returns `None` once all blocks were read and the checksum is verified.
+    pub fn read_block(&mut self) -> Result<Option<Vec<u8>>> {
+        if self.num_read == self.header.num_blocks() {
+            // Verify the checksum.
+            let mut checksum = [0u8; 32];
+            self.decoder.read_exact(&mut checksum)?;
+            ensure!(checksum[..] == self.hasher.clone().finalize()[..], "The block archive checksum does not match");
+            return Ok(None);
+        }
+
+        let mut length = [0u8; 4];
+        self.decoder.read_exact(&mut length)?;
+        let length = u32::from_le_bytes(length);
+        ensure!(length <= MAXIMUM_BLOCK_SIZE, "The block archive contains an oversized block");
+        let mut block_bytes = vec![0u8; length as usize];
+        self.decoder.read_exact(&mut block_bytes)?;
+
+        self.hasher.update(length.to_le_bytes());
+        self.hasher.update(&block_bytes);
+        self.num_read += 1;
+        Ok(Some(block_bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_archive(blocks: &[Vec<u8>]) -> Vec<u8> {
+        let header = ArchiveHeader { network: 1, start_height: 10, end_height: 10 + blocks.len() as u32 };
+        let mut writer = ArchiveWriter::new(Vec::new(), header).unwrap();
+        for block in blocks {
+            writer.write_block(block).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn test_archive_round_trip() {
+        let blocks = vec![vec![1u8; 100], vec![], vec![2u8; 5000]];
+        let archive = sample_archive(&blocks);
+
+        let mut reader = ArchiveReader::new(&archive[..]).unwrap();
+        assert_eq!(reader.header(), ArchiveHeader { network: 1, start_height: 10, end_height: 13 });
+        for block in &blocks {
+            assert_eq!(&reader.read_block().unwrap().unwrap(), block);
+        }
+        assert!(reader.read_block().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_archive_checksum() {
+        let blocks = vec![vec![1u8; 100], vec![2u8; 100]];
+        let archive = sample_archive(&blocks);
+
+        // Corrupt the last byte of the checksum, and re-compress the archive.
+        let mut bytes = Vec::new();
+        Decoder::new(&archive[..]).unwrap().read_to_end(&mut bytes).unwrap();
+        *bytes.last_mut().unwrap() ^= 1;
+        let archive = zstd::encode_all(&bytes[..], COMPRESSION_LEVEL).unwrap();
+
+        // Ensure the corruption is detected.
+        let mut reader = ArchiveReader::new(&archive[..]).unwrap();
+        assert!(reader.read_block().unwrap().is_some());
+        assert!(reader.read_block().unwrap().is_some());
+        assert!(reader.read_block().is_err());
+    }
+
+    #[test]
+    fn test_archive_index() {
+        let entry =
+            |file: &str, start_height, end_height| ArchiveIndexEntry { file: file.into(), start_height, end_height };
+
+        let mut index = ArchiveIndex::new(1);
+        index.append(entry("0.blocks", 0, 100)).unwrap();
+        index.append(entry("100.blocks", 100, 250)).unwrap();
+        assert_eq!(index.end_height(), 250);
+        index.check().unwrap();
+
+        // Ensure the archives must be contiguous, non-empty, and distinct.
+        assert!(index.clone().append(entry("300.blocks", 300, 400)).is_err());
+        assert!(index.clone().append(entry("250.blocks", 250, 250)).is_err());
+        assert!(index.clone().append(entry("0.blocks", 250, 300)).is_err());
+        // Ensure the index must start from genesis.
+        assert!(ArchiveIndex { network: 1, archives: vec![entry("100.blocks", 100, 250)] }.check().is_err());
+    }
+
+    #[test]
+    fn test_archive_incomplete() {
+        let header = ArchiveHeader { network: 1, start_height: 0, end_height: 2 };
+        let mut writer = ArchiveWriter::new(Vec::new(), header).unwrap();
+        writer.write_block(&[1u8; 10]).unwrap();
+        // Ensure an archive cannot be finished with missing blocks.
+        assert!(writer.finish().is_err());
+    }
+}
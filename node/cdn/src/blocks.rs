@@ -16,28 +16,32 @@
 // https://github.com/rust-lang/rust-clippy/issues/6446
 #![allow(clippy::await_holding_lock)]
 
+use crate::{ArchiveIndex, ArchiveIndexEntry, ArchiveReader, ARCHIVE_INDEX_FILE};
 use snarkvm::prelude::{
     block::Block,
     store::{cow_to_copied, ConsensusStorage},
     Deserialize,
     DeserializeOwned,
+    FromBytes,
     Ledger,
     Network,
     Serialize,
 };
 
-use anyhow::{anyhow, bail, Result};
+use anyhow::{anyhow, bail, ensure, Result};
 use colored::Colorize;
 use parking_lot::Mutex;
 use reqwest::Client;
 use std::{
     cmp,
+    io::{self, Read},
     sync::{
         atomic::{AtomicBool, AtomicU32, Ordering},
         Arc,
     },
     time::{Duration, Instant},
 };
+use tokio::sync::mpsc;
 
 /// The number of blocks per file.
 const BLOCKS_PER_FILE: u32 = 50;
@@ -45,6 +49,8 @@ const BLOCKS_PER_FILE: u32 = 50;
 const CONCURRENT_REQUESTS: u32 = 16;
 /// Maximum number of pending sync blocks.
 const MAXIMUM_PENDING_BLOCKS: u32 = BLOCKS_PER_FILE * CONCURRENT_REQUESTS * 2;
+/// The maximum number of downloaded chunks of a block archive that are buffered ahead of its ingestion.
+const MAXIMUM_BUFFERED_CHUNKS: usize = 1024;
 /// Maximum number of attempts for a request to the CDN.
 const MAXIMUM_REQUEST_ATTEMPTS: u8 = 10;
 /// The supported network.
@@ -52,6 +58,8 @@ const NETWORK_ID: u16 = 0;
 
 /// Loads blocks from a CDN into the ledger.
 ///
+/// If the given URL points to the index of a static block archive, the blocks are loaded from the archive.
+///
 /// On success, this function returns the completed block height.
 /// On failure, this function returns the last successful block height (if any), along with the error.
 pub async fn sync_ledger_with_cdn<N: Network, C: ConsensusStorage<N>>(
@@ -59,6 +67,11 @@ pub async fn sync_ledger_with_cdn<N: Network, C: ConsensusStorage<N>>(
     ledger: Ledger<N, C>,
     shutdown: Arc<AtomicBool>,
 ) -> Result<u32, (u32, anyhow::Error)> {
+    // If the URL points to a static block archive, load the blocks from the archive.
+    if base_url.ends_with(ARCHIVE_INDEX_FILE) {
+        return sync_ledger_with_archive(base_url, ledger, shutdown).await;
+    }
+
     // Fetch the node height.
     let start_height = ledger.latest_height() + 1;
     // Load the blocks from the CDN into the ledger.
@@ -96,6 +109,206 @@ pub async fn sync_ledger_with_cdn<N: Network, C: ConsensusStorage<N>>(
     }
 }
 
+/// Loads blocks from a static block archive into the ledger, verifying each block before advancing.
+///
+/// On success, this function returns the completed block height.
+/// On failure, this function returns the last successful block height (if any), along with the error.
+pub async fn sync_ledger_with_archive<N: Network, C: ConsensusStorage<N>>(
+    index_url: &str,
+    ledger: Ledger<N, C>,
+    shutdown: Arc<AtomicBool>,
+) -> Result<u32, (u32, anyhow::Error)> {
+    // Create a Client to maintain a connection pool throughout the sync.
+    let client = match Client::builder().build() {
+        Ok(client) => client,
+        Err(error) => {
+            return Err((ledger.latest_height(), anyhow!("Failed to create an archive request client - {error}")))
+        }
+    };
+
+    // Fetch the index of the archive.
+    let index = match archive_index::<N>(&client, index_url).await {
+        Ok(index) => index,
+        Err(error) => return Err((ledger.latest_height(), error)),
+    };
+    // Select the archives with blocks the ledger does not have yet.
+    let start_height = ledger.latest_height() + 1;
+    let entries: Vec<_> = index.archives.into_iter().filter(|entry| entry.end_height > start_height).collect();
+    let Some(end_height) = entries.last().map(|entry| entry.end_height) else {
+        debug!("The block archive has no blocks after block {}", start_height - 1);
+        return Ok(start_height - 1);
+    };
+
+    // The archive files are located next to the index.
+    let base_url = index_url.trim_end_matches(ARCHIVE_INDEX_FILE).to_string();
+    let fetch = |entry: &ArchiveIndexEntry| {
+        let (sender, receiver) = mpsc::channel(MAXIMUM_BUFFERED_CHUNKS);
+        tokio::spawn(cdn_stream_bytes(client.clone(), format!("{base_url}{}", entry.file), sender));
+        StreamReader::new(receiver)
+    };
+
+    let timer = Instant::now();
+    // Start downloading the next archive while the current one is being ingested.
+    let mut next_download = entries.first().map(fetch);
+    for (i, entry) in entries.iter().enumerate() {
+        let Some(reader) = next_download.take() else {
+            break;
+        };
+        next_download = entries.get(i + 1).map(fetch);
+
+        // If the node is shutting down, stop the sync.
+        if shutdown.load(Ordering::Acquire) {
+            info!("Stopping block sync at {} - shutting down", ledger.latest_height());
+            // We can shut down cleanly from here, as the node hasn't been started yet.
+            std::process::exit(0);
+        }
+
+        // Ingest the archive on a blocking thread, as it is streamed.
+        let ledger_clone = ledger.clone();
+        let entry_clone = entry.clone();
+        let result = tokio::task::spawn_blocking(move || ingest_archive(&ledger_clone, &entry_clone, reader)).await;
+        match result {
+            Ok(Ok(())) => (),
+            Ok(Err(error)) => return Err((ledger.latest_height(), error)),
+            Err(error) => return Err((ledger.latest_height(), anyhow!("Failed to ingest '{}' - {error}", entry.file))),
+        }
+
+        // Log the progress.
+        log_progress::<1>(timer, ledger.latest_height(), start_height, end_height, "block");
+    }
+
+    Ok(ledger.latest_height())
+}
+
+/// Retrieves and checks the index of a static block archive.
+async fn archive_index<N: Network>(client: &Client, index_url: &str) -> Result<ArchiveIndex> {
+    // Fetch the index.
+    let bytes = cdn_get_bytes(client, index_url).await?;
+    // Parse the index.
+    let index = match serde_json::from_slice::<ArchiveIndex>(&bytes) {
+        Ok(index) => index,
+        Err(error) => bail!("Failed to parse the block archive index - {error}"),
+    };
+    ensure!(index.network == N::ID, "The block archive is for network {}, not network {}", index.network, N::ID);
+    index.check()?;
+    Ok(index)
+}
+
+/// Retrieves the raw bytes from the given URL.
+async fn cdn_get_bytes(client: &Client, url: &str) -> Result<Vec<u8>> {
+    // Fetch the bytes from the given URL.
+    let response = match client.get(url).send().await {
+        Ok(response) => response.error_for_status()?,
+        Err(error) => bail!("Failed to fetch '{url}' - {error}"),
+    };
+    // Parse the response.
+    match response.bytes().await {
+        Ok(bytes) => Ok(bytes.to_vec()),
+        Err(error) => bail!("Failed to parse the response from '{url}' - {error}"),
+    }
+}
+
+/// Streams the body of the given URL to the given channel, in chunks, and reports a failed download to it.
+async fn cdn_stream_bytes(client: Client, url: String, sender: mpsc::Sender<Result<Vec<u8>>>) {
+    if let Err(error) = cdn_send_chunks(&client, &url, &sender).await {
+        let _ = sender.send(Err(error)).await;
+    }
+}
+
+/// Sends the body of the given URL to the given channel, in chunks, until the receiver is dropped.
+async fn cdn_send_chunks(client: &Client, url: &str, sender: &mpsc::Sender<Result<Vec<u8>>>) -> Result<()> {
+    let mut response = match client.get(url).send().await {
+        Ok(response) => response.error_for_status()?,
+        Err(error) => bail!("Failed to fetch '{url}' - {error}"),
+    };
+    loop {
+        match response.chunk().await {
+            Ok(Some(chunk)) => {
+                // If the archive is no longer ingested, stop the download.
+                if sender.send(Ok(chunk.to_vec())).await.is_err() {
+                    return Ok(());
+                }
+            }
+            Ok(None) => return Ok(()),
+            Err(error) => bail!("Failed to download '{url}' - {error}"),
+        }
+    }
+}
+
+/// A blocking reader of the chunks of a download, which are streamed through a bounded channel.
+struct StreamReader {
+    /// The receiver of the downloaded chunks.
+    receiver: mpsc::Receiver<Result<Vec<u8>>>,
+    /// The current chunk.
+    chunk: Vec<u8>,
+    /// The number of bytes of the current chunk that were read.
+    offset: usize,
+}
+
+impl StreamReader {
+    /// Initializes a new reader of the chunks of the given receiver.
+    fn new(receiver: mpsc::Receiver<Result<Vec<u8>>>) -> Self {
+        Self { receiver, chunk: Vec::new(), offset: 0 }
+    }
+}
+
+impl Read for StreamReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // Wait for the next chunk, once the current one was read.
+        while self.offset == self.chunk.len() {
+            match self.receiver.blocking_recv() {
+                Some(Ok(chunk)) => (self.chunk, self.offset) = (chunk, 0),
+                Some(Err(error)) => return Err(io::Error::new(io::ErrorKind::Other, error.to_string())),
+                // The download completed.
+                None => return Ok(0),
+            }
+        }
+        let num_bytes = cmp::min(buf.len(), self.chunk.len() - self.offset);
+        buf[..num_bytes].copy_from_slice(&self.chunk[self.offset..self.offset + num_bytes]);
+        self.offset += num_bytes;
+        Ok(num_bytes)
+    }
+}
+
+/// Verifies each block of the given archive against the ledger, and advances the ledger with the new ones.
+fn ingest_archive<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    entry: &ArchiveIndexEntry,
+    input: impl Read,
+) -> Result<()> {
+    // Read the archive header, and ensure it matches the index.
+    let mut reader = ArchiveReader::new(input)?;
+    let header = reader.header();
+    ensure!(
+        header.network == N::ID && header.start_height == entry.start_height && header.end_height == entry.end_height,
+        "The header of '{}' does not match the block archive index",
+        entry.file
+    );
+
+    // Ingest the blocks, in order.
+    let mut expected_height = header.start_height;
+    while let Some(bytes) = reader.read_block()? {
+        let block = Block::<N>::from_bytes_le(&bytes)?;
+        ensure!(block.height() == expected_height, "Expected block {expected_height} in '{}'", entry.file);
+        // Ensure the blocks the ledger already has are the same, and advance with the new ones.
+        if block.height() <= ledger.latest_height() {
+            ensure!(
+                ledger.get_hash(block.height())? == block.hash(),
+                "Block {} of '{}' conflicts with the ledger",
+                block.height(),
+                entry.file
+            );
+        } else {
+            ledger
+                .check_next_block(&block)
+                .and_then(|_| ledger.advance_to_next_block(&block))
+                .map_err(|error| anyhow!("Failed to sync block {} from '{}' - {error}", block.height(), entry.file))?;
+        }
+        expected_height += 1;
+    }
+    Ok(())
+}
+
 /// Loads blocks from a CDN and process them with the given function.
 ///
 /// On success, this function returns the completed block height.
@@ -435,13 +648,14 @@ fn log_progress<const OBJECTS_PER_FILE: u32>(
 #[cfg(test)]
 mod tests {
     use crate::{
-        blocks::{cdn_get, cdn_height, log_progress, BLOCKS_PER_FILE},
+        blocks::{cdn_get, cdn_height, log_progress, StreamReader, BLOCKS_PER_FILE},
         load_blocks,
     };
     use snarkvm::prelude::{block::Block, MainnetV0};
 
     use parking_lot::RwLock;
-    use std::{sync::Arc, time::Instant};
+    use std::{io::Read, sync::Arc, time::Instant};
+    use tokio::sync::mpsc;
 
     type CurrentNetwork = MainnetV0;
 
@@ -518,6 +732,28 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_stream_reader() {
+        let (sender, receiver) = mpsc::channel(2);
+        let writer = std::thread::spawn(move || {
+            for chunk in [vec![1, 2, 3], vec![], vec![4, 5]] {
+                sender.blocking_send(Ok(chunk)).unwrap();
+            }
+        });
+        // Ensure the chunks are read in order, across the bounded channel.
+        let mut bytes = Vec::new();
+        StreamReader::new(receiver).read_to_end(&mut bytes).unwrap();
+        assert_eq!(bytes, vec![1, 2, 3, 4, 5]);
+        writer.join().unwrap();
+
+        // Ensure a failed download is reported to the reader.
+        let (sender, receiver) = mpsc::channel(2);
+        sender.blocking_send(Ok(vec![1])).unwrap();
+        sender.blocking_send(Err(anyhow::anyhow!("The download failed"))).unwrap();
+        let mut bytes = Vec::new();
+        assert!(StreamReader::new(receiver).read_to_end(&mut bytes).is_err());
+    }
+
     #[test]
     fn test_log_progress() {
         // This test sanity checks that basic arithmetic is correct (i.e. no divide by zero, etc.).
@@ -17,5 +17,8 @@
 #[macro_use]
 extern crate tracing;
 
+mod archive;
+pub use archive::*;
+
 mod blocks;
-pub use blocks::{load_blocks, sync_ledger_with_cdn};
+pub use blocks::{load_blocks, sync_ledger_with_archive, sync_ledger_with_cdn};
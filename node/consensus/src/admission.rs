@@ -0,0 +1,231 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_bft::helpers::{fmt_id, TransactionFilter};
+use snarkvm::{ledger::block::Transaction, prelude::*};
+
+use anyhow::Result;
+use std::marker::PhantomData;
+
+/// The admission policy of the memory pool, which rejects unconfirmed transactions before they are queued,
+/// so that operators can protect their validators from spam.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AdmissionPolicy<N: Network> {
+    /// The minimum fee of a transaction, in microcredits.
+    min_fee: u64,
+    /// The minimum fee of a transaction per byte of its size, in microcredits.
+    min_fee_per_byte: u64,
+    /// The maximum size of a transaction, in bytes.
+    max_transaction_size: usize,
+    /// The maximum nested execution depth, i.e. the maximum number of transitions in an execution,
+    /// as every nested call adds a transition.
+    max_execution_depth: usize,
+    /// The maximum number of pending transactions of a fee payer in the memory pool.
+    max_pending_transactions_per_account: usize,
+    /// The maximum total size of the pending transactions of a fee payer in the memory pool, in bytes.
+    max_pending_bytes_per_account: usize,
+    /// The local filter of the transactions, by program.
+    transaction_filter: TransactionFilter<N>,
+    /// PhantomData.
+    _phantom: PhantomData<N>,
+}
+
+impl<N: Network> Default for AdmissionPolicy<N> {
+    /// Returns the default policy, which admits every transaction within the protocol limits.
+    fn default() -> Self {
+        Self {
+            min_fee: 0,
+            min_fee_per_byte: 0,
+            max_transaction_size: N::MAX_TRANSACTION_SIZE,
+            max_execution_depth: Transaction::<N>::MAX_TRANSITIONS,
+            max_pending_transactions_per_account: usize::MAX,
+            max_pending_bytes_per_account: usize::MAX,
+            transaction_filter: Default::default(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<N: Network> AdmissionPolicy<N> {
+    /// Initializes a new policy, with the given values or the defaults, ensuring they are within the protocol limits.
+    pub fn new(
+        min_fee: Option<u64>,
+        min_fee_per_byte: Option<u64>,
+        max_transaction_size: Option<usize>,
+        max_execution_depth: Option<usize>,
+        max_pending_transactions_per_account: Option<usize>,
+        max_pending_bytes_per_account: Option<usize>,
+    ) -> Result<Self> {
+        let max_transaction_size = max_transaction_size.unwrap_or(N::MAX_TRANSACTION_SIZE);
+        ensure!(
+            (1..=N::MAX_TRANSACTION_SIZE).contains(&max_transaction_size),
+            "The maximum transaction size must be between 1 and {} bytes",
+            N::MAX_TRANSACTION_SIZE
+        );
+        let max_execution_depth = max_execution_depth.unwrap_or(Transaction::<N>::MAX_TRANSITIONS);
+        ensure!(
+            (1..=Transaction::<N>::MAX_TRANSITIONS).contains(&max_execution_depth),
+            "The maximum execution depth must be between 1 and {}",
+            Transaction::<N>::MAX_TRANSITIONS
+        );
+        let max_pending_transactions_per_account = max_pending_transactions_per_account.unwrap_or(usize::MAX);
+        ensure!(
+            max_pending_transactions_per_account > 0,
+            "The maximum pending transactions per account must be positive"
+        );
+        let max_pending_bytes_per_account = max_pending_bytes_per_account.unwrap_or(usize::MAX);
+        // Note: A fee payer must be able to queue at least one transaction of the maximum size.
+        ensure!(
+            max_pending_bytes_per_account >= max_transaction_size,
+            "The maximum pending bytes per account must be at least the maximum transaction size ({max_transaction_size} bytes)"
+        );
+        Ok(Self {
+            min_fee: min_fee.unwrap_or(0),
+            min_fee_per_byte: min_fee_per_byte.unwrap_or(0),
+            max_transaction_size,
+            max_execution_depth,
+            max_pending_transactions_per_account,
+            max_pending_bytes_per_account,
+            transaction_filter: Default::default(),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Sets the local filter of the transactions, which rejects the transactions of the denied programs,
+    /// or below the minimum fee of a program.
+    pub fn with_transaction_filter(mut self, transaction_filter: TransactionFilter<N>) -> Self {
+        self.transaction_filter = transaction_filter;
+        self
+    }
+
+    /// Returns the minimum fee of a transaction, in microcredits.
+    pub const fn min_fee(&self) -> u64 {
+        self.min_fee
+    }
+
+    /// Returns the minimum fee of a transaction per byte of its size, in microcredits.
+    pub const fn min_fee_per_byte(&self) -> u64 {
+        self.min_fee_per_byte
+    }
+
+    /// Returns the maximum size of a transaction, in bytes.
+    pub const fn max_transaction_size(&self) -> usize {
+        self.max_transaction_size
+    }
+
+    /// Returns the maximum nested execution depth.
+    pub const fn max_execution_depth(&self) -> usize {
+        self.max_execution_depth
+    }
+
+    /// Returns the maximum number of pending transactions of a fee payer in the memory pool.
+    pub const fn max_pending_transactions_per_account(&self) -> usize {
+        self.max_pending_transactions_per_account
+    }
+
+    /// Returns the maximum total size of the pending transactions of a fee payer in the memory pool, in bytes.
+    pub const fn max_pending_bytes_per_account(&self) -> usize {
+        self.max_pending_bytes_per_account
+    }
+
+    /// Returns the local filter of the transactions.
+    pub const fn transaction_filter(&self) -> &TransactionFilter<N> {
+        &self.transaction_filter
+    }
+
+    /// Ensures the given transaction is admitted by the policy.
+    pub fn check(&self, transaction: &Transaction<N>) -> Result<()> {
+        // Ensure the transaction is admitted by the local transaction filter.
+        self.transaction_filter.check(transaction)?;
+        // If the limits of a transaction are the defaults, every transaction is admitted, so skip the checks.
+        // Note: The limits per account depend on the memory pool, and are enforced as the transaction is queued.
+        let default = Self::default();
+        if self.min_fee == default.min_fee
+            && self.min_fee_per_byte == default.min_fee_per_byte
+            && self.max_transaction_size == default.max_transaction_size
+            && self.max_execution_depth == default.max_execution_depth
+        {
+            return Ok(());
+        }
+        let transaction_id = fmt_id(transaction.id());
+
+        // Ensure the transaction does not exceed the maximum size.
+        let size = transaction.to_bytes_le()?.len();
+        ensure!(
+            size <= self.max_transaction_size,
+            "Transaction '{transaction_id}' is {size} bytes, exceeding the maximum of {} bytes",
+            self.max_transaction_size
+        );
+        // Ensure the execution does not exceed the maximum depth.
+        if let Some(execution) = transaction.execution() {
+            ensure!(
+                execution.len() <= self.max_execution_depth,
+                "Transaction '{transaction_id}' has {} transitions, exceeding the maximum depth of {}",
+                execution.len(),
+                self.max_execution_depth
+            );
+        }
+        // Ensure the fee meets the minimum fee, and the minimum fee for its size.
+        let fee = *transaction.fee_amount()?;
+        let min_fee = self.min_fee.max(self.min_fee_per_byte.saturating_mul(size as u64));
+        ensure!(
+            fee >= min_fee,
+            "Transaction '{transaction_id}' has a fee of {fee} microcredits, below the minimum of {min_fee} microcredits"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type CurrentNetwork = MainnetV0;
+    type Policy = AdmissionPolicy<CurrentNetwork>;
+
+    #[test]
+    fn test_admission_policy() {
+        // Ensure the defaults match the protocol limits.
+        assert_eq!(Policy::new(None, None, None, None, None, None).unwrap(), Policy::default());
+        assert_eq!(Policy::default().min_fee(), 0);
+        assert_eq!(Policy::default().max_transaction_size(), CurrentNetwork::MAX_TRANSACTION_SIZE);
+        assert_eq!(Policy::default().max_execution_depth(), Transaction::<CurrentNetwork>::MAX_TRANSITIONS);
+
+        // Ensure the values within the limits are accepted.
+        let policy = Policy::new(Some(1000), Some(2), Some(10_000), Some(4), Some(16), Some(100_000)).unwrap();
+        assert_eq!(policy.min_fee(), 1000);
+        assert_eq!(policy.min_fee_per_byte(), 2);
+        assert_eq!(policy.max_transaction_size(), 10_000);
+        assert_eq!(policy.max_execution_depth(), 4);
+        assert_eq!(policy.max_pending_transactions_per_account(), 16);
+        assert_eq!(policy.max_pending_bytes_per_account(), 100_000);
+
+        // Ensure the values beyond the limits are rejected.
+        assert!(Policy::new(None, None, Some(0), None, None, None).is_err());
+        assert!(Policy::new(None, None, Some(CurrentNetwork::MAX_TRANSACTION_SIZE + 1), None, None, None).is_err());
+        assert!(Policy::new(None, None, None, Some(0), None, None).is_err());
+        assert!(Policy::new(None, None, None, Some(Transaction::<CurrentNetwork>::MAX_TRANSITIONS + 1), None, None)
+            .is_err());
+        assert!(Policy::new(None, None, None, None, Some(0), None).is_err());
+        // Ensure the pending bytes per account fit a transaction of the maximum size.
+        assert!(Policy::new(None, None, Some(10_000), None, None, Some(9_999)).is_err());
+        assert!(Policy::new(None, None, None, None, None, Some(CurrentNetwork::MAX_TRANSACTION_SIZE - 1)).is_err());
+
+        // Ensure the transaction filter is set.
+        let filter = TransactionFilter::parse(&["spam.aleo".to_string()], &[]).unwrap();
+        let policy = Policy::default().with_transaction_filter(filter.clone());
+        assert_eq!(policy.transaction_filter(), &filter);
+        assert_ne!(policy, Policy::default());
+    }
+}
@@ -17,15 +17,31 @@
 #[macro_use]
 extern crate tracing;
 
-use snarkos_account::Account;
+mod admission;
+pub use admission::*;
+
+mod pending;
+pub use pending::*;
+
+mod queue;
+use queue::*;
+
 use snarkos_node_bft::{
     helpers::{
         fmt_id,
         init_consensus_channels,
+        AuditLog,
         ConsensusReceiver,
+        Failover,
+        OperatorLane,
+        ParticipationConfig,
         PrimaryReceiver,
         PrimarySender,
+        ProposalConfig,
+        Signer,
+        SigningJournal,
         Storage as NarwhalStorage,
+        TimeoutConfig,
     },
     spawn_blocking,
     Primary,
@@ -48,7 +64,7 @@ use colored::Colorize;
 use indexmap::IndexMap;
 use lru::LruCache;
 use parking_lot::Mutex;
-use std::{future::Future, net::SocketAddr, num::NonZeroUsize, sync::Arc};
+use std::{future::Future, net::SocketAddr, num::NonZeroUsize, path::Path, sync::Arc};
 use tokio::{
     sync::{oneshot, OnceCell},
     task::JoinHandle,
@@ -57,33 +73,16 @@ use tokio::{
 #[cfg(feature = "metrics")]
 use std::collections::HashMap;
 
-/// The capacity of the queue reserved for deployments.
-/// Note: This is an inbound queue capacity, not a Narwhal-enforced capacity.
-const CAPACITY_FOR_DEPLOYMENTS: usize = 1 << 10;
-/// The capacity of the queue reserved for executions.
-/// Note: This is an inbound queue capacity, not a Narwhal-enforced capacity.
-const CAPACITY_FOR_EXECUTIONS: usize = 1 << 10;
 /// The capacity of the queue reserved for solutions.
 /// Note: This is an inbound queue capacity, not a Narwhal-enforced capacity.
 const CAPACITY_FOR_SOLUTIONS: usize = 1 << 10;
 /// The **suggested** maximum number of deployments in each interval.
 /// Note: This is an inbound queue limit, not a Narwhal-enforced limit.
 const MAX_DEPLOYMENTS_PER_INTERVAL: usize = 1;
-
-/// Helper struct to track incoming transactions.
-struct TransactionsQueue<N: Network> {
-    pub deployments: LruCache<N::TransactionID, Transaction<N>>,
-    pub executions: LruCache<N::TransactionID, Transaction<N>>,
-}
-
-impl<N: Network> Default for TransactionsQueue<N> {
-    fn default() -> Self {
-        Self {
-            deployments: LruCache::new(NonZeroUsize::new(CAPACITY_FOR_DEPLOYMENTS).unwrap()),
-            executions: LruCache::new(NonZeroUsize::new(CAPACITY_FOR_EXECUTIONS).unwrap()),
-        }
-    }
-}
+/// The maximum time an unconfirmed transaction may wait in the queue before it expires.
+const MEMPOOL_TRANSACTION_TTL_IN_SECS: i64 = 10 * 60; // seconds
+/// The interval at which expired transactions are removed from the queue.
+const MEMPOOL_EXPIRATION_INTERVAL_IN_SECS: u64 = 30; // seconds
 
 #[derive(Clone)]
 pub struct Consensus<N: Network> {
@@ -97,10 +96,14 @@ pub struct Consensus<N: Network> {
     solutions_queue: Arc<Mutex<LruCache<SolutionID<N>, Solution<N>>>>,
     /// The unconfirmed transactions queue.
     transactions_queue: Arc<Mutex<TransactionsQueue<N>>>,
-    /// The recently-seen unconfirmed solutions.
-    seen_solutions: Arc<Mutex<LruCache<SolutionID<N>, ()>>>,
-    /// The recently-seen unconfirmed transactions.
-    seen_transactions: Arc<Mutex<LruCache<N::TransactionID, ()>>>,
+    /// The recently-seen unconfirmed solutions, with the UNIX timestamp at which they were first seen.
+    seen_solutions: Arc<Mutex<LruCache<SolutionID<N>, i64>>>,
+    /// The recently-seen unconfirmed transactions, with the UNIX timestamp at which they were first seen.
+    seen_transactions: Arc<Mutex<LruCache<N::TransactionID, i64>>>,
+    /// The admission policy of the memory pool.
+    admission_policy: AdmissionPolicy<N>,
+    /// The priority lane for the transactions of the operators of the validator.
+    operator_lane: Arc<OperatorLane<N>>,
     #[cfg(feature = "metrics")]
     transmissions_queue_timestamps: Arc<Mutex<HashMap<TransmissionID<N>, i64>>>,
     /// The spawned handles.
@@ -110,7 +113,7 @@ pub struct Consensus<N: Network> {
 impl<N: Network> Consensus<N> {
     /// Initializes a new instance of consensus.
     pub fn new(
-        account: Account<N>,
+        signer: impl Into<Signer<N>>,
         ledger: Arc<dyn LedgerService<N>>,
         ip: Option<SocketAddr>,
         trusted_validators: &[SocketAddr],
@@ -126,7 +129,7 @@ impl<N: Network> Consensus<N> {
         // Initialize the Narwhal storage.
         let storage = NarwhalStorage::new(ledger.clone(), transmissions, BatchHeader::<N>::MAX_GC_ROUNDS as u64);
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger.clone(), ip, trusted_validators, dev)?;
+        let bft = BFT::new(signer, storage, ledger.clone(), ip, trusted_validators, dev)?;
         // Return the consensus.
         Ok(Self {
             ledger,
@@ -136,12 +139,76 @@ impl<N: Network> Consensus<N> {
             transactions_queue: Default::default(),
             seen_solutions: Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(1 << 16).unwrap()))),
             seen_transactions: Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(1 << 16).unwrap()))),
+            admission_policy: Default::default(),
+            operator_lane: Default::default(),
             #[cfg(feature = "metrics")]
             transmissions_queue_timestamps: Default::default(),
             handles: Default::default(),
         })
     }
 
+    /// Sets the configuration of the batch proposals of the primary.
+    pub fn with_proposal_config(mut self, proposal_config: ProposalConfig<N>) -> Self {
+        self.bft = self.bft.with_proposal_config(proposal_config);
+        self
+    }
+
+    /// Sets the configuration of the round timeouts of the BFT.
+    pub fn with_timeout_config(mut self, timeout_config: TimeoutConfig) -> Self {
+        self.bft = self.bft.with_timeout_config(timeout_config);
+        self
+    }
+
+    /// Sets the active/standby failover, which only lets the holder of the signing lease propose and sign.
+    pub fn with_failover(mut self, failover: Arc<Failover<N>>) -> Self {
+        self.bft = self.bft.with_failover(failover);
+        self
+    }
+
+    /// Sets the journal of the signed batches, which protects against equivocation.
+    pub fn with_signing_journal(mut self, signing_journal: SigningJournal<N>) -> Self {
+        self.bft = self.bft.with_signing_journal(signing_journal);
+        self
+    }
+
+    /// Enables the checkpoints of the in-flight state, so that the primary recovers it after a crash.
+    pub fn with_state_checkpoints(mut self) -> Self {
+        self.bft = self.bft.with_state_checkpoints();
+        self
+    }
+
+    /// Enables the tracking of the participation of this validator, which alerts when it drops below the threshold.
+    pub fn with_participation_config(mut self, config: ParticipationConfig) -> Self {
+        self.bft = self.bft.with_participation_config(config);
+        self
+    }
+
+    /// Sets the audit log, which records the consensus events of the BFT.
+    pub fn with_audit_log(mut self, audit_log: AuditLog<N>) -> Self {
+        self.bft = self.bft.with_audit_log(audit_log);
+        self
+    }
+
+    /// Sets the number of rounds of certificates that the BFT storage retains.
+    pub fn with_retention_rounds(self, retention_rounds: u64) -> Result<Self> {
+        self.bft.storage().set_retention_rounds(retention_rounds)?;
+        Ok(self)
+    }
+
+    /// Sets the admission policy of the memory pool, whose transaction filter also applies to the batch proposals.
+    pub fn with_admission_policy(mut self, admission_policy: AdmissionPolicy<N>) -> Self {
+        self.bft = self.bft.with_transaction_filter(admission_policy.transaction_filter().clone());
+        self.admission_policy = admission_policy;
+        self
+    }
+
+    /// Sets the priority lane for the transactions of the operators, which bypass the fee ordering of the queue.
+    pub fn with_operator_lane(mut self, operator_lane: OperatorLane<N>) -> Self {
+        self.bft = self.bft.with_operator_lane(operator_lane.clone());
+        self.operator_lane = Arc::new(operator_lane);
+        self
+    }
+
     /// Run the consensus instance.
     pub async fn run(&mut self, primary_sender: PrimarySender<N>, primary_receiver: PrimaryReceiver<N>) -> Result<()> {
         info!("Starting the consensus instance...");
@@ -196,6 +263,16 @@ impl<N: Network> Consensus<N> {
 }
 
 impl<N: Network> Consensus<N> {
+    /// Returns the UNIX timestamp at which this node first received the given unconfirmed transmission, if it was
+    /// received directly, rather than through the workers of its peers.
+    pub fn transmission_received_at(&self, transmission_id: TransmissionID<N>) -> Option<i64> {
+        match transmission_id {
+            TransmissionID::Solution(solution_id) => self.seen_solutions.lock().peek(&solution_id).copied(),
+            TransmissionID::Transaction(transaction_id) => self.seen_transactions.lock().peek(&transaction_id).copied(),
+            TransmissionID::Ratification => None,
+        }
+    }
+
     /// Returns the unconfirmed transmission IDs.
     pub fn unconfirmed_transmission_ids(&self) -> impl '_ + Iterator<Item = TransmissionID<N>> {
         self.worker_transmission_ids().chain(self.inbound_transmission_ids())
@@ -271,7 +348,7 @@ impl<N: Network> Consensus<N> {
             .clone()
             .into_iter()
             .chain(tx_queue.executions.clone())
-            .map(|(id, tx)| (id, Data::Object(tx)))
+            .map(|(id, entry)| (id, Data::Object(entry.transaction)))
     }
 }
 
@@ -289,7 +366,7 @@ impl<N: Network> Consensus<N> {
             let solution_id = solution.id();
 
             // Check if the transaction was recently seen.
-            if self.seen_solutions.lock().put(solution_id, ()).is_some() {
+            if mark_seen(&self.seen_solutions, solution_id) {
                 // If the transaction was recently seen, return early.
                 return Ok(());
             }
@@ -358,22 +435,34 @@ impl<N: Network> Consensus<N> {
                 bail!("Transaction '{}' is a fee transaction {}", fmt_id(transaction_id), "(skipping)".dimmed());
             }
             // Check if the transaction was recently seen.
-            if self.seen_transactions.lock().put(transaction_id, ()).is_some() {
+            if mark_seen(&self.seen_transactions, transaction_id) {
                 // If the transaction was recently seen, return early.
                 return Ok(());
             }
+            // Check if the transaction is admitted by the admission policy.
+            self.admission_policy.check(&transaction)?;
             // Check if the transaction already exists in the ledger.
             if self.ledger.contains_transmission(&TransmissionID::from(&transaction_id))? {
                 bail!("Transaction '{}' exists in the ledger {}", fmt_id(transaction_id), "(skipping)".dimmed());
             }
+            // Send the transactions of the operators directly to the primary, bypassing the fee ordering of the queue.
+            if self.operator_lane.is_operator_transaction(&transaction) {
+                debug!("Adding operator transaction '{}' to the memory pool...", fmt_id(transaction_id));
+                return self
+                    .primary_sender()
+                    .send_unconfirmed_transaction(transaction_id, Data::Object(transaction))
+                    .await;
+            }
             // Add the transaction to the memory pool.
             trace!("Received unconfirmed transaction '{}' in the queue", fmt_id(transaction_id));
-            if transaction.is_deploy() {
-                if self.transactions_queue.lock().deployments.put(transaction_id, transaction).is_some() {
-                    bail!("Transaction '{}' exists in the memory pool", fmt_id(transaction_id));
-                }
-            } else if self.transactions_queue.lock().executions.put(transaction_id, transaction).is_some() {
-                bail!("Transaction '{}' exists in the memory pool", fmt_id(transaction_id));
+            let timestamp = snarkos_node_bft::helpers::now();
+            // Note: If the queue is full, the transaction with the lowest fee is evicted.
+            if let Some(evicted_id) =
+                self.transactions_queue.lock().insert(transaction, timestamp, &self.admission_policy)?
+            {
+                debug!("Evicted unconfirmed transaction '{}' from the full memory pool", fmt_id(evicted_id));
+                #[cfg(feature = "metrics")]
+                self.remove_queued_transaction_metrics(&[evicted_id], metrics::consensus::EVICTED_TRANSACTIONS);
             }
         }
 
@@ -397,12 +486,9 @@ impl<N: Network> Consensus<N> {
             let selector_iter = (0..num_deployments).map(|_| true).interleave((0..num_executions).map(|_| false));
             // Drain the transactions from the queue, interleaving deployments and executions.
             selector_iter
-                .filter_map(|select_deployment| {
-                    if select_deployment {
-                        tx_queue.deployments.pop_lru().map(|(_, tx)| tx)
-                    } else {
-                        tx_queue.executions.pop_lru().map(|(_, tx)| tx)
-                    }
+                .filter_map(|select_deployment| match select_deployment {
+                    true => tx_queue.pop_deployment(),
+                    false => tx_queue.pop_execution(),
                 })
                 .collect_vec()
         };
@@ -427,6 +513,51 @@ impl<N: Network> Consensus<N> {
     }
 }
 
+impl<N: Network> Consensus<N> {
+    /// Saves the unconfirmed transactions in the memory pool to the given file, so that they survive a restart.
+    /// Returns the number of saved transactions.
+    pub fn save_pending_transactions(&self, path: &Path) -> Result<usize> {
+        // Collect the unconfirmed transactions, from the workers and the inbound queue.
+        let transactions = self
+            .unconfirmed_transactions()
+            .filter_map(|(transaction_id, transaction)| match transaction.deserialize_blocking() {
+                Ok(transaction) => Some(transaction),
+                Err(error) => {
+                    warn!("Skipping the pending transaction '{}' - {error}", fmt_id(transaction_id));
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        // Save the pending transactions.
+        let pending_transactions = PendingTransactions::new(transactions);
+        pending_transactions.save(path)?;
+        Ok(pending_transactions.len())
+    }
+
+    /// Loads the unconfirmed transactions saved by a previous run from the given file, and adds them
+    /// to the memory pool, re-validating each of them. Returns the number of re-added transactions.
+    pub async fn load_pending_transactions(&self, path: &Path) -> Result<usize> {
+        // Load the pending transactions, and remove the file, as they are only re-added once.
+        let pending_transactions = PendingTransactions::<N>::load(path)?;
+        if let Err(error) = std::fs::remove_file(path) {
+            if error.kind() != std::io::ErrorKind::NotFound {
+                bail!("Failed to remove the pending transactions in {} - {error}", path.display());
+            }
+        }
+
+        // Add the pending transactions to the memory pool, which re-validates them.
+        let mut num_transactions = 0;
+        for transaction in pending_transactions.into_transactions() {
+            let transaction_id = transaction.id();
+            match self.add_unconfirmed_transaction(transaction).await {
+                Ok(()) => num_transactions += 1,
+                Err(error) => debug!("Discarding the pending transaction '{}' - {error}", fmt_id(transaction_id)),
+            }
+        }
+        Ok(num_transactions)
+    }
+}
+
 impl<N: Network> Consensus<N> {
     /// Starts the consensus handlers.
     fn start_handlers(&self, consensus_receiver: ConsensusReceiver<N>) {
@@ -439,9 +570,42 @@ impl<N: Network> Consensus<N> {
                 self_.process_bft_subdag(committed_subdag, transmissions, callback).await;
             }
         });
+
+        // Periodically remove the expired transactions from the queue.
+        let self_ = self.clone();
+        self.spawn(async move {
+            loop {
+                tokio::time::sleep(std::time::Duration::from_secs(MEMPOOL_EXPIRATION_INTERVAL_IN_SECS)).await;
+                self_.expire_unconfirmed_transactions();
+            }
+        });
+    }
+
+    /// Removes the unconfirmed transactions that have waited in the queue for longer than the TTL.
+    fn expire_unconfirmed_transactions(&self) {
+        // Determine the cutoff timestamp.
+        let cutoff = snarkos_node_bft::helpers::now().saturating_sub(MEMPOOL_TRANSACTION_TTL_IN_SECS);
+        // Remove the expired transactions.
+        let expired = self.transactions_queue.lock().expire(cutoff);
+        if !expired.is_empty() {
+            info!("Expired {} unconfirmed transaction(s) from the memory pool", expired.len());
+            #[cfg(feature = "metrics")]
+            self.remove_queued_transaction_metrics(&expired, metrics::consensus::EXPIRED_TRANSACTIONS);
+        }
+    }
+
+    /// Updates the metrics for the given transactions, which were removed from the queue without being confirmed.
+    #[cfg(feature = "metrics")]
+    fn remove_queued_transaction_metrics(&self, transaction_ids: &[N::TransactionID], counter: &'static str) {
+        let mut timestamps = self.transmissions_queue_timestamps.lock();
+        for transaction_id in transaction_ids {
+            timestamps.remove(&TransmissionID::Transaction(*transaction_id));
+        }
+        metrics::counter(counter, transaction_ids.len() as u64);
     }
 
     /// Processes the committed subdag and transmissions from the BFT.
+    #[tracing::instrument(level = "info", skip_all, fields(round = subdag.anchor_round()))]
     async fn process_bft_subdag(
         &self,
         subdag: Subdag<N>,
@@ -451,7 +615,8 @@ impl<N: Network> Consensus<N> {
         // Try to advance to the next block.
         let self_ = self.clone();
         let transmissions_ = transmissions.clone();
-        let result = spawn_blocking! { self_.try_advance_to_next_block(subdag, transmissions_) };
+        let span = tracing::Span::current();
+        let result = spawn_blocking! { span.in_scope(|| self_.try_advance_to_next_block(subdag, transmissions_)) };
 
         // If the block failed to advance, reinsert the transmissions into the memory pool.
         if let Err(e) = &result {
@@ -465,6 +630,7 @@ impl<N: Network> Consensus<N> {
     }
 
     /// Attempts to advance to the next block.
+    #[tracing::instrument(level = "info", skip_all)]
     fn try_advance_to_next_block(
         &self,
         subdag: Subdag<N>,
@@ -563,3 +729,14 @@ impl<N: Network> Consensus<N> {
         self.handles.lock().iter().for_each(|handle| handle.abort());
     }
 }
+
+/// Marks the given ID as seen, and returns `true` if it was recently seen.
+/// Note: The timestamp at which the ID was first seen is kept, so that the age of the transmission can be reported.
+fn mark_seen<K: std::hash::Hash + Eq>(seen: &Mutex<LruCache<K, i64>>, id: K) -> bool {
+    let mut seen = seen.lock();
+    if seen.get(&id).is_some() {
+        return true;
+    }
+    seen.put(id, snarkos_node_bft::helpers::now());
+    false
+}
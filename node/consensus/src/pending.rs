@@ -0,0 +1,117 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{block::Transaction, FromBytes, Network, ToBytes};
+
+use anyhow::{bail, ensure, Result};
+use std::{
+    fs,
+    io::{BufReader, BufWriter, ErrorKind, Read, Write},
+    path::Path,
+};
+
+/// The unconfirmed transactions in the memory pool of a validator.
+///
+/// The pending transactions are persisted when the node shuts down, so that on restart,
+/// they are re-validated and re-added to the memory pool, instead of being dropped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingTransactions<N: Network> {
+    /// The pending transactions.
+    transactions: Vec<Transaction<N>>,
+}
+
+impl<N: Network> PendingTransactions<N> {
+    /// Initializes the pending transactions.
+    pub fn new(transactions: Vec<Transaction<N>>) -> Self {
+        Self { transactions }
+    }
+
+    /// Loads the pending transactions from the given file.
+    /// If the file does not exist, there are no pending transactions.
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = match fs::File::open(path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Self::new(vec![])),
+            Err(error) => bail!("Failed to read the pending transactions from {} - {error}", path.display()),
+        };
+        let mut reader = BufReader::new(file);
+        // Read the number of transactions.
+        let num_transactions = u32::read_le(&mut reader)?;
+        // Read the transactions.
+        let transactions =
+            (0..num_transactions).map(|_| Transaction::read_le(&mut reader)).collect::<Result<Vec<_>, _>>()?;
+        // Ensure there are no trailing bytes.
+        ensure!(
+            reader.bytes().next().is_none(),
+            "Found trailing bytes in the pending transactions in {}",
+            path.display()
+        );
+        Ok(Self::new(transactions))
+    }
+
+    /// Saves the pending transactions to the given file.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut writer = BufWriter::new(fs::File::create(path)?);
+        // Write the number of transactions.
+        (self.transactions.len() as u32).write_le(&mut writer)?;
+        // Write the transactions.
+        for transaction in &self.transactions {
+            transaction.write_le(&mut writer)?;
+        }
+        Ok(writer.flush()?)
+    }
+
+    /// Returns the number of pending transactions.
+    pub fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
+    /// Returns `true` if there are no pending transactions.
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    /// Returns the pending transactions.
+    pub fn into_transactions(self) -> Vec<Transaction<N>> {
+        self.transactions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::MainnetV0;
+
+    use rand::Rng;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_pending_transactions() {
+        let path = std::env::temp_dir().join(format!("snarkos-mempool-{}", rand::thread_rng().gen::<u64>()));
+
+        // Ensure a missing file means there are no pending transactions.
+        assert!(PendingTransactions::<CurrentNetwork>::load(&path).unwrap().is_empty());
+
+        // Ensure the pending transactions survive a round trip through the disk.
+        let pending_transactions = PendingTransactions::<CurrentNetwork>::new(vec![]);
+        pending_transactions.save(&path).unwrap();
+        assert_eq!(PendingTransactions::load(&path).unwrap(), pending_transactions);
+
+        // Ensure a file with trailing bytes is rejected.
+        std::fs::write(&path, [0u8; 5]).unwrap();
+        assert!(PendingTransactions::<CurrentNetwork>::load(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
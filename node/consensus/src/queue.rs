@@ -0,0 +1,196 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::AdmissionPolicy;
+use snarkos_node_bft::helpers::fmt_id;
+use snarkvm::{ledger::block::Transaction, prelude::*};
+
+use anyhow::Result;
+use lru::LruCache;
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    num::NonZeroUsize,
+};
+
+/// The capacity of the queue reserved for deployments.
+/// Note: This is an inbound queue capacity, not a Narwhal-enforced capacity.
+pub(crate) const CAPACITY_FOR_DEPLOYMENTS: usize = 1 << 10;
+/// The capacity of the queue reserved for executions.
+/// Note: This is an inbound queue capacity, not a Narwhal-enforced capacity.
+pub(crate) const CAPACITY_FOR_EXECUTIONS: usize = 1 << 10;
+
+/// An unconfirmed transaction waiting in the queue.
+#[derive(Clone)]
+pub(crate) struct QueuedTransaction<N: Network> {
+    /// The transaction.
+    pub transaction: Transaction<N>,
+    /// The fee of the transaction, in microcredits.
+    pub fee: u64,
+    /// The payer of the fee, if the fee is public.
+    pub payer: Option<Address<N>>,
+    /// The size of the transaction, in bytes.
+    pub size: usize,
+    /// The UNIX timestamp (in seconds) at which the transaction was queued.
+    pub timestamp: i64,
+}
+
+/// The pending transactions of a fee payer in the queue.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct PendingAccount {
+    /// The number of pending transactions.
+    pub num_transactions: usize,
+    /// The total size of the pending transactions, in bytes.
+    pub num_bytes: usize,
+}
+
+/// The queue of unconfirmed transactions, waiting to be sent to the memory pool.
+pub(crate) struct TransactionsQueue<N: Network> {
+    pub deployments: LruCache<N::TransactionID, QueuedTransaction<N>>,
+    pub executions: LruCache<N::TransactionID, QueuedTransaction<N>>,
+    /// The pending transactions of every fee payer in the queue.
+    /// Note: Transactions with a private fee have no known payer, and are not accounted for.
+    accounts: HashMap<Address<N>, PendingAccount>,
+}
+
+impl<N: Network> Default for TransactionsQueue<N> {
+    fn default() -> Self {
+        Self {
+            deployments: LruCache::new(NonZeroUsize::new(CAPACITY_FOR_DEPLOYMENTS).unwrap()),
+            executions: LruCache::new(NonZeroUsize::new(CAPACITY_FOR_EXECUTIONS).unwrap()),
+            accounts: Default::default(),
+        }
+    }
+}
+
+impl<N: Network> TransactionsQueue<N> {
+    /// Inserts the given transaction into the queue, at the given timestamp.
+    /// If the fee payer reached its limits of pending transactions in the given policy, the transaction is rejected.
+    /// If the queue is full, the transaction with the lowest fee is evicted to make room,
+    /// and its ID is returned. If the given transaction does not pay more than it, the transaction is rejected.
+    pub fn insert(
+        &mut self,
+        transaction: Transaction<N>,
+        timestamp: i64,
+        policy: &AdmissionPolicy<N>,
+    ) -> Result<Option<N::TransactionID>> {
+        let transaction_id = transaction.id();
+        // Retrieve the fee of the transaction, and its payer.
+        let fee = *transaction.fee_amount()?;
+        let payer = transaction.fee_transition().and_then(|fee| fee.payer());
+        let size = transaction.to_bytes_le()?.len();
+        // Ensure the fee payer is within its limits of pending transactions.
+        if let Some(payer) = payer {
+            let pending = self.pending(&payer);
+            ensure!(
+                pending.num_transactions < policy.max_pending_transactions_per_account(),
+                "Transaction '{}' exceeds the limit of {} pending transactions for '{payer}'",
+                fmt_id(transaction_id),
+                policy.max_pending_transactions_per_account()
+            );
+            ensure!(
+                pending.num_bytes.saturating_add(size) <= policy.max_pending_bytes_per_account(),
+                "Transaction '{}' exceeds the limit of {} pending bytes for '{payer}'",
+                fmt_id(transaction_id),
+                policy.max_pending_bytes_per_account()
+            );
+        }
+        // Select the queue for the transaction.
+        let queue = match transaction.is_deploy() {
+            true => &mut self.deployments,
+            false => &mut self.executions,
+        };
+        // Ensure the transaction is not already in the queue.
+        if queue.contains(&transaction_id) {
+            bail!("Transaction '{}' exists in the memory pool", fmt_id(transaction_id));
+        }
+        // If the queue is full, evict the transaction with the lowest fee.
+        let mut evicted = None;
+        if queue.len() >= queue.cap().get() {
+            // Find the transaction with the lowest fee, preferring the oldest on a tie.
+            let lowest = queue.iter().rev().min_by_key(|(_, entry)| entry.fee).map(|(id, entry)| (*id, entry.fee));
+            if let Some((lowest_id, lowest_fee)) = lowest {
+                // Ensure the transaction pays more than the transaction it would evict.
+                ensure!(
+                    fee > lowest_fee,
+                    "Transaction '{}' has a fee of {fee} microcredits, which is too low to enter the full memory pool",
+                    fmt_id(transaction_id)
+                );
+                if let Some(entry) = queue.pop(&lowest_id) {
+                    Self::release(&mut self.accounts, &entry);
+                }
+                evicted = Some(lowest_id);
+            }
+        }
+        // Insert the transaction into the queue.
+        queue.put(transaction_id, QueuedTransaction { transaction, fee, payer, size, timestamp });
+        if let Some(payer) = payer {
+            let pending = self.accounts.entry(payer).or_default();
+            pending.num_transactions += 1;
+            pending.num_bytes += size;
+        }
+        Ok(evicted)
+    }
+
+    /// Returns the pending transactions of the given fee payer in the queue.
+    pub fn pending(&self, payer: &Address<N>) -> PendingAccount {
+        self.accounts.get(payer).copied().unwrap_or_default()
+    }
+
+    /// Removes and returns the least recently queued deployment.
+    pub fn pop_deployment(&mut self) -> Option<Transaction<N>> {
+        let (_, entry) = self.deployments.pop_lru()?;
+        Self::release(&mut self.accounts, &entry);
+        Some(entry.transaction)
+    }
+
+    /// Removes and returns the least recently queued execution.
+    pub fn pop_execution(&mut self) -> Option<Transaction<N>> {
+        let (_, entry) = self.executions.pop_lru()?;
+        Self::release(&mut self.accounts, &entry);
+        Some(entry.transaction)
+    }
+
+    /// Releases the given transaction from the pending transactions of its fee payer.
+    fn release(accounts: &mut HashMap<Address<N>, PendingAccount>, entry: &QueuedTransaction<N>) {
+        let Some(payer) = entry.payer else {
+            return;
+        };
+        if let Entry::Occupied(mut pending) = accounts.entry(payer) {
+            let account = pending.get_mut();
+            account.num_transactions = account.num_transactions.saturating_sub(1);
+            account.num_bytes = account.num_bytes.saturating_sub(entry.size);
+            if account.num_transactions == 0 {
+                pending.remove();
+            }
+        }
+    }
+
+    /// Removes the transactions that were queued at or before the given cutoff timestamp,
+    /// and returns their IDs.
+    pub fn expire(&mut self, cutoff: i64) -> Vec<N::TransactionID> {
+        let mut expired = Vec::new();
+        for queue in [&mut self.deployments, &mut self.executions] {
+            // Collect the IDs of the expired transactions.
+            let ids: Vec<_> = queue.iter().filter(|(_, entry)| entry.timestamp <= cutoff).map(|(id, _)| *id).collect();
+            // Remove the expired transactions.
+            for id in ids {
+                if let Some(entry) = queue.pop(&id) {
+                    Self::release(&mut self.accounts, &entry);
+                }
+                expired.push(id);
+            }
+        }
+        expired
+    }
+}
@@ -23,6 +23,7 @@ pub use snarkvm::metrics::*;
 #[cfg(not(feature = "serial"))]
 use rayon::prelude::*;
 
+use metrics_exporter_prometheus::Matcher;
 use parking_lot::Mutex;
 use snarkvm::{
     ledger::narwhal::TransmissionID,
@@ -30,6 +31,7 @@ use snarkvm::{
 };
 use std::{
     collections::HashMap,
+    net::SocketAddr,
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc,
@@ -37,10 +39,28 @@ use std::{
 };
 use time::OffsetDateTime;
 
-/// Initializes the metrics and returns a handle to the task running the metrics exporter.
-pub fn initialize_metrics() {
+/// The default address of the Prometheus exporter.
+pub const DEFAULT_METRICS_IP: &str = "0.0.0.0:9000";
+
+/// The buckets (in seconds) of the latency histograms.
+const LATENCY_BUCKETS_IN_SECS: [f64; 12] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+/// Initializes the metrics, and the Prometheus exporter serving them at `/metrics` on the given address,
+/// with the given labels attached to every metric (e.g. the node type and the network).
+pub fn initialize_metrics(listen: SocketAddr, labels: &[(&str, String)]) {
     // Build the Prometheus exporter.
-    metrics_exporter_prometheus::PrometheusBuilder::new().install().expect("can't build the prometheus exporter");
+    let builder = labels.iter().fold(
+        metrics_exporter_prometheus::PrometheusBuilder::new().with_http_listener(listen),
+        |builder, (key, value)| builder.add_global_label(*key, value),
+    );
+    // Render the latency histograms with buckets, so their regressions and outliers are visible.
+    let builder = crate::names::LATENCY_HISTOGRAM_NAMES
+        .iter()
+        .try_fold(builder, |builder, name| {
+            builder.set_buckets_for_metric(Matcher::Full(name.to_string()), &LATENCY_BUCKETS_IN_SECS)
+        })
+        .unwrap_or_else(|error| panic!("can't set the buckets of the latency histograms - {error}"));
+    builder.install().unwrap_or_else(|error| panic!("can't build the prometheus exporter on {listen} - {error}"));
 
     // Register the snarkVM metrics.
     snarkvm::metrics::register_metrics();
@@ -57,6 +77,19 @@ pub fn initialize_metrics() {
     }
 }
 
+/// Increments the counter with the given name and label by one.
+/// Note: The counters with a label are not registered on init, as their label values are not known in advance.
+pub fn increment_counter_label(name: &'static str, label_key: &'static str, label_value: String) {
+    ::metrics::counter!(name, label_key => label_value).increment(1);
+}
+
+/// Increments the counter with the given name and pair of labels by the given value.
+/// Note: The counters with labels are not registered on init, as their label values are not known in advance.
+pub fn increment_counter_labels(name: &'static str, labels: [(&'static str, String); 2], value: u64) {
+    let [(key0, value0), (key1, value1)] = labels;
+    ::metrics::counter!(name, key0 => value0, key1 => value1).increment(value);
+}
+
 pub fn update_block_metrics<N: Network>(block: &Block<N>) {
     use snarkvm::ledger::ConfirmedTransaction;
 
@@ -12,9 +12,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-pub(super) const COUNTER_NAMES: [&str; 2] = [bft::LEADERS_ELECTED, consensus::STALE_UNCONFIRMED_TRANSMISSIONS];
+pub(super) const COUNTER_NAMES: [&str; 20] = [
+    bft::LEADERS_ELECTED,
+    bft::LEADERS_MISSED,
+    bft::BATCHES_PROPOSED,
+    bft::BATCHES_SIGNED,
+    bft::GC_TRANSMISSIONS,
+    bft::SPECULATED_TRANSACTIONS,
+    bft::SPECULATION_SKIPPED_TRANSACTIONS,
+    bft::EXISTENCE_FILTER_HITS,
+    bft::EXISTENCE_FILTER_MISSES,
+    consensus::STALE_UNCONFIRMED_TRANSMISSIONS,
+    consensus::EXPIRED_TRANSACTIONS,
+    consensus::EVICTED_TRANSACTIONS,
+    prover::ATTEMPTS,
+    prover::SOLUTIONS_FOUND,
+    prover::SOLUTIONS_ACCEPTED,
+    prover::SOLUTIONS_STALE,
+    prover::SOLUTIONS_DUPLICATE,
+    prover::SOLUTIONS_BELOW_TARGET,
+    rest::CACHE_HITS,
+    rest::CACHE_MISSES,
+];
 
-pub(super) const GAUGE_NAMES: [&str; 26] = [
+pub(super) const GAUGE_NAMES: [&str; 51] = [
     bft::CONNECTED,
     bft::CONNECTING,
     bft::LAST_STORED_ROUND,
@@ -23,6 +44,19 @@ pub(super) const GAUGE_NAMES: [&str; 26] = [
     bft::HEIGHT,
     bft::LAST_COMMITTED_ROUND,
     bft::IS_SYNCED,
+    bft::PARTICIPATION_PROPOSALS,
+    bft::PARTICIPATION_SIGNATURES,
+    bft::OUTBOUND_QUEUE_HIGH,
+    bft::OUTBOUND_QUEUE_NORMAL,
+    bft::OUTBOUND_QUEUE_LOW,
+    bft::UNCONFIRMED_SOLUTIONS,
+    bft::UNCONFIRMED_TRANSACTIONS,
+    bft::UNCONFIRMED_RATIFICATIONS,
+    bft::DAG_DEPTH,
+    bft::UNCOMMITTED_ROUNDS,
+    bft::GC_LAG,
+    bft::STORAGE_ROUNDS,
+    bft::STORAGE_CERTIFICATES,
     blocks::SOLUTIONS,
     blocks::TRANSACTIONS,
     blocks::ACCEPTED_DEPLOY,
@@ -37,14 +71,38 @@ pub(super) const GAUGE_NAMES: [&str; 26] = [
     consensus::COMMITTED_CERTIFICATES,
     consensus::UNCONFIRMED_SOLUTIONS,
     consensus::UNCONFIRMED_TRANSACTIONS,
+    disk::AVAILABLE_BYTES,
+    disk::IS_LOW,
+    prover::ATTEMPTS_PER_SEC,
+    prover::PAUSED,
+    prover::PROOF_TARGET,
     router::CONNECTED,
     router::CANDIDATE,
     router::RESTRICTED,
+    router::OUTBOUND_QUEUE_HIGH,
+    router::OUTBOUND_QUEUE_NORMAL,
+    router::OUTBOUND_QUEUE_LOW,
+    sync::BLOCKS_BEHIND,
+    sync::BLOCK_REQUESTS,
+    sync::CANON_HEIGHT,
+    sync::GREATEST_PEER_HEIGHT,
     tcp::TCP_TASKS,
 ];
 
-pub(super) const HISTOGRAM_NAMES: [&str; 3] =
-    [bft::COMMIT_ROUNDS_LATENCY, consensus::CERTIFICATE_COMMIT_LATENCY, consensus::BLOCK_LATENCY];
+pub(super) const HISTOGRAM_NAMES: [&str; 7] = [
+    bft::COMMIT_ROUNDS_LATENCY,
+    bft::ROUND_DURATION,
+    bft::TRANSMISSION_FETCH_LATENCY,
+    blocks::FINALIZE_LATENCY,
+    blocks::VERIFICATION_LATENCY,
+    consensus::CERTIFICATE_COMMIT_LATENCY,
+    consensus::BLOCK_LATENCY,
+];
+
+/// The histograms of the latency of the critical paths, which are rendered with buckets (instead of quantiles).
+/// Note: The REST request latency is labeled with the route, so it is not registered on init.
+pub(super) const LATENCY_HISTOGRAM_NAMES: [&str; 4] =
+    [bft::TRANSMISSION_FETCH_LATENCY, blocks::FINALIZE_LATENCY, blocks::VERIFICATION_LATENCY, rest::REQUEST_LATENCY];
 
 pub mod bft {
     pub const COMMIT_ROUNDS_LATENCY: &str = "snarkos_bft_commit_rounds_latency_secs"; // <-- This one doesn't even make sense.
@@ -57,6 +115,31 @@ pub mod bft {
     pub const HEIGHT: &str = "snarkos_bft_height_total";
     pub const LAST_COMMITTED_ROUND: &str = "snarkos_bft_last_committed_round";
     pub const IS_SYNCED: &str = "snarkos_bft_is_synced";
+    pub const PARTICIPATION_PROPOSALS: &str = "snarkos_bft_participation_proposals_percent";
+    pub const PARTICIPATION_SIGNATURES: &str = "snarkos_bft_participation_signatures_percent";
+    pub const OUTBOUND_QUEUE_HIGH: &str = "snarkos_bft_outbound_queue_high";
+    pub const OUTBOUND_QUEUE_NORMAL: &str = "snarkos_bft_outbound_queue_normal";
+    pub const OUTBOUND_QUEUE_LOW: &str = "snarkos_bft_outbound_queue_low";
+    pub const ROUND_DURATION: &str = "snarkos_bft_round_duration_secs";
+    pub const LEADERS_MISSED: &str = "snarkos_bft_leaders_missed_total";
+    pub const LEADER_ELECTIONS: &str = "snarkos_bft_leader_elections_total";
+    pub const BATCHES_PROPOSED: &str = "snarkos_bft_batches_proposed_total";
+    pub const BATCHES_SIGNED: &str = "snarkos_bft_batches_signed_total";
+    pub const GC_TRANSMISSIONS: &str = "snarkos_bft_gc_transmissions_total";
+    pub const SPECULATED_TRANSACTIONS: &str = "snarkos_bft_speculated_transactions_total";
+    pub const SPECULATION_SKIPPED_TRANSACTIONS: &str = "snarkos_bft_speculation_skipped_transactions_total";
+    pub const EXISTENCE_FILTER_HITS: &str = "snarkos_bft_existence_filter_hits_total";
+    pub const EXISTENCE_FILTER_MISSES: &str = "snarkos_bft_existence_filter_misses_total";
+    pub const CERTIFICATES_RECEIVED: &str = "snarkos_bft_certificates_received_total";
+    pub const UNCONFIRMED_SOLUTIONS: &str = "snarkos_bft_unconfirmed_solutions_total";
+    pub const UNCONFIRMED_TRANSACTIONS: &str = "snarkos_bft_unconfirmed_transactions_total";
+    pub const UNCONFIRMED_RATIFICATIONS: &str = "snarkos_bft_unconfirmed_ratifications_total";
+    pub const DAG_DEPTH: &str = "snarkos_bft_dag_depth_rounds";
+    pub const UNCOMMITTED_ROUNDS: &str = "snarkos_bft_uncommitted_rounds";
+    pub const GC_LAG: &str = "snarkos_bft_gc_lag_rounds";
+    pub const STORAGE_ROUNDS: &str = "snarkos_bft_storage_rounds";
+    pub const STORAGE_CERTIFICATES: &str = "snarkos_bft_storage_certificates";
+    pub const TRANSMISSION_FETCH_LATENCY: &str = "snarkos_bft_transmission_fetch_latency_secs";
 }
 
 pub mod blocks {
@@ -71,6 +154,8 @@ pub mod blocks {
     pub const PROOF_TARGET: &str = "snarkos_blocks_proof_target";
     pub const COINBASE_TARGET: &str = "snarkos_blocks_coinbase_target";
     pub const CUMULATIVE_PROOF_TARGET: &str = "snarkos_blocks_cumulative_proof_target";
+    pub const VERIFICATION_LATENCY: &str = "snarkos_blocks_verification_latency_secs";
+    pub const FINALIZE_LATENCY: &str = "snarkos_blocks_finalize_latency_secs";
 }
 
 pub mod consensus {
@@ -81,12 +166,52 @@ pub mod consensus {
     pub const UNCONFIRMED_SOLUTIONS: &str = "snarkos_consensus_unconfirmed_solutions_total";
     pub const TRANSMISSION_LATENCY: &str = "snarkos_consensus_transmission_latency";
     pub const STALE_UNCONFIRMED_TRANSMISSIONS: &str = "snarkos_consensus_stale_unconfirmed_transmissions";
+    pub const EXPIRED_TRANSACTIONS: &str = "snarkos_consensus_expired_transactions_total";
+    pub const EVICTED_TRANSACTIONS: &str = "snarkos_consensus_evicted_transactions_total";
+}
+
+pub mod disk {
+    pub const AVAILABLE_BYTES: &str = "snarkos_disk_available_bytes";
+    pub const IS_LOW: &str = "snarkos_disk_is_low";
+}
+
+pub mod prover {
+    pub const ATTEMPTS: &str = "snarkos_prover_attempts_total";
+    pub const ATTEMPTS_PER_SEC: &str = "snarkos_prover_attempts_per_sec";
+    pub const PAUSED: &str = "snarkos_prover_paused";
+    pub const PROOF_TARGET: &str = "snarkos_prover_proof_target";
+    pub const SOLUTIONS_FOUND: &str = "snarkos_prover_solutions_found_total";
+    pub const SOLUTIONS_ACCEPTED: &str = "snarkos_prover_solutions_accepted_total";
+    pub const SOLUTIONS_STALE: &str = "snarkos_prover_solutions_stale_total";
+    pub const SOLUTIONS_DUPLICATE: &str = "snarkos_prover_solutions_duplicate_total";
+    pub const SOLUTIONS_BELOW_TARGET: &str = "snarkos_prover_solutions_below_target_total";
+}
+
+pub mod rest {
+    pub const CACHE_HITS: &str = "snarkos_rest_cache_hits_total";
+    pub const CACHE_MISSES: &str = "snarkos_rest_cache_misses_total";
+    pub const REQUEST_LATENCY: &str = "snarkos_rest_request_latency_secs";
 }
 
 pub mod router {
     pub const CONNECTED: &str = "snarkos_router_connected_total";
     pub const CANDIDATE: &str = "snarkos_router_candidate_total";
     pub const RESTRICTED: &str = "snarkos_router_restricted_total";
+    pub const OUTBOUND_QUEUE_HIGH: &str = "snarkos_router_outbound_queue_high";
+    pub const OUTBOUND_QUEUE_NORMAL: &str = "snarkos_router_outbound_queue_normal";
+    pub const OUTBOUND_QUEUE_LOW: &str = "snarkos_router_outbound_queue_low";
+    // The traffic counters are labeled with the peer and the message type.
+    pub const SENT_MESSAGES: &str = "snarkos_router_sent_messages_total";
+    pub const SENT_BYTES: &str = "snarkos_router_sent_bytes_total";
+    pub const RECEIVED_MESSAGES: &str = "snarkos_router_received_messages_total";
+    pub const RECEIVED_BYTES: &str = "snarkos_router_received_bytes_total";
+}
+
+pub mod sync {
+    pub const BLOCKS_BEHIND: &str = "snarkos_sync_blocks_behind";
+    pub const BLOCK_REQUESTS: &str = "snarkos_sync_block_requests";
+    pub const CANON_HEIGHT: &str = "snarkos_sync_canon_height";
+    pub const GREATEST_PEER_HEIGHT: &str = "snarkos_sync_greatest_peer_height";
 }
 
 pub mod tcp {
@@ -0,0 +1,283 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::{store::ConsensusStorage, Ledger},
+    prelude::{block::Block, Network, ToBytes},
+};
+
+use anyhow::{anyhow, ensure, Result};
+use indexmap::IndexMap;
+use rocksdb::{Options, WriteBatch, DB};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::path::Path;
+
+/// The key prefix of the per-height statistics.
+const PREFIX_BLOCK: u8 = 0;
+/// The key prefix of the per-program call counts.
+const PREFIX_PROGRAM: u8 = 1;
+/// The key of the cumulative statistics.
+const KEY_TOTALS: &[u8] = &[2];
+/// The key of the hash of the latest indexed block.
+const KEY_TIP: &[u8] = &[3];
+
+/// The statistics of a single block.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BlockStatistics {
+    /// The height of the block.
+    pub height: u32,
+    /// The timestamp of the block.
+    pub timestamp: i64,
+    /// The size of the block, in bytes.
+    pub size: u64,
+    /// The number of confirmed transactions, including the rejected ones.
+    pub num_transactions: u64,
+    /// The number of transitions.
+    pub num_transitions: u64,
+    /// The number of accepted program deployments.
+    pub num_deployments: u64,
+    /// The fees paid in the block, in microcredits.
+    pub fees: u64,
+}
+
+impl BlockStatistics {
+    /// Computes the statistics of the given block, along with its program call counts.
+    fn new<N: Network>(block: &Block<N>) -> Result<(Self, IndexMap<String, u64>)> {
+        let transactions = block.transactions();
+        let mut statistics = Self {
+            height: block.height(),
+            timestamp: block.timestamp(),
+            size: block.to_bytes_le()?.len() as u64,
+            num_transactions: transactions.len() as u64,
+            ..Default::default()
+        };
+        let mut program_calls = IndexMap::<String, u64>::new();
+        for transaction in transactions.iter() {
+            if transaction.is_accepted() && transaction.transaction().is_deploy() {
+                statistics.num_deployments += 1;
+            }
+            statistics.fees += transaction.transaction().fee_amount().map(|fee| *fee).unwrap_or_default();
+            for transition in transaction.transaction().transitions() {
+                statistics.num_transitions += 1;
+                *program_calls.entry(transition.program_id().to_string()).or_default() += 1;
+            }
+        }
+        Ok((statistics, program_calls))
+    }
+}
+
+/// The cumulative statistics of the chain, up to a given height.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ChainStatistics {
+    /// The height of the latest indexed block.
+    pub height: u32,
+    /// The timestamp of the genesis block.
+    pub first_timestamp: i64,
+    /// The timestamp of the latest indexed block.
+    pub latest_timestamp: i64,
+    /// The total size of the blocks, in bytes.
+    pub total_size: u64,
+    /// The number of confirmed transactions, including the rejected ones.
+    pub num_transactions: u64,
+    /// The number of transitions.
+    pub num_transitions: u64,
+    /// The number of accepted program deployments.
+    pub num_deployments: u64,
+    /// The fees paid, in microcredits.
+    pub total_fees: u64,
+}
+
+impl ChainStatistics {
+    /// Accounts for the next block, in ascending height order.
+    fn add_block(&mut self, block: &BlockStatistics) {
+        if block.height == 0 {
+            self.first_timestamp = block.timestamp;
+        }
+        self.height = block.height;
+        self.latest_timestamp = block.timestamp;
+        self.total_size += block.size;
+        self.num_transactions += block.num_transactions;
+        self.num_transitions += block.num_transitions;
+        self.num_deployments += block.num_deployments;
+        self.total_fees += block.fees;
+    }
+}
+
+/// An optional index of the per-height counts and totals of the chain, and of the program call counts,
+/// so that the statistics are answered without scanning the blocks. It is kept in its own database,
+/// next to the ledger, and is updated as the ledger advances.
+pub struct AnalyticsIndex {
+    /// The database of the index.
+    db: DB,
+}
+
+impl AnalyticsIndex {
+    /// Opens the index at the given path, creating it if it does not exist.
+    pub fn open(path: &Path) -> Result<Self> {
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        let db = DB::open(&options, path)
+            .map_err(|error| anyhow!("Couldn't open the analytics index at {} - {error}", path.display()))?;
+        Ok(Self { db })
+    }
+
+    /// Opens the existing index at the given path, for reading only.
+    pub fn open_read_only(path: &Path) -> Result<Self> {
+        let db = DB::open_for_read_only(&Options::default(), path, false)
+            .map_err(|error| anyhow!("Couldn't open the analytics index at {} - {error}", path.display()))?;
+        Ok(Self { db })
+    }
+
+    /// Returns the cumulative statistics, if any block was indexed.
+    pub fn totals(&self) -> Result<Option<ChainStatistics>> {
+        self.get(KEY_TOTALS)
+    }
+
+    /// Returns the cumulative statistics, if the latest indexed block is still in the given ledger.
+    pub fn canonical_totals<N: Network, C: ConsensusStorage<N>>(
+        &self,
+        ledger: &Ledger<N, C>,
+    ) -> Result<Option<ChainStatistics>> {
+        let Some(totals) = self.totals()? else { return Ok(None) };
+        let tip = self.get::<N::BlockHash>(KEY_TIP)?;
+        let is_canonical = totals.height <= ledger.latest_height() && tip == Some(ledger.get_hash(totals.height)?);
+        Ok(is_canonical.then_some(totals))
+    }
+
+    /// Returns the statistics of the block at the given height, if it was indexed.
+    pub fn get_block(&self, height: u32) -> Result<Option<BlockStatistics>> {
+        self.get(&block_key(height))
+    }
+
+    /// Returns the number of calls to every program.
+    pub fn program_calls(&self) -> Result<IndexMap<String, u64>> {
+        let mut program_calls = IndexMap::new();
+        for entry in self.db.prefix_iterator([PREFIX_PROGRAM]) {
+            let (key, value) = entry?;
+            if key.first() != Some(&PREFIX_PROGRAM) {
+                break;
+            }
+            program_calls.insert(String::from_utf8(key[1..].to_vec())?, serde_json::from_slice(&value)?);
+        }
+        Ok(program_calls)
+    }
+
+    /// Returns the height of the next block to index.
+    pub fn next_height(&self) -> Result<u32> {
+        Ok(self.totals()?.map(|totals| totals.height + 1).unwrap_or_default())
+    }
+
+    /// Indexes the given block, which must be the next one.
+    pub fn insert_block<N: Network>(&self, block: &Block<N>) -> Result<()> {
+        let next_height = self.next_height()?;
+        ensure!(block.height() == next_height, "Expected block {next_height} in the analytics index");
+        if next_height > 0 {
+            let tip = self.get::<N::BlockHash>(KEY_TIP)?;
+            ensure!(tip == Some(block.previous_hash()), "Block {next_height} does not extend the analytics index");
+        }
+
+        let (statistics, program_calls) = BlockStatistics::new(block)?;
+        let mut totals = self.totals()?.unwrap_or_default();
+        totals.add_block(&statistics);
+
+        // Write the statistics of the block, the totals, and the program call counts atomically.
+        let mut batch = WriteBatch::default();
+        batch.put(block_key(block.height()), serde_json::to_vec(&statistics)?);
+        batch.put(KEY_TOTALS, serde_json::to_vec(&totals)?);
+        batch.put(KEY_TIP, serde_json::to_vec(&block.hash())?);
+        for (program_id, num_calls) in program_calls {
+            let key = [&[PREFIX_PROGRAM], program_id.as_bytes()].concat();
+            let previous = self.get::<u64>(&key)?.unwrap_or_default();
+            batch.put(key, serde_json::to_vec(&(previous + num_calls))?);
+        }
+        Ok(self.db.write(batch)?)
+    }
+
+    /// Indexes the blocks the ledger advanced by since the last call; returns the height of the next block to index.
+    pub fn catch_up<N: Network, C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>) -> Result<u32> {
+        // Rebuild the index if its latest block is no longer in the ledger, e.g. after a rollback.
+        if self.totals()?.is_some() && self.canonical_totals(ledger)?.is_none() {
+            warn!("The analytics index diverged from the ledger, rebuilding it");
+            self.clear()?;
+        }
+        let mut next_height = self.next_height()?;
+        while next_height <= ledger.latest_height() {
+            self.insert_block(&ledger.get_block(next_height)?)?;
+            next_height += 1;
+        }
+        Ok(next_height)
+    }
+
+    /// Removes every entry of the index.
+    fn clear(&self) -> Result<()> {
+        let mut batch = WriteBatch::default();
+        batch.delete_range([u8::MIN], [u8::MAX]);
+        Ok(self.db.write(batch)?)
+    }
+
+    /// Returns the deserialized value for the given key, if it exists.
+    fn get<T: DeserializeOwned>(&self, key: &[u8]) -> Result<Option<T>> {
+        match self.db.get_pinned(key)? {
+            Some(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Returns the key of the statistics of the block at the given height; heights are big-endian, to keep them ordered.
+fn block_key(height: u32) -> Vec<u8> {
+    [&[PREFIX_BLOCK][..], &height.to_be_bytes()].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{FromBytes, MainnetV0};
+
+    use rand::Rng;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_analytics_index() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let path = std::env::temp_dir().join(format!("snarkos-analytics-{}", rand::thread_rng().gen::<u64>()));
+        let index = AnalyticsIndex::open(&path).unwrap();
+        assert_eq!(index.next_height().unwrap(), 0);
+        assert_eq!(index.totals().unwrap(), None);
+
+        // Ensure the genesis block is indexed, and only once.
+        index.insert_block(&genesis).unwrap();
+        assert!(index.insert_block(&genesis).is_err());
+        assert_eq!(index.next_height().unwrap(), 1);
+
+        // Ensure the totals match the statistics of the genesis block.
+        let block = index.get_block(0).unwrap().unwrap();
+        let totals = index.totals().unwrap().unwrap();
+        assert_eq!(block.num_transactions, genesis.transactions().len() as u64);
+        assert_eq!(totals.num_transactions, block.num_transactions);
+        assert_eq!(totals.num_transitions, block.num_transitions);
+        assert_eq!(totals.first_timestamp, genesis.timestamp());
+        assert_eq!(index.program_calls().unwrap().values().sum::<u64>(), block.num_transitions);
+
+        // Ensure the index is emptied, for a rebuild.
+        index.clear().unwrap();
+        assert_eq!(index.totals().unwrap(), None);
+        assert!(index.program_calls().unwrap().is_empty());
+        index.insert_block(&genesis).unwrap();
+
+        drop(index);
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+}
@@ -0,0 +1,23 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{block::Block, Network};
+
+use anyhow::Result;
+
+/// A read-through source of the blocks that were migrated out of the ledger.
+pub trait BlockArchive<N: Network>: Send + Sync {
+    /// Returns the archived block at the given height, if it exists.
+    fn get_archived_block(&self, height: u32) -> Result<Option<Block<N>>>;
+}
@@ -0,0 +1,128 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{block::Block, Network, Transaction};
+
+use anyhow::Result;
+use lru::LruCache;
+use parking_lot::Mutex;
+use std::{
+    hash::Hash,
+    num::NonZeroUsize,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// A read-through cache of the recently requested blocks and transactions.
+pub struct BlockCache<N: Network> {
+    /// The recently requested blocks, keyed by height.
+    blocks: Mutex<LruCache<u32, Block<N>>>,
+    /// The recently requested transactions, keyed by ID.
+    transactions: Mutex<LruCache<N::TransactionID, Transaction<N>>>,
+    /// The number of requests served from the cache.
+    hits: AtomicU64,
+    /// The number of requests that were read from storage.
+    misses: AtomicU64,
+}
+
+impl<N: Network> BlockCache<N> {
+    /// Initializes a new cache, holding up to the given number of blocks and of transactions.
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            blocks: Mutex::new(LruCache::new(capacity)),
+            transactions: Mutex::new(LruCache::new(capacity)),
+            hits: Default::default(),
+            misses: Default::default(),
+        }
+    }
+
+    /// Returns the number of requests served from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of requests that were read from storage.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Returns the block at the given height, reading it with the given closure on a cache miss.
+    pub fn get_block(&self, height: u32, read: impl FnOnce() -> Result<Block<N>>) -> Result<Block<N>> {
+        self.get_or_read(&self.blocks, height, read)
+    }
+
+    /// Returns the transaction for the given ID, reading it with the given closure on a cache miss.
+    pub fn get_transaction(
+        &self,
+        id: N::TransactionID,
+        read: impl FnOnce() -> Result<Transaction<N>>,
+    ) -> Result<Transaction<N>> {
+        self.get_or_read(&self.transactions, id, read)
+    }
+
+    /// Returns the cached value for the given key, or reads and caches it on a miss.
+    fn get_or_read<K: Hash + Eq, V: Clone>(
+        &self,
+        cache: &Mutex<LruCache<K, V>>,
+        key: K,
+        read: impl FnOnce() -> Result<V>,
+    ) -> Result<V> {
+        // Return the cached value, if it exists.
+        if let Some(value) = cache.lock().get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            #[cfg(feature = "metrics")]
+            metrics::increment_counter(metrics::rest::CACHE_HITS);
+            return Ok(value.clone());
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "metrics")]
+        metrics::increment_counter(metrics::rest::CACHE_MISSES);
+
+        // Read the value from storage, without holding the lock.
+        let value = read()?;
+        cache.lock().put(key, value.clone());
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{FromBytes, MainnetV0};
+
+    use anyhow::bail;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_block_cache() {
+        let block = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let cache = BlockCache::<CurrentNetwork>::new(NonZeroUsize::new(1).unwrap());
+
+        // Ensure the first request reads from storage.
+        assert_eq!(cache.get_block(0, || Ok(block.clone())).unwrap(), block);
+        assert_eq!((cache.hits(), cache.misses()), (0, 1));
+        // Ensure the second request is served from the cache.
+        assert_eq!(cache.get_block(0, || bail!("Unexpected read")).unwrap(), block);
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+
+        // Ensure a failed read is not cached.
+        assert!(cache.get_block(1, || bail!("Missing block")).is_err());
+        assert_eq!((cache.hits(), cache.misses()), (1, 2));
+
+        // Ensure the least recently used block is evicted.
+        assert_eq!(cache.get_block(2, || Ok(block.clone())).unwrap(), block);
+        assert!(cache.get_block(0, || bail!("Evicted block")).is_err());
+        assert_eq!((cache.hits(), cache.misses()), (1, 4));
+    }
+}
@@ -0,0 +1,211 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::{store::ConsensusStorage, Ledger},
+    prelude::{Network, ToBytes},
+};
+
+use anyhow::{ensure, Result};
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::{
+    fmt,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+/// A deterministic digest of the ledger state at a given block, which nodes at the same height
+/// are expected to agree on. Besides the block hash and the state root, it covers the committee,
+/// the ID indexes and every mapping entry, which the block header does not commit to.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(bound = "")]
+pub struct StateDigest<N: Network> {
+    /// The height of the block the digest was computed at.
+    pub height: u32,
+    /// The hash of the block the digest was computed at.
+    pub block_hash: N::BlockHash,
+    /// The SHA-256 digest of the state, as hex.
+    pub digest: String,
+    /// The digests of the ID indexes, as hex, which the state digest covers.
+    pub index_roots: IndexMap<&'static str, String>,
+}
+
+impl<N: Network> StateDigest<N> {
+    /// Computes the digest of the given ledger at its latest block.
+    pub fn new<C: ConsensusStorage<N>>(ledger: &Ledger<N, C>) -> Result<Self> {
+        let block = ledger.latest_block();
+        let mut hasher = Sha256::new();
+        hasher.update(block.height().to_le_bytes());
+        hasher.update(block.hash().to_bytes_le()?);
+        hasher.update(ledger.latest_state_root().to_bytes_le()?);
+        hasher.update(ledger.latest_committee()?.to_bytes_le()?);
+
+        // Hash the ID indexes, which are checked for duplicates, but are not covered by the state root.
+        let vm = ledger.vm();
+        let index_roots = [
+            ("transaction_ids", index_root(vm.transaction_store().transaction_ids().map(|id| id.to_bytes_le()))?),
+            ("solution_ids", index_root(vm.block_store().solution_ids().map(|id| id.to_bytes_le()))?),
+            ("serial_numbers", index_root(vm.transition_store().serial_numbers().map(|sn| sn.to_bytes_le()))?),
+            ("commitments", index_root(vm.transition_store().commitments().map(|cm| cm.to_bytes_le()))?),
+        ];
+        for (_, root) in &index_roots {
+            hasher.update(root);
+        }
+
+        // Hash the entries of every mapping, in a canonical order, as the storage order differs between backends.
+        let mut program_ids = vm.process().read().program_ids().copied().collect::<Vec<_>>();
+        program_ids.sort_by_cached_key(|program_id| program_id.to_string());
+        for program_id in program_ids {
+            let mut mapping_names = vm.finalize_store().get_mapping_names_confirmed(&program_id)?;
+            mapping_names.sort_by_cached_key(|mapping_name| mapping_name.to_string());
+            for mapping_name in mapping_names {
+                let mut entries = vm
+                    .finalize_store()
+                    .get_mapping_confirmed(program_id, mapping_name)?
+                    .into_iter()
+                    .map(|(key, value)| Ok((key.to_bytes_le()?, value.to_bytes_le()?)))
+                    .collect::<Result<Vec<_>>>()?;
+                entries.sort_unstable();
+
+                hasher.update(program_id.to_bytes_le()?);
+                hasher.update(mapping_name.to_bytes_le()?);
+                hasher.update((entries.len() as u64).to_le_bytes());
+                for (key, value) in entries {
+                    hasher.update((key.len() as u64).to_le_bytes());
+                    hasher.update(key);
+                    hasher.update((value.len() as u64).to_le_bytes());
+                    hasher.update(value);
+                }
+            }
+        }
+
+        // Ensure the ledger did not advance in the meantime, as the state would no longer match the block.
+        ensure!(ledger.latest_hash() == block.hash(), "The ledger advanced while the state digest was computed");
+        Ok(Self {
+            height: block.height(),
+            block_hash: block.hash(),
+            digest: to_hex(&hasher.finalize()),
+            index_roots: index_roots.into_iter().map(|(name, root)| (name, to_hex(&root))).collect(),
+        })
+    }
+}
+
+impl<N: Network> fmt::Display for StateDigest<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "State digest at block {} ({}): {}", self.height, self.block_hash, self.digest)
+    }
+}
+
+/// The state digests of a node, which are computed on the block-advance path, at every multiple of the interval.
+pub struct StateDigests<N: Network> {
+    /// The number of blocks between the digests, or zero if they are disabled.
+    interval: AtomicU32,
+    /// The latest digest, if any.
+    latest: RwLock<Option<StateDigest<N>>>,
+}
+
+impl<N: Network> Default for StateDigests<N> {
+    /// Initializes the state digests, which are disabled.
+    fn default() -> Self {
+        Self { interval: Default::default(), latest: Default::default() }
+    }
+}
+
+impl<N: Network> StateDigests<N> {
+    /// Enables the state digests at every multiple of the given number of blocks.
+    pub fn enable(&self, interval: u32) {
+        self.interval.store(interval, Ordering::Relaxed);
+    }
+
+    /// Returns the latest state digest, if any.
+    pub fn latest(&self) -> Option<StateDigest<N>> {
+        self.latest.read().clone()
+    }
+
+    /// Computes and logs the state digest, if the ledger just advanced to a multiple of the interval.
+    ///
+    /// Note: This runs before the ledger advances to the next block, which it delays until the digest is computed,
+    /// so that a digest is computed at every multiple of the interval, including while the node is syncing.
+    pub fn on_advance<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>, height: u32) {
+        if !is_digest_due(height, self.interval.load(Ordering::Relaxed)) {
+            return;
+        }
+        match StateDigest::new(ledger) {
+            Ok(digest) => {
+                info!("{digest}");
+                *self.latest.write() = Some(digest);
+            }
+            Err(error) => warn!("Failed to compute the state digest at block {height} - {error}"),
+        }
+    }
+}
+
+/// Returns `true` if a state digest is due at the given height, for the given interval (zero if disabled).
+fn is_digest_due(height: u32, interval: u32) -> bool {
+    interval != 0 && height % interval == 0
+}
+
+/// Returns the root of the given index, i.e. the sum of the SHA-256 hashes of its items modulo 2^256,
+/// which does not depend on the order of the items, as the storage order differs between backends.
+fn index_root(items: impl Iterator<Item = Result<Vec<u8>>>) -> Result<[u8; 32]> {
+    let mut sum = [0u64; 4];
+    for item in items {
+        let hash = Sha256::digest(item?);
+        let mut carry = false;
+        for (limb, bytes) in sum.iter_mut().zip(hash.chunks_exact(8)) {
+            let (value, overflow) = limb.overflowing_add(u64::from_le_bytes(bytes.try_into()?));
+            let (value, carry_overflow) = value.overflowing_add(carry as u64);
+            *limb = value;
+            carry = overflow || carry_overflow;
+        }
+    }
+    let mut root = [0u8; 32];
+    for (bytes, limb) in root.chunks_exact_mut(8).zip(sum) {
+        bytes.copy_from_slice(&limb.to_le_bytes());
+    }
+    Ok(root)
+}
+
+/// Returns the given bytes as hex.
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_digest_due() {
+        assert!(is_digest_due(0, 100));
+        assert!(!is_digest_due(99, 100));
+        assert!(is_digest_due(100, 100));
+        assert!(is_digest_due(300, 100));
+        // Ensure no digest is due while they are disabled.
+        assert!(!is_digest_due(100, 0));
+    }
+
+    #[test]
+    fn test_index_root() {
+        let items = [vec![1u8], vec![2, 3], vec![4; 40]];
+        let root = index_root(items.iter().cloned().map(Ok)).unwrap();
+
+        // Ensure the root does not depend on the order of the items, but on each of them.
+        assert_eq!(index_root(items.iter().rev().cloned().map(Ok)).unwrap(), root);
+        assert_ne!(index_root(items[1..].iter().cloned().map(Ok)).unwrap(), root);
+        assert_eq!(index_root(std::iter::empty()).unwrap(), [0u8; 32]);
+    }
+}
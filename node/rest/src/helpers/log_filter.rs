@@ -0,0 +1,65 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{anyhow, bail, Result};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+
+/// The request to change the log filter of the node.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogFilterRequest {
+    /// The filter directives to apply, i.e. `snarkos_node_bft=debug`.
+    #[serde(default)]
+    pub directives: Vec<String>,
+    /// If `true`, the directives that were applied at runtime are discarded first.
+    #[serde(default)]
+    pub reset: bool,
+}
+
+/// The log filter of the node.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogFilter {
+    /// The filter of the logger, i.e. `info,snarkos_node_bft=debug`.
+    pub filter: String,
+    /// The filter directives that were applied at runtime.
+    pub directives: Vec<String>,
+}
+
+/// The controller of the logger, which changes its filter at runtime.
+pub trait LogFilterController: Send + Sync {
+    /// Returns the log filter.
+    fn log_filter(&self) -> LogFilter;
+
+    /// Applies the given request, and returns the resulting log filter.
+    fn set_log_filter(&self, request: &LogFilterRequest) -> Result<LogFilter>;
+}
+
+/// The controller of the logger, which is global, like the logger itself.
+static LOG_FILTER_CONTROLLER: OnceCell<Box<dyn LogFilterController>> = OnceCell::new();
+
+/// Registers the controller of the logger, which serves the `node/logLevel` endpoints.
+pub fn set_log_filter_controller<C: LogFilterController + 'static>(controller: C) -> Result<()> {
+    if LOG_FILTER_CONTROLLER.set(Box::new(controller)).is_err() {
+        bail!("The log filter controller is already registered");
+    }
+    Ok(())
+}
+
+/// Returns the controller of the logger, if it is registered.
+pub(crate) fn log_filter_controller() -> Result<&'static dyn LogFilterController> {
+    LOG_FILTER_CONTROLLER
+        .get()
+        .map(|controller| controller.as_ref())
+        .ok_or_else(|| anyhow!("The log filter of this node cannot be changed at runtime"))
+}
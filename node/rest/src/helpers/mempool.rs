@@ -0,0 +1,125 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::narwhal::{Transmission, TransmissionID},
+    prelude::{Network, ToBytes},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// An unconfirmed transmission in the memory pool of the node.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MempoolEntry {
+    /// The ID of the transmission.
+    pub id: String,
+    /// The kind of the transmission, i.e. `solution` or `transaction`.
+    pub kind: String,
+    /// The total fee of the transaction, in microcredits.
+    pub fee: Option<u64>,
+    /// The priority fee of the transaction, in microcredits.
+    pub priority_fee: Option<u64>,
+    /// The size of the transmission, in bytes.
+    pub size_in_bytes: usize,
+    /// The time since the node received the transmission, in seconds, if it was received directly.
+    pub age_in_secs: Option<i64>,
+}
+
+impl MempoolEntry {
+    /// Initializes a new entry for the given transmission, which was received at the given UNIX timestamp.
+    /// Note: This function deserializes the transaction, so it should be called off the async runtime.
+    pub fn new<N: Network>(
+        id: TransmissionID<N>,
+        transmission: &Transmission<N>,
+        received_at: Option<i64>,
+        now: i64,
+    ) -> Self {
+        let (id, kind) = match id {
+            TransmissionID::Ratification => ("ratification".to_string(), "ratification"),
+            TransmissionID::Solution(id) => (id.to_string(), "solution"),
+            TransmissionID::Transaction(id) => (id.to_string(), "transaction"),
+        };
+        // Retrieve the fees of the transaction.
+        let transaction = match transmission {
+            Transmission::Transaction(data) => data.clone().deserialize_blocking().ok(),
+            _ => None,
+        };
+        let fee = transaction.as_ref().and_then(|transaction| transaction.fee_amount().ok()).map(|fee| *fee);
+        let priority_fee =
+            transaction.as_ref().and_then(|transaction| transaction.priority_fee_amount().ok()).map(|fee| *fee);
+        Self {
+            id,
+            kind: kind.to_string(),
+            fee,
+            priority_fee,
+            size_in_bytes: transmission.to_bytes_le().map(|bytes| bytes.len()).unwrap_or_default(),
+            age_in_secs: received_at.map(|received_at| now.saturating_sub(received_at).max(0)),
+        }
+    }
+}
+
+/// The statistics of the memory pool of the node.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MempoolStats {
+    /// The number of unconfirmed solutions.
+    pub num_solutions: usize,
+    /// The number of unconfirmed transactions.
+    pub num_transactions: usize,
+    /// The total size of the unconfirmed transmissions, in bytes.
+    pub total_size_in_bytes: usize,
+    /// The total fees of the unconfirmed transactions, in microcredits.
+    pub total_fees: u64,
+    /// The age of the oldest unconfirmed transmission, in seconds.
+    pub oldest_age_in_secs: Option<i64>,
+}
+
+impl MempoolStats {
+    /// Returns the statistics of the given entries.
+    pub fn from_entries(entries: &[MempoolEntry]) -> Self {
+        Self {
+            num_solutions: entries.iter().filter(|entry| entry.kind == "solution").count(),
+            num_transactions: entries.iter().filter(|entry| entry.kind == "transaction").count(),
+            total_size_in_bytes: entries.iter().map(|entry| entry.size_in_bytes).sum(),
+            total_fees: entries.iter().filter_map(|entry| entry.fee).fold(0u64, |total, fee| total.saturating_add(fee)),
+            oldest_age_in_secs: entries.iter().filter_map(|entry| entry.age_in_secs).max(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(kind: &str, fee: Option<u64>, size_in_bytes: usize, age_in_secs: Option<i64>) -> MempoolEntry {
+        MempoolEntry { id: String::new(), kind: kind.to_string(), fee, priority_fee: None, size_in_bytes, age_in_secs }
+    }
+
+    #[test]
+    fn test_mempool_stats() {
+        // Ensure the statistics of an empty memory pool are empty.
+        assert_eq!(MempoolStats::from_entries(&[]), MempoolStats::default());
+
+        let entries = [
+            entry("solution", None, 100, Some(5)),
+            entry("transaction", Some(1000), 2000, Some(30)),
+            entry("transaction", Some(500), 1500, None),
+        ];
+        let stats = MempoolStats::from_entries(&entries);
+        assert_eq!(stats.num_solutions, 1);
+        assert_eq!(stats.num_transactions, 2);
+        assert_eq!(stats.total_size_in_bytes, 3600);
+        assert_eq!(stats.total_fees, 1500);
+        assert_eq!(stats.oldest_age_in_secs, Some(30));
+    }
+}
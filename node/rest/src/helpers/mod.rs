@@ -12,8 +12,29 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod analytics;
+pub use analytics::*;
+
+mod archive;
+pub use archive::*;
+
 mod auth;
 pub use auth::*;
 
+mod cache;
+pub use cache::*;
+
+mod digest;
+pub use digest::*;
+
 mod error;
 pub use error::*;
+
+mod log_filter;
+pub use log_filter::*;
+
+mod mempool;
+pub use mempool::*;
+
+mod tip;
+pub use tip::*;
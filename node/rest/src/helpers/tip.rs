@@ -0,0 +1,116 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::committee::Committee,
+    prelude::{block::Block, store::ConsensusStorage, Ledger, Network},
+};
+
+use anyhow::Result;
+use arc_swap::ArcSwapOption;
+use std::sync::Arc;
+
+/// A consistent view of the ledger tip, as of a single block.
+pub struct TipView<N: Network> {
+    /// The latest block.
+    pub block: Block<N>,
+    /// The state root as of the latest block.
+    pub state_root: N::StateRoot,
+    /// The committee as of the latest block, if it could be read.
+    pub committee: Option<Committee<N>>,
+    /// The epoch hash as of the latest block, if it could be read.
+    pub epoch_hash: Option<N::BlockHash>,
+}
+
+/// The latest view of the ledger tip, which is swapped atomically once the ledger advances,
+/// so that queries for the tip never wait for a block to be added.
+///
+/// Note: The view serves the `latest/*` routes, the latest block by height, the puzzle, the mapping metadata,
+/// and the solution checks. The other routes still read the ledger directly.
+pub struct LatestTip<N: Network> {
+    view: ArcSwapOption<TipView<N>>,
+}
+
+impl<N: Network> Default for LatestTip<N> {
+    /// Initializes an empty view of the ledger tip.
+    fn default() -> Self {
+        Self { view: ArcSwapOption::empty() }
+    }
+}
+
+impl<N: Network> LatestTip<N> {
+    /// Returns the latest view of the ledger tip, if one was loaded.
+    pub fn load(&self) -> Option<Arc<TipView<N>>> {
+        self.view.load_full()
+    }
+
+    /// Replaces the view with the given one, unless it is for the same block; returns `true` if it was replaced.
+    pub fn update(&self, view: TipView<N>) -> bool {
+        if self.view.load().as_ref().is_some_and(|current| current.block.hash() == view.block.hash()) {
+            return false;
+        }
+        self.view.store(Some(Arc::new(view)));
+        true
+    }
+
+    /// Reloads the view from the ledger if it advanced; returns `true` if the view was replaced.
+    ///
+    /// Note: This function reads from the ledger, so it should be called off the request path.
+    pub fn refresh<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>) -> Result<bool> {
+        // Return early if the ledger has not advanced.
+        let latest_hash = ledger.latest_hash();
+        if self.view.load().as_ref().is_some_and(|current| current.block.hash() == latest_hash) {
+            return Ok(false);
+        }
+
+        // Read the tip; the reads are not atomic, so discard them if the ledger advanced in the meantime.
+        let block = ledger.latest_block();
+        let state_root = ledger.latest_state_root();
+        let committee = ledger.latest_committee().ok();
+        let epoch_hash = ledger.latest_epoch_hash().ok();
+        if ledger.latest_hash() != block.hash() {
+            return Ok(false);
+        }
+        Ok(self.update(TipView { block, state_root, committee, epoch_hash }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{FromBytes, MainnetV0};
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_latest_tip() {
+        let block = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let view = || TipView {
+            block: block.clone(),
+            state_root: block.header().previous_state_root(),
+            committee: None,
+            epoch_hash: None,
+        };
+        let tip = LatestTip::<CurrentNetwork>::default();
+        assert!(tip.load().is_none());
+
+        // Ensure the first view is stored.
+        assert!(tip.update(view()));
+        assert_eq!(tip.load().unwrap().block, block);
+        // Ensure a view for the same block is not swapped in again.
+        let loaded = tip.load().unwrap();
+        assert!(!tip.update(view()));
+        assert!(Arc::ptr_eq(&loaded, &tip.load().unwrap()));
+    }
+}
@@ -29,11 +29,11 @@ use snarkos_node_router::{
 };
 use snarkvm::{
     console::{program::ProgramID, types::Field},
-    ledger::narwhal::Data,
-    prelude::{cfg_into_iter, store::ConsensusStorage, Ledger, Network},
+    ledger::{committee::Committee, narwhal::Data},
+    prelude::{block::Block, cfg_into_iter, store::ConsensusStorage, Ledger, Network, Transaction},
 };
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use axum::{
     body::Body,
     extract::{ConnectInfo, DefaultBodyLimit, Path, Query, State},
@@ -46,14 +46,17 @@ use axum::{
 };
 use axum_extra::response::ErasedJson;
 use parking_lot::Mutex;
-use std::{net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, num::NonZeroUsize, path::PathBuf, sync::Arc, time::Duration};
 use tokio::{net::TcpListener, task::JoinHandle};
 use tower_governor::{governor::GovernorConfigBuilder, GovernorLayer};
 use tower_http::{
     cors::{Any, CorsLayer},
-    trace::TraceLayer,
+    trace::{DefaultMakeSpan, TraceLayer},
 };
 
+/// The interval at which the REST server checks whether the ledger tip advanced.
+const TIP_REFRESH_INTERVAL_IN_MS: u64 = 100;
+
 /// A REST API server for the ledger.
 #[derive(Clone)]
 pub struct Rest<N: Network, C: ConsensusStorage<N>, R: Routing<N>> {
@@ -63,6 +66,18 @@ pub struct Rest<N: Network, C: ConsensusStorage<N>, R: Routing<N>> {
     ledger: Ledger<N, C>,
     /// The node (routing).
     routing: Arc<R>,
+    /// The archive of the blocks that were migrated out of the ledger, if any.
+    archive: Option<Arc<dyn BlockArchive<N>>>,
+    /// The cache of the recently requested blocks and transactions, if enabled.
+    cache: Option<Arc<BlockCache<N>>>,
+    /// The path of the latest state snapshot, if the node produces them.
+    snapshot_path: Option<PathBuf>,
+    /// The index of the chain statistics, if enabled.
+    analytics: Option<Arc<AnalyticsIndex>>,
+    /// The state digests of the node.
+    state_digests: Arc<StateDigests<N>>,
+    /// The latest view of the ledger tip, which serves the queries for the tip.
+    tip: Arc<LatestTip<N>>,
     /// The server handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
 }
@@ -72,17 +87,61 @@ impl<N: Network, C: 'static + ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R>
     pub async fn start(
         rest_ip: SocketAddr,
         rest_rps: u32,
+        rest_cache_size: usize,
         consensus: Option<Consensus<N>>,
         ledger: Ledger<N, C>,
         routing: Arc<R>,
+        archive: Option<Arc<dyn BlockArchive<N>>>,
+        snapshot_path: Option<PathBuf>,
+        analytics: Option<Arc<AnalyticsIndex>>,
+        state_digests: Arc<StateDigests<N>>,
     ) -> Result<Self> {
+        // Initialize the cache, unless it is disabled.
+        let cache = NonZeroUsize::new(rest_cache_size).map(|capacity| Arc::new(BlockCache::new(capacity)));
         // Initialize the server.
-        let mut server = Self { consensus, ledger, routing, handles: Default::default() };
+        let mut server = Self {
+            consensus,
+            ledger,
+            routing,
+            archive,
+            cache,
+            snapshot_path,
+            analytics,
+            state_digests,
+            tip: Default::default(),
+            handles: Default::default(),
+        };
+        // Load the ledger tip, and keep it up to date.
+        server.spawn_tip_refresher();
         // Spawn the server.
         server.spawn_server(rest_ip, rest_rps).await;
         // Return the server.
         Ok(server)
     }
+
+    /// Spawns a task that swaps in a new view of the ledger tip whenever the ledger advances,
+    /// so that the queries for the tip never contend with block insertion.
+    fn spawn_tip_refresher(&self) {
+        // Load the initial view, so that it is ready once the server starts.
+        if let Err(error) = self.tip.refresh(&self.ledger) {
+            warn!("Failed to load the ledger tip - {error}");
+        }
+
+        let ledger = self.ledger.clone();
+        let tip = self.tip.clone();
+        self.handles.lock().push(tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_millis(TIP_REFRESH_INTERVAL_IN_MS)).await;
+                // Read from the ledger on a blocking thread, as it may wait for a block to be added.
+                let (ledger, tip) = (ledger.clone(), tip.clone());
+                match tokio::task::spawn_blocking(move || tip.refresh(&ledger)).await {
+                    Ok(Ok(_)) => (),
+                    Ok(Err(error)) => warn!("Failed to refresh the ledger tip - {error}"),
+                    Err(error) => error!("Failed to refresh the ledger tip - {error}"),
+                }
+            }
+        }));
+    }
 }
 
 impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
@@ -95,6 +154,90 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     pub const fn handles(&self) -> &Arc<Mutex<Vec<JoinHandle<()>>>> {
         &self.handles
     }
+
+    /// Returns the latest block height, from the latest view of the tip if it is loaded.
+    fn tip_height(&self) -> u32 {
+        match self.tip.load() {
+            Some(view) => view.block.height(),
+            None => self.ledger.latest_height(),
+        }
+    }
+
+    /// Returns the latest block hash, from the latest view of the tip if it is loaded.
+    fn tip_hash(&self) -> N::BlockHash {
+        match self.tip.load() {
+            Some(view) => view.block.hash(),
+            None => self.ledger.latest_hash(),
+        }
+    }
+
+    /// Returns the latest block, from the latest view of the tip if it is loaded.
+    fn tip_block(&self) -> Block<N> {
+        match self.tip.load() {
+            Some(view) => view.block.clone(),
+            None => self.ledger.latest_block(),
+        }
+    }
+
+    /// Returns the latest state root, from the latest view of the tip if it is loaded.
+    fn tip_state_root(&self) -> N::StateRoot {
+        match self.tip.load() {
+            Some(view) => view.state_root,
+            None => self.ledger.latest_state_root(),
+        }
+    }
+
+    /// Returns the latest committee, from the latest view of the tip if it is loaded.
+    fn tip_committee(&self) -> Result<Committee<N>> {
+        match self.tip.load().and_then(|view| view.committee.clone()) {
+            Some(committee) => Ok(committee),
+            None => self.ledger.latest_committee(),
+        }
+    }
+
+    /// Returns the latest epoch hash, from the latest view of the tip if it is loaded.
+    fn tip_epoch_hash(&self) -> Result<N::BlockHash> {
+        match self.tip.load().and_then(|view| view.epoch_hash) {
+            Some(epoch_hash) => Ok(epoch_hash),
+            None => self.ledger.latest_epoch_hash(),
+        }
+    }
+
+    /// Returns the index of the chain statistics, if enabled.
+    fn analytics(&self) -> Result<&AnalyticsIndex> {
+        self.analytics.as_deref().ok_or_else(|| anyhow!("This node does not index the chain statistics"))
+    }
+
+    /// Returns the block at the given height, from the latest view of the tip if it is the latest block,
+    /// or otherwise reading through the cache, if enabled.
+    fn fetch_block(&self, height: u32) -> Result<Block<N>> {
+        if let Some(view) = self.tip.load().filter(|view| view.block.height() == height) {
+            return Ok(view.block.clone());
+        }
+        match &self.cache {
+            Some(cache) => cache.get_block(height, || self.read_block(height)),
+            None => self.read_block(height),
+        }
+    }
+
+    /// Returns the transaction for the given ID, reading through the cache, if enabled.
+    fn fetch_transaction(&self, id: N::TransactionID) -> Result<Transaction<N>> {
+        match &self.cache {
+            Some(cache) => cache.get_transaction(id, || self.ledger.get_transaction(id)),
+            None => self.ledger.get_transaction(id),
+        }
+    }
+
+    /// Returns the block at the given height, reading through to the archive if it was migrated out of the ledger.
+    fn read_block(&self, height: u32) -> Result<Block<N>> {
+        match self.ledger.get_block(height) {
+            Ok(block) => Ok(block),
+            Err(error) => match &self.archive {
+                Some(archive) => archive.get_archived_block(height)?.ok_or(error),
+                None => Err(error),
+            },
+        }
+    }
 }
 
 impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
@@ -133,6 +276,10 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
 
             // All the endpoints before the call to `route_layer` are protected with JWT auth.
             .route(&format!("/{network}/node/address"), get(Self::get_node_address))
+            .route(&format!("/{network}/node/mempool"), get(Self::get_node_mempool))
+            .route(&format!("/{network}/node/mempool/stats"), get(Self::get_node_mempool_stats))
+            .route(&format!("/{network}/node/mempool/:id"), get(Self::get_node_mempool_transmission))
+            .route(&format!("/{network}/node/logLevel"), get(Self::get_node_log_level).post(Self::set_node_log_level))
             .route_layer(middleware::from_fn(auth_middleware))
 
             // ----------------- DEPRECATED ROUTES -----------------
@@ -159,15 +306,21 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
             // The path param here is actually only the height, but the name must match the route
             // above, otherwise there'll be a conflict at runtime.
             .route(&format!("/{network}/block/:height_or_hash/transactions"), get(Self::get_block_transactions))
+            .route(&format!("/{network}/block/:height_or_hash/header"), get(Self::get_block_header))
+            .route(&format!("/{network}/block/:height_or_hash/authority"), get(Self::get_block_authority))
 
             // GET and POST ../transaction/..
             .route(&format!("/{network}/transaction/:id"), get(Self::get_transaction))
             .route(&format!("/{network}/transaction/confirmed/:id"), get(Self::get_confirmed_transaction))
+            .route(&format!("/{network}/transaction/:id/path"), get(Self::get_transaction_path))
             .route(&format!("/{network}/transaction/broadcast"), post(Self::transaction_broadcast))
 
             // POST ../solution/broadcast
             .route(&format!("/{network}/solution/broadcast"), post(Self::solution_broadcast))
 
+            // GET ../puzzle/latest
+            .route(&format!("/{network}/puzzle/latest"), get(Self::get_puzzle_latest))
+
             // GET ../find/..
             .route(&format!("/{network}/find/blockHash/:tx_id"), get(Self::find_block_hash))
             .route(&format!("/{network}/find/blockHeight/:state_root"), get(Self::find_block_height_from_state_root))
@@ -179,6 +332,9 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
             .route(&format!("/{network}/peers/count"), get(Self::get_peers_count))
             .route(&format!("/{network}/peers/all"), get(Self::get_peers_all))
             .route(&format!("/{network}/peers/all/metrics"), get(Self::get_peers_all_metrics))
+            .route(&format!("/{network}/peers/all/latency"), get(Self::get_peers_all_latency))
+            .route(&format!("/{network}/peers/all/traffic"), get(Self::get_peers_all_traffic))
+            .route(&format!("/{network}/peers/offenses"), get(Self::get_peers_offenses))
 
             // GET ../program/..
             .route(&format!("/{network}/program/:id"), get(Self::get_program))
@@ -194,6 +350,11 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
             .route(&format!("/{network}/statePath/:commitment"), get(Self::get_state_path_for_commitment))
             .route(&format!("/{network}/stateRoot/latest"), get(Self::get_state_root_latest))
             .route(&format!("/{network}/stateRoot/:height"), get(Self::get_state_root))
+            .route(&format!("/{network}/snapshot/latest"), get(Self::get_snapshot_latest))
+            .route(&format!("/{network}/digest/latest"), get(Self::get_digest_latest))
+            .route(&format!("/{network}/statistics/latest"), get(Self::get_statistics_latest))
+            .route(&format!("/{network}/statistics/programs"), get(Self::get_statistics_programs))
+            .route(&format!("/{network}/statistics/:height"), get(Self::get_statistics))
             .route(&format!("/{network}/committee/latest"), get(Self::get_committee_latest))
             .route(&format!("/{network}/committee/:height"), get(Self::get_committee))
             .route(&format!("/{network}/delegators/:validator"), get(Self::get_delegators_for_validator));
@@ -203,11 +364,15 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
             let routes =
                 routes.route(&format!("/{network}/block/:blockHeight/history/:mapping"), get(Self::get_history));
 
+            // If the `metrics` feature is enabled, record the latency of the requests per route.
+            #[cfg(feature = "metrics")]
+            let routes = routes.layer(middleware::from_fn(metrics_middleware));
+
             routes
             // Pass in `Rest` to make things convenient.
             .with_state(self.clone())
-            // Enable tower-http tracing.
-            .layer(TraceLayer::new_for_http())
+            // Enable tower-http tracing, with a span per request that is exported to OTLP, if enabled.
+            .layer(TraceLayer::new_for_http().make_span_with(DefaultMakeSpan::new().level(tracing::Level::INFO)))
             // Custom logging.
             .layer(middleware::from_fn(log_middleware))
             // Enable CORS.
@@ -239,6 +404,19 @@ async fn log_middleware(
     Ok(next.run(request).await)
 }
 
+/// Records the latency of the request, labeled with its route (i.e. `/mainnet/block/:height_or_hash`).
+#[cfg(feature = "metrics")]
+async fn metrics_middleware(request: Request<Body>, next: Next) -> Response {
+    // Note: The unmatched requests are not recorded, to bound the number of labels.
+    let route = request.extensions().get::<axum::extract::MatchedPath>().map(|path| path.as_str().to_string());
+    let start = std::time::Instant::now();
+    let response = next.run(request).await;
+    if let Some(route) = route {
+        metrics::histogram_label(metrics::rest::REQUEST_LATENCY, "route", route, start.elapsed().as_secs_f64());
+    }
+    response
+}
+
 /// Formats an ID into a truncated identifier (for logging purposes).
 pub fn fmt_id(id: impl ToString) -> String {
     let id = id.to_string();
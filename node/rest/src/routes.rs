@@ -15,7 +15,7 @@
 use super::*;
 use snarkos_node_router::{messages::UnconfirmedSolution, SYNC_LENIENCY};
 use snarkvm::{
-    ledger::puzzle::Solution,
+    ledger::{narwhal::TransmissionID, puzzle::Solution},
     prelude::{block::Transaction, Address, Identifier, LimitedWriter, Plaintext, ToBytes},
 };
 
@@ -23,6 +23,7 @@ use indexmap::IndexMap;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use time::OffsetDateTime;
 
 /// The `get_blocks` query object.
 #[derive(Deserialize, Serialize)]
@@ -47,48 +48,48 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     // Deprecated: Use `get_block_height_latest` instead.
     // GET /<network>/latest/height
     pub(crate) async fn latest_height(State(rest): State<Self>) -> ErasedJson {
-        ErasedJson::pretty(rest.ledger.latest_height())
+        ErasedJson::pretty(rest.tip_height())
     }
 
     // Deprecated: Use `get_block_hash_latest` instead.
     // GET /<network>/latest/hash
     pub(crate) async fn latest_hash(State(rest): State<Self>) -> ErasedJson {
-        ErasedJson::pretty(rest.ledger.latest_hash())
+        ErasedJson::pretty(rest.tip_hash())
     }
 
     // Deprecated: Use `get_block_latest` instead.
     // GET /<network>/latest/block
     pub(crate) async fn latest_block(State(rest): State<Self>) -> ErasedJson {
-        ErasedJson::pretty(rest.ledger.latest_block())
+        ErasedJson::pretty(rest.tip_block())
     }
 
     // Deprecated: Use `get_state_root_latest` instead.
     // GET /<network>/latest/stateRoot
     pub(crate) async fn latest_state_root(State(rest): State<Self>) -> ErasedJson {
-        ErasedJson::pretty(rest.ledger.latest_state_root())
+        ErasedJson::pretty(rest.tip_state_root())
     }
 
     // Deprecated: Use `get_committee_latest` instead.
     // GET /<network>/latest/committee
     pub(crate) async fn latest_committee(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
-        Ok(ErasedJson::pretty(rest.ledger.latest_committee()?))
+        Ok(ErasedJson::pretty(rest.tip_committee()?))
     }
 
     // ---------------------------------------------------------
 
     // GET /<network>/block/height/latest
     pub(crate) async fn get_block_height_latest(State(rest): State<Self>) -> ErasedJson {
-        ErasedJson::pretty(rest.ledger.latest_height())
+        ErasedJson::pretty(rest.tip_height())
     }
 
     // GET /<network>/block/hash/latest
     pub(crate) async fn get_block_hash_latest(State(rest): State<Self>) -> ErasedJson {
-        ErasedJson::pretty(rest.ledger.latest_hash())
+        ErasedJson::pretty(rest.tip_hash())
     }
 
     // GET /<network>/block/latest
     pub(crate) async fn get_block_latest(State(rest): State<Self>) -> ErasedJson {
-        ErasedJson::pretty(rest.ledger.latest_block())
+        ErasedJson::pretty(rest.tip_block())
     }
 
     // GET /<network>/block/{height}
@@ -100,13 +101,13 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         // Manually parse the height or the height of the hash, axum doesn't support different types
         // for the same path param.
         let block = if let Ok(height) = height_or_hash.parse::<u32>() {
-            rest.ledger.get_block(height)?
+            rest.fetch_block(height)?
         } else {
             let hash = height_or_hash
                 .parse::<N::BlockHash>()
                 .map_err(|_| RestError("invalid input, it is neither a block height nor a block hash".to_string()))?;
 
-            rest.ledger.get_block_by_hash(&hash)?
+            rest.fetch_block(rest.ledger.get_height(&hash)?)?
         };
 
         Ok(ErasedJson::pretty(block))
@@ -138,7 +139,7 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         // Prepare a closure for the blocking work.
         let get_json_blocks = move || -> Result<ErasedJson, RestError> {
             let blocks = cfg_into_iter!((start_height..end_height))
-                .map(|height| rest.ledger.get_block(height))
+                .map(|height| rest.fetch_block(height))
                 .collect::<Result<Vec<_>, _>>()?;
 
             Ok(ErasedJson::pretty(blocks))
@@ -164,7 +165,26 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         State(rest): State<Self>,
         Path(height): Path<u32>,
     ) -> Result<ErasedJson, RestError> {
-        Ok(ErasedJson::pretty(rest.ledger.get_transactions(height)?))
+        match rest.ledger.get_transactions(height) {
+            Ok(transactions) => Ok(ErasedJson::pretty(transactions)),
+            Err(_) => Ok(ErasedJson::pretty(rest.fetch_block(height)?.transactions())),
+        }
+    }
+
+    // GET /<network>/block/{height}/header
+    pub(crate) async fn get_block_header(
+        State(rest): State<Self>,
+        Path(height): Path<u32>,
+    ) -> Result<ErasedJson, RestError> {
+        Ok(ErasedJson::pretty(rest.ledger.get_header(height)?))
+    }
+
+    // GET /<network>/block/{height}/authority
+    pub(crate) async fn get_block_authority(
+        State(rest): State<Self>,
+        Path(height): Path<u32>,
+    ) -> Result<ErasedJson, RestError> {
+        Ok(ErasedJson::pretty(rest.fetch_block(height)?.authority()))
     }
 
     // GET /<network>/transaction/{transactionID}
@@ -172,7 +192,24 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         State(rest): State<Self>,
         Path(tx_id): Path<N::TransactionID>,
     ) -> Result<ErasedJson, RestError> {
-        Ok(ErasedJson::pretty(rest.ledger.get_transaction(tx_id)?))
+        Ok(ErasedJson::pretty(rest.fetch_transaction(tx_id)?))
+    }
+
+    // GET /<network>/transaction/{transactionID}/path
+    pub(crate) async fn get_transaction_path(
+        State(rest): State<Self>,
+        Path(tx_id): Path<N::TransactionID>,
+    ) -> Result<ErasedJson, RestError> {
+        let Some(block_hash) = rest.ledger.find_block_hash(&tx_id)? else {
+            return Err(RestError(format!("Transaction '{tx_id}' is not in a block")));
+        };
+        let height = rest.ledger.get_height(&block_hash)?;
+        // Return the height of the block, and the Merkle path of the transaction in its transactions root.
+        let path = match rest.ledger.get_transactions(height) {
+            Ok(transactions) => transactions.to_path(tx_id)?,
+            Err(_) => rest.fetch_block(height)?.transactions().to_path(tx_id)?,
+        };
+        Ok(ErasedJson::pretty((height, path)))
     }
 
     // GET /<network>/transaction/confirmed/{transactionID}
@@ -209,6 +246,72 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         }
     }
 
+    // GET /<network>/node/mempool
+    pub(crate) async fn get_node_mempool(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        Ok(ErasedJson::pretty(rest.mempool_entries().await?))
+    }
+
+    // GET /<network>/node/mempool/stats
+    pub(crate) async fn get_node_mempool_stats(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        Ok(ErasedJson::pretty(MempoolStats::from_entries(&rest.mempool_entries().await?)))
+    }
+
+    // GET /<network>/node/mempool/{transmissionID}
+    pub(crate) async fn get_node_mempool_transmission(
+        State(rest): State<Self>,
+        Path(id): Path<String>,
+    ) -> Result<ErasedJson, RestError> {
+        let consensus = rest.consensus.ok_or(RestError("Route isn't available for this node type".to_string()))?;
+        // Find the transmission with the given ID.
+        let Some((transmission_id, transmission)) =
+            consensus.unconfirmed_transmissions().find(|(transmission_id, _)| match transmission_id {
+                TransmissionID::Solution(solution_id) => solution_id.to_string() == id,
+                TransmissionID::Transaction(transaction_id) => transaction_id.to_string() == id,
+                TransmissionID::Ratification => false,
+            })
+        else {
+            return Err(RestError(format!("Transmission '{id}' is not in the memory pool")));
+        };
+        let received_at = consensus.transmission_received_at(transmission_id);
+        // Construct the entry.
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let transmission_ = transmission.clone();
+        let entry =
+            tokio::task::spawn_blocking(move || MempoolEntry::new(transmission_id, &transmission_, received_at, now))
+                .await
+                .map_err(|err| RestError(format!("Unable to inspect the memory pool - {err}")))?;
+        Ok(ErasedJson::pretty(json!({ "entry": entry, "transmission": transmission })))
+    }
+
+    // GET /<network>/node/logLevel
+    pub(crate) async fn get_node_log_level() -> Result<ErasedJson, RestError> {
+        Ok(ErasedJson::pretty(log_filter_controller()?.log_filter()))
+    }
+
+    // POST /<network>/node/logLevel
+    pub(crate) async fn set_node_log_level(Json(request): Json<LogFilterRequest>) -> Result<ErasedJson, RestError> {
+        let log_filter = log_filter_controller()?.set_log_filter(&request)?;
+        info!("Changed the log filter to '{}'", log_filter.filter);
+        Ok(ErasedJson::pretty(log_filter))
+    }
+
+    /// Returns the entries of the unconfirmed transmissions in the memory pool.
+    async fn mempool_entries(&self) -> Result<Vec<MempoolEntry>, RestError> {
+        let consensus =
+            self.consensus.clone().ok_or(RestError("Route isn't available for this node type".to_string()))?;
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        tokio::task::spawn_blocking(move || {
+            consensus
+                .unconfirmed_transmissions()
+                .map(|(id, transmission)| {
+                    MempoolEntry::new(id, &transmission, consensus.transmission_received_at(id), now)
+                })
+                .collect::<Vec<_>>()
+        })
+        .await
+        .map_err(|err| RestError(format!("Unable to inspect the memory pool - {err}")))
+    }
+
     // GET /<network>/program/{programID}
     pub(crate) async fn get_program(
         State(rest): State<Self>,
@@ -239,7 +342,7 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         if metadata.map(|q| q.metadata).unwrap_or(false) {
             return Ok(ErasedJson::pretty(json!({
                 "data": mapping_value,
-                "height": rest.ledger.latest_height(),
+                "height": rest.tip_height(),
             })));
         }
 
@@ -257,7 +360,7 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
 
     // GET /<network>/stateRoot/latest
     pub(crate) async fn get_state_root_latest(State(rest): State<Self>) -> ErasedJson {
-        ErasedJson::pretty(rest.ledger.latest_state_root())
+        ErasedJson::pretty(rest.tip_state_root())
     }
 
     // GET /<network>/stateRoot/{height}
@@ -270,7 +373,7 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
 
     // GET /<network>/committee/latest
     pub(crate) async fn get_committee_latest(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
-        Ok(ErasedJson::pretty(rest.ledger.latest_committee()?))
+        Ok(ErasedJson::pretty(rest.tip_committee()?))
     }
 
     // GET /<network>/committee/{height}
@@ -281,6 +384,54 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         Ok(ErasedJson::pretty(rest.ledger.get_committee(height)?))
     }
 
+    // GET /<network>/snapshot/latest
+    pub(crate) async fn get_snapshot_latest(State(rest): State<Self>) -> Result<Response, RestError> {
+        let Some(path) = rest.snapshot_path.clone() else {
+            return Err(RestError("This node does not produce state snapshots".to_string()));
+        };
+
+        // Stream the latest state snapshot, which the node replaces atomically, so the open file stays intact.
+        let file = tokio::fs::File::open(path)
+            .await
+            .map_err(|err| RestError(format!("No state snapshot is available yet - {err}")))?;
+        Response::builder()
+            .header(CONTENT_TYPE, "application/octet-stream")
+            .body(Body::from_stream(tokio_util::io::ReaderStream::new(file)))
+            .map_err(|err| RestError(format!("Unable to serve the state snapshot - {err}")))
+    }
+
+    // GET /<network>/digest/latest
+    pub(crate) async fn get_digest_latest(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        match rest.state_digests.latest() {
+            Some(digest) => Ok(ErasedJson::pretty(digest)),
+            None => Err(RestError("No state digest was computed yet (see '--state-digest-interval')".to_string())),
+        }
+    }
+
+    // GET /<network>/statistics/latest
+    pub(crate) async fn get_statistics_latest(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        match rest.analytics()?.totals()? {
+            Some(totals) => Ok(ErasedJson::pretty(totals)),
+            None => Err(RestError("No block was indexed yet".to_string())),
+        }
+    }
+
+    // GET /<network>/statistics/{height}
+    pub(crate) async fn get_statistics(
+        State(rest): State<Self>,
+        Path(height): Path<u32>,
+    ) -> Result<ErasedJson, RestError> {
+        match rest.analytics()?.get_block(height)? {
+            Some(statistics) => Ok(ErasedJson::pretty(statistics)),
+            None => Err(RestError(format!("Block {height} was not indexed yet"))),
+        }
+    }
+
+    // GET /<network>/statistics/programs
+    pub(crate) async fn get_statistics_programs(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        Ok(ErasedJson::pretty(rest.analytics()?.program_calls()?))
+    }
+
     // GET /<network>/delegators/{validator}
     pub(crate) async fn get_delegators_for_validator(
         State(rest): State<Self>,
@@ -314,6 +465,28 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         ErasedJson::pretty(rest.routing.router().connected_metrics())
     }
 
+    // GET /<network>/peers/all/latency
+    pub(crate) async fn get_peers_all_latency(State(rest): State<Self>) -> ErasedJson {
+        // Report the smoothed round-trip time of each connected peer in milliseconds, if measured.
+        let latencies = rest.routing.router().connected_latencies();
+        ErasedJson::pretty(
+            latencies
+                .into_iter()
+                .map(|(peer_ip, rtt)| (peer_ip, rtt.map(|rtt| rtt.as_millis() as u64)))
+                .collect::<IndexMap<_, _>>(),
+        )
+    }
+
+    // GET /<network>/peers/all/traffic
+    pub(crate) async fn get_peers_all_traffic(State(rest): State<Self>) -> ErasedJson {
+        ErasedJson::pretty(rest.routing.router().connected_traffic().into_iter().collect::<IndexMap<_, _>>())
+    }
+
+    // GET /<network>/peers/offenses
+    pub(crate) async fn get_peers_offenses(State(rest): State<Self>) -> ErasedJson {
+        ErasedJson::pretty(rest.routing.router().offenses())
+    }
+
     // GET /<network>/node/address
     pub(crate) async fn get_node_address(State(rest): State<Self>) -> ErasedJson {
         ErasedJson::pretty(rest.routing.router().address())
@@ -418,9 +591,9 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
             // Verify the solution.
             None => {
                 // Compute the current epoch hash.
-                let epoch_hash = rest.ledger.latest_epoch_hash()?;
+                let epoch_hash = rest.tip_epoch_hash()?;
                 // Retrieve the current proof target.
-                let proof_target = rest.ledger.latest_proof_target();
+                let proof_target = rest.tip_block().proof_target();
                 // Ensure that the solution is valid for the given epoch.
                 let puzzle = rest.ledger.puzzle().clone();
                 // Verify the solution in a blocking task.
@@ -447,6 +620,17 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         Ok(ErasedJson::pretty(solution_id))
     }
 
+    // GET /<network>/puzzle/latest
+    pub(crate) async fn get_puzzle_latest(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        let block = rest.tip_block();
+        Ok(ErasedJson::pretty(json!({
+            "height": block.height(),
+            "epoch_hash": rest.tip_epoch_hash()?,
+            "proof_target": block.proof_target(),
+            "coinbase_target": block.coinbase_target(),
+        })))
+    }
+
     // GET /{network}/block/{blockHeight}/history/{mapping}
     #[cfg(feature = "history")]
     pub(crate) async fn get_history(
@@ -0,0 +1,137 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use snarkvm::{
+    ledger::block::Block,
+    prelude::{FromBytes, ToBytes},
+};
+
+use anyhow::{anyhow, Result};
+use std::borrow::Cow;
+
+/// A request for the transactions of an announced block, which are missing to reconstruct it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockTransactionsRequest<N: Network> {
+    /// The hash of the announced block.
+    pub block_hash: N::BlockHash,
+    /// The IDs of the missing transactions.
+    pub transaction_ids: Vec<N::TransactionID>,
+}
+
+impl<N: Network> MessageTrait for BlockTransactionsRequest<N> {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        "BlockTransactionsRequest".into()
+    }
+}
+
+impl<N: Network> ToBytes for BlockTransactionsRequest<N> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        self.block_hash.write_le(&mut writer)?;
+        u32::try_from(self.transaction_ids.len()).map_err(error)?.write_le(&mut writer)?;
+        self.transaction_ids.iter().try_for_each(|id| id.write_le(&mut writer))
+    }
+}
+
+impl<N: Network> FromBytes for BlockTransactionsRequest<N> {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let block_hash = N::BlockHash::read_le(&mut reader)?;
+        let num_ids = u32::read_le(&mut reader)?;
+        let transaction_ids =
+            (0..num_ids).map(|_| N::TransactionID::read_le(&mut reader)).collect::<io::Result<_>>()?;
+        Ok(Self { block_hash, transaction_ids })
+    }
+}
+
+/// The transactions of an announced block, in response to a `BlockTransactionsRequest`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockTransactionsResponse<N: Network> {
+    /// The hash of the announced block.
+    pub block_hash: N::BlockHash,
+    /// The requested transactions.
+    pub transactions: Vec<Transaction<N>>,
+}
+
+impl<N: Network> BlockTransactionsResponse<N> {
+    /// Initializes a new `BlockTransactionsResponse` message, with the given transactions of the block.
+    pub fn new(block: &Block<N>, transaction_ids: &[N::TransactionID]) -> Result<Self> {
+        let transactions = transaction_ids
+            .iter()
+            .map(|id| match block.transactions().get(id) {
+                Some(confirmed) => Ok(confirmed.transaction().clone()),
+                None => Err(anyhow!("Transaction '{id}' is not in block {}", block.height())),
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { block_hash: block.hash(), transactions })
+    }
+}
+
+impl<N: Network> MessageTrait for BlockTransactionsResponse<N> {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        "BlockTransactionsResponse".into()
+    }
+}
+
+impl<N: Network> ToBytes for BlockTransactionsResponse<N> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        self.block_hash.write_le(&mut writer)?;
+        u32::try_from(self.transactions.len()).map_err(error)?.write_le(&mut writer)?;
+        self.transactions.iter().try_for_each(|transaction| transaction.write_le(&mut writer))
+    }
+}
+
+impl<N: Network> FromBytes for BlockTransactionsResponse<N> {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let block_hash = N::BlockHash::read_le(&mut reader)?;
+        let num_transactions = u32::read_le(&mut reader)?;
+        let transactions =
+            (0..num_transactions).map(|_| Transaction::read_le(&mut reader)).collect::<io::Result<_>>()?;
+        Ok(Self { block_hash, transactions })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::{block_response::prop_tests::any_block, BlockTransactionsRequest, BlockTransactionsResponse};
+    use snarkvm::{
+        prelude::block::Block,
+        utilities::{FromBytes, ToBytes},
+    };
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use test_strategy::proptest;
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    #[proptest]
+    fn block_transactions_roundtrip(#[strategy(any_block())] block: Block<CurrentNetwork>) {
+        let transaction_ids = block.transaction_ids().copied().collect::<Vec<_>>();
+
+        let request = BlockTransactionsRequest { block_hash: block.hash(), transaction_ids: transaction_ids.clone() };
+        let mut bytes = BytesMut::default().writer();
+        request.write_le(&mut bytes).unwrap();
+        assert_eq!(BlockTransactionsRequest::read_le(&mut bytes.into_inner().reader()).unwrap(), request);
+
+        let response = BlockTransactionsResponse::new(&block, &transaction_ids).unwrap();
+        assert_eq!(response.transactions.len(), transaction_ids.len());
+        let mut bytes = BytesMut::default().writer();
+        response.write_le(&mut bytes).unwrap();
+        assert_eq!(BlockTransactionsResponse::read_le(&mut bytes.into_inner().reader()).unwrap(), response);
+    }
+}
@@ -25,6 +25,10 @@ pub struct ChallengeRequest<N: Network> {
     pub node_type: NodeType,
     pub address: Address<N>,
     pub nonce: u64,
+    /// The optional protocol features supported by the sender.
+    /// Note: This field is omitted on the wire if it is empty, and it is only set by the responder of the handshake,
+    /// once it knows that the version of the initiator supports it (see `Message::CAPABILITIES_VERSION`).
+    pub capabilities: Capabilities,
 }
 
 impl<N: Network> MessageTrait for ChallengeRequest<N> {
@@ -42,6 +46,9 @@ impl<N: Network> ToBytes for ChallengeRequest<N> {
         self.node_type.write_le(&mut writer)?;
         self.address.write_le(&mut writer)?;
         self.nonce.write_le(&mut writer)?;
+        if !self.capabilities.is_empty() {
+            self.capabilities.write_le(&mut writer)?;
+        }
         Ok(())
     }
 }
@@ -53,20 +60,41 @@ impl<N: Network> FromBytes for ChallengeRequest<N> {
         let node_type = NodeType::read_le(&mut reader)?;
         let address = Address::<N>::read_le(&mut reader)?;
         let nonce = u64::read_le(&mut reader)?;
-
-        Ok(Self { version, listener_port, node_type, address, nonce })
+        // Read the capabilities, if they are present.
+        let mut remaining = Vec::new();
+        reader.read_to_end(&mut remaining)?;
+        let capabilities = match remaining.is_empty() {
+            true => Capabilities::default(),
+            false => {
+                let mut remaining = &remaining[..];
+                let capabilities = Capabilities::read_le(&mut remaining)?;
+                if !remaining.is_empty() {
+                    return Err(error("Leftover bytes in the capabilities"));
+                }
+                capabilities
+            }
+        };
+
+        Ok(Self { version, listener_port, node_type, address, nonce, capabilities })
     }
 }
 
 impl<N: Network> ChallengeRequest<N> {
     pub fn new(listener_port: u16, node_type: NodeType, address: Address<N>, nonce: u64) -> Self {
-        Self { version: Message::<N>::VERSION, listener_port, node_type, address, nonce }
+        let capabilities = Capabilities::default();
+        Self { version: Message::<N>::VERSION, listener_port, node_type, address, nonce, capabilities }
+    }
+
+    /// Sets the capabilities of the sender.
+    pub fn with_capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = capabilities;
+        self
     }
 }
 
 #[cfg(test)]
 pub mod prop_tests {
-    use crate::{ChallengeRequest, NodeType};
+    use crate::{Capabilities, ChallengeRequest, NodeType};
     use snarkvm::{
         console::prelude::{FromBytes, ToBytes},
         prelude::{Address, TestRng, Uniform},
@@ -93,14 +121,27 @@ pub mod prop_tests {
             .boxed()
     }
 
+    pub fn any_capabilities() -> BoxedStrategy<Capabilities> {
+        (any::<u64>(), any::<Option<u16>>(), any::<Option<(u16, u16)>>())
+            .prop_map(|(flags, quic_port, message_version)| {
+                let capabilities = Capabilities::default().with_flags(flags).with_quic_port(quic_port);
+                match message_version {
+                    Some((message_id, version)) => capabilities.with_message_version(message_id, version),
+                    None => capabilities,
+                }
+            })
+            .boxed()
+    }
+
     pub fn any_challenge_request() -> BoxedStrategy<ChallengeRequest<CurrentNetwork>> {
-        (any_valid_address(), any::<u64>(), any::<u32>(), any::<u16>(), any_node_type())
-            .prop_map(|(address, nonce, version, listener_port, node_type)| ChallengeRequest {
+        (any_valid_address(), any::<u64>(), any::<u32>(), any::<u16>(), any_node_type(), any_capabilities())
+            .prop_map(|(address, nonce, version, listener_port, node_type, capabilities)| ChallengeRequest {
                 address,
                 nonce,
                 version,
                 listener_port,
                 node_type,
+                capabilities,
             })
             .boxed()
     }
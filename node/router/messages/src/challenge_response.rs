@@ -27,6 +27,10 @@ pub struct ChallengeResponse<N: Network> {
     pub restrictions_id: Field<N>,
     pub signature: Data<Signature<N>>,
     pub nonce: u64,
+    /// The optional protocol features supported by the sender.
+    /// Note: This field is omitted on the wire if it is empty, and it is only set by the initiator of the handshake,
+    /// once it knows that the version of the responder supports it (see `Message::CAPABILITIES_VERSION`).
+    pub capabilities: Capabilities,
 }
 
 impl<N: Network> MessageTrait for ChallengeResponse<N> {
@@ -42,24 +46,42 @@ impl<N: Network> ToBytes for ChallengeResponse<N> {
         self.genesis_header.write_le(&mut writer)?;
         self.restrictions_id.write_le(&mut writer)?;
         self.signature.write_le(&mut writer)?;
-        self.nonce.write_le(&mut writer)
+        self.nonce.write_le(&mut writer)?;
+        if !self.capabilities.is_empty() {
+            self.capabilities.write_le(&mut writer)?;
+        }
+        Ok(())
     }
 }
 
 impl<N: Network> FromBytes for ChallengeResponse<N> {
     fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
-        Ok(Self {
-            genesis_header: Header::read_le(&mut reader)?,
-            restrictions_id: Field::read_le(&mut reader)?,
-            signature: Data::read_le(&mut reader)?,
-            nonce: u64::read_le(reader)?,
-        })
+        let genesis_header = Header::read_le(&mut reader)?;
+        let restrictions_id = Field::read_le(&mut reader)?;
+        let signature = Data::read_le(&mut reader)?;
+        let nonce = u64::read_le(&mut reader)?;
+        // Read the capabilities, if they are present.
+        let mut remaining = Vec::new();
+        reader.read_to_end(&mut remaining)?;
+        let capabilities = match remaining.is_empty() {
+            true => Capabilities::default(),
+            false => {
+                let mut remaining = &remaining[..];
+                let capabilities = Capabilities::read_le(&mut remaining)?;
+                if !remaining.is_empty() {
+                    return Err(error("Leftover bytes in the capabilities"));
+                }
+                capabilities
+            }
+        };
+
+        Ok(Self { genesis_header, restrictions_id, signature, nonce, capabilities })
     }
 }
 
 #[cfg(test)]
 pub mod prop_tests {
-    use crate::ChallengeResponse;
+    use crate::{challenge_request::prop_tests::any_capabilities, ChallengeResponse};
     use snarkvm::{
         console::prelude::{FromBytes, ToBytes},
         ledger::{ledger_test_helpers::sample_genesis_block, narwhal::Data},
@@ -93,12 +115,13 @@ pub mod prop_tests {
     }
 
     pub fn any_challenge_response() -> BoxedStrategy<ChallengeResponse<CurrentNetwork>> {
-        (any_genesis_header(), any_signature(), any::<u64>())
-            .prop_map(|(genesis_header, sig, nonce)| ChallengeResponse {
+        (any_genesis_header(), any_signature(), any::<u64>(), any_capabilities())
+            .prop_map(|(genesis_header, sig, nonce, capabilities)| ChallengeResponse {
                 genesis_header,
                 restrictions_id: any_restrictions_id(),
                 signature: Data::Object(sig),
                 nonce,
+                capabilities,
             })
             .boxed()
     }
@@ -112,6 +135,7 @@ pub mod prop_tests {
             ChallengeResponse::read_le(buf.into_inner().reader()).unwrap();
 
         assert_eq!(original.genesis_header, deserialized.genesis_header);
+        assert_eq!(original.capabilities, deserialized.capabilities);
         assert_eq!(
             original.signature.deserialize_blocking().unwrap(),
             deserialized.signature.deserialize_blocking().unwrap()
@@ -0,0 +1,280 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use snarkvm::{
+    ledger::{
+        authority::Authority,
+        block::{Block, ConfirmedTransaction, Ratifications, Solutions, Transactions},
+    },
+    prelude::{FinalizeOperation, FromBytes, ToBytes},
+};
+
+use anyhow::{bail, ensure, Result};
+use std::borrow::Cow;
+
+/// A confirmed transaction in a compact block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CompactTransaction<N: Network> {
+    /// An accepted deployment, as (index, transaction ID, finalize operations), whose body is omitted.
+    AcceptedDeploy(u32, N::TransactionID, Vec<FinalizeOperation<N>>),
+    /// An accepted execution, as (index, transaction ID, finalize operations), whose body is omitted.
+    AcceptedExecute(u32, N::TransactionID, Vec<FinalizeOperation<N>>),
+    /// A rejected transaction, which is sent in full, as peers do not have its fee transaction.
+    Full(ConfirmedTransaction<N>),
+}
+
+impl<N: Network> CompactTransaction<N> {
+    /// Returns the ID of the omitted transaction, if any.
+    pub fn omitted_id(&self) -> Option<&N::TransactionID> {
+        match self {
+            Self::AcceptedDeploy(_, id, _) | Self::AcceptedExecute(_, id, _) => Some(id),
+            Self::Full(..) => None,
+        }
+    }
+}
+
+impl<N: Network> From<&ConfirmedTransaction<N>> for CompactTransaction<N> {
+    /// Omits the body of the given confirmed transaction, if it is accepted.
+    fn from(confirmed: &ConfirmedTransaction<N>) -> Self {
+        match confirmed {
+            ConfirmedTransaction::AcceptedDeploy(index, transaction, finalize) => {
+                Self::AcceptedDeploy(*index, transaction.id(), finalize.clone())
+            }
+            ConfirmedTransaction::AcceptedExecute(index, transaction, finalize) => {
+                Self::AcceptedExecute(*index, transaction.id(), finalize.clone())
+            }
+            confirmed => Self::Full(confirmed.clone()),
+        }
+    }
+}
+
+impl<N: Network> ToBytes for CompactTransaction<N> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        match self {
+            Self::AcceptedDeploy(index, id, finalize) | Self::AcceptedExecute(index, id, finalize) => {
+                let variant: u8 = if matches!(self, Self::AcceptedDeploy(..)) { 0 } else { 1 };
+                variant.write_le(&mut writer)?;
+                index.write_le(&mut writer)?;
+                id.write_le(&mut writer)?;
+                u32::try_from(finalize.len()).map_err(error)?.write_le(&mut writer)?;
+                finalize.iter().try_for_each(|operation| operation.write_le(&mut writer))
+            }
+            Self::Full(confirmed) => {
+                2u8.write_le(&mut writer)?;
+                confirmed.write_le(&mut writer)
+            }
+        }
+    }
+}
+
+impl<N: Network> FromBytes for CompactTransaction<N> {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        match u8::read_le(&mut reader)? {
+            variant @ (0 | 1) => {
+                let index = u32::read_le(&mut reader)?;
+                let id = N::TransactionID::read_le(&mut reader)?;
+                let num_operations = u32::read_le(&mut reader)?;
+                let finalize =
+                    (0..num_operations).map(|_| FinalizeOperation::read_le(&mut reader)).collect::<io::Result<_>>()?;
+                match variant {
+                    0 => Ok(Self::AcceptedDeploy(index, id, finalize)),
+                    _ => Ok(Self::AcceptedExecute(index, id, finalize)),
+                }
+            }
+            2 => Ok(Self::Full(ConfirmedTransaction::read_le(&mut reader)?)),
+            variant => Err(error(format!("Unknown compact transaction variant {variant}"))),
+        }
+    }
+}
+
+/// A block announcement, which omits the bodies of the accepted transactions,
+/// so that peers reconstruct the block from the transactions they have already seen.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompactBlock<N: Network> {
+    /// The hash of the block.
+    pub block_hash: N::BlockHash,
+    /// The hash of the previous block.
+    pub previous_hash: N::BlockHash,
+    /// The header of the block.
+    pub header: Header<N>,
+    /// The authority of the block.
+    pub authority: Authority<N>,
+    /// The ratifications of the block.
+    pub ratifications: Ratifications<N>,
+    /// The solutions of the block.
+    pub solutions: Solutions<N>,
+    /// The IDs of the aborted solutions.
+    pub aborted_solution_ids: Vec<SolutionID<N>>,
+    /// The confirmed transactions, of which the accepted ones are omitted.
+    pub transactions: Vec<CompactTransaction<N>>,
+    /// The IDs of the aborted transactions.
+    pub aborted_transaction_ids: Vec<N::TransactionID>,
+}
+
+impl<N: Network> From<&Block<N>> for CompactBlock<N> {
+    /// Initializes a new `CompactBlock` message.
+    fn from(block: &Block<N>) -> Self {
+        Self {
+            block_hash: block.hash(),
+            previous_hash: block.previous_hash(),
+            header: *block.header(),
+            authority: block.authority().clone(),
+            ratifications: block.ratifications().clone(),
+            solutions: block.solutions().clone(),
+            aborted_solution_ids: block.aborted_solution_ids().clone(),
+            transactions: block.transactions().iter().map(CompactTransaction::from).collect(),
+            aborted_transaction_ids: block.aborted_transaction_ids().clone(),
+        }
+    }
+}
+
+impl<N: Network> CompactBlock<N> {
+    /// Returns the height of the block.
+    pub fn height(&self) -> u32 {
+        self.header.height()
+    }
+
+    /// Returns the IDs of the omitted transactions.
+    pub fn omitted_transaction_ids(&self) -> impl Iterator<Item = &N::TransactionID> {
+        self.transactions.iter().filter_map(CompactTransaction::omitted_id)
+    }
+
+    /// Reconstructs the block, retrieving the omitted transactions with the given function.
+    pub fn reconstruct(
+        &self,
+        mut get_transaction: impl FnMut(&N::TransactionID) -> Option<Transaction<N>>,
+    ) -> Result<Block<N>> {
+        let mut transactions = Vec::with_capacity(self.transactions.len());
+        for compact in &self.transactions {
+            let confirmed = match compact {
+                CompactTransaction::AcceptedDeploy(index, id, finalize) => match get_transaction(id) {
+                    Some(transaction) => ConfirmedTransaction::accepted_deploy(*index, transaction, finalize.clone())?,
+                    None => bail!("Missing transaction '{id}'"),
+                },
+                CompactTransaction::AcceptedExecute(index, id, finalize) => match get_transaction(id) {
+                    Some(transaction) => ConfirmedTransaction::accepted_execute(*index, transaction, finalize.clone())?,
+                    None => bail!("Missing transaction '{id}'"),
+                },
+                CompactTransaction::Full(confirmed) => confirmed.clone(),
+            };
+            // Ensure the retrieved transaction is the one that was omitted.
+            if let Some(id) = compact.omitted_id() {
+                ensure!(confirmed.id() == *id, "Mismatching transaction '{id}'");
+            }
+            transactions.push(confirmed);
+        }
+        // Reconstruct the block.
+        let block = Block::from(
+            self.previous_hash,
+            self.header,
+            self.authority.clone(),
+            self.ratifications.clone(),
+            self.solutions.clone(),
+            self.aborted_solution_ids.clone(),
+            transactions.into_iter().collect::<Transactions<N>>(),
+            self.aborted_transaction_ids.clone(),
+        )?;
+        // Ensure the reconstructed block matches the announced block hash.
+        ensure!(block.hash() == self.block_hash, "The reconstructed block {} has a mismatching hash", self.height());
+        Ok(block)
+    }
+}
+
+impl<N: Network> MessageTrait for CompactBlock<N> {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        format!("CompactBlock {}", self.height()).into()
+    }
+}
+
+/// Writes the length-prefixed list of the given items.
+fn write_list<T: ToBytes, W: io::Write>(items: &[T], mut writer: W) -> io::Result<()> {
+    u32::try_from(items.len()).map_err(error)?.write_le(&mut writer)?;
+    items.iter().try_for_each(|item| item.write_le(&mut writer))
+}
+
+/// Reads a length-prefixed list of items.
+fn read_list<T: FromBytes, R: io::Read>(mut reader: R) -> io::Result<Vec<T>> {
+    let num_items = u32::read_le(&mut reader)?;
+    (0..num_items).map(|_| T::read_le(&mut reader)).collect()
+}
+
+impl<N: Network> ToBytes for CompactBlock<N> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        self.block_hash.write_le(&mut writer)?;
+        self.previous_hash.write_le(&mut writer)?;
+        self.header.write_le(&mut writer)?;
+        self.authority.write_le(&mut writer)?;
+        self.ratifications.write_le(&mut writer)?;
+        self.solutions.write_le(&mut writer)?;
+        write_list(&self.aborted_solution_ids, &mut writer)?;
+        write_list(&self.transactions, &mut writer)?;
+        write_list(&self.aborted_transaction_ids, &mut writer)
+    }
+}
+
+impl<N: Network> FromBytes for CompactBlock<N> {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        Ok(Self {
+            block_hash: N::BlockHash::read_le(&mut reader)?,
+            previous_hash: N::BlockHash::read_le(&mut reader)?,
+            header: Header::read_le(&mut reader)?,
+            authority: Authority::read_le(&mut reader)?,
+            ratifications: Ratifications::read_le(&mut reader)?,
+            solutions: Solutions::read_le(&mut reader)?,
+            aborted_solution_ids: read_list(&mut reader)?,
+            transactions: read_list(&mut reader)?,
+            aborted_transaction_ids: read_list(&mut reader)?,
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::{block_response::prop_tests::any_block, CompactBlock};
+    use snarkvm::utilities::{FromBytes, ToBytes};
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use proptest::prelude::{BoxedStrategy, Strategy};
+    use test_strategy::proptest;
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    pub fn any_compact_block() -> BoxedStrategy<CompactBlock<CurrentNetwork>> {
+        any_block().prop_map(|block| CompactBlock::from(&block)).boxed()
+    }
+
+    #[proptest]
+    fn compact_block_roundtrip(#[strategy(any_compact_block())] compact_block: CompactBlock<CurrentNetwork>) {
+        let mut bytes = BytesMut::default().writer();
+        compact_block.write_le(&mut bytes).unwrap();
+        let decoded = CompactBlock::read_le(&mut bytes.into_inner().reader()).unwrap();
+        assert_eq!(decoded, compact_block);
+    }
+
+    #[proptest]
+    fn compact_block_reconstruct(#[strategy(any_block())] block: snarkvm::prelude::block::Block<CurrentNetwork>) {
+        let compact_block = CompactBlock::from(&block);
+        // Ensure the block is reconstructed from its own transactions.
+        let get_transaction = |id: &_| block.transactions().get(id).map(|confirmed| confirmed.transaction().clone());
+        assert_eq!(compact_block.reconstruct(get_transaction).unwrap(), block);
+        // Ensure the block is not reconstructed if a transaction is missing.
+        if compact_block.omitted_transaction_ids().next().is_some() {
+            assert!(compact_block.reconstruct(|_| None).is_err());
+        }
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{error, FromBytes, ToBytes};
+
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, io};
+
+/// The set of optional protocol features supported by a node, exchanged during the handshake.
+///
+/// The capabilities are encoded with a version and a length prefix, so that nodes can skip the fields
+/// introduced by newer versions. This allows new features to be rolled out incrementally,
+/// as each side only enables a feature once it knows that the peer supports it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capabilities {
+    /// The bitset of supported features.
+    flags: u64,
+    /// The QUIC port of the node, if it supports the QUIC transport.
+    quic_port: Option<u16>,
+    /// The map of message IDs to the highest version of the message the node understands.
+    /// Messages that are absent from the map are understood in their initial version.
+    message_versions: BTreeMap<u16, u16>,
+}
+
+impl Capabilities {
+    /// The flag indicating support for compact block announcements.
+    pub const COMPACT_BLOCKS: u64 = 1 << 3;
+    /// The flag indicating support for an encrypted (QUIC) transport.
+    pub const ENCRYPTED_TRANSPORT: u64 = 1 << 0;
+    /// The flag indicating that the node consents to be introduced to peers for hole punching.
+    pub const HOLE_PUNCHING: u64 = 1 << 2;
+    /// The flag indicating that the node offers to relay introductions between its peers.
+    pub const RELAY: u64 = 1 << 1;
+    /// The version of the capabilities encoding.
+    pub const VERSION: u8 = 1;
+
+    /// Returns `true` if no capabilities are set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns `true` if all of the given flags are set.
+    pub const fn supports(&self, flags: u64) -> bool {
+        self.flags & flags == flags
+    }
+
+    /// Returns the bitset of supported features.
+    pub const fn flags(&self) -> u64 {
+        self.flags
+    }
+
+    /// Sets the given flags.
+    pub fn with_flags(mut self, flags: u64) -> Self {
+        self.flags |= flags;
+        self
+    }
+
+    /// Returns the QUIC port, if the node supports the encrypted transport.
+    pub fn quic_port(&self) -> Option<u16> {
+        self.quic_port.filter(|_| self.supports(Self::ENCRYPTED_TRANSPORT))
+    }
+
+    /// Sets the QUIC port, which implies support for an encrypted transport.
+    pub fn with_quic_port(mut self, quic_port: Option<u16>) -> Self {
+        if quic_port.is_some() {
+            self.flags |= Self::ENCRYPTED_TRANSPORT;
+        }
+        self.quic_port = quic_port;
+        self
+    }
+
+    /// Returns the highest version of the given message ID that the node understands.
+    pub fn message_version(&self, message_id: u16) -> u16 {
+        self.message_versions.get(&message_id).copied().unwrap_or_default()
+    }
+
+    /// Sets the highest version of the given message ID that the node understands.
+    pub fn with_message_version(mut self, message_id: u16, version: u16) -> Self {
+        self.message_versions.insert(message_id, version);
+        self
+    }
+
+    /// Returns the capabilities shared by this node and the given peer.
+    pub fn intersect(&self, other: &Self) -> Self {
+        let message_versions = self
+            .message_versions
+            .iter()
+            .filter_map(|(id, version)| other.message_versions.get(id).map(|other| (*id, *version.min(other))))
+            .collect();
+        Self { flags: self.flags & other.flags, quic_port: other.quic_port.and(self.quic_port), message_versions }
+    }
+}
+
+impl ToBytes for Capabilities {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        // Serialize the fields.
+        let mut bytes = Vec::new();
+        self.flags.write_le(&mut bytes)?;
+        match self.quic_port {
+            Some(quic_port) => {
+                1u8.write_le(&mut bytes)?;
+                quic_port.write_le(&mut bytes)?;
+            }
+            None => 0u8.write_le(&mut bytes)?,
+        }
+        u16::try_from(self.message_versions.len()).map_err(error)?.write_le(&mut bytes)?;
+        for (message_id, version) in &self.message_versions {
+            message_id.write_le(&mut bytes)?;
+            version.write_le(&mut bytes)?;
+        }
+
+        // Write the version, followed by the length-prefixed fields.
+        Self::VERSION.write_le(&mut writer)?;
+        u16::try_from(bytes.len()).map_err(error)?.write_le(&mut writer)?;
+        writer.write_all(&bytes)
+    }
+}
+
+impl FromBytes for Capabilities {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        // Read the version.
+        let version = u8::read_le(&mut reader)?;
+        if version == 0 {
+            return Err(error("Invalid capabilities version"));
+        }
+        // Read the length-prefixed fields.
+        let length = u16::read_le(&mut reader)?;
+        let mut bytes = vec![0u8; length as usize];
+        reader.read_exact(&mut bytes)?;
+        let mut bytes = &bytes[..];
+
+        // Deserialize the fields known to this version; the fields added by newer versions are skipped.
+        let flags = u64::read_le(&mut bytes)?;
+        let quic_port = match u8::read_le(&mut bytes)? {
+            0 => None,
+            1 => Some(u16::read_le(&mut bytes)?),
+            _ => return Err(error("Invalid QUIC port")),
+        };
+        let num_message_versions = u16::read_le(&mut bytes)?;
+        let mut message_versions = BTreeMap::new();
+        for _ in 0..num_message_versions {
+            let message_id = u16::read_le(&mut bytes)?;
+            let version = u16::read_le(&mut bytes)?;
+            message_versions.insert(message_id, version);
+        }
+
+        Ok(Self { flags, quic_port, message_versions })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_capabilities() -> Capabilities {
+        Capabilities::default()
+            .with_flags(Capabilities::RELAY)
+            .with_quic_port(Some(4130))
+            .with_message_version(1, 2)
+            .with_message_version(7, 1)
+    }
+
+    #[test]
+    fn test_capabilities_roundtrip() {
+        for capabilities in [Capabilities::default(), sample_capabilities()] {
+            let bytes = capabilities.to_bytes_le().unwrap();
+            assert_eq!(Capabilities::read_le(&bytes[..]).unwrap(), capabilities);
+        }
+    }
+
+    #[test]
+    fn test_capabilities_skip_unknown_fields() {
+        let capabilities = sample_capabilities();
+        let bytes = capabilities.to_bytes_le().unwrap();
+
+        // Simulate a newer version, with an additional field appended to the encoding.
+        let mut newer = vec![Capabilities::VERSION + 1];
+        newer.extend_from_slice(&(u16::from_le_bytes([bytes[1], bytes[2]]) + 4).to_le_bytes());
+        newer.extend_from_slice(&bytes[3..]);
+        newer.extend_from_slice(&[1, 2, 3, 4]);
+
+        // Ensure the known fields are read, and the unknown ones are skipped.
+        assert_eq!(Capabilities::read_le(&newer[..]).unwrap(), capabilities);
+    }
+
+    #[test]
+    fn test_capabilities_flags() {
+        let capabilities = sample_capabilities();
+        assert!(capabilities.supports(Capabilities::RELAY));
+        assert!(capabilities.supports(Capabilities::ENCRYPTED_TRANSPORT));
+        assert!(!capabilities.supports(Capabilities::COMPACT_BLOCKS));
+        assert!(!capabilities.supports(Capabilities::RELAY | Capabilities::COMPACT_BLOCKS));
+        assert_eq!(capabilities.quic_port(), Some(4130));
+        assert_eq!(capabilities.message_version(1), 2);
+        assert_eq!(capabilities.message_version(3), 0);
+
+        // Ensure the intersection only retains the shared capabilities.
+        let other = Capabilities::default().with_flags(Capabilities::COMPACT_BLOCKS).with_message_version(1, 1);
+        let shared = capabilities.intersect(&other);
+        assert_eq!(shared.flags(), 0);
+        assert_eq!(shared.quic_port(), None);
+        assert_eq!(shared.message_version(1), 1);
+        assert_eq!(shared.message_version(7), 0);
+    }
+}
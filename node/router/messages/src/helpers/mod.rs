@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod capabilities;
+pub use capabilities::Capabilities;
+
 mod codec;
 pub use codec::MessageCodec;
 
@@ -26,12 +26,18 @@ pub use block_request::BlockRequest;
 mod block_response;
 pub use block_response::BlockResponse;
 
+mod block_transactions;
+pub use block_transactions::{BlockTransactionsRequest, BlockTransactionsResponse};
+
 mod challenge_request;
 pub use challenge_request::ChallengeRequest;
 
 mod challenge_response;
 pub use challenge_response::ChallengeResponse;
 
+mod compact_block;
+pub use compact_block::{CompactBlock, CompactTransaction};
+
 mod disconnect;
 pub use disconnect::Disconnect;
 
@@ -53,6 +59,12 @@ pub use puzzle_request::PuzzleRequest;
 mod puzzle_response;
 pub use puzzle_response::PuzzleResponse;
 
+mod relay_introduction;
+pub use relay_introduction::RelayIntroduction;
+
+mod relay_request;
+pub use relay_request::RelayRequest;
+
 mod unconfirmed_solution;
 pub use unconfirmed_solution::UnconfirmedSolution;
 
@@ -101,6 +113,11 @@ pub enum Message<N: Network> {
     PuzzleResponse(PuzzleResponse<N>),
     UnconfirmedSolution(UnconfirmedSolution<N>),
     UnconfirmedTransaction(UnconfirmedTransaction<N>),
+    RelayRequest(RelayRequest),
+    RelayIntroduction(RelayIntroduction),
+    CompactBlock(CompactBlock<N>),
+    BlockTransactionsRequest(BlockTransactionsRequest<N>),
+    BlockTransactionsResponse(BlockTransactionsResponse<N>),
 }
 
 impl<N: Network> From<DisconnectReason> for Message<N> {
@@ -110,8 +127,14 @@ impl<N: Network> From<DisconnectReason> for Message<N> {
 }
 
 impl<N: Network> Message<N> {
+    /// The first version of the network protocol that exchanges the capabilities during the handshake,
+    /// and understands the relay and compact block messages.
+    pub const CAPABILITIES_VERSION: u32 = 16;
+    /// The minimum version of the network protocol that is accepted from the peers.
+    /// Note: The peers below `CAPABILITIES_VERSION` are neither sent the capabilities, nor the messages they gate.
+    pub const MIN_VERSION: u32 = 15;
     /// The version of the network protocol; it can be incremented in order to force users to update.
-    pub const VERSION: u32 = 15;
+    pub const VERSION: u32 = 16;
 
     /// Returns the message name.
     #[inline]
@@ -130,6 +153,11 @@ impl<N: Network> Message<N> {
             Self::PuzzleResponse(message) => message.name(),
             Self::UnconfirmedSolution(message) => message.name(),
             Self::UnconfirmedTransaction(message) => message.name(),
+            Self::RelayRequest(message) => message.name(),
+            Self::RelayIntroduction(message) => message.name(),
+            Self::CompactBlock(message) => message.name(),
+            Self::BlockTransactionsRequest(message) => message.name(),
+            Self::BlockTransactionsResponse(message) => message.name(),
         }
     }
 
@@ -150,6 +178,11 @@ impl<N: Network> Message<N> {
             Self::PuzzleResponse(..) => 10,
             Self::UnconfirmedSolution(..) => 11,
             Self::UnconfirmedTransaction(..) => 12,
+            Self::RelayRequest(..) => 13,
+            Self::RelayIntroduction(..) => 14,
+            Self::CompactBlock(..) => 15,
+            Self::BlockTransactionsRequest(..) => 16,
+            Self::BlockTransactionsResponse(..) => 17,
         }
     }
 
@@ -194,6 +227,11 @@ impl<N: Network> ToBytes for Message<N> {
             Self::PuzzleResponse(message) => message.write_le(writer),
             Self::UnconfirmedSolution(message) => message.write_le(writer),
             Self::UnconfirmedTransaction(message) => message.write_le(writer),
+            Self::RelayRequest(message) => message.write_le(writer),
+            Self::RelayIntroduction(message) => message.write_le(writer),
+            Self::CompactBlock(message) => message.write_le(writer),
+            Self::BlockTransactionsRequest(message) => message.write_le(writer),
+            Self::BlockTransactionsResponse(message) => message.write_le(writer),
         }
     }
 }
@@ -220,7 +258,12 @@ impl<N: Network> FromBytes for Message<N> {
             10 => Self::PuzzleResponse(PuzzleResponse::read_le(&mut reader)?),
             11 => Self::UnconfirmedSolution(UnconfirmedSolution::read_le(&mut reader)?),
             12 => Self::UnconfirmedTransaction(UnconfirmedTransaction::read_le(&mut reader)?),
-            13.. => return Err(error("Unknown message ID {id}")),
+            13 => Self::RelayRequest(RelayRequest::read_le(&mut reader)?),
+            14 => Self::RelayIntroduction(RelayIntroduction::read_le(&mut reader)?),
+            15 => Self::CompactBlock(CompactBlock::read_le(&mut reader)?),
+            16 => Self::BlockTransactionsRequest(BlockTransactionsRequest::read_le(&mut reader)?),
+            17 => Self::BlockTransactionsResponse(BlockTransactionsResponse::read_le(&mut reader)?),
+            18.. => return Err(error("Unknown message ID {id}")),
         };
 
         // Ensure that there are no "dangling" bytes.
@@ -0,0 +1,77 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use snarkvm::prelude::{FromBytes, ToBytes};
+
+use std::borrow::Cow;
+
+/// An introduction by a relay, instructing the node to punch a connection through to the given peer,
+/// while the peer simultaneously does the same.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RelayIntroduction {
+    /// The public address of the peer, as observed by the relay.
+    pub peer_addr: SocketAddr,
+    /// Whether the node acts as the initiator of the handshake, which the peer acts as the responder of.
+    pub is_initiator: bool,
+}
+
+impl MessageTrait for RelayIntroduction {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        "RelayIntroduction".into()
+    }
+}
+
+impl ToBytes for RelayIntroduction {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        self.peer_addr.write_le(&mut writer)?;
+        self.is_initiator.write_le(&mut writer)
+    }
+}
+
+impl FromBytes for RelayIntroduction {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let peer_addr = SocketAddr::read_le(&mut reader)?;
+        let is_initiator = bool::read_le(&mut reader)?;
+
+        Ok(Self { peer_addr, is_initiator })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::{peer_response::prop_tests::any_valid_socket_addr, RelayIntroduction};
+    use snarkvm::utilities::{FromBytes, ToBytes};
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use proptest::prelude::{any, BoxedStrategy, Strategy};
+    use test_strategy::proptest;
+
+    pub fn any_relay_introduction() -> BoxedStrategy<RelayIntroduction> {
+        (any_valid_socket_addr(), any::<bool>())
+            .prop_map(|(peer_addr, is_initiator)| RelayIntroduction { peer_addr, is_initiator })
+            .boxed()
+    }
+
+    #[proptest]
+    fn relay_introduction_roundtrip(#[strategy(any_relay_introduction())] relay_introduction: RelayIntroduction) {
+        let mut bytes = BytesMut::default().writer();
+        relay_introduction.write_le(&mut bytes).unwrap();
+        let decoded = RelayIntroduction::read_le(&mut bytes.into_inner().reader()).unwrap();
+        assert_eq!(decoded, relay_introduction);
+    }
+}
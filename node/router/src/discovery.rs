@@ -0,0 +1,89 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::Router;
+use snarkvm::prelude::Network;
+
+use anyhow::{ensure, Result};
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use std::net::SocketAddr;
+
+impl<N: Network> Router<N> {
+    /// The mDNS service type advertised by the nodes.
+    const LOCAL_DISCOVERY_SERVICE_TYPE: &'static str = "_snarkos._tcp.local.";
+
+    /// Enables the discovery of peers on the local network via mDNS.
+    /// This node is advertised on the local network, and the nodes it discovers are added to the candidate peers.
+    pub fn enable_local_discovery(&self) -> Result<()> {
+        // Ensure the local discovery is only enabled once.
+        let mut local_discovery = self.local_discovery.lock();
+        ensure!(local_discovery.is_none(), "Local peer discovery is already enabled");
+
+        // Initialize the mDNS daemon.
+        let daemon = ServiceDaemon::new()?;
+
+        // Advertise this node, along with its network ID.
+        let port = self.local_ip().port();
+        let instance_name = format!("{}-{port}", &self.address().to_string()[..20]);
+        let host_name = format!("{instance_name}.local.");
+        let network = N::ID.to_string();
+        let properties = [("network", network.as_str())];
+        let service = ServiceInfo::new(
+            Self::LOCAL_DISCOVERY_SERVICE_TYPE,
+            &instance_name,
+            &host_name,
+            "",
+            port,
+            &properties[..],
+        )?
+        .enable_addr_auto();
+        let fullname = service.get_fullname().to_string();
+        daemon.register(service)?;
+
+        // Browse for the other nodes on the local network.
+        let receiver = daemon.browse(Self::LOCAL_DISCOVERY_SERVICE_TYPE)?;
+        let router = self.clone();
+        self.spawn(async move {
+            while let Ok(event) = receiver.recv_async().await {
+                let ServiceEvent::ServiceResolved(info) = event else { continue };
+                // Skip this node, and the nodes on other networks.
+                if info.get_fullname() == fullname || info.get_property_val_str("network") != Some(network.as_str()) {
+                    continue;
+                }
+                // Add the discovered node to the candidate peers.
+                let peers = info
+                    .get_addresses()
+                    .iter()
+                    .map(|ip| SocketAddr::new((*ip).into(), info.get_port()))
+                    .filter(|peer_ip| !router.is_connected(peer_ip))
+                    .collect::<Vec<_>>();
+                if !peers.is_empty() {
+                    debug!("Discovered {peers:?} on the local network");
+                    router.insert_candidate_peers(&peers);
+                }
+            }
+        });
+        *local_discovery = Some(daemon);
+
+        info!("Enabled local peer discovery");
+        Ok(())
+    }
+
+    /// Disables the discovery of peers on the local network, if it is enabled.
+    pub(crate) fn disable_local_discovery(&self) {
+        if let Some(daemon) = self.local_discovery.lock().take() {
+            let _ = daemon.shutdown();
+        }
+    }
+}
@@ -18,6 +18,8 @@ use crate::{
     Peer,
     Router,
 };
+#[cfg(feature = "quic")]
+use snarkos_node_tcp::QuicUpgrade;
 use snarkos_node_tcp::{ConnectionSide, Tcp, P2P};
 use snarkvm::{
     ledger::narwhal::Data,
@@ -82,6 +84,7 @@ async fn send<N: Network>(
 
 impl<N: Network> Router<N> {
     /// Executes the handshake protocol.
+    #[tracing::instrument(level = "debug", skip_all, fields(peer = %peer_addr))]
     pub async fn handshake<'a>(
         &'a self,
         peer_addr: SocketAddr,
@@ -131,6 +134,8 @@ impl<N: Network> Router<N> {
     ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, MessageCodec<N>>)> {
         // This value is immediately guaranteed to be present, so it can be unwrapped.
         let peer_ip = peer_ip.unwrap();
+        // Determine the listening port to advertise to the peer.
+        let listener_port = self.advertised_port(stream.local_addr()?);
         // Construct the stream.
         let mut framed = Framed::new(stream, MessageCodec::<N>::handshake());
 
@@ -142,7 +147,8 @@ impl<N: Network> Router<N> {
         // Sample a random nonce.
         let our_nonce = rng.gen();
         // Send a challenge request to the peer.
-        let our_request = ChallengeRequest::new(self.local_ip().port(), self.node_type, self.address(), our_nonce);
+        // Note: The capabilities are sent in the challenge response instead, once the version of the peer is known.
+        let our_request = ChallengeRequest::new(listener_port, self.node_type, self.address(), our_nonce);
         send(&mut framed, peer_addr, Message::ChallengeRequest(our_request)).await?;
 
         /* Step 2: Receive the peer's challenge response followed by the challenge request. */
@@ -181,17 +187,26 @@ impl<N: Network> Router<N> {
         let Ok(our_signature) = self.account.sign_bytes(&data, rng) else {
             return Err(error(format!("Failed to sign the challenge request nonce from '{peer_addr}'")));
         };
-        // Send the challenge response.
+        // Send the challenge response, along with the capabilities.
         let our_response = ChallengeResponse {
             genesis_header,
             restrictions_id,
             signature: Data::Object(our_signature),
             nonce: response_nonce,
+            capabilities: self.capabilities_for(peer_request.version),
         };
         send(&mut framed, peer_addr, Message::ChallengeResponse(our_response)).await?;
 
         // Add the peer to the router.
-        self.insert_connected_peer(Peer::new(peer_ip, &peer_request), peer_addr);
+        let peer_capabilities = peer_request.capabilities.clone();
+        self.insert_connected_peer(Peer::new(peer_ip, &peer_request, peer_capabilities), peer_addr);
+
+        // If both sides support QUIC, connect to the peer's QUIC endpoint once the handshake completes.
+        #[cfg(feature = "quic")]
+        if let (Some(_), Some(peer_quic_port)) = (self.quic_port(), peer_request.capabilities.quic_port()) {
+            let addr = SocketAddr::new(peer_addr.ip(), peer_quic_port);
+            self.tcp.request_quic_upgrade(peer_addr, QuicUpgrade::Connect { addr, token: our_nonce });
+        }
 
         Ok((peer_ip, framed))
     }
@@ -205,6 +220,8 @@ impl<N: Network> Router<N> {
         genesis_header: Header<N>,
         restrictions_id: Field<N>,
     ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, MessageCodec<N>>)> {
+        // Determine the listening port to advertise to the peer.
+        let listener_port = self.advertised_port(stream.local_addr()?);
         // Construct the stream.
         let mut framed = Framed::new(stream, MessageCodec::<N>::handshake());
 
@@ -238,24 +255,29 @@ impl<N: Network> Router<N> {
             return Err(error(format!("Failed to sign the challenge request nonce from '{peer_addr}'")));
         };
         // Send the challenge response.
+        // Note: The capabilities are sent in the challenge request below.
         let our_response = ChallengeResponse {
             genesis_header,
             restrictions_id,
             signature: Data::Object(our_signature),
             nonce: response_nonce,
+            capabilities: Default::default(),
         };
         send(&mut framed, peer_addr, Message::ChallengeResponse(our_response)).await?;
 
         // Sample a random nonce.
         let our_nonce = rng.gen();
         // Send the challenge request.
-        let our_request = ChallengeRequest::new(self.local_ip().port(), self.node_type, self.address(), our_nonce);
+        let our_request = ChallengeRequest::new(listener_port, self.node_type, self.address(), our_nonce)
+            .with_capabilities(self.capabilities_for(peer_request.version));
         send(&mut framed, peer_addr, Message::ChallengeRequest(our_request)).await?;
 
         /* Step 3: Receive the challenge response. */
 
         // Listen for the challenge response message.
         let peer_response = expect_message!(Message::ChallengeResponse, framed, peer_addr);
+        // Retrieve the capabilities of the peer, which the initiator sends in its challenge response.
+        let peer_capabilities = peer_response.capabilities.clone();
         // Verify the challenge response. If a disconnect reason was returned, send the disconnect message and abort.
         if let Some(reason) = self
             .verify_challenge_response(
@@ -273,13 +295,26 @@ impl<N: Network> Router<N> {
             return Err(error(format!("Dropped '{peer_addr}' for reason: {reason:?}")));
         }
         // Add the peer to the router.
-        self.insert_connected_peer(Peer::new(peer_ip, &peer_request), peer_addr);
+        #[cfg(feature = "quic")]
+        let peer_quic_port = peer_capabilities.quic_port();
+        self.insert_connected_peer(Peer::new(peer_ip, &peer_request, peer_capabilities), peer_addr);
+
+        // If both sides support QUIC, await the peer's QUIC stream once the handshake completes.
+        #[cfg(feature = "quic")]
+        if let (Some(_), Some(_)) = (self.quic_port(), peer_quic_port) {
+            self.tcp
+                .request_quic_upgrade(peer_addr, QuicUpgrade::Accept { addr: peer_addr, token: peer_request.nonce });
+        }
 
         Ok((peer_ip, framed))
     }
 
     /// Ensure the peer is allowed to connect.
     fn ensure_peer_is_allowed(&self, peer_ip: SocketAddr) -> Result<()> {
+        // Ensure the node is not shutting down.
+        if self.is_shutting_down() {
+            bail!("Dropping connection request from '{peer_ip}' (shutting down)")
+        }
         // Ensure the peer IP is not this node.
         if self.is_local_ip(&peer_ip) {
             bail!("Dropping connection request from '{peer_ip}' (attempted to self-connect)")
@@ -292,8 +327,8 @@ impl<N: Network> Router<N> {
         if self.is_connected(&peer_ip) {
             bail!("Dropping connection request from '{peer_ip}' (already connected)")
         }
-        // Only allow trusted peers to connect if allow_external_peers is set
-        if !self.allow_external_peers() && !self.is_trusted(&peer_ip) {
+        // Only allow trusted and pinned peers to connect if allow_external_peers is set
+        if !self.allow_external_peers() && !self.is_trusted(&peer_ip) && !self.is_pinned(&peer_ip) {
             bail!("Dropping connection request from '{peer_ip}' (untrusted)")
         }
         // Ensure the peer is not restricted.
@@ -321,10 +356,11 @@ impl<N: Network> Router<N> {
         message: &ChallengeRequest<N>,
     ) -> Option<DisconnectReason> {
         // Retrieve the components of the challenge request.
-        let &ChallengeRequest { version, listener_port: _, node_type: _, address: _, nonce: _ } = message;
+        let &ChallengeRequest { version, listener_port: _, node_type: _, address: _, nonce: _, capabilities: _ } =
+            message;
 
         // Ensure the message protocol version is not outdated.
-        if version < Message::<N>::VERSION {
+        if version < Message::<N>::MIN_VERSION {
             warn!("Dropping '{peer_addr}' on version {version} (outdated)");
             return Some(DisconnectReason::OutdatedClientVersion);
         }
@@ -344,7 +380,7 @@ impl<N: Network> Router<N> {
         expected_nonce: u64,
     ) -> Option<DisconnectReason> {
         // Retrieve the components of the challenge response.
-        let ChallengeResponse { genesis_header, restrictions_id, signature, nonce } = response;
+        let ChallengeResponse { genesis_header, restrictions_id, signature, nonce, capabilities: _ } = response;
 
         // Verify the challenge response, by checking that the block header matches.
         if genesis_header != expected_genesis_header {
@@ -13,14 +13,22 @@
 // limitations under the License.
 
 use crate::{
-    messages::{DisconnectReason, Message, PeerRequest},
+    messages::{Capabilities, DisconnectReason, Message, PeerRequest, RelayRequest},
     Outbound,
     Router,
 };
 use snarkvm::prelude::Network;
 
 use colored::Colorize;
-use rand::{prelude::IteratorRandom, rngs::OsRng};
+use rand::{
+    prelude::{IteratorRandom, SliceRandom},
+    rngs::OsRng,
+    Rng,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    net::{IpAddr, SocketAddr},
+};
 
 /// A helper function to compute the maximum of two numbers.
 /// See Rust issue 92391: https://github.com/rust-lang/rust/issues/92391.
@@ -31,9 +39,35 @@ pub const fn max(a: usize, b: usize) -> usize {
     }
 }
 
+/// Returns the subnet of the given IP, as its /16 prefix for IPv4 or /32 prefix for IPv6.
+fn subnet_of(ip: IpAddr) -> Vec<u8> {
+    match ip {
+        IpAddr::V4(ip) => ip.octets()[..2].to_vec(),
+        IpAddr::V6(ip) => ip.octets()[..4].to_vec(),
+    }
+}
+
+/// Returns up to `num_peers` peer IPs, each from a distinct subnet, sampled at random.
+pub fn diverse_peer_ips<R: Rng>(
+    peer_ips: impl IntoIterator<Item = SocketAddr>,
+    num_peers: usize,
+    rng: &mut R,
+) -> HashSet<SocketAddr> {
+    // Group the peer IPs by subnet.
+    let mut subnets = HashMap::<Vec<u8>, Vec<SocketAddr>>::new();
+    for peer_ip in peer_ips {
+        subnets.entry(subnet_of(peer_ip.ip())).or_default().push(peer_ip);
+    }
+    // Select one random peer from each of up to `num_peers` random subnets.
+    subnets
+        .into_values()
+        .choose_multiple(rng, num_peers)
+        .into_iter()
+        .filter_map(|ips| ips.choose(rng).copied())
+        .collect()
+}
+
 pub trait Heartbeat<N: Network>: Outbound<N> {
-    /// The duration in seconds to sleep in between heartbeat executions.
-    const HEARTBEAT_IN_SECS: u64 = 25; // 25 seconds
     /// The minimum number of peers required to maintain connections with.
     const MINIMUM_NUMBER_OF_PEERS: usize = 3;
     /// The median number of peers to maintain connections with.
@@ -42,6 +76,10 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
     const MAXIMUM_NUMBER_OF_PEERS: usize = 21;
     /// The maximum number of provers to maintain connections with.
     const MAXIMUM_NUMBER_OF_PROVERS: usize = Self::MAXIMUM_NUMBER_OF_PEERS / 4;
+    /// The number of peers from distinct subnets to retain for resilience, regardless of their latency.
+    const NUMBER_OF_DIVERSE_PEERS: usize = 3;
+    /// The maximum number of unreachable peers to request an introduction to, per heartbeat.
+    const NUMBER_OF_RELAY_REQUESTS: usize = 2;
 
     /// Handles the heartbeat request.
     fn heartbeat(&self) {
@@ -58,8 +96,28 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         self.handle_bootstrap_peers();
         // Keep the trusted peers connected.
         self.handle_trusted_peers();
+        // Request introductions to the unreachable peers.
+        self.handle_unreachable_peers();
         // Keep the puzzle request up to date.
         self.handle_puzzle_request();
+        // Persist the known peers, if they changed.
+        self.router().update_peer_cache();
+        // Update the outbound queue metrics.
+        #[cfg(feature = "metrics")]
+        self.update_outbound_queue_metrics();
+    }
+
+    /// Updates the metrics for the outbound message queues.
+    #[cfg(feature = "metrics")]
+    fn update_outbound_queue_metrics(&self) {
+        use snarkos_node_tcp::protocols::MessagePriority;
+
+        metrics::gauge(metrics::router::OUTBOUND_QUEUE_HIGH, self.outbound_queue_depth(MessagePriority::High) as f64);
+        metrics::gauge(
+            metrics::router::OUTBOUND_QUEUE_NORMAL,
+            self.outbound_queue_depth(MessagePriority::Normal) as f64,
+        );
+        metrics::gauge(metrics::router::OUTBOUND_QUEUE_LOW, self.outbound_queue_depth(MessagePriority::Low) as f64);
     }
 
     /// TODO (howardwu): Consider checking minimum number of validators, to exclude clients and provers.
@@ -91,7 +149,7 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         for peer in self.router().get_connected_peers() {
             // Disconnect if the peer has not communicated back within the predefined time.
             let elapsed = peer.last_seen().elapsed().as_secs();
-            if elapsed > Router::<N>::RADIO_SILENCE_IN_SECS {
+            if elapsed > self.router().keep_alive().idle_timeout().as_secs() {
                 warn!("Peer {} has not communicated in {elapsed} seconds", peer.ip());
                 // Disconnect from this peer.
                 self.router().disconnect(peer.ip());
@@ -117,12 +175,13 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         // Retrieve the bootstrap peers.
         let bootstrap = self.router().bootstrap_peers();
 
-        // Find the oldest connected peer, that is neither trusted nor a bootstrap peer.
+        // Find the oldest connected peer, that is neither trusted, pinned, nor a bootstrap peer.
         let oldest_peer = self
             .router()
             .get_connected_peers()
             .iter()
             .filter(|peer| !trusted.contains(&peer.ip()) && !bootstrap.contains(&peer.ip()))
+            .filter(|peer| !self.router().is_pinned(&peer.ip()))
             .filter(|peer| !self.router().cache.contains_inbound_block_request(&peer.ip())) // Skip if the peer is syncing.
             .filter(|peer| self.is_block_synced() || self.router().cache.num_outbound_block_requests(&peer.ip()) == 0) // Skip if you are syncing from this peer.
             .min_by_key(|peer| peer.last_seen())
@@ -173,24 +232,27 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
                 .connected_provers()
                 .into_iter()
                 .filter(|peer_ip| !trusted.contains(peer_ip) && !bootstrap.contains(peer_ip))
+                .filter(|peer_ip| !self.router().is_pinned(peer_ip))
                 .choose_multiple(rng, num_surplus_provers);
 
             // TODO (howardwu): As a validator, prioritize disconnecting from clients.
-            //  Remove RNG, pick the `n` oldest nodes.
-            // Determine the clients and validators to disconnect from.
-            let peer_ips_to_disconnect = self
+            // Determine the clients and validators eligible for disconnection.
+            let mut candidates = self
                 .router()
                 .get_connected_peers()
                 .into_iter()
-                .filter_map(|peer| {
-                    let peer_ip = peer.ip();
-                    if !peer.is_prover() && !trusted.contains(&peer_ip) && !bootstrap.contains(&peer_ip) {
-                        Some(peer_ip)
-                    } else {
-                        None
-                    }
-                })
-                .choose_multiple(rng, num_surplus_clients_validators);
+                .filter(|peer| !peer.is_prover() && !trusted.contains(&peer.ip()) && !bootstrap.contains(&peer.ip()))
+                .filter(|peer| !self.router().is_pinned(&peer.ip()))
+                .collect::<Vec<_>>();
+            // Retain a handful of peers from distinct subnets, to remain resilient to regional outages.
+            let diverse = diverse_peer_ips(candidates.iter().map(|peer| peer.ip()), Self::NUMBER_OF_DIVERSE_PEERS, rng);
+            candidates.retain(|peer| !diverse.contains(&peer.ip()));
+            // Prefer disconnecting from the peers with the highest round-trip time.
+            // Note: Peers without a measured round-trip time are considered the fastest, as they are new.
+            candidates.sort_unstable_by_key(|peer| std::cmp::Reverse(peer.rtt().unwrap_or_default()));
+            // Determine the clients and validators to disconnect from.
+            let peer_ips_to_disconnect =
+                candidates.into_iter().take(num_surplus_clients_validators).map(|peer| peer.ip()).collect::<Vec<_>>();
 
             // Proceed to send disconnect requests to these peers.
             for peer_ip in peer_ips_to_disconnect.into_iter().chain(prover_ips_to_disconnect) {
@@ -279,6 +341,33 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         }
     }
 
+    /// This function asks a connected relay to introduce this node to the peers it was unable to reach.
+    fn handle_unreachable_peers(&self) {
+        // Skip if the node does not consent to hole punching.
+        if !self.router().relay_mode().is_hole_punching() {
+            return;
+        }
+        // Select a connected relay at random.
+        let relay = self
+            .router()
+            .get_connected_peers()
+            .into_iter()
+            .filter(|peer| peer.capabilities().supports(Capabilities::RELAY))
+            .map(|peer| peer.ip())
+            .choose(&mut OsRng);
+        // Request the relay to introduce this node to the unreachable peers.
+        if let Some(relay_ip) = relay {
+            for peer_ip in self.router().take_unreachable_peers(Self::NUMBER_OF_RELAY_REQUESTS) {
+                // Skip the peers that were connected to in the meantime.
+                if peer_ip == relay_ip || self.router().is_connected(&peer_ip) {
+                    continue;
+                }
+                debug!("Requesting an introduction to '{peer_ip}' from '{relay_ip}'");
+                self.send(relay_ip, Message::RelayRequest(RelayRequest { target: peer_ip }));
+            }
+        }
+    }
+
     /// This function updates the puzzle if network has updated.
     fn handle_puzzle_request(&self) {
         // No-op
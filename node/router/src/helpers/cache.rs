@@ -42,6 +42,12 @@ pub struct Cache<N: Network> {
     seen_inbound_puzzle_requests: RwLock<HashMap<SocketAddr, VecDeque<OffsetDateTime>>>,
     /// The map of peer IPs to their recent timestamps.
     seen_inbound_block_requests: RwLock<HashMap<SocketAddr, VecDeque<OffsetDateTime>>>,
+    /// The map of peer IPs to their recent compact block timestamps.
+    seen_inbound_compact_blocks: RwLock<HashMap<SocketAddr, VecDeque<OffsetDateTime>>>,
+    /// The map of peer IPs to their recent relay request timestamps.
+    seen_inbound_relay_requests: RwLock<HashMap<SocketAddr, VecDeque<OffsetDateTime>>>,
+    /// The map of peer IPs to their recent relay introduction timestamps.
+    seen_inbound_relay_introductions: RwLock<HashMap<SocketAddr, VecDeque<OffsetDateTime>>>,
     /// The map of solution IDs to their last seen timestamp.
     seen_inbound_solutions: RwLock<LinkedHashMap<SolutionKey<N>, OffsetDateTime>>,
     /// The map of transaction IDs to their last seen timestamp.
@@ -56,6 +62,8 @@ pub struct Cache<N: Network> {
     seen_outbound_transactions: RwLock<LinkedHashMap<TransactionKey<N>, OffsetDateTime>>,
     /// The map of peer IPs to the number of sent peer requests.
     seen_outbound_peer_requests: RwLock<HashMap<SocketAddr, u32>>,
+    /// The map of relay IPs to the number of sent relay requests.
+    seen_outbound_relay_requests: RwLock<HashMap<SocketAddr, u32>>,
 }
 
 impl<N: Network> Default for Cache<N> {
@@ -67,7 +75,9 @@ impl<N: Network> Default for Cache<N> {
 
 impl<N: Network> Cache<N> {
     const INBOUND_BLOCK_REQUEST_INTERVAL: i64 = 60;
+    const INBOUND_COMPACT_BLOCK_INTERVAL: i64 = 60;
     const INBOUND_PUZZLE_REQUEST_INTERVAL: i64 = 60;
+    const INBOUND_RELAY_INTERVAL: i64 = 60;
 
     /// Initializes a new instance of the cache.
     pub fn new() -> Self {
@@ -76,6 +86,9 @@ impl<N: Network> Cache<N> {
             seen_inbound_messages: Default::default(),
             seen_inbound_puzzle_requests: Default::default(),
             seen_inbound_block_requests: Default::default(),
+            seen_inbound_compact_blocks: Default::default(),
+            seen_inbound_relay_requests: Default::default(),
+            seen_inbound_relay_introductions: Default::default(),
             seen_inbound_solutions: RwLock::new(LinkedHashMap::with_capacity(MAX_CACHE_SIZE)),
             seen_inbound_transactions: RwLock::new(LinkedHashMap::with_capacity(MAX_CACHE_SIZE)),
             seen_outbound_block_requests: Default::default(),
@@ -83,6 +96,7 @@ impl<N: Network> Cache<N> {
             seen_outbound_solutions: RwLock::new(LinkedHashMap::with_capacity(MAX_CACHE_SIZE)),
             seen_outbound_transactions: RwLock::new(LinkedHashMap::with_capacity(MAX_CACHE_SIZE)),
             seen_outbound_peer_requests: Default::default(),
+            seen_outbound_relay_requests: Default::default(),
         }
     }
 }
@@ -108,6 +122,21 @@ impl<N: Network> Cache<N> {
         Self::retain_and_insert(&self.seen_inbound_block_requests, peer_ip, Self::INBOUND_BLOCK_REQUEST_INTERVAL)
     }
 
+    /// Inserts a new timestamp for the given peer IP, returning the number of recent compact blocks.
+    pub fn insert_inbound_compact_block(&self, peer_ip: SocketAddr) -> usize {
+        Self::retain_and_insert(&self.seen_inbound_compact_blocks, peer_ip, Self::INBOUND_COMPACT_BLOCK_INTERVAL)
+    }
+
+    /// Inserts a new timestamp for the given peer IP, returning the number of recent relay requests.
+    pub fn insert_inbound_relay_request(&self, peer_ip: SocketAddr) -> usize {
+        Self::retain_and_insert(&self.seen_inbound_relay_requests, peer_ip, Self::INBOUND_RELAY_INTERVAL)
+    }
+
+    /// Inserts a new timestamp for the given relay IP, returning the number of recent relay introductions.
+    pub fn insert_inbound_relay_introduction(&self, peer_ip: SocketAddr) -> usize {
+        Self::retain_and_insert(&self.seen_inbound_relay_introductions, peer_ip, Self::INBOUND_RELAY_INTERVAL)
+    }
+
     /// Inserts a solution ID into the cache, returning the previously seen timestamp if it existed.
     pub fn insert_inbound_solution(&self, peer_ip: SocketAddr, solution_id: SolutionID<N>) -> Option<OffsetDateTime> {
         Self::refresh_and_insert(&self.seen_inbound_solutions, (peer_ip, solution_id))
@@ -196,6 +225,21 @@ impl<N: Network> Cache<N> {
     pub fn decrement_outbound_peer_requests(&self, peer_ip: SocketAddr) -> u32 {
         Self::decrement_counter(&self.seen_outbound_peer_requests, peer_ip)
     }
+
+    /// Returns `true` if the cache contains a relay request sent to the given relay.
+    pub fn contains_outbound_relay_request(&self, peer_ip: &SocketAddr) -> bool {
+        self.seen_outbound_relay_requests.read().get(peer_ip).map(|r| *r > 0).unwrap_or(false)
+    }
+
+    /// Increment the relay IP's number of relay requests, returning the updated number of relay requests.
+    pub fn increment_outbound_relay_requests(&self, peer_ip: SocketAddr) -> u32 {
+        Self::increment_counter(&self.seen_outbound_relay_requests, peer_ip)
+    }
+
+    /// Decrement the relay IP's number of relay requests, returning the updated number of relay requests.
+    pub fn decrement_outbound_relay_requests(&self, peer_ip: SocketAddr) -> u32 {
+        Self::decrement_counter(&self.seen_outbound_relay_requests, peer_ip)
+    }
 }
 
 impl<N: Network> Cache<N> {
@@ -438,4 +482,34 @@ mod tests {
         // Check the cache is empty.
         assert!(!cache.contains_outbound_peer_request(peer_ip));
     }
+
+    #[test]
+    fn test_relay_requests() {
+        let cache = Cache::<CurrentNetwork>::default();
+        let peer_ip = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 1234);
+
+        // Check the relay requests and introductions are counted within the interval.
+        for i in 1..=3 {
+            assert_eq!(cache.insert_inbound_relay_request(peer_ip), i);
+            assert_eq!(cache.insert_inbound_relay_introduction(peer_ip), i);
+        }
+
+        // Check the outbound relay requests are counted until the introduction arrives.
+        assert!(!cache.contains_outbound_relay_request(&peer_ip));
+        assert_eq!(cache.increment_outbound_relay_requests(peer_ip), 1);
+        assert!(cache.contains_outbound_relay_request(&peer_ip));
+        assert_eq!(cache.decrement_outbound_relay_requests(peer_ip), 0);
+        assert!(!cache.contains_outbound_relay_request(&peer_ip));
+    }
+
+    #[test]
+    fn test_inbound_compact_blocks() {
+        let cache = Cache::<CurrentNetwork>::default();
+        let peer_ip = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 1234);
+
+        // Check the compact blocks are counted within the interval.
+        for i in 1..=3 {
+            assert_eq!(cache.insert_inbound_compact_block(peer_ip), i);
+        }
+    }
 }
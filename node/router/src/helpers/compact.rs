@@ -0,0 +1,135 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::messages::CompactBlock;
+use snarkvm::prelude::{
+    block::{Block, Transaction},
+    Network,
+};
+
+use anyhow::Result;
+use linked_hash_map::LinkedHashMap;
+use parking_lot::RwLock;
+use std::{collections::HashMap, net::SocketAddr};
+
+/// The maximum number of recently seen transactions kept to reconstruct compact blocks.
+const MAX_POOL_TRANSACTIONS: usize = 1 << 12;
+/// The maximum number of compact blocks awaiting their missing transactions.
+const MAX_PENDING_BLOCKS: usize = 8;
+
+/// The pool of recently seen transactions, from which announced compact blocks are reconstructed,
+/// along with the compact blocks that await their missing transactions from the announcing peer.
+#[derive(Debug)]
+pub struct CompactBlockPool<N: Network> {
+    /// The map of transaction IDs to the recently seen unconfirmed transactions, in insertion order.
+    transactions: RwLock<LinkedHashMap<N::TransactionID, Transaction<N>>>,
+    /// The map of block hashes to the compact blocks awaiting their missing transactions, with the announcing peer.
+    pending: RwLock<LinkedHashMap<N::BlockHash, (SocketAddr, CompactBlock<N>)>>,
+}
+
+impl<N: Network> Default for CompactBlockPool<N> {
+    /// Initializes an empty pool.
+    fn default() -> Self {
+        Self { transactions: Default::default(), pending: Default::default() }
+    }
+}
+
+impl<N: Network> CompactBlockPool<N> {
+    /// Inserts the given unconfirmed transaction, evicting the oldest one if the pool is full.
+    pub fn insert_transaction(&self, transaction: Transaction<N>) {
+        let mut transactions = self.transactions.write();
+        transactions.insert(transaction.id(), transaction);
+        while transactions.len() > MAX_POOL_TRANSACTIONS {
+            transactions.pop_front();
+        }
+    }
+
+    /// Returns `true` if the pool contains the given transaction.
+    pub fn contains_transaction(&self, id: &N::TransactionID) -> bool {
+        self.transactions.read().contains_key(id)
+    }
+
+    /// Returns the IDs of the transactions of the compact block that are missing from the pool.
+    pub fn missing_transaction_ids(&self, compact_block: &CompactBlock<N>) -> Vec<N::TransactionID> {
+        compact_block.omitted_transaction_ids().filter(|id| !self.contains_transaction(id)).copied().collect()
+    }
+
+    /// Reconstructs the given compact block from the given transactions, and the ones in the pool.
+    pub fn reconstruct(&self, compact_block: &CompactBlock<N>, transactions: Vec<Transaction<N>>) -> Result<Block<N>> {
+        let mut transactions: HashMap<_, _> = transactions.into_iter().map(|tx| (tx.id(), tx)).collect();
+        let pool = self.transactions.read();
+        compact_block.reconstruct(|id| transactions.remove(id).or_else(|| pool.get(id).cloned()))
+    }
+
+    /// Inserts the compact block announced by the given peer, to await its missing transactions.
+    /// Returns `false` if the block is already awaiting its missing transactions.
+    pub fn insert_pending(&self, peer_ip: SocketAddr, compact_block: CompactBlock<N>) -> bool {
+        let mut pending = self.pending.write();
+        if pending.contains_key(&compact_block.block_hash) {
+            return false;
+        }
+        pending.insert(compact_block.block_hash, (peer_ip, compact_block));
+        while pending.len() > MAX_PENDING_BLOCKS {
+            pending.pop_front();
+        }
+        true
+    }
+
+    /// Removes and returns the compact block with the given hash, if it awaits transactions from the given peer.
+    pub fn remove_pending(&self, peer_ip: SocketAddr, block_hash: &N::BlockHash) -> Option<CompactBlock<N>> {
+        let mut pending = self.pending.write();
+        match pending.get(block_hash) {
+            Some((announcer, _)) if *announcer == peer_ip => pending.remove(block_hash).map(|(_, block)| block),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{FromBytes, MainnetV0};
+
+    use std::net::{IpAddr, Ipv4Addr};
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_compact_block_pool() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let compact_block = CompactBlock::from(&genesis);
+        let peer_ip = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 4130);
+        let other_ip = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 4131);
+        let pool = CompactBlockPool::<CurrentNetwork>::default();
+
+        // Ensure the genesis transactions are missing from an empty pool.
+        let missing = pool.missing_transaction_ids(&compact_block);
+        assert_eq!(missing.len(), genesis.transactions().len());
+        assert!(pool.reconstruct(&compact_block, vec![]).is_err());
+
+        // Ensure the block is reconstructed once the transactions are in the pool.
+        for confirmed in genesis.transactions().iter() {
+            pool.insert_transaction(confirmed.transaction().clone());
+        }
+        assert!(pool.missing_transaction_ids(&compact_block).is_empty());
+        assert_eq!(pool.reconstruct(&compact_block, vec![]).unwrap(), genesis);
+
+        // Ensure a pending block is only handed back for the announcing peer, and only once.
+        assert!(pool.insert_pending(peer_ip, compact_block.clone()));
+        assert!(!pool.insert_pending(peer_ip, compact_block.clone()));
+        assert!(pool.remove_pending(other_ip, &genesis.hash()).is_none());
+        assert_eq!(pool.remove_pending(peer_ip, &genesis.hash()), Some(compact_block));
+        assert!(pool.remove_pending(peer_ip, &genesis.hash()).is_none());
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{ensure, Result};
+use std::time::Duration;
+
+/// The settings that keep the connections to the peers alive, and detect the ones that went silent.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct KeepAlive {
+    /// The interval between heartbeats, which maintain the set of connected peers.
+    heartbeat_interval: Duration,
+    /// The interval between pings to each connected peer.
+    ping_interval: Duration,
+    /// The duration after which a connected peer that has not sent any message is disconnected.
+    idle_timeout: Duration,
+    /// The idle time after which TCP keep-alive probes are sent on a connection, if enabled.
+    tcp_keepalive: Option<Duration>,
+}
+
+impl Default for KeepAlive {
+    /// Initializes the default keep-alive settings.
+    fn default() -> Self {
+        Self {
+            heartbeat_interval: Duration::from_secs(25),
+            ping_interval: Duration::from_secs(20),
+            idle_timeout: Duration::from_secs(150),
+            tcp_keepalive: None,
+        }
+    }
+}
+
+impl KeepAlive {
+    /// Initializes new keep-alive settings, ensuring a connected peer is pinged and checked
+    /// at least once before it is considered idle.
+    pub fn new(
+        heartbeat_interval: Duration,
+        ping_interval: Duration,
+        idle_timeout: Duration,
+        tcp_keepalive: Option<Duration>,
+    ) -> Result<Self> {
+        ensure!(!heartbeat_interval.is_zero(), "The heartbeat interval must be greater than zero");
+        ensure!(!ping_interval.is_zero(), "The ping interval must be greater than zero");
+        ensure!(ping_interval < idle_timeout, "The ping interval must be shorter than the idle timeout");
+        ensure!(heartbeat_interval < idle_timeout, "The heartbeat interval must be shorter than the idle timeout");
+        if let Some(tcp_keepalive) = tcp_keepalive {
+            ensure!(tcp_keepalive.as_secs() > 0, "The TCP keep-alive time must be at least one second");
+        }
+        Ok(Self { heartbeat_interval, ping_interval, idle_timeout, tcp_keepalive })
+    }
+
+    /// Returns the interval between heartbeats.
+    pub const fn heartbeat_interval(&self) -> Duration {
+        self.heartbeat_interval
+    }
+
+    /// Returns the interval between pings to each connected peer.
+    pub const fn ping_interval(&self) -> Duration {
+        self.ping_interval
+    }
+
+    /// Returns the duration after which a connected peer that has not sent any message is disconnected.
+    pub const fn idle_timeout(&self) -> Duration {
+        self.idle_timeout
+    }
+
+    /// Returns the idle time after which TCP keep-alive probes are sent on a connection, if enabled.
+    pub const fn tcp_keepalive(&self) -> Option<Duration> {
+        self.tcp_keepalive
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keep_alive_new() {
+        let secs = Duration::from_secs;
+
+        // Ensure the defaults are valid.
+        let default = KeepAlive::default();
+        assert_eq!(
+            KeepAlive::new(default.heartbeat_interval(), default.ping_interval(), default.idle_timeout(), None)
+                .unwrap(),
+            default
+        );
+
+        // Ensure settings suited to an aggressive NAT are accepted.
+        let keep_alive = KeepAlive::new(secs(10), secs(5), secs(30), Some(secs(15))).unwrap();
+        assert_eq!(keep_alive.ping_interval(), secs(5));
+        assert_eq!(keep_alive.tcp_keepalive(), Some(secs(15)));
+
+        // Ensure peers cannot be considered idle before they are pinged.
+        assert!(KeepAlive::new(secs(10), secs(30), secs(30), None).is_err());
+        assert!(KeepAlive::new(secs(30), secs(5), secs(30), None).is_err());
+        // Ensure zero intervals are rejected.
+        assert!(KeepAlive::new(secs(0), secs(5), secs(30), None).is_err());
+        assert!(KeepAlive::new(secs(10), secs(0), secs(30), None).is_err());
+        assert!(KeepAlive::new(secs(10), secs(5), secs(30), Some(secs(0))).is_err());
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{anyhow, Error, Result};
+use std::{fmt, net::SocketAddr, str::FromStr};
+
+/// An additional listening address, along with the port that is advertised to the peers connecting through it.
+/// Note: Only the port is advertised, as the peers learn the IP address of this node from the connection itself.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ListenAddr {
+    /// The address the listener is bound to.
+    bind: SocketAddr,
+    /// The port advertised to the peers connecting through the listener.
+    advertised_port: u16,
+}
+
+impl ListenAddr {
+    /// Initializes a new listening address, with the given bind address and advertised port.
+    pub const fn new(bind: SocketAddr, advertised_port: u16) -> Self {
+        Self { bind, advertised_port }
+    }
+
+    /// Returns the address the listener is bound to.
+    pub const fn bind(&self) -> SocketAddr {
+        self.bind
+    }
+
+    /// Returns the port advertised to the peers connecting through the listener.
+    pub const fn advertised_port(&self) -> u16 {
+        self.advertised_port
+    }
+
+    /// Returns `true` if the given local address of a connection belongs to this listener.
+    pub fn accepts(&self, local_addr: SocketAddr) -> bool {
+        self.bind.port() == local_addr.port() && (self.bind.ip().is_unspecified() || self.bind.ip() == local_addr.ip())
+    }
+}
+
+impl FromStr for ListenAddr {
+    type Err = Error;
+
+    /// Parses a listening address of the form `<bind>` or `<bind>=<advertised port>`.
+    fn from_str(s: &str) -> Result<Self> {
+        let (bind, advertised_port) = match s.split_once('=') {
+            Some((bind, advertised_port)) => (bind, Some(advertised_port)),
+            None => (s, None),
+        };
+        // Parse the bind address.
+        let bind =
+            bind.trim().parse::<SocketAddr>().map_err(|e| anyhow!("Invalid listening address '{bind}' - {e}"))?;
+        // Parse the advertised port, defaulting to the bind port.
+        let advertised_port = match advertised_port {
+            Some(port) => port.trim().parse::<u16>().map_err(|e| anyhow!("Invalid advertised port '{port}' - {e}"))?,
+            None => bind.port(),
+        };
+        Ok(Self::new(bind, advertised_port))
+    }
+}
+
+impl fmt::Display for ListenAddr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.bind.port() == self.advertised_port {
+            true => write!(f, "{}", self.bind),
+            false => write!(f, "{}={}", self.bind, self.advertised_port),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_listen_addr() {
+        let bind = SocketAddr::from_str("10.0.0.5:4130").unwrap();
+
+        // Ensure the advertised port defaults to the bind port.
+        let listen_addr = ListenAddr::from_str("10.0.0.5:4130").unwrap();
+        assert_eq!(listen_addr, ListenAddr::new(bind, 4130));
+        assert_eq!(listen_addr.to_string(), "10.0.0.5:4130");
+
+        // Ensure an explicit advertised port is parsed.
+        let listen_addr = ListenAddr::from_str("10.0.0.5:4130=4140").unwrap();
+        assert_eq!(listen_addr, ListenAddr::new(bind, 4140));
+        assert_eq!(listen_addr.to_string(), "10.0.0.5:4130=4140");
+
+        // Ensure malformed addresses are rejected.
+        assert!(ListenAddr::from_str("10.0.0.5").is_err());
+        assert!(ListenAddr::from_str("10.0.0.5:4130=").is_err());
+        assert!(ListenAddr::from_str("=10.0.0.5:4130").is_err());
+        assert!(ListenAddr::from_str("10.0.0.5:4130=203.0.113.7:4140").is_err());
+    }
+
+    #[test]
+    fn test_accepts() {
+        let specific = ListenAddr::from_str("10.0.0.5:4130").unwrap();
+        assert!(specific.accepts(SocketAddr::from_str("10.0.0.5:4130").unwrap()));
+        assert!(!specific.accepts(SocketAddr::from_str("10.0.0.6:4130").unwrap()));
+        assert!(!specific.accepts(SocketAddr::from_str("10.0.0.5:4131").unwrap()));
+
+        let unspecified = ListenAddr::from_str("0.0.0.0:4130").unwrap();
+        assert!(unspecified.accepts(SocketAddr::from_str("10.0.0.6:4130").unwrap()));
+        assert!(!unspecified.accepts(SocketAddr::from_str("10.0.0.6:4131").unwrap()));
+    }
+}
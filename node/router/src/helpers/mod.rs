@@ -15,8 +15,32 @@
 mod cache;
 pub use cache::Cache;
 
+mod compact;
+pub use compact::*;
+
+mod keepalive;
+pub use keepalive::*;
+
+mod listen;
+pub use listen::*;
+
+mod offenses;
+pub use offenses::*;
+
 mod peer;
 pub use peer::*;
 
+mod peer_cache;
+pub use peer_cache::*;
+
+mod relay;
+pub use relay::*;
+
 mod resolver;
 pub use resolver::*;
+
+mod solution_limit;
+pub use solution_limit::*;
+
+mod traffic;
+pub use traffic::*;
@@ -0,0 +1,296 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    net::{IpAddr, SocketAddr},
+};
+use time::OffsetDateTime;
+
+/// The kind of invalid data, or excessive traffic, served by a peer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum Offense {
+    /// The peer served a block that failed verification.
+    InvalidBlock,
+    /// The peer served a solution that failed verification.
+    InvalidSolution,
+    /// The peer served a transaction that failed verification.
+    InvalidTransaction,
+    /// The peer served more solutions than its rate limit.
+    SolutionFlood,
+}
+
+impl Offense {
+    /// Returns the score added to the peer for this offense.
+    pub const fn score(&self) -> u32 {
+        match self {
+            Self::InvalidBlock => 50,
+            Self::InvalidSolution | Self::InvalidTransaction => 10,
+            Self::SolutionFlood => 5,
+        }
+    }
+}
+
+impl fmt::Display for Offense {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidBlock => write!(f, "an invalid block"),
+            Self::InvalidSolution => write!(f, "an invalid solution"),
+            Self::InvalidTransaction => write!(f, "an invalid transaction"),
+            Self::SolutionFlood => write!(f, "a flood of solutions"),
+        }
+    }
+}
+
+/// The penalty applied to a peer for an offense.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Penalty {
+    /// The offense is added to the peer's score, and the connection is maintained.
+    Score,
+    /// The peer is banned for the given number of seconds, as its score crossed the threshold for the first time.
+    TemporaryBan(i64),
+    /// The peer is banned for the given number of seconds, as its score crossed the threshold again.
+    LongBan(i64),
+}
+
+impl Penalty {
+    /// Returns `true` if the penalty bans the peer.
+    pub const fn is_ban(&self) -> bool {
+        !matches!(self, Self::Score)
+    }
+}
+
+impl fmt::Display for Penalty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Score => write!(f, "score hit"),
+            Self::TemporaryBan(secs) => write!(f, "temporary ban for {secs}s"),
+            Self::LongBan(secs) => write!(f, "long ban for {secs}s"),
+        }
+    }
+}
+
+/// The offense history of a single peer.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct OffenseRecord {
+    /// The current score of the peer, which decays over time.
+    score: u32,
+    /// The number of times the peer has been banned.
+    num_bans: u32,
+    /// The UNIX timestamp until which the peer is banned, if it was ever banned.
+    banned_until: Option<i64>,
+    /// The UNIX timestamps of the most recent offenses, along with the offenses.
+    history: VecDeque<(i64, Offense)>,
+}
+
+impl OffenseRecord {
+    /// Returns the current score of the peer.
+    pub const fn score(&self) -> u32 {
+        self.score
+    }
+
+    /// Returns the number of times the peer has been banned.
+    pub const fn num_bans(&self) -> u32 {
+        self.num_bans
+    }
+
+    /// Returns `true` if the peer is banned at the given UNIX timestamp.
+    pub fn is_banned_at(&self, timestamp: i64) -> bool {
+        self.banned_until.map_or(false, |banned_until| timestamp < banned_until)
+    }
+
+    /// Returns the most recent offenses, along with their UNIX timestamps.
+    pub const fn history(&self) -> &VecDeque<(i64, Offense)> {
+        &self.history
+    }
+
+    /// Returns `true` if the record can be forgotten at the given UNIX timestamp, as the peer is not banned,
+    /// and has not offended for `Offenses::RECORD_EXPIRY_IN_SECS`.
+    fn is_expired_at(&self, timestamp: i64) -> bool {
+        let last_offense = self.history.back().map_or(i64::MIN, |(last_offense, _)| *last_offense);
+        !self.is_banned_at(timestamp) && timestamp.saturating_sub(last_offense) >= Offenses::RECORD_EXPIRY_IN_SECS
+    }
+}
+
+/// The offenses committed by peers, along with the escalating penalties applied to them.
+///
+/// Every offense adds to the peer's score, which decays by one point every `SCORE_DECAY_IN_SECS`.
+/// Once the score reaches `BAN_THRESHOLD`, it is reset and the peer is banned: temporarily the first time,
+/// and for a long time on every subsequent occasion.
+///
+/// The offenses are recorded by IP address, so that a peer cannot evade a ban by changing its port,
+/// and the record of a peer expires once it is no longer banned and has not offended for `RECORD_EXPIRY_IN_SECS`.
+#[derive(Debug, Default)]
+pub struct Offenses(RwLock<HashMap<IpAddr, OffenseRecord>>);
+
+impl Offenses {
+    /// The score at which a peer is banned.
+    const BAN_THRESHOLD: u32 = 100;
+    /// The duration in seconds of a long ban.
+    const LONG_BAN_IN_SECS: i64 = 24 * 60 * 60;
+    /// The maximum number of offenses retained in the history of each peer.
+    const MAXIMUM_HISTORY_LENGTH: usize = 32;
+    /// The duration in seconds after which the record of a peer that is not banned expires, since its last offense.
+    const RECORD_EXPIRY_IN_SECS: i64 = 7 * 24 * 60 * 60;
+    /// The duration in seconds for a single point of the score to decay.
+    const SCORE_DECAY_IN_SECS: i64 = 60;
+    /// The duration in seconds of a temporary ban.
+    const TEMPORARY_BAN_IN_SECS: i64 = 10 * 60;
+
+    /// Records the given offense by the peer, and returns the resulting penalty.
+    pub fn insert(&self, peer_ip: SocketAddr, offense: Offense) -> Penalty {
+        self.insert_at(peer_ip, offense, OffsetDateTime::now_utc().unix_timestamp())
+    }
+
+    /// Records the given offense by the peer at the given UNIX timestamp, and returns the resulting penalty.
+    fn insert_at(&self, peer_ip: SocketAddr, offense: Offense, timestamp: i64) -> Penalty {
+        let mut offenses = self.0.write();
+        // Remove the expired records.
+        offenses.retain(|_, record| !record.is_expired_at(timestamp));
+        let record = offenses.entry(peer_ip.ip()).or_default();
+
+        // Decay the score since the previous offense.
+        if let Some((previous, _)) = record.history.back() {
+            let decay = (timestamp.saturating_sub(*previous) / Self::SCORE_DECAY_IN_SECS).clamp(0, u32::MAX as i64);
+            record.score = record.score.saturating_sub(decay as u32);
+        }
+        // Record the offense.
+        record.score = record.score.saturating_add(offense.score());
+        record.history.push_back((timestamp, offense));
+        while record.history.len() > Self::MAXIMUM_HISTORY_LENGTH {
+            record.history.pop_front();
+        }
+
+        // Escalate the penalty, if the score crossed the threshold.
+        if record.score < Self::BAN_THRESHOLD {
+            return Penalty::Score;
+        }
+        record.score = 0;
+        record.num_bans += 1;
+        let penalty = match record.num_bans {
+            1 => Penalty::TemporaryBan(Self::TEMPORARY_BAN_IN_SECS),
+            _ => Penalty::LongBan(Self::LONG_BAN_IN_SECS),
+        };
+        if let Penalty::TemporaryBan(secs) | Penalty::LongBan(secs) = penalty {
+            record.banned_until = Some(timestamp.saturating_add(secs));
+        }
+        penalty
+    }
+
+    /// Returns `true` if the given peer is currently banned, on any of its ports.
+    pub fn is_banned(&self, peer_ip: &SocketAddr) -> bool {
+        let timestamp = OffsetDateTime::now_utc().unix_timestamp();
+        self.0.read().get(&peer_ip.ip()).map_or(false, |record| record.is_banned_at(timestamp))
+    }
+
+    /// Returns the offense record of the given IP address, if it committed any offense.
+    pub fn get(&self, ip: &IpAddr) -> Option<OffenseRecord> {
+        self.0.read().get(ip).cloned()
+    }
+
+    /// Returns the offense records of all IP addresses that committed an offense.
+    pub fn snapshot(&self) -> HashMap<IpAddr, OffenseRecord> {
+        self.0.read().clone()
+    }
+
+    /// Returns the number of currently banned peers.
+    pub fn number_of_banned_peers(&self) -> usize {
+        let timestamp = OffsetDateTime::now_utc().unix_timestamp();
+        self.0.read().values().filter(|record| record.is_banned_at(timestamp)).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escalating_penalties() {
+        let offenses = Offenses::default();
+        let peer_ip: SocketAddr = "1.2.3.4:4130".parse().unwrap();
+
+        // The first invalid block is a score hit.
+        assert_eq!(offenses.insert_at(peer_ip, Offense::InvalidBlock, 0), Penalty::Score);
+        assert_eq!(offenses.get(&peer_ip.ip()).unwrap().score(), 50);
+        assert!(!offenses.get(&peer_ip.ip()).unwrap().is_banned_at(0));
+
+        // The second invalid block results in a temporary ban.
+        let penalty = offenses.insert_at(peer_ip, Offense::InvalidBlock, 0);
+        assert_eq!(penalty, Penalty::TemporaryBan(Offenses::TEMPORARY_BAN_IN_SECS));
+        let record = offenses.get(&peer_ip.ip()).unwrap();
+        assert_eq!(record.score(), 0);
+        assert_eq!(record.num_bans(), 1);
+        assert!(record.is_banned_at(Offenses::TEMPORARY_BAN_IN_SECS - 1));
+        assert!(!record.is_banned_at(Offenses::TEMPORARY_BAN_IN_SECS));
+
+        // Reoffending after the temporary ban results in a long ban.
+        let timestamp = Offenses::TEMPORARY_BAN_IN_SECS;
+        assert_eq!(offenses.insert_at(peer_ip, Offense::InvalidBlock, timestamp), Penalty::Score);
+        let penalty = offenses.insert_at(peer_ip, Offense::InvalidBlock, timestamp);
+        assert_eq!(penalty, Penalty::LongBan(Offenses::LONG_BAN_IN_SECS));
+        let record = offenses.get(&peer_ip.ip()).unwrap();
+        assert_eq!(record.num_bans(), 2);
+        assert!(record.is_banned_at(timestamp + Offenses::LONG_BAN_IN_SECS - 1));
+        assert_eq!(record.history().len(), 4);
+
+        // The ban applies to the other ports of the same host, but not to other hosts.
+        let other_port: SocketAddr = "1.2.3.4:4131".parse().unwrap();
+        assert!(offenses.get(&other_port.ip()).unwrap().is_banned_at(timestamp));
+        assert_eq!(offenses.snapshot().len(), 1);
+        assert!(offenses.get(&"1.2.3.5".parse().unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_score_decay() {
+        let offenses = Offenses::default();
+        let peer_ip: SocketAddr = "1.2.3.4:4130".parse().unwrap();
+
+        // Occasional invalid transactions never result in a ban.
+        for i in 0..100 {
+            let timestamp = i * 10 * Offenses::SCORE_DECAY_IN_SECS;
+            assert_eq!(offenses.insert_at(peer_ip, Offense::InvalidTransaction, timestamp), Penalty::Score);
+        }
+        assert_eq!(offenses.get(&peer_ip.ip()).unwrap().score(), 10);
+        assert_eq!(offenses.get(&peer_ip.ip()).unwrap().history().len(), Offenses::MAXIMUM_HISTORY_LENGTH);
+
+        // A burst of invalid transactions results in a ban.
+        let timestamp = 1000 * Offenses::SCORE_DECAY_IN_SECS;
+        let penalties: Vec<_> =
+            (0..10).map(|_| offenses.insert_at(peer_ip, Offense::InvalidTransaction, timestamp)).collect();
+        assert!(penalties[..9].iter().all(|penalty| *penalty == Penalty::Score));
+        assert!(penalties[9].is_ban());
+    }
+
+    #[test]
+    fn test_record_expiry() {
+        let offenses = Offenses::default();
+        let peer_ip: SocketAddr = "1.2.3.4:4130".parse().unwrap();
+        let other_ip: SocketAddr = "1.2.3.5:4130".parse().unwrap();
+
+        // A banned peer is retained for as long as it is banned.
+        offenses.insert_at(peer_ip, Offense::InvalidBlock, 0);
+        offenses.insert_at(peer_ip, Offense::InvalidBlock, 0);
+        offenses.insert_at(other_ip, Offense::InvalidTransaction, Offenses::TEMPORARY_BAN_IN_SECS - 1);
+        assert!(offenses.get(&peer_ip.ip()).is_some());
+
+        // Once the ban is over and the peer has not offended for a while, its record expires.
+        let timestamp = Offenses::RECORD_EXPIRY_IN_SECS;
+        offenses.insert_at(other_ip, Offense::InvalidTransaction, timestamp);
+        assert!(offenses.get(&peer_ip.ip()).is_none());
+        assert!(offenses.get(&other_ip.ip()).is_some());
+    }
+}
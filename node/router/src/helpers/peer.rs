@@ -12,10 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::messages::{ChallengeRequest, NodeType};
+use crate::messages::{Capabilities, ChallengeRequest, NodeType};
 use snarkvm::prelude::{Address, Network};
 
-use std::{net::SocketAddr, time::Instant};
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
 
 /// The state for each connected peer.
 #[derive(Clone, Debug)]
@@ -28,22 +31,31 @@ pub struct Peer<N: Network> {
     node_type: NodeType,
     /// The message version of the peer.
     version: u32,
+    /// The optional protocol features supported by the peer.
+    capabilities: Capabilities,
     /// The timestamp of the first message received from the peer.
     first_seen: Instant,
     /// The timestamp of the last message received from this peer.
     last_seen: Instant,
+    /// The timestamp of the last `Ping` sent to this peer, if a `Pong` is still outstanding.
+    last_ping_sent: Option<Instant>,
+    /// The smoothed round-trip time to this peer, as measured via ping-pong.
+    rtt: Option<Duration>,
 }
 
 impl<N: Network> Peer<N> {
-    /// Initializes a new instance of `Peer`.
-    pub fn new(listening_ip: SocketAddr, challenge_request: &ChallengeRequest<N>) -> Self {
+    /// Initializes a new instance of `Peer`, with the capabilities it advertised during the handshake.
+    pub fn new(listening_ip: SocketAddr, challenge_request: &ChallengeRequest<N>, capabilities: Capabilities) -> Self {
         Self {
             peer_ip: listening_ip,
             address: challenge_request.address,
             node_type: challenge_request.node_type,
             version: challenge_request.version,
+            capabilities,
             first_seen: Instant::now(),
             last_seen: Instant::now(),
+            last_ping_sent: None,
+            rtt: None,
         }
     }
 
@@ -82,6 +94,11 @@ impl<N: Network> Peer<N> {
         self.version
     }
 
+    /// Returns the optional protocol features supported by the peer.
+    pub const fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+
     /// Returns the first seen timestamp of the peer.
     pub fn first_seen(&self) -> Instant {
         self.first_seen
@@ -91,6 +108,11 @@ impl<N: Network> Peer<N> {
     pub fn last_seen(&self) -> Instant {
         self.last_seen
     }
+
+    /// Returns the smoothed round-trip time to the peer, if it has been measured.
+    pub const fn rtt(&self) -> Option<Duration> {
+        self.rtt
+    }
 }
 
 impl<N: Network> Peer<N> {
@@ -108,4 +130,22 @@ impl<N: Network> Peer<N> {
     pub fn set_last_seen(&mut self, last_seen: Instant) {
         self.last_seen = last_seen;
     }
+
+    /// Records the timestamp of a `Ping` sent to the peer.
+    pub fn set_ping_sent(&mut self, ping_sent: Instant) {
+        self.last_ping_sent = Some(ping_sent);
+    }
+
+    /// Records the receipt of a `Pong` from the peer, and updates the smoothed round-trip time.
+    /// Returns the newly-measured round-trip time, if a `Ping` was outstanding.
+    pub fn record_pong(&mut self, pong_received: Instant) -> Option<Duration> {
+        // Compute the round-trip time for the outstanding ping.
+        let sample = pong_received.saturating_duration_since(self.last_ping_sent.take()?);
+        // Smooth the round-trip time, weighing the new sample by 1/8 (as in RFC 6298).
+        self.rtt = Some(match self.rtt {
+            Some(rtt) => (rtt * 7 + sample) / 8,
+            None => sample,
+        });
+        Some(sample)
+    }
 }
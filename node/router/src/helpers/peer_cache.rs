@@ -0,0 +1,166 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{messages::NodeType, Peer};
+use snarkvm::prelude::{Address, Network};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::{fs, net::SocketAddr, path::Path};
+use time::OffsetDateTime;
+
+/// A peer that was connected to the node, as persisted across restarts.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct CachedPeer<N: Network> {
+    /// The IP address of the peer, with the port set to the listener port.
+    pub ip: SocketAddr,
+    /// The Aleo address of the peer.
+    pub address: Address<N>,
+    /// The node type of the peer.
+    pub node_type: NodeType,
+    /// The UNIX timestamp at which the peer was last connected.
+    pub last_connected: i64,
+}
+
+impl<N: Network> From<&Peer<N>> for CachedPeer<N> {
+    /// Returns the metadata of the given connected peer, as of now.
+    fn from(peer: &Peer<N>) -> Self {
+        Self {
+            ip: peer.ip(),
+            address: peer.address(),
+            node_type: peer.node_type(),
+            last_connected: OffsetDateTime::now_utc().unix_timestamp(),
+        }
+    }
+}
+
+/// The listener identity of the node and its known peers, as persisted across restarts.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct PeerCache<N: Network> {
+    /// The Aleo address of the node.
+    address: Address<N>,
+    /// The listening address of the node.
+    listener_ip: SocketAddr,
+    /// The known peers, from the most to the least recently connected.
+    peers: Vec<CachedPeer<N>>,
+}
+
+impl<N: Network> PeerCache<N> {
+    /// The maximum number of peers retained in the cache.
+    pub const MAXIMUM_NUMBER_OF_PEERS: usize = 256;
+
+    /// Initializes a new peer cache, retaining the most recently connected peers.
+    pub fn new(address: Address<N>, listener_ip: SocketAddr, mut peers: Vec<CachedPeer<N>>) -> Self {
+        peers.sort_unstable_by(|a, b| b.last_connected.cmp(&a.last_connected));
+        peers.truncate(Self::MAXIMUM_NUMBER_OF_PEERS);
+        Self { address, listener_ip, peers }
+    }
+
+    /// Returns the Aleo address of the node.
+    pub const fn address(&self) -> Address<N> {
+        self.address
+    }
+
+    /// Returns the listening address of the node.
+    pub const fn listener_ip(&self) -> SocketAddr {
+        self.listener_ip
+    }
+
+    /// Returns the known peers, from the most to the least recently connected.
+    pub fn peers(&self) -> &[CachedPeer<N>] {
+        &self.peers
+    }
+
+    /// Returns `true` if the given peer cache holds the same node and peers, regardless of their timestamps.
+    pub fn has_same_peers(&self, other: &Self) -> bool {
+        let key = |cache: &Self| {
+            let mut peers = cache.peers.iter().map(|peer| (peer.ip, peer.address, peer.node_type)).collect::<Vec<_>>();
+            peers.sort_unstable_by_key(|(ip, ..)| *ip);
+            peers
+        };
+        self.address == other.address && self.listener_ip == other.listener_ip && key(self) == key(other)
+    }
+
+    /// Loads the peer cache from the file system.
+    pub fn load(path: &Path) -> Result<Self> {
+        let bytes =
+            fs::read(path).map_err(|err| anyhow!("Couldn't read the peer cache at {} - {err}", path.display()))?;
+        serde_json::from_slice(&bytes)
+            .map_err(|err| anyhow!("Couldn't deserialize the peer cache at {} - {err}", path.display()))
+    }
+
+    /// Stores the peer cache to the file system.
+    pub fn store(&self, path: &Path) -> Result<()> {
+        let bytes = serde_json::to_vec_pretty(self)?;
+        // Write to a temporary file first, so that a crash never leaves a truncated cache behind.
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, bytes)
+            .map_err(|err| anyhow!("Couldn't write the peer cache to {} - {err}", temp_path.display()))?;
+        fs::rename(&temp_path, path)
+            .map_err(|err| anyhow!("Couldn't move the peer cache to {} - {err}", path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{prelude::PrivateKey, utilities::TestRng};
+
+    use rand::Rng;
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    fn sample_address(rng: &mut TestRng) -> Address<CurrentNetwork> {
+        Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_peer_cache() {
+        let rng = &mut TestRng::default();
+
+        // Initialize more peers than can be cached.
+        let peers = (0..PeerCache::<CurrentNetwork>::MAXIMUM_NUMBER_OF_PEERS + 10)
+            .map(|i| CachedPeer {
+                ip: SocketAddr::from(([10, 0, (i / 256) as u8, (i % 256) as u8], 4130)),
+                address: sample_address(rng),
+                node_type: NodeType::Client,
+                last_connected: rng.gen_range(0..1_000_000),
+            })
+            .collect::<Vec<_>>();
+        let cache = PeerCache::new(sample_address(rng), "1.2.3.4:4130".parse().unwrap(), peers);
+
+        // Ensure the most recently connected peers are retained, in order.
+        assert_eq!(cache.peers().len(), PeerCache::<CurrentNetwork>::MAXIMUM_NUMBER_OF_PEERS);
+        assert!(cache.peers().windows(2).all(|w| w[0].last_connected >= w[1].last_connected));
+
+        // Ensure a refresh of the timestamps does not count as a change, unlike a new peer.
+        let mut refreshed = cache.peers().to_vec();
+        refreshed.iter_mut().for_each(|peer| peer.last_connected += 1);
+        assert!(cache.has_same_peers(&PeerCache::new(cache.address(), cache.listener_ip(), refreshed.clone())));
+        refreshed[0].ip = "10.1.0.0:4130".parse().unwrap();
+        assert!(!cache.has_same_peers(&PeerCache::new(cache.address(), cache.listener_ip(), refreshed)));
+
+        // Ensure the cache survives a round trip through the file system.
+        let path = std::env::temp_dir().join(format!("snarkos-peer-cache-{}", rng.gen::<u64>()));
+        cache.store(&path).unwrap();
+        assert_eq!(PeerCache::load(&path).unwrap(), cache);
+        fs::remove_file(&path).unwrap();
+
+        // Ensure a missing cache is reported.
+        assert!(PeerCache::<CurrentNetwork>::load(&path).is_err());
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::messages::Capabilities;
+
+/// The settings for relaying introductions between peers, which lets two peers behind a NAT
+/// establish a direct connection by dialing each other at the same time (i.e. TCP hole punching).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RelayMode {
+    /// If the flag is set, the node introduces the connected peers that consent to hole punching.
+    relay: bool,
+    /// If the flag is set, the node consents to be introduced to peers, and dials them from its listening port.
+    hole_punching: bool,
+}
+
+impl RelayMode {
+    /// Initializes new relay settings.
+    pub const fn new(relay: bool, hole_punching: bool) -> Self {
+        Self { relay, hole_punching }
+    }
+
+    /// Returns `true` if the node introduces the connected peers that consent to hole punching.
+    pub const fn is_relay(&self) -> bool {
+        self.relay
+    }
+
+    /// Returns `true` if the node consents to be introduced to peers for hole punching.
+    pub const fn is_hole_punching(&self) -> bool {
+        self.hole_punching
+    }
+
+    /// Returns the capability flags to advertise to the peers.
+    pub const fn flags(&self) -> u64 {
+        let mut flags = 0;
+        if self.relay {
+            flags |= Capabilities::RELAY;
+        }
+        if self.hole_punching {
+            flags |= Capabilities::HOLE_PUNCHING;
+        }
+        flags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relay_mode_flags() {
+        assert_eq!(RelayMode::default().flags(), 0);
+
+        let capabilities = Capabilities::default().with_flags(RelayMode::new(true, false).flags());
+        assert!(capabilities.supports(Capabilities::RELAY));
+        assert!(!capabilities.supports(Capabilities::HOLE_PUNCHING));
+
+        let capabilities = Capabilities::default().with_flags(RelayMode::new(false, true).flags());
+        assert!(!capabilities.supports(Capabilities::RELAY));
+        assert!(capabilities.supports(Capabilities::HOLE_PUNCHING));
+    }
+}
@@ -0,0 +1,148 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{ensure, Result};
+use parking_lot::Mutex;
+use std::{
+    collections::{HashMap, VecDeque},
+    net::SocketAddr,
+};
+use time::OffsetDateTime;
+
+/// The limits on the rate of unconfirmed solutions accepted from peers,
+/// so that a flood of solutions cannot crowd out the processing of transactions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SolutionRateLimit {
+    /// The maximum number of solutions accepted from a single peer per interval.
+    max_per_peer: usize,
+    /// The maximum number of solutions accepted from all peers per interval.
+    max_total: usize,
+}
+
+impl SolutionRateLimit {
+    /// The interval in seconds over which the solutions are counted.
+    pub const INTERVAL_IN_SECS: i64 = 60;
+
+    /// Initializes new limits, with the given values or unbounded, ensuring they are nonzero.
+    pub fn new(max_per_peer: Option<usize>, max_total: Option<usize>) -> Result<Self> {
+        let max_per_peer = max_per_peer.unwrap_or(usize::MAX);
+        ensure!(max_per_peer > 0, "The maximum number of solutions per peer must be nonzero");
+        let max_total = max_total.unwrap_or(usize::MAX);
+        ensure!(max_total > 0, "The maximum number of solutions must be nonzero");
+        Ok(Self { max_per_peer, max_total })
+    }
+
+    /// Returns the maximum number of solutions accepted from a single peer per interval.
+    pub const fn max_per_peer(&self) -> usize {
+        self.max_per_peer
+    }
+
+    /// Returns the maximum number of solutions accepted from all peers per interval.
+    pub const fn max_total(&self) -> usize {
+        self.max_total
+    }
+}
+
+/// The outcome of checking an unconfirmed solution against the rate limits.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SolutionRate {
+    /// The solution is within the limits.
+    Accepted,
+    /// The peer exceeded its own limit, and is penalized.
+    PeerExceeded,
+    /// The peers exceeded the total limit; the solution is dropped, without penalizing the peer.
+    TotalExceeded,
+}
+
+/// The tracker of the unconfirmed solutions recently received from peers.
+#[derive(Debug)]
+pub struct SolutionLimiter {
+    /// The limits on the rate of unconfirmed solutions.
+    limit: SolutionRateLimit,
+    /// The UNIX timestamps of the solutions recently received from each peer, along with the accepted ones.
+    timestamps: Mutex<(HashMap<SocketAddr, VecDeque<i64>>, VecDeque<i64>)>,
+}
+
+impl SolutionLimiter {
+    /// Initializes a new tracker with the given limits.
+    pub fn new(limit: SolutionRateLimit) -> Self {
+        Self { limit, timestamps: Default::default() }
+    }
+
+    /// Returns the limits on the rate of unconfirmed solutions.
+    pub const fn limit(&self) -> &SolutionRateLimit {
+        &self.limit
+    }
+
+    /// Records an unconfirmed solution from the given peer, and returns whether it is within the limits.
+    pub fn check(&self, peer_ip: SocketAddr) -> SolutionRate {
+        self.check_at(peer_ip, OffsetDateTime::now_utc().unix_timestamp())
+    }
+
+    /// Records an unconfirmed solution from the given peer at the given UNIX timestamp,
+    /// and returns whether it is within the limits.
+    fn check_at(&self, peer_ip: SocketAddr, timestamp: i64) -> SolutionRate {
+        let mut timestamps = self.timestamps.lock();
+        let (peers, accepted) = &mut *timestamps;
+
+        // Retain only the timestamps that are within the interval.
+        let is_recent = |t: &i64| timestamp.saturating_sub(*t) < SolutionRateLimit::INTERVAL_IN_SECS;
+        peers.retain(|_, peer_timestamps| {
+            peer_timestamps.retain(is_recent);
+            !peer_timestamps.is_empty()
+        });
+        accepted.retain(is_recent);
+
+        // Every solution counts toward the limit of the peer, so that a flooding peer remains limited.
+        let peer_timestamps = peers.entry(peer_ip).or_default();
+        peer_timestamps.push_back(timestamp);
+        if peer_timestamps.len() > self.limit.max_per_peer {
+            return SolutionRate::PeerExceeded;
+        }
+        // Only the accepted solutions count toward the total limit.
+        if accepted.len() >= self.limit.max_total {
+            return SolutionRate::TotalExceeded;
+        }
+        accepted.push_back(timestamp);
+        SolutionRate::Accepted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solution_rate_limit() {
+        assert!(SolutionRateLimit::new(Some(0), None).is_err());
+        assert!(SolutionRateLimit::new(None, Some(0)).is_err());
+
+        let limiter = SolutionLimiter::new(SolutionRateLimit::new(Some(2), Some(3)).unwrap());
+        let peer_a = "1.2.3.4:4130".parse().unwrap();
+        let peer_b = "1.2.3.5:4130".parse().unwrap();
+
+        // Each peer is limited on its own.
+        assert_eq!(limiter.check_at(peer_a, 0), SolutionRate::Accepted);
+        assert_eq!(limiter.check_at(peer_a, 0), SolutionRate::Accepted);
+        assert_eq!(limiter.check_at(peer_a, 0), SolutionRate::PeerExceeded);
+        // The peers are limited in total, without exceeding their own limit.
+        assert_eq!(limiter.check_at(peer_b, 0), SolutionRate::Accepted);
+        assert_eq!(limiter.check_at(peer_b, 0), SolutionRate::TotalExceeded);
+
+        // The limits reset after the interval.
+        let timestamp = SolutionRateLimit::INTERVAL_IN_SECS;
+        assert_eq!(limiter.check_at(peer_a, timestamp), SolutionRate::Accepted);
+        assert_eq!(limiter.check_at(peer_b, timestamp), SolutionRate::Accepted);
+    }
+}
@@ -0,0 +1,209 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::messages::{Message, MessageCodec};
+use snarkvm::prelude::Network;
+
+use bytes::BytesMut;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    net::SocketAddr,
+    sync::Arc,
+};
+use tokio_util::codec::{Decoder, Encoder};
+
+/// The number of messages and bytes exchanged for a single message type.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct TrafficCounter {
+    /// The number of messages.
+    pub messages: u64,
+    /// The number of bytes.
+    pub bytes: u64,
+}
+
+impl TrafficCounter {
+    /// Records a message of the given size.
+    fn record(&mut self, size: usize) {
+        self.messages = self.messages.saturating_add(1);
+        self.bytes = self.bytes.saturating_add(size as u64);
+    }
+}
+
+/// The traffic exchanged with a single peer, broken down by message type.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct PeerTraffic {
+    /// The traffic sent to the peer.
+    pub sent: BTreeMap<Cow<'static, str>, TrafficCounter>,
+    /// The traffic received from the peer.
+    pub received: BTreeMap<Cow<'static, str>, TrafficCounter>,
+}
+
+impl PeerTraffic {
+    /// Returns the total traffic sent to the peer.
+    pub fn total_sent(&self) -> TrafficCounter {
+        Self::total(&self.sent)
+    }
+
+    /// Returns the total traffic received from the peer.
+    pub fn total_received(&self) -> TrafficCounter {
+        Self::total(&self.received)
+    }
+
+    /// Returns the sum of the given counters.
+    fn total(counters: &BTreeMap<Cow<'static, str>, TrafficCounter>) -> TrafficCounter {
+        counters.values().fold(TrafficCounter::default(), |total, counter| TrafficCounter {
+            messages: total.messages.saturating_add(counter.messages),
+            bytes: total.bytes.saturating_add(counter.bytes),
+        })
+    }
+}
+
+/// The traffic exchanged with each connected peer, keyed by the (ambiguous) peer address.
+#[derive(Debug, Default)]
+pub struct Traffic(RwLock<HashMap<SocketAddr, PeerTraffic>>);
+
+impl Traffic {
+    /// Records a message of the given type and size sent to the peer.
+    pub fn record_sent(&self, peer_addr: SocketAddr, name: Cow<'static, str>, size: usize) {
+        #[cfg(feature = "metrics")]
+        Self::update_metrics(metrics::router::SENT_MESSAGES, metrics::router::SENT_BYTES, peer_addr, &name, size);
+        self.0.write().entry(peer_addr).or_default().sent.entry(name).or_default().record(size);
+    }
+
+    /// Records a message of the given type and size received from the peer.
+    pub fn record_received(&self, peer_addr: SocketAddr, name: Cow<'static, str>, size: usize) {
+        #[cfg(feature = "metrics")]
+        Self::update_metrics(
+            metrics::router::RECEIVED_MESSAGES,
+            metrics::router::RECEIVED_BYTES,
+            peer_addr,
+            &name,
+            size,
+        );
+        self.0.write().entry(peer_addr).or_default().received.entry(name).or_default().record(size);
+    }
+
+    /// Increments the message and byte counters of the given peer and message type.
+    #[cfg(feature = "metrics")]
+    fn update_metrics(messages: &'static str, bytes: &'static str, peer_addr: SocketAddr, name: &str, size: usize) {
+        let labels = || [("peer", peer_addr.to_string()), ("message", name.to_string())];
+        metrics::increment_counter_labels(messages, labels(), 1);
+        metrics::increment_counter_labels(bytes, labels(), size as u64);
+    }
+
+    /// Returns the traffic exchanged with the given peer, if any.
+    pub fn get(&self, peer_addr: &SocketAddr) -> Option<PeerTraffic> {
+        self.0.read().get(peer_addr).cloned()
+    }
+
+    /// Removes the traffic exchanged with the given peer.
+    pub fn remove(&self, peer_addr: &SocketAddr) -> Option<PeerTraffic> {
+        self.0.write().remove(peer_addr)
+    }
+}
+
+/// A wrapper around the [`MessageCodec`] that records the traffic exchanged with a peer.
+pub struct TrafficCodec<N: Network> {
+    /// The underlying message codec.
+    codec: MessageCodec<N>,
+    /// The traffic of the router.
+    traffic: Arc<Traffic>,
+    /// The (ambiguous) address of the peer.
+    peer_addr: SocketAddr,
+    /// The number of bytes read for a message that is not fully decoded yet.
+    acc: usize,
+}
+
+impl<N: Network> TrafficCodec<N> {
+    /// Initializes a new traffic codec for the given peer.
+    pub fn new(traffic: Arc<Traffic>, peer_addr: SocketAddr) -> Self {
+        Self { codec: Default::default(), traffic, peer_addr, acc: 0 }
+    }
+}
+
+impl<N: Network> Encoder<Message<N>> for TrafficCodec<N> {
+    type Error = std::io::Error;
+
+    fn encode(&mut self, message: Message<N>, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let name = message.name();
+        let initial_len = dst.len();
+        self.codec.encode(message, dst)?;
+        self.traffic.record_sent(self.peer_addr, name, dst.len().saturating_sub(initial_len));
+        Ok(())
+    }
+}
+
+impl<N: Network> Decoder for TrafficCodec<N> {
+    type Error = std::io::Error;
+    type Item = Message<N>;
+
+    fn decode(&mut self, source: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let initial_len = source.len();
+        let message = self.codec.decode(source)?;
+        // Accumulate the bytes read until the message is fully decoded.
+        self.acc += initial_len - source.len();
+        if let Some(message) = &message {
+            self.traffic.record_received(self.peer_addr, message.name(), self.acc);
+            self.acc = 0;
+        }
+        Ok(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{NodeType, Ping, Pong};
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    #[test]
+    fn test_traffic_codec() {
+        let traffic = Arc::new(Traffic::default());
+        let peer_addr = "1.2.3.4:4130".parse().unwrap();
+        let mut codec = TrafficCodec::<CurrentNetwork>::new(traffic.clone(), peer_addr);
+
+        // Send a ping and a pong.
+        let mut bytes = BytesMut::new();
+        codec.encode(Message::Ping(Ping::new(NodeType::Client, None)), &mut bytes).unwrap();
+        let ping_len = bytes.len();
+        codec.encode(Message::Pong(Pong { is_fork: None }), &mut bytes).unwrap();
+        let pong_len = bytes.len() - ping_len;
+
+        // Receive the ping one byte at a time, then the pong.
+        let mut source = BytesMut::new();
+        for byte in bytes.split_to(ping_len).iter() {
+            source.extend_from_slice(&[*byte]);
+            let _ = codec.decode(&mut source).unwrap();
+        }
+        source.extend_from_slice(&bytes);
+        assert!(codec.decode(&mut source).unwrap().is_some());
+
+        // Ensure the traffic is recorded per message type.
+        let peer_traffic = traffic.get(&peer_addr).unwrap();
+        assert_eq!(peer_traffic.sent["Ping"], TrafficCounter { messages: 1, bytes: ping_len as u64 });
+        assert_eq!(peer_traffic.sent["Pong"], TrafficCounter { messages: 1, bytes: pong_len as u64 });
+        assert_eq!(peer_traffic.received["Ping"], TrafficCounter { messages: 1, bytes: ping_len as u64 });
+        assert_eq!(peer_traffic.received["Pong"], TrafficCounter { messages: 1, bytes: pong_len as u64 });
+        assert_eq!(peer_traffic.total_sent(), TrafficCounter { messages: 2, bytes: (ping_len + pong_len) as u64 });
+        assert_eq!(peer_traffic.total_received(), peer_traffic.total_sent());
+
+        // Ensure the traffic is removed with the peer.
+        assert!(traffic.remove(&peer_addr).is_some());
+        assert!(traffic.get(&peer_addr).is_none());
+    }
+}
@@ -16,27 +16,36 @@ use crate::{
     messages::{
         BlockRequest,
         BlockResponse,
+        BlockTransactionsRequest,
+        Capabilities,
+        CompactBlock,
         DataBlocks,
+        DisconnectReason,
         Message,
         PeerResponse,
         Ping,
         Pong,
+        RelayIntroduction,
+        RelayRequest,
         UnconfirmedSolution,
         UnconfirmedTransaction,
     },
+    Offense,
     Outbound,
     Peer,
+    Penalty,
+    SolutionRate,
 };
 use snarkos_node_tcp::protocols::Reading;
 use snarkvm::prelude::{
-    block::{Block, Header, Transaction},
+    block::{Block, Header, Transaction, Transactions},
     puzzle::Solution,
     Network,
 };
 
 use anyhow::{anyhow, bail, Result};
 use snarkos_node_tcp::is_bogon_ip;
-use std::net::SocketAddr;
+use std::{fmt::Display, net::SocketAddr};
 use tokio::task::spawn_blocking;
 
 /// The max number of peers to send in a `PeerResponse` message.
@@ -52,8 +61,12 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
     const MAXIMUM_PUZZLE_REQUESTS_PER_INTERVAL: usize = 5;
     /// The maximum number of block requests per interval.
     const MAXIMUM_BLOCK_REQUESTS_PER_INTERVAL: usize = 256;
-    /// The duration in seconds to sleep in between ping requests with a connected peer.
-    const PING_SLEEP_IN_SECS: u64 = 20; // 20 seconds
+    /// The maximum number of compact blocks per interval.
+    const MAXIMUM_COMPACT_BLOCKS_PER_INTERVAL: usize = 120;
+    /// The maximum number of relay requests per interval.
+    const MAXIMUM_RELAY_REQUESTS_PER_INTERVAL: usize = 5;
+    /// The maximum number of relay introductions per interval, from a single relay.
+    const MAXIMUM_RELAY_INTRODUCTIONS_PER_INTERVAL: usize = 10;
     /// The time frame to enforce the `MESSAGE_LIMIT`.
     const MESSAGE_LIMIT_TIME_FRAME_IN_SECS: i64 = 5;
     /// The maximum number of messages accepted within `MESSAGE_LIMIT_TIME_FRAME_IN_SECS`.
@@ -83,6 +96,11 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
         // checking that the message is valid, and then calling the appropriate (trait) handler.
         match message {
             Message::BlockRequest(message) => {
+                // Ignore new block requests, as the node is draining its connections to shut down.
+                if self.router().is_shutting_down() {
+                    trace!("Ignoring a block request from '{peer_ip}' (shutting down)");
+                    return Ok(());
+                }
                 let BlockRequest { start_height, end_height } = &message;
                 // Insert the block request for the peer, and fetch the recent frequency.
                 let frequency = self.router().cache.insert_inbound_block_request(peer_ip);
@@ -122,12 +140,16 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                 });
                 let blocks = match recv.await {
                     Ok(Ok(blocks)) => blocks,
-                    Ok(Err(error)) => bail!("Peer '{peer_ip}' sent an invalid block response - {error}"),
+                    Ok(Err(error)) => return self.handle_offense(peer_ip, Offense::InvalidBlock, error),
                     Err(error) => bail!("Peer '{peer_ip}' sent an invalid block response - {error}"),
                 };
 
                 // Ensure the block response is well-formed.
-                blocks.ensure_response_is_well_formed(peer_ip, request.start_height, request.end_height)?;
+                if let Err(error) =
+                    blocks.ensure_response_is_well_formed(peer_ip, request.start_height, request.end_height)
+                {
+                    return self.handle_offense(peer_ip, Offense::InvalidBlock, error);
+                }
 
                 // Process the block response.
                 let node = self.clone();
@@ -136,11 +158,68 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                     false => bail!("Peer '{peer_ip}' sent an invalid block response"),
                 }
             }
+            Message::BlockTransactionsRequest(message) => {
+                // Insert the block request for the peer, and fetch the recent frequency.
+                let frequency = self.router().cache.insert_inbound_block_request(peer_ip);
+                // Check if the number of block requests is within the limit.
+                if frequency > Self::MAXIMUM_BLOCK_REQUESTS_PER_INTERVAL {
+                    bail!("Peer '{peer_ip}' is not following the protocol (excessive block requests)")
+                }
+                // Ensure the request does not exceed the number of transactions in a block.
+                if message.transaction_ids.len() > Transactions::<N>::MAX_TRANSACTIONS {
+                    bail!("Block transactions request from '{peer_ip}' has an excessive number of transactions")
+                }
+
+                let node = self.clone();
+                match spawn_blocking(move || node.block_transactions_request(peer_ip, message)).await? {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid block transactions request"),
+                }
+            }
+            Message::BlockTransactionsResponse(message) => {
+                // Remove the pending compact block, checking if this node requested its transactions from this peer.
+                let Some(compact_block) = self.router().compact_blocks().remove_pending(peer_ip, &message.block_hash)
+                else {
+                    bail!("Peer '{peer_ip}' is not following the protocol (unexpected block transactions response)")
+                };
+
+                // Process the block transactions response.
+                let node = self.clone();
+                match spawn_blocking(move || {
+                    node.block_transactions_response(peer_ip, compact_block, message.transactions)
+                })
+                .await?
+                {
+                    true => Ok(()),
+                    false => self.handle_offense(peer_ip, Offense::InvalidBlock, "the compact block is malformed"),
+                }
+            }
             Message::ChallengeRequest(..) | Message::ChallengeResponse(..) => {
                 // Disconnect as the peer is not following the protocol.
                 bail!("Peer '{peer_ip}' is not following the protocol")
             }
+            Message::CompactBlock(message) => {
+                // Insert the compact block for the peer, and fetch the recent frequency.
+                let frequency = self.router().cache.insert_inbound_compact_block(peer_ip);
+                // Check if the number of compact blocks is within the limit.
+                if frequency > Self::MAXIMUM_COMPACT_BLOCKS_PER_INTERVAL {
+                    bail!("Peer '{peer_ip}' is not following the protocol (excessive compact blocks)")
+                }
+
+                // Process the compact block.
+                let node = self.clone();
+                match spawn_blocking(move || node.compact_block(peer_ip, message)).await? {
+                    true => Ok(()),
+                    false => self.handle_offense(peer_ip, Offense::InvalidBlock, "the compact block is malformed"),
+                }
+            }
             Message::Disconnect(message) => {
+                // If the peer is shutting down gracefully, disconnect without treating it as a protocol violation.
+                if message.reason == DisconnectReason::ShuttingDown {
+                    debug!("Peer '{peer_ip}' is shutting down");
+                    self.router().disconnect(peer_ip);
+                    return Ok(());
+                }
                 bail!("{:?}", message.reason)
             }
             Message::PeerRequest(..) => match self.peer_request(peer_ip) {
@@ -163,7 +242,7 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
             }
             Message::Ping(message) => {
                 // Ensure the message protocol version is not outdated.
-                if message.version < Message::<N>::VERSION {
+                if message.version < Message::<N>::MIN_VERSION {
                     bail!("Dropping '{peer_ip}' on message version {} (outdated)", message.version);
                 }
 
@@ -195,10 +274,18 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                     false => bail!("Peer '{peer_ip}' sent an invalid ping"),
                 }
             }
-            Message::Pong(message) => match self.pong(peer_ip, message) {
-                true => Ok(()),
-                false => bail!("Peer '{peer_ip}' sent an invalid pong"),
-            },
+            Message::Pong(message) => {
+                // Update the round-trip time of the connected peer.
+                if let Some(rtt) = self.router().update_pong_received_for_connected_peer(peer_ip) {
+                    trace!("Round-trip time to '{peer_ip}' is {}ms", rtt.as_millis());
+                }
+
+                // Process the pong message.
+                match self.pong(peer_ip, message) {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid pong"),
+                }
+            }
             Message::PuzzleRequest(..) => {
                 // Insert the puzzle request for the peer, and fetch the recent frequency.
                 let frequency = self.router().cache.insert_inbound_puzzle_request(peer_ip);
@@ -231,6 +318,49 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                     false => bail!("Peer '{peer_ip}' sent an invalid puzzle response"),
                 }
             }
+            Message::RelayRequest(message) => {
+                // Ensure this node offers to relay.
+                if !self.router().relay_mode().is_relay() {
+                    bail!("Peer '{peer_ip}' is not following the protocol (unexpected relay request)")
+                }
+                // Insert the relay request for the peer, and fetch the recent frequency.
+                let frequency = self.router().cache.insert_inbound_relay_request(peer_ip);
+                // Check if the number of relay requests is within the limit.
+                if frequency > Self::MAXIMUM_RELAY_REQUESTS_PER_INTERVAL {
+                    bail!("Peer '{peer_ip}' is not following the protocol (excessive relay requests)")
+                }
+                // Process the relay request.
+                match self.relay_request(peer_ip, message) {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid relay request"),
+                }
+            }
+            Message::RelayIntroduction(message) => {
+                // Ensure this node consents to hole punching.
+                if !self.router().relay_mode().is_hole_punching() {
+                    bail!("Peer '{peer_ip}' is not following the protocol (unexpected relay introduction)")
+                }
+                // As the initiator, check that this node previously sent a relay request to this peer.
+                if message.is_initiator {
+                    if !self.router().cache.contains_outbound_relay_request(&peer_ip) {
+                        bail!("Peer '{peer_ip}' is not following the protocol (unexpected relay introduction)")
+                    }
+                    // Decrement the number of relay requests.
+                    self.router().cache.decrement_outbound_relay_requests(peer_ip);
+                }
+                // As the responder, ignore the introductions in excess of the limit, as they are requested by others.
+                else if self.router().cache.insert_inbound_relay_introduction(peer_ip)
+                    > Self::MAXIMUM_RELAY_INTRODUCTIONS_PER_INTERVAL
+                {
+                    trace!("Skipping 'RelayIntroduction' from '{peer_ip}' (excessive introductions)");
+                    return Ok(());
+                }
+                // Process the relay introduction.
+                match self.relay_introduction(peer_ip, message) {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid relay introduction"),
+                }
+            }
             Message::UnconfirmedSolution(message) => {
                 // Do not process unconfirmed solutions if the node is too far behind.
                 if self.num_blocks_behind() > SYNC_LENIENCY {
@@ -244,12 +374,23 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                     trace!("Skipping 'UnconfirmedSolution' from '{peer_ip}'");
                     return Ok(());
                 }
+                // Ensure the solution is within the rate limits, penalizing the peers that flood solutions.
+                match self.router().check_solution_rate(peer_ip) {
+                    SolutionRate::Accepted => (),
+                    SolutionRate::PeerExceeded => {
+                        return self.handle_offense(peer_ip, Offense::SolutionFlood, "exceeded the rate limit");
+                    }
+                    SolutionRate::TotalExceeded => {
+                        trace!("Skipping 'UnconfirmedSolution' from '{peer_ip}' (rate limited)");
+                        return Ok(());
+                    }
+                }
                 // Clone the serialized message.
                 let serialized = message.clone();
                 // Perform the deferred non-blocking deserialization of the solution.
                 let solution = match message.solution.deserialize().await {
                     Ok(solution) => solution,
-                    Err(error) => bail!("[UnconfirmedSolution] {error}"),
+                    Err(error) => return self.handle_offense(peer_ip, Offense::InvalidSolution, error),
                 };
                 // Check that the solution parameters match.
                 if message.solution_id != solution.id() {
@@ -280,7 +421,7 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                 // Perform the deferred non-blocking deserialization of the transaction.
                 let transaction = match message.transaction.deserialize().await {
                     Ok(transaction) => transaction,
-                    Err(error) => bail!("[UnconfirmedTransaction] {error}"),
+                    Err(error) => return self.handle_offense(peer_ip, Offense::InvalidTransaction, error),
                 };
                 // Check that the transaction parameters match.
                 if message.transaction_id != transaction.id() {
@@ -295,12 +436,47 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
         }
     }
 
+    /// Records the given offense by the peer, which served data that failed verification.
+    /// The message is dropped, and the connection is maintained unless the peer is banned as a result.
+    fn handle_offense(&self, peer_ip: SocketAddr, offense: Offense, error: impl Display) -> Result<()> {
+        match self.router().insert_offense(peer_ip, offense) {
+            Penalty::Score => {
+                warn!("Peer '{peer_ip}' sent {offense} - {error}");
+                Ok(())
+            }
+            penalty => bail!("Peer '{peer_ip}' sent {offense} ({penalty}) - {error}"),
+        }
+    }
+
     /// Handles a `BlockRequest` message.
     fn block_request(&self, peer_ip: SocketAddr, _message: BlockRequest) -> bool;
 
     /// Handles a `BlockResponse` message.
     fn block_response(&self, peer_ip: SocketAddr, _blocks: Vec<Block<N>>) -> bool;
 
+    /// Handles a `BlockTransactionsRequest` message.
+    /// By default, the request is rejected, as only the nodes that announce blocks serve their transactions.
+    fn block_transactions_request(&self, _peer_ip: SocketAddr, _message: BlockTransactionsRequest<N>) -> bool {
+        false
+    }
+
+    /// Handles a `BlockTransactionsResponse` message, with the compact block that awaited the transactions.
+    /// By default, the response is ignored, as only the nodes that follow the announcements request transactions.
+    fn block_transactions_response(
+        &self,
+        _peer_ip: SocketAddr,
+        _compact_block: CompactBlock<N>,
+        _transactions: Vec<Transaction<N>>,
+    ) -> bool {
+        true
+    }
+
+    /// Handles a `CompactBlock` message.
+    /// By default, the announcement is ignored, as the node advances its ledger through other means.
+    fn compact_block(&self, _peer_ip: SocketAddr, _message: CompactBlock<N>) -> bool {
+        true
+    }
+
     /// Handles a `PeerRequest` message.
     fn peer_request(&self, peer_ip: SocketAddr) -> bool {
         // Retrieve the connected peers.
@@ -353,6 +529,54 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
     /// Handles a `PuzzleResponse` message.
     fn puzzle_response(&self, peer_ip: SocketAddr, _epoch_hash: N::BlockHash, _header: Header<N>) -> bool;
 
+    /// Handles a `RelayRequest` message, by introducing the peer and the requested target to each other.
+    fn relay_request(&self, peer_ip: SocketAddr, message: RelayRequest) -> bool {
+        let target_ip = message.target;
+        // Ensure the peer does not request to be introduced to itself.
+        if target_ip == peer_ip {
+            return false;
+        }
+        // Ensure the peer consents to hole punching.
+        match self.router().get_connected_peer(&peer_ip) {
+            Some(peer) if peer.capabilities().supports(Capabilities::HOLE_PUNCHING) => (),
+            _ => return false,
+        }
+        // Ensure the target is connected, and consents to hole punching.
+        match self.router().get_connected_peer(&target_ip) {
+            Some(target) if target.capabilities().supports(Capabilities::HOLE_PUNCHING) => (),
+            _ => {
+                trace!("Unable to introduce '{peer_ip}' to '{target_ip}' (not reachable through this relay)");
+                return true;
+            }
+        }
+        // Retrieve the addresses of both peers, as observed by this node (i.e. their NAT mappings).
+        let (Some(peer_addr), Some(target_addr)) =
+            (self.router().resolve_to_ambiguous(&peer_ip), self.router().resolve_to_ambiguous(&target_ip))
+        else {
+            return true;
+        };
+        debug!("Introducing '{peer_ip}' to '{target_ip}'");
+        // Send a `RelayIntroduction` message to both peers, so that they dial each other at the same time.
+        self.send(target_ip, Message::RelayIntroduction(RelayIntroduction { peer_addr, is_initiator: false }));
+        self.send(
+            peer_ip,
+            Message::RelayIntroduction(RelayIntroduction { peer_addr: target_addr, is_initiator: true }),
+        );
+        true
+    }
+
+    /// Handles a `RelayIntroduction` message, by punching through to the introduced peer.
+    fn relay_introduction(&self, _peer_ip: SocketAddr, message: RelayIntroduction) -> bool {
+        // Ensure the introduced address is valid.
+        let peer_addr = message.peer_addr;
+        if !self.router().is_dev() && !self.router().is_valid_peer_ip(&peer_addr) {
+            return false;
+        }
+        // Attempt to punch through to the introduced peer.
+        self.router().punch(peer_addr, message.is_initiator);
+        true
+    }
+
     /// Handles an `UnconfirmedSolution` message.
     async fn unconfirmed_solution(
         &self,
@@ -21,6 +21,8 @@ extern crate tracing;
 
 pub use snarkos_node_router_messages as messages;
 
+mod discovery;
+
 mod handshake;
 
 mod heartbeat;
@@ -38,21 +40,26 @@ pub use outbound::*;
 mod routing;
 pub use routing::*;
 
-use crate::messages::NodeType;
+use crate::messages::{Capabilities, Message, NodeType};
 use snarkos_account::Account;
-use snarkos_node_tcp::{is_bogon_ip, is_unspecified_or_broadcast_ip, Config, Tcp};
+use snarkos_node_tcp::{is_bogon_ip, is_unspecified_or_broadcast_ip, Config, ConnectionSide, Tcp};
 use snarkvm::prelude::{Address, Network, PrivateKey, ViewKey};
 
 use anyhow::{bail, Result};
+use indexmap::IndexSet;
 use parking_lot::{Mutex, RwLock};
 use std::{
     collections::{HashMap, HashSet},
     future::Future,
-    net::SocketAddr,
+    net::{IpAddr, SocketAddr},
     ops::Deref,
+    path::PathBuf,
     str::FromStr,
-    sync::Arc,
-    time::Instant,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 use tokio::task::JoinHandle;
 
@@ -70,16 +77,29 @@ impl<N: Network> Deref for Router<N> {
 pub struct InnerRouter<N: Network> {
     /// The TCP stack.
     tcp: Tcp,
+    /// The additional listening addresses, along with their advertised ports.
+    listen_addrs: Vec<ListenAddr>,
+    /// The settings that keep the connections to the peers alive.
+    keep_alive: KeepAlive,
+    /// The settings for relaying introductions between peers.
+    relay_mode: RelayMode,
     /// The node type.
     node_type: NodeType,
     /// The account of the node.
     account: Account<N>,
     /// The cache.
     cache: Cache<N>,
+    /// The pool of recently seen transactions, to reconstruct the announced compact blocks.
+    compact_blocks: CompactBlockPool<N>,
     /// The resolver.
     resolver: Resolver,
     /// The set of trusted peers.
     trusted_peers: HashSet<SocketAddr>,
+    /// The map of pinned peer IPs to their reconnection state, as (number of failed attempts, time of the next attempt).
+    /// Pinned peers are always kept connected, and are never evicted.
+    pinned_peers: RwLock<HashMap<SocketAddr, (u32, Instant)>>,
+    /// The mDNS daemon used to discover peers on the local network, if enabled.
+    local_discovery: Mutex<Option<mdns_sd::ServiceDaemon>>,
     /// The map of connected peer IPs to their peer handlers.
     connected_peers: RwLock<HashMap<SocketAddr, Peer<N>>>,
     /// The set of handshaking peers. While `Tcp` already recognizes the connecting IP addresses
@@ -91,21 +111,49 @@ pub struct InnerRouter<N: Network> {
     candidate_peers: RwLock<HashSet<SocketAddr>>,
     /// The set of restricted peer IPs.
     restricted_peers: RwLock<HashMap<SocketAddr, Instant>>,
+    /// The set of peer IPs that could not be reached directly, to be introduced by a relay.
+    unreachable_peers: Mutex<IndexSet<SocketAddr>>,
+    /// The offenses committed by peers serving invalid data.
+    offenses: Offenses,
+    /// The traffic exchanged with each connected peer.
+    traffic: Arc<Traffic>,
+    /// The path of the peer cache, which persists the known peers across restarts, if enabled.
+    peer_cache_path: RwLock<Option<PathBuf>>,
+    /// The tracker of the unconfirmed solutions received from peers, if rate limited.
+    solution_limiter: RwLock<Option<Arc<SolutionLimiter>>>,
+    /// The map of known peer IPs to their metadata, as of their last connection.
+    cached_peers: RwLock<HashMap<SocketAddr, CachedPeer<N>>>,
+    /// The peer cache that was last written to the file system, along with the time of the write.
+    stored_peer_cache: Mutex<Option<(Instant, PeerCache<N>)>>,
     /// The spawned handles.
     handles: Mutex<Vec<JoinHandle<()>>>,
     /// If the flag is set, the node will engage in P2P gossip to request more peers.
     allow_external_peers: bool,
     /// The boolean flag for the development mode.
     is_dev: bool,
+    /// The boolean flag indicating the router is draining its connections to shut down.
+    is_shutting_down: AtomicBool,
 }
 
 impl<N: Network> Router<N> {
+    /// The duration in seconds to wait for a hole punch to succeed.
+    const HOLE_PUNCH_TIMEOUT_IN_SECS: u64 = 5;
+    /// The maximum number of cached peers to reconnect to right away on startup.
+    const MAXIMUM_CACHED_RECONNECTIONS: usize = 8;
     /// The maximum number of candidate peers permitted to be stored in the node.
     const MAXIMUM_CANDIDATE_PEERS: usize = 10_000;
     /// The maximum number of connection failures permitted by an inbound connecting peer.
     const MAXIMUM_CONNECTION_FAILURES: usize = 5;
-    /// The duration in seconds after which a connected peer is considered inactive or
-    /// disconnected if no message has been received in the meantime.
+    /// The maximum number of unreachable peers permitted to be stored in the node.
+    const MAXIMUM_UNREACHABLE_PEERS: usize = 64;
+    /// The minimum interval in seconds between the writes of the peer cache, while the node is running.
+    const PEER_CACHE_INTERVAL_IN_SECS: u64 = 300;
+    /// The initial duration in seconds to wait before reconnecting to a pinned peer.
+    const PINNED_PEER_BACKOFF_BASE_IN_SECS: u64 = 1;
+    /// The maximum duration in seconds to wait before reconnecting to a pinned peer.
+    const PINNED_PEER_BACKOFF_MAX_IN_SECS: u64 = 300;
+    /// The duration in seconds for which a peer remains restricted, and over which
+    /// the inbound connection attempts of a peer are counted.
     const RADIO_SILENCE_IN_SECS: u64 = 150; // 2.5 minutes
 }
 
@@ -113,30 +161,52 @@ impl<N: Network> Router<N> {
     /// Initializes a new `Router` instance.
     pub async fn new(
         node_ip: SocketAddr,
+        listen_addrs: &[ListenAddr],
         node_type: NodeType,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
+        pinned_peers: &[SocketAddr],
         max_peers: u16,
+        keep_alive: KeepAlive,
+        relay_mode: RelayMode,
         allow_external_peers: bool,
         is_dev: bool,
     ) -> Result<Self> {
         // Initialize the TCP stack.
-        let tcp = Tcp::new(Config::new(node_ip, max_peers));
+        let mut config = Config::new(node_ip, max_peers);
+        config.keepalive_secs = keep_alive.tcp_keepalive().map(|keepalive| keepalive.as_secs() as u32);
+        // Share the listening port with the outbound connections, so that holes can be punched through the NAT.
+        config.reuse_port = relay_mode.is_hole_punching();
+        let tcp = Tcp::new(config);
         // Initialize the router.
         Ok(Self(Arc::new(InnerRouter {
             tcp,
+            listen_addrs: listen_addrs.to_vec(),
+            keep_alive,
+            relay_mode,
             node_type,
             account,
             cache: Default::default(),
+            compact_blocks: Default::default(),
             resolver: Default::default(),
             trusted_peers: trusted_peers.iter().copied().collect(),
+            pinned_peers: RwLock::new(pinned_peers.iter().map(|ip| (*ip, (0, Instant::now()))).collect()),
+            local_discovery: Default::default(),
             connected_peers: Default::default(),
             connecting_peers: Default::default(),
             candidate_peers: Default::default(),
             restricted_peers: Default::default(),
+            unreachable_peers: Default::default(),
+            offenses: Default::default(),
+            traffic: Default::default(),
+            peer_cache_path: Default::default(),
+            solution_limiter: Default::default(),
+            cached_peers: Default::default(),
+            stored_peer_cache: Default::default(),
             handles: Default::default(),
             allow_external_peers,
             is_dev,
+            is_shutting_down: Default::default(),
         })))
     }
 }
@@ -163,14 +233,107 @@ impl<N: Network> Router<N> {
                 Err(error) => {
                     router.connecting_peers.lock().remove(&peer_ip);
                     warn!("Unable to connect to '{peer_ip}' - {error}");
+                    // If the peer could not be reached, ask a relay to introduce it instead.
+                    if router.relay_mode.is_hole_punching() && error.kind() == std::io::ErrorKind::TimedOut {
+                        router.insert_unreachable_peer(peer_ip);
+                    }
                     false
                 }
             }
         }))
     }
 
-    /// Ensure we are allowed to connect to the given peer.
+    /// Attempts to connect to the given peer IP, which was introduced by a relay, by punching through the NAT.
+    /// Both peers dial each other at the same time; the initiator is chosen by the relay.
+    pub fn punch(&self, peer_ip: SocketAddr, is_initiator: bool) -> Option<JoinHandle<bool>> {
+        // Return early if the attempt is against the protocol rules.
+        // Note: As the responder, the peer is not marked as connecting, as the responder side of the handshake does it.
+        let result = match is_initiator {
+            true => self.check_connection_attempt(peer_ip),
+            false => self.check_connection_rules(peer_ip),
+        };
+        if let Err(forbidden_message) = result {
+            warn!("{forbidden_message}");
+            return None;
+        }
+        let side = match is_initiator {
+            true => ConnectionSide::Initiator,
+            false => ConnectionSide::Responder,
+        };
+
+        let router = self.clone();
+        Some(tokio::spawn(async move {
+            let timeout = Duration::from_secs(Self::HOLE_PUNCH_TIMEOUT_IN_SECS);
+            // Attempt to punch through to the introduced peer.
+            match router.tcp.punch(peer_ip, side, timeout).await {
+                Ok(()) => {
+                    debug!("Punched through to '{peer_ip}'");
+                    true
+                }
+                Err(error) => {
+                    if is_initiator {
+                        router.connecting_peers.lock().remove(&peer_ip);
+                    }
+                    warn!("Unable to punch through to '{peer_ip}' - {error}");
+                    false
+                }
+            }
+        }))
+    }
+
+    /// Attempts to connect to the given pinned peer, backing off exponentially upon failure.
+    pub fn connect_pinned_peer(&self, peer_ip: SocketAddr) {
+        // Attempt to connect to the pinned peer.
+        let Some(handle) = self.connect(peer_ip) else {
+            self.update_pinned_peer_backoff(peer_ip, false);
+            return;
+        };
+        // Update the backoff once the connection attempt completes.
+        let router = self.clone();
+        tokio::spawn(async move {
+            let is_connected = matches!(handle.await, Ok(true));
+            router.update_pinned_peer_backoff(peer_ip, is_connected);
+        });
+    }
+
+    /// Updates the reconnection state of the given pinned peer, based on the outcome of a connection attempt.
+    fn update_pinned_peer_backoff(&self, peer_ip: SocketAddr, is_connected: bool) {
+        if let Some((num_failures, next_attempt)) = self.pinned_peers.write().get_mut(&peer_ip) {
+            match is_connected {
+                true => *num_failures = 0,
+                false => {
+                    *num_failures = num_failures.saturating_add(1);
+                    let backoff = Self::pinned_peer_backoff(*num_failures);
+                    debug!("Retrying the pinned peer '{peer_ip}' in {} seconds", backoff.as_secs());
+                    *next_attempt = Instant::now() + backoff;
+                }
+            }
+        }
+    }
+
+    /// Returns the duration to wait before reconnecting to a pinned peer, given the number of failed attempts.
+    pub fn pinned_peer_backoff(num_failures: u32) -> Duration {
+        let backoff = Self::PINNED_PEER_BACKOFF_BASE_IN_SECS.saturating_mul(1u64 << num_failures.min(32));
+        Duration::from_secs(backoff.min(Self::PINNED_PEER_BACKOFF_MAX_IN_SECS))
+    }
+
+    /// Ensure we are allowed to connect to the given peer, and mark it as connecting.
     fn check_connection_attempt(&self, peer_ip: SocketAddr) -> Result<()> {
+        // Ensure the connection attempt follows the protocol rules.
+        self.check_connection_rules(peer_ip)?;
+        // Ensure the node is not already connecting to this peer.
+        if !self.connecting_peers.lock().insert(peer_ip) {
+            bail!("Dropping connection attempt to '{peer_ip}' (already shaking hands as the initiator)")
+        }
+        Ok(())
+    }
+
+    /// Ensure a connection to the given peer follows the protocol rules.
+    fn check_connection_rules(&self, peer_ip: SocketAddr) -> Result<()> {
+        // Ensure the node is not shutting down.
+        if self.is_shutting_down() {
+            bail!("Dropping connection attempt to '{peer_ip}' (shutting down)")
+        }
         // Ensure the peer IP is not this node.
         if self.is_local_ip(&peer_ip) {
             bail!("Dropping connection attempt to '{peer_ip}' (attempted to self-connect)")
@@ -187,10 +350,6 @@ impl<N: Network> Router<N> {
         if self.is_restricted(&peer_ip) {
             bail!("Dropping connection attempt to '{peer_ip}' (restricted)")
         }
-        // Ensure the node is not already connecting to this peer.
-        if !self.connecting_peers.lock().insert(peer_ip) {
-            bail!("Dropping connection attempt to '{peer_ip}' (already shaking hands as the initiator)")
-        }
         Ok(())
     }
 
@@ -219,10 +378,73 @@ impl<N: Network> Router<N> {
         self.tcp.listening_addr().expect("The TCP listener is not enabled")
     }
 
+    /// Returns the optional protocol features supported by this node, which are advertised during the handshake.
+    pub fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+            .with_flags(self.relay_mode.flags() | Capabilities::COMPACT_BLOCKS)
+            .with_quic_port(self.quic_port())
+    }
+
+    /// Returns the capabilities to advertise to a peer on the given message version.
+    /// Note: The capabilities are only advertised to the peers whose version supports them, as the older peers
+    /// reject the handshake messages that carry them.
+    pub fn capabilities_for(&self, peer_version: u32) -> Capabilities {
+        match peer_version >= Message::<N>::CAPABILITIES_VERSION {
+            true => self.capabilities(),
+            false => Capabilities::default(),
+        }
+    }
+
+    /// Returns the QUIC port of this node, if the QUIC transport is enabled.
+    pub fn quic_port(&self) -> Option<u16> {
+        #[cfg(feature = "quic")]
+        {
+            self.tcp.quic_addr().ok().map(|addr| addr.port())
+        }
+        #[cfg(not(feature = "quic"))]
+        {
+            None
+        }
+    }
+
+    /// Returns the additional listening addresses of this node.
+    pub fn listen_addrs(&self) -> &[ListenAddr] {
+        &self.listen_addrs
+    }
+
+    /// Returns the settings that keep the connections to the peers alive.
+    pub fn keep_alive(&self) -> &KeepAlive {
+        &self.keep_alive
+    }
+
+    /// Returns the settings for relaying introductions between peers.
+    pub fn relay_mode(&self) -> &RelayMode {
+        &self.relay_mode
+    }
+
+    /// Returns the pool of recently seen transactions, to reconstruct the announced compact blocks.
+    pub fn compact_blocks(&self) -> &CompactBlockPool<N> {
+        &self.compact_blocks
+    }
+
+    /// Returns the listening port to advertise to a peer, given the local address of the connection.
+    /// If the connection goes through an additional listener, or leaves through its interface,
+    /// its advertised port is returned; otherwise, the listening port of this node is returned.
+    pub fn advertised_port(&self, local_addr: SocketAddr) -> u16 {
+        self.listen_addrs
+            .iter()
+            .find(|listen_addr| listen_addr.accepts(local_addr))
+            .or_else(|| self.listen_addrs.iter().find(|listen_addr| listen_addr.bind().ip() == local_addr.ip()))
+            .map(|listen_addr| listen_addr.advertised_port())
+            .unwrap_or_else(|| self.local_ip().port())
+    }
+
     /// Returns `true` if the given IP is this node.
     pub fn is_local_ip(&self, ip: &SocketAddr) -> bool {
-        *ip == self.local_ip()
-            || (ip.ip().is_unspecified() || ip.ip().is_loopback()) && ip.port() == self.local_ip().port()
+        let is_local = |local_ip: SocketAddr| {
+            *ip == local_ip || (ip.ip().is_unspecified() || ip.ip().is_loopback()) && ip.port() == local_ip.port()
+        };
+        is_local(self.local_ip()) || self.listen_addrs.iter().any(|listen_addr| is_local(listen_addr.bind()))
     }
 
     /// Returns `true` if the given IP is not this node, is not a bogon address, and is not unspecified.
@@ -260,6 +482,16 @@ impl<N: Network> Router<N> {
         self.allow_external_peers
     }
 
+    /// Returns `true` if the router is draining its connections to shut down.
+    pub fn is_shutting_down(&self) -> bool {
+        self.is_shutting_down.load(Ordering::Acquire)
+    }
+
+    /// Marks the router as shutting down, so that no new connections or sync requests are accepted.
+    pub fn start_shutting_down(&self) {
+        self.is_shutting_down.store(true, Ordering::Release);
+    }
+
     /// Returns the listener IP address from the (ambiguous) peer address.
     pub fn resolve_to_listener(&self, peer_addr: &SocketAddr) -> Option<SocketAddr> {
         self.resolver.get_listener(peer_addr)
@@ -295,13 +527,20 @@ impl<N: Network> Router<N> {
         self.connecting_peers.lock().contains(ip)
     }
 
-    /// Returns `true` if the given IP is restricted.
+    /// Returns `true` if the given IP is restricted or banned.
+    /// Trusted and pinned peers are never banned.
     pub fn is_restricted(&self, ip: &SocketAddr) -> bool {
         self.restricted_peers
             .read()
             .get(ip)
             .map(|time| time.elapsed().as_secs() < Self::RADIO_SILENCE_IN_SECS)
             .unwrap_or(false)
+            || (self.offenses.is_banned(ip) && !self.is_trusted(ip) && !self.is_pinned(ip))
+    }
+
+    /// Returns `true` if the given IP is pinned.
+    pub fn is_pinned(&self, ip: &SocketAddr) -> bool {
+        self.pinned_peers.read().contains_key(ip)
     }
 
     /// Returns `true` if the given IP is trusted.
@@ -384,11 +623,39 @@ impl<N: Network> Router<N> {
         self.restricted_peers.read().keys().copied().collect()
     }
 
+    /// Returns the list of peers that could not be reached directly.
+    pub fn unreachable_peers(&self) -> Vec<SocketAddr> {
+        self.unreachable_peers.lock().iter().copied().collect()
+    }
+
+    /// Returns the offense records of the peers that served invalid data.
+    pub fn offenses(&self) -> HashMap<IpAddr, OffenseRecord> {
+        self.offenses.snapshot()
+    }
+
     /// Returns the list of trusted peers.
     pub fn trusted_peers(&self) -> &HashSet<SocketAddr> {
         &self.trusted_peers
     }
 
+    /// Returns the list of pinned peers.
+    pub fn pinned_peers(&self) -> Vec<SocketAddr> {
+        self.pinned_peers.read().keys().copied().collect()
+    }
+
+    /// Returns the list of disconnected pinned peers that are due for a reconnection attempt.
+    pub fn pinned_peers_to_reconnect(&self) -> Vec<SocketAddr> {
+        let now = Instant::now();
+        self.pinned_peers
+            .read()
+            .iter()
+            .filter(|(peer_ip, (_, next_attempt))| {
+                *next_attempt <= now && !self.is_connected(peer_ip) && !self.is_connecting(peer_ip)
+            })
+            .map(|(peer_ip, _)| *peer_ip)
+            .collect()
+    }
+
     /// Returns the list of bootstrap peers.
     #[allow(clippy::if_same_then_else)]
     pub fn bootstrap_peers(&self) -> Vec<SocketAddr> {
@@ -422,6 +689,33 @@ impl<N: Network> Router<N> {
         }
     }
 
+    /// Returns the smoothed round-trip time to the given connected peer, if it has been measured.
+    pub fn get_latency(&self, peer_ip: &SocketAddr) -> Option<Duration> {
+        self.connected_peers.read().get(peer_ip).and_then(|peer| peer.rtt())
+    }
+
+    /// Returns the list of connected peers and their smoothed round-trip times, if measured.
+    pub fn connected_latencies(&self) -> Vec<(SocketAddr, Option<Duration>)> {
+        self.connected_peers.read().iter().map(|(ip, peer)| (*ip, peer.rtt())).collect()
+    }
+
+    /// Returns the traffic exchanged with each connected peer, broken down by message type.
+    pub fn connected_traffic(&self) -> Vec<(SocketAddr, PeerTraffic)> {
+        self.connected_peers
+            .read()
+            .keys()
+            .map(|ip| {
+                let traffic = self.resolver.get_ambiguous(ip).and_then(|addr| self.traffic.get(&addr));
+                (*ip, traffic.unwrap_or_default())
+            })
+            .collect()
+    }
+
+    /// Returns a codec that records the traffic exchanged with the given (ambiguous) peer address.
+    pub fn traffic_codec(&self, peer_addr: SocketAddr) -> TrafficCodec<N> {
+        TrafficCodec::new(self.traffic.clone(), peer_addr)
+    }
+
     /// Returns the list of metrics for the connected peers.
     pub fn connected_metrics(&self) -> Vec<(SocketAddr, NodeType)> {
         self.connected_peers.read().iter().map(|(ip, peer)| (*ip, peer.node_type())).collect()
@@ -445,6 +739,8 @@ impl<N: Network> Router<N> {
         self.candidate_peers.write().remove(&peer_ip);
         // Remove this peer from the restricted peers, if it exists.
         self.restricted_peers.write().remove(&peer_ip);
+        // Remove this peer from the unreachable peers, if it exists.
+        self.unreachable_peers.lock().shift_remove(&peer_ip);
         #[cfg(feature = "metrics")]
         self.update_metrics();
     }
@@ -471,6 +767,22 @@ impl<N: Network> Router<N> {
         self.update_metrics();
     }
 
+    /// Inserts the given peer into the unreachable peers, evicting the oldest one if the set is full.
+    pub fn insert_unreachable_peer(&self, peer_ip: SocketAddr) {
+        let mut unreachable_peers = self.unreachable_peers.lock();
+        if unreachable_peers.len() >= Self::MAXIMUM_UNREACHABLE_PEERS {
+            unreachable_peers.shift_remove_index(0);
+        }
+        unreachable_peers.insert(peer_ip);
+    }
+
+    /// Removes up to `num_peers` of the oldest unreachable peers, returning them.
+    pub fn take_unreachable_peers(&self, num_peers: usize) -> Vec<SocketAddr> {
+        let mut unreachable_peers = self.unreachable_peers.lock();
+        let num_peers = num_peers.min(unreachable_peers.len());
+        unreachable_peers.drain(..num_peers).collect()
+    }
+
     /// Inserts the given peer into the restricted peers.
     pub fn insert_restricted_peer(&self, peer_ip: SocketAddr) {
         // Remove this peer from the candidate peers, if it exists.
@@ -481,6 +793,22 @@ impl<N: Network> Router<N> {
         self.update_metrics();
     }
 
+    /// Records the given offense by the peer, and returns the resulting penalty.
+    /// If the peer is banned, its connection attempts are refused until the ban expires.
+    /// The offenses of trusted and pinned peers are not recorded.
+    pub fn insert_offense(&self, peer_ip: SocketAddr, offense: Offense) -> Penalty {
+        if self.is_trusted(&peer_ip) || self.is_pinned(&peer_ip) {
+            return Penalty::Score;
+        }
+        let penalty = self.offenses.insert(peer_ip, offense);
+        if penalty.is_ban() {
+            warn!("Banning '{peer_ip}' for serving {offense} ({penalty})");
+            // Remove this peer from the candidate peers, if it exists.
+            self.candidate_peers.write().remove(&peer_ip);
+        }
+        penalty
+    }
+
     /// Updates the connected peer with the given function.
     pub fn update_connected_peer<Fn: FnMut(&mut Peer<N>)>(
         &self,
@@ -506,8 +834,32 @@ impl<N: Network> Router<N> {
         }
     }
 
+    /// Records that a `Ping` was sent to the given connected peer.
+    pub fn update_ping_sent_for_connected_peer(&self, peer_ip: SocketAddr) {
+        if let Some(peer) = self.connected_peers.write().get_mut(&peer_ip) {
+            peer.set_ping_sent(Instant::now());
+        }
+    }
+
+    /// Records that a `Pong` was received from the given connected peer,
+    /// returning the smoothed round-trip time to the peer, if it has been measured.
+    pub fn update_pong_received_for_connected_peer(&self, peer_ip: SocketAddr) -> Option<Duration> {
+        let mut connected_peers = self.connected_peers.write();
+        let peer = connected_peers.get_mut(&peer_ip)?;
+        peer.record_pong(Instant::now());
+        peer.rtt()
+    }
+
     /// Removes the connected peer and adds them to the candidate peers.
     pub fn remove_connected_peer(&self, peer_ip: SocketAddr) {
+        // Record the metadata of this peer as of its last connection.
+        if let Some(peer) = self.connected_peers.read().get(&peer_ip) {
+            self.cached_peers.write().insert(peer_ip, CachedPeer::from(peer));
+        }
+        // Removes the traffic exchanged with this peer.
+        if let Some(peer_addr) = self.resolver.get_ambiguous(&peer_ip) {
+            self.traffic.remove(&peer_addr);
+        }
         // Removes the bidirectional map between the listener address and (ambiguous) peer address.
         self.resolver.remove_peer(&peer_ip);
         // Remove this peer from the connected peers, if it exists.
@@ -532,6 +884,113 @@ impl<N: Network> Router<N> {
         self.update_metrics();
     }
 
+    /// Enables the peer cache at the given path, which persists the known peers across restarts.
+    /// The peers cached by a previous run are restored, and the most recently connected ones are reconnected to.
+    pub fn enable_peer_cache(&self, path: PathBuf) {
+        // Load the peer cache of the previous run, if it exists.
+        if path.exists() {
+            match PeerCache::<N>::load(&path) {
+                Ok(cache) if cache.address() != self.address() => {
+                    warn!("Ignoring the peer cache at {}, as it belongs to {}", path.display(), cache.address())
+                }
+                Ok(cache) => self.restore_peer_cache(cache),
+                Err(error) => warn!("{error}"),
+            }
+        }
+        *self.peer_cache_path.write() = Some(path);
+    }
+
+    /// Enables the rate limits on the unconfirmed solutions accepted from peers.
+    pub fn enable_solution_rate_limit(&self, limit: SolutionRateLimit) {
+        *self.solution_limiter.write() = Some(Arc::new(SolutionLimiter::new(limit)));
+    }
+
+    /// Records an unconfirmed solution from the given peer, and returns whether it is within the rate limits.
+    pub fn check_solution_rate(&self, peer_ip: SocketAddr) -> SolutionRate {
+        match self.solution_limiter.read().as_ref() {
+            Some(limiter) => limiter.check(peer_ip),
+            None => SolutionRate::Accepted,
+        }
+    }
+
+    /// Restores the known peers from the given peer cache, and reconnects to the most recently connected ones.
+    fn restore_peer_cache(&self, cache: PeerCache<N>) {
+        if cache.listener_ip() != self.local_ip() {
+            info!(
+                "The listening address changed from '{}' to '{}' since the last run",
+                cache.listener_ip(),
+                self.local_ip()
+            );
+        }
+        info!("Restoring {} known peer(s) from the peer cache", cache.peers().len());
+
+        // Determine the peers that are eligible for a connection, from the most to the least recently connected.
+        let eligible_peers = cache
+            .peers()
+            .iter()
+            .map(|peer| peer.ip)
+            .filter(|ip| !self.is_local_ip(ip) && !self.is_connected(ip) && !self.is_restricted(ip))
+            .filter(|ip| self.allow_external_peers() || self.is_trusted(ip) || self.is_pinned(ip))
+            .collect::<Vec<_>>();
+        self.cached_peers.write().extend(cache.peers().iter().map(|peer| (peer.ip, peer.clone())));
+
+        // Reconnect to the most recently connected peers, and keep the others as candidates.
+        let (reconnect_peers, candidate_peers) =
+            eligible_peers.split_at(Self::MAXIMUM_CACHED_RECONNECTIONS.min(eligible_peers.len()));
+        for peer_ip in reconnect_peers {
+            self.connect(*peer_ip);
+        }
+        self.insert_candidate_peers(candidate_peers);
+    }
+
+    /// Returns the peer cache of the known peers, after refreshing the metadata of the connected peers.
+    fn peer_cache(&self) -> PeerCache<N> {
+        let mut cached_peers = self.cached_peers.write();
+        // Refresh the metadata of the connected peers.
+        for peer in self.connected_peers.read().values() {
+            cached_peers.insert(peer.ip(), CachedPeer::from(peer));
+        }
+        let cache = PeerCache::new(self.address(), self.local_ip(), cached_peers.values().cloned().collect());
+        // Forget the peers that no longer fit in the cache.
+        *cached_peers = cache.peers().iter().map(|peer| (peer.ip, peer.clone())).collect();
+        cache
+    }
+
+    /// Writes the known peers to the peer cache in the background, if it is enabled, the known peers changed
+    /// since the last write, and at least `PEER_CACHE_INTERVAL_IN_SECS` elapsed since then.
+    pub fn update_peer_cache(&self) {
+        let Some(path) = self.peer_cache_path.read().clone() else {
+            return;
+        };
+        let cache = self.peer_cache();
+        {
+            let mut stored_peer_cache = self.stored_peer_cache.lock();
+            if let Some((timestamp, stored)) = &*stored_peer_cache {
+                if timestamp.elapsed().as_secs() < Self::PEER_CACHE_INTERVAL_IN_SECS || stored.has_same_peers(&cache) {
+                    return;
+                }
+            }
+            *stored_peer_cache = Some((Instant::now(), cache.clone()));
+        }
+        tokio::task::spawn_blocking(move || {
+            if let Err(error) = cache.store(&path) {
+                warn!("{error}");
+            }
+        });
+    }
+
+    /// Stores the known peers to the peer cache, if it is enabled.
+    pub fn store_peer_cache(&self) {
+        let Some(path) = self.peer_cache_path.read().clone() else {
+            return;
+        };
+        let cache = self.peer_cache();
+        if let Err(error) = cache.store(&path) {
+            warn!("{error}");
+        }
+        *self.stored_peer_cache.lock() = Some((Instant::now(), cache));
+    }
+
     /// Spawns a task with the given future; it should only be used for long-running tasks.
     pub fn spawn<T: Future<Output = ()> + Send + 'static>(&self, future: T) {
         self.handles.lock().push(tokio::spawn(future));
@@ -540,6 +999,12 @@ impl<N: Network> Router<N> {
     /// Shuts down the router.
     pub async fn shut_down(&self) {
         info!("Shutting down the router...");
+        // Stop accepting new connections.
+        self.start_shutting_down();
+        // Stop the local peer discovery.
+        self.disable_local_discovery();
+        // Persist the known peers.
+        self.store_peer_cache();
         // Abort the tasks.
         self.handles.lock().iter().for_each(|handle| handle.abort());
         // Close the listener.
@@ -13,12 +13,12 @@
 // limitations under the License.
 
 use crate::{
-    messages::{Message, Ping},
+    messages::{Capabilities, CompactBlock, Message, Ping},
     Router,
 };
 use snarkos_node_sync_locators::BlockLocators;
-use snarkos_node_tcp::protocols::Writing;
-use snarkvm::prelude::Network;
+use snarkos_node_tcp::protocols::{MessagePriority, Writing};
+use snarkvm::prelude::{block::Block, Network};
 use std::io;
 
 use std::net::SocketAddr;
@@ -36,7 +36,10 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
 
     /// Sends a "Ping" message to the given peer.
     fn send_ping(&self, peer_ip: SocketAddr, block_locators: Option<BlockLocators<N>>) {
-        self.send(peer_ip, Message::Ping(Ping::new(self.router().node_type(), block_locators)));
+        if self.send(peer_ip, Message::Ping(Ping::new(self.router().node_type(), block_locators))).is_some() {
+            // Record the time the ping was sent, to measure the round-trip time upon the `Pong`.
+            self.router().update_ping_sent_for_connected_peer(peer_ip);
+        }
     }
 
     /// Sends the given message to specified peer.
@@ -69,6 +72,10 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         if matches!(message, Message::PeerRequest(_)) {
             self.router().cache.increment_outbound_peer_requests(peer_ip);
         }
+        // If the message type is a relay request, increment the cache.
+        if matches!(message, Message::RelayRequest(_)) {
+            self.router().cache.increment_outbound_relay_requests(peer_ip);
+        }
         // Retrieve the message name.
         let name = message.name();
         // Send the message to the peer.
@@ -111,6 +118,21 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         }
     }
 
+    /// Announces the given block as a compact block to every connected client that supports it,
+    /// excluding the specified peer IPs.
+    fn announce_block(&self, block: &Block<N>, excluded_peers: &[SocketAddr]) {
+        let message = Message::CompactBlock(CompactBlock::from(block));
+        // Iterate through the connected clients that support compact blocks.
+        for peer in self.router().get_connected_peers() {
+            if peer.is_client()
+                && peer.capabilities().supports(Capabilities::COMPACT_BLOCKS)
+                && !excluded_peers.contains(&peer.ip())
+            {
+                self.send(peer.ip(), message.clone());
+            }
+        }
+    }
+
     /// Sends the given message to every connected validator, excluding the sender and any specified IPs.
     fn propagate_to_validators(&self, message: Message<N>, excluded_peers: &[SocketAddr]) {
         // TODO (howardwu): Serialize large messages once only.
@@ -166,3 +188,34 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         }
     }
 }
+
+/// Returns the outbound queue priority of the given message.
+///
+/// Handshake and block response messages are sent first, so that sync and connection
+/// management are not starved by gossip traffic; peer exchange, pings, and transaction
+/// gossip yield to everything else. A disconnect is queued behind the pending messages,
+/// so that they are flushed before it. Relay introductions are time-sensitive,
+/// as both sides of a hole punch must dial each other at the same time, and so is the
+/// propagation of compact blocks.
+pub fn message_priority<N: Network>(message: &Message<N>) -> MessagePriority {
+    match message {
+        Message::BlockResponse(..)
+        | Message::ChallengeRequest(..)
+        | Message::ChallengeResponse(..)
+        | Message::RelayIntroduction(..)
+        | Message::CompactBlock(..)
+        | Message::BlockTransactionsResponse(..) => MessagePriority::High,
+        Message::BlockRequest(..)
+        | Message::BlockTransactionsRequest(..)
+        | Message::PuzzleRequest(..)
+        | Message::PuzzleResponse(..)
+        | Message::RelayRequest(..)
+        | Message::UnconfirmedSolution(..) => MessagePriority::Normal,
+        Message::Disconnect(..)
+        | Message::PeerRequest(..)
+        | Message::PeerResponse(..)
+        | Message::Ping(..)
+        | Message::Pong(..)
+        | Message::UnconfirmedTransaction(..) => MessagePriority::Low,
+    }
+}
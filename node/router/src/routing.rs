@@ -12,7 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{Heartbeat, Inbound, Outbound};
+use crate::{
+    messages::{DisconnectReason, Message},
+    Heartbeat,
+    Inbound,
+    Outbound,
+};
 use snarkos_node_tcp::{
     protocols::{Disconnect, Handshake, OnConnect},
     P2P,
@@ -25,6 +30,11 @@ use core::time::Duration;
 pub trait Routing<N: Network>:
     P2P + Disconnect + OnConnect + Handshake + Inbound<N> + Outbound<N> + Heartbeat<N>
 {
+    /// The maximum duration in seconds to wait for the outbound queues to flush on shutdown.
+    const DRAIN_TIMEOUT_IN_SECS: u64 = 3;
+    /// The duration in seconds to sleep in between checks on the pinned peers.
+    const PINNED_PEERS_INTERVAL_IN_SECS: u64 = 1;
+
     /// Initialize the routing.
     async fn initialize_routing(&self) {
         // Enable the TCP protocols.
@@ -37,11 +47,23 @@ pub trait Routing<N: Network>:
         self.enable_listener().await;
         // Initialize the heartbeat.
         self.initialize_heartbeat();
+        // Initialize the pinned peers.
+        self.initialize_pinned_peers();
     }
 
     // Start listening for inbound connections.
     async fn enable_listener(&self) {
         self.tcp().enable_listener().await.expect("Failed to enable the TCP listener");
+        // Start the QUIC endpoint.
+        #[cfg(feature = "quic")]
+        self.tcp().enable_quic().await.expect("Failed to enable the QUIC endpoint");
+        // Start listening on the additional addresses.
+        for listen_addr in self.router().listen_addrs() {
+            self.tcp()
+                .enable_additional_listener(listen_addr.bind())
+                .await
+                .expect("Failed to enable an additional TCP listener");
+        }
     }
 
     /// Initialize a new instance of the heartbeat.
@@ -51,9 +73,55 @@ pub trait Routing<N: Network>:
             loop {
                 // Process a heartbeat in the router.
                 self_clone.heartbeat();
-                // Sleep for `HEARTBEAT_IN_SECS` seconds.
-                tokio::time::sleep(Duration::from_secs(Self::HEARTBEAT_IN_SECS)).await;
+                // Sleep until the next heartbeat.
+                tokio::time::sleep(self_clone.router().keep_alive().heartbeat_interval()).await;
+            }
+        });
+    }
+
+    /// Initialize a new instance of the loop that keeps the pinned peers connected.
+    fn initialize_pinned_peers(&self) {
+        // Skip if there are no pinned peers.
+        if self.router().pinned_peers().is_empty() {
+            return;
+        }
+        let self_clone = self.clone();
+        self.router().spawn(async move {
+            loop {
+                // Reconnect to the disconnected pinned peers that are due for a reconnection attempt.
+                for peer_ip in self_clone.router().pinned_peers_to_reconnect() {
+                    self_clone.router().connect_pinned_peer(peer_ip);
+                }
+                // Sleep for `PINNED_PEERS_INTERVAL_IN_SECS` seconds.
+                tokio::time::sleep(Duration::from_secs(Self::PINNED_PEERS_INTERVAL_IN_SECS)).await;
             }
         });
     }
+
+    /// Gracefully drains the connections, prior to shutting down.
+    /// This stops accepting new connections and sync requests, notifies every connected peer
+    /// that the node is shutting down, and waits (up to a timeout) for the outbound queues to flush.
+    async fn drain_connections(&self) {
+        // Stop accepting new connections and sync requests.
+        self.router().start_shutting_down();
+
+        // Notify the connected peers that this node is shutting down.
+        let connected_peers = self.router().connected_peers();
+        debug!("Draining connections to {} peers...", connected_peers.len());
+        let receivers = connected_peers
+            .into_iter()
+            .filter_map(|peer_ip| self.send(peer_ip, Message::Disconnect(DisconnectReason::ShuttingDown.into())))
+            .collect::<Vec<_>>();
+
+        // Wait for the disconnect messages (and any previously-queued messages) to be flushed.
+        let timeout = Duration::from_secs(Self::DRAIN_TIMEOUT_IN_SECS);
+        if tokio::time::timeout(timeout, futures::future::join_all(receivers)).await.is_err() {
+            warn!("Timed out while draining the connections to the peers");
+        }
+
+        // Disconnect from all peers.
+        for peer_ip in self.router().connected_peers() {
+            let _ = self.router().disconnect(peer_ip).await;
+        }
+    }
 }
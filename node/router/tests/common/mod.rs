@@ -23,7 +23,7 @@ use std::{
 };
 
 use snarkos_account::Account;
-use snarkos_node_router::{messages::NodeType, Router};
+use snarkos_node_router::{messages::NodeType, KeepAlive, RelayMode, Router};
 use snarkvm::prelude::{block::Block, FromBytes, MainnetV0 as CurrentNetwork, Network};
 
 /// A helper macro to print the TCP listening address, along with the connected and connecting peers.
@@ -73,10 +73,14 @@ pub fn initialize_logger(level: u8) {
 pub async fn client(listening_port: u16, max_peers: u16) -> TestRouter<CurrentNetwork> {
     Router::new(
         SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), listening_port),
+        &[],
         NodeType::Client,
         sample_account(),
         &[],
+        &[],
         max_peers,
+        KeepAlive::default(),
+        RelayMode::default(),
         true,
         true,
     )
@@ -90,10 +94,14 @@ pub async fn client(listening_port: u16, max_peers: u16) -> TestRouter<CurrentNe
 pub async fn prover(listening_port: u16, max_peers: u16) -> TestRouter<CurrentNetwork> {
     Router::new(
         SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), listening_port),
+        &[],
         NodeType::Prover,
         sample_account(),
         &[],
+        &[],
         max_peers,
+        KeepAlive::default(),
+        RelayMode::default(),
         true,
         true,
     )
@@ -112,10 +120,14 @@ pub async fn validator(
 ) -> TestRouter<CurrentNetwork> {
     Router::new(
         SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), listening_port),
+        &[],
         NodeType::Validator,
         sample_account(),
         trusted_peers,
+        &[],
         max_peers,
+        KeepAlive::default(),
+        RelayMode::default(),
         allow_external_peers,
         true,
     )
@@ -14,24 +14,17 @@
 
 use crate::common::sample_genesis_block;
 use snarkos_node_router::{
-    messages::{
-        BlockRequest,
-        DisconnectReason,
-        Message,
-        MessageCodec,
-        Ping,
-        Pong,
-        UnconfirmedSolution,
-        UnconfirmedTransaction,
-    },
+    message_priority,
+    messages::{BlockRequest, DisconnectReason, Message, Ping, Pong, UnconfirmedSolution, UnconfirmedTransaction},
     Heartbeat,
     Inbound,
     Outbound,
     Router,
     Routing,
+    TrafficCodec,
 };
 use snarkos_node_tcp::{
-    protocols::{Disconnect, Handshake, OnConnect, Reading, Writing},
+    protocols::{Disconnect, Handshake, MessagePriority, OnConnect, Reading, Writing},
     Connection,
     ConnectionSide,
     Tcp,
@@ -109,25 +102,30 @@ impl<N: Network> Disconnect for TestRouter<N> {
 
 #[async_trait]
 impl<N: Network> Writing for TestRouter<N> {
-    type Codec = MessageCodec<N>;
+    type Codec = TrafficCodec<N>;
     type Message = Message<N>;
 
     /// Creates an [`Encoder`] used to write the outbound messages to the target stream.
     /// The `side` parameter indicates the connection side **from the node's perspective**.
-    fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router().traffic_codec(peer_addr)
+    }
+
+    /// Returns the outbound queue priority of the given message.
+    fn priority(&self, message: &Self::Message) -> MessagePriority {
+        message_priority(message)
     }
 }
 
 #[async_trait]
 impl<N: Network> Reading for TestRouter<N> {
-    type Codec = MessageCodec<N>;
+    type Codec = TrafficCodec<N>;
     type Message = Message<N>;
 
     /// Creates a [`Decoder`] used to interpret messages from the network.
     /// The `side` param indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router().traffic_codec(peer_addr)
     }
 
     /// Processes a message received from the network.
@@ -255,3 +255,35 @@ async fn test_connect_simultaneously_with_handshake() {
         assert_eq!(node1.number_of_connected_peers(), 1);
     }
 }
+
+#[test]
+fn test_pinned_peer_backoff() {
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+    type Router = snarkos_node_router::Router<CurrentNetwork>;
+
+    // Ensure the backoff grows exponentially with the number of failures.
+    assert_eq!(Router::pinned_peer_backoff(0), Duration::from_secs(1));
+    assert_eq!(Router::pinned_peer_backoff(1), Duration::from_secs(2));
+    assert_eq!(Router::pinned_peer_backoff(4), Duration::from_secs(16));
+    // Ensure the backoff is capped.
+    assert_eq!(Router::pinned_peer_backoff(9), Duration::from_secs(300));
+    assert_eq!(Router::pinned_peer_backoff(u32::MAX), Duration::from_secs(300));
+}
+
+#[tokio::test]
+async fn test_punch_applies_connection_rules() {
+    // Create 2 routers.
+    let node0 = client(0, 2).await;
+    let node1 = client(0, 2).await;
+    node0.tcp().enable_listener().await.unwrap();
+    node1.tcp().enable_listener().await.unwrap();
+
+    // Ensure a restricted peer is refused on both sides of a hole punch.
+    node0.insert_restricted_peer(node1.local_ip());
+    assert!(node0.punch(node1.local_ip(), true).is_none());
+    assert!(node0.punch(node1.local_ip(), false).is_none());
+
+    // Ensure the node never punches through to itself, on either side.
+    assert!(node1.punch(node1.local_ip(), true).is_none());
+    assert!(node1.punch(node1.local_ip(), false).is_none());
+}
@@ -15,7 +15,11 @@
 mod common;
 use common::*;
 
-use snarkos_node_tcp::{protocols::Handshake, P2P};
+use snarkos_node_router::Routing;
+use snarkos_node_tcp::{
+    protocols::{Disconnect, Handshake, Reading, Writing},
+    P2P,
+};
 
 use core::time::Duration;
 
@@ -108,3 +112,45 @@ async fn test_disconnect_with_handshake() {
     assert_eq!(node1.tcp().num_connected(), 1); // Router 1 has no way of knowing that Router 0 disconnected.
     assert_eq!(node1.tcp().num_connecting(), 0);
 }
+
+#[tokio::test]
+async fn test_drain_connections() {
+    // Create 2 routers.
+    let node0 = validator(0, 1, &[], true).await;
+    let node1 = client(0, 1).await;
+
+    // Enable the protocols.
+    for node in [&node0, &node1] {
+        node.enable_handshake().await;
+        node.enable_reading().await;
+        node.enable_writing().await;
+        node.enable_disconnect().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Check the router level.
+    assert_eq!(node0.number_of_connected_peers(), 1);
+    assert_eq!(node1.number_of_connected_peers(), 1);
+
+    // Drain the connections of node0.
+    node0.drain_connections().await;
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    print_tcp!(node0);
+    print_tcp!(node1);
+
+    // Ensure both sides have disconnected.
+    assert!(node0.is_shutting_down());
+    assert_eq!(node0.number_of_connected_peers(), 0);
+    assert_eq!(node1.number_of_connected_peers(), 0);
+    // Ensure node1 did not treat the shutdown as misbehavior.
+    assert!(!node1.is_restricted(&node0.local_ip()));
+    // Ensure node0 no longer accepts new connections.
+    assert!(node0.connect(node1.local_ip()).is_none());
+}
@@ -0,0 +1,103 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_router::{
+    messages::{Message, NodeType, Ping},
+    Routing,
+};
+use snarkos_node_tcp::{
+    protocols::{Disconnect, Handshake, Reading, Writing},
+    Faults,
+    P2P,
+};
+use snarkvm::prelude::MainnetV0 as CurrentNetwork;
+
+use core::time::Duration;
+use std::time::Instant;
+
+/// Returns 2 connected routers, with all of their protocols enabled.
+async fn connected_routers() -> (TestRouter<CurrentNetwork>, TestRouter<CurrentNetwork>) {
+    // Create 2 routers.
+    let node0 = validator(0, 1, &[], true).await;
+    let node1 = client(0, 1).await;
+
+    // Enable the protocols.
+    for node in [&node0, &node1] {
+        node.enable_handshake().await;
+        node.enable_reading().await;
+        node.enable_writing().await;
+        node.enable_disconnect().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    assert_eq!(node0.number_of_connected_peers(), 1);
+    assert_eq!(node1.number_of_connected_peers(), 1);
+
+    (node0, node1)
+}
+
+#[tokio::test]
+async fn test_injected_latency() {
+    let (node0, node1) = connected_routers().await;
+
+    // Delay the messages from node0 to node1.
+    let latency = Duration::from_millis(300);
+    node0.tcp().faults().insert(node1.local_ip(), Faults::latency(latency));
+
+    // Ensure the message is only delivered after the latency.
+    let start = Instant::now();
+    let ping = Message::Ping(Ping::new(NodeType::Validator, None));
+    node0.unicast(node1.local_ip(), ping).unwrap().await.unwrap().unwrap();
+    assert!(start.elapsed() >= latency);
+}
+
+#[tokio::test]
+async fn test_injected_drop() {
+    let (node0, node1) = connected_routers().await;
+    let (num_received, _) = node1.tcp().stats().received();
+
+    // Drop all messages from node0 to node1.
+    node0.tcp().faults().insert(node1.local_ip(), Faults::drop_rate(1.0));
+
+    // Ensure the message is reported as delivered, but never arrives.
+    let ping = Message::Ping(Ping::new(NodeType::Validator, None));
+    node0.unicast(node1.local_ip(), ping).unwrap().await.unwrap().unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(node1.tcp().stats().received().0, num_received);
+    // Ensure the connection is unaffected.
+    assert_eq!(node0.number_of_connected_peers(), 1);
+}
+
+#[tokio::test]
+async fn test_injected_disconnect() {
+    let (node0, node1) = connected_routers().await;
+
+    // Sever the connection from node0 to node1 on the next message.
+    node0.tcp().faults().insert(node1.local_ip(), Faults::disconnect_rate(1.0));
+
+    // Ensure the message fails, and the connection is severed.
+    let ping = Message::Ping(Ping::new(NodeType::Validator, None));
+    assert!(node0.unicast(node1.local_ip(), ping).unwrap().await.unwrap().is_err());
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(node0.number_of_connected_peers(), 0);
+    assert_eq!(node1.number_of_connected_peers(), 0);
+}
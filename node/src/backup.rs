@@ -0,0 +1,224 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::console::network::Network;
+
+use anyhow::{anyhow, ensure, Result};
+use rocksdb::{Options, WriteBatch, DB};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{BufReader, Read, Write},
+    path::Path,
+};
+use zstd::stream::{read::Decoder, write::Encoder};
+
+/// The magic bytes at the start of every backup archive.
+const BACKUP_MAGIC: &[u8; 8] = b"ALEOBKUP";
+/// The version of the backup archive format.
+const BACKUP_VERSION: u8 = 1;
+/// The number of entries written to a database in a single batch.
+const BATCH_SIZE: usize = 10_000;
+/// The zstd compression level of backup archives.
+const COMPRESSION_LEVEL: i32 = 3;
+/// The maximum size of the manifest, in bytes.
+const MAXIMUM_MANIFEST_SIZE: u32 = 64 * 1024;
+
+/// The metadata of a ledger backup.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct BackupManifest<N: Network> {
+    /// The network ID of the ledger.
+    pub network: u16,
+    /// The height of the latest block in the backup.
+    pub height: u32,
+    /// The hash of the latest block in the backup.
+    pub hash: N::BlockHash,
+    /// The version of snarkOS that took the backup.
+    pub version: String,
+    /// The UNIX timestamp at which the backup was taken.
+    pub timestamp: i64,
+    /// The state digest at the latest block, if one was computed (see `StateDigest`).
+    #[serde(default)]
+    pub digest: Option<String>,
+}
+
+/// Writes the manifest and then the given database entries as a backup archive to the given writer.
+/// Every key and value is length-prefixed, and an empty key marks the end of the entries.
+pub fn write_backup<N: Network>(
+    entries: impl Iterator<Item = Result<(Box<[u8]>, Box<[u8]>)>>,
+    manifest: &BackupManifest<N>,
+    writer: impl Write,
+) -> Result<()> {
+    let mut encoder = Encoder::new(writer, COMPRESSION_LEVEL)?;
+    // Checksum the archive, so that a corrupted one is rejected when it is restored.
+    encoder.include_checksum(true)?;
+    encoder.write_all(BACKUP_MAGIC)?;
+    encoder.write_all(&[BACKUP_VERSION])?;
+    write_bytes(&mut encoder, &serde_json::to_vec(manifest)?)?;
+    for entry in entries {
+        let (key, value) = entry?;
+        ensure!(!key.is_empty(), "The ledger contains an empty key");
+        write_bytes(&mut encoder, &key)?;
+        write_bytes(&mut encoder, &value)?;
+    }
+    write_bytes(&mut encoder, &[])?;
+    Ok(encoder.finish()?.flush()?)
+}
+
+/// Writes the given length-prefixed bytes.
+fn write_bytes(writer: &mut impl Write, bytes: &[u8]) -> Result<()> {
+    writer.write_all(&u32::try_from(bytes.len())?.to_le_bytes())?;
+    Ok(writer.write_all(bytes)?)
+}
+
+/// A reader of a backup archive, which reads its manifest first, and then unpacks its entries.
+pub struct BackupReader<N: Network, R: Read = File> {
+    /// The decompressed archive.
+    decoder: Decoder<'static, BufReader<R>>,
+    /// The manifest of the backup.
+    manifest: BackupManifest<N>,
+}
+
+impl<N: Network> BackupReader<N> {
+    /// Opens the backup archive at the given path, and reads its manifest.
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path)
+            .map_err(|err| anyhow!("Couldn't open the backup archive at {} - {err}", path.display()))?;
+        Self::new(file).map_err(|err| anyhow!("Couldn't read the backup archive at {} - {err}", path.display()))
+    }
+}
+
+impl<N: Network, R: Read> BackupReader<N, R> {
+    /// Reads the manifest of the backup archive from the given reader.
+    pub fn new(reader: R) -> Result<Self> {
+        let mut decoder = Decoder::new(reader)?;
+        let mut header = [0u8; 9];
+        decoder.read_exact(&mut header).map_err(|_| anyhow!("This is not a backup archive"))?;
+        ensure!(&header[..8] == BACKUP_MAGIC, "This is not a backup archive");
+        ensure!(header[8] == BACKUP_VERSION, "Unsupported backup archive version ({})", header[8]);
+
+        let manifest = read_bytes(&mut decoder, MAXIMUM_MANIFEST_SIZE)?;
+        let manifest = serde_json::from_slice(&manifest)
+            .map_err(|err| anyhow!("Couldn't deserialize the manifest of the backup - {err}"))?;
+        Ok(Self { decoder, manifest })
+    }
+
+    /// Returns the manifest of the backup.
+    pub const fn manifest(&self) -> &BackupManifest<N> {
+        &self.manifest
+    }
+
+    /// Unpacks the entries of the backup into a new database with the given options in the given directory.
+    pub fn unpack(mut self, dir: &Path, options: Options) -> Result<()> {
+        let decoder = &mut self.decoder;
+        let entries = std::iter::from_fn(move || match read_bytes(decoder, u32::MAX) {
+            Ok(key) if key.is_empty() => None,
+            Ok(key) => Some(read_bytes(decoder, u32::MAX).map(|value| (key.into(), value.into()))),
+            Err(error) => Some(Err(error)),
+        });
+        copy_entries(entries, dir, options)?;
+        // Read to the end, so that the checksum of the archive is verified.
+        let mut trailing = Vec::new();
+        self.decoder.read_to_end(&mut trailing)?;
+        ensure!(trailing.is_empty(), "The backup archive has trailing data");
+        Ok(())
+    }
+}
+
+/// Reads length-prefixed bytes, up to the given maximum length.
+fn read_bytes(reader: &mut impl Read, maximum_length: u32) -> Result<Vec<u8>> {
+    let mut length = [0u8; 4];
+    reader.read_exact(&mut length).map_err(|_| anyhow!("The backup archive is truncated"))?;
+    let length = u32::from_le_bytes(length);
+    ensure!(length <= maximum_length, "The backup archive contains an oversized entry");
+    let mut bytes = vec![0u8; length as usize];
+    reader.read_exact(&mut bytes).map_err(|_| anyhow!("The backup archive is truncated"))?;
+    Ok(bytes)
+}
+
+/// Writes the given entries into a new database with the given options in the given directory.
+pub fn copy_entries(
+    entries: impl Iterator<Item = Result<(Box<[u8]>, Box<[u8]>)>>,
+    dir: &Path,
+    mut options: Options,
+) -> Result<()> {
+    if let Some(parent) = dir.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    options.create_if_missing(true);
+    options.set_error_if_exists(true);
+    let database =
+        DB::open(&options, dir).map_err(|err| anyhow!("Couldn't create the database at {} - {err}", dir.display()))?;
+    let mut batch = WriteBatch::default();
+    for entry in entries {
+        let (key, value) = entry?;
+        batch.put(key, value);
+        if batch.len() >= BATCH_SIZE {
+            database.write(std::mem::take(&mut batch))?;
+        }
+    }
+    database.write(batch)?;
+    Ok(database.flush()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{Block, FromBytes, MainnetV0};
+
+    use rand::Rng;
+    use rocksdb::IteratorMode;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_backup_round_trip() {
+        let root = std::env::temp_dir().join(format!("snarkos-backup-{}", rand::thread_rng().gen::<u64>()));
+        let entries: Vec<(Box<[u8]>, Box<[u8]>)> =
+            (0u64..1_000).map(|key| (key.to_be_bytes().into(), vec![1u8; 64].into())).collect();
+
+        // Write the entries to an archive.
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let manifest = BackupManifest::<CurrentNetwork> {
+            network: CurrentNetwork::ID,
+            height: 0,
+            hash: genesis.hash(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            timestamp: 0,
+            digest: None,
+        };
+        let mut archive = Vec::new();
+        write_backup(entries.clone().into_iter().map(Ok), &manifest, &mut archive).unwrap();
+
+        // Ensure the archive unpacks into the same entries.
+        let reader = BackupReader::<CurrentNetwork, _>::new(&archive[..]).unwrap();
+        assert_eq!(reader.manifest(), &manifest);
+        reader.unpack(&root.join("restored"), Options::default()).unwrap();
+        let database = DB::open_for_read_only(&Options::default(), root.join("restored"), false).unwrap();
+        let restored = database.iterator(IteratorMode::Start).map(|entry| entry.unwrap()).collect::<Vec<_>>();
+        assert_eq!(restored, entries);
+        drop(database);
+
+        // Ensure a truncated archive is rejected.
+        let result = BackupReader::<CurrentNetwork, _>::new(&archive[..archive.len() / 2])
+            .and_then(|reader| reader.unpack(&root.join("truncated"), Options::default()));
+        assert!(result.is_err());
+        // Ensure anything but a backup archive is rejected.
+        let bytes = zstd::encode_all(&b"not a backup"[..], 3).unwrap();
+        assert!(BackupReader::<CurrentNetwork, _>::new(&bytes[..]).is_err());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
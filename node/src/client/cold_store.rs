@@ -0,0 +1,116 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_rest::BlockArchive;
+use snarkvm::prelude::{block::Block, FromBytes, Network, ToBytes};
+
+use anyhow::{anyhow, Result};
+use std::{fs, marker::PhantomData, path::PathBuf};
+
+/// The cold store keeps the full blocks that were pruned from the ledger, one file per block,
+/// on a separate (cheaper) path, i.e. a large disk or a mounted object store.
+#[derive(Clone)]
+pub struct ColdStore<N: Network> {
+    /// The root directory of the cold store.
+    path: PathBuf,
+    /// PhantomData.
+    _phantom: PhantomData<N>,
+}
+
+impl<N: Network> ColdStore<N> {
+    /// The number of blocks per subdirectory, to keep the directories small.
+    const BLOCKS_PER_DIRECTORY: u32 = 10_000;
+
+    /// Initializes a new cold store at the given path.
+    pub fn new(path: PathBuf) -> Result<Self> {
+        fs::create_dir_all(&path)
+            .map_err(|err| anyhow!("Couldn't create the cold store at {} - {err}", path.display()))?;
+        Ok(Self { path, _phantom: PhantomData })
+    }
+
+    /// Returns the path of the file of the block at the given height.
+    fn block_path(&self, height: u32) -> PathBuf {
+        let directory = height / Self::BLOCKS_PER_DIRECTORY * Self::BLOCKS_PER_DIRECTORY;
+        self.path.join(directory.to_string()).join(format!("{height}.block"))
+    }
+
+    /// Returns `true` if the block at the given height is in the cold store.
+    pub fn contains(&self, height: u32) -> bool {
+        self.block_path(height).exists()
+    }
+
+    /// Stores the given block.
+    pub fn insert(&self, block: &Block<N>) -> Result<()> {
+        let path = self.block_path(block.height());
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        // Write to a temporary file first, so that a crash never leaves a truncated block behind.
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, block.to_bytes_le()?)
+            .map_err(|err| anyhow!("Couldn't write block {} to the cold store - {err}", block.height()))?;
+        fs::rename(&temp_path, &path)?;
+        Ok(())
+    }
+
+    /// Returns the block at the given height, if it is in the cold store.
+    pub fn get(&self, height: u32) -> Result<Option<Block<N>>> {
+        let path = self.block_path(height);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let block = Block::from_bytes_le(&fs::read(&path)?)?;
+        match block.height() == height {
+            true => Ok(Some(block)),
+            false => Err(anyhow!("The cold store contains block {} in place of block {height}", block.height())),
+        }
+    }
+}
+
+impl<N: Network> BlockArchive<N> for ColdStore<N> {
+    /// Returns the archived block at the given height, if it exists.
+    fn get_archived_block(&self, height: u32) -> Result<Option<Block<N>>> {
+        self.get(height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::MainnetV0;
+
+    use rand::Rng;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_cold_store() {
+        let path = std::env::temp_dir().join(format!("snarkos-cold-store-{}", rand::thread_rng().gen::<u64>()));
+        let store = ColdStore::<CurrentNetwork>::new(path.clone()).unwrap();
+
+        // Ensure the blocks are spread across the subdirectories.
+        assert_eq!(store.block_path(9_999), path.join("0").join("9999.block"));
+        assert_eq!(store.block_path(10_000), path.join("10000").join("10000.block"));
+
+        // Ensure the genesis block survives a round trip through the cold store.
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        assert!(!store.contains(0));
+        assert!(store.get(0).unwrap().is_none());
+        store.insert(&genesis).unwrap();
+        assert!(store.contains(0));
+        assert_eq!(store.get(0).unwrap(), Some(genesis));
+
+        fs::remove_dir_all(path).unwrap();
+    }
+}
@@ -12,21 +12,36 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod cold_store;
+pub use cold_store::*;
+
+mod pool;
+pub use pool::*;
+
+mod pruner;
+pub use pruner::*;
+
+mod snapshot;
+pub use snapshot::*;
+
 mod router;
 
-use crate::traits::NodeInterface;
+use crate::{traits::NodeInterface, DiskWatchdog, HealthSnapshot, MembershipWatcher, RoleTransitions};
 use snarkos_account::Account;
 use snarkos_node_bft::ledger_service::CoreLedgerService;
-use snarkos_node_rest::Rest;
+use snarkos_node_rest::{AnalyticsIndex, BlockArchive, Rest, StateDigests};
 use snarkos_node_router::{
     messages::{Message, NodeType, UnconfirmedSolution},
     Heartbeat,
     Inbound,
+    KeepAlive,
+    ListenAddr,
     Outbound,
+    RelayMode,
     Router,
     Routing,
 };
-use snarkos_node_sync::{BlockSync, BlockSyncMode};
+use snarkos_node_sync::{BlockSync, BlockSyncMode, InvalidBlocks, SyncCheckpoint};
 use snarkos_node_tcp::{
     protocols::{Disconnect, Handshake, OnConnect, Reading, Writing},
     P2P,
@@ -35,6 +50,7 @@ use snarkvm::{
     console::network::Network,
     ledger::{
         block::{Block, Header},
+        narwhal::Data,
         puzzle::{Puzzle, Solution},
         store::ConsensusStorage,
         Ledger,
@@ -44,9 +60,11 @@ use snarkvm::{
 use aleo_std::StorageMode;
 use anyhow::Result;
 use core::future::Future;
+use once_cell::sync::OnceCell;
 use parking_lot::Mutex;
 use std::{
     net::SocketAddr,
+    path::PathBuf,
     sync::{atomic::AtomicBool, Arc},
 };
 use tokio::task::JoinHandle;
@@ -66,6 +84,22 @@ pub struct Client<N: Network, C: ConsensusStorage<N>> {
     genesis: Block<N>,
     /// The puzzle.
     puzzle: Puzzle<N>,
+    /// The pruner, if the node is in pruned mode.
+    pruner: Option<Pruner<N, C>>,
+    /// The number of blocks between state snapshots, if the node produces them.
+    snapshot_interval: Option<u32>,
+    /// The path where the latest state snapshot is stored.
+    snapshot_path: PathBuf,
+    /// The storage mode of the ledger, which the state snapshots are read from.
+    storage_mode: StorageMode,
+    /// The index of the chain statistics, if enabled.
+    analytics: Option<Arc<AnalyticsIndex>>,
+    /// The state digests, which are computed as the ledger advances, once they are enabled.
+    state_digests: Arc<StateDigests<N>>,
+    /// The disk watchdog, if enabled.
+    disk_watchdog: Arc<OnceCell<DiskWatchdog>>,
+    /// The transitions to the validator role, once they are enabled.
+    role_transitions: Arc<RoleTransitions>,
     /// The spawned handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
     /// The shutdown signal.
@@ -76,10 +110,23 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
     /// Initializes a new client node.
     pub async fn new(
         node_ip: SocketAddr,
+        listen_addrs: &[ListenAddr],
         rest_ip: Option<SocketAddr>,
         rest_rps: u32,
+        rest_cache_size: usize,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
+        pinned_peers: &[SocketAddr],
+        sync_peers: &[SocketAddr],
+        sync_checkpoint: Option<SyncCheckpoint<N>>,
+        blocks_to_keep: Option<u32>,
+        max_ledger_size: Option<u64>,
+        cold_storage: Option<PathBuf>,
+        snapshot_interval: Option<u32>,
+        analytics: bool,
+        keep_alive: KeepAlive,
+        relay_mode: RelayMode,
+        pool: Option<PoolConfig>,
         genesis: Block<N>,
         cdn: Option<String>,
         storage_mode: StorageMode,
@@ -102,20 +149,65 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
             }
         }
 
+        // Initialize the pruner, migrating the pruned blocks to the cold store, if any.
+        // Note: If only the maximum ledger size is set, the blocks are pruned only while the ledger exceeds it.
+        let cold_store = cold_storage.map(ColdStore::new).transpose()?;
+        let ledger_dir = aleo_std::aleo_ledger_dir(N::ID, storage_mode.clone());
+        let pruner = blocks_to_keep
+            .or(max_ledger_size.map(|_| u32::MAX))
+            .map(|blocks_to_keep| {
+                Pruner::new(ledger.clone(), blocks_to_keep, crate::pruned_height_path(N::ID, &storage_mode))
+                    .map(|pruner| pruner.with_cold_store(cold_store.clone()))
+                    .map(|pruner| pruner.with_max_ledger_size(ledger_dir, max_ledger_size))
+            })
+            .transpose()?;
+
+        // Open the analytics index, if enabled.
+        let analytics = match analytics {
+            true => Some(Arc::new(AnalyticsIndex::open(&crate::analytics_index_path(N::ID, &storage_mode))?)),
+            false => None,
+        };
+
         // Initialize the ledger service.
         let ledger_service = Arc::new(CoreLedgerService::<N, C>::new(ledger.clone(), shutdown.clone()));
+        // Compute the state digests on the block-advance path, once they are enabled.
+        let state_digests = Arc::new(StateDigests::default());
+        ledger_service.on_advance({
+            let (ledger, state_digests) = (ledger.clone(), state_digests.clone());
+            move |block| state_digests.on_advance(&ledger, block.height())
+        });
         // Initialize the sync module.
-        let sync = BlockSync::new(BlockSyncMode::Router, ledger_service.clone());
+        let invalid_blocks = InvalidBlocks::load(&crate::invalid_blocks_path(N::ID, &storage_mode))?;
+        let sync = BlockSync::new(BlockSyncMode::Router, ledger_service.clone(), sync_peers)
+            .with_checkpoint(sync_checkpoint)
+            .with_invalid_blocks(invalid_blocks);
+        // Resume the sync with the blocks that were received, but not yet advanced with, before the last shutdown.
+        match sync.load_pending_blocks(&crate::pending_blocks_path(N::ID, &storage_mode)) {
+            Ok(0) => (),
+            Ok(num_blocks) => info!("Resuming the sync with {num_blocks} pending blocks"),
+            Err(error) => warn!("Failed to load the pending blocks - {error}"),
+        }
         // Determine if the client should allow external peers.
         let allow_external_peers = true;
+        // Pin the sync peers, so that the connections to them are maintained.
+        let mut pinned_peers = pinned_peers.to_vec();
+        for peer_ip in sync_peers {
+            if !pinned_peers.contains(peer_ip) {
+                pinned_peers.push(*peer_ip);
+            }
+        }
 
         // Initialize the node router.
         let router = Router::new(
             node_ip,
+            listen_addrs,
             NodeType::Client,
             account,
             trusted_peers,
+            &pinned_peers,
             Self::MAXIMUM_NUMBER_OF_PEERS as u16,
+            keep_alive,
+            relay_mode,
             allow_external_peers,
             matches!(storage_mode, StorageMode::Development(_)),
         )
@@ -128,18 +220,63 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
             sync: Arc::new(sync),
             genesis,
             puzzle: ledger.puzzle().clone(),
+            pruner,
+            snapshot_interval,
+            snapshot_path: crate::snapshot_path(N::ID, &storage_mode),
+            storage_mode: storage_mode.clone(),
+            analytics,
+            state_digests,
+            disk_watchdog: Default::default(),
+            role_transitions: Default::default(),
             handles: Default::default(),
             shutdown,
         };
 
         // Initialize the REST server.
         if let Some(rest_ip) = rest_ip {
-            node.rest = Some(Rest::start(rest_ip, rest_rps, None, ledger.clone(), Arc::new(node.clone())).await?);
+            // Read pruned blocks through from the cold store, if any.
+            let archive = cold_store.map(|cold_store| Arc::new(cold_store) as Arc<dyn BlockArchive<N>>);
+            // Serve the state snapshots, if the node produces them.
+            let snapshot_path = node.snapshot_interval.map(|_| node.snapshot_path.clone());
+            node.rest = Some(
+                Rest::start(
+                    rest_ip,
+                    rest_rps,
+                    rest_cache_size,
+                    None,
+                    ledger.clone(),
+                    Arc::new(node.clone()),
+                    archive,
+                    snapshot_path,
+                    node.analytics.clone(),
+                    node.state_digests.clone(),
+                )
+                .await?,
+            );
         }
         // Initialize the routing.
         node.initialize_routing().await;
+        // Restore the known peers of the previous run.
+        node.router.enable_peer_cache(crate::peer_cache_path(N::ID, &storage_mode));
         // Initialize the sync module.
         node.initialize_sync();
+        // Initialize the pruning.
+        node.initialize_pruning();
+        // Initialize the state snapshots.
+        node.initialize_snapshots();
+        // Initialize the analytics index.
+        node.initialize_analytics();
+        // Initialize the block announcements.
+        node.initialize_block_announcements();
+        // Initialize the pool coordinator, if enabled.
+        if let Some(pool) = pool {
+            node.initialize_pool(pool).await?;
+        }
+        // Initialize the membership watcher, which warns when the address of the client is bonded in the committee,
+        // and requests the client to become a validator, if the role transitions are enabled.
+        let address = node.router.address();
+        let watcher = MembershipWatcher::new(ledger.clone(), address, NodeType::Client, node.role_transitions.clone());
+        node.handles.lock().push(watcher.spawn());
         // Initialize the notification message loop.
         node.handles.lock().push(crate::start_notification_message_loop());
         // Pass the node to the signal handler.
@@ -157,11 +294,59 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
     pub fn rest(&self) -> &Option<Rest<N, C, Self>> {
         &self.rest
     }
+
+    /// Returns the state digests.
+    pub fn state_digests(&self) -> &StateDigests<N> {
+        &self.state_digests
+    }
+
+    /// Returns the role transitions.
+    pub fn role_transitions(&self) -> &Arc<RoleTransitions> {
+        &self.role_transitions
+    }
+
+    /// Sets the disk watchdog, which pauses the state snapshots while the ledger volume runs low on space.
+    pub(crate) fn set_disk_watchdog(&self, watchdog: DiskWatchdog) {
+        let _ = self.disk_watchdog.set(watchdog);
+    }
+
+    /// Returns `true` if the ledger volume is running low on space.
+    fn is_disk_low(&self) -> bool {
+        self.disk_watchdog.get().map_or(false, |watchdog| watchdog.is_low())
+    }
+
+    /// Returns a snapshot of the health of the node, for the alerts.
+    pub(crate) fn health(&self) -> HealthSnapshot {
+        HealthSnapshot {
+            height: Some(self.ledger.latest_height()),
+            num_peers: self.router.number_of_connected_peers(),
+            num_sync_peers: self.sync.num_peers_with_locators(),
+            num_forked_peers: self.sync.find_forked_peers().len(),
+            ..Default::default()
+        }
+    }
 }
 
 impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
+    /// The interval at which the analytics index is updated, in seconds.
+    const ANALYTICS_INTERVAL_IN_SECS: u64 = 5;
+    /// The interval at which the node checks for new blocks to announce, in milliseconds.
+    const BLOCK_ANNOUNCEMENT_INTERVAL_IN_MS: u64 = 100;
+    /// The interval at which the pool coordinator checks for new work, in milliseconds.
+    const POOL_JOB_INTERVAL_IN_MS: u64 = 1000;
+    /// The interval at which the pool coordinator reports the contribution of the workers, in seconds.
+    const POOL_REPORT_INTERVAL_IN_SECS: u64 = 300;
+    /// The interval at which the ledger is pruned, in seconds.
+    const PRUNING_INTERVAL_IN_SECS: u64 = 60;
+    /// The interval at which the node checks whether a state snapshot is due, in seconds.
+    const SNAPSHOT_INTERVAL_IN_SECS: u64 = 60;
+
     /// Initializes the sync pool.
     fn initialize_sync(&self) {
+        // Start the block verification pipeline.
+        let node = self.clone();
+        self.handles.lock().push(tokio::spawn(async move { node.sync.run_block_verification().await }));
+
         // Start the sync loop.
         let node = self.clone();
         self.handles.lock().push(tokio::spawn(async move {
@@ -172,14 +357,190 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
                     break;
                 }
 
-                // Sleep briefly to avoid triggering spam detection.
-                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                // Wait for the sync to make progress, or sleep briefly, to avoid triggering spam detection.
+                node.sync.wait_for_sync_progress(std::time::Duration::from_secs(5)).await;
                 // Perform the sync routine.
                 node.sync.try_block_sync(&node).await;
             }
         }));
     }
 
+    /// Initializes the pruning loop, if the node is in pruned mode.
+    fn initialize_pruning(&self) {
+        let Some(pruner) = self.pruner.clone() else { return };
+        if pruner.blocks_to_keep() < u32::MAX {
+            info!("Pruning the transactions of all but the latest {} blocks", pruner.blocks_to_keep());
+        }
+        if let Some(max_ledger_size) = pruner.max_ledger_size() {
+            info!("Pruning the transactions of the oldest blocks while the ledger exceeds {max_ledger_size} bytes");
+        }
+
+        let node = self.clone();
+        self.handles.lock().push(tokio::spawn(async move {
+            loop {
+                // If the Ctrl-C handler registered the signal, stop the node.
+                if node.shutdown.load(std::sync::atomic::Ordering::Relaxed) {
+                    info!("Shutting down pruning");
+                    break;
+                }
+
+                // Discard the transactions of the blocks that fell out of the retained range.
+                let pruner_ = pruner.clone();
+                match tokio::task::spawn_blocking(move || pruner_.prune()).await {
+                    Ok(Ok(())) => (),
+                    Ok(Err(error)) => warn!("Failed to prune the ledger - {error}"),
+                    Err(error) => warn!("[tokio::spawn_blocking] {error}"),
+                }
+                // Sleep briefly, to let new blocks accumulate.
+                tokio::time::sleep(std::time::Duration::from_secs(Self::PRUNING_INTERVAL_IN_SECS)).await;
+            }
+        }));
+    }
+
+    /// Initializes the state snapshot loop, if the node produces snapshots.
+    fn initialize_snapshots(&self) {
+        let Some(interval) = self.snapshot_interval else { return };
+        info!("Producing a state snapshot every {interval} blocks");
+
+        let node = self.clone();
+        self.handles.lock().push(tokio::spawn(async move {
+            let mut last_height = None;
+            loop {
+                // If the Ctrl-C handler registered the signal, stop the node.
+                if node.shutdown.load(std::sync::atomic::Ordering::Relaxed) {
+                    info!("Shutting down state snapshots");
+                    break;
+                }
+
+                // Take a snapshot, once the ledger crossed into the next interval, unless the disk is running low.
+                if is_snapshot_due(last_height, node.ledger.latest_height(), interval) && !node.is_disk_low() {
+                    let node_ = node.clone();
+                    let take_snapshot =
+                        move || write_snapshot(&node_.ledger, node_.storage_mode.clone(), &node_.snapshot_path);
+                    match tokio::task::spawn_blocking(take_snapshot).await {
+                        Ok(Ok(manifest)) => {
+                            info!("Produced a state snapshot at block {}", manifest.height);
+                            last_height = Some(manifest.height);
+                        }
+                        Ok(Err(error)) => warn!("Failed to produce a state snapshot - {error}"),
+                        Err(error) => warn!("[tokio::spawn_blocking] {error}"),
+                    }
+                }
+                // Sleep briefly, to let new blocks accumulate.
+                tokio::time::sleep(std::time::Duration::from_secs(Self::SNAPSHOT_INTERVAL_IN_SECS)).await;
+            }
+        }));
+    }
+
+    /// Initializes the analytics index loop, which indexes the new blocks, if the index is enabled.
+    fn initialize_analytics(&self) {
+        let Some(analytics) = self.analytics.clone() else { return };
+        info!("Indexing the chain statistics");
+
+        let node = self.clone();
+        self.handles.lock().push(tokio::spawn(async move {
+            loop {
+                // If the Ctrl-C handler registered the signal, stop the node.
+                if node.shutdown.load(std::sync::atomic::Ordering::Relaxed) {
+                    info!("Shutting down the analytics index");
+                    break;
+                }
+
+                // Index the blocks the ledger advanced by.
+                let (ledger, analytics) = (node.ledger.clone(), analytics.clone());
+                match tokio::task::spawn_blocking(move || analytics.catch_up(&ledger)).await {
+                    Ok(Ok(_)) => (),
+                    Ok(Err(error)) => warn!("Failed to update the analytics index - {error}"),
+                    Err(error) => warn!("[tokio::spawn_blocking] {error}"),
+                }
+                // Sleep briefly, to let new blocks accumulate.
+                tokio::time::sleep(std::time::Duration::from_secs(Self::ANALYTICS_INTERVAL_IN_SECS)).await;
+            }
+        }));
+    }
+
+    /// Initializes the block announcement loop, which relays the new blocks to the connected clients as compact blocks.
+    fn initialize_block_announcements(&self) {
+        let node = self.clone();
+        self.handles.lock().push(tokio::spawn(async move {
+            let mut last_height = node.ledger.latest_height();
+            loop {
+                // If the Ctrl-C handler registered the signal, stop the node.
+                if node.shutdown.load(std::sync::atomic::Ordering::Relaxed) {
+                    info!("Shutting down block announcements");
+                    break;
+                }
+
+                // Announce the latest block, once the node is synced, as the lagging peers sync the older blocks.
+                let latest_height = node.ledger.latest_height();
+                if latest_height > last_height && node.sync.is_block_synced() {
+                    node.announce_block(&node.ledger.latest_block(), &[]);
+                }
+                last_height = latest_height;
+                // Sleep briefly, to let the ledger advance.
+                tokio::time::sleep(std::time::Duration::from_millis(Self::BLOCK_ANNOUNCEMENT_INTERVAL_IN_MS)).await;
+            }
+        }));
+    }
+
+    /// Initializes the pool coordinator, which distributes the puzzle work for the address of the node to the workers.
+    async fn initialize_pool(&self, config: PoolConfig) -> Result<()> {
+        let listener = tokio::net::TcpListener::bind(config.listen()).await?;
+        info!("Coordinating a pool for '{}' on '{}'", self.router.address(), config.listen());
+        let (coordinator, mut solution_receiver) =
+            PoolCoordinator::new(config, self.router.address(), self.puzzle.clone());
+        let coordinator = Arc::new(coordinator);
+
+        // Accept the connections from the workers.
+        self.handles.lock().push(tokio::spawn(coordinator.clone().run(listener)));
+
+        // Broadcast the solutions found by the workers.
+        let node = self.clone();
+        self.handles.lock().push(tokio::spawn(async move {
+            while let Some(solution) = solution_receiver.recv().await {
+                let message = Message::UnconfirmedSolution(UnconfirmedSolution {
+                    solution_id: solution.id(),
+                    solution: Data::Object(solution),
+                });
+                node.propagate(message, &[]);
+            }
+        }));
+
+        // Update the work of the workers as the ledger advances, and report their contribution periodically.
+        let node = self.clone();
+        self.handles.lock().push(tokio::spawn(async move {
+            let mut last_report = std::time::Instant::now();
+            loop {
+                // If the Ctrl-C handler registered the signal, stop the node.
+                if node.shutdown.load(std::sync::atomic::Ordering::Relaxed) {
+                    info!("Shutting down the pool coordinator");
+                    break;
+                }
+
+                // Assign the latest epoch to the workers.
+                match node.ledger.latest_epoch_hash() {
+                    Ok(epoch_hash) => {
+                        coordinator.update_job(epoch_hash, node.ledger.latest_block().header().proof_target())
+                    }
+                    Err(error) => warn!("Failed to retrieve the latest epoch hash - {error}"),
+                }
+                // Report the contribution of the workers.
+                if last_report.elapsed().as_secs() >= Self::POOL_REPORT_INTERVAL_IN_SECS {
+                    for (worker, stats) in coordinator.contributions() {
+                        info!(
+                            "Pool worker '{worker}' - {} shares, {} solutions, {} rejected",
+                            stats.shares, stats.solutions, stats.rejected
+                        );
+                    }
+                    last_report = std::time::Instant::now();
+                }
+                // Sleep briefly, to let the ledger advance.
+                tokio::time::sleep(std::time::Duration::from_millis(Self::POOL_JOB_INTERVAL_IN_MS)).await;
+            }
+        }));
+        Ok(())
+    }
+
     /// Spawns a task with the given future; it should only be used for long-running tasks.
     pub fn spawn<T: Future<Output = ()> + Send + 'static>(&self, future: T) {
         self.handles.lock().push(tokio::spawn(future));
@@ -196,10 +557,27 @@ impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Client<N, C> {
         trace!("Shutting down the node...");
         self.shutdown.store(true, std::sync::atomic::Ordering::Relaxed);
 
+        // Drain the connections to the peers.
+        trace!("Draining the connections...");
+        self.drain_connections().await;
+
         // Abort the tasks.
         trace!("Shutting down the validator...");
         self.handles.lock().iter().for_each(|handle| handle.abort());
 
+        // Stop the REST server, as the node may be started again in another role.
+        if let Some(rest) = &self.rest {
+            rest.handles().lock().iter().for_each(|handle| handle.abort());
+        }
+
+        // Compact the journal of the pending blocks of the sync, which are not requested again on restart.
+        trace!("Saving the pending blocks...");
+        match self.sync.save_pending_blocks() {
+            Ok(0) => (),
+            Ok(num_blocks) => info!("Saved {num_blocks} pending blocks"),
+            Err(error) => warn!("Failed to save the pending blocks - {error}"),
+        }
+
         // Shut down the router.
         self.router.shut_down().await;
 
@@ -0,0 +1,426 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::check_worker_name;
+use snarkvm::{
+    ledger::puzzle::{Puzzle, Solution, SolutionID},
+    prelude::{Address, Network},
+};
+
+use anyhow::{anyhow, bail, ensure, Result};
+use futures_util::StreamExt;
+use indexmap::IndexMap;
+use parking_lot::{Mutex, RwLock};
+use serde_json::{json, Value};
+use std::{
+    collections::{HashMap, HashSet},
+    net::SocketAddr,
+    sync::Arc,
+};
+use tokio::{
+    io::AsyncWriteExt,
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+use tokio_util::codec::{FramedRead, LinesCodec};
+
+/// The configuration of the pool coordinator.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PoolConfig {
+    /// The address to listen for the workers on.
+    listen: SocketAddr,
+    /// The divisor of the proof target of the network, which determines the share target.
+    share_divisor: u64,
+}
+
+impl PoolConfig {
+    /// The default divisor of the proof target, so that a worker submits 16 shares per solution on average.
+    pub const DEFAULT_SHARE_DIVISOR: u64 = 16;
+    /// The maximum divisor of the proof target, which bounds the rate of shares to verify.
+    pub const MAX_SHARE_DIVISOR: u64 = 1024;
+
+    /// Initializes a new pool configuration, ensuring the share divisor is within bounds.
+    pub fn new(listen: SocketAddr, share_divisor: Option<u64>) -> Result<Self> {
+        let share_divisor = share_divisor.unwrap_or(Self::DEFAULT_SHARE_DIVISOR);
+        ensure!(
+            (1..=Self::MAX_SHARE_DIVISOR).contains(&share_divisor),
+            "The share divisor must be between 1 and {}",
+            Self::MAX_SHARE_DIVISOR
+        );
+        Ok(Self { listen, share_divisor })
+    }
+
+    /// Returns the address to listen for the workers on.
+    pub const fn listen(&self) -> SocketAddr {
+        self.listen
+    }
+
+    /// Returns the divisor of the proof target of the network.
+    pub const fn share_divisor(&self) -> u64 {
+        self.share_divisor
+    }
+}
+
+/// The contribution of a worker to the pool.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct WorkerStats {
+    /// The number of accepted shares.
+    pub shares: u64,
+    /// The number of accepted shares that met the proof target of the network.
+    pub solutions: u64,
+    /// The number of rejected shares.
+    pub rejected: u64,
+}
+
+/// The outcome of a valid share.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Share {
+    /// The share meets the share target.
+    Accepted,
+    /// The share also meets the proof target of the network, so it is a solution.
+    Solution,
+}
+
+/// The pool coordinator, which assigns the puzzle work to the connected workers, verifies their shares,
+/// broadcasts the solutions among them, and tracks the contribution of each worker.
+///
+/// The protocol with the workers is documented in the pool client of the prover (see `PoolClient`).
+pub struct PoolCoordinator<N: Network> {
+    /// The configuration.
+    config: PoolConfig,
+    /// The address of the pool, which the workers prove for.
+    address: Address<N>,
+    /// The puzzle.
+    puzzle: Puzzle<N>,
+    /// The current epoch hash and proof target of the network.
+    job: RwLock<Option<(N::BlockHash, u64)>>,
+    /// The shares accepted in the current epoch.
+    seen_shares: Mutex<HashSet<SolutionID<N>>>,
+    /// The contribution of each worker.
+    workers: RwLock<IndexMap<String, WorkerStats>>,
+    /// The senders to the connected workers.
+    connections: Mutex<HashMap<SocketAddr, mpsc::Sender<String>>>,
+    /// The sender of the solutions to broadcast.
+    solution_sender: mpsc::Sender<Solution<N>>,
+}
+
+impl<N: Network> PoolCoordinator<N> {
+    /// The maximum number of connected workers.
+    const MAX_CONNECTIONS: usize = 1024;
+    /// The maximum size of a message from a worker, in bytes.
+    const MAX_MESSAGE_SIZE: usize = 64 * 1024;
+    /// The maximum number of tracked workers.
+    const MAX_WORKERS: usize = 4096;
+
+    /// Initializes a new pool coordinator, which proves for the given address,
+    /// and returns the receiver of the solutions to broadcast.
+    pub fn new(config: PoolConfig, address: Address<N>, puzzle: Puzzle<N>) -> (Self, mpsc::Receiver<Solution<N>>) {
+        let (solution_sender, solution_receiver) = mpsc::channel(1024);
+        let coordinator = Self {
+            config,
+            address,
+            puzzle,
+            job: Default::default(),
+            seen_shares: Default::default(),
+            workers: Default::default(),
+            connections: Default::default(),
+            solution_sender,
+        };
+        (coordinator, solution_receiver)
+    }
+
+    /// Returns the share target, for the given proof target of the network.
+    fn share_target(&self, proof_target: u64) -> u64 {
+        (proof_target / self.config.share_divisor).max(1)
+    }
+
+    /// Returns the number of connected workers.
+    pub fn num_connections(&self) -> usize {
+        self.connections.lock().len()
+    }
+
+    /// Returns the contribution of each worker.
+    pub fn contributions(&self) -> IndexMap<String, WorkerStats> {
+        self.workers.read().clone()
+    }
+
+    /// Updates the epoch hash and proof target of the network, and notifies the workers of the new work.
+    pub fn update_job(&self, epoch_hash: N::BlockHash, proof_target: u64) {
+        let previous = self.job.write().replace((epoch_hash, proof_target));
+        if previous == Some((epoch_hash, proof_target)) {
+            return;
+        }
+        // Clear the seen shares on a new epoch.
+        if previous.map(|(hash, _)| hash) != Some(epoch_hash) {
+            self.seen_shares.lock().clear();
+        }
+        // Notify the workers.
+        let notify = self.notify_message(epoch_hash, proof_target);
+        self.connections
+            .lock()
+            .retain(|_, sender| !matches!(sender.try_send(notify.clone()), Err(mpsc::error::TrySendError::Closed(_))));
+    }
+
+    /// Returns the notification of the given work.
+    fn notify_message(&self, epoch_hash: N::BlockHash, proof_target: u64) -> String {
+        format!(
+            "{}\n",
+            json!({ "method": "notify", "params": [epoch_hash.to_string(), self.share_target(proof_target)] })
+        )
+    }
+
+    /// Checks the given share from the given worker, and records the outcome in the contribution of the worker.
+    pub fn check_share(&self, worker: &str, solution: &Solution<N>) -> Result<Share> {
+        let result = self.check_share_inner(solution);
+        let mut workers = self.workers.write();
+        if let Some(stats) = workers.get_mut(worker) {
+            match result {
+                Ok(Share::Accepted) => stats.shares += 1,
+                Ok(Share::Solution) => {
+                    stats.shares += 1;
+                    stats.solutions += 1;
+                }
+                Err(_) => stats.rejected += 1,
+            }
+        }
+        result
+    }
+
+    /// Checks the given share.
+    fn check_share_inner(&self, solution: &Solution<N>) -> Result<Share> {
+        let Some((epoch_hash, proof_target)) = *self.job.read() else { bail!("The pool has no work yet") };
+        // Ensure the share is for the current epoch, and for the pool address.
+        ensure!(solution.epoch_hash() == epoch_hash, "Stale share");
+        ensure!(solution.address() == self.address, "The share is not for the pool address");
+        // Ensure the share is new.
+        ensure!(!self.seen_shares.lock().contains(&solution.id()), "Duplicate share");
+        // Ensure the share meets the share target.
+        let target = self.puzzle.get_proof_target(solution)?;
+        ensure!(target == solution.target(), "The share has an invalid target");
+        ensure!(target >= self.share_target(proof_target), "The share does not meet the share target");
+        // Record the share, ensuring it was not accepted concurrently.
+        ensure!(self.seen_shares.lock().insert(solution.id()), "Duplicate share");
+        match target >= proof_target {
+            true => Ok(Share::Solution),
+            false => Ok(Share::Accepted),
+        }
+    }
+
+    /// Registers the given worker, ensuring the number of tracked workers is bounded.
+    fn register_worker(&self, worker: &str) -> Result<()> {
+        let mut workers = self.workers.write();
+        if !workers.contains_key(worker) {
+            ensure!(workers.len() < Self::MAX_WORKERS, "The pool has too many workers");
+            workers.insert(worker.to_string(), Default::default());
+        }
+        Ok(())
+    }
+
+    /// Accepts the connections from the workers, until the listener fails.
+    pub async fn run(self: Arc<Self>, listener: TcpListener) {
+        loop {
+            match listener.accept().await {
+                Ok((stream, peer_addr)) => {
+                    if self.num_connections() >= Self::MAX_CONNECTIONS {
+                        debug!("Rejecting a pool worker at '{peer_addr}' (too many connections)");
+                        continue;
+                    }
+                    let coordinator = self.clone();
+                    tokio::spawn(async move { coordinator.handle_connection(stream, peer_addr).await });
+                }
+                Err(error) => {
+                    error!("The pool listener failed - {error}");
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Processes the messages of the worker on the given connection, until it closes.
+    async fn handle_connection(self: Arc<Self>, stream: TcpStream, peer_addr: SocketAddr) {
+        let (reader, mut writer) = stream.into_split();
+        let (sender, mut receiver) = mpsc::channel::<String>(64);
+        // Write the outgoing messages to the worker.
+        let writer_task = tokio::spawn(async move {
+            while let Some(line) = receiver.recv().await {
+                if writer.write_all(line.as_bytes()).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        // Process the messages from the worker.
+        let mut worker = None;
+        let mut lines = FramedRead::new(reader, LinesCodec::new_with_max_length(Self::MAX_MESSAGE_SIZE));
+        while let Some(Ok(line)) = lines.next().await {
+            let response = match self.clone().process_message(&line, &mut worker, peer_addr, &sender).await {
+                Ok(response) => response,
+                Err(error) => {
+                    debug!("Invalid message from the pool worker at '{peer_addr}' - {error}");
+                    break;
+                }
+            };
+            if sender.send(format!("{response}\n")).await.is_err() {
+                break;
+            }
+        }
+        self.connections.lock().remove(&peer_addr);
+        writer_task.abort();
+    }
+
+    /// Processes the given message from a worker, and returns the response.
+    async fn process_message(
+        self: Arc<Self>,
+        line: &str,
+        worker: &mut Option<String>,
+        peer_addr: SocketAddr,
+        sender: &mpsc::Sender<String>,
+    ) -> Result<Value> {
+        let message: Value = serde_json::from_str(line)?;
+        let id = message.get("id").and_then(Value::as_u64).ok_or_else(|| anyhow!("Missing ID"))?;
+        let method = message.get("method").and_then(Value::as_str).ok_or_else(|| anyhow!("Missing method"))?;
+        let params = message.get("params").and_then(Value::as_array).ok_or_else(|| anyhow!("Missing params"))?;
+        // Ensure the worker name is valid.
+        let name = params.first().and_then(Value::as_str).ok_or_else(|| anyhow!("Missing worker name"))?;
+        check_worker_name(name)?;
+
+        match method {
+            "subscribe" => {
+                ensure!(worker.is_none(), "The worker is already subscribed");
+                if let Err(error) = self.register_worker(name) {
+                    return Ok(json!({ "id": id, "error": error.to_string() }));
+                }
+                *worker = Some(name.to_string());
+                self.connections.lock().insert(peer_addr, sender.clone());
+                info!("Pool worker '{name}' connected from '{peer_addr}'");
+                // Send the current work right after the subscription.
+                if let Some((epoch_hash, proof_target)) = *self.job.read() {
+                    let _ = sender.try_send(self.notify_message(epoch_hash, proof_target));
+                }
+                Ok(json!({ "id": id, "result": { "address": self.address.to_string() } }))
+            }
+            "submit" => {
+                // Ensure the share is submitted by the subscribed worker.
+                let Some(worker) = worker.clone() else { bail!("The worker is not subscribed") };
+                ensure!(worker == name, "The share is not from the subscribed worker");
+                let solution: Solution<N> =
+                    serde_json::from_value(params.get(1).cloned().ok_or_else(|| anyhow!("Missing share"))?)?;
+                // Check the share.
+                let coordinator = self.clone();
+                let solution_ = solution.clone();
+                let result = tokio::task::spawn_blocking(move || coordinator.check_share(&worker, &solution_)).await?;
+                match result {
+                    Ok(share) => {
+                        // Broadcast the solution.
+                        if share == Share::Solution {
+                            info!("Pool worker '{name}' found a solution '{}'", solution.id());
+                            let _ = self.solution_sender.try_send(solution);
+                        }
+                        Ok(json!({ "id": id, "result": true }))
+                    }
+                    Err(error) => Ok(json!({ "id": id, "error": error.to_string() })),
+                }
+            }
+            _ => Ok(json!({ "id": id, "error": format!("Unknown method '{method}'") })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PoolClient;
+    use snarkvm::{
+        ledger::{puzzle::PartialSolution, store::helpers::memory::ConsensusMemory},
+        prelude::{MainnetV0, PrivateKey, TestRng},
+        synthesizer::VM,
+    };
+
+    use deadline::deadline;
+    use rand::Rng;
+    use std::time::Duration;
+
+    type CurrentNetwork = MainnetV0;
+
+    fn sample_coordinator(
+        rng: &mut TestRng,
+    ) -> (PoolCoordinator<CurrentNetwork>, mpsc::Receiver<Solution<CurrentNetwork>>) {
+        let address = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        let puzzle = VM::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::new_puzzle().unwrap();
+        let config = PoolConfig::new("127.0.0.1:0".parse().unwrap(), None).unwrap();
+        PoolCoordinator::new(config, address, puzzle)
+    }
+
+    #[test]
+    fn test_pool_config() {
+        let listen = "127.0.0.1:4140".parse().unwrap();
+        assert_eq!(PoolConfig::new(listen, None).unwrap().share_divisor(), PoolConfig::DEFAULT_SHARE_DIVISOR);
+        assert_eq!(PoolConfig::new(listen, Some(1)).unwrap().share_divisor(), 1);
+        assert!(PoolConfig::new(listen, Some(0)).is_err());
+        assert!(PoolConfig::new(listen, Some(PoolConfig::MAX_SHARE_DIVISOR + 1)).is_err());
+    }
+
+    #[test]
+    fn test_check_share() {
+        let rng = &mut TestRng::default();
+        let (coordinator, _) = sample_coordinator(rng);
+        coordinator.register_worker("rig").unwrap();
+
+        let epoch_hash = <CurrentNetwork as Network>::BlockHash::default();
+        let share = |address, counter| Solution::new(PartialSolution::new(epoch_hash, address, counter).unwrap(), 1);
+
+        // Ensure the shares are rejected before the pool has work.
+        assert!(coordinator.check_share("rig", &share(coordinator.address, 0)).is_err());
+        coordinator.update_job(epoch_hash, 1 << 20);
+        assert_eq!(coordinator.share_target(1 << 20), (1 << 20) / PoolConfig::DEFAULT_SHARE_DIVISOR);
+
+        // Ensure the shares for another address are rejected.
+        let other = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        assert!(coordinator.check_share("rig", &share(other, 0)).is_err());
+        // Ensure the stale shares are rejected.
+        coordinator.update_job(rng.gen(), 1 << 20);
+        assert!(coordinator.check_share("rig", &share(coordinator.address, 0)).is_err());
+
+        // Ensure the rejections are recorded for the worker.
+        assert_eq!(coordinator.contributions()["rig"], WorkerStats { shares: 0, solutions: 0, rejected: 3 });
+    }
+
+    #[tokio::test]
+    async fn test_subscribe() {
+        let rng = &mut TestRng::default();
+        let (coordinator, _) = sample_coordinator(rng);
+        let epoch_hash = <CurrentNetwork as Network>::BlockHash::default();
+        coordinator.update_job(epoch_hash, 1 << 20);
+        let address = coordinator.address;
+
+        // Start the coordinator.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server = listener.local_addr().unwrap();
+        let coordinator = Arc::new(coordinator);
+        tokio::spawn(coordinator.clone().run(listener));
+
+        // Connect a pool client, and ensure it receives the work.
+        let client = Arc::new(PoolClient::<CurrentNetwork>::new(&server.to_string(), "rig").unwrap());
+        let client_ = client.clone();
+        tokio::spawn(async move { client_.run(Default::default()).await });
+        let client_ = client.clone();
+        deadline!(Duration::from_secs(5), move || client_.job().is_some());
+        let job = client.job().unwrap();
+        assert_eq!(job.address, address);
+        assert_eq!(job.epoch_hash, epoch_hash);
+        assert_eq!(job.share_target, (1 << 20) / PoolConfig::DEFAULT_SHARE_DIVISOR);
+        assert_eq!(coordinator.num_connections(), 1);
+        assert!(coordinator.contributions().contains_key("rig"));
+    }
+}
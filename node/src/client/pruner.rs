@@ -0,0 +1,340 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::ColdStore;
+use snarkvm::{
+    console::network::Network,
+    ledger::{
+        block::{Block, Execution, Fee, Output, Transaction, Transition},
+        store::ConsensusStorage,
+        Ledger,
+    },
+};
+
+use anyhow::{anyhow, ensure, Error, Result};
+use std::{
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+};
+
+/// The maximum number of blocks pruned in a single iteration.
+const MAXIMUM_BLOCKS_PER_ITERATION: u32 = 1_000;
+
+/// The pruner discards the proofs and the output records of the transactions of old blocks, keeping the
+/// transaction and transition IDs, the serial numbers and the commitments, so that new blocks are still checked
+/// against them, and the old blocks can still be read.
+#[derive(Clone)]
+pub struct Pruner<N: Network, C: ConsensusStorage<N>> {
+    /// The ledger of the node.
+    ledger: Ledger<N, C>,
+    /// The number of most recent blocks whose transactions are retained.
+    blocks_to_keep: u32,
+    /// The greatest height whose transactions have been discarded.
+    pruned_height: Arc<AtomicU32>,
+    /// The path where the pruned height is persisted, right next to the ledger.
+    path: PathBuf,
+    /// The cold store that the full blocks are migrated to before they are pruned, if any.
+    cold_store: Option<ColdStore<N>>,
+    /// The directory of the ledger and its maximum size (in bytes), beyond which the oldest blocks are pruned, if any.
+    max_ledger_size: Option<(PathBuf, u64)>,
+}
+
+impl<N: Network, C: ConsensusStorage<N>> Pruner<N, C> {
+    /// The minimum number of blocks to keep, so that recent queries and new blocks can still be served.
+    pub const MINIMUM_BLOCKS_TO_KEEP: u32 = N::NUM_BLOCKS_PER_EPOCH;
+
+    /// Initializes a new pruner, resuming from the pruned height of the previous run.
+    pub fn new(ledger: Ledger<N, C>, blocks_to_keep: u32, path: PathBuf) -> Result<Self> {
+        ensure!(
+            blocks_to_keep >= Self::MINIMUM_BLOCKS_TO_KEEP,
+            "The number of blocks to keep must be at least {}",
+            Self::MINIMUM_BLOCKS_TO_KEEP
+        );
+        // Load the pruned height of the previous run, if it exists.
+        let pruned_height = match path.exists() {
+            true => fs::read_to_string(&path)?
+                .trim()
+                .parse::<u32>()
+                .map_err(|err| anyhow!("Couldn't parse the pruned height at {} - {err}", path.display()))?,
+            false => 0,
+        };
+        Ok(Self {
+            ledger,
+            blocks_to_keep,
+            pruned_height: Arc::new(AtomicU32::new(pruned_height)),
+            path,
+            cold_store: None,
+            max_ledger_size: None,
+        })
+    }
+
+    /// Sets the cold store that the full blocks are migrated to before they are pruned.
+    pub fn with_cold_store(mut self, cold_store: Option<ColdStore<N>>) -> Self {
+        self.cold_store = cold_store;
+        self
+    }
+
+    /// Sets the maximum size (in bytes) of the ledger at the given directory, beyond which the transactions of
+    /// the oldest blocks are discarded, down to the minimum number of blocks to keep.
+    pub fn with_max_ledger_size(mut self, ledger_dir: PathBuf, max_ledger_size: Option<u64>) -> Self {
+        self.max_ledger_size = max_ledger_size.map(|max_ledger_size| (ledger_dir, max_ledger_size));
+        self
+    }
+
+    /// Returns the maximum size (in bytes) of the ledger, if any.
+    pub fn max_ledger_size(&self) -> Option<u64> {
+        self.max_ledger_size.as_ref().map(|(_, max_ledger_size)| *max_ledger_size)
+    }
+
+    /// Returns the number of most recent blocks whose transactions are retained.
+    pub const fn blocks_to_keep(&self) -> u32 {
+        self.blocks_to_keep
+    }
+
+    /// Returns the greatest height whose transactions have been discarded.
+    pub fn pruned_height(&self) -> u32 {
+        self.pruned_height.load(Ordering::SeqCst)
+    }
+
+    /// Returns the cold store, if any.
+    pub const fn cold_store(&self) -> Option<&ColdStore<N>> {
+        self.cold_store.as_ref()
+    }
+
+    /// Returns `true` if the block at the given height has been pruned.
+    pub fn is_pruned(&self, height: u32) -> bool {
+        height != 0 && height <= self.pruned_height()
+    }
+
+    /// Returns the blocks in the given range, reading the pruned ones through from the cold store,
+    /// or `None` if some of them were pruned without a cold store.
+    pub fn get_blocks(&self, heights: Range<u32>) -> Result<Option<Vec<Block<N>>>> {
+        let Some(cold_store) = &self.cold_store else {
+            return match heights.clone().any(|height| self.is_pruned(height)) {
+                true => Ok(None),
+                false => self.ledger.get_blocks(heights).map(Some),
+            };
+        };
+        heights
+            .map(|height| match self.is_pruned(height) {
+                true => cold_store.get(height)?.ok_or_else(|| anyhow!("Block {height} is missing from the cold store")),
+                false => self.ledger.get_block(height),
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(Some)
+    }
+
+    /// Discards the proofs and the output records of the blocks that fell out of the retained range.
+    /// Note: This method is blocking, and should be called from a blocking task.
+    pub fn prune(&self) -> Result<()> {
+        // Retain only the minimum number of blocks while the ledger exceeds its maximum size.
+        let mut blocks_to_keep = self.blocks_to_keep;
+        if let Some((ledger_dir, max_ledger_size)) = &self.max_ledger_size {
+            let ledger_size = directory_size(ledger_dir)?;
+            if ledger_size > *max_ledger_size {
+                blocks_to_keep = blocks_to_keep.min(Self::MINIMUM_BLOCKS_TO_KEEP);
+                debug!("The ledger exceeds its maximum size ({ledger_size} > {max_ledger_size} bytes)");
+            }
+        }
+        // Determine the heights to prune in this iteration.
+        let heights = next_heights(self.pruned_height(), self.ledger.latest_height(), blocks_to_keep);
+        if heights.is_empty() {
+            if blocks_to_keep < self.blocks_to_keep {
+                warn!("The ledger exceeds its maximum size, but only the minimum number of blocks is retained already");
+            }
+            return Ok(());
+        }
+        // Discard the transactions of each block, in ascending order.
+        for height in heights.clone() {
+            let block = self.ledger.get_block(height)?;
+            // Migrate the full block to the cold store first, if any.
+            if let Some(cold_store) = &self.cold_store {
+                cold_store.insert(&block)?;
+            }
+            for transaction in block.transactions().iter() {
+                self.prune_transaction(transaction)?;
+            }
+            self.pruned_height.store(height, Ordering::SeqCst);
+        }
+        // Persist the pruned height, so that pruning resumes from it after a restart.
+        fs::write(&self.path, self.pruned_height().to_string())
+            .map_err(|err| anyhow!("Couldn't write the pruned height to {} - {err}", self.path.display()))?;
+        debug!("Pruned the transactions of blocks {} to {}", heights.start, heights.end - 1);
+        Ok(())
+    }
+
+    /// Replaces the given transaction in the ledger with its pruned form, in a single atomic batch.
+    fn prune_transaction(&self, transaction: &Transaction<N>) -> Result<()> {
+        let Some(pruned) = prune_transaction(transaction)? else {
+            return Ok(());
+        };
+        let transaction_store = self.ledger.vm().block_store().transaction_store();
+        transaction_store.start_atomic();
+        match transaction_store.remove(&transaction.id()).and_then(|_| transaction_store.insert(&pruned)) {
+            Ok(()) => transaction_store.finish_atomic(),
+            Err(error) => {
+                transaction_store.abort_atomic();
+                Err(error)
+            }
+        }
+    }
+}
+
+/// Returns the given execution without its proofs and its output records, or `None` for a deployment.
+/// Note: The IDs of a transaction and its transitions commit to neither, so they are left unchanged,
+/// along with the serial numbers and the commitments.
+fn prune_transaction<N: Network>(transaction: &Transaction<N>) -> Result<Option<Transaction<N>>> {
+    let Transaction::Execute(_, execution, fee) = transaction else {
+        return Ok(None);
+    };
+    let transitions = execution.transitions().map(prune_transition).collect::<Result<Vec<_>>>()?;
+    let execution = Execution::from(transitions.into_iter(), execution.global_state_root(), None)?;
+    let fee = fee
+        .as_ref()
+        .map(|fee| {
+            Ok::<_, Error>(Fee::from_unchecked(prune_transition(fee.transition())?, fee.global_state_root(), None))
+        })
+        .transpose()?;
+    let pruned = Transaction::from_execution(execution, fee)?;
+    ensure!(pruned.id() == transaction.id(), "Pruning changed the ID of transaction '{}'", transaction.id());
+    Ok(Some(pruned))
+}
+
+/// Returns the given transition without its output records.
+fn prune_transition<N: Network>(transition: &Transition<N>) -> Result<Transition<N>> {
+    let outputs = transition
+        .outputs()
+        .iter()
+        .map(|output| match output {
+            Output::Record(commitment, checksum, _) => Output::Record(*commitment, *checksum, None),
+            output => output.clone(),
+        })
+        .collect();
+    Transition::new(
+        *transition.program_id(),
+        *transition.function_name(),
+        transition.inputs().to_vec(),
+        outputs,
+        *transition.tpk(),
+        *transition.tcm(),
+        *transition.scm(),
+    )
+}
+
+/// Returns the total size (in bytes) of the files in the given directory, including its subdirectories.
+fn directory_size(path: &Path) -> Result<u64> {
+    let mut size = 0;
+    for entry in fs::read_dir(path).map_err(|err| anyhow!("Couldn't read the directory {} - {err}", path.display()))? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        // Note: The metadata does not follow the symbolic links, which are not counted.
+        if metadata.is_dir() {
+            size += directory_size(&entry.path())?;
+        } else if metadata.is_file() {
+            size += metadata.len();
+        }
+    }
+    Ok(size)
+}
+
+/// Returns the heights to prune next, given the pruned height, the latest height and the number of blocks to keep.
+/// Note: The genesis block is never pruned.
+fn next_heights(pruned_height: u32, latest_height: u32, blocks_to_keep: u32) -> Range<u32> {
+    let start = pruned_height + 1;
+    let end = latest_height.saturating_sub(blocks_to_keep).saturating_add(1).max(start);
+    start..end.min(start + MAXIMUM_BLOCKS_PER_ITERATION)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{ledger::store::helpers::memory::ConsensusMemory, prelude::MainnetV0};
+
+    use aleo_std::StorageMode;
+    use rand::Rng;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_next_heights() {
+        // Ensure nothing is pruned while the ledger is shorter than the retained range.
+        assert!(next_heights(0, 0, 100).is_empty());
+        assert!(next_heights(0, 100, 100).is_empty());
+        // Ensure the genesis block is never pruned, and the most recent blocks are retained.
+        assert_eq!(next_heights(0, 150, 100), 1..51);
+        assert_eq!(next_heights(50, 150, 100), 51..51);
+        assert_eq!(next_heights(40, 150, 100), 41..51);
+        // Ensure a single iteration is bounded.
+        assert_eq!(next_heights(0, 10_000, 100), 1..MAXIMUM_BLOCKS_PER_ITERATION + 1);
+    }
+
+    #[test]
+    fn test_prune_transaction() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let ledger =
+            Ledger::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::load(genesis.clone(), StorageMode::Production)
+                .unwrap();
+        let path = std::env::temp_dir().join(format!("snarkos-pruned-height-{}", rand::thread_rng().gen::<u64>()));
+        let pruner = Pruner::new(ledger.clone(), Pruner::<CurrentNetwork, _>::MINIMUM_BLOCKS_TO_KEEP, path).unwrap();
+
+        for transaction in genesis.transactions().iter() {
+            pruner.prune_transaction(transaction).unwrap();
+
+            // Ensure the proofs and the output records are discarded.
+            let pruned = ledger.get_transaction(transaction.id()).unwrap();
+            if let Transaction::Execute(_, execution, _) = &pruned {
+                assert!(execution.proof().is_none());
+            }
+            assert!(pruned
+                .transitions()
+                .flat_map(Transition::outputs)
+                .all(|output| !matches!(output, Output::Record(_, _, Some(_)))));
+            // Ensure the serial numbers and the commitments are kept.
+            for serial_number in transaction.serial_numbers() {
+                assert!(ledger.contains_serial_number(serial_number).unwrap());
+            }
+            for commitment in transaction.commitments() {
+                assert!(ledger.contains_commitment(commitment).unwrap());
+            }
+            // Ensure the transaction is still rejected as a replay.
+            assert!(ledger.vm().check_transaction(transaction, None, &mut rand::thread_rng()).is_err());
+        }
+
+        // Ensure the pruned block is still read, and still matches its header.
+        let block = ledger.get_block(0).unwrap();
+        assert_eq!(block.hash(), genesis.hash());
+        assert_eq!(block.transactions().to_transactions_root().unwrap(), genesis.header().transactions_root());
+    }
+
+    #[test]
+    fn test_directory_size() {
+        let path = std::env::temp_dir().join(format!("snarkos-directory-size-{}", rand::thread_rng().gen::<u64>()));
+        fs::create_dir_all(path.join("nested")).unwrap();
+        assert_eq!(directory_size(&path).unwrap(), 0);
+
+        // Ensure the files in the nested directories are counted.
+        fs::write(path.join("a"), [0u8; 100]).unwrap();
+        fs::write(path.join("nested").join("b"), [0u8; 50]).unwrap();
+        assert_eq!(directory_size(&path).unwrap(), 150);
+
+        // Ensure a missing directory is an error.
+        fs::remove_dir_all(&path).unwrap();
+        assert!(directory_size(&path).is_err());
+    }
+}
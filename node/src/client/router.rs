@@ -14,27 +14,31 @@
 
 use super::*;
 use snarkos_node_router::{
+    message_priority,
     messages::{
         BlockRequest,
         BlockResponse,
+        BlockTransactionsRequest,
+        BlockTransactionsResponse,
+        CompactBlock,
         DataBlocks,
         DisconnectReason,
-        MessageCodec,
         Ping,
         Pong,
         PuzzleResponse,
         UnconfirmedTransaction,
     },
     Routing,
+    TrafficCodec,
 };
 use snarkos_node_sync::communication_service::CommunicationService;
-use snarkos_node_tcp::{Connection, ConnectionSide, Tcp};
+use snarkos_node_tcp::{protocols::MessagePriority, Connection, ConnectionSide, Tcp};
 use snarkvm::{
     ledger::narwhal::Data,
     prelude::{block::Transaction, Network},
 };
 
-use std::{io, net::SocketAddr, time::Duration};
+use std::{io, net::SocketAddr};
 
 impl<N: Network, C: ConsensusStorage<N>> P2P for Client<N, C> {
     /// Returns a reference to the TCP instance.
@@ -93,25 +97,30 @@ impl<N: Network, C: ConsensusStorage<N>> Disconnect for Client<N, C> {
 
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> Writing for Client<N, C> {
-    type Codec = MessageCodec<N>;
+    type Codec = TrafficCodec<N>;
     type Message = Message<N>;
 
     /// Creates an [`Encoder`] used to write the outbound messages to the target stream.
     /// The `side` parameter indicates the connection side **from the node's perspective**.
-    fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router().traffic_codec(peer_addr)
+    }
+
+    /// Returns the outbound queue priority of the given message.
+    fn priority(&self, message: &Self::Message) -> MessagePriority {
+        message_priority(message)
     }
 }
 
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> Reading for Client<N, C> {
-    type Codec = MessageCodec<N>;
+    type Codec = TrafficCodec<N>;
     type Message = Message<N>;
 
     /// Creates a [`Decoder`] used to interpret messages from the network.
     /// The `side` param indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router().traffic_codec(peer_addr)
     }
 
     /// Processes a message received from the network.
@@ -146,6 +155,27 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
             }
         }
     }
+
+    /// Reconstructs the announced block from the given transactions and the pool, and advances the ledger with it.
+    /// Returns `false` if the block could not be reconstructed.
+    fn advance_with_compact_block(
+        &self,
+        peer_ip: SocketAddr,
+        compact_block: &CompactBlock<N>,
+        transactions: Vec<Transaction<N>>,
+    ) -> bool {
+        let block = match self.router.compact_blocks().reconstruct(compact_block, transactions) {
+            Ok(block) => block,
+            Err(error) => {
+                warn!("Failed to reconstruct block {} from '{peer_ip}' - {error}", compact_block.height());
+                return false;
+            }
+        };
+        if let Err(error) = self.sync.advance_with_announced_block(block) {
+            debug!("Skipping the block announced by '{peer_ip}' - {error}");
+        }
+        true
+    }
 }
 
 #[async_trait]
@@ -201,9 +231,18 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Client<N, C> {
     fn block_request(&self, peer_ip: SocketAddr, message: BlockRequest) -> bool {
         let BlockRequest { start_height, end_height } = &message;
 
-        // Retrieve the blocks within the requested range.
-        let blocks = match self.ledger.get_blocks(*start_height..*end_height) {
-            Ok(blocks) => Data::Object(DataBlocks(blocks)),
+        // Retrieve the blocks within the requested range, reading pruned blocks through from the cold store.
+        let blocks = match self.pruner.as_ref() {
+            Some(pruner) => pruner.get_blocks(*start_height..*end_height),
+            None => self.ledger.get_blocks(*start_height..*end_height).map(Some),
+        };
+        let blocks = match blocks {
+            Ok(Some(blocks)) => Data::Object(DataBlocks(blocks)),
+            // If the blocks were pruned, ignore the request; the peer will retry with another node.
+            Ok(None) => {
+                debug!("Ignoring a request for pruned blocks {start_height} to {end_height} from '{peer_ip}'");
+                return true;
+            }
             Err(error) => {
                 error!("Failed to retrieve blocks {start_height} to {end_height} from the ledger - {error}");
                 return false;
@@ -226,6 +265,57 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Client<N, C> {
         }
     }
 
+    /// Handles a `CompactBlock` message, by reconstructing the next block from the transactions in the pool,
+    /// and requesting the missing ones from the peer.
+    fn compact_block(&self, peer_ip: SocketAddr, message: CompactBlock<N>) -> bool {
+        // Only the next block is reconstructed, as the other blocks are synced through block requests.
+        if message.height() != self.ledger.latest_height() + 1 || message.previous_hash != self.ledger.latest_hash() {
+            return true;
+        }
+        // If the transactions are in the pool, reconstruct the block right away.
+        let missing_ids = self.router.compact_blocks().missing_transaction_ids(&message);
+        if missing_ids.is_empty() {
+            return self.advance_with_compact_block(peer_ip, &message, vec![]);
+        }
+        // Otherwise, request the missing transactions from the peer.
+        let block_hash = message.block_hash;
+        if self.router.compact_blocks().insert_pending(peer_ip, message) {
+            trace!("Requesting {} missing transactions of the announced block from '{peer_ip}'", missing_ids.len());
+            let request = BlockTransactionsRequest { block_hash, transaction_ids: missing_ids };
+            Outbound::send(self, peer_ip, Message::BlockTransactionsRequest(request));
+        }
+        true
+    }
+
+    /// Handles a `BlockTransactionsRequest` message, by sending back the transactions of the announced block.
+    fn block_transactions_request(&self, peer_ip: SocketAddr, message: BlockTransactionsRequest<N>) -> bool {
+        let response = self
+            .ledger
+            .get_height(&message.block_hash)
+            .and_then(|height| self.ledger.get_block(height))
+            .and_then(|block| BlockTransactionsResponse::new(&block, &message.transaction_ids));
+        match response {
+            Ok(response) => {
+                Outbound::send(self, peer_ip, Message::BlockTransactionsResponse(response));
+                true
+            }
+            Err(error) => {
+                warn!("Failed to serve the block transactions requested by '{peer_ip}' - {error}");
+                false
+            }
+        }
+    }
+
+    /// Handles a `BlockTransactionsResponse` message, by reconstructing the announced block.
+    fn block_transactions_response(
+        &self,
+        peer_ip: SocketAddr,
+        compact_block: CompactBlock<N>,
+        transactions: Vec<Transaction<N>>,
+    ) -> bool {
+        self.advance_with_compact_block(peer_ip, &compact_block, transactions)
+    }
+
     /// Processes the block locators and sends back a `Pong` message.
     fn ping(&self, peer_ip: SocketAddr, message: Ping<N>) -> bool {
         // Check if the sync module is in router mode.
@@ -245,13 +335,18 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Client<N, C> {
         true
     }
 
-    /// Sleeps for a period and then sends a `Ping` message to the peer.
+    /// Records the round-trip time to the peer, then sleeps for a period and sends a `Ping` message to the peer.
     fn pong(&self, peer_ip: SocketAddr, _message: Pong) -> bool {
+        // Update the round-trip time of the peer in the sync pool.
+        if let Some(rtt) = self.router().get_latency(&peer_ip) {
+            self.sync.update_peer_latency(peer_ip, rtt);
+        }
+
         // Spawn an asynchronous task for the `Ping` request.
         let self_ = self.clone();
         tokio::spawn(async move {
             // Sleep for the preset time before sending a `Ping` request.
-            tokio::time::sleep(Duration::from_secs(Self::PING_SLEEP_IN_SECS)).await;
+            tokio::time::sleep(self_.router().keep_alive().ping_interval()).await;
             // Check that the peer is still connected.
             if self_.router().is_connected(&peer_ip) {
                 // Retrieve the block locators.
@@ -338,6 +433,8 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Client<N, C> {
         }
         // Check that the transaction is well-formed and unique.
         if self.ledger.check_transaction_basic(&transaction, None, &mut rand::thread_rng()).is_ok() {
+            // Keep the transaction to reconstruct compact blocks.
+            self.router.compact_blocks().insert_transaction(transaction);
             // Propagate the `UnconfirmedTransaction`.
             self.propagate(Message::UnconfirmedTransaction(serialized), &[peer_ip]);
         }
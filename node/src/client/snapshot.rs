@@ -0,0 +1,101 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{write_backup, BackupManifest};
+use snarkvm::{
+    console::network::Network,
+    ledger::{
+        store::{
+            helpers::rocksdb::internal::{self, Database},
+            ConsensusStorage,
+        },
+        Ledger,
+    },
+};
+
+use aleo_std::StorageMode;
+use anyhow::{anyhow, ensure, Result};
+use rocksdb::IteratorMode;
+use std::{
+    fs::{self, File},
+    io::BufWriter,
+    path::Path,
+};
+use time::OffsetDateTime;
+
+/// Writes a state snapshot of the given (live) ledger to the given path, and returns its manifest.
+///
+/// A state snapshot is a backup archive of the whole ledger database, holding the finalize state along with
+/// the blocks that produced it, so that a new node restores it with `snarkos ledger restore` instead of
+/// replaying the chain. The restore verifies the blocks up to the latest one, which the new node is expected
+/// to cross-check with its peers. Note that no block commits to the finalize state itself, so the state is
+/// trusted to match the blocks, as it is read from the same consistent view of the database.
+pub fn write_snapshot<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    storage_mode: StorageMode,
+    path: &Path,
+) -> Result<BackupManifest<N>> {
+    let database = internal::RocksDB::open(N::ID, storage_mode)?;
+    // Read from a single view of the database, in which each block is either fully applied, or not at all.
+    let hash = ledger.latest_hash();
+    let view = database.snapshot();
+    // Ensure the ledger did not advance in the meantime, as the view would no longer match the manifest.
+    // Note: The restore rejects a snapshot whose latest block does not match its manifest regardless.
+    ensure!(ledger.latest_hash() == hash, "The ledger advanced while the snapshot was taken");
+    let manifest = BackupManifest {
+        network: N::ID,
+        height: ledger.latest_height(),
+        hash,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        timestamp: OffsetDateTime::now_utc().unix_timestamp(),
+        // Note: The state digest is not computed here, as it would hold up the live ledger.
+        digest: None,
+    };
+
+    // Write to a temporary file first, so that the served snapshot is never truncated.
+    let temp_path = path.with_extension("tmp");
+    let result = File::create(&temp_path)
+        .map_err(|err| anyhow!("Couldn't create the snapshot at {} - {err}", temp_path.display()))
+        .and_then(|file| {
+            let entries = view.iterator(IteratorMode::Start).map(|entry| Ok(entry?));
+            write_backup(entries, &manifest, BufWriter::new(file))
+        });
+    if let Err(error) = result {
+        let _ = fs::remove_file(&temp_path);
+        return Err(error);
+    }
+    fs::rename(&temp_path, path)?;
+    Ok(manifest)
+}
+
+/// Returns `true` if a snapshot should be taken at the given height, for the given (non-zero) interval.
+pub(crate) fn is_snapshot_due(last_height: Option<u32>, latest_height: u32, interval: u32) -> bool {
+    match last_height {
+        Some(last_height) => latest_height / interval > last_height / interval,
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_snapshot_due() {
+        assert!(is_snapshot_due(None, 0, 100));
+        assert!(!is_snapshot_due(Some(100), 150, 100));
+        assert!(!is_snapshot_due(Some(150), 199, 100));
+        assert!(is_snapshot_due(Some(150), 200, 100));
+    }
+}
@@ -0,0 +1,139 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::store::helpers::rocksdb::internal::{self, Database},
+    prelude::Network,
+};
+
+use aleo_std::StorageMode;
+use anyhow::{bail, ensure, Result};
+use std::{
+    str::FromStr,
+    time::{Duration, Instant},
+};
+use time::{Date, OffsetDateTime};
+
+/// A daily window of UTC hours in which the ledger database is compacted, such as `2-5`,
+/// so that the tombstones of a long-running node are discarded while its traffic is low.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CompactionSchedule {
+    /// The hour at which the window starts.
+    start_hour: u8,
+    /// The hour at which the window ends; if it precedes the start, the window spans midnight.
+    end_hour: u8,
+}
+
+impl CompactionSchedule {
+    /// The interval at which the node checks whether a compaction is due, in seconds.
+    const CHECK_INTERVAL_IN_SECS: u64 = 300;
+
+    /// Returns `true` if the given hour is within the window.
+    pub fn contains(&self, hour: u8) -> bool {
+        match self.start_hour < self.end_hour {
+            true => (self.start_hour..self.end_hour).contains(&hour),
+            false => hour >= self.start_hour || hour < self.end_hour,
+        }
+    }
+
+    /// Returns the future that compacts the ledger database once per day, within the window.
+    pub(crate) async fn run<N: Network>(self, storage_mode: StorageMode) {
+        let mut last_date: Option<Date> = None;
+        loop {
+            tokio::time::sleep(Duration::from_secs(Self::CHECK_INTERVAL_IN_SECS)).await;
+            let now = OffsetDateTime::now_utc();
+            if !self.contains(now.hour()) || last_date == Some(now.date()) {
+                continue;
+            }
+            last_date = Some(now.date());
+
+            let storage_mode = storage_mode.clone();
+            match tokio::task::spawn_blocking(move || compact_ledger::<N>(storage_mode)).await {
+                Ok(Ok(stats)) => info!(
+                    "Compacted the ledger from {} to {} bytes in {:.1} seconds",
+                    stats.size_before,
+                    stats.size_after,
+                    stats.elapsed.as_secs_f64()
+                ),
+                Ok(Err(error)) => warn!("Failed to compact the ledger - {error}"),
+                Err(error) => warn!("Failed to compact the ledger - {error}"),
+            }
+        }
+    }
+}
+
+impl FromStr for CompactionSchedule {
+    type Err = anyhow::Error;
+
+    /// Parses a window of UTC hours, such as `2-5`.
+    fn from_str(s: &str) -> Result<Self> {
+        let Some((start_hour, end_hour)) = s.split_once('-') else {
+            bail!("The compaction schedule '{s}' must be a range of UTC hours, such as '2-5'");
+        };
+        let (start_hour, end_hour) = (start_hour.trim().parse::<u8>()?, end_hour.trim().parse::<u8>()?);
+        ensure!(start_hour < 24 && end_hour < 24, "The hours of the compaction schedule must be below 24");
+        ensure!(start_hour != end_hour, "The compaction schedule must not be empty");
+        Ok(Self { start_hour, end_hour })
+    }
+}
+
+/// The statistics of a compaction of the ledger database.
+#[derive(Copy, Clone, Debug)]
+pub struct CompactionStats {
+    /// The size of the SST files before the compaction, in bytes.
+    pub size_before: u64,
+    /// The size of the SST files after the compaction, in bytes.
+    pub size_after: u64,
+    /// The duration of the compaction.
+    pub elapsed: Duration,
+}
+
+/// Compacts the full key range of the ledger database of this process.
+/// Note: This method is blocking, and should be called from a blocking task.
+pub fn compact_ledger<N: Network>(storage_mode: StorageMode) -> Result<CompactionStats> {
+    // Note: The database is opened once per process, so this is the same instance that the ledger uses.
+    let database = internal::RocksDB::open(N::ID, storage_mode)?;
+    let size_before = database.property_int_value("rocksdb.total-sst-files-size")?.unwrap_or_default();
+    let timer = Instant::now();
+    database.compact_range(None::<&[u8]>, None::<&[u8]>);
+    let elapsed = timer.elapsed();
+    let size_after = database.property_int_value("rocksdb.total-sst-files-size")?.unwrap_or_default();
+    Ok(CompactionStats { size_before, size_after, elapsed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compaction_schedule() {
+        let schedule = CompactionSchedule::from_str("2-5").unwrap();
+        assert!(!schedule.contains(1));
+        assert!(schedule.contains(2));
+        assert!(schedule.contains(4));
+        assert!(!schedule.contains(5));
+
+        // Ensure a window may span midnight.
+        let schedule = CompactionSchedule::from_str("22-1").unwrap();
+        assert!(schedule.contains(23));
+        assert!(schedule.contains(0));
+        assert!(!schedule.contains(1));
+        assert!(!schedule.contains(12));
+
+        // Ensure the malformed schedules are rejected.
+        assert!(CompactionSchedule::from_str("2").is_err());
+        assert!(CompactionSchedule::from_str("2-24").is_err());
+        assert!(CompactionSchedule::from_str("3-3").is_err());
+    }
+}
@@ -0,0 +1,181 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod router;
+
+mod topology;
+pub use topology::*;
+
+use snarkos_account::Account;
+use snarkos_node_router::{messages::NodeType, KeepAlive, RelayMode, Router};
+use snarkos_node_tcp::{
+    protocols::{Disconnect, Handshake, OnConnect, Reading, Writing},
+    P2P,
+};
+use snarkvm::prelude::{block::Block, Network};
+
+use anyhow::Result;
+use indexmap::{IndexMap, IndexSet};
+use parking_lot::RwLock;
+use std::{
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The state recorded for each peer visited by the crawler.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CrawledPeer {
+    /// The message version of the peer, if it sent a `Ping`.
+    pub version: Option<u32>,
+    /// The node type of the peer, if it sent a `Ping`.
+    pub node_type: Option<NodeType>,
+    /// The latest block height of the peer, if it sent block locators.
+    pub height: Option<u32>,
+    /// Whether the crawler completed a handshake with the peer.
+    pub is_reachable: bool,
+    /// The peers advertised by this peer, if it sent a `PeerResponse`.
+    pub neighbors: Option<IndexSet<SocketAddr>>,
+}
+
+impl CrawledPeer {
+    /// Returns `true` if the crawler has learned everything it needs from the peer.
+    pub fn is_complete(&self) -> bool {
+        self.version.is_some() && self.neighbors.is_some()
+    }
+}
+
+/// A network crawler, which walks the peer-exchange responses across the network,
+/// recording the version, height, and connectivity of every peer it visits.
+#[derive(Clone)]
+pub struct Crawler<N: Network> {
+    /// The router of the crawler.
+    router: Router<N>,
+    /// The genesis block.
+    genesis: Block<N>,
+    /// The map of visited peer IPs to their crawled state.
+    peers: Arc<RwLock<IndexMap<SocketAddr, CrawledPeer>>>,
+    /// The maximum number of concurrent connections.
+    max_connections: usize,
+}
+
+impl<N: Network> Crawler<N> {
+    /// The duration in milliseconds to sleep in between crawl iterations.
+    const CRAWL_INTERVAL_IN_MILLIS: u64 = 500;
+    /// The duration in seconds to wait on a peer, before moving on.
+    const PEER_TIMEOUT_IN_SECS: u64 = 15;
+
+    /// Initializes a new crawler, starting from the given seed peers.
+    pub async fn new(
+        node_ip: SocketAddr,
+        account: Account<N>,
+        seeds: &[SocketAddr],
+        genesis: Block<N>,
+        max_connections: u16,
+        is_dev: bool,
+    ) -> Result<Self> {
+        // Initialize the router.
+        // Note: The crawler presents itself as a prover, as it does not maintain a ledger.
+        let router = Router::new(
+            node_ip,
+            &[],
+            NodeType::Prover,
+            account,
+            &[],
+            &[],
+            max_connections,
+            KeepAlive::default(),
+            RelayMode::default(),
+            true,
+            is_dev,
+        )
+        .await?;
+        // Initialize the crawler.
+        let crawler = Self { router, genesis, peers: Default::default(), max_connections: max_connections as usize };
+        // Enable the TCP protocols.
+        // Note: The heartbeat is not initialized, as the crawler manages its own connections.
+        crawler.enable_handshake().await;
+        crawler.enable_reading().await;
+        crawler.enable_writing().await;
+        crawler.enable_disconnect().await;
+        crawler.enable_on_connect().await;
+        crawler.tcp().enable_listener().await?;
+        // Add the seeds to the candidate peers.
+        crawler.router.insert_candidate_peers(seeds);
+        Ok(crawler)
+    }
+
+    /// Crawls the network for up to the given duration, and returns the topology of the visited peers.
+    pub async fn crawl(&self, duration: Duration) -> Topology {
+        let start = Instant::now();
+        // The map of in-flight peer IPs to the time the crawler connected to them.
+        let mut in_flight = IndexMap::<SocketAddr, Instant>::new();
+
+        while start.elapsed() < duration {
+            // Release the peers that are complete, or have timed out.
+            in_flight.retain(|peer_ip, connected_at| {
+                let is_complete = self.peers.read().get(peer_ip).map_or(false, |peer| peer.is_complete());
+                let is_timed_out = connected_at.elapsed() > Duration::from_secs(Self::PEER_TIMEOUT_IN_SECS);
+                if is_complete || is_timed_out {
+                    self.router.disconnect(*peer_ip);
+                }
+                !is_complete && !is_timed_out
+            });
+
+            // Select the unvisited candidate peers, up to the number of available connections.
+            let num_available = self.max_connections.saturating_sub(in_flight.len());
+            let candidates = self
+                .router
+                .candidate_peers()
+                .into_iter()
+                .filter(|peer_ip| !self.peers.read().contains_key(peer_ip))
+                .take(num_available)
+                .collect::<Vec<_>>();
+
+            // If there is nothing left to crawl, then stop.
+            if candidates.is_empty() && in_flight.is_empty() {
+                break;
+            }
+
+            // Connect to the candidate peers.
+            for peer_ip in candidates {
+                self.peers.write().insert(peer_ip, Default::default());
+                self.router.connect(peer_ip);
+                in_flight.insert(peer_ip, Instant::now());
+            }
+
+            debug!("Crawled {} peers ({} in flight)", self.peers.read().len(), in_flight.len());
+            tokio::time::sleep(Duration::from_millis(Self::CRAWL_INTERVAL_IN_MILLIS)).await;
+        }
+
+        // Shut down the router.
+        self.router.shut_down().await;
+
+        Topology::new(self.peers.read().clone())
+    }
+
+    /// Updates the crawled state of the given peer, and disconnects if the peer is complete.
+    fn update_peer<F: FnOnce(&mut CrawledPeer)>(&self, peer_ip: SocketAddr, update_fn: F) {
+        let is_complete = match self.peers.write().get_mut(&peer_ip) {
+            Some(peer) => {
+                update_fn(peer);
+                peer.is_complete()
+            }
+            None => false,
+        };
+        if is_complete {
+            self.router.disconnect(peer_ip);
+        }
+    }
+}
@@ -0,0 +1,227 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use snarkos_node_router::{
+    message_priority,
+    messages::{
+        BlockRequest,
+        DisconnectReason,
+        Message,
+        PeerRequest,
+        Ping,
+        Pong,
+        UnconfirmedSolution,
+        UnconfirmedTransaction,
+    },
+    Inbound,
+    Outbound,
+    TrafficCodec,
+};
+use snarkos_node_tcp::{is_bogon_ip, protocols::MessagePriority, Connection, ConnectionSide, Tcp};
+use snarkvm::prelude::{
+    block::{Header, Transaction},
+    puzzle::Solution,
+    Field,
+    Zero,
+};
+
+use std::io;
+
+impl<N: Network> P2P for Crawler<N> {
+    /// Returns a reference to the TCP instance.
+    fn tcp(&self) -> &Tcp {
+        self.router.tcp()
+    }
+}
+
+#[async_trait]
+impl<N: Network> Handshake for Crawler<N> {
+    /// Performs the handshake protocol.
+    async fn perform_handshake(&self, mut connection: Connection) -> io::Result<Connection> {
+        // Perform the handshake.
+        let peer_addr = connection.addr();
+        let conn_side = connection.side();
+        let stream = self.borrow_stream(&mut connection);
+        let genesis_header = *self.genesis.header();
+        let restrictions_id = Field::zero(); // The crawler bypasses restrictions, since it does not validate transactions.
+        self.router.handshake(peer_addr, stream, conn_side, genesis_header, restrictions_id).await?;
+
+        Ok(connection)
+    }
+}
+
+#[async_trait]
+impl<N: Network> OnConnect for Crawler<N> {
+    async fn on_connect(&self, peer_addr: SocketAddr) {
+        // Resolve the peer address to the listener address.
+        let Some(peer_ip) = self.router.resolve_to_listener(&peer_addr) else { return };
+        // Mark the peer as reachable.
+        self.update_peer(peer_ip, |peer| peer.is_reachable = true);
+        // Send the first `Ping` message to the peer.
+        self.send_ping(peer_ip, None);
+        // Request the peers of the peer.
+        self.send(peer_ip, Message::PeerRequest(PeerRequest));
+    }
+}
+
+#[async_trait]
+impl<N: Network> Disconnect for Crawler<N> {
+    /// Any extra operations to be performed during a disconnect.
+    async fn handle_disconnect(&self, peer_addr: SocketAddr) {
+        if let Some(peer_ip) = self.router.resolve_to_listener(&peer_addr) {
+            self.router.remove_connected_peer(peer_ip);
+        }
+    }
+}
+
+#[async_trait]
+impl<N: Network> Writing for Crawler<N> {
+    type Codec = TrafficCodec<N>;
+    type Message = Message<N>;
+
+    /// Creates an [`Encoder`] used to write the outbound messages to the target stream.
+    /// The `side` parameter indicates the connection side **from the node's perspective**.
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router().traffic_codec(peer_addr)
+    }
+
+    /// Returns the outbound queue priority of the given message.
+    fn priority(&self, message: &Self::Message) -> MessagePriority {
+        message_priority(message)
+    }
+}
+
+#[async_trait]
+impl<N: Network> Reading for Crawler<N> {
+    type Codec = TrafficCodec<N>;
+    type Message = Message<N>;
+
+    /// Creates a [`Decoder`] used to interpret messages from the network.
+    /// The `side` param indicates the connection side **from the node's perspective**.
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router().traffic_codec(peer_addr)
+    }
+
+    /// Processes a message received from the network.
+    async fn process_message(&self, peer_addr: SocketAddr, message: Self::Message) -> io::Result<()> {
+        // Process the message. Disconnect if the peer violated the protocol.
+        if let Err(error) = self.inbound(peer_addr, message).await {
+            if let Some(peer_ip) = self.router().resolve_to_listener(&peer_addr) {
+                debug!("Disconnecting from '{peer_addr}' - {error}");
+                // Disconnect from this peer.
+                self.router().disconnect(peer_ip);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<N: Network> Outbound<N> for Crawler<N> {
+    /// Returns a reference to the router.
+    fn router(&self) -> &Router<N> {
+        &self.router
+    }
+
+    /// Returns `true` if the node is synced up to the latest block (within the given tolerance).
+    fn is_block_synced(&self) -> bool {
+        true
+    }
+
+    /// Returns the number of blocks this node is behind the greatest peer height.
+    fn num_blocks_behind(&self) -> u32 {
+        0
+    }
+}
+
+#[async_trait]
+impl<N: Network> Inbound<N> for Crawler<N> {
+    /// Handles a `BlockRequest` message.
+    fn block_request(&self, peer_ip: SocketAddr, _message: BlockRequest) -> bool {
+        debug!("Disconnecting '{peer_ip}' for the following reason - {:?}", DisconnectReason::ProtocolViolation);
+        false
+    }
+
+    /// Handles a `BlockResponse` message.
+    fn block_response(&self, peer_ip: SocketAddr, _blocks: Vec<Block<N>>) -> bool {
+        debug!("Disconnecting '{peer_ip}' for the following reason - {:?}", DisconnectReason::ProtocolViolation);
+        false
+    }
+
+    /// Records the advertised peers, and adds them to the candidate peers.
+    fn peer_response(&self, peer_ip: SocketAddr, peers: &[SocketAddr]) -> bool {
+        // Filter out invalid addresses.
+        let peers = match self.router().is_dev() {
+            // In development mode, relax the validity requirements to make operating devnets more flexible.
+            true => peers.iter().copied().filter(|ip| !is_bogon_ip(ip.ip())).collect::<Vec<_>>(),
+            // In production mode, ensure the peer IPs are valid.
+            false => peers.iter().copied().filter(|ip| self.router().is_valid_peer_ip(ip)).collect(),
+        };
+        // Record the neighbors of the peer.
+        self.update_peer(peer_ip, |peer| peer.neighbors = Some(peers.iter().copied().collect()));
+        // Adds the given peer IPs to the list of candidate peers.
+        self.router().insert_candidate_peers(&peers);
+        true
+    }
+
+    /// Records the version, node type, and height of the peer, and sends back a `Pong` message.
+    fn ping(&self, peer_ip: SocketAddr, message: Ping<N>) -> bool {
+        // Send a `Pong` message to the peer.
+        self.send(peer_ip, Message::Pong(Pong { is_fork: Some(false) }));
+        // Record the state of the peer.
+        self.update_peer(peer_ip, |peer| {
+            peer.version = Some(message.version);
+            peer.node_type = Some(message.node_type);
+            peer.height = message.block_locators.as_ref().map(|locators| locators.latest_locator_height());
+        });
+        true
+    }
+
+    /// Does not send another `Ping`, as the crawler only needs a single round-trip.
+    fn pong(&self, _peer_ip: SocketAddr, _message: Pong) -> bool {
+        true
+    }
+
+    /// Disconnects on receipt of a `PuzzleRequest` message.
+    fn puzzle_request(&self, peer_ip: SocketAddr) -> bool {
+        debug!("Disconnecting '{peer_ip}' for the following reason - {:?}", DisconnectReason::ProtocolViolation);
+        false
+    }
+
+    /// Ignores the `PuzzleResponse` message.
+    fn puzzle_response(&self, _peer_ip: SocketAddr, _epoch_hash: N::BlockHash, _header: Header<N>) -> bool {
+        true
+    }
+
+    /// Ignores the `UnconfirmedSolution` message.
+    async fn unconfirmed_solution(
+        &self,
+        _peer_ip: SocketAddr,
+        _serialized: UnconfirmedSolution<N>,
+        _solution: Solution<N>,
+    ) -> bool {
+        true
+    }
+
+    /// Ignores the `UnconfirmedTransaction` message.
+    async fn unconfirmed_transaction(
+        &self,
+        _peer_ip: SocketAddr,
+        _serialized: UnconfirmedTransaction<N>,
+        _transaction: Transaction<N>,
+    ) -> bool {
+        true
+    }
+}
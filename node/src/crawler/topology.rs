@@ -0,0 +1,162 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CrawledPeer;
+
+use indexmap::{IndexMap, IndexSet};
+use serde_json::{json, Value};
+use std::{fmt::Write, net::SocketAddr};
+
+/// The topology of the network, as observed by the crawler.
+#[derive(Clone, Debug, Default)]
+pub struct Topology {
+    /// The map of visited peer IPs to their crawled state.
+    peers: IndexMap<SocketAddr, CrawledPeer>,
+}
+
+impl Topology {
+    /// Initializes a new topology from the given crawled peers.
+    pub fn new(peers: IndexMap<SocketAddr, CrawledPeer>) -> Self {
+        Self { peers }
+    }
+
+    /// Returns the crawled peers.
+    pub const fn peers(&self) -> &IndexMap<SocketAddr, CrawledPeer> {
+        &self.peers
+    }
+
+    /// Returns the number of peers that completed a handshake with the crawler.
+    pub fn num_reachable(&self) -> usize {
+        self.peers.values().filter(|peer| peer.is_reachable).count()
+    }
+
+    /// Returns the topology report as JSON.
+    pub fn to_json(&self) -> Value {
+        let peers = self
+            .peers
+            .iter()
+            .map(|(peer_ip, peer)| {
+                let neighbors =
+                    peer.neighbors.as_ref().map(|ips| ips.iter().map(|ip| ip.to_string()).collect::<Vec<_>>());
+                json!({
+                    "ip": peer_ip.to_string(),
+                    "reachable": peer.is_reachable,
+                    "version": peer.version,
+                    "node_type": peer.node_type.map(|node_type| node_type.to_string()),
+                    "height": peer.height,
+                    "neighbors": neighbors,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "num_peers": self.peers.len(),
+            "num_reachable": self.num_reachable(),
+            "peers": peers,
+        })
+    }
+
+    /// Returns the topology report as GraphML, where each peer is a node and each advertised neighbor is an edge.
+    pub fn to_graphml(&self) -> String {
+        let mut output = String::new();
+        output += "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n";
+        output += "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n";
+        output += "  <key id=\"reachable\" for=\"node\" attr.name=\"reachable\" attr.type=\"boolean\"/>\n";
+        output += "  <key id=\"version\" for=\"node\" attr.name=\"version\" attr.type=\"long\"/>\n";
+        output += "  <key id=\"node_type\" for=\"node\" attr.name=\"node_type\" attr.type=\"string\"/>\n";
+        output += "  <key id=\"height\" for=\"node\" attr.name=\"height\" attr.type=\"long\"/>\n";
+        output += "  <graph id=\"snarkos\" edgedefault=\"directed\">\n";
+
+        // Collect every peer that appears in the topology, including the unvisited neighbors.
+        let mut node_ips = self.peers.keys().copied().collect::<IndexSet<_>>();
+        node_ips.extend(self.peers.values().filter_map(|peer| peer.neighbors.as_ref()).flatten().copied());
+
+        // Write the nodes.
+        for peer_ip in &node_ips {
+            let _ = writeln!(output, "    <node id=\"{peer_ip}\">");
+            if let Some(peer) = self.peers.get(peer_ip) {
+                let _ = writeln!(output, "      <data key=\"reachable\">{}</data>", peer.is_reachable);
+                if let Some(version) = peer.version {
+                    let _ = writeln!(output, "      <data key=\"version\">{version}</data>");
+                }
+                if let Some(node_type) = peer.node_type {
+                    let _ = writeln!(output, "      <data key=\"node_type\">{node_type}</data>");
+                }
+                if let Some(height) = peer.height {
+                    let _ = writeln!(output, "      <data key=\"height\">{height}</data>");
+                }
+            }
+            output += "    </node>\n";
+        }
+
+        // Write the edges.
+        for (peer_ip, peer) in &self.peers {
+            for neighbor_ip in peer.neighbors.iter().flatten() {
+                let _ = writeln!(output, "    <edge source=\"{peer_ip}\" target=\"{neighbor_ip}\"/>");
+            }
+        }
+
+        output += "  </graph>\n";
+        output += "</graphml>\n";
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_router::messages::NodeType;
+
+    fn sample_topology() -> Topology {
+        let peer_a: SocketAddr = "1.2.3.4:4130".parse().unwrap();
+        let peer_b: SocketAddr = "5.6.7.8:4130".parse().unwrap();
+        let peer_c: SocketAddr = "9.10.11.12:4130".parse().unwrap();
+
+        let mut peers = IndexMap::new();
+        peers.insert(peer_a, CrawledPeer {
+            version: Some(15),
+            node_type: Some(NodeType::Validator),
+            height: Some(100),
+            is_reachable: true,
+            neighbors: Some([peer_b, peer_c].into_iter().collect()),
+        });
+        peers.insert(peer_b, CrawledPeer::default());
+        Topology::new(peers)
+    }
+
+    #[test]
+    fn test_topology_to_json() {
+        let topology = sample_topology();
+        let json = topology.to_json();
+        assert_eq!(json["num_peers"], 2);
+        assert_eq!(json["num_reachable"], 1);
+        assert_eq!(json["peers"][0]["ip"], "1.2.3.4:4130");
+        assert_eq!(json["peers"][0]["height"], 100);
+        assert_eq!(json["peers"][0]["node_type"], "Validator");
+        assert_eq!(json["peers"][0]["neighbors"].as_array().unwrap().len(), 2);
+        assert_eq!(json["peers"][1]["reachable"], false);
+        assert!(json["peers"][1]["neighbors"].is_null());
+    }
+
+    #[test]
+    fn test_topology_to_graphml() {
+        let topology = sample_topology();
+        let graphml = topology.to_graphml();
+        // Ensure the unvisited neighbor is included as a node.
+        assert_eq!(graphml.matches("<node ").count(), 3);
+        assert_eq!(graphml.matches("<edge ").count(), 2);
+        assert!(graphml.contains("<edge source=\"1.2.3.4:4130\" target=\"9.10.11.12:4130\"/>"));
+        assert!(graphml.contains("<data key=\"height\">100</data>"));
+    }
+}
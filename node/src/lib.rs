@@ -30,22 +30,103 @@ pub use snarkos_node_sync as sync;
 pub use snarkos_node_tcp as tcp;
 pub use snarkvm;
 
+mod alerts;
+pub use alerts::*;
+
+mod backup;
+pub use backup::*;
+
 mod client;
 pub use client::*;
 
+mod compaction;
+pub use compaction::*;
+
+mod crawler;
+pub use crawler::*;
+
+mod state_diff;
+pub use state_diff::*;
+
+mod light;
+pub use light::*;
+
 mod prover;
 pub use prover::*;
 
 mod validator;
 pub use validator::*;
 
+mod membership;
+pub use membership::*;
+
 mod node;
 pub use node::*;
 
 mod traits;
 pub use traits::*;
 
+mod watchdog;
+pub use watchdog::*;
+
 use aleo_std::StorageMode;
+use std::path::PathBuf;
+
+/// Returns the path where the peer cache of the node is stored, right next to its ledger.
+pub fn peer_cache_path(network: u16, storage_mode: &StorageMode) -> PathBuf {
+    let mut path = aleo_std::aleo_ledger_dir(network, storage_mode.clone()).into_os_string();
+    path.push(".peers");
+    path.into()
+}
+
+/// Returns the path where the pruned height of the node is stored, right next to its ledger.
+pub fn pruned_height_path(network: u16, storage_mode: &StorageMode) -> PathBuf {
+    let mut path = aleo_std::aleo_ledger_dir(network, storage_mode.clone()).into_os_string();
+    path.push(".pruned");
+    path.into()
+}
+
+/// Returns the path where the blocks marked as invalid by the operator are stored, right next to the ledger.
+pub fn invalid_blocks_path(network: u16, storage_mode: &StorageMode) -> PathBuf {
+    let mut path = aleo_std::aleo_ledger_dir(network, storage_mode.clone()).into_os_string();
+    path.push(".invalid");
+    path.into()
+}
+
+/// Returns the path where the pending blocks of the sync are journaled as they are received, right next to the ledger.
+pub fn pending_blocks_path(network: u16, storage_mode: &StorageMode) -> PathBuf {
+    let mut path = aleo_std::aleo_ledger_dir(network, storage_mode.clone()).into_os_string();
+    path.push(".pending");
+    path.into()
+}
+
+/// Returns the path where the memory pool of the validator is stored on shutdown, right next to the ledger.
+pub fn mempool_path(network: u16, storage_mode: &StorageMode) -> PathBuf {
+    let mut path = aleo_std::aleo_ledger_dir(network, storage_mode.clone()).into_os_string();
+    path.push(".mempool");
+    path.into()
+}
+
+/// Returns the path where the analytics index of the node is stored, right next to its ledger.
+pub fn analytics_index_path(network: u16, storage_mode: &StorageMode) -> PathBuf {
+    let mut path = aleo_std::aleo_ledger_dir(network, storage_mode.clone()).into_os_string();
+    path.push(".analytics");
+    path.into()
+}
+
+/// Returns the path where the latest state snapshot of the node is stored, right next to its ledger.
+pub fn snapshot_path(network: u16, storage_mode: &StorageMode) -> PathBuf {
+    let mut path = aleo_std::aleo_ledger_dir(network, storage_mode.clone()).into_os_string();
+    path.push(".snapshot");
+    path.into()
+}
+
+/// Returns the path where the light client persists its tracked block headers, right next to the ledger.
+pub fn light_headers_path(network: u16, storage_mode: &StorageMode) -> PathBuf {
+    let mut path = aleo_std::aleo_ledger_dir(network, storage_mode.clone()).into_os_string();
+    path.push(".light");
+    path.into()
+}
 
 /// A helper to log instructions to recover.
 pub fn log_clean_error(storage_mode: &StorageMode) {
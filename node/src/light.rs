@@ -0,0 +1,572 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    console::network::{CanaryV0, MainnetV0, TestnetV0},
+    ledger::{
+        authority::Authority,
+        block::{Block, Header, Ratify, TransactionsPath},
+        committee::Committee,
+        narwhal::BatchCertificate,
+    },
+    prelude::{Address, DeserializeOwned, FromBytes, Network, ToBits, ToBytes},
+};
+
+use anyhow::{bail, ensure, Result};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::get,
+    Json,
+    Router,
+};
+use parking_lot::RwLock;
+use reqwest::Client;
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// A light client tracks the block headers of the network through the REST API of one or more
+/// full nodes (the sources), without maintaining a ledger. Every header is checked to link to the
+/// previous one, and to be anchored by a certificate signed by a quorum of the committee, while
+/// the committees and block hashes must be reported unanimously by the sources. The committees are
+/// trusted from the genesis committee onwards, as each new committee must be endorsed by at least
+/// f+1 stake of the previous trusted committee, so that at least one honest member vouches for it.
+#[derive(Clone)]
+pub struct LightClient<N: Network> {
+    /// The base URLs of the REST API of the sources.
+    sources: Vec<String>,
+    /// The HTTP client.
+    client: Client,
+    /// The most recent block headers, along with their block hashes.
+    headers: Arc<RwLock<BTreeMap<u32, (N::BlockHash, Header<N>)>>>,
+    /// The trusted committees, by the height of the block they are in effect at.
+    committees: Arc<RwLock<BTreeMap<u32, Committee<N>>>>,
+    /// The path where the most recent block headers and trusted committees are persisted, if any.
+    path: Option<PathBuf>,
+    /// The shutdown signal.
+    shutdown: Arc<AtomicBool>,
+}
+
+impl<N: Network> LightClient<N> {
+    /// The maximum number of trusted committees retained.
+    const MAXIMUM_COMMITTEES: usize = 100;
+    /// The maximum number of block headers retained.
+    const MAXIMUM_HEADERS: usize = 10_000;
+    /// The maximum number of block headers persisted.
+    const MAXIMUM_PERSISTED_HEADERS: usize = 1_000;
+    /// The number of blocks between the persisted snapshots of the block headers.
+    const PERSIST_INTERVAL: u32 = 100;
+    /// The duration in seconds to sleep once the light client caught up with the sources.
+    const SYNC_INTERVAL_IN_SECS: u64 = 5;
+
+    /// Initializes a new light client, starting from the given genesis block.
+    pub fn new(genesis: Block<N>, sources: Vec<String>, shutdown: Arc<AtomicBool>) -> Result<Self> {
+        ensure!(!sources.is_empty(), "A light client requires at least one source");
+        let sources = sources.into_iter().map(|source| source.trim_end_matches('/').to_string()).collect();
+        let headers = BTreeMap::from([(genesis.height(), (genesis.hash(), *genesis.header()))]);
+        // The trust in the committees is chained from the genesis committee.
+        let committee = match genesis.ratifications().iter().next() {
+            Some(Ratify::Genesis(committee, ..)) => *committee.clone(),
+            _ => bail!("The genesis block has no committee"),
+        };
+        let committees = BTreeMap::from([(genesis.height(), committee)]);
+        Ok(Self {
+            sources,
+            client: Client::new(),
+            headers: Arc::new(RwLock::new(headers)),
+            committees: Arc::new(RwLock::new(committees)),
+            path: None,
+            shutdown,
+        })
+    }
+
+    /// Persists the block headers and the trusted committees at the given path, resuming from the ones of the
+    /// previous run, if any.
+    pub fn with_storage(mut self, path: PathBuf) -> Result<Self> {
+        if path.exists() {
+            let (headers, committees) = load_state::<N>(&fs::read(&path)?)?;
+            info!("Resuming the light client from block {}", headers.keys().last().copied().unwrap_or_default());
+            *self.headers.write() = headers;
+            *self.committees.write() = committees;
+        }
+        self.path = Some(path);
+        Ok(self)
+    }
+
+    /// Returns the latest tracked block height.
+    pub fn latest_height(&self) -> u32 {
+        self.headers.read().keys().last().copied().unwrap_or_default()
+    }
+
+    /// Returns the tracked block hash and header at the given height, if it is retained.
+    pub fn get_header(&self, height: u32) -> Option<(N::BlockHash, Header<N>)> {
+        self.headers.read().get(&height).copied()
+    }
+
+    /// Tracks the block headers of the sources, until the node shuts down.
+    pub async fn run(&self) {
+        info!("Tracking the block headers of {} source(s)", self.sources.len());
+        while !self.shutdown.load(Ordering::Relaxed) {
+            match self.advance().await {
+                Ok(true) => (),
+                // If the light client caught up, sleep briefly, to let new blocks accumulate.
+                Ok(false) => tokio::time::sleep(Duration::from_secs(Self::SYNC_INTERVAL_IN_SECS)).await,
+                Err(error) => {
+                    warn!("Failed to advance the light client - {error}");
+                    tokio::time::sleep(Duration::from_secs(Self::SYNC_INTERVAL_IN_SECS)).await;
+                }
+            }
+        }
+        if let Err(error) = self.persist() {
+            warn!("Failed to persist the light client headers - {error}");
+        }
+    }
+
+    /// Serves the tracked block headers and the verified transactions over a REST API at the given address.
+    pub async fn serve(self, rest_ip: SocketAddr) {
+        let network = match network_name::<N>() {
+            Ok(network) => network,
+            Err(error) => return error!("Failed to start the light client REST server - {error}"),
+        };
+        let router = Router::new()
+            .route(
+                &format!("/{network}/latest/height"),
+                get(|State(client): State<Self>| async move { Json(client.latest_height()) }),
+            )
+            .route(
+                &format!("/{network}/block/:height/header"),
+                get(|State(client): State<Self>, Path(height): Path<u32>| async move {
+                    client.get_header(height).map(Json).ok_or((StatusCode::NOT_FOUND, "The block is not tracked"))
+                }),
+            )
+            .route(
+                &format!("/{network}/transaction/:id/verify"),
+                get(|State(client): State<Self>, Path(id): Path<N::TransactionID>| async move {
+                    client.verify_transaction(id).await.map(Json).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))
+                }),
+            )
+            .with_state(self);
+
+        info!("Starting the light client REST server at {rest_ip}");
+        let listener = match tokio::net::TcpListener::bind(rest_ip).await {
+            Ok(listener) => listener,
+            Err(error) => return error!("Failed to bind the light client REST server to {rest_ip} - {error}"),
+        };
+        if let Err(error) = axum::serve(listener, router).await {
+            error!("The light client REST server stopped - {error}");
+        }
+    }
+
+    /// Attempts to track the next block header, and returns `false` if the sources have no new block.
+    pub async fn advance(&self) -> Result<bool> {
+        let (previous_hash, previous_header) = match self.headers.read().last_key_value() {
+            Some((_, entry)) => *entry,
+            None => bail!("The light client has no block header"),
+        };
+        let height = previous_header.height() + 1;
+
+        // Fetch the next block header and its certificates from the first source.
+        let source_height: u32 = self.get(&self.sources[0], "block/height/latest").await?;
+        if source_height < height {
+            return Ok(false);
+        }
+        let header: Header<N> = self.get(&self.sources[0], &format!("block/{height}/header")).await?;
+        ensure!(header.height() == height, "The source provided the header of block {} instead", header.height());
+        let authority: Authority<N> = self.get(&self.sources[0], &format!("block/{height}/authority")).await?;
+
+        // Ensure the header extends the tracked headers, and is anchored by a quorum of the committee.
+        let hash = to_block_hash(previous_hash, &header)?;
+        let round = committee_lookback_round::<N>(header.round());
+        let (committee_height, committee, is_trusted) = self.get_committee_for_round(round).await?;
+        let signers = verify_quorum(&header, &authority, &committee)?;
+        // If the committee is new, trust it once the previous trusted committee endorsed it.
+        if !is_trusted {
+            self.trust_committee(committee_height, committee, &signers)?;
+        }
+
+        // Ensure the other sources agree on the block at this height.
+        for source in &self.sources[1..] {
+            let other_height: u32 = self.get(source, &format!("height/{hash}")).await?;
+            ensure!(other_height == height, "The source {source} does not agree on block {height}");
+        }
+
+        // Track the block header.
+        {
+            let mut headers = self.headers.write();
+            headers.insert(height, (hash, header));
+            while headers.len() > Self::MAXIMUM_HEADERS {
+                headers.pop_first();
+            }
+        }
+        debug!("Tracked the header of block {height}");
+
+        // Periodically persist the headers, so that a restart resumes from them.
+        if height % Self::PERSIST_INTERVAL == 0 {
+            self.persist()?;
+        }
+        Ok(true)
+    }
+
+    /// Ensures the given transaction is included in a tracked block, and returns the block height.
+    /// The sources provide the Merkle path of the transaction, which must lead to the tracked transactions root.
+    pub async fn verify_transaction(&self, transaction_id: N::TransactionID) -> Result<u32> {
+        let (height, path): (u32, TransactionsPath<N>) =
+            self.get(&self.sources[0], &format!("transaction/{transaction_id}/path")).await?;
+        let Some((_, header)) = self.get_header(height) else {
+            bail!("Transaction '{transaction_id}' is not in a tracked block");
+        };
+        ensure!(
+            N::verify_merkle_path_bhp(&path, &header.transactions_root(), &transaction_id.to_bits_le()),
+            "The source provided an invalid inclusion proof of the transaction in block {height}"
+        );
+        Ok(height)
+    }
+
+    /// Returns the height of the block the committee for the given round is in effect at, the committee,
+    /// and whether it is trusted. A new committee must be reported unanimously by the sources, and start at
+    /// a tracked block certified by the previous committee.
+    async fn get_committee_for_round(&self, round: u64) -> Result<(u32, Committee<N>, bool)> {
+        // The committee for a round is the one of the latest block up to that round.
+        let (height, header) = {
+            let headers = self.headers.read();
+            let mut heights = headers.iter().rev().filter(|(_, (_, header))| header.round() <= round);
+            match heights.next() {
+                Some((height, (_, header))) => (*height, *header),
+                None => bail!("The committee for round {round} precedes the tracked headers"),
+            }
+        };
+        if let Some(committee) = self.committees.read().get(&height) {
+            return Ok((height, committee.clone(), true));
+        }
+
+        // Fetch the committee from every source.
+        let committee: Committee<N> = self.get(&self.sources[0], &format!("committee/{height}")).await?;
+        for source in &self.sources[1..] {
+            let other: Committee<N> = self.get(source, &format!("committee/{height}")).await?;
+            ensure!(other == committee, "The source {source} does not agree on the committee of block {height}");
+        }
+
+        // Ensure the committee transitions from the previous one at a tracked block.
+        {
+            let headers = self.headers.read();
+            let committees = self.committees.read();
+            let previous = committees.range(..height).next_back().map(|(_, committee)| committee);
+            verify_committee_transition(&headers, previous, &header, &committee)?;
+        }
+        Ok((height, committee, false))
+    }
+
+    /// Trusts the given committee, in effect at the given height, if the given signers of a certificate
+    /// signed under it hold at least f+1 stake of the previous trusted committee.
+    fn trust_committee(&self, height: u32, committee: Committee<N>, signers: &HashSet<Address<N>>) -> Result<()> {
+        let mut committees = self.committees.write();
+        let Some((_, previous)) = committees.range(..height).next_back() else {
+            bail!("The committee of block {height} precedes the trusted committees");
+        };
+        ensure!(
+            previous.is_availability_threshold_reached(signers),
+            "The committee of block {height} is not endorsed by the previous trusted committee"
+        );
+        committees.insert(height, committee);
+        while committees.len() > Self::MAXIMUM_COMMITTEES {
+            committees.pop_first();
+        }
+        debug!("Trusted the committee of block {height}");
+        Ok(())
+    }
+
+    /// Persists the most recent block headers and the trusted committees, if a path is set.
+    fn persist(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let bytes = {
+            let headers = self.headers.read();
+            let skip = headers.len().saturating_sub(Self::MAXIMUM_PERSISTED_HEADERS);
+            let mut bytes = Vec::new();
+            u32::try_from(headers.len() - skip)?.write_le(&mut bytes)?;
+            for (hash, header) in headers.values().skip(skip) {
+                hash.write_le(&mut bytes)?;
+                header.write_le(&mut bytes)?;
+            }
+            let committees = self.committees.read();
+            u32::try_from(committees.len())?.write_le(&mut bytes)?;
+            for (height, committee) in committees.iter() {
+                height.write_le(&mut bytes)?;
+                committee.write_le(&mut bytes)?;
+            }
+            bytes
+        };
+        // Write to a temporary file first, so that a crash never leaves truncated headers behind.
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, bytes)?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    /// Returns the deserialized response of the given source for the given path.
+    async fn get<T: DeserializeOwned>(&self, source: &str, path: &str) -> Result<T> {
+        let url = format!("{source}/{}/{path}", network_name::<N>()?);
+        let response = self.client.get(&url).send().await?;
+        ensure!(response.status().is_success(), "Failed to fetch {url} - {}", response.status());
+        Ok(response.json().await?)
+    }
+}
+
+/// Returns the name of the network, as used in the REST API.
+pub(crate) fn network_name<N: Network>() -> Result<&'static str> {
+    match N::ID {
+        MainnetV0::ID => Ok("mainnet"),
+        TestnetV0::ID => Ok("testnet"),
+        CanaryV0::ID => Ok("canary"),
+        unknown_id => bail!("Unknown network ID ({unknown_id})"),
+    }
+}
+
+/// Returns the round of the committee that signs the certificates of the given round.
+/// Note: This mirrors the committee lookback of the ledger service.
+fn committee_lookback_round<N: Network>(round: u64) -> u64 {
+    // Committees are updated in even rounds.
+    let previous_round = match round % 2 == 0 {
+        true => round.saturating_sub(1),
+        false => round.saturating_sub(2),
+    };
+    previous_round.saturating_sub(Committee::<N>::COMMITTEE_LOOKBACK_RANGE)
+}
+
+/// The persisted block headers and trusted committees of the light client.
+type LightClientState<N> = (BTreeMap<u32, (<N as Network>::BlockHash, Header<N>)>, BTreeMap<u32, Committee<N>>);
+
+/// Returns the persisted block headers, ensuring they form a chain, and the persisted trusted committees.
+fn load_state<N: Network>(bytes: &[u8]) -> Result<LightClientState<N>> {
+    let mut reader = bytes;
+    let num_headers = u32::read_le(&mut reader)?;
+    ensure!(num_headers > 0, "The persisted light client headers are empty");
+    let mut headers = BTreeMap::new();
+    let mut previous_hash = None;
+    for _ in 0..num_headers {
+        let hash = N::BlockHash::read_le(&mut reader)?;
+        let header = Header::<N>::read_le(&mut reader)?;
+        if let Some(previous_hash) = previous_hash {
+            ensure!(
+                to_block_hash(previous_hash, &header)? == hash,
+                "The persisted header {} is not linked",
+                header.height()
+            );
+        }
+        previous_hash = Some(hash);
+        headers.insert(header.height(), (hash, header));
+    }
+    let num_committees = u32::read_le(&mut reader)?;
+    ensure!(num_committees > 0, "The persisted light client committees are empty");
+    let mut committees = BTreeMap::new();
+    for _ in 0..num_committees {
+        let height = u32::read_le(&mut reader)?;
+        committees.insert(height, Committee::<N>::read_le(&mut reader)?);
+    }
+    Ok((headers, committees))
+}
+
+/// Returns the hash of the block with the given header, which must extend the block with the given hash.
+fn to_block_hash<N: Network>(previous_hash: N::BlockHash, header: &Header<N>) -> Result<N::BlockHash> {
+    ensure!(header.is_valid(), "Block {} has a malformed header", header.height());
+    let header_root = header.to_root()?;
+    Ok(N::hash_bhp1024(&[previous_hash.to_bits_le(), header_root.to_bits_le()].concat())?.into())
+}
+
+/// Ensures the given committee is in effect at the block of the given header, and transitions from the previous
+/// committee, if any, at a tracked block, which the previous committee certified.
+fn verify_committee_transition<N: Network>(
+    headers: &BTreeMap<u32, (N::BlockHash, Header<N>)>,
+    previous: Option<&Committee<N>>,
+    header: &Header<N>,
+    committee: &Committee<N>,
+) -> Result<()> {
+    let (height, starting_round) = (header.height(), committee.starting_round());
+    ensure!(starting_round <= header.round(), "The committee of block {height} starts after the block");
+    if let Some(previous) = previous {
+        ensure!(
+            starting_round >= previous.starting_round(),
+            "The committee of block {height} precedes the previous one"
+        );
+        if starting_round == previous.starting_round() {
+            ensure!(committee == previous, "The committee of block {height} differs from the previous one");
+            return Ok(());
+        }
+    }
+    // Ensure the committee starts at a tracked block, unless that block precedes the tracked headers.
+    let is_anchored = headers.values().any(|(_, header)| header.round() == starting_round);
+    let precedes = headers.values().next().is_some_and(|(_, header)| header.round() > starting_round);
+    ensure!(is_anchored || precedes, "The committee of block {height} does not start at a certified block");
+    Ok(())
+}
+
+/// Ensures the given header is anchored by a leader certificate signed by a quorum of the given committee,
+/// and returns the signers of the certificate.
+fn verify_quorum<N: Network>(
+    header: &Header<N>,
+    authority: &Authority<N>,
+    committee: &Committee<N>,
+) -> Result<HashSet<Address<N>>> {
+    let Authority::Quorum(subdag) = authority else {
+        bail!("Block {} is not certified by the committee", header.height());
+    };
+    ensure!(subdag.to_subdag_root()? == header.subdag_root(), "The subdag does not match block {}", header.height());
+    ensure!(subdag.anchor_round() == header.round(), "The subdag of block {} is for another round", header.height());
+    verify_certificate(subdag.leader_certificate(), committee)
+}
+
+/// Ensures the given certificate is signed by a quorum of the given committee, and returns its signers.
+fn verify_certificate<N: Network>(
+    certificate: &BatchCertificate<N>,
+    committee: &Committee<N>,
+) -> Result<HashSet<Address<N>>> {
+    let batch_id = certificate.batch_id();
+    let round = certificate.round();
+    // Ensure the batch was proposed under the given committee.
+    ensure!(certificate.committee_id() == committee.id(), "The batch in round {round} is for another committee");
+
+    // Ensure the author signed the batch.
+    let author = certificate.author();
+    ensure!(committee.is_committee_member(author), "The author {author} is not in the committee for round {round}");
+    ensure!(
+        certificate.batch_header().signature().verify(&author, &[batch_id]),
+        "The author {author} did not sign the batch in round {round}"
+    );
+
+    // Ensure every signer is in the committee, and signed the batch.
+    let mut signers = HashSet::with_capacity(certificate.signatures().len() + 1);
+    signers.insert(author);
+    for signature in certificate.signatures() {
+        let signer = signature.to_address();
+        ensure!(committee.is_committee_member(signer), "The signer {signer} is not in the committee for round {round}");
+        ensure!(signature.verify(&signer, &[batch_id]), "The signer {signer} did not sign the batch in round {round}");
+        signers.insert(signer);
+    }
+
+    // Ensure the signatures reached the quorum threshold.
+    ensure!(committee.is_quorum_threshold_reached(&signers), "The batch in round {round} did not reach quorum");
+    Ok(signers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_committee_lookback_round() {
+        let range = Committee::<CurrentNetwork>::COMMITTEE_LOOKBACK_RANGE;
+        assert_eq!(committee_lookback_round::<CurrentNetwork>(0), 0);
+        assert_eq!(committee_lookback_round::<CurrentNetwork>(range + 2), 1);
+        assert_eq!(committee_lookback_round::<CurrentNetwork>(range + 3), 1);
+        assert_eq!(committee_lookback_round::<CurrentNetwork>(range + 4), 3);
+    }
+
+    #[test]
+    fn test_to_block_hash() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        assert_eq!(to_block_hash(genesis.previous_hash(), genesis.header()).unwrap(), genesis.hash());
+        assert_ne!(to_block_hash(genesis.hash(), genesis.header()).unwrap(), genesis.hash());
+    }
+
+    #[test]
+    fn test_verify_committee_transition() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let committee = match genesis.ratifications().iter().next() {
+            Some(Ratify::Genesis(committee, ..)) => *committee.clone(),
+            _ => panic!("The genesis block has no committee"),
+        };
+        let headers = BTreeMap::from([(0, (genesis.hash(), *genesis.header()))]);
+
+        // Ensure the genesis committee starts at the genesis block.
+        verify_committee_transition(&headers, None, genesis.header(), &committee).unwrap();
+        verify_committee_transition(&headers, Some(&committee), genesis.header(), &committee).unwrap();
+        // Ensure a committee must start at a tracked block.
+        assert!(verify_committee_transition(&BTreeMap::new(), None, genesis.header(), &committee).is_err());
+    }
+
+    #[test]
+    fn test_load_state() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let committee = match genesis.ratifications().iter().next() {
+            Some(Ratify::Genesis(committee, ..)) => *committee.clone(),
+            _ => panic!("The genesis block has no committee"),
+        };
+        let mut bytes = 1u32.to_bytes_le().unwrap();
+        bytes.extend(genesis.hash().to_bytes_le().unwrap());
+        bytes.extend(genesis.header().to_bytes_le().unwrap());
+        let headers_only = bytes.clone();
+        bytes.extend(1u32.to_bytes_le().unwrap());
+        bytes.extend(0u32.to_bytes_le().unwrap());
+        bytes.extend(committee.to_bytes_le().unwrap());
+        let (headers, committees) = load_state::<CurrentNetwork>(&bytes).unwrap();
+        assert_eq!(headers, BTreeMap::from([(0, (genesis.hash(), *genesis.header()))]));
+        assert_eq!(committees, BTreeMap::from([(0, committee)]));
+
+        // Ensure empty or truncated state is rejected, including state without a trusted committee.
+        assert!(load_state::<CurrentNetwork>(&0u32.to_bytes_le().unwrap()).is_err());
+        assert!(load_state::<CurrentNetwork>(&bytes[..bytes.len() - 1]).is_err());
+        assert!(load_state::<CurrentNetwork>(&headers_only).is_err());
+    }
+
+    #[test]
+    fn test_trust_committee() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let client = LightClient::new(genesis, vec!["http://localhost:3030".into()], Default::default()).unwrap();
+        let committee = client.committees.read()[&0].clone();
+
+        // Ensure a new committee is only trusted if the previous trusted committee endorsed it.
+        assert!(client.trust_committee(1, committee.clone(), &HashSet::new()).is_err());
+        assert!(!client.committees.read().contains_key(&1));
+        let signers = committee.members().keys().copied().collect();
+        client.trust_committee(1, committee.clone(), &signers).unwrap();
+        assert_eq!(client.committees.read().get(&1), Some(&committee));
+        // Ensure a committee preceding the trusted ones is never trusted.
+        client.committees.write().remove(&0);
+        assert!(client.trust_committee(0, committee, &signers).is_err());
+    }
+
+    #[test]
+    fn test_light_client() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        assert!(LightClient::new(genesis.clone(), vec![], Default::default()).is_err());
+
+        let client = LightClient::new(genesis.clone(), vec!["http://localhost:3030/".into()], Default::default());
+        let client = client.unwrap();
+        assert_eq!(client.sources, vec!["http://localhost:3030".to_string()]);
+        assert_eq!(client.latest_height(), 0);
+        assert_eq!(client.get_header(0), Some((genesis.hash(), *genesis.header())));
+
+        // Ensure the headers are persisted, and the light client resumes from them.
+        let path = std::env::temp_dir().join(format!("snarkos-light-{}", std::process::id()));
+        let client = client.with_storage(path.clone()).unwrap();
+        client.persist().unwrap();
+        let resumed = LightClient::new(genesis.clone(), vec!["http://localhost:3030".into()], Default::default());
+        let resumed = resumed.unwrap().with_storage(path.clone()).unwrap();
+        assert_eq!(resumed.get_header(0), Some((genesis.hash(), *genesis.header())));
+        assert_eq!(*resumed.committees.read(), *client.committees.read());
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,271 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_router::messages::NodeType;
+use snarkvm::{
+    ledger::{store::ConsensusStorage, Ledger},
+    prelude::{Address, Network},
+};
+
+use parking_lot::Mutex;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::{sync::Notify, task::JoinHandle};
+
+/// The membership of an address in the latest committee.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Membership {
+    /// The address is not a committee member.
+    NotMember,
+    /// The address is a committee member, with the given stake in microcredits.
+    Member(u64),
+}
+
+/// Returns the role the node should switch to for the given membership, if it differs from its own:
+/// a client becomes a validator once it is a committee member, and a validator becomes a client otherwise.
+pub fn target_role(node_type: NodeType, membership: Membership) -> Option<NodeType> {
+    match (node_type, membership) {
+        (NodeType::Client, Membership::Member(_)) => Some(NodeType::Validator),
+        (NodeType::Validator, Membership::NotMember) => Some(NodeType::Client),
+        _ => None,
+    }
+}
+
+/// Returns the log line for the transition of the node between the given memberships, if any,
+/// along with `true` if it warrants a warning. If `is_switching` is set, the node switches roles on its own.
+pub fn membership_transition(
+    node_type: NodeType,
+    previous: Option<Membership>,
+    current: Membership,
+    is_switching: bool,
+) -> Option<(bool, String)> {
+    // If the membership did not change, there is nothing to log.
+    if previous == Some(current) {
+        return None;
+    }
+    match (node_type.is_validator(), previous, current) {
+        (true, None, Membership::NotMember) if is_switching => {
+            Some((true, "This validator is not a committee member - switching to a client".to_string()))
+        }
+        (true, Some(_), Membership::NotMember) if is_switching => {
+            Some((true, "This validator left the committee - switching to a client".to_string()))
+        }
+        (false, _, Membership::Member(stake)) if is_switching => Some((
+            false,
+            format!("The address of this node is a committee member, with {stake} microcredits staked - switching to a validator"),
+        )),
+        (true, None, Membership::Member(stake)) => {
+            Some((false, format!("This validator is a committee member, with {stake} microcredits staked")))
+        }
+        (true, None, Membership::NotMember) => Some((
+            true,
+            "This validator is not a committee member - it syncs with the network, and starts proposing batches once bonded"
+                .to_string(),
+        )),
+        (true, Some(Membership::NotMember), Membership::Member(stake)) => Some((
+            false,
+            format!("This validator joined the committee, with {stake} microcredits staked - it starts proposing batches"),
+        )),
+        (true, Some(_), Membership::NotMember) => Some((
+            true,
+            "This validator left the committee - it stops proposing batches, and resumes once bonded again".to_string(),
+        )),
+        (true, Some(Membership::Member(previous)), Membership::Member(stake)) => Some((
+            false,
+            format!("The stake of this validator changed from {previous} to {stake} microcredits"),
+        )),
+        // Note: The node type is part of the handshake with the peers, so a client must restart to become a validator,
+        // which it does on its own with the role transitions.
+        (false, _, Membership::NotMember) => None,
+        (false, _, Membership::Member(stake)) => Some((
+            true,
+            format!(
+                "The address of this node is a committee member, with {stake} microcredits staked - restart the node with '--validator' (or '--auto-role') to participate in consensus"
+            ),
+        )),
+    }
+}
+
+/// The transitions of a node between the client and validator roles, which the membership watcher requests
+/// once they are enabled, and which the operator of the node carries out by starting it again in its new role.
+#[derive(Default)]
+pub struct RoleTransitions {
+    /// Whether the transitions are enabled.
+    is_enabled: AtomicBool,
+    /// The role the node should switch to, if requested.
+    requested: Mutex<Option<NodeType>>,
+    /// Notifies the operator of a request.
+    notify: Notify,
+}
+
+impl RoleTransitions {
+    /// Enables the transitions.
+    pub fn enable(&self) {
+        self.is_enabled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the transitions are enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.is_enabled.load(Ordering::Relaxed)
+    }
+
+    /// Requests the node to switch to the given role.
+    fn request(&self, role: NodeType) {
+        *self.requested.lock() = Some(role);
+        self.notify.notify_one();
+    }
+
+    /// Waits until the node is requested to switch roles, and returns its new role.
+    pub async fn wait(&self) -> NodeType {
+        loop {
+            if let Some(role) = self.requested.lock().take() {
+                return role;
+            }
+            self.notify.notified().await;
+        }
+    }
+}
+
+/// Watches the membership of the address of the node in the latest committee, and logs its transitions,
+/// so that the operator learns when the node is bonded in or out of the committee. Once the role transitions
+/// are enabled, it also requests the node to switch roles whenever its role no longer matches its membership.
+pub struct MembershipWatcher<N: Network, C: ConsensusStorage<N>> {
+    /// The ledger.
+    ledger: Ledger<N, C>,
+    /// The address of the node.
+    address: Address<N>,
+    /// The type of the node.
+    node_type: NodeType,
+    /// The role transitions of the node.
+    role_transitions: Arc<RoleTransitions>,
+}
+
+impl<N: Network, C: ConsensusStorage<N>> MembershipWatcher<N, C> {
+    /// The interval at which the membership is checked, in seconds.
+    const CHECK_INTERVAL_IN_SECS: u64 = 30;
+
+    /// Initializes a new watcher for the given address.
+    pub fn new(
+        ledger: Ledger<N, C>,
+        address: Address<N>,
+        node_type: NodeType,
+        role_transitions: Arc<RoleTransitions>,
+    ) -> Self {
+        Self { ledger, address, node_type, role_transitions }
+    }
+
+    /// Returns the membership of the address in the latest committee.
+    fn membership(&self) -> Option<Membership> {
+        match self.ledger.latest_committee() {
+            Ok(committee) => match committee.is_committee_member(self.address) {
+                true => Some(Membership::Member(committee.get_stake(self.address))),
+                false => Some(Membership::NotMember),
+            },
+            Err(error) => {
+                warn!("Failed to retrieve the latest committee - {error}");
+                None
+            }
+        }
+    }
+
+    /// Spawns a task that checks the membership periodically, logs its transitions, and requests the node
+    /// to switch roles, if enabled.
+    pub(crate) fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut previous = None;
+            loop {
+                if let Some(current) = self.membership() {
+                    let is_switching = self.role_transitions.is_enabled();
+                    match membership_transition(self.node_type, previous, current, is_switching) {
+                        Some((true, message)) => warn!("{message}"),
+                        Some((false, message)) => info!("{message}"),
+                        None => (),
+                    }
+                    // Note: The request is repeated on every check, until the node switches roles.
+                    if let Some(role) = target_role(self.node_type, current).filter(|_| is_switching) {
+                        self.role_transitions.request(role);
+                    }
+                    previous = Some(current);
+                }
+                tokio::time::sleep(Duration::from_secs(Self::CHECK_INTERVAL_IN_SECS)).await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_membership_transition() {
+        let (validator, client) = (NodeType::Validator, NodeType::Client);
+        let (member, not_member) = (Membership::Member(10), Membership::NotMember);
+
+        // Ensure an unchanged membership is not logged.
+        assert_eq!(membership_transition(validator, Some(member), member, false), None);
+        assert_eq!(membership_transition(client, Some(not_member), not_member, false), None);
+
+        // Ensure the transitions of a validator are logged, with a warning when it is not a member.
+        assert!(!membership_transition(validator, None, member, false).unwrap().0);
+        assert!(membership_transition(validator, None, not_member, false).unwrap().0);
+        assert!(membership_transition(validator, Some(member), not_member, false).unwrap().0);
+        assert!(!membership_transition(validator, Some(not_member), member, false).unwrap().0);
+        let (is_warning, message) =
+            membership_transition(validator, Some(member), Membership::Member(20), false).unwrap();
+        assert!(!is_warning);
+        assert!(message.contains("from 10 to 20"));
+
+        // Ensure a client is only warned when it is bonded as a committee member.
+        assert_eq!(membership_transition(client, None, not_member, false), None);
+        assert!(membership_transition(client, None, member, false).unwrap().0);
+        assert!(membership_transition(client, Some(not_member), member, false).unwrap().0);
+        assert_eq!(membership_transition(client, Some(member), not_member, false), None);
+
+        // Ensure the nodes that switch roles say so.
+        let (_, message) = membership_transition(client, None, member, true).unwrap();
+        assert!(message.contains("switching to a validator"));
+        let (_, message) = membership_transition(validator, Some(member), not_member, true).unwrap();
+        assert!(message.contains("switching to a client"));
+        assert!(!membership_transition(validator, None, member, true).unwrap().0);
+    }
+
+    #[test]
+    fn test_target_role() {
+        // Ensure a client becomes a validator once bonded, and a validator becomes a client once unbonded.
+        assert_eq!(target_role(NodeType::Client, Membership::Member(10)), Some(NodeType::Validator));
+        assert_eq!(target_role(NodeType::Client, Membership::NotMember), None);
+        assert_eq!(target_role(NodeType::Validator, Membership::NotMember), Some(NodeType::Client));
+        assert_eq!(target_role(NodeType::Validator, Membership::Member(10)), None);
+        assert_eq!(target_role(NodeType::Prover, Membership::Member(10)), None);
+    }
+
+    #[tokio::test]
+    async fn test_role_transitions() {
+        let transitions = RoleTransitions::default();
+        assert!(!transitions.is_enabled());
+        transitions.enable();
+        assert!(transitions.is_enabled());
+
+        // Ensure the latest request is returned, even if it was made before waiting.
+        transitions.request(NodeType::Client);
+        transitions.request(NodeType::Validator);
+        assert_eq!(transitions.wait().await, NodeType::Validator);
+    }
+}
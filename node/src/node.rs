@@ -12,12 +12,43 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{traits::NodeInterface, Client, Prover, Validator};
+use crate::{
+    traits::NodeInterface,
+    AlertConfig,
+    AlertMonitor,
+    Client,
+    CompactionSchedule,
+    DiskWatchdog,
+    HealthSnapshot,
+    PoolClient,
+    PoolConfig,
+    Prover,
+    ProverDevices,
+    ProverThrottle,
+    RoleTransitions,
+    SolutionSubmitter,
+    StateDiffExporter,
+    Validator,
+};
 use snarkos_account::Account;
-use snarkos_node_router::messages::NodeType;
+use snarkos_node_bft::helpers::{
+    AuditLog,
+    Failover,
+    OperatorLane,
+    ParticipationConfig,
+    ProposalConfig,
+    RemoteSigner,
+    TimeoutConfig,
+};
+use snarkos_node_consensus::AdmissionPolicy;
+use snarkos_node_router::{messages::NodeType, KeepAlive, ListenAddr, RelayMode, SolutionRateLimit};
+use snarkos_node_sync::SyncCheckpoint;
 use snarkvm::prelude::{
     block::Block,
-    store::helpers::{memory::ConsensusMemory, rocksdb::ConsensusDB},
+    store::{
+        helpers::{memory::ConsensusMemory, rocksdb::ConsensusDB},
+        ConsensusStorage,
+    },
     Address,
     Network,
     PrivateKey,
@@ -28,28 +59,57 @@ use aleo_std::StorageMode;
 use anyhow::Result;
 use std::{
     net::SocketAddr,
+    path::{Path, PathBuf},
     sync::{atomic::AtomicBool, Arc},
 };
 
-pub enum Node<N: Network> {
+/// A node of the given type, whose ledger is backed by the given storage (by default, RocksDB).
+pub enum Node<N: Network, C: ConsensusStorage<N> = ConsensusDB<N>> {
     /// A validator is a full node, capable of validating blocks.
-    Validator(Arc<Validator<N, ConsensusDB<N>>>),
+    Validator(Arc<Validator<N, C>>),
     /// A prover is a light node, capable of producing proofs for consensus.
     Prover(Arc<Prover<N, ConsensusMemory<N>>>),
     /// A client node is a full node, capable of querying with the network.
-    Client(Arc<Client<N, ConsensusDB<N>>>),
+    Client(Arc<Client<N, C>>),
 }
 
-impl<N: Network> Node<N> {
+impl<N: Network, C: ConsensusStorage<N>> Clone for Node<N, C> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Validator(node) => Self::Validator(node.clone()),
+            Self::Prover(node) => Self::Prover(node.clone()),
+            Self::Client(node) => Self::Client(node.clone()),
+        }
+    }
+}
+
+impl<N: Network, C: ConsensusStorage<N>> Node<N, C> {
     /// Initializes a new validator node.
     pub async fn new_validator(
         node_ip: SocketAddr,
+        listen_addrs: &[ListenAddr],
         bft_ip: Option<SocketAddr>,
         rest_ip: Option<SocketAddr>,
         rest_rps: u32,
+        rest_cache_size: usize,
         account: Account<N>,
+        remote_signer: Option<RemoteSigner<N>>,
         trusted_peers: &[SocketAddr],
+        pinned_peers: &[SocketAddr],
+        keep_alive: KeepAlive,
+        relay_mode: RelayMode,
         trusted_validators: &[SocketAddr],
+        proposal_config: ProposalConfig<N>,
+        timeout_config: TimeoutConfig,
+        participation_config: ParticipationConfig,
+        admission_policy: AdmissionPolicy<N>,
+        solution_rate_limit: Option<SolutionRateLimit>,
+        persist_mempool: bool,
+        operator_lane: OperatorLane<N>,
+        bft_retention_rounds: Option<u64>,
+        signing_journal: Option<PathBuf>,
+        failover: Option<Failover<N>>,
+        audit_log: Option<AuditLog<N>>,
         genesis: Block<N>,
         cdn: Option<String>,
         storage_mode: StorageMode,
@@ -60,12 +120,29 @@ impl<N: Network> Node<N> {
         Ok(Self::Validator(Arc::new(
             Validator::new(
                 node_ip,
+                listen_addrs,
                 bft_ip,
                 rest_ip,
                 rest_rps,
+                rest_cache_size,
                 account,
+                remote_signer,
                 trusted_peers,
+                pinned_peers,
+                keep_alive,
+                relay_mode,
                 trusted_validators,
+                proposal_config,
+                timeout_config,
+                participation_config,
+                admission_policy,
+                solution_rate_limit,
+                persist_mempool,
+                operator_lane,
+                bft_retention_rounds,
+                signing_journal,
+                failover,
+                audit_log,
                 genesis,
                 cdn,
                 storage_mode,
@@ -80,30 +157,94 @@ impl<N: Network> Node<N> {
     /// Initializes a new prover node.
     pub async fn new_prover(
         node_ip: SocketAddr,
+        listen_addrs: &[ListenAddr],
         account: Account<N>,
         trusted_peers: &[SocketAddr],
+        pinned_peers: &[SocketAddr],
+        keep_alive: KeepAlive,
+        relay_mode: RelayMode,
+        devices: ProverDevices,
+        throttle: ProverThrottle,
+        pool: Option<PoolClient<N>>,
+        submitter: SolutionSubmitter<N>,
+        status_ip: Option<SocketAddr>,
         genesis: Block<N>,
         storage_mode: StorageMode,
         shutdown: Arc<AtomicBool>,
     ) -> Result<Self> {
-        Ok(Self::Prover(Arc::new(Prover::new(node_ip, account, trusted_peers, genesis, storage_mode, shutdown).await?)))
+        Ok(Self::Prover(Arc::new(
+            Prover::new(
+                node_ip,
+                listen_addrs,
+                account,
+                trusted_peers,
+                pinned_peers,
+                keep_alive,
+                relay_mode,
+                devices,
+                throttle,
+                pool,
+                submitter,
+                status_ip,
+                genesis,
+                storage_mode,
+                shutdown,
+            )
+            .await?,
+        )))
     }
 
     /// Initializes a new client node.
     pub async fn new_client(
         node_ip: SocketAddr,
+        listen_addrs: &[ListenAddr],
         rest_ip: Option<SocketAddr>,
         rest_rps: u32,
+        rest_cache_size: usize,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
+        pinned_peers: &[SocketAddr],
+        sync_peers: &[SocketAddr],
+        sync_checkpoint: Option<SyncCheckpoint<N>>,
+        blocks_to_keep: Option<u32>,
+        max_ledger_size: Option<u64>,
+        cold_storage: Option<PathBuf>,
+        snapshot_interval: Option<u32>,
+        analytics: bool,
+        keep_alive: KeepAlive,
+        relay_mode: RelayMode,
+        pool: Option<PoolConfig>,
         genesis: Block<N>,
         cdn: Option<String>,
         storage_mode: StorageMode,
         shutdown: Arc<AtomicBool>,
     ) -> Result<Self> {
         Ok(Self::Client(Arc::new(
-            Client::new(node_ip, rest_ip, rest_rps, account, trusted_peers, genesis, cdn, storage_mode, shutdown)
-                .await?,
+            Client::new(
+                node_ip,
+                listen_addrs,
+                rest_ip,
+                rest_rps,
+                rest_cache_size,
+                account,
+                trusted_peers,
+                pinned_peers,
+                sync_peers,
+                sync_checkpoint,
+                blocks_to_keep,
+                max_ledger_size,
+                cold_storage,
+                snapshot_interval,
+                analytics,
+                keep_alive,
+                relay_mode,
+                pool,
+                genesis,
+                cdn,
+                storage_mode,
+                shutdown,
+            )
+            .await?,
         )))
     }
 
@@ -143,6 +284,23 @@ impl<N: Network> Node<N> {
         }
     }
 
+    /// Returns the latest block height of the node, if it keeps a ledger.
+    pub fn latest_height(&self) -> Option<u32> {
+        match self {
+            Self::Validator(node) => Some(node.ledger().latest_height()),
+            Self::Prover(_) => None,
+            Self::Client(node) => Some(node.ledger().latest_height()),
+        }
+    }
+
+    /// Returns the current round of the BFT, if the node is a validator.
+    pub fn current_round(&self) -> Option<u64> {
+        match self {
+            Self::Validator(node) => Some(node.current_round()),
+            Self::Prover(_) | Self::Client(_) => None,
+        }
+    }
+
     /// Returns `true` if the node is in development mode.
     pub fn is_dev(&self) -> bool {
         match self {
@@ -151,4 +309,105 @@ impl<N: Network> Node<N> {
             Self::Client(node) => node.is_dev(),
         }
     }
+
+    /// Shuts down the node.
+    pub async fn shut_down(&self) {
+        match self {
+            Self::Validator(node) => node.shut_down().await,
+            Self::Prover(node) => node.shut_down().await,
+            Self::Client(node) => node.shut_down().await,
+        }
+    }
+
+    /// Enables the discovery of peers on the local network.
+    pub fn enable_local_discovery(&self) -> Result<()> {
+        match self {
+            Self::Validator(node) => node.enable_local_discovery(),
+            Self::Prover(node) => node.enable_local_discovery(),
+            Self::Client(node) => node.enable_local_discovery(),
+        }
+    }
+
+    /// Limits the rate of inbound connection attempts to the node.
+    pub fn set_accept_rate_limit(&self, limit: AcceptRateLimit) {
+        match self {
+            Self::Validator(node) => node.set_accept_rate_limit(limit),
+            Self::Prover(node) => node.set_accept_rate_limit(limit),
+            Self::Client(node) => node.set_accept_rate_limit(limit),
+        }
+    }
+
+    /// Enables the compaction of the ledger database once per day, within the given window.
+    pub fn enable_scheduled_compaction(&self, schedule: CompactionSchedule, storage_mode: StorageMode) {
+        match self {
+            Self::Validator(node) => node.spawn(schedule.run::<N>(storage_mode)),
+            Self::Prover(_) => (),
+            Self::Client(node) => node.spawn(schedule.run::<N>(storage_mode)),
+        }
+    }
+
+    /// Enables the disk watchdog, which monitors the free space on the ledger volume.
+    /// Note: The caller is expected to shut the node down once the watchdog reports critical free space.
+    pub fn enable_disk_watchdog(&self, watchdog: DiskWatchdog) {
+        match self {
+            Self::Validator(node) => node.spawn(watchdog.run()),
+            Self::Prover(node) => node.spawn(watchdog.run()),
+            Self::Client(node) => {
+                node.set_disk_watchdog(watchdog.clone());
+                node.spawn(watchdog.run())
+            }
+        }
+    }
+
+    /// Enables the alerts, which post to the configured webhooks whenever the health of the node changes.
+    pub fn enable_alerts(&self, config: AlertConfig, disk_watchdog: Option<DiskWatchdog>) {
+        let monitor = AlertMonitor::new(config);
+        let is_disk_low = move || disk_watchdog.as_ref().map_or(false, |watchdog| watchdog.is_low());
+        match self {
+            Self::Validator(node) => {
+                let probe = node.clone();
+                node.spawn(monitor.run(move || HealthSnapshot { is_disk_low: is_disk_low(), ..probe.health() }))
+            }
+            Self::Prover(node) => {
+                let probe = node.clone();
+                node.spawn(monitor.run(move || HealthSnapshot { is_disk_low: is_disk_low(), ..probe.health() }))
+            }
+            Self::Client(node) => {
+                let probe = node.clone();
+                node.spawn(monitor.run(move || HealthSnapshot { is_disk_low: is_disk_low(), ..probe.health() }))
+            }
+        }
+    }
+
+    /// Enables the state digests, which are computed at every multiple of the given number of blocks,
+    /// logged, and served over REST.
+    pub fn enable_state_digests(&self, interval: u32) {
+        match self {
+            Self::Validator(node) => node.state_digests().enable(interval),
+            Self::Prover(_) => (),
+            Self::Client(node) => node.state_digests().enable(interval),
+        }
+    }
+
+    /// Enables the export of the finalize state diff of every block, which are appended to the given path.
+    pub fn enable_state_diffs(&self, path: &Path) {
+        match self {
+            Self::Validator(node) => node.spawn(StateDiffExporter::new(node.ledger().clone(), path).run()),
+            Self::Prover(_) => (),
+            Self::Client(node) => node.spawn(StateDiffExporter::new(node.ledger().clone(), path).run()),
+        }
+    }
+
+    /// Enables the transitions between the client and validator roles, as the address of the node is bonded
+    /// in or out of the committee, and returns them, unless the node is a prover.
+    /// Note: The caller is expected to start the node again in the role it is requested to switch to.
+    pub fn enable_role_transitions(&self) -> Option<Arc<RoleTransitions>> {
+        let role_transitions = match self {
+            Self::Validator(node) => node.role_transitions(),
+            Self::Prover(_) => return None,
+            Self::Client(node) => node.role_transitions(),
+        };
+        role_transitions.enable();
+        Some(role_transitions.clone())
+    }
 }
@@ -0,0 +1,156 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{anyhow, bail, ensure, Result};
+use indexmap::IndexSet;
+use std::path::Path;
+
+/// The CPU affinity of the node, which pins the prover threads to a set of cores,
+/// and the other threads (i.e. networking and ledger) to the remaining cores.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CpuAffinity {
+    /// The cores that the prover threads are pinned to.
+    prover_cores: Vec<usize>,
+    /// The cores that the other threads are pinned to.
+    other_cores: Vec<usize>,
+}
+
+impl CpuAffinity {
+    /// Initializes a new CPU affinity, pinning the prover threads to the given cores, out of the given number of cores.
+    pub fn new(prover_cores: Vec<usize>, num_cores: usize) -> Result<Self> {
+        ensure!(!prover_cores.is_empty(), "At least one core must be assigned to the prover");
+        if let Some(core) = prover_cores.iter().find(|core| **core >= num_cores) {
+            bail!("Core {core} does not exist (this machine has {num_cores} cores)");
+        }
+        let other_cores: Vec<_> = (0..num_cores).filter(|core| !prover_cores.contains(core)).collect();
+        ensure!(!other_cores.is_empty(), "At least one core must be left for the networking and ledger threads");
+        Ok(Self { prover_cores, other_cores })
+    }
+
+    /// Parses the given list of cores, in the Linux `cpulist` format (e.g. `0-7,16,18-19`).
+    pub fn parse_cores(list: &str) -> Result<Vec<usize>> {
+        let parse = |core: &str| core.trim().parse::<usize>().map_err(|_| anyhow!("Invalid core '{core}' in '{list}'"));
+        let mut cores = IndexSet::new();
+        for range in list.trim().split(',') {
+            let (start, end) = match range.split_once('-') {
+                Some((start, end)) => (parse(start)?, parse(end)?),
+                None => (parse(range)?, parse(range)?),
+            };
+            ensure!(start <= end, "Invalid core range '{range}' in '{list}'");
+            for core in start..=end {
+                ensure!(cores.insert(core), "Duplicate core '{core}' in '{list}'");
+            }
+        }
+        Ok(cores.into_iter().collect())
+    }
+
+    /// Returns the cores of the given comma-separated NUMA nodes (e.g. `0` or `0,1`), as reported by Linux.
+    pub fn numa_node_cores(nodes: &str) -> Result<Vec<usize>> {
+        Self::numa_node_cores_in(Path::new("/sys/devices/system/node"), nodes)
+    }
+
+    /// Returns the cores of the given comma-separated NUMA nodes, from the given `sysfs` directory.
+    fn numa_node_cores_in(sysfs: &Path, nodes: &str) -> Result<Vec<usize>> {
+        let mut cores = Vec::new();
+        for node in nodes.split(',').map(str::trim) {
+            let Ok(node) = node.parse::<u32>() else { bail!("Invalid NUMA node '{node}'") };
+            let path = sysfs.join(format!("node{node}")).join("cpulist");
+            let list = std::fs::read_to_string(&path)
+                .map_err(|err| anyhow!("Couldn't read the cores of NUMA node {node} at {} - {err}", path.display()))?;
+            for core in Self::parse_cores(&list)? {
+                ensure!(!cores.contains(&core), "The NUMA node {node} is listed twice");
+                cores.push(core);
+            }
+        }
+        Ok(cores)
+    }
+
+    /// Returns the cores that the prover threads are pinned to.
+    pub fn prover_cores(&self) -> &[usize] {
+        &self.prover_cores
+    }
+
+    /// Returns the cores that the other threads are pinned to.
+    pub fn other_cores(&self) -> &[usize] {
+        &self.other_cores
+    }
+
+    /// Pins the current thread, which is the prover thread with the given index, to one of the prover cores.
+    pub fn pin_prover_thread(&self, index: usize) -> bool {
+        Self::pin_current_thread(&self.prover_cores, index)
+    }
+
+    /// Pins the current thread, which is the other thread with the given index, to one of the other cores.
+    pub fn pin_other_thread(&self, index: usize) -> bool {
+        Self::pin_current_thread(&self.other_cores, index)
+    }
+
+    /// Pins the current thread to a core of the given cores, chosen round-robin by the given index.
+    fn pin_current_thread(cores: &[usize], index: usize) -> bool {
+        match cores.is_empty() {
+            true => false,
+            false => core_affinity::set_for_current(core_affinity::CoreId { id: cores[index % cores.len()] }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cores() {
+        assert_eq!(CpuAffinity::parse_cores("0-3,8,10-11").unwrap(), vec![0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(CpuAffinity::parse_cores("5\n").unwrap(), vec![5]);
+
+        // Ensure the invalid lists are rejected.
+        assert!(CpuAffinity::parse_cores("").is_err());
+        assert!(CpuAffinity::parse_cores("3-1").is_err());
+        assert!(CpuAffinity::parse_cores("0-2,2").is_err());
+        assert!(CpuAffinity::parse_cores("a").is_err());
+    }
+
+    #[test]
+    fn test_new() {
+        let affinity = CpuAffinity::new(vec![2, 3], 4).unwrap();
+        assert_eq!(affinity.prover_cores(), [2, 3]);
+        assert_eq!(affinity.other_cores(), [0, 1]);
+
+        // Ensure the prover cores exist, and leave at least one core for the other threads.
+        assert!(CpuAffinity::new(vec![], 4).is_err());
+        assert!(CpuAffinity::new(vec![4], 4).is_err());
+        assert!(CpuAffinity::new(vec![0, 1, 2, 3], 4).is_err());
+
+        // Ensure the default affinity pins nothing.
+        assert!(!CpuAffinity::default().pin_prover_thread(0));
+        assert!(!CpuAffinity::default().pin_other_thread(0));
+    }
+
+    #[test]
+    fn test_numa_node_cores() {
+        let sysfs = std::env::temp_dir().join(format!("snarkos-numa-{}", std::process::id()));
+        for (node, list) in [(0, "0-3,8-11\n"), (1, "4-7,12-15\n")] {
+            std::fs::create_dir_all(sysfs.join(format!("node{node}"))).unwrap();
+            std::fs::write(sysfs.join(format!("node{node}")).join("cpulist"), list).unwrap();
+        }
+        assert_eq!(CpuAffinity::numa_node_cores_in(&sysfs, "1").unwrap(), vec![4, 5, 6, 7, 12, 13, 14, 15]);
+        assert_eq!(CpuAffinity::numa_node_cores_in(&sysfs, "0,1").unwrap().len(), 16);
+
+        // Ensure the missing and duplicate nodes are rejected.
+        assert!(CpuAffinity::numa_node_cores_in(&sysfs, "2").is_err());
+        assert!(CpuAffinity::numa_node_cores_in(&sysfs, "0,0").is_err());
+        assert!(CpuAffinity::numa_node_cores_in(&sysfs, "x").is_err());
+        std::fs::remove_dir_all(sysfs).unwrap();
+    }
+}
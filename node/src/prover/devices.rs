@@ -0,0 +1,278 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{anyhow, bail, ensure, Result};
+use indexmap::IndexSet;
+use std::{
+    process::Command,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Instant,
+};
+
+#[cfg(feature = "cuda")]
+#[link(name = "cudart")]
+extern "C" {
+    /// Sets the CUDA device that the current thread launches its kernels on, and returns `0` on success.
+    fn cudaSetDevice(device: i32) -> i32;
+}
+
+/// The devices that the prover splits its puzzle instances across.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ProverDevices {
+    /// The puzzle instances run on the CPU, and on any GPU that the proving backend uses by default.
+    #[default]
+    Default,
+    /// The puzzle instances are split evenly across the given CUDA devices, each running on its own threads.
+    Cuda(Vec<u32>),
+}
+
+impl ProverDevices {
+    /// The number of puzzle instances per CUDA device, so that a device is fed while the previous proof is finalized.
+    pub const INSTANCES_PER_CUDA_DEVICE: usize = 2;
+    /// The maximum number of CUDA devices.
+    pub const MAX_CUDA_DEVICES: usize = 16;
+
+    /// Parses the given comma-separated list of CUDA device IDs (e.g. `0,2`).
+    pub fn parse_cuda(devices: &str) -> Result<Self> {
+        let mut ids = IndexSet::new();
+        for id in devices.split(',').map(str::trim) {
+            let Ok(id) = id.parse::<u32>() else { bail!("Invalid CUDA device ID '{id}'") };
+            ensure!(ids.insert(id), "Duplicate CUDA device ID '{id}'");
+        }
+        ensure!(ids.len() <= Self::MAX_CUDA_DEVICES, "At most {} CUDA devices are supported", Self::MAX_CUDA_DEVICES);
+        Ok(Self::Cuda(ids.into_iter().collect()))
+    }
+
+    /// Returns the IDs and the names of the CUDA devices of this machine, as listed by `nvidia-smi`.
+    ///
+    /// Note: The IDs are in the PCI bus order, which is the order of the CUDA devices if `CUDA_DEVICE_ORDER=PCI_BUS_ID`.
+    pub fn list_cuda() -> Result<Vec<(u32, String)>> {
+        let output = Command::new("nvidia-smi")
+            .args(["--query-gpu=index,name", "--format=csv,noheader"])
+            .output()
+            .map_err(|err| anyhow!("Couldn't list the CUDA devices with 'nvidia-smi' - {err}"))?;
+        ensure!(output.status.success(), "Couldn't list the CUDA devices with 'nvidia-smi' ({})", output.status);
+        Self::parse_device_list(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Parses the `index, name` lines listed by `nvidia-smi`.
+    fn parse_device_list(list: &str) -> Result<Vec<(u32, String)>> {
+        list.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| match line.split_once(',') {
+                Some((id, name)) => Ok((id.trim().parse()?, name.trim().to_string())),
+                None => bail!("Invalid CUDA device '{line}'"),
+            })
+            .collect()
+    }
+
+    /// Ensures the selected devices are among the given devices of this machine.
+    pub fn ensure_available(&self, available: &[(u32, String)]) -> Result<()> {
+        if let Self::Cuda(ids) = self {
+            for id in ids {
+                ensure!(
+                    available.iter().any(|(available_id, _)| available_id == id),
+                    "CUDA device {id} does not exist (this machine has {} CUDA devices)",
+                    available.len()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the value of `CUDA_VISIBLE_DEVICES` that restricts the proving backend to the selected devices.
+    pub fn cuda_visible_devices(&self) -> Option<String> {
+        match self {
+            Self::Default => None,
+            Self::Cuda(ids) => Some(ids.iter().map(ToString::to_string).collect::<Vec<_>>().join(",")),
+        }
+    }
+
+    /// Returns the labels of the devices.
+    pub fn labels(&self) -> Vec<String> {
+        match self {
+            Self::Default => vec!["default".to_string()],
+            Self::Cuda(ids) => ids.iter().map(|id| format!("cuda:{id}")).collect(),
+        }
+    }
+
+    /// Returns the number of puzzle instances, given the number for the default devices.
+    pub fn num_puzzle_instances(&self, default_instances: usize) -> usize {
+        match self {
+            Self::Default => default_instances,
+            Self::Cuda(ids) => ids.len() * Self::INSTANCES_PER_CUDA_DEVICE,
+        }
+    }
+}
+
+/// The proving statistics of each device of the prover.
+pub struct DeviceStats {
+    /// The labels of the devices.
+    labels: Vec<String>,
+    /// The number of puzzle iterations of each device.
+    iterations: Vec<AtomicU64>,
+    /// The number of solutions of each device.
+    solutions: Vec<AtomicU64>,
+    /// The time at which the statistics started.
+    start: Instant,
+}
+
+impl DeviceStats {
+    /// Initializes the statistics for the given devices.
+    pub fn new(devices: &ProverDevices) -> Self {
+        let labels = devices.labels();
+        let iterations = labels.iter().map(|_| AtomicU64::new(0)).collect();
+        let solutions = labels.iter().map(|_| AtomicU64::new(0)).collect();
+        Self { labels, iterations, solutions, start: Instant::now() }
+    }
+
+    /// Returns the number of devices.
+    pub fn num_devices(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Records a puzzle iteration on the given device, and whether it found a solution.
+    pub fn record(&self, device: usize, found_solution: bool) {
+        if let (Some(iterations), Some(solutions)) = (self.iterations.get(device), self.solutions.get(device)) {
+            iterations.fetch_add(1, Ordering::Relaxed);
+            if found_solution {
+                solutions.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Returns the label, the number of iterations, and the solutions per minute of each device.
+    pub fn rates(&self) -> Vec<(String, u64, f64)> {
+        let minutes = self.start.elapsed().as_secs_f64().max(1.0) / 60.0;
+        self.labels
+            .iter()
+            .zip(self.iterations.iter().zip(&self.solutions))
+            .map(|(label, (iterations, solutions))| {
+                let rate = solutions.load(Ordering::Relaxed) as f64 / minutes;
+                (label.clone(), iterations.load(Ordering::Relaxed), rate)
+            })
+            .collect()
+    }
+}
+
+/// The thread pools that bind the puzzle instances to their devices.
+///
+/// Every thread of the pool of a CUDA device makes it the current device of the thread, so the proving backend
+/// launches the kernels of the puzzle instances of the device on it, including from its parallel iterators.
+#[derive(Default)]
+pub struct DevicePools {
+    /// The thread pool of each CUDA device, in the order of `CUDA_VISIBLE_DEVICES`.
+    #[cfg(feature = "cuda")]
+    pools: Vec<rayon::ThreadPool>,
+}
+
+impl DevicePools {
+    /// Initializes the thread pools of the given devices, splitting the threads of the proving backend across them.
+    pub fn new(devices: &ProverDevices) -> Result<Self> {
+        match devices {
+            ProverDevices::Default => Ok(Self::default()),
+            #[cfg(feature = "cuda")]
+            ProverDevices::Cuda(ids) => {
+                let num_threads = (rayon::current_num_threads() / ids.len()).max(1);
+                let pools = (0..ids.len())
+                    .map(|ordinal| {
+                        rayon::ThreadPoolBuilder::new()
+                            .stack_size(8 * 1024 * 1024)
+                            .num_threads(num_threads)
+                            .thread_name(move |index| format!("prover-cuda-{ordinal}-{index}"))
+                            .start_handler(move |_| {
+                                // Note: The ordinals of the devices are relative to `CUDA_VISIBLE_DEVICES`.
+                                if unsafe { cudaSetDevice(ordinal as i32) } != 0 {
+                                    error!("Couldn't select the CUDA device {ordinal} for a prover thread");
+                                }
+                            })
+                            .build()
+                            .map_err(|err| anyhow!("Couldn't initialize the threads of CUDA device {ordinal} - {err}"))
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(Self { pools })
+            }
+            #[cfg(not(feature = "cuda"))]
+            ProverDevices::Cuda(_) => bail!("The CUDA devices require snarkOS to be built with 'cuda'"),
+        }
+    }
+
+    /// Runs the given puzzle iteration on the given device.
+    pub fn install<R: Send>(&self, device: usize, iteration: impl FnOnce() -> R + Send) -> R {
+        #[cfg(feature = "cuda")]
+        if let Some(pool) = self.pools.get(device) {
+            return pool.install(iteration);
+        }
+        #[cfg(not(feature = "cuda"))]
+        let _ = device;
+        iteration()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cuda() {
+        let devices = ProverDevices::parse_cuda("0,2").unwrap();
+        assert_eq!(devices, ProverDevices::Cuda(vec![0, 2]));
+        assert_eq!(devices.cuda_visible_devices(), Some("0,2".to_string()));
+        assert_eq!(devices.labels(), vec!["cuda:0".to_string(), "cuda:2".to_string()]);
+        assert_eq!(devices.num_puzzle_instances(6), 2 * ProverDevices::INSTANCES_PER_CUDA_DEVICE);
+
+        // Ensure the default devices are unchanged.
+        assert_eq!(ProverDevices::default().cuda_visible_devices(), None);
+        assert_eq!(ProverDevices::default().num_puzzle_instances(6), 6);
+
+        // Ensure invalid lists are rejected.
+        assert!(ProverDevices::parse_cuda("").is_err());
+        assert!(ProverDevices::parse_cuda("0,a").is_err());
+        assert!(ProverDevices::parse_cuda("1,1").is_err());
+        assert!(ProverDevices::parse_cuda(&(0..17).map(|id| id.to_string()).collect::<Vec<_>>().join(",")).is_err());
+    }
+
+    #[test]
+    fn test_parse_device_list() {
+        let list = "0, NVIDIA GeForce RTX 4090\n1, NVIDIA GeForce RTX 4090\n2, NVIDIA A100-SXM4-80GB\n";
+        let available = ProverDevices::parse_device_list(list).unwrap();
+        assert_eq!(available.len(), 3);
+        assert_eq!(available[2], (2, "NVIDIA A100-SXM4-80GB".to_string()));
+        assert!(ProverDevices::parse_device_list("").unwrap().is_empty());
+        assert!(ProverDevices::parse_device_list("No devices were found").is_err());
+
+        // Ensure the selected devices must exist.
+        assert!(ProverDevices::Cuda(vec![0, 2]).ensure_available(&available).is_ok());
+        assert!(ProverDevices::Cuda(vec![0, 3]).ensure_available(&available).is_err());
+        assert!(ProverDevices::default().ensure_available(&[]).is_ok());
+    }
+
+    #[test]
+    fn test_device_stats() {
+        let stats = DeviceStats::new(&ProverDevices::Cuda(vec![0, 2]));
+        assert_eq!(stats.num_devices(), 2);
+        stats.record(0, true);
+        stats.record(0, false);
+        stats.record(1, false);
+        // Ensure an unknown device is ignored.
+        stats.record(2, true);
+
+        let rates = stats.rates();
+        assert_eq!(rates[0].0, "cuda:0");
+        assert_eq!(rates[0].1, 2);
+        assert!(rates[0].2 > 0.0);
+        assert_eq!(rates[1].1, 1);
+        assert_eq!(rates[1].2, 0.0);
+    }
+}
@@ -0,0 +1,136 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{puzzle::SolutionID, Network};
+
+use core::fmt;
+use indexmap::IndexSet;
+use parking_lot::Mutex;
+
+/// The reason a solution is dropped before it is broadcast.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FilterReason {
+    /// The epoch of the solution ended before it was broadcast.
+    Stale,
+    /// The solution was already broadcast recently.
+    Duplicate,
+    /// The solution is below the latest proof target, which increased while it was being proven.
+    BelowTarget,
+}
+
+impl fmt::Display for FilterReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Stale => write!(f, "its epoch ended"),
+            Self::Duplicate => write!(f, "it was already broadcast"),
+            Self::BelowTarget => write!(f, "it is below the latest proof target"),
+        }
+    }
+}
+
+/// The filter of the solutions of the prover, which drops the duplicate, stale, and below-target solutions
+/// before they are broadcast, as the peers would reject them.
+pub struct SolutionFilter<N: Network> {
+    /// The IDs of the recently broadcast solutions, from the oldest to the newest.
+    recent: Mutex<IndexSet<SolutionID<N>>>,
+}
+
+impl<N: Network> Default for SolutionFilter<N> {
+    fn default() -> Self {
+        Self { recent: Default::default() }
+    }
+}
+
+impl<N: Network> SolutionFilter<N> {
+    /// The maximum number of recently broadcast solutions to track.
+    pub const MAX_RECENT_SOLUTIONS: usize = 1024;
+
+    /// Checks the given solution, of the given epoch and proof target, against the latest epoch hash and proof target,
+    /// and records it as broadcast if it passes the filter.
+    pub fn check(
+        &self,
+        solution_id: SolutionID<N>,
+        epoch_hash: N::BlockHash,
+        solution_target: u64,
+        latest_epoch_hash: Option<N::BlockHash>,
+        latest_proof_target: Option<u64>,
+    ) -> Result<(), FilterReason> {
+        // Ensure the epoch of the solution did not end.
+        if latest_epoch_hash != Some(epoch_hash) {
+            return Err(FilterReason::Stale);
+        }
+        // Ensure the solution meets the latest proof target.
+        if latest_proof_target.map_or(false, |proof_target| solution_target < proof_target) {
+            return Err(FilterReason::BelowTarget);
+        }
+        // Ensure the solution was not broadcast recently.
+        let mut recent = self.recent.lock();
+        if !recent.insert(solution_id) {
+            return Err(FilterReason::Duplicate);
+        }
+        if recent.len() > Self::MAX_RECENT_SOLUTIONS {
+            recent.shift_remove_index(0);
+        }
+        Ok(())
+    }
+
+    /// Returns the number of recently broadcast solutions that are tracked.
+    pub fn num_recent(&self) -> usize {
+        self.recent.lock().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{Field, MainnetV0};
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_solution_filter() {
+        let filter = SolutionFilter::<CurrentNetwork>::default();
+        let epoch_hash: <CurrentNetwork as Network>::BlockHash = Field::<CurrentNetwork>::from_u32(1).into();
+        let next_epoch_hash = Field::<CurrentNetwork>::from_u32(2).into();
+        let id = SolutionID::from(1u64);
+
+        // Ensure a solution that meets the latest work passes once, and is then a duplicate.
+        assert_eq!(filter.check(id, epoch_hash, 100, Some(epoch_hash), Some(100)), Ok(()));
+        assert_eq!(filter.check(id, epoch_hash, 100, Some(epoch_hash), Some(100)), Err(FilterReason::Duplicate));
+        // Ensure the stale and below-target solutions are dropped, and are not recorded as broadcast.
+        let id = SolutionID::from(2u64);
+        assert_eq!(filter.check(id, epoch_hash, 100, Some(next_epoch_hash), Some(100)), Err(FilterReason::Stale));
+        assert_eq!(filter.check(id, epoch_hash, 100, None, Some(100)), Err(FilterReason::Stale));
+        assert_eq!(filter.check(id, epoch_hash, 99, Some(epoch_hash), Some(100)), Err(FilterReason::BelowTarget));
+        assert_eq!(filter.check(id, epoch_hash, 99, Some(epoch_hash), None), Ok(()));
+        assert_eq!(filter.num_recent(), 2);
+    }
+
+    #[test]
+    fn test_solution_filter_evicts_the_oldest() {
+        let filter = SolutionFilter::<CurrentNetwork>::default();
+        let epoch_hash: <CurrentNetwork as Network>::BlockHash = Field::<CurrentNetwork>::from_u32(1).into();
+        let max = SolutionFilter::<CurrentNetwork>::MAX_RECENT_SOLUTIONS as u64;
+        for id in 0..=max {
+            assert_eq!(filter.check(SolutionID::from(id), epoch_hash, 1, Some(epoch_hash), Some(1)), Ok(()));
+        }
+        assert_eq!(filter.num_recent(), max as usize);
+        // Ensure the oldest solution was evicted, and the newest is still tracked.
+        assert_eq!(filter.check(SolutionID::from(0), epoch_hash, 1, Some(epoch_hash), Some(1)), Ok(()));
+        assert_eq!(
+            filter.check(SolutionID::from(max), epoch_hash, 1, Some(epoch_hash), Some(1)),
+            Err(FilterReason::Duplicate)
+        );
+    }
+}
@@ -0,0 +1,347 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{ProverDevices, ProverStats, ProverThrottle, SolutionFilter, SolutionSubmitter, StatusServer, SubmitMode};
+use crate::light::network_name;
+use snarkvm::{
+    ledger::{
+        puzzle::{Puzzle, Solution},
+        store::helpers::memory::ConsensusMemory,
+    },
+    prelude::{Address, Network},
+    synthesizer::VM,
+};
+
+use anyhow::{anyhow, bail, ensure, Result};
+use core::{str::FromStr, time::Duration};
+use parking_lot::RwLock;
+use rand::{rngs::OsRng, Rng};
+use reqwest::Client;
+use serde_json::Value;
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+/// The latest puzzle of the network, as served by a node at `GET /<network>/puzzle/latest`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PuzzleWork<N: Network> {
+    /// The latest block height.
+    pub height: u32,
+    /// The latest epoch hash.
+    pub epoch_hash: N::BlockHash,
+    /// The latest proof target.
+    pub proof_target: u64,
+    /// The latest coinbase target.
+    pub coinbase_target: u64,
+}
+
+impl<N: Network> PuzzleWork<N> {
+    /// Parses the latest puzzle from the given response of a node.
+    pub fn from_json(value: &Value) -> Result<Self> {
+        let field = |name: &str| value.get(name).ok_or_else(|| anyhow!("The puzzle is missing the '{name}' field"));
+        let number = |name: &str| field(name)?.as_u64().ok_or_else(|| anyhow!("Invalid '{name}' in the puzzle"));
+        let Some(epoch_hash) = field("epoch_hash")?.as_str() else { bail!("Invalid 'epoch_hash' in the puzzle") };
+        let work = Self {
+            height: u32::try_from(number("height")?)?,
+            epoch_hash: N::BlockHash::from_str(epoch_hash).map_err(|_| anyhow!("Invalid epoch hash '{epoch_hash}'"))?,
+            proof_target: number("proof_target")?,
+            coinbase_target: number("coinbase_target")?,
+        };
+        ensure!(work.proof_target > 0, "Invalid proof target in the puzzle");
+        Ok(work)
+    }
+}
+
+/// A headless prover, which runs neither the P2P network nor a ledger: it polls the REST API of the given node(s)
+/// for the latest puzzle, proves it locally, and submits its solutions to the first reachable node.
+#[derive(Clone)]
+pub struct HeadlessProver<N: Network> {
+    /// The address that the prover proves for.
+    address: Address<N>,
+    /// The puzzle.
+    puzzle: Puzzle<N>,
+    /// The number of puzzle instances.
+    num_puzzle_instances: usize,
+    /// The limits on the resources that the prover uses.
+    throttle: ProverThrottle,
+    /// The HTTP client, to fetch the latest puzzle.
+    client: Client,
+    /// The solution submitter, whose endpoints are the nodes.
+    submitter: Arc<SolutionSubmitter<N>>,
+    /// The latest puzzle, and the time at which it was fetched.
+    work: Arc<RwLock<Option<(PuzzleWork<N>, Instant)>>>,
+    /// The filter of the solutions, before they are submitted.
+    filter: Arc<SolutionFilter<N>>,
+    /// The performance statistics.
+    stats: Arc<ProverStats<N>>,
+    /// The shutdown signal.
+    shutdown: Arc<AtomicBool>,
+}
+
+impl<N: Network> HeadlessProver<N> {
+    /// The age after which the latest puzzle is outdated, and the puzzle pauses, in seconds.
+    const MAX_WORK_AGE_IN_SECS: u64 = 60;
+    /// The interval at which the latest puzzle is fetched, in seconds.
+    const POLL_INTERVAL_IN_SECS: u64 = 2;
+    /// The timeout of a request for the latest puzzle.
+    const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+    /// The interval at which the rate of the puzzle attempts is sampled, in seconds.
+    const STATS_INTERVAL_IN_SECS: u64 = 10;
+
+    /// Initializes a new headless prover, proving for the given address on the given devices,
+    /// for the given REST URL(s) of the nodes.
+    pub fn new(
+        address: Address<N>,
+        nodes: &[String],
+        devices: &ProverDevices,
+        throttle: ProverThrottle,
+        shutdown: Arc<AtomicBool>,
+    ) -> Result<Self> {
+        ensure!(!nodes.is_empty(), "A headless prover requires at least one node");
+        Ok(Self {
+            address,
+            puzzle: VM::<N, ConsensusMemory<N>>::new_puzzle()?,
+            // Note: As the prover runs neither the network nor a ledger, only one core is left for the polling.
+            num_puzzle_instances: throttle
+                .num_puzzle_instances(devices.num_puzzle_instances(num_cpus::get().saturating_sub(1).clamp(1, 6))),
+            throttle,
+            client: Client::builder().timeout(Self::REQUEST_TIMEOUT).build()?,
+            submitter: Arc::new(SolutionSubmitter::new(nodes, SubmitMode::Failover)?),
+            work: Default::default(),
+            filter: Default::default(),
+            stats: Default::default(),
+            shutdown,
+        })
+    }
+
+    /// Returns the REST URLs of the nodes.
+    pub fn nodes(&self) -> &[String] {
+        self.submitter.endpoints()
+    }
+
+    /// Returns the number of puzzle instances.
+    pub const fn num_puzzle_instances(&self) -> usize {
+        self.num_puzzle_instances
+    }
+
+    /// Returns the latest puzzle, unless it is outdated.
+    pub fn work(&self) -> Option<PuzzleWork<N>> {
+        let max_age = Duration::from_secs(Self::MAX_WORK_AGE_IN_SECS);
+        self.work.read().filter(|(_, time)| time.elapsed() <= max_age).map(|(work, _)| work)
+    }
+
+    /// Starts the polling of the nodes, the puzzle instances, and the status endpoint, if one is given.
+    pub async fn start(&self, status_ip: Option<SocketAddr>) -> Result<()> {
+        // Serve the status of the prover.
+        if let Some(status_ip) = status_ip {
+            let listener = tokio::net::TcpListener::bind(status_ip).await?;
+            info!("Serving the prover status at 'http://{status_ip}/status'");
+            let prover = self.clone();
+            tokio::spawn(StatusServer::run(listener, Arc::new(move || prover.status())));
+        }
+        info!(
+            "Starting a headless prover with {} puzzle instance(s), for {} node(s)",
+            self.num_puzzle_instances,
+            self.nodes().len()
+        );
+        // Poll the nodes for the latest puzzle.
+        let prover = self.clone();
+        tokio::spawn(async move { prover.poll_loop().await });
+        // Sample the rate of the puzzle attempts.
+        let prover = self.clone();
+        tokio::spawn(async move {
+            while !prover.shutdown.load(Ordering::Relaxed) {
+                tokio::time::sleep(Duration::from_secs(Self::STATS_INTERVAL_IN_SECS)).await;
+                prover.stats.sample();
+            }
+        });
+        // Start the puzzle instances.
+        for _ in 0..self.num_puzzle_instances {
+            let prover = self.clone();
+            tokio::spawn(async move { prover.puzzle_loop().await });
+        }
+        Ok(())
+    }
+
+    /// Returns the status of the prover, as served by the status endpoint.
+    fn status(&self) -> Value {
+        let mut status = self.stats.to_json();
+        status["paused"] = self.work().is_none().into();
+        status["height"] = self.work().map(|work| work.height).into();
+        status["pending_solutions"] = self.submitter.num_pending().into();
+        status
+    }
+
+    /// Fetches the latest puzzle from the nodes, and retries the pending solutions, until the node shuts down.
+    async fn poll_loop(&self) {
+        while !self.shutdown.load(Ordering::Relaxed) {
+            match self.fetch_work().await {
+                Ok(work) => {
+                    let previous = self.work.write().replace((work, Instant::now())).map(|(work, _)| work);
+                    if previous.map(|previous| previous.height) != Some(work.height) {
+                        info!(
+                            "Puzzle (Block {}, Coinbase Target {}, Proof Target {})",
+                            work.height, work.coinbase_target, work.proof_target
+                        );
+                    }
+                    // Retry the pending solutions of the current epoch, and discard the stale ones.
+                    for solution in self.submitter.take_pending() {
+                        match solution.epoch_hash() == work.epoch_hash {
+                            true => self.submit_solution(solution).await,
+                            false => {
+                                debug!("Discarding the stale solution '{}'", solution.id());
+                                self.stats.record_stale();
+                            }
+                        }
+                    }
+                }
+                Err(error) => warn!("Failed to fetch the latest puzzle - {error}"),
+            }
+            tokio::time::sleep(Duration::from_secs(Self::POLL_INTERVAL_IN_SECS)).await;
+        }
+    }
+
+    /// Fetches the latest puzzle from the first node that serves it.
+    async fn fetch_work(&self) -> Result<PuzzleWork<N>> {
+        for node in self.nodes() {
+            let url = format!("{node}/{}/puzzle/latest", network_name::<N>()?);
+            let response = match self.client.get(&url).send().await {
+                Ok(response) if response.status().is_success() => response,
+                Ok(response) => {
+                    debug!("Failed to fetch {url} - {}", response.status());
+                    continue;
+                }
+                Err(error) => {
+                    debug!("Failed to fetch {url} - {error}");
+                    continue;
+                }
+            };
+            return PuzzleWork::from_json(&response.json::<Value>().await?);
+        }
+        bail!("None of the {} node(s) is reachable", self.nodes().len())
+    }
+
+    /// Executes an instance of the puzzle, until the node shuts down.
+    async fn puzzle_loop(&self) {
+        while !self.shutdown.load(Ordering::Relaxed) {
+            // If the latest puzzle is missing or outdated, then skip this iteration.
+            let Some(work) = self.work() else {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                continue;
+            };
+
+            // Execute the puzzle.
+            let prover = self.clone();
+            let timer = Instant::now();
+            let result =
+                tokio::task::spawn_blocking(move || prover.puzzle_iteration(work.epoch_hash, work.proof_target)).await;
+            self.stats.record_attempt(work.epoch_hash, work.proof_target, matches!(result, Ok(Some(_))));
+
+            // If the prover found a solution, then submit it, unless it is stale, a duplicate, or below the target.
+            if let Ok(Some((solution_target, solution))) = result {
+                info!("Found a Solution '{}' (Proof Target {solution_target})", solution.id());
+                let latest = self.work();
+                let check = self.filter.check(
+                    solution.id(),
+                    solution.epoch_hash(),
+                    solution_target,
+                    latest.map(|work| work.epoch_hash),
+                    latest.map(|work| work.proof_target),
+                );
+                match check {
+                    Ok(()) => self.submit_solution(solution).await,
+                    Err(reason) => {
+                        debug!("Dropping the solution '{}', as {reason}", solution.id());
+                        self.stats.record_filtered(reason);
+                    }
+                }
+            }
+
+            // Idle for a share of the proving time, to meet the target utilization.
+            let idle_time = self.throttle.idle_time(timer.elapsed());
+            if !idle_time.is_zero() {
+                tokio::time::sleep(idle_time).await;
+            }
+        }
+    }
+
+    /// Performs one iteration of the puzzle, for the given epoch hash and proof target.
+    fn puzzle_iteration(&self, epoch_hash: N::BlockHash, proof_target: u64) -> Option<(u64, Solution<N>)> {
+        let solution = self.puzzle.prove(epoch_hash, self.address, OsRng.gen(), Some(proof_target)).ok()?;
+        self.puzzle.get_proof_target(&solution).ok().map(|solution_target| (solution_target, solution))
+    }
+
+    /// Submits the solution to the first reachable node, and queues it for a retry if no node accepted it.
+    async fn submit_solution(&self, solution: Solution<N>) {
+        match self.submitter.submit(&solution).await {
+            0 => {
+                warn!("No node is reachable, retrying the solution '{}' later", solution.id());
+                self.submitter.queue(solution);
+            }
+            _ => self.stats.record_accepted(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use snarkvm::prelude::{MainnetV0, PrivateKey, TestRng};
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_puzzle_work_from_json() {
+        let epoch_hash = <CurrentNetwork as Network>::BlockHash::default();
+        let value =
+            json!({ "height": 10, "epoch_hash": epoch_hash.to_string(), "proof_target": 100, "coinbase_target": 400 });
+        let work = PuzzleWork::<CurrentNetwork>::from_json(&value).unwrap();
+        assert_eq!(work, PuzzleWork { height: 10, epoch_hash, proof_target: 100, coinbase_target: 400 });
+
+        // Ensure a malformed puzzle is rejected.
+        let missing = json!({ "height": 10, "epoch_hash": epoch_hash.to_string(), "proof_target": 100 });
+        assert!(PuzzleWork::<CurrentNetwork>::from_json(&missing).is_err());
+        let invalid_hash = json!({ "height": 10, "epoch_hash": "ab1", "proof_target": 100, "coinbase_target": 400 });
+        assert!(PuzzleWork::<CurrentNetwork>::from_json(&invalid_hash).is_err());
+        let zero_target =
+            json!({ "height": 10, "epoch_hash": epoch_hash.to_string(), "proof_target": 0, "coinbase_target": 0 });
+        assert!(PuzzleWork::<CurrentNetwork>::from_json(&zero_target).is_err());
+    }
+
+    #[test]
+    fn test_headless_prover() {
+        let rng = &mut TestRng::default();
+        let address = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        let throttle = ProverThrottle::new(Some(2), None).unwrap();
+
+        // Ensure the prover requires a node, and an HTTP(S) URL.
+        let devices = ProverDevices::default();
+        assert!(HeadlessProver::new(address, &[], &devices, throttle, Default::default()).is_err());
+        let nodes = ["1.2.3.4:3030".to_string()];
+        assert!(HeadlessProver::new(address, &nodes, &devices, throttle, Default::default()).is_err());
+
+        // Ensure the puzzle instances are capped by the throttle, and there is no work until a puzzle is fetched.
+        let nodes = ["http://1.2.3.4:3030/".to_string()];
+        let prover = HeadlessProver::new(address, &nodes, &devices, throttle, Default::default()).unwrap();
+        assert_eq!(prover.nodes(), ["http://1.2.3.4:3030"]);
+        assert!(prover.num_puzzle_instances() <= 2);
+        assert!(prover.work().is_none());
+    }
+}
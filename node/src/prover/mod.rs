@@ -12,16 +12,46 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod affinity;
+pub use affinity::*;
+
+mod devices;
+pub use devices::*;
+
+mod filter;
+pub use filter::*;
+
+mod headless;
+pub use headless::*;
+
+mod pause;
+pub use pause::*;
+
+mod pool;
+pub use pool::*;
+
 mod router;
 
-use crate::traits::NodeInterface;
+mod stats;
+pub use stats::*;
+
+mod submitter;
+pub use submitter::*;
+
+mod throttle;
+pub use throttle::*;
+
+use crate::{traits::NodeInterface, HealthSnapshot};
 use snarkos_account::Account;
 use snarkos_node_bft::ledger_service::ProverLedgerService;
 use snarkos_node_router::{
     messages::{Message, NodeType, UnconfirmedSolution},
     Heartbeat,
     Inbound,
+    KeepAlive,
+    ListenAddr,
     Outbound,
+    RelayMode,
     Router,
     Routing,
 };
@@ -34,8 +64,9 @@ use snarkvm::{
     ledger::narwhal::Data,
     prelude::{
         block::{Block, Header},
-        puzzle::{Puzzle, Solution},
+        puzzle::{PartialSolution, Puzzle, Solution},
         store::ConsensusStorage,
+        Address,
         Network,
     },
     synthesizer::VM,
@@ -44,7 +75,7 @@ use snarkvm::{
 use aleo_std::StorageMode;
 use anyhow::Result;
 use colored::Colorize;
-use core::{marker::PhantomData, time::Duration};
+use core::{future::Future, marker::PhantomData, time::Duration};
 use parking_lot::{Mutex, RwLock};
 use rand::{rngs::OsRng, CryptoRng, Rng};
 use snarkos_node_bft::helpers::fmt_id;
@@ -54,6 +85,7 @@ use std::{
         atomic::{AtomicBool, AtomicU8, Ordering},
         Arc,
     },
+    time::Instant,
 };
 use tokio::task::JoinHandle;
 
@@ -72,10 +104,28 @@ pub struct Prover<N: Network, C: ConsensusStorage<N>> {
     latest_epoch_hash: Arc<RwLock<Option<N::BlockHash>>>,
     /// The latest block header.
     latest_block_header: Arc<RwLock<Option<Header<N>>>>,
+    /// The time at which the latest puzzle was received.
+    latest_puzzle_time: Arc<RwLock<Option<Instant>>>,
+    /// Whether the puzzle is paused, as the view of the chain of the prover is stale.
+    is_paused: Arc<AtomicBool>,
     /// The number of puzzle instances.
     puzzle_instances: Arc<AtomicU8>,
     /// The maximum number of puzzle instances.
     max_puzzle_instances: u8,
+    /// The thread pools that bind the puzzle instances to their devices.
+    device_pools: Arc<DevicePools>,
+    /// The proving statistics of each device.
+    device_stats: Arc<DeviceStats>,
+    /// The limits on the resources that the prover uses.
+    throttle: ProverThrottle,
+    /// The pool client, if the prover proves for a pool.
+    pool: Option<Arc<PoolClient<N>>>,
+    /// The solution submitter.
+    submitter: Arc<SolutionSubmitter<N>>,
+    /// The performance statistics.
+    stats: Arc<ProverStats<N>>,
+    /// The filter of the solutions, before they are broadcast.
+    filter: Arc<SolutionFilter<N>>,
     /// The spawned handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
     /// The shutdown signal.
@@ -88,8 +138,17 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
     /// Initializes a new prover node.
     pub async fn new(
         node_ip: SocketAddr,
+        listen_addrs: &[ListenAddr],
         account: Account<N>,
         trusted_peers: &[SocketAddr],
+        pinned_peers: &[SocketAddr],
+        keep_alive: KeepAlive,
+        relay_mode: RelayMode,
+        devices: ProverDevices,
+        throttle: ProverThrottle,
+        pool: Option<PoolClient<N>>,
+        submitter: SolutionSubmitter<N>,
+        status_ip: Option<SocketAddr>,
         genesis: Block<N>,
         storage_mode: StorageMode,
         shutdown: Arc<AtomicBool>,
@@ -100,23 +159,29 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
         // Initialize the ledger service.
         let ledger_service = Arc::new(ProverLedgerService::new());
         // Initialize the sync module.
-        let sync = BlockSync::new(BlockSyncMode::Router, ledger_service.clone());
+        let sync = BlockSync::new(BlockSyncMode::Router, ledger_service.clone(), &[]);
         // Determine if the prover should allow external peers.
         let allow_external_peers = true;
 
         // Initialize the node router.
         let router = Router::new(
             node_ip,
+            listen_addrs,
             NodeType::Prover,
             account,
             trusted_peers,
+            pinned_peers,
             Self::MAXIMUM_NUMBER_OF_PEERS as u16,
+            keep_alive,
+            relay_mode,
             allow_external_peers,
             matches!(storage_mode, StorageMode::Development(_)),
         )
         .await?;
-        // Compute the maximum number of puzzle instances.
-        let max_puzzle_instances = num_cpus::get().saturating_sub(2).clamp(1, 6);
+        // Compute the maximum number of puzzle instances, which are split evenly across the devices,
+        // and capped by the number of prover threads.
+        let max_puzzle_instances =
+            throttle.num_puzzle_instances(devices.num_puzzle_instances(num_cpus::get().saturating_sub(2).clamp(1, 6)));
         // Initialize the node.
         let node = Self {
             router,
@@ -125,14 +190,42 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
             puzzle: VM::<N, C>::new_puzzle()?,
             latest_epoch_hash: Default::default(),
             latest_block_header: Default::default(),
+            latest_puzzle_time: Default::default(),
+            is_paused: Default::default(),
             puzzle_instances: Default::default(),
             max_puzzle_instances: u8::try_from(max_puzzle_instances)?,
+            device_pools: Arc::new(DevicePools::new(&devices)?),
+            device_stats: Arc::new(DeviceStats::new(&devices)),
+            throttle,
+            pool: pool.map(Arc::new),
+            submitter: Arc::new(submitter),
+            stats: Default::default(),
+            filter: Default::default(),
             handles: Default::default(),
             shutdown,
             _phantom: Default::default(),
         };
         // Initialize the routing.
         node.initialize_routing().await;
+        // Restore the known peers of the previous run.
+        node.router.enable_peer_cache(crate::peer_cache_path(N::ID, &storage_mode));
+        // Log the limits on the resources of the prover.
+        if let Some(threads) = throttle.threads() {
+            info!("Capping the prover at {threads} threads");
+        }
+        if throttle.utilization() < ProverThrottle::MAX_UTILIZATION {
+            info!("Targeting a prover utilization of {}%", throttle.utilization());
+        }
+        // Initialize the pool client.
+        node.initialize_pool();
+        // Initialize the solution submission retries.
+        node.initialize_submission();
+        // Initialize the statistics, and the status endpoint, if enabled.
+        node.initialize_stats(status_ip).await?;
+        // Initialize the pausing of the puzzle, while the view of the chain is stale.
+        node.initialize_pause_monitor();
+        // Initialize the prefetch of the puzzle of the next epoch.
+        node.initialize_epoch_prefetch();
         // Initialize the puzzle.
         node.initialize_puzzle().await;
         // Initialize the notification message loop.
@@ -142,6 +235,17 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
         // Return the node.
         Ok(node)
     }
+
+    /// Returns a snapshot of the health of the node, for the alerts.
+    /// Note: As the prover does not keep a ledger, it is only checked for its peers.
+    pub(crate) fn health(&self) -> HealthSnapshot {
+        HealthSnapshot { num_peers: self.router.number_of_connected_peers(), ..Default::default() }
+    }
+
+    /// Spawns a task with the given future; it should only be used for long-running tasks.
+    pub fn spawn<T: Future<Output = ()> + Send + 'static>(&self, future: T) {
+        self.handles.lock().push(tokio::spawn(future));
+    }
 }
 
 #[async_trait]
@@ -154,6 +258,10 @@ impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Prover<N, C> {
         debug!("Shutting down the puzzle...");
         self.shutdown.store(true, Ordering::Relaxed);
 
+        // Drain the connections to the peers.
+        debug!("Draining the connections...");
+        self.drain_connections().await;
+
         // Abort the tasks.
         debug!("Shutting down the prover...");
         self.handles.lock().iter().for_each(|handle| handle.abort());
@@ -166,26 +274,227 @@ impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Prover<N, C> {
 }
 
 impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
+    /// The interval at which the solution rate of each device is reported.
+    const DEVICE_REPORT_INTERVAL_IN_SECS: u64 = 300;
+    /// The interval at which the epoch hash of the next epoch is looked up, in milliseconds.
+    const EPOCH_PREFETCH_INTERVAL_IN_MS: u64 = 1000;
+    /// The number of heartbeats without a new puzzle, after which the puzzle is outdated.
+    const MAX_PUZZLE_AGE_IN_HEARTBEATS: u32 = 4;
+    /// The interval at which a paused prover requests a new puzzle, in seconds.
+    const PAUSED_PUZZLE_REQUEST_INTERVAL_IN_SECS: u64 = 5;
+    /// The interval at which the staleness of the puzzle is checked, in milliseconds.
+    const PAUSE_CHECK_INTERVAL_IN_MS: u64 = 1000;
+    /// The interval at which the rate of the puzzle attempts is sampled.
+    const STATS_INTERVAL_IN_SECS: u64 = 10;
+    /// The interval at which the solutions that no target accepted are retried.
+    const SUBMISSION_RETRY_INTERVAL_IN_SECS: u64 = 5;
+
+    /// Initialize the connection to the pool, if the prover proves for a pool.
+    fn initialize_pool(&self) {
+        if let Some(pool) = &self.pool {
+            info!("Proving for the pool at '{}' as worker '{}'", pool.server(), pool.worker());
+            let (pool, shutdown) = (pool.clone(), self.shutdown.clone());
+            self.handles.lock().push(tokio::spawn(async move { pool.run(shutdown).await }));
+        }
+    }
+
+    /// Initialize the monitor that pauses the puzzle while the view of the chain of the prover is stale,
+    /// and resumes it once a fresh puzzle is received.
+    /// Note: The prover of a pool works on the job assigned by the pool, which tracks the chain instead.
+    fn initialize_pause_monitor(&self) {
+        if self.pool.is_some() {
+            return;
+        }
+        let prover = self.clone();
+        self.handles.lock().push(tokio::spawn(async move {
+            let mut last_request = Instant::now();
+            loop {
+                tokio::time::sleep(Duration::from_millis(Self::PAUSE_CHECK_INTERVAL_IN_MS)).await;
+                let reason = prover.pause_reason();
+                match (reason, prover.is_paused.load(Ordering::Relaxed)) {
+                    (Some(reason), false) => warn!("Pausing the puzzle, as {reason}"),
+                    (None, true) => info!("Resuming the puzzle, as the prover caught up with the peers"),
+                    _ => (),
+                }
+                prover.is_paused.store(reason.is_some(), Ordering::Relaxed);
+                #[cfg(feature = "metrics")]
+                metrics::gauge(metrics::prover::PAUSED, reason.is_some() as u8 as f64);
+
+                // Request a new puzzle, to catch up with the peers.
+                if reason.is_some() && last_request.elapsed().as_secs() >= Self::PAUSED_PUZZLE_REQUEST_INTERVAL_IN_SECS
+                {
+                    prover.handle_puzzle_request();
+                    last_request = Instant::now();
+                }
+            }
+        }));
+    }
+
+    /// Returns the reason to pause the puzzle, if the latest puzzle is stale.
+    fn pause_reason(&self) -> Option<PauseReason> {
+        // If no puzzle was received yet, there is no work to pause.
+        let puzzle_height = self.latest_block_header.read().as_ref().map(Header::height)?;
+        let puzzle_age = self.latest_puzzle_time.read().map(|time| time.elapsed())?;
+        let max_puzzle_age = self.router.keep_alive().heartbeat_interval() * Self::MAX_PUZZLE_AGE_IN_HEARTBEATS;
+        let greatest_peer_height = self.sync.find_sync_peers().and_then(|(peers, _)| peers.values().max().copied());
+        check_puzzle_staleness(puzzle_height, puzzle_age, max_puzzle_age, greatest_peer_height, N::NUM_BLOCKS_PER_EPOCH)
+    }
+
+    /// Initialize the prefetch of the next epoch, which precomputes the puzzle of the next epoch in the background
+    /// once the peers produced its epoch hash (i.e. the last block of the current epoch), so the puzzle does not
+    /// stall at the epoch boundary.
+    fn initialize_epoch_prefetch(&self) {
+        let prover = self.clone();
+        self.handles.lock().push(tokio::spawn(async move {
+            let mut prefetched_epoch_hash = None;
+            loop {
+                tokio::time::sleep(Duration::from_millis(Self::EPOCH_PREFETCH_INTERVAL_IN_MS)).await;
+                // Retrieve the epoch hash of the next epoch, unless it was already prefetched.
+                let Some(epoch_hash) = prover.next_epoch_hash() else { continue };
+                if prefetched_epoch_hash.replace(epoch_hash) == Some(epoch_hash) {
+                    continue;
+                }
+                // Precompute the puzzle of the next epoch, which is cached for the subsequent proofs.
+                let (puzzle, address, timer) = (prover.puzzle.clone(), prover.address(), Instant::now());
+                let result = tokio::task::spawn_blocking(move || {
+                    puzzle.get_leaves(&PartialSolution::new(epoch_hash, address, 0)?).map(|_| ())
+                })
+                .await;
+                match result {
+                    Ok(Ok(())) => info!(
+                        "Prefetched the puzzle for Epoch '{}' {}",
+                        fmt_id(epoch_hash),
+                        format!("({} ms)", timer.elapsed().as_millis()).dimmed()
+                    ),
+                    Ok(Err(error)) => {
+                        warn!("Failed to prefetch the puzzle for Epoch '{}' - {error}", fmt_id(epoch_hash))
+                    }
+                    Err(error) => warn!("Failed to prefetch the puzzle for Epoch '{}' - {error}", fmt_id(epoch_hash)),
+                }
+            }
+        }));
+    }
+
+    /// Returns the epoch hash of the next epoch, if the peers produced the last block of the current epoch.
+    /// Note: The epoch hash of an epoch is the hash of the last block of the previous epoch.
+    fn next_epoch_hash(&self) -> Option<N::BlockHash> {
+        let puzzle_height = self.latest_block_header.read().as_ref().map(Header::height)?;
+        let last_height = (puzzle_height / N::NUM_BLOCKS_PER_EPOCH + 1) * N::NUM_BLOCKS_PER_EPOCH - 1;
+        let epoch_hash = self.sync.find_sync_peer_hash(last_height)?;
+        // Ensure the prover did not already advance to the next epoch.
+        let latest_epoch_hash = *self.latest_epoch_hash.read();
+        (latest_epoch_hash != Some(epoch_hash)).then_some(epoch_hash)
+    }
+
+    /// Initialize the sampling of the statistics, and the status endpoint, if one is given.
+    async fn initialize_stats(&self, status_ip: Option<SocketAddr>) -> Result<()> {
+        // Sample the rate of the puzzle attempts.
+        let stats = self.stats.clone();
+        self.handles.lock().push(tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(Self::STATS_INTERVAL_IN_SECS)).await;
+                stats.sample();
+            }
+        }));
+
+        // Serve the status of the prover.
+        if let Some(status_ip) = status_ip {
+            let listener = tokio::net::TcpListener::bind(status_ip).await?;
+            info!("Serving the prover status at 'http://{status_ip}/status'");
+            let prover = self.clone();
+            self.handles.lock().push(tokio::spawn(StatusServer::run(listener, Arc::new(move || prover.status()))));
+        }
+        Ok(())
+    }
+
+    /// Returns the status of the prover, as served by the status endpoint.
+    fn status(&self) -> serde_json::Value {
+        let mut status = self.stats.to_json();
+        status["paused"] = self.is_paused.load(Ordering::Relaxed).into();
+        status["peers"] = self.router.number_of_connected_peers().into();
+        status["pending_solutions"] = self.submitter.num_pending().into();
+        status["devices"] = self
+            .device_stats
+            .rates()
+            .into_iter()
+            .map(|(label, proofs, rate)| serde_json::json!({ "device": label, "proofs": proofs, "solutions_per_min": rate }))
+            .collect();
+        if let Some(pool) = &self.pool {
+            let (accepted, rejected) = pool.num_shares();
+            status["pool"] = serde_json::json!({ "server": pool.server(), "shares_accepted": accepted, "shares_rejected": rejected });
+        }
+        status
+    }
+
+    /// Initialize the retries of the solutions that no target accepted, until their epoch ends.
+    fn initialize_submission(&self) {
+        if !self.submitter.endpoints().is_empty() {
+            info!(
+                "Submitting the solutions to {} endpoint(s) ({:?})",
+                self.submitter.endpoints().len(),
+                self.submitter.mode()
+            );
+        }
+        let prover = self.clone();
+        self.handles.lock().push(tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(Self::SUBMISSION_RETRY_INTERVAL_IN_SECS)).await;
+                // Retry the pending solutions of the current epoch, and discard the stale ones.
+                let latest_epoch_hash = *prover.latest_epoch_hash.read();
+                for solution in prover.submitter.take_pending() {
+                    match Some(solution.epoch_hash()) == latest_epoch_hash {
+                        true => prover.submit_solution(solution).await,
+                        false => {
+                            debug!("Discarding the stale solution '{}'", solution.id());
+                            prover.stats.record_stale();
+                        }
+                    }
+                }
+            }
+        }));
+    }
+
     /// Initialize a new instance of the puzzle.
     async fn initialize_puzzle(&self) {
-        for _ in 0..self.max_puzzle_instances {
+        // Split the puzzle instances evenly across the devices.
+        for instance in 0..self.max_puzzle_instances as usize {
+            let device = instance % self.device_stats.num_devices();
             let prover = self.clone();
             self.handles.lock().push(tokio::spawn(async move {
-                prover.puzzle_loop().await;
+                prover.puzzle_loop(device).await;
             }));
         }
+        // Report the solution rate of each device.
+        let prover = self.clone();
+        self.handles.lock().push(tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(Self::DEVICE_REPORT_INTERVAL_IN_SECS)).await;
+                for (label, num_iterations, rate) in prover.device_stats.rates() {
+                    info!("Prover device '{label}' - {rate:.2} solutions/min ({num_iterations} proofs)");
+                }
+            }
+        }));
     }
 
-    /// Executes an instance of the puzzle.
-    async fn puzzle_loop(&self) {
+    /// Executes an instance of the puzzle on the given device.
+    async fn puzzle_loop(&self, device: usize) {
         loop {
             // If the node is not connected to any peers, then skip this iteration.
-            if self.router.number_of_connected_peers() == 0 {
+            // Note: The prover of a pool submits its shares to the pool, and a prover with submission endpoints
+            // keeps proving for the latest epoch, as it can submit its solutions to the endpoints instead of the peers.
+            let has_other_targets = self.pool.is_some() || !self.submitter.endpoints().is_empty();
+            if !has_other_targets && self.router.number_of_connected_peers() == 0 {
                 debug!("Skipping an iteration of the puzzle (no connected peers)");
                 tokio::time::sleep(Duration::from_secs(N::ANCHOR_TIME as u64)).await;
                 continue;
             }
 
+            // If the view of the chain is stale, then skip this iteration, as the solutions would be rejected.
+            if self.is_paused.load(Ordering::Relaxed) {
+                tokio::time::sleep(Duration::from_millis(Self::PAUSE_CHECK_INTERVAL_IN_MS)).await;
+                continue;
+            }
+
             // If the number of instances of the puzzle exceeds the maximum, then skip this iteration.
             if self.num_puzzle_instances() > self.max_puzzle_instances {
                 // Sleep for a brief period of time.
@@ -193,29 +502,70 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
                 continue;
             }
 
-            // Read the latest epoch hash.
-            let latest_epoch_hash = *self.latest_epoch_hash.read();
-            // Read the latest state.
-            let latest_state = self
-                .latest_block_header
-                .read()
-                .as_ref()
-                .map(|header| (header.coinbase_target(), header.proof_target()));
-
-            // If the latest epoch hash and latest state exists, then proceed to generate a solution.
-            if let (Some(epoch_hash), Some((coinbase_target, proof_target))) = (latest_epoch_hash, latest_state) {
-                // Execute the puzzle.
+            // Determine the work, which is the job assigned by the pool, or the latest state of the network.
+            let work = match &self.pool {
+                Some(pool) => pool.job().map(|job| (job.address, job.epoch_hash, job.share_target)),
+                None => {
+                    // Read the latest epoch hash.
+                    let latest_epoch_hash = *self.latest_epoch_hash.read();
+                    // Read the latest proof target.
+                    let latest_proof_target = self.latest_block_header.read().as_ref().map(Header::proof_target);
+                    latest_epoch_hash.zip(latest_proof_target).map(|(hash, target)| (self.address(), hash, target))
+                }
+            };
+
+            // If the work exists, then proceed to generate a solution.
+            if let Some((address, epoch_hash, proof_target)) = work {
+                // Execute the puzzle on the device of this instance.
                 let prover = self.clone();
+                let timer = Instant::now();
                 let result = tokio::task::spawn_blocking(move || {
-                    prover.puzzle_iteration(epoch_hash, coinbase_target, proof_target, &mut OsRng)
+                    let iteration = || prover.puzzle_iteration(address, epoch_hash, proof_target, &mut OsRng);
+                    prover.device_pools.install(device, iteration)
                 })
                 .await;
+                // Record the iteration on the device.
+                self.device_stats.record(device, matches!(result, Ok(Some(_))));
+                self.stats.record_attempt(epoch_hash, proof_target, matches!(result, Ok(Some(_))));
 
-                // If the prover found a solution, then broadcast it.
+                // If the prover found a solution, then submit it to the pool, or broadcast it.
                 if let Ok(Some((solution_target, solution))) = result {
-                    info!("Found a Solution '{}' (Proof Target {solution_target})", solution.id());
-                    // Broadcast the solution.
-                    self.broadcast_solution(solution);
+                    match &self.pool {
+                        Some(pool) => {
+                            info!("Found a Share '{}' (Proof Target {solution_target})", solution.id());
+                            // Submit the share to the pool.
+                            if let Err(error) = pool.submit(&solution).await {
+                                warn!("Failed to submit a share to the pool - {error}");
+                            }
+                        }
+                        None => {
+                            info!("Found a Solution '{}' (Proof Target {solution_target})", solution.id());
+                            // Submit the solution, unless it is stale, a duplicate, or below the latest proof target.
+                            let latest_epoch_hash = *self.latest_epoch_hash.read();
+                            let latest_proof_target =
+                                self.latest_block_header.read().as_ref().map(Header::proof_target);
+                            let check = self.filter.check(
+                                solution.id(),
+                                solution.epoch_hash(),
+                                solution_target,
+                                latest_epoch_hash,
+                                latest_proof_target,
+                            );
+                            match check {
+                                Ok(()) => self.submit_solution(solution).await,
+                                Err(reason) => {
+                                    debug!("Dropping the solution '{}', as {reason}", solution.id());
+                                    self.stats.record_filtered(reason);
+                                }
+                            }
+                        }
+                    }
+                }
+
+                // Idle for a share of the proving time, to meet the target utilization.
+                let idle_time = self.throttle.idle_time(timer.elapsed());
+                if !idle_time.is_zero() {
+                    tokio::time::sleep(idle_time).await;
                 }
             } else {
                 // Otherwise, sleep for a brief period of time, to await for puzzle state.
@@ -230,11 +580,11 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
         }
     }
 
-    /// Performs one iteration of the puzzle.
+    /// Performs one iteration of the puzzle, for the given address.
     fn puzzle_iteration<R: Rng + CryptoRng>(
         &self,
+        address: Address<N>,
         epoch_hash: N::BlockHash,
-        coinbase_target: u64,
         proof_target: u64,
         rng: &mut R,
     ) -> Option<(u64, Solution<N>)> {
@@ -244,14 +594,13 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
         debug!(
             "Proving 'Puzzle' for Epoch '{}' {}",
             fmt_id(epoch_hash),
-            format!("(Coinbase Target {coinbase_target}, Proof Target {proof_target})").dimmed()
+            format!("(Proof Target {proof_target})").dimmed()
         );
 
         // Compute the solution.
-        let result =
-            self.puzzle.prove(epoch_hash, self.address(), rng.gen(), Some(proof_target)).ok().and_then(|solution| {
-                self.puzzle.get_proof_target(&solution).ok().map(|solution_target| (solution_target, solution))
-            });
+        let result = self.puzzle.prove(epoch_hash, address, rng.gen(), Some(proof_target)).ok().and_then(|solution| {
+            self.puzzle.get_proof_target(&solution).ok().map(|solution_target| (solution_target, solution))
+        });
 
         // Decrement the puzzle instances.
         self.decrement_puzzle_instances();
@@ -259,6 +608,28 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
         result
     }
 
+    /// Submits the solution to the connected peers and the submission endpoints, according to the submit mode,
+    /// and queues it for a retry if no target accepted it.
+    async fn submit_solution(&self, solution: Solution<N>) {
+        // Broadcast the solution to the connected peers.
+        let has_peers = self.router.number_of_connected_peers() > 0;
+        if has_peers {
+            self.broadcast_solution(solution.clone());
+        }
+        // Submit the solution to the endpoints, unless the peers received it and the endpoints are a failover.
+        let num_accepted = match (self.submitter.mode(), has_peers) {
+            (SubmitMode::Failover, true) => 0,
+            _ => self.submitter.submit(&solution).await,
+        };
+        match has_peers || num_accepted > 0 {
+            true => self.stats.record_accepted(),
+            false => {
+                warn!("No submission target is reachable, retrying the solution '{}' later", solution.id());
+                self.submitter.queue(solution);
+            }
+        }
+    }
+
     /// Broadcasts the solution to the network.
     fn broadcast_solution(&self, solution: Solution<N>) {
         // Prepare the unconfirmed solution message.
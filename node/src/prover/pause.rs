@@ -0,0 +1,90 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::{fmt, time::Duration};
+
+/// The maximum number of blocks that the peers may be ahead of the latest puzzle, before the prover pauses.
+pub const MAX_PUZZLE_BLOCKS_BEHIND: u32 = 10;
+
+/// The reason the prover pauses the puzzle, as its view of the chain is stale.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PauseReason {
+    /// The prover has not received a puzzle for longer than the maximum age.
+    OutdatedPuzzle(Duration),
+    /// The peers advanced to a new epoch, so the solutions for the latest puzzle would be rejected.
+    NewEpoch,
+    /// The peers are the given number of blocks ahead of the latest puzzle.
+    BehindTip(u32),
+}
+
+impl fmt::Display for PauseReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::OutdatedPuzzle(age) => write!(f, "no puzzle was received in the last {} seconds", age.as_secs()),
+            Self::NewEpoch => write!(f, "the peers advanced to a new epoch"),
+            Self::BehindTip(num_blocks) => write!(f, "the latest puzzle is {num_blocks} blocks behind the peers"),
+        }
+    }
+}
+
+/// Returns the reason to pause the puzzle, if the latest puzzle, at the given height and of the given age,
+/// is stale with respect to the given maximum age, and the greatest block height of the peers.
+pub fn check_puzzle_staleness(
+    puzzle_height: u32,
+    puzzle_age: Duration,
+    max_puzzle_age: Duration,
+    greatest_peer_height: Option<u32>,
+    num_blocks_per_epoch: u32,
+) -> Option<PauseReason> {
+    // Ensure the puzzle was refreshed recently.
+    if puzzle_age > max_puzzle_age {
+        return Some(PauseReason::OutdatedPuzzle(puzzle_age));
+    }
+    // Ensure the peers are not ahead of the puzzle, by a new epoch, or by too many blocks.
+    let peer_height = greatest_peer_height?;
+    if peer_height / num_blocks_per_epoch.max(1) > puzzle_height / num_blocks_per_epoch.max(1) {
+        return Some(PauseReason::NewEpoch);
+    }
+    match peer_height.saturating_sub(puzzle_height) {
+        num_blocks if num_blocks > MAX_PUZZLE_BLOCKS_BEHIND => Some(PauseReason::BehindTip(num_blocks)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_AGE: Duration = Duration::from_secs(100);
+    const EPOCH: u32 = 360;
+
+    #[test]
+    fn test_check_puzzle_staleness() {
+        let fresh = Duration::from_secs(10);
+        // Ensure a fresh puzzle at the tip is not stale.
+        assert_eq!(check_puzzle_staleness(400, fresh, MAX_AGE, None, EPOCH), None);
+        assert_eq!(check_puzzle_staleness(400, fresh, MAX_AGE, Some(400), EPOCH), None);
+        assert_eq!(check_puzzle_staleness(400, fresh, MAX_AGE, Some(410), EPOCH), None);
+        // Ensure a peer behind the puzzle does not pause the prover.
+        assert_eq!(check_puzzle_staleness(400, fresh, MAX_AGE, Some(300), EPOCH), None);
+
+        // Ensure an outdated puzzle is stale.
+        let old = Duration::from_secs(101);
+        assert_eq!(check_puzzle_staleness(400, old, MAX_AGE, None, EPOCH), Some(PauseReason::OutdatedPuzzle(old)));
+        // Ensure a puzzle of a previous epoch is stale.
+        assert_eq!(check_puzzle_staleness(719, fresh, MAX_AGE, Some(720), EPOCH), Some(PauseReason::NewEpoch));
+        // Ensure a puzzle too far behind the peers is stale.
+        assert_eq!(check_puzzle_staleness(400, fresh, MAX_AGE, Some(411), EPOCH), Some(PauseReason::BehindTip(11)));
+    }
+}
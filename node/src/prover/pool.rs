@@ -0,0 +1,325 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The pool client of the prover, which proves for a pool server instead of for its own account.
+//!
+//! The protocol is Stratum-style: newline-delimited JSON messages over TCP.
+//! - The prover subscribes with `{"id": 1, "method": "subscribe", "params": ["<worker>", "<version>"]}`,
+//!   and the pool responds with `{"id": 1, "result": {"address": "aleo1..."}}`, which is the address to prove for.
+//! - The pool assigns the work with `{"method": "notify", "params": ["<epoch hash>", <share target>]}`,
+//!   whenever the epoch or the share target changes.
+//! - The prover submits each share, i.e. a solution that meets the share target, with
+//!   `{"id": <id>, "method": "submit", "params": ["<worker>", <solution>]}`,
+//!   and the pool responds with `{"id": <id>, "result": true}`, or `{"id": <id>, "error": "<reason>"}`.
+//!
+//! The pool server broadcasts the shares that meet the proof target of the network,
+//! and shares the rewards of the pool address among its workers.
+//! A client node coordinates such a pool with `--pool-server` (see `PoolCoordinator`).
+
+use snarkvm::prelude::{puzzle::Solution, Address, Network};
+
+use anyhow::{anyhow, bail, ensure, Result};
+use core::str::FromStr;
+use futures_util::StreamExt;
+use parking_lot::RwLock;
+use serde_json::{json, Value};
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::{
+    io::AsyncWriteExt,
+    net::{tcp::OwnedWriteHalf, TcpStream},
+    sync::Mutex,
+};
+use tokio_util::codec::{FramedRead, LinesCodec};
+
+/// The maximum length of a worker name.
+pub const MAX_WORKER_NAME_LENGTH: usize = 64;
+
+/// Ensures the given worker name is valid.
+pub fn check_worker_name(worker: &str) -> Result<()> {
+    ensure!(
+        !worker.is_empty() && worker.len() <= MAX_WORKER_NAME_LENGTH,
+        "The worker name must be between 1 and {MAX_WORKER_NAME_LENGTH} characters"
+    );
+    ensure!(
+        worker.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'),
+        "The worker name may only contain alphanumeric characters, '-', '_', and '.'"
+    );
+    Ok(())
+}
+
+/// The work assigned by the pool.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PoolJob<N: Network> {
+    /// The address to prove for.
+    pub address: Address<N>,
+    /// The epoch hash.
+    pub epoch_hash: N::BlockHash,
+    /// The share target, which a solution must meet to be submitted.
+    pub share_target: u64,
+}
+
+/// The pool client of the prover.
+pub struct PoolClient<N: Network> {
+    /// The address of the pool server, as `host:port`.
+    server: String,
+    /// The name of this worker.
+    worker: String,
+    /// The address to prove for, as returned by the pool on subscription.
+    address: RwLock<Option<Address<N>>>,
+    /// The latest epoch hash and share target, as assigned by the pool.
+    assignment: RwLock<Option<(N::BlockHash, u64)>>,
+    /// The writer of the connection to the pool, if connected.
+    writer: Mutex<Option<OwnedWriteHalf>>,
+    /// The ID of the next request.
+    next_id: AtomicU64,
+    /// The number of accepted shares.
+    accepted: AtomicU64,
+    /// The number of rejected shares.
+    rejected: AtomicU64,
+}
+
+impl<N: Network> PoolClient<N> {
+    /// The maximum size of a message from the pool, in bytes.
+    const MAX_MESSAGE_SIZE: usize = 64 * 1024;
+    /// The delay before reconnecting to the pool.
+    const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+    /// The ID of the subscription request.
+    const SUBSCRIBE_ID: u64 = 1;
+
+    /// Initializes a new pool client for the given pool URL (`tcp://host:port` or `host:port`) and worker name.
+    pub fn new(url: &str, worker: &str) -> Result<Self> {
+        let server = url.trim_start_matches("stratum+tcp://").trim_start_matches("tcp://").trim_end_matches('/');
+        ensure!(!server.contains("://"), "The pool URL must use 'tcp://'");
+        let Some((host, port)) = server.rsplit_once(':') else { bail!("The pool URL must include a port") };
+        ensure!(!host.is_empty() && port.parse::<u16>().is_ok(), "Invalid pool URL '{url}'");
+        check_worker_name(worker)?;
+        Ok(Self {
+            server: server.to_string(),
+            worker: worker.to_string(),
+            address: Default::default(),
+            assignment: Default::default(),
+            writer: Default::default(),
+            next_id: AtomicU64::new(Self::SUBSCRIBE_ID + 1),
+            accepted: Default::default(),
+            rejected: Default::default(),
+        })
+    }
+
+    /// Returns the address of the pool server.
+    pub fn server(&self) -> &str {
+        &self.server
+    }
+
+    /// Returns the name of this worker.
+    pub fn worker(&self) -> &str {
+        &self.worker
+    }
+
+    /// Returns the current job, if the pool has assigned one.
+    pub fn job(&self) -> Option<PoolJob<N>> {
+        let address = (*self.address.read())?;
+        let (epoch_hash, share_target) = (*self.assignment.read())?;
+        Some(PoolJob { address, epoch_hash, share_target })
+    }
+
+    /// Returns the number of accepted and rejected shares.
+    pub fn num_shares(&self) -> (u64, u64) {
+        (self.accepted.load(Ordering::Relaxed), self.rejected.load(Ordering::Relaxed))
+    }
+
+    /// Submits the given share to the pool.
+    pub async fn submit(&self, solution: &Solution<N>) -> Result<()> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.send(json!({ "id": id, "method": "submit", "params": [self.worker, solution] })).await
+    }
+
+    /// Connects to the pool, and keeps the connection alive (reconnecting as needed) until the shutdown.
+    pub async fn run(&self, shutdown: Arc<AtomicBool>) {
+        while !shutdown.load(Ordering::Relaxed) {
+            if let Err(error) = self.connect_and_serve().await {
+                warn!("Disconnected from the pool at '{}' - {error}", self.server);
+            }
+            // Clear the connection and the assignment, as the assignment may be stale by the reconnection.
+            *self.writer.lock().await = None;
+            *self.assignment.write() = None;
+            tokio::time::sleep(Self::RECONNECT_DELAY).await;
+        }
+    }
+
+    /// Connects to the pool, subscribes, and processes the messages from the pool until the connection closes.
+    async fn connect_and_serve(&self) -> Result<()> {
+        let stream = TcpStream::connect(&self.server).await?;
+        let (reader, writer) = stream.into_split();
+        *self.writer.lock().await = Some(writer);
+        // Subscribe to the pool.
+        let version = env!("CARGO_PKG_VERSION");
+        self.send(json!({ "id": Self::SUBSCRIBE_ID, "method": "subscribe", "params": [self.worker, version] })).await?;
+        info!("Connected to the pool at '{}' as worker '{}'", self.server, self.worker);
+
+        // Process the messages from the pool.
+        let mut lines = FramedRead::new(reader, LinesCodec::new_with_max_length(Self::MAX_MESSAGE_SIZE));
+        while let Some(line) = lines.next().await {
+            let line = line?;
+            if let Err(error) = self.process_message(&line) {
+                warn!("Invalid message from the pool at '{}' - {error}", self.server);
+            }
+        }
+        bail!("The pool closed the connection")
+    }
+
+    /// Processes the given message from the pool.
+    fn process_message(&self, line: &str) -> Result<()> {
+        let message: Value = serde_json::from_str(line)?;
+        // Process a notification.
+        if let Some(method) = message.get("method").and_then(Value::as_str) {
+            ensure!(method == "notify", "Unknown method '{method}'");
+            let params = message.get("params").and_then(Value::as_array).ok_or_else(|| anyhow!("Missing params"))?;
+            let (Some(epoch_hash), Some(share_target)) =
+                (params.first().and_then(Value::as_str), params.get(1).and_then(Value::as_u64))
+            else {
+                bail!("Invalid notification")
+            };
+            let epoch_hash = N::BlockHash::from_str(epoch_hash).map_err(|_| anyhow!("Invalid epoch hash"))?;
+            ensure!(share_target > 0, "Invalid share target");
+            *self.assignment.write() = Some((epoch_hash, share_target));
+            debug!("The pool assigned epoch '{epoch_hash}' with share target {share_target}");
+            return Ok(());
+        }
+        // Process a response.
+        let id = message.get("id").and_then(Value::as_u64).ok_or_else(|| anyhow!("Missing ID"))?;
+        match (id, message.get("result"), message.get("error")) {
+            (_, _, Some(error)) if !error.is_null() => {
+                ensure!(id != Self::SUBSCRIBE_ID, "The pool rejected the subscription - {error}");
+                self.rejected.fetch_add(1, Ordering::Relaxed);
+                debug!("The pool rejected a share - {error}");
+            }
+            (Self::SUBSCRIBE_ID, Some(result), _) => {
+                let address =
+                    result.get("address").and_then(Value::as_str).ok_or_else(|| anyhow!("Missing address"))?;
+                let address = Address::from_str(address)?;
+                *self.address.write() = Some(address);
+                info!("Proving for the pool address '{address}'");
+            }
+            (_, Some(Value::Bool(true)), _) => {
+                self.accepted.fetch_add(1, Ordering::Relaxed);
+            }
+            _ => bail!("Invalid response"),
+        }
+        Ok(())
+    }
+
+    /// Sends the given message to the pool.
+    async fn send(&self, message: Value) -> Result<()> {
+        let mut line = serde_json::to_vec(&message)?;
+        line.push(b'\n');
+        match self.writer.lock().await.as_mut() {
+            Some(writer) => Ok(writer.write_all(&line).await?),
+            None => bail!("Not connected to the pool at '{}'", self.server),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{MainnetV0, PrivateKey, TestRng};
+
+    use deadline::deadline;
+    use tokio::{
+        io::{AsyncBufReadExt, BufReader},
+        net::TcpListener,
+    };
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_new() {
+        let client = PoolClient::<CurrentNetwork>::new("tcp://pool.example.com:4140", "rig-1").unwrap();
+        assert_eq!(client.server(), "pool.example.com:4140");
+        assert_eq!(client.worker(), "rig-1");
+        assert!(client.job().is_none());
+        assert!(PoolClient::<CurrentNetwork>::new("pool.example.com:4140", "rig_1.a").is_ok());
+
+        // Ensure the invalid URLs and worker names are rejected.
+        assert!(PoolClient::<CurrentNetwork>::new("https://pool.example.com:4140", "rig").is_err());
+        assert!(PoolClient::<CurrentNetwork>::new("pool.example.com", "rig").is_err());
+        assert!(PoolClient::<CurrentNetwork>::new("pool.example.com:port", "rig").is_err());
+        assert!(PoolClient::<CurrentNetwork>::new("pool.example.com:4140", "").is_err());
+        assert!(PoolClient::<CurrentNetwork>::new("pool.example.com:4140", "rig 1").is_err());
+    }
+
+    #[test]
+    fn test_process_message() {
+        let rng = &mut TestRng::default();
+        let address = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        let epoch_hash = <CurrentNetwork as Network>::BlockHash::default();
+
+        let client = PoolClient::<CurrentNetwork>::new("127.0.0.1:4140", "rig").unwrap();
+        // Ensure the job requires both the subscription and the notification.
+        client
+            .process_message(&json!({ "method": "notify", "params": [epoch_hash.to_string(), 100] }).to_string())
+            .unwrap();
+        assert!(client.job().is_none());
+        client.process_message(&json!({ "id": 1, "result": { "address": address.to_string() } }).to_string()).unwrap();
+        assert_eq!(client.job(), Some(PoolJob { address, epoch_hash, share_target: 100 }));
+
+        // Ensure the share responses are counted.
+        client.process_message(&json!({ "id": 2, "result": true }).to_string()).unwrap();
+        client.process_message(&json!({ "id": 3, "error": "stale share" }).to_string()).unwrap();
+        assert_eq!(client.num_shares(), (1, 1));
+
+        // Ensure the invalid messages are rejected.
+        assert!(client.process_message("not json").is_err());
+        assert!(client.process_message(&json!({ "method": "mine", "params": [] }).to_string()).is_err());
+        assert!(client.process_message(&json!({ "method": "notify", "params": ["ab1", 100] }).to_string()).is_err());
+        assert!(client.process_message(&json!({ "id": 1, "error": "unknown worker" }).to_string()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe() {
+        let rng = &mut TestRng::default();
+        let address = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        let epoch_hash = <CurrentNetwork as Network>::BlockHash::default();
+
+        // Start a pool server, which assigns a job to the subscriber.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            let subscribe: Value = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+            assert_eq!(subscribe["method"], "subscribe");
+            assert_eq!(subscribe["params"][0], "rig");
+            let response = json!({ "id": 1, "result": { "address": address.to_string() } });
+            let notify = json!({ "method": "notify", "params": [epoch_hash.to_string(), 100] });
+            writer.write_all(format!("{response}\n{notify}\n").as_bytes()).await.unwrap();
+            // Keep the connection open.
+            let _ = lines.next_line().await;
+        });
+
+        let client = Arc::new(PoolClient::<CurrentNetwork>::new(&server.to_string(), "rig").unwrap());
+        let client_ = client.clone();
+        tokio::spawn(async move { client_.run(Default::default()).await });
+
+        // Ensure the job is assigned.
+        deadline!(Duration::from_secs(5), move || client.job().is_some());
+    }
+}
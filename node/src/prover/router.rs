@@ -14,17 +14,12 @@
 
 use super::*;
 
-use snarkos_node_router::messages::{
-    BlockRequest,
-    DisconnectReason,
-    Message,
-    MessageCodec,
-    Ping,
-    Pong,
-    PuzzleRequest,
-    UnconfirmedTransaction,
+use snarkos_node_router::{
+    message_priority,
+    messages::{BlockRequest, DisconnectReason, Message, Ping, Pong, PuzzleRequest, UnconfirmedTransaction},
+    TrafficCodec,
 };
-use snarkos_node_tcp::{Connection, ConnectionSide, Tcp};
+use snarkos_node_tcp::{protocols::MessagePriority, Connection, ConnectionSide, Tcp};
 use snarkvm::prelude::{block::Transaction, Field, Network, Zero};
 
 use std::{io, net::SocketAddr};
@@ -78,25 +73,30 @@ impl<N: Network, C: ConsensusStorage<N>> Disconnect for Prover<N, C> {
 
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> Writing for Prover<N, C> {
-    type Codec = MessageCodec<N>;
+    type Codec = TrafficCodec<N>;
     type Message = Message<N>;
 
     /// Creates an [`Encoder`] used to write the outbound messages to the target stream.
     /// The `side` parameter indicates the connection side **from the node's perspective**.
-    fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router().traffic_codec(peer_addr)
+    }
+
+    /// Returns the outbound queue priority of the given message.
+    fn priority(&self, message: &Self::Message) -> MessagePriority {
+        message_priority(message)
     }
 }
 
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> Reading for Prover<N, C> {
-    type Codec = MessageCodec<N>;
+    type Codec = TrafficCodec<N>;
     type Message = Message<N>;
 
     /// Creates a [`Decoder`] used to interpret messages from the network.
     /// The `side` param indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router().traffic_codec(peer_addr)
     }
 
     /// Processes a message received from the network.
@@ -181,13 +181,18 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Prover<N, C> {
         true
     }
 
-    /// Sleeps for a period and then sends a `Ping` message to the peer.
+    /// Records the round-trip time to the peer, then sleeps for a period and sends a `Ping` message to the peer.
     fn pong(&self, peer_ip: SocketAddr, _message: Pong) -> bool {
+        // Update the round-trip time of the peer in the sync pool.
+        if let Some(rtt) = self.router().get_latency(&peer_ip) {
+            self.sync.update_peer_latency(peer_ip, rtt);
+        }
+
         // Spawn an asynchronous task for the `Ping` request.
         let self_clone = self.clone();
         tokio::spawn(async move {
             // Sleep for the preset time before sending a `Ping` request.
-            tokio::time::sleep(Duration::from_secs(Self::PING_SLEEP_IN_SECS)).await;
+            tokio::time::sleep(self_clone.router().keep_alive().ping_interval()).await;
             // Check that the peer is still connected.
             if self_clone.router().is_connected(&peer_ip) {
                 // Send a `Ping` message to the peer.
@@ -218,6 +223,8 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Prover<N, C> {
         self.latest_epoch_hash.write().replace(epoch_hash);
         // Save the latest block header in the node.
         self.latest_block_header.write().replace(header);
+        // Save the time at which the puzzle was received.
+        self.latest_puzzle_time.write().replace(Instant::now());
 
         trace!("Received 'PuzzleResponse' from '{peer_ip}' (Block {block_height})");
         true
@@ -0,0 +1,256 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::FilterReason;
+use snarkvm::prelude::Network;
+
+use anyhow::Result;
+use core::time::Duration;
+use parking_lot::{Mutex, RwLock};
+use serde_json::{json, Value};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+/// The performance statistics of the prover.
+pub struct ProverStats<N: Network> {
+    /// The number of puzzle attempts.
+    attempts: AtomicU64,
+    /// The number of solutions found.
+    solutions_found: AtomicU64,
+    /// The number of solutions that a peer or an endpoint received.
+    solutions_accepted: AtomicU64,
+    /// The number of solutions that were discarded, as their epoch ended before they were submitted.
+    solutions_stale: AtomicU64,
+    /// The number of solutions that were dropped before they were broadcast, as they were already broadcast.
+    solutions_duplicate: AtomicU64,
+    /// The number of solutions that were dropped before they were broadcast, as they were below the proof target.
+    solutions_below_target: AtomicU64,
+    /// The latest epoch hash and proof target that the prover worked on.
+    work: RwLock<Option<(N::BlockHash, u64)>>,
+    /// The number of attempts and the time at the last sample.
+    last_sample: Mutex<(u64, Instant)>,
+    /// The number of attempts per second, between the last two samples.
+    attempts_per_sec: RwLock<f64>,
+}
+
+impl<N: Network> Default for ProverStats<N> {
+    fn default() -> Self {
+        Self {
+            attempts: Default::default(),
+            solutions_found: Default::default(),
+            solutions_accepted: Default::default(),
+            solutions_stale: Default::default(),
+            solutions_duplicate: Default::default(),
+            solutions_below_target: Default::default(),
+            work: Default::default(),
+            last_sample: Mutex::new((0, Instant::now())),
+            attempts_per_sec: Default::default(),
+        }
+    }
+}
+
+impl<N: Network> ProverStats<N> {
+    /// Records a puzzle attempt for the given work, and whether it found a solution.
+    pub fn record_attempt(&self, epoch_hash: N::BlockHash, proof_target: u64, found_solution: bool) {
+        self.attempts.fetch_add(1, Ordering::Relaxed);
+        *self.work.write() = Some((epoch_hash, proof_target));
+        if found_solution {
+            self.solutions_found.fetch_add(1, Ordering::Relaxed);
+        }
+
+        #[cfg(feature = "metrics")]
+        {
+            metrics::increment_counter(metrics::prover::ATTEMPTS);
+            metrics::gauge(metrics::prover::PROOF_TARGET, proof_target as f64);
+            if found_solution {
+                metrics::increment_counter(metrics::prover::SOLUTIONS_FOUND);
+            }
+        }
+    }
+
+    /// Records a solution that a peer or an endpoint received.
+    pub fn record_accepted(&self) {
+        self.solutions_accepted.fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "metrics")]
+        metrics::increment_counter(metrics::prover::SOLUTIONS_ACCEPTED);
+    }
+
+    /// Records a solution that was discarded, as its epoch ended before it was submitted.
+    pub fn record_stale(&self) {
+        self.solutions_stale.fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "metrics")]
+        metrics::increment_counter(metrics::prover::SOLUTIONS_STALE);
+    }
+
+    /// Records a solution that was dropped before it was broadcast, for the given reason.
+    pub fn record_filtered(&self, reason: FilterReason) {
+        match reason {
+            FilterReason::Stale => self.record_stale(),
+            FilterReason::Duplicate => {
+                self.solutions_duplicate.fetch_add(1, Ordering::Relaxed);
+                #[cfg(feature = "metrics")]
+                metrics::increment_counter(metrics::prover::SOLUTIONS_DUPLICATE);
+            }
+            FilterReason::BelowTarget => {
+                self.solutions_below_target.fetch_add(1, Ordering::Relaxed);
+                #[cfg(feature = "metrics")]
+                metrics::increment_counter(metrics::prover::SOLUTIONS_BELOW_TARGET);
+            }
+        }
+    }
+
+    /// Samples the number of attempts per second since the last sample, and returns it.
+    pub fn sample(&self) -> f64 {
+        let attempts = self.attempts.load(Ordering::Relaxed);
+        let mut last_sample = self.last_sample.lock();
+        let seconds = last_sample.1.elapsed().as_secs_f64().max(f64::EPSILON);
+        let attempts_per_sec = attempts.saturating_sub(last_sample.0) as f64 / seconds;
+        *last_sample = (attempts, Instant::now());
+        *self.attempts_per_sec.write() = attempts_per_sec;
+
+        #[cfg(feature = "metrics")]
+        metrics::gauge(metrics::prover::ATTEMPTS_PER_SEC, attempts_per_sec);
+        attempts_per_sec
+    }
+
+    /// Returns the statistics as a JSON object.
+    pub fn to_json(&self) -> Value {
+        let work = *self.work.read();
+        json!({
+            "attempts": self.attempts.load(Ordering::Relaxed),
+            "attempts_per_sec": *self.attempts_per_sec.read(),
+            "solutions_found": self.solutions_found.load(Ordering::Relaxed),
+            "solutions_accepted": self.solutions_accepted.load(Ordering::Relaxed),
+            "solutions_stale": self.solutions_stale.load(Ordering::Relaxed),
+            "solutions_duplicate": self.solutions_duplicate.load(Ordering::Relaxed),
+            "solutions_below_target": self.solutions_below_target.load(Ordering::Relaxed),
+            "epoch_hash": work.map(|(epoch_hash, _)| epoch_hash.to_string()),
+            "proof_target": work.map(|(_, proof_target)| proof_target),
+        })
+    }
+}
+
+/// The local status endpoint of the prover, which serves its statistics as JSON on `GET /status`.
+pub struct StatusServer;
+
+impl StatusServer {
+    /// The maximum size of a status request, in bytes.
+    const MAX_REQUEST_SIZE: usize = 4096;
+    /// The time to wait for a status request.
+    const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Serves the status returned by the given function, until the listener fails.
+    pub async fn run(listener: TcpListener, status: Arc<dyn Fn() -> Value + Send + Sync>) {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let status = status.clone();
+                    tokio::spawn(async move {
+                        if let Err(error) = Self::respond(stream, status).await {
+                            debug!("Failed to respond to a status request - {error}");
+                        }
+                    });
+                }
+                Err(error) => {
+                    error!("The prover status listener failed - {error}");
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Responds to the request on the given stream.
+    async fn respond(mut stream: TcpStream, status: Arc<dyn Fn() -> Value + Send + Sync>) -> Result<()> {
+        let mut buffer = vec![0u8; Self::MAX_REQUEST_SIZE];
+        let num_bytes = tokio::time::timeout(Self::REQUEST_TIMEOUT, stream.read(&mut buffer)).await??;
+        let request = String::from_utf8_lossy(&buffer[..num_bytes]);
+        // Only the request line matters, i.e. `GET /status HTTP/1.1`.
+        let target = request.lines().next().map(|line| line.split_whitespace().take(2).collect::<Vec<_>>());
+        let (code, body) = match target.as_deref() {
+            Some(["GET", "/status"]) => ("200 OK", status().to_string()),
+            _ => ("404 Not Found", json!({ "error": "Not found" }).to_string()),
+        };
+        let response = format!(
+            "HTTP/1.1 {code}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(response.as_bytes()).await?;
+        Ok(stream.shutdown().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::MainnetV0;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_prover_stats() {
+        let stats = ProverStats::<CurrentNetwork>::default();
+        assert_eq!(stats.to_json()["epoch_hash"], Value::Null);
+
+        let epoch_hash = <CurrentNetwork as Network>::BlockHash::default();
+        stats.record_attempt(epoch_hash, 100, false);
+        stats.record_attempt(epoch_hash, 100, true);
+        stats.record_accepted();
+        stats.record_stale();
+        stats.record_filtered(FilterReason::Stale);
+        stats.record_filtered(FilterReason::Duplicate);
+        assert!(stats.sample() > 0.0);
+
+        let status = stats.to_json();
+        assert_eq!(status["attempts"], 2);
+        assert_eq!(status["solutions_found"], 1);
+        assert_eq!(status["solutions_accepted"], 1);
+        assert_eq!(status["solutions_stale"], 2);
+        assert_eq!(status["solutions_duplicate"], 1);
+        assert_eq!(status["solutions_below_target"], 0);
+        assert_eq!(status["epoch_hash"], epoch_hash.to_string());
+        assert_eq!(status["proof_target"], 100);
+
+        // Ensure the rate only accounts for the attempts since the last sample.
+        assert_eq!(stats.sample(), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_status_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(StatusServer::run(listener, Arc::new(|| json!({ "attempts": 7 }))));
+
+        let request = |request: &'static str| async move {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            stream.write_all(request.as_bytes()).await.unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            response
+        };
+        let response = request("GET /status HTTP/1.1\r\nHost: localhost\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with(r#"{"attempts":7}"#));
+        let response = request("GET /metrics HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 404 Not Found"));
+    }
+}
@@ -0,0 +1,246 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::light::network_name;
+use snarkvm::prelude::{puzzle::Solution, Network};
+
+use anyhow::{bail, ensure, Result};
+use futures_util::future::join_all;
+use parking_lot::Mutex;
+use reqwest::Client;
+use std::{
+    collections::VecDeque,
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+/// The way the prover submits its solutions to the REST endpoints.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum SubmitMode {
+    /// The solutions are broadcast to the connected peers, or submitted to the first reachable endpoint
+    /// if no peer is connected, starting from the endpoint that last accepted a solution.
+    #[default]
+    Failover,
+    /// The solutions are broadcast to the connected peers, and submitted to every endpoint.
+    All,
+}
+
+impl FromStr for SubmitMode {
+    type Err = anyhow::Error;
+
+    fn from_str(mode: &str) -> Result<Self> {
+        match mode {
+            "failover" => Ok(Self::Failover),
+            "all" => Ok(Self::All),
+            _ => bail!("Invalid submit mode '{mode}' (expected 'failover' or 'all')"),
+        }
+    }
+}
+
+/// The solution submitter of the prover, which submits the solutions to the REST endpoints of nodes,
+/// as a fallback for, or in addition to, the connected peers.
+pub struct SolutionSubmitter<N: Network> {
+    /// The base URLs of the REST endpoints.
+    endpoints: Vec<String>,
+    /// The submit mode.
+    mode: SubmitMode,
+    /// The HTTP client.
+    client: Client,
+    /// The index of the endpoint that last accepted a solution.
+    primary: AtomicUsize,
+    /// The solutions that no target accepted, which are retried until their epoch ends.
+    pending: Mutex<VecDeque<Solution<N>>>,
+}
+
+impl<N: Network> Default for SolutionSubmitter<N> {
+    fn default() -> Self {
+        Self {
+            endpoints: Vec::new(),
+            mode: SubmitMode::default(),
+            client: Client::new(),
+            primary: Default::default(),
+            pending: Default::default(),
+        }
+    }
+}
+
+impl<N: Network> SolutionSubmitter<N> {
+    /// The maximum number of solutions pending a retry.
+    const MAX_PENDING_SOLUTIONS: usize = 64;
+    /// The timeout of a submission to an endpoint.
+    const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Initializes a new solution submitter, for the given REST endpoints.
+    pub fn new(endpoints: &[String], mode: SubmitMode) -> Result<Self> {
+        let mut urls: Vec<String> = Vec::with_capacity(endpoints.len());
+        for endpoint in endpoints {
+            ensure!(
+                endpoint.starts_with("http://") || endpoint.starts_with("https://"),
+                "The submit endpoint '{endpoint}' must be an HTTP(S) URL"
+            );
+            let url = endpoint.trim_end_matches('/').to_string();
+            ensure!(!urls.contains(&url), "Duplicate submit endpoint '{endpoint}'");
+            urls.push(url);
+        }
+        let client = Client::builder().timeout(Self::REQUEST_TIMEOUT).build()?;
+        Ok(Self { endpoints: urls, mode, client, ..Default::default() })
+    }
+
+    /// Returns the base URLs of the REST endpoints.
+    pub fn endpoints(&self) -> &[String] {
+        &self.endpoints
+    }
+
+    /// Returns the submit mode.
+    pub const fn mode(&self) -> SubmitMode {
+        self.mode
+    }
+
+    /// Returns the number of solutions pending a retry.
+    pub fn num_pending(&self) -> usize {
+        self.pending.lock().len()
+    }
+
+    /// Queues the given solution for a retry, evicting the oldest solution if the queue is full.
+    pub fn queue(&self, solution: Solution<N>) {
+        let mut pending = self.pending.lock();
+        if pending.len() >= Self::MAX_PENDING_SOLUTIONS {
+            pending.pop_front();
+        }
+        pending.push_back(solution);
+    }
+
+    /// Removes and returns the solutions pending a retry.
+    pub fn take_pending(&self) -> Vec<Solution<N>> {
+        self.pending.lock().drain(..).collect()
+    }
+
+    /// Returns the indices of the endpoints, in the order they are tried, starting from the primary.
+    fn endpoint_order(&self) -> impl Iterator<Item = usize> {
+        let (start, num_endpoints) = (self.primary.load(Ordering::Relaxed), self.endpoints.len());
+        (0..num_endpoints).map(move |i| (start + i) % num_endpoints)
+    }
+
+    /// Submits the given solution to the endpoints, according to the submit mode,
+    /// and returns the number of endpoints that accepted it.
+    pub async fn submit(&self, solution: &Solution<N>) -> usize {
+        match self.mode {
+            SubmitMode::Failover => {
+                for index in self.endpoint_order() {
+                    match self.post(&self.endpoints[index], solution).await {
+                        Ok(()) => {
+                            // Start from this endpoint next time.
+                            self.primary.store(index, Ordering::Relaxed);
+                            return 1;
+                        }
+                        Err(error) => warn!("Failed to submit a solution to '{}' - {error}", self.endpoints[index]),
+                    }
+                }
+                0
+            }
+            SubmitMode::All => {
+                let results = join_all(self.endpoints.iter().map(|endpoint| self.post(endpoint, solution))).await;
+                results
+                    .into_iter()
+                    .zip(&self.endpoints)
+                    .filter(|(result, endpoint)| match result {
+                        Ok(()) => true,
+                        Err(error) => {
+                            warn!("Failed to submit a solution to '{endpoint}' - {error}");
+                            false
+                        }
+                    })
+                    .count()
+            }
+        }
+    }
+
+    /// Posts the given solution to the given endpoint.
+    async fn post(&self, endpoint: &str, solution: &Solution<N>) -> Result<()> {
+        let url = format!("{endpoint}/{}/solution/broadcast", network_name::<N>()?);
+        let response = self.client.post(&url).json(solution).send().await?;
+        ensure!(response.status().is_success(), "{}", response.text().await.unwrap_or_default());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{puzzle::PartialSolution, Address, MainnetV0, PrivateKey, TestRng};
+
+    type CurrentNetwork = MainnetV0;
+
+    fn sample_solution(rng: &mut TestRng, counter: u64) -> Solution<CurrentNetwork> {
+        let address = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        let epoch_hash = <CurrentNetwork as Network>::BlockHash::default();
+        Solution::new(PartialSolution::new(epoch_hash, address, counter).unwrap(), 1)
+    }
+
+    #[test]
+    fn test_new() {
+        let endpoints = ["http://127.0.0.1:3030/".to_string(), "https://api.example.com".to_string()];
+        let submitter = SolutionSubmitter::<CurrentNetwork>::new(&endpoints, SubmitMode::All).unwrap();
+        assert_eq!(submitter.endpoints(), ["http://127.0.0.1:3030", "https://api.example.com"]);
+        assert_eq!(submitter.mode(), SubmitMode::All);
+
+        // Ensure the invalid endpoints are rejected.
+        let endpoints = ["127.0.0.1:3030".to_string()];
+        assert!(SolutionSubmitter::<CurrentNetwork>::new(&endpoints, SubmitMode::Failover).is_err());
+        let endpoints = ["http://127.0.0.1:3030".to_string(), "http://127.0.0.1:3030/".to_string()];
+        assert!(SolutionSubmitter::<CurrentNetwork>::new(&endpoints, SubmitMode::Failover).is_err());
+
+        // Ensure the submit modes are parsed.
+        assert_eq!(SubmitMode::from_str("failover").unwrap(), SubmitMode::Failover);
+        assert_eq!(SubmitMode::from_str("all").unwrap(), SubmitMode::All);
+        assert!(SubmitMode::from_str("first").is_err());
+    }
+
+    #[test]
+    fn test_endpoint_order() {
+        let endpoints = ["http://a:3030".to_string(), "http://b:3030".to_string(), "http://c:3030".to_string()];
+        let submitter = SolutionSubmitter::<CurrentNetwork>::new(&endpoints, SubmitMode::Failover).unwrap();
+        assert_eq!(submitter.endpoint_order().collect::<Vec<_>>(), vec![0, 1, 2]);
+        // Ensure the endpoint that last accepted a solution is tried first.
+        submitter.primary.store(2, Ordering::Relaxed);
+        assert_eq!(submitter.endpoint_order().collect::<Vec<_>>(), vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn test_queue() {
+        let rng = &mut TestRng::default();
+        let submitter = SolutionSubmitter::<CurrentNetwork>::default();
+        for counter in 0..SolutionSubmitter::<CurrentNetwork>::MAX_PENDING_SOLUTIONS as u64 + 1 {
+            submitter.queue(sample_solution(rng, counter));
+        }
+        // Ensure the oldest solution is evicted.
+        let pending = submitter.take_pending();
+        assert_eq!(pending.len(), SolutionSubmitter::<CurrentNetwork>::MAX_PENDING_SOLUTIONS);
+        assert_eq!(pending[0].partial_solution().counter(), 1);
+        assert_eq!(submitter.num_pending(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_submit_unreachable() {
+        let rng = &mut TestRng::default();
+        let endpoints = ["http://127.0.0.1:1".to_string(), "http://127.0.0.1:2".to_string()];
+        for mode in [SubmitMode::Failover, SubmitMode::All] {
+            let submitter = SolutionSubmitter::<CurrentNetwork>::new(&endpoints, mode).unwrap();
+            assert_eq!(submitter.submit(&sample_solution(rng, 0)).await, 0);
+        }
+        // Ensure a submitter without endpoints accepts nothing.
+        assert_eq!(SolutionSubmitter::<CurrentNetwork>::default().submit(&sample_solution(rng, 0)).await, 0);
+    }
+}
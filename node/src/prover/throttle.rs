@@ -0,0 +1,106 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{ensure, Result};
+use core::time::Duration;
+
+/// The limits on the resources that the prover uses, so that it can share a machine with other work.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ProverThrottle {
+    /// The maximum number of threads of the proving backend, if capped.
+    threads: Option<usize>,
+    /// The target percentage of the time that each puzzle instance spends proving.
+    utilization: u8,
+}
+
+impl Default for ProverThrottle {
+    fn default() -> Self {
+        Self { threads: None, utilization: Self::MAX_UTILIZATION }
+    }
+}
+
+impl ProverThrottle {
+    /// The maximum utilization, at which the puzzle instances never idle.
+    pub const MAX_UTILIZATION: u8 = 100;
+
+    /// Initializes a new throttle, ensuring the number of threads and the utilization are within bounds.
+    pub fn new(threads: Option<usize>, utilization: Option<u8>) -> Result<Self> {
+        ensure!(threads != Some(0), "The number of prover threads must be at least 1");
+        let utilization = utilization.unwrap_or(Self::MAX_UTILIZATION);
+        ensure!(
+            (1..=Self::MAX_UTILIZATION).contains(&utilization),
+            "The prover utilization must be between 1 and {} percent",
+            Self::MAX_UTILIZATION
+        );
+        Ok(Self { threads, utilization })
+    }
+
+    /// Returns the maximum number of threads of the proving backend, if capped.
+    pub const fn threads(&self) -> Option<usize> {
+        self.threads
+    }
+
+    /// Returns the target percentage of the time that each puzzle instance spends proving.
+    pub const fn utilization(&self) -> u8 {
+        self.utilization
+    }
+
+    /// Returns the number of puzzle instances, given the number for the devices, which is capped by the threads.
+    pub fn num_puzzle_instances(&self, device_instances: usize) -> usize {
+        match self.threads {
+            Some(threads) => device_instances.min(threads),
+            None => device_instances,
+        }
+    }
+
+    /// Returns the time that a puzzle instance idles after proving for the given time, to meet the utilization.
+    pub fn idle_time(&self, busy_time: Duration) -> Duration {
+        let idle_percentage = u32::from(Self::MAX_UTILIZATION - self.utilization);
+        busy_time * idle_percentage / u32::from(self.utilization)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let throttle = ProverThrottle::new(None, None).unwrap();
+        assert_eq!(throttle, ProverThrottle::default());
+        assert_eq!(throttle.num_puzzle_instances(6), 6);
+
+        let throttle = ProverThrottle::new(Some(4), Some(50)).unwrap();
+        assert_eq!(throttle.threads(), Some(4));
+        assert_eq!(throttle.utilization(), 50);
+        assert_eq!(throttle.num_puzzle_instances(6), 4);
+        assert_eq!(throttle.num_puzzle_instances(2), 2);
+
+        // Ensure the invalid limits are rejected.
+        assert!(ProverThrottle::new(Some(0), None).is_err());
+        assert!(ProverThrottle::new(None, Some(0)).is_err());
+        assert!(ProverThrottle::new(None, Some(101)).is_err());
+    }
+
+    #[test]
+    fn test_idle_time() {
+        let busy_time = Duration::from_secs(3);
+        // Ensure the puzzle instances never idle at full utilization.
+        assert_eq!(ProverThrottle::default().idle_time(busy_time), Duration::ZERO);
+        // Ensure the idle time meets the utilization.
+        assert_eq!(ProverThrottle::new(None, Some(75)).unwrap().idle_time(busy_time), Duration::from_secs(1));
+        assert_eq!(ProverThrottle::new(None, Some(50)).unwrap().idle_time(busy_time), busy_time);
+        assert_eq!(ProverThrottle::new(None, Some(25)).unwrap().idle_time(busy_time), Duration::from_secs(9));
+    }
+}
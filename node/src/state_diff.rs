@@ -0,0 +1,202 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::{block::Block, store::ConsensusStorage, ConfirmedTransaction, Ledger},
+    prelude::{FinalizeOperation, Network},
+};
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::{
+    fs,
+    io::{BufWriter, ErrorKind, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Returns the state diff of the given block, as JSON.
+///
+/// The diff lists the finalize operations of every confirmed transaction, which identify the mapping entries
+/// by their IDs, along with the created record commitments, the spent serial numbers, and the deployed programs.
+/// Note: The state changes of the ratifications (e.g. the staking rewards) are not covered, as blocks do not
+/// record them.
+pub fn state_diff<N: Network>(block: &Block<N>) -> Value {
+    let aborted_transaction_ids = block.aborted_transaction_ids().iter().map(|id| id.to_string()).collect::<Vec<_>>();
+    let transactions = block.transactions().iter().map(transaction_diff).collect::<Vec<_>>();
+    json!({
+        "height": block.height(),
+        "block_hash": block.hash().to_string(),
+        "previous_hash": block.previous_hash().to_string(),
+        "timestamp": block.timestamp(),
+        "transactions": transactions,
+        "aborted_transaction_ids": aborted_transaction_ids,
+    })
+}
+
+/// Returns the state diff of the given confirmed transaction, as JSON.
+fn transaction_diff<N: Network>(confirmed: &ConfirmedTransaction<N>) -> Value {
+    // Note: For a rejected transaction, only its fee transaction is included in the block.
+    let transaction = confirmed.transaction();
+    let kind = match confirmed {
+        ConfirmedTransaction::AcceptedDeploy(..) | ConfirmedTransaction::RejectedDeploy(..) => "deploy",
+        ConfirmedTransaction::AcceptedExecute(..) | ConfirmedTransaction::RejectedExecute(..) => "execute",
+    };
+    let id = confirmed.to_unconfirmed_transaction_id().map(|id| id.to_string()).ok();
+    // Note: A rejected deployment does not deploy its program.
+    let deployed_program = match confirmed.is_accepted() {
+        true => transaction.deployment().map(|deployment| deployment.program_id().to_string()),
+        false => None,
+    };
+    json!({
+        "id": id,
+        "type": kind,
+        "status": if confirmed.is_accepted() { "accepted" } else { "rejected" },
+        "index": confirmed.index(),
+        "deployed_program": deployed_program,
+        "operations": confirmed.finalize_operations().iter().map(finalize_operation).collect::<Vec<_>>(),
+        "commitments": transaction.commitments().map(|commitment| commitment.to_string()).collect::<Vec<_>>(),
+        "serial_numbers": transaction.serial_numbers().map(|serial| serial.to_string()).collect::<Vec<_>>(),
+    })
+}
+
+/// Returns the given finalize operation, as JSON.
+fn finalize_operation<N: Network>(operation: &FinalizeOperation<N>) -> Value {
+    match operation {
+        FinalizeOperation::InitializeMapping(mapping_id) => {
+            json!({ "type": "initialize_mapping", "mapping_id": mapping_id.to_string() })
+        }
+        FinalizeOperation::InsertKeyValue(mapping_id, key_id, value_id) => json!({
+            "type": "insert_key_value",
+            "mapping_id": mapping_id.to_string(),
+            "key_id": key_id.to_string(),
+            "value_id": value_id.to_string(),
+        }),
+        FinalizeOperation::UpdateKeyValue(mapping_id, index, key_id, value_id) => json!({
+            "type": "update_key_value",
+            "mapping_id": mapping_id.to_string(),
+            "index": index,
+            "key_id": key_id.to_string(),
+            "value_id": value_id.to_string(),
+        }),
+        FinalizeOperation::RemoveKeyValue(mapping_id, index) => {
+            json!({ "type": "remove_key_value", "mapping_id": mapping_id.to_string(), "index": index })
+        }
+        FinalizeOperation::ReplaceMapping(mapping_id) => {
+            json!({ "type": "replace_mapping", "mapping_id": mapping_id.to_string() })
+        }
+        FinalizeOperation::RemoveMapping(mapping_id) => {
+            json!({ "type": "remove_mapping", "mapping_id": mapping_id.to_string() })
+        }
+    }
+}
+
+/// Appends the state diff of every advanced block to a file, with one JSON object per line.
+///
+/// The height of the last exported block is kept in a sibling file, so that the export resumes after a restart.
+/// A block may be exported twice if the node stops between the two writes, so consumers should treat the height
+/// as an idempotency key.
+pub struct StateDiffExporter<N: Network, C: ConsensusStorage<N>> {
+    /// The ledger.
+    ledger: Ledger<N, C>,
+    /// The path of the state diffs.
+    path: PathBuf,
+    /// The path of the height of the last exported block.
+    height_path: PathBuf,
+}
+
+impl<N: Network, C: ConsensusStorage<N>> StateDiffExporter<N, C> {
+    /// The interval at which the exporter checks for new blocks.
+    const CHECK_INTERVAL_IN_SECS: u64 = 1;
+    /// The maximum number of blocks exported before the progress is saved.
+    const MAX_BLOCKS_PER_BATCH: u32 = 1000;
+
+    /// Initializes a new exporter to the given path.
+    pub fn new(ledger: Ledger<N, C>, path: &Path) -> Self {
+        let mut height_path = path.as_os_str().to_owned();
+        height_path.push(".height");
+        Self { ledger, path: path.to_path_buf(), height_path: height_path.into() }
+    }
+
+    /// Returns the height of the next block to export.
+    fn next_height(&self) -> Result<u32> {
+        match fs::read_to_string(&self.height_path) {
+            Ok(height) => Ok(height.trim().parse::<u32>().context("Malformed state diff height")? + 1),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(0),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Exports the state diffs of the blocks the ledger advanced by, in batches.
+    /// Returns the number of exported blocks.
+    pub fn catch_up(&self) -> Result<u32> {
+        let start_height = self.next_height()?;
+        let end_height = self.ledger.latest_height().min(start_height.saturating_add(Self::MAX_BLOCKS_PER_BATCH - 1));
+        if start_height > end_height {
+            return Ok(0);
+        }
+        // Append the state diffs of the batch.
+        let file = fs::OpenOptions::new().create(true).append(true).open(&self.path)?;
+        let mut writer = BufWriter::new(file);
+        for height in start_height..=end_height {
+            let block = self.ledger.get_block(height)?;
+            serde_json::to_writer(&mut writer, &state_diff(&block))?;
+            writer.write_all(b"\n")?;
+        }
+        writer.into_inner().map_err(|error| error.into_error())?.sync_data()?;
+        // Save the progress.
+        fs::write(&self.height_path, end_height.to_string())?;
+        Ok(end_height - start_height + 1)
+    }
+
+    /// Exports the state diffs of the new blocks, until the task is aborted.
+    pub(crate) async fn run(self) {
+        let exporter = std::sync::Arc::new(self);
+        loop {
+            let exporter_ = exporter.clone();
+            match tokio::task::spawn_blocking(move || exporter_.catch_up()).await {
+                // If the exporter is behind, continue right away.
+                Ok(Ok(num_blocks)) if num_blocks == Self::MAX_BLOCKS_PER_BATCH => continue,
+                Ok(Ok(_)) => (),
+                Ok(Err(error)) => warn!("Failed to export the state diffs - {error}"),
+                Err(error) => warn!("[tokio::spawn_blocking] {error}"),
+            }
+            tokio::time::sleep(Duration::from_secs(Self::CHECK_INTERVAL_IN_SECS)).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{FromBytes, MainnetV0};
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_state_diff() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let diff = state_diff(&genesis);
+
+        assert_eq!(diff["height"], 0);
+        assert_eq!(diff["block_hash"], genesis.hash().to_string());
+        // Ensure every transaction is listed with its finalize operations.
+        let transactions = diff["transactions"].as_array().unwrap();
+        assert_eq!(transactions.len(), genesis.transactions().len());
+        for (transaction, confirmed) in transactions.iter().zip(genesis.transactions().iter()) {
+            assert_eq!(transaction["status"], "accepted");
+            assert_eq!(transaction["operations"].as_array().unwrap().len(), confirmed.finalize_operations().len());
+        }
+    }
+}
@@ -13,9 +13,11 @@
 // limitations under the License.
 
 use snarkos_node_router::{messages::NodeType, Routing};
+use snarkos_node_tcp::{AcceptRateLimit, P2P};
 use snarkvm::prelude::{Address, Network, PrivateKey, ViewKey};
 
-use once_cell::sync::OnceCell;
+use anyhow::{bail, Result};
+use once_cell::sync::{Lazy, OnceCell};
 use std::{
     future::Future,
     io,
@@ -25,6 +27,23 @@ use std::{
     },
     time::Duration,
 };
+use tokio::sync::watch;
+
+/// The generation of the signal handlers, which is bumped whenever a node is started in the process,
+/// so that a node started again in another role supersedes the signal handler of its previous role.
+static SIGNAL_HANDLER_GENERATION: Lazy<watch::Sender<usize>> = Lazy::new(|| watch::channel(0).0);
+
+/// The hook that runs once the node has shut down gracefully, right before the process exits.
+static EXIT_HOOK: OnceCell<Box<dyn Fn() + Send + Sync>> = OnceCell::new();
+
+/// Registers the hook that runs once the node has shut down gracefully, right before the process exits,
+/// i.e. to flush the exported traces.
+pub fn set_exit_hook(hook: impl Fn() + Send + Sync + 'static) -> Result<()> {
+    if EXIT_HOOK.set(Box::new(hook)).is_err() {
+        bail!("The exit hook is already registered");
+    }
+    Ok(())
+}
 
 #[async_trait]
 pub trait NodeInterface<N: Network>: Routing<N> {
@@ -53,6 +72,16 @@ pub trait NodeInterface<N: Network>: Routing<N> {
         self.router().is_dev()
     }
 
+    /// Enables the discovery of peers on the local network.
+    fn enable_local_discovery(&self) -> Result<()> {
+        self.router().enable_local_discovery()
+    }
+
+    /// Limits the rate of inbound connection attempts to the node.
+    fn set_accept_rate_limit(&self, limit: AcceptRateLimit) {
+        self.router().tcp().accept_limiter().set_limit(Some(limit))
+    }
+
     /// Handles OS signals for the node to intercept and perform a clean shutdown.
     /// The optional `shutdown_flag` flag can be used to cleanly terminate the syncing process.
     fn handle_signals(shutdown_flag: Arc<AtomicBool>) -> Arc<OnceCell<Self>> {
@@ -86,9 +115,18 @@ pub trait NodeInterface<N: Network>: Routing<N> {
             tokio::signal::ctrl_c()
         }
 
+        // Supersede the signal handler of the previous node in the process, if any.
+        SIGNAL_HANDLER_GENERATION.send_modify(|generation| *generation += 1);
+        let mut superseded = SIGNAL_HANDLER_GENERATION.subscribe();
+
         let node_clone = node.clone();
         tokio::task::spawn(async move {
-            match signal_listener().await {
+            let result = tokio::select! {
+                result = signal_listener() => result,
+                // If the node is superseded, release it, as the new node handles the signals instead.
+                _ = superseded.changed() => return,
+            };
+            match result {
                 Ok(()) => {
                     warn!("==========================================================================================");
                     warn!("⚠️  Attention - Starting the graceful shutdown procedure (ETA: 30 seconds)...");
@@ -106,6 +144,11 @@ pub trait NodeInterface<N: Network>: Routing<N> {
                     // A best-effort attempt to let any ongoing activity conclude.
                     tokio::time::sleep(Duration::from_secs(3)).await;
 
+                    // Run the exit hook, if any, as it may block.
+                    if let Some(hook) = EXIT_HOOK.get() {
+                        let _ = tokio::task::spawn_blocking(hook).await;
+                    }
+
                     // Terminate the process.
                     std::process::exit(0);
                 }
@@ -14,18 +14,38 @@
 
 mod router;
 
-use crate::traits::NodeInterface;
+use crate::{traits::NodeInterface, HealthSnapshot, MembershipWatcher, RoleTransitions};
 use snarkos_account::Account;
-use snarkos_node_bft::{helpers::init_primary_channels, ledger_service::CoreLedgerService, spawn_blocking};
-use snarkos_node_consensus::Consensus;
-use snarkos_node_rest::Rest;
+use snarkos_node_bft::{
+    helpers::{
+        init_primary_channels,
+        AuditLog,
+        Failover,
+        OperatorLane,
+        ParticipationConfig,
+        ParticipationStatus,
+        ProposalConfig,
+        RemoteSigner,
+        Signer,
+        SigningJournal,
+        TimeoutConfig,
+    },
+    ledger_service::CoreLedgerService,
+    spawn_blocking,
+};
+use snarkos_node_consensus::{AdmissionPolicy, Consensus};
+use snarkos_node_rest::{Rest, StateDigests};
 use snarkos_node_router::{
     messages::{NodeType, PuzzleResponse, UnconfirmedSolution, UnconfirmedTransaction},
     Heartbeat,
     Inbound,
+    KeepAlive,
+    ListenAddr,
     Outbound,
+    RelayMode,
     Router,
     Routing,
+    SolutionRateLimit,
 };
 use snarkos_node_sync::{BlockSync, BlockSyncMode};
 use snarkos_node_tcp::{
@@ -46,6 +66,7 @@ use core::future::Future;
 use parking_lot::Mutex;
 use std::{
     net::SocketAddr,
+    path::PathBuf,
     sync::{atomic::AtomicBool, Arc},
     time::Duration,
 };
@@ -64,6 +85,12 @@ pub struct Validator<N: Network, C: ConsensusStorage<N>> {
     rest: Option<Rest<N, C, Self>>,
     /// The sync module.
     sync: BlockSync<N>,
+    /// The state digests, which are computed as the ledger advances, once they are enabled.
+    state_digests: Arc<StateDigests<N>>,
+    /// The path where the memory pool is stored on shutdown, if enabled.
+    mempool_path: Option<PathBuf>,
+    /// The transitions to the client role, once they are enabled.
+    role_transitions: Arc<RoleTransitions>,
     /// The spawned handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
     /// The shutdown signal.
@@ -74,12 +101,29 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
     /// Initializes a new validator node.
     pub async fn new(
         node_ip: SocketAddr,
+        listen_addrs: &[ListenAddr],
         bft_ip: Option<SocketAddr>,
         rest_ip: Option<SocketAddr>,
         rest_rps: u32,
+        rest_cache_size: usize,
         account: Account<N>,
+        remote_signer: Option<RemoteSigner<N>>,
         trusted_peers: &[SocketAddr],
+        pinned_peers: &[SocketAddr],
+        keep_alive: KeepAlive,
+        relay_mode: RelayMode,
         trusted_validators: &[SocketAddr],
+        proposal_config: ProposalConfig<N>,
+        timeout_config: TimeoutConfig,
+        participation_config: ParticipationConfig,
+        admission_policy: AdmissionPolicy<N>,
+        solution_rate_limit: Option<SolutionRateLimit>,
+        persist_mempool: bool,
+        operator_lane: OperatorLane<N>,
+        bft_retention_rounds: Option<u64>,
+        signing_journal: Option<PathBuf>,
+        failover: Option<Failover<N>>,
+        audit_log: Option<AuditLog<N>>,
         genesis: Block<N>,
         cdn: Option<String>,
         storage_mode: StorageMode,
@@ -90,6 +134,9 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
         // Initialize the signal handler.
         let signal_node = Self::handle_signals(shutdown.clone());
 
+        // Open the signing journal, if enabled, which ensures no other instance is signing with it.
+        let signing_journal = signing_journal.map(SigningJournal::<N>::open).transpose()?;
+
         // Initialize the ledger.
         let ledger = Ledger::load(genesis, storage_mode.clone())?;
 
@@ -106,24 +153,71 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
 
         // Initialize the ledger service.
         let ledger_service = Arc::new(CoreLedgerService::new(ledger.clone(), shutdown.clone()));
+        // Compute the state digests on the block-advance path, once they are enabled.
+        let state_digests = Arc::new(StateDigests::default());
+        ledger_service.on_advance({
+            let (ledger, state_digests) = (ledger.clone(), state_digests.clone());
+            move |block| state_digests.on_advance(&ledger, block.height())
+        });
         // Initialize the sync module.
-        let sync = BlockSync::new(BlockSyncMode::Gateway, ledger_service.clone());
+        // Note: A standby instance syncs the blocks from its router peers, as the other validators only accept
+        // one connection per validator address, which the active instance holds.
+        let failover = failover.map(Arc::new);
+        let sync_mode = match failover.as_ref().map_or(false, |failover| !failover.is_active()) {
+            true => BlockSyncMode::Router,
+            false => BlockSyncMode::Gateway,
+        };
+        let sync = BlockSync::new(sync_mode, ledger_service.clone(), &[]);
 
+        // Initialize the signer of the validator, which is the remote signer, if one is given.
+        let signer = match remote_signer {
+            Some(remote_signer) => Signer::Remote(remote_signer),
+            None => Signer::Local(account.clone()),
+        };
+        let address = signer.address();
         // Initialize the consensus.
-        let mut consensus =
-            Consensus::new(account.clone(), ledger_service, bft_ip, trusted_validators, storage_mode.clone())?;
+        let mut consensus = Consensus::new(signer, ledger_service, bft_ip, trusted_validators, storage_mode.clone())?
+            .with_proposal_config(proposal_config)
+            .with_timeout_config(timeout_config)
+            .with_participation_config(participation_config)
+            .with_admission_policy(admission_policy)
+            .with_operator_lane(operator_lane)
+            .with_state_checkpoints();
+        if let Some(signing_journal) = signing_journal {
+            consensus = consensus.with_signing_journal(signing_journal);
+        }
+        // Note: With the failover, the signing journal is only opened while holding the signing lease.
+        if let Some(failover) = &failover {
+            match failover.is_active() {
+                true => info!("Starting the validator instance '{}' as the active instance", failover.instance()),
+                false => info!("Starting the validator instance '{}' on standby", failover.instance()),
+            }
+            consensus = consensus.with_failover(failover.clone());
+        }
+        if let Some(audit_log) = audit_log {
+            consensus = consensus.with_audit_log(audit_log);
+        }
+        if let Some(retention_rounds) = bft_retention_rounds {
+            consensus = consensus.with_retention_rounds(retention_rounds)?;
+        }
         // Initialize the primary channels.
         let (primary_sender, primary_receiver) = init_primary_channels::<N>();
         // Start the consensus.
         consensus.run(primary_sender, primary_receiver).await?;
 
         // Initialize the node router.
+        // Note: With a remote signer, the given account is an ephemeral identity for the router,
+        // as the router does not require the account key of the validator.
         let router = Router::new(
             node_ip,
+            listen_addrs,
             NodeType::Validator,
             account,
             trusted_peers,
+            pinned_peers,
             Self::MAXIMUM_NUMBER_OF_PEERS as u16,
+            keep_alive,
+            relay_mode,
             allow_external_peers,
             matches!(storage_mode, StorageMode::Development(_)),
         )
@@ -136,19 +230,54 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
             router,
             rest: None,
             sync,
+            state_digests: state_digests.clone(),
+            mempool_path: persist_mempool.then(|| crate::mempool_path(N::ID, &storage_mode)),
+            role_transitions: Default::default(),
             handles: Default::default(),
             shutdown,
         };
         // Initialize the transaction pool.
-        node.initialize_transaction_pool(storage_mode, dev_txs)?;
+        node.initialize_transaction_pool(storage_mode.clone(), dev_txs)?;
+        // Re-add the pending transactions of the previous run to the memory pool, if enabled.
+        node.initialize_pending_transactions();
 
         // Initialize the REST server.
         if let Some(rest_ip) = rest_ip {
-            node.rest =
-                Some(Rest::start(rest_ip, rest_rps, Some(consensus), ledger.clone(), Arc::new(node.clone())).await?);
+            node.rest = Some(
+                Rest::start(
+                    rest_ip,
+                    rest_rps,
+                    rest_cache_size,
+                    Some(consensus),
+                    ledger.clone(),
+                    Arc::new(node.clone()),
+                    None,
+                    None,
+                    None,
+                    state_digests,
+                )
+                .await?,
+            );
         }
         // Initialize the routing.
         node.initialize_routing().await;
+        // Restore the known peers of the previous run.
+        node.router.enable_peer_cache(crate::peer_cache_path(N::ID, &storage_mode));
+        // Rate limit the unconfirmed solutions from peers, if enabled.
+        if let Some(limit) = solution_rate_limit {
+            node.router.enable_solution_rate_limit(limit);
+        }
+        // Initialize the block announcements.
+        node.initialize_block_announcements();
+        // Keep a standby instance synced, until it is promoted.
+        if let Some(failover) = failover.filter(|failover| !failover.is_active()) {
+            node.initialize_standby_sync(failover);
+        }
+        // Initialize the membership watcher, which logs when the validator is bonded in or out of the committee,
+        // and requests the validator to become a client, if the role transitions are enabled.
+        let watcher =
+            MembershipWatcher::new(ledger.clone(), address, NodeType::Validator, node.role_transitions.clone());
+        node.handles.lock().push(watcher.spawn());
         // Initialize the notification message loop.
         node.handles.lock().push(crate::start_notification_message_loop());
         // Pass the node to the signal handler.
@@ -166,9 +295,40 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
     pub fn rest(&self) -> &Option<Rest<N, C, Self>> {
         &self.rest
     }
+
+    /// Returns the state digests.
+    pub fn state_digests(&self) -> &StateDigests<N> {
+        &self.state_digests
+    }
+
+    /// Returns the role transitions.
+    pub fn role_transitions(&self) -> &Arc<RoleTransitions> {
+        &self.role_transitions
+    }
+
+    /// Returns the current round of the BFT.
+    pub fn current_round(&self) -> u64 {
+        self.consensus.bft().storage().current_round()
+    }
+
+    /// Returns a snapshot of the health of the node, for the alerts.
+    pub(crate) fn health(&self) -> HealthSnapshot {
+        let participation = self.consensus.bft().participation_status();
+        HealthSnapshot {
+            height: Some(self.ledger.latest_height()),
+            num_peers: self.router.number_of_connected_peers(),
+            num_sync_peers: self.sync.num_peers_with_locators(),
+            num_forked_peers: self.sync.find_forked_peers().len(),
+            is_participation_degraded: participation == Some(ParticipationStatus::Degraded),
+            ..Default::default()
+        }
+    }
 }
 
 impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
+    /// The interval at which the node checks for new blocks to announce, in milliseconds.
+    const BLOCK_ANNOUNCEMENT_INTERVAL_IN_MS: u64 = 100;
+
     // /// Initialize the transaction pool.
     // fn initialize_transaction_pool(&self, dev: Option<u16>) -> Result<()> {
     //     use snarkvm::{
@@ -339,6 +499,21 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
     //     Ok(())
     // }
 
+    /// Re-adds the pending transactions saved by the previous run to the memory pool, if enabled.
+    fn initialize_pending_transactions(&self) {
+        let Some(path) = self.mempool_path.clone() else {
+            return;
+        };
+        let consensus = self.consensus.clone();
+        self.spawn(async move {
+            match consensus.load_pending_transactions(&path).await {
+                Ok(0) => (),
+                Ok(num_transactions) => info!("Re-added {num_transactions} pending transactions to the memory pool"),
+                Err(error) => warn!("Failed to load the pending transactions - {error}"),
+            }
+        });
+    }
+
     /// Initialize the transaction pool.
     fn initialize_transaction_pool(&self, storage_mode: StorageMode, dev_txs: bool) -> Result<()> {
         use snarkvm::console::{
@@ -407,6 +582,51 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
         Ok(())
     }
 
+    /// Initializes the block announcement loop, which announces the committed blocks to the connected clients
+    /// as compact blocks, so that they reconstruct them from the transactions they have already seen.
+    fn initialize_block_announcements(&self) {
+        let node = self.clone();
+        self.spawn(async move {
+            let mut last_height = node.ledger.latest_height();
+            loop {
+                // If the Ctrl-C handler registered the signal, stop the node.
+                if node.shutdown.load(std::sync::atomic::Ordering::Relaxed) {
+                    info!("Shutting down block announcements");
+                    break;
+                }
+
+                // Announce the latest block, once the node is synced, as the lagging peers sync the older blocks.
+                let latest_height = node.ledger.latest_height();
+                if latest_height > last_height && node.is_block_synced() {
+                    node.announce_block(&node.ledger.latest_block(), &[]);
+                }
+                last_height = latest_height;
+                // Sleep briefly, to let the ledger advance.
+                tokio::time::sleep(Duration::from_millis(Self::BLOCK_ANNOUNCEMENT_INTERVAL_IN_MS)).await;
+            }
+        });
+    }
+
+    /// Initializes the sync of a standby instance, which syncs the blocks from its router peers until it is promoted,
+    /// so that it is fully synced by then. The BFT storage is then synced with the ledger (see `Primary::run`).
+    fn initialize_standby_sync(&self, failover: Arc<Failover<N>>) {
+        // Start the block verification pipeline.
+        let node = self.clone();
+        self.spawn(async move { node.sync.run_block_verification().await });
+
+        // Start the sync loop.
+        let node = self.clone();
+        self.spawn(async move {
+            while !failover.is_active() {
+                // Wait for the sync to make progress, or sleep briefly, to avoid triggering spam detection.
+                node.sync.wait_for_sync_progress(Duration::from_secs(5)).await;
+                // Perform the sync routine.
+                node.sync.try_block_sync(&node).await;
+            }
+            info!("Stopped syncing the blocks from the router peers, as this validator instance was promoted");
+        });
+    }
+
     /// Spawns a task with the given future; it should only be used for long-running tasks.
     pub fn spawn<T: Future<Output = ()> + Send + 'static>(&self, future: T) {
         self.handles.lock().push(tokio::spawn(future));
@@ -423,13 +643,31 @@ impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Validator<N, C> {
         trace!("Shutting down the node...");
         self.shutdown.store(true, std::sync::atomic::Ordering::Relaxed);
 
+        // Drain the connections to the peers.
+        trace!("Draining the connections...");
+        self.drain_connections().await;
+
         // Abort the tasks.
         trace!("Shutting down the validator...");
         self.handles.lock().iter().for_each(|handle| handle.abort());
 
+        // Stop the REST server, as the node may be started again in another role.
+        if let Some(rest) = &self.rest {
+            rest.handles().lock().iter().for_each(|handle| handle.abort());
+        }
+
         // Shut down the router.
         self.router.shut_down().await;
 
+        // Save the pending transactions of the memory pool, if enabled, so that they are re-added on restart.
+        if let Some(path) = &self.mempool_path {
+            trace!("Saving the pending transactions...");
+            match self.consensus.save_pending_transactions(path) {
+                Ok(num_transactions) => info!("Saved {num_transactions} pending transactions"),
+                Err(error) => warn!("Failed to save the pending transactions - {error}"),
+            }
+        }
+
         // Shut down consensus.
         trace!("Shutting down consensus...");
         self.consensus.shut_down().await;
@@ -477,12 +715,17 @@ mod tests {
 
         let validator = Validator::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::new(
             node,
+            &[],
             None,
             Some(rest),
             10,
+            0,
             account,
             &[],
             &[],
+            Default::default(),
+            Default::default(),
+            &[],
             genesis,
             None,
             storage_mode,
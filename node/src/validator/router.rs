@@ -13,24 +13,30 @@
 // limitations under the License.
 
 use super::*;
-use snarkos_node_router::messages::{
-    BlockRequest,
-    BlockResponse,
-    DataBlocks,
-    DisconnectReason,
-    Message,
-    MessageCodec,
-    Ping,
-    Pong,
-    UnconfirmedTransaction,
+use snarkos_node_router::{
+    message_priority,
+    messages::{
+        BlockRequest,
+        BlockResponse,
+        BlockTransactionsRequest,
+        BlockTransactionsResponse,
+        DataBlocks,
+        DisconnectReason,
+        Message,
+        Ping,
+        Pong,
+        UnconfirmedTransaction,
+    },
+    TrafficCodec,
 };
-use snarkos_node_tcp::{Connection, ConnectionSide, Tcp};
+use snarkos_node_sync::communication_service::CommunicationService;
+use snarkos_node_tcp::{protocols::MessagePriority, Connection, ConnectionSide, Tcp};
 use snarkvm::{
     ledger::narwhal::Data,
     prelude::{block::Transaction, error, Network},
 };
 
-use std::{io, net::SocketAddr, time::Duration};
+use std::{io, net::SocketAddr};
 
 impl<N: Network, C: ConsensusStorage<N>> P2P for Validator<N, C> {
     /// Returns a reference to the TCP instance.
@@ -89,25 +95,30 @@ impl<N: Network, C: ConsensusStorage<N>> Disconnect for Validator<N, C> {
 
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> Writing for Validator<N, C> {
-    type Codec = MessageCodec<N>;
+    type Codec = TrafficCodec<N>;
     type Message = Message<N>;
 
     /// Creates an [`Encoder`] used to write the outbound messages to the target stream.
     /// The `side` parameter indicates the connection side **from the node's perspective**.
-    fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router().traffic_codec(peer_addr)
+    }
+
+    /// Returns the outbound queue priority of the given message.
+    fn priority(&self, message: &Self::Message) -> MessagePriority {
+        message_priority(message)
     }
 }
 
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> Reading for Validator<N, C> {
-    type Codec = MessageCodec<N>;
+    type Codec = TrafficCodec<N>;
     type Message = Message<N>;
 
     /// Creates a [`Decoder`] used to interpret messages from the network.
     /// The `side` param indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router().traffic_codec(peer_addr)
     }
 
     /// Processes a message received from the network.
@@ -144,6 +155,27 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
     }
 }
 
+#[async_trait]
+impl<N: Network, C: ConsensusStorage<N>> CommunicationService for Validator<N, C> {
+    /// The message type.
+    type Message = Message<N>;
+
+    /// Prepares a block request to be sent.
+    fn prepare_block_request(start_height: u32, end_height: u32) -> Self::Message {
+        debug_assert!(start_height < end_height, "Invalid block request format");
+        Message::BlockRequest(BlockRequest { start_height, end_height })
+    }
+
+    /// Sends the given message to specified peer, which only happens while the validator instance is on standby.
+    async fn send(
+        &self,
+        peer_ip: SocketAddr,
+        message: Self::Message,
+    ) -> Option<tokio::sync::oneshot::Receiver<io::Result<()>>> {
+        Outbound::send(self, peer_ip, message)
+    }
+}
+
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> Routing<N> for Validator<N, C> {}
 
@@ -200,6 +232,25 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Validator<N, C> {
         }
     }
 
+    /// Handles a `BlockTransactionsRequest` message, by sending back the transactions of the announced block.
+    fn block_transactions_request(&self, peer_ip: SocketAddr, message: BlockTransactionsRequest<N>) -> bool {
+        let response = self
+            .ledger
+            .get_height(&message.block_hash)
+            .and_then(|height| self.ledger.get_block(height))
+            .and_then(|block| BlockTransactionsResponse::new(&block, &message.transaction_ids));
+        match response {
+            Ok(response) => {
+                Outbound::send(self, peer_ip, Message::BlockTransactionsResponse(response));
+                true
+            }
+            Err(error) => {
+                warn!("Failed to serve the block transactions requested by '{peer_ip}' - {error}");
+                false
+            }
+        }
+    }
+
     /// Processes the block locators and sends back a `Pong` message.
     fn ping(&self, peer_ip: SocketAddr, message: Ping<N>) -> bool {
         // Check if the sync module is in router mode.
@@ -225,7 +276,7 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Validator<N, C> {
         let self_ = self.clone();
         tokio::spawn(async move {
             // Sleep for the preset time before sending a `Ping` request.
-            tokio::time::sleep(Duration::from_secs(Self::PING_SLEEP_IN_SECS)).await;
+            tokio::time::sleep(self_.router().keep_alive().ping_interval()).await;
             // Check that the peer is still connected.
             if self_.router().is_connected(&peer_ip) {
                 // Retrieve the block locators.
@@ -0,0 +1,179 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{anyhow, ensure, Result};
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::sync::Notify;
+
+/// The status of the ledger volume.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DiskStatus {
+    /// There is enough free space.
+    Healthy,
+    /// The free space is below the warning threshold.
+    Low,
+    /// The free space is below the shutdown threshold.
+    Critical,
+}
+
+/// The disk watchdog monitors the free space on the ledger volume, so that the node can warn
+/// the operator, and stop gracefully, before the database runs out of space and corrupts itself.
+#[derive(Clone, Debug)]
+pub struct DiskWatchdog {
+    /// The path of the ledger.
+    path: PathBuf,
+    /// The free space (in bytes) below which the node warns and pauses non-essential writes.
+    warning_threshold: u64,
+    /// The free space (in bytes) below which the node shuts down, if set.
+    shutdown_threshold: Option<u64>,
+    /// Whether the free space is currently below the warning threshold.
+    is_low: Arc<AtomicBool>,
+    /// Notified once the free space falls below the shutdown threshold.
+    critical: Arc<Notify>,
+}
+
+impl DiskWatchdog {
+    /// The interval at which the free space is checked, in seconds.
+    const CHECK_INTERVAL_IN_SECS: u64 = 30;
+
+    /// Initializes a new disk watchdog for the ledger at the given path.
+    pub fn new(path: PathBuf, warning_threshold: u64, shutdown_threshold: Option<u64>) -> Result<Self> {
+        if let Some(shutdown_threshold) = shutdown_threshold {
+            ensure!(
+                shutdown_threshold < warning_threshold,
+                "The disk shutdown threshold must be lower than the warning threshold"
+            );
+        }
+        Ok(Self {
+            path,
+            warning_threshold,
+            shutdown_threshold,
+            is_low: Default::default(),
+            critical: Default::default(),
+        })
+    }
+
+    /// Returns `true` if the free space was below the warning threshold at the last check.
+    pub fn is_low(&self) -> bool {
+        self.is_low.load(Ordering::Relaxed)
+    }
+
+    /// Returns the free space on the ledger volume, in bytes.
+    pub fn available_space(&self) -> Result<u64> {
+        // The ledger may not exist yet, in which case its closest existing ancestor is on the same volume.
+        let path = self.path.ancestors().find(|path| path.exists()).unwrap_or(Path::new("."));
+        fs2::available_space(path).map_err(|err| anyhow!("Couldn't read the free space at {} - {err}", path.display()))
+    }
+
+    /// Returns the status for the given free space.
+    fn status(&self, available: u64) -> DiskStatus {
+        match self.shutdown_threshold {
+            Some(threshold) if available < threshold => DiskStatus::Critical,
+            _ if available < self.warning_threshold => DiskStatus::Low,
+            _ => DiskStatus::Healthy,
+        }
+    }
+
+    /// Checks the free space on the ledger volume, and returns its status.
+    pub fn check(&self) -> Result<DiskStatus> {
+        let available = self.available_space()?;
+        let status = self.status(available);
+        self.is_low.store(status != DiskStatus::Healthy, Ordering::Relaxed);
+
+        #[cfg(feature = "metrics")]
+        {
+            metrics::gauge(metrics::disk::AVAILABLE_BYTES, available as f64);
+            metrics::gauge(metrics::disk::IS_LOW, (status != DiskStatus::Healthy) as u8 as f64);
+        }
+
+        const GB: u64 = 1024 * 1024 * 1024;
+        match status {
+            DiskStatus::Healthy => trace!("The ledger volume has {} GB of free space", available / GB),
+            DiskStatus::Low => warn!(
+                "The ledger volume is running out of space ({} GB left), pausing non-essential writes",
+                available / GB
+            ),
+            DiskStatus::Critical => error!("The ledger volume is almost full ({} GB left)", available / GB),
+        }
+        Ok(status)
+    }
+
+    /// Waits until the free space falls below the shutdown threshold, at which point the node must be stopped.
+    pub async fn wait_until_critical(&self) {
+        self.critical.notified().await
+    }
+
+    /// Runs the watchdog loop, until the free space becomes critical.
+    pub(crate) async fn run(self) {
+        loop {
+            match self.check() {
+                Ok(DiskStatus::Critical) => {
+                    error!("The node must stop, to avoid corrupting the ledger on a full disk");
+                    // Note: The permit is kept until the owner of the node waits for it.
+                    self.critical.notify_one();
+                    break;
+                }
+                Ok(_) => (),
+                Err(error) => warn!("{error}"),
+            }
+            tokio::time::sleep(Duration::from_secs(Self::CHECK_INTERVAL_IN_SECS)).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disk_status() {
+        let watchdog = DiskWatchdog::new(std::env::temp_dir(), 100, Some(10)).unwrap();
+        assert_eq!(watchdog.status(1_000), DiskStatus::Healthy);
+        assert_eq!(watchdog.status(100), DiskStatus::Healthy);
+        assert_eq!(watchdog.status(99), DiskStatus::Low);
+        assert_eq!(watchdog.status(9), DiskStatus::Critical);
+
+        // Ensure the watchdog never shuts the node down without a shutdown threshold.
+        let watchdog = DiskWatchdog::new(std::env::temp_dir(), 100, None).unwrap();
+        assert_eq!(watchdog.status(0), DiskStatus::Low);
+
+        // Ensure the thresholds are ordered.
+        assert!(DiskWatchdog::new(std::env::temp_dir(), 100, Some(100)).is_err());
+    }
+
+    #[test]
+    fn test_available_space() {
+        // Ensure the free space is read from the closest existing ancestor.
+        let watchdog = DiskWatchdog::new(std::env::temp_dir().join("snarkos-missing").join("ledger"), 0, None).unwrap();
+        assert!(watchdog.available_space().is_ok());
+        assert_eq!(watchdog.check().unwrap(), DiskStatus::Healthy);
+        assert!(!watchdog.is_low());
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_critical() {
+        // Ensure the owner of the node is notified once the free space becomes critical, even if it waits later.
+        let watchdog = DiskWatchdog::new(std::env::temp_dir(), u64::MAX, Some(u64::MAX - 1)).unwrap();
+        watchdog.clone().run().await;
+        assert!(watchdog.is_low());
+        watchdog.wait_until_critical().await;
+    }
+}
@@ -0,0 +1,159 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::locators::{BlockLocators, CHECKPOINT_INTERVAL, NUM_RECENT_BLOCKS};
+use snarkos_node_bft_ledger_service::LedgerService;
+use snarkvm::prelude::Network;
+
+use anyhow::Result;
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use std::collections::BTreeMap;
+
+/// An index of the canonical block hashes at the heights that block locators refer to.
+///
+/// The index holds the hashes at every checkpoint height, and at the recent heights of the canonical chain,
+/// so that the block locators of peers can be compared against the canonical chain without reading every
+/// block hash from storage. It is updated incrementally, and only reads the blocks added since its last update.
+#[derive(Debug)]
+pub struct AncestorIndex<N: Network> {
+    /// The canonical block hashes at the checkpoint heights, where the `i`-th hash is at `i * CHECKPOINT_INTERVAL`.
+    checkpoints: RwLock<Vec<N::BlockHash>>,
+    /// The canonical block hashes at the recent heights.
+    recents: RwLock<BTreeMap<u32, N::BlockHash>>,
+}
+
+impl<N: Network> Default for AncestorIndex<N> {
+    /// Initializes an empty index.
+    fn default() -> Self {
+        Self { checkpoints: Default::default(), recents: Default::default() }
+    }
+}
+
+impl<N: Network> AncestorIndex<N> {
+    /// Updates the index to the latest block of the given canonical chain.
+    /// The entries that are no longer canonical, e.g. after the ledger was rolled back, are replaced.
+    pub fn update(&self, canon: &dyn LedgerService<N>) -> Result<()> {
+        // Retrieve the latest block height.
+        let latest_height = canon.latest_block_height();
+
+        // Update the checkpoints.
+        let mut checkpoints = self.checkpoints.write();
+        let num_checkpoints = (latest_height / CHECKPOINT_INTERVAL) as usize + 1;
+        checkpoints.truncate(num_checkpoints);
+        // As a block hash commits to the history before it, only the last checkpoint needs to be checked.
+        while let Some(hash) = checkpoints.last() {
+            let height = (checkpoints.len() as u32 - 1) * CHECKPOINT_INTERVAL;
+            if canon.get_block_hash(height)? == *hash {
+                break;
+            }
+            checkpoints.pop();
+        }
+        for index in checkpoints.len()..num_checkpoints {
+            checkpoints.push(canon.get_block_hash(index as u32 * CHECKPOINT_INTERVAL)?);
+        }
+        drop(checkpoints);
+
+        // Update the recents.
+        let mut recents = self.recents.write();
+        let start_height = latest_height.saturating_sub(NUM_RECENT_BLOCKS as u32 - 1);
+        recents.retain(|height, _| (start_height..=latest_height).contains(height));
+        // As a block hash commits to the history before it, only the last recent block needs to be checked.
+        while let Some((height, hash)) = recents.last_key_value() {
+            if canon.get_block_hash(*height)? == *hash {
+                break;
+            }
+            recents.pop_last();
+        }
+        let next_height = recents.last_key_value().map_or(start_height, |(height, _)| height + 1);
+        for height in next_height..=latest_height {
+            recents.insert(height, canon.get_block_hash(height)?);
+        }
+        Ok(())
+    }
+
+    /// Returns the canonical block hash at the given height, if it is indexed.
+    pub fn get_hash(&self, height: u32) -> Option<N::BlockHash> {
+        if let Some(hash) = self.recents.read().get(&height) {
+            return Some(*hash);
+        }
+        match height % CHECKPOINT_INTERVAL {
+            0 => self.checkpoints.read().get((height / CHECKPOINT_INTERVAL) as usize).copied(),
+            _ => None,
+        }
+    }
+
+    /// Returns the block locators of the canonical chain, as of the last update.
+    pub fn block_locators(&self) -> Result<BlockLocators<N>> {
+        // Hold both locks, so that the recents and checkpoints are from the same update.
+        let checkpoints = self.checkpoints.read();
+        let recents = self.recents.read();
+        let recents: IndexMap<_, _> = recents.iter().map(|(height, hash)| (*height, *hash)).collect();
+        let checkpoints =
+            checkpoints.iter().enumerate().map(|(index, hash)| (index as u32 * CHECKPOINT_INTERVAL, *hash)).collect();
+        BlockLocators::new(recents, checkpoints)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_bft_ledger_service::MockLedgerService;
+    use snarkvm::prelude::TestRng;
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    fn sample_ledger_service(height: u32) -> MockLedgerService<CurrentNetwork> {
+        let rng = &mut TestRng::default();
+        let committee = snarkvm::ledger::committee::test_helpers::sample_committee(rng);
+        MockLedgerService::new_at_height(committee, height)
+    }
+
+    #[test]
+    fn test_ancestor_index() {
+        let height = 2 * CHECKPOINT_INTERVAL + 500;
+        let ledger = sample_ledger_service(height);
+        let index = AncestorIndex::default();
+        index.update(&ledger).unwrap();
+
+        // Ensure the checkpoints and the recent blocks are indexed.
+        for height in [0, CHECKPOINT_INTERVAL, 2 * CHECKPOINT_INTERVAL, height - NUM_RECENT_BLOCKS as u32 + 1, height] {
+            assert_eq!(index.get_hash(height), Some(ledger.get_block_hash(height).unwrap()));
+        }
+        // Ensure the other blocks are not indexed.
+        assert_eq!(index.get_hash(1), None);
+        assert_eq!(index.get_hash(height - NUM_RECENT_BLOCKS as u32), None);
+        assert_eq!(index.get_hash(height + 1), None);
+
+        // Ensure the block locators are well-formed.
+        let locators = index.block_locators().unwrap();
+        locators.ensure_is_valid().unwrap();
+        assert_eq!(locators.latest_locator_height(), height);
+    }
+
+    #[test]
+    fn test_ancestor_index_rollback() {
+        let index = AncestorIndex::default();
+        index.update(&sample_ledger_service(2 * CHECKPOINT_INTERVAL + 500)).unwrap();
+
+        // Ensure the index follows a ledger that was rolled back.
+        let height = CHECKPOINT_INTERVAL + 500;
+        let ledger = sample_ledger_service(height);
+        index.update(&ledger).unwrap();
+        assert_eq!(index.get_hash(2 * CHECKPOINT_INTERVAL), None);
+        assert_eq!(index.get_hash(height), Some(ledger.get_block_hash(height).unwrap()));
+        assert_eq!(index.get_hash(height + 1), None);
+        index.block_locators().unwrap().ensure_is_valid().unwrap();
+    }
+}
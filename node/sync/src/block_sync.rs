@@ -15,27 +15,33 @@
 use crate::{
     helpers::{PeerPair, PrepareSyncRequest, SyncRequest},
     locators::BlockLocators,
+    AncestorIndex,
+    InvalidBlocks,
+    PendingBlocks,
+    SyncCheckpoint,
 };
 use snarkos_node_bft_ledger_service::LedgerService;
 use snarkos_node_router::messages::DataBlocks;
 use snarkos_node_sync_communication_service::CommunicationService;
-use snarkos_node_sync_locators::{CHECKPOINT_INTERVAL, NUM_RECENT_BLOCKS};
 use snarkvm::prelude::{block::Block, Network};
 
 use anyhow::{bail, ensure, Result};
 use indexmap::{IndexMap, IndexSet};
 use itertools::Itertools;
+use once_cell::sync::OnceCell;
 use parking_lot::{Mutex, RwLock};
 use rand::{prelude::IteratorRandom, CryptoRng, Rng};
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashMap},
     net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::Path,
     sync::{
         atomic::{AtomicBool, AtomicU32, Ordering},
         Arc,
     },
-    time::Instant,
+    time::{Duration, Instant},
 };
+use tokio::sync::{mpsc, Notify};
 
 #[cfg(not(test))]
 pub const REDUNDANCY_FACTOR: usize = 1;
@@ -45,7 +51,9 @@ const EXTRA_REDUNDANCY_FACTOR: usize = REDUNDANCY_FACTOR * 3;
 const NUM_SYNC_CANDIDATE_PEERS: usize = REDUNDANCY_FACTOR * 5;
 
 const BLOCK_REQUEST_TIMEOUT_IN_SECS: u64 = 600; // 600 seconds
-const MAX_BLOCK_REQUESTS: usize = 50; // 50 requests
+const MAX_BLOCK_REQUESTS: usize = 100; // 100 requests
+const MAX_BLOCK_REQUESTS_PER_PEER: usize = 10; // 10 requests
+const MAX_VERIFIED_BLOCKS: usize = 16; // 16 blocks
 
 /// The maximum number of blocks tolerated before the primary is considered behind its peers.
 pub const MAX_BLOCKS_BEHIND: u32 = 1; // blocks
@@ -89,9 +97,21 @@ pub struct BlockSync<N: Network> {
     /// This map is a linearly-increasing map of block heights to block hashes,
     /// updated solely from the ledger and candidate blocks (not from peers' block locators, to ensure there are no forks).
     canon: Arc<dyn LedgerService<N>>,
+    /// The index of the canonical block hashes at the heights that block locators refer to.
+    ancestors: Arc<AncestorIndex<N>>,
+    /// The set of peer IPs that block requests are restricted to, if non-empty.
+    /// Block locators are still tracked for every peer, so that the sync status reflects the whole network.
+    trusted_sync_peers: Arc<IndexSet<SocketAddr>>,
+    /// The trusted checkpoint, below which the blocks are not fully verified, if set.
+    checkpoint: Option<SyncCheckpoint<N>>,
+    /// The blocks an operator marked as invalid, which are never synced.
+    invalid_blocks: Arc<InvalidBlocks<N>>,
     /// The map of peer IP to their block locators.
     /// The block locators are consistent with the canonical map and every other peer's block locators.
     locators: Arc<RwLock<IndexMap<SocketAddr, BlockLocators<N>>>>,
+    /// The map of peer IP to their smoothed round-trip time.
+    /// This map is used to prefer low-latency peers when sending block requests.
+    latencies: Arc<RwLock<IndexMap<SocketAddr, Duration>>>,
     /// The map of peer-to-peer to their common ancestor.
     /// This map is used to determine which peers to request blocks from.
     common_ancestors: Arc<RwLock<IndexMap<PeerPair, u32>>>,
@@ -110,15 +130,27 @@ pub struct BlockSync<N: Network> {
     num_blocks_behind: Arc<AtomicU32>,
     /// The lock to guarantee advance_with_sync_blocks() is called only once at a time.
     advance_with_sync_blocks_lock: Arc<Mutex<()>>,
+    /// The notifier for the block responses that are ready to be verified.
+    pending_blocks: Arc<Notify>,
+    /// The notifier for the progress of the sync, which frees up room for new block requests.
+    sync_progress: Arc<Notify>,
+    /// The journal of the received blocks, if enabled (see `load_pending_blocks`).
+    journal: Arc<OnceCell<PendingBlocks<N>>>,
 }
 
 impl<N: Network> BlockSync<N> {
     /// Initializes a new block sync module.
-    pub fn new(mode: BlockSyncMode, ledger: Arc<dyn LedgerService<N>>) -> Self {
+    /// If `trusted_sync_peers` is non-empty, blocks are only requested from the given peers.
+    pub fn new(mode: BlockSyncMode, ledger: Arc<dyn LedgerService<N>>, trusted_sync_peers: &[SocketAddr]) -> Self {
         Self {
             mode,
             canon: ledger,
+            ancestors: Default::default(),
+            trusted_sync_peers: Arc::new(trusted_sync_peers.iter().copied().collect()),
+            checkpoint: None,
+            invalid_blocks: Default::default(),
             locators: Default::default(),
+            latencies: Default::default(),
             common_ancestors: Default::default(),
             requests: Default::default(),
             responses: Default::default(),
@@ -126,15 +158,50 @@ impl<N: Network> BlockSync<N> {
             is_block_synced: Default::default(),
             num_blocks_behind: Default::default(),
             advance_with_sync_blocks_lock: Default::default(),
+            pending_blocks: Default::default(),
+            sync_progress: Default::default(),
+            journal: Default::default(),
         }
     }
 
+    /// Sets the trusted checkpoint, below which the blocks are linked by their hashes, but not fully verified.
+    /// Only the peers whose block locators agree with the checkpoint are synced from.
+    pub fn with_checkpoint(mut self, checkpoint: Option<SyncCheckpoint<N>>) -> Self {
+        self.checkpoint = checkpoint;
+        self
+    }
+
+    /// Sets the blocks an operator marked as invalid, which are never synced.
+    /// Only the peers whose block locators do not include an invalid block are synced from.
+    pub fn with_invalid_blocks(mut self, invalid_blocks: InvalidBlocks<N>) -> Self {
+        self.invalid_blocks = Arc::new(invalid_blocks);
+        self
+    }
+
     /// Returns the block sync mode.
     #[inline]
     pub const fn mode(&self) -> BlockSyncMode {
         self.mode
     }
 
+    /// Returns the trusted checkpoint, if set.
+    #[inline]
+    pub const fn checkpoint(&self) -> Option<&SyncCheckpoint<N>> {
+        self.checkpoint.as_ref()
+    }
+
+    /// Returns the peers that block requests are restricted to (empty if unrestricted).
+    #[inline]
+    pub fn trusted_sync_peers(&self) -> &IndexSet<SocketAddr> {
+        &self.trusted_sync_peers
+    }
+
+    /// Returns `true` if blocks may be requested from the given peer IP.
+    #[inline]
+    pub fn is_allowed_sync_peer(&self, peer_ip: &SocketAddr) -> bool {
+        self.trusted_sync_peers.is_empty() || self.trusted_sync_peers.contains(peer_ip)
+    }
+
     /// Returns `true` if the node is synced up to the latest block (within the given tolerance).
     #[inline]
     pub fn is_block_synced(&self) -> bool {
@@ -197,25 +264,10 @@ impl<N: Network> BlockSync<N> {
     /// Returns the block locators.
     #[inline]
     pub fn get_block_locators(&self) -> Result<BlockLocators<N>> {
-        // Retrieve the latest block height.
-        let latest_height = self.canon.latest_block_height();
-
-        // Initialize the recents map.
-        let mut recents = IndexMap::with_capacity(NUM_RECENT_BLOCKS);
-        // Retrieve the recent block hashes.
-        for height in latest_height.saturating_sub((NUM_RECENT_BLOCKS - 1) as u32)..=latest_height {
-            recents.insert(height, self.canon.get_block_hash(height)?);
-        }
-
-        // Initialize the checkpoints map.
-        let mut checkpoints = IndexMap::with_capacity((latest_height / CHECKPOINT_INTERVAL + 1).try_into()?);
-        // Retrieve the checkpoint block hashes.
-        for height in (0..=latest_height).step_by(CHECKPOINT_INTERVAL as usize) {
-            checkpoints.insert(height, self.canon.get_block_hash(height)?);
-        }
-
+        // Update the index of the canonical chain.
+        self.ancestors.update(&*self.canon)?;
         // Construct the block locators.
-        BlockLocators::new(recents, checkpoints)
+        self.ancestors.block_locators()
     }
 
     /// Performs one iteration of the block sync.
@@ -227,25 +279,12 @@ impl<N: Network> BlockSync<N> {
         trace!("Prepared {} block requests", block_requests.len());
 
         // If there are no block requests, but there are pending block responses in the sync pool,
-        // then try to advance the ledger using these pending block responses.
+        // then wake the verification pipeline to advance the ledger using these pending block responses.
         // Note: This condition is guarded by `mode.is_router()` because validators sync blocks
         // using another code path that updates both `storage` and `ledger` when advancing blocks.
         if block_requests.is_empty() && !self.responses.read().is_empty() && self.mode.is_router() {
-            // Retrieve the latest block height.
-            let current_height = self.canon.latest_block_height();
-
-            // Acquire the lock to ensure try_advancing_with_block_responses is called only once at a time.
-            // If the lock is already acquired, return early.
-            let Some(_lock) = self.advance_with_sync_blocks_lock.try_lock() else {
-                trace!(
-                    "Skipping a call to try_block_sync() as a block advance is already in progress (at block {current_height})"
-                );
-                return;
-            };
-
-            // Try to advance the ledger with the sync pool.
-            trace!("No block requests to send - try advancing with block responses (at block {current_height})");
-            self.try_advancing_with_block_responses(current_height);
+            trace!("No block requests to send - waking the block verification pipeline");
+            self.pending_blocks.notify_one();
             // Return early.
             return;
         }
@@ -261,13 +300,13 @@ impl<N: Network> BlockSync<N> {
                 }
             };
 
-            // Use a randomly sampled subset of the sync IPs.
-            let sync_ips: IndexSet<_> = sync_peers
-                .keys()
-                .copied()
-                .choose_multiple(&mut rand::thread_rng(), max_num_sync_ips)
-                .into_iter()
-                .collect();
+            // Use a randomly sampled subset of the least loaded, low-latency sync IPs.
+            let sync_ips = self.select_sync_ips(&sync_peers, max_num_sync_ips, &mut rand::thread_rng());
+            // If every sync peer is saturated with block requests, wait for their responses.
+            if sync_ips.is_empty() {
+                trace!("Every sync peer is saturated with block requests");
+                break 'outer;
+            }
 
             // Calculate the end height.
             let end_height = start_height.saturating_add(requests.len() as u32);
@@ -325,11 +364,19 @@ impl<N: Network> BlockSync<N> {
     }
 
     /// Attempts to advance with blocks from the sync pool.
+    /// In router mode, the blocks are handed off to the verification pipeline (see `run_block_verification`).
     #[inline]
     pub fn advance_with_sync_blocks(&self, peer_ip: SocketAddr, blocks: Vec<Block<N>>) -> Result<()> {
         // Process the block response from the given peer IP.
         self.process_block_response(peer_ip, blocks)?;
 
+        // In router mode, wake the verification pipeline, and the sync loop, as the peer can serve more blocks.
+        if self.mode.is_router() {
+            self.pending_blocks.notify_one();
+            self.sync_progress.notify_one();
+            return Ok(());
+        }
+
         // Acquire the lock to ensure this function is called only once at a time.
         // If the lock is already acquired, return early.
         let Some(_lock) = self.advance_with_sync_blocks_lock.try_lock() else {
@@ -344,21 +391,220 @@ impl<N: Network> BlockSync<N> {
         Ok(())
     }
 
-    /// Handles the block responses from the sync pool.
-    fn try_advancing_with_block_responses(&self, mut current_height: u32) {
-        while let Some(block) = self.remove_block_response(current_height + 1) {
-            // Ensure the block height matches.
-            if block.height() != current_height + 1 {
-                warn!("Block height mismatch: expected {}, found {}", current_height + 1, block.height());
+    /// Advances the ledger with the block a peer announced, if it is the next block, and is not being synced already.
+    /// The block is handed off to the verification pipeline (see `run_block_verification`).
+    pub fn advance_with_announced_block(&self, block: Block<N>) -> Result<()> {
+        // Ensure the announced block is the next block.
+        let height = block.height();
+        ensure!(height == self.canon.latest_block_height() + 1, "The announced block {height} is not the next block");
+        // Ensure the announced block was not marked as invalid.
+        self.invalid_blocks.check_block(&block)?;
+        // Skip the announced block, if it is already being synced.
+        if self.check_block_request(height).is_err() {
+            trace!("Skipping the announced block {height} (already syncing)");
+            return Ok(());
+        }
+        // Insert the announced block into the sync pool, and wake the verification pipeline.
+        self.responses.write().insert(height, block.clone());
+        self.journal_block(&block);
+        self.pending_blocks.notify_one();
+        Ok(())
+    }
+
+    /// Runs the block verification pipeline, which advances the ledger with the block responses, in order,
+    /// as soon as they are ready. The pipeline has three stages, which run concurrently:
+    /// - the download stage, i.e. the block requests to the sync peers, bounded by `MAX_BLOCK_REQUESTS`,
+    /// - the verification stage, which verifies the transactions of the next blocks, spread over the cores,
+    /// - the execution stage, which checks each block against the ledger, and advances the ledger with it.
+    ///
+    /// The verified blocks are handed to the execution stage through a queue bounded by `MAX_VERIFIED_BLOCKS`,
+    /// so the next blocks are downloaded and verified while the previous ones are executed.
+    pub async fn run_block_verification(&self) {
+        let (sender, receiver) = mpsc::channel(MAX_VERIFIED_BLOCKS);
+        let restart = Arc::new(AtomicBool::new(false));
+        tokio::join!(self.run_verification_stage(sender, restart.clone()), self.run_execution_stage(receiver, restart));
+    }
+
+    /// Runs the verification stage of the pipeline, which takes the block responses in order,
+    /// verifies their transactions, and queues them for the execution stage.
+    async fn run_verification_stage(&self, sender: mpsc::Sender<Block<N>>, restart: Arc<AtomicBool>) {
+        // The height of the next block to verify.
+        let mut next_height = self.canon.latest_block_height() + 1;
+        loop {
+            // Restart from the latest block, if the execution stage discarded the queued blocks,
+            // or if the ledger advanced with other blocks meanwhile.
+            let latest_height = self.canon.latest_block_height();
+            if restart.swap(false, Ordering::AcqRel) || next_height <= latest_height {
+                next_height = latest_height + 1;
+            }
+            // Take the next block response, or wait for new block responses.
+            let Some(block) = self.remove_block_response(next_height) else {
+                self.pending_blocks.notified().await;
+                continue;
+            };
+            // Wake the sync loop, as the block response frees up room for new block requests.
+            self.sync_progress.notify_one();
+
+            // Verify the transactions of the block on a blocking thread, unless it is covered by the checkpoint.
+            let block = match self.checkpoint.map_or(false, |checkpoint| checkpoint.covers(next_height)) {
+                true => block,
+                false => {
+                    let canon = self.canon.clone();
+                    let result = tokio::task::spawn_blocking(move || {
+                        let result = canon.verify_transactions(&block);
+                        (block, result)
+                    })
+                    .await;
+                    match result {
+                        // Note: The execution stage checks the block in full regardless, so a failure is not final,
+                        // e.g. if a transaction refers to the state root of a block that is still queued.
+                        Ok((block, result)) => {
+                            if let Err(error) = result {
+                                trace!("Deferring the verification of block {next_height} - {error}");
+                            }
+                            block
+                        }
+                        Err(error) => {
+                            error!("Block verification failed - {error}");
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            // Queue the block for the execution stage, waiting while the queue is full.
+            if sender.send(block).await.is_err() {
+                break;
+            }
+            next_height += 1;
+        }
+    }
+
+    /// Runs the execution stage of the pipeline, which advances the ledger with the verified blocks, in order.
+    async fn run_execution_stage(&self, mut receiver: mpsc::Receiver<Block<N>>, restart: Arc<AtomicBool>) {
+        while let Some(block) = receiver.recv().await {
+            // Discard the blocks queued after a block that failed, as they do not extend the ledger.
+            if block.height() != self.canon.latest_block_height() + 1 {
+                trace!("Discarding the queued block {}", block.height());
+                continue;
+            }
+            // Advance the ledger with the block, on a blocking thread.
+            let self_ = self.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                // Acquire the lock to ensure the ledger is advanced by one task at a time.
+                let _lock = self_.advance_with_sync_blocks_lock.lock();
+                self_.try_advancing_with_block(block)
+            })
+            .await;
+            let is_advanced = match result {
+                Ok(Ok(())) => true,
+                Ok(Err(error)) => {
+                    warn!("{error}");
+                    false
+                }
+                Err(error) => {
+                    error!("Block execution failed - {error}");
+                    false
+                }
+            };
+            // Restart the verification stage from the latest block, so the failed block is requested again.
+            if !is_advanced {
+                restart.store(true, Ordering::Release);
+                self.pending_blocks.notify_one();
+            }
+            // Wake the sync loop, as the ledger advanced, or the failed block has to be requested again.
+            self.sync_progress.notify_one();
+        }
+    }
+
+    /// Waits until the sync makes progress, or the given timeout elapses.
+    pub async fn wait_for_sync_progress(&self, timeout: Duration) {
+        let _ = tokio::time::timeout(timeout, self.sync_progress.notified()).await;
+    }
+
+    /// Compacts the journal of the received blocks to the ones that extend the ledger, but were not yet
+    /// advanced with, e.g. on shutdown. Returns the number of journaled blocks.
+    pub fn save_pending_blocks(&self) -> Result<usize> {
+        match self.journal.get() {
+            Some(journal) => journal.compact(|| self.contiguous_block_responses()),
+            None => Ok(0),
+        }
+    }
+
+    /// Loads the blocks journaled by a previous run from the given file into the sync pool,
+    /// so that they are not requested from the peers again, and journals the received blocks from now on.
+    /// Returns the number of loaded blocks.
+    pub fn load_pending_blocks(&self, path: &Path) -> Result<usize> {
+        ensure!(self.journal.get().is_none(), "The pending blocks were already loaded");
+        // Order the journaled blocks by height, as they were journaled in the order they were received.
+        let (journal, blocks) = PendingBlocks::<N>::open(path)?;
+        let blocks = blocks.into_iter().map(|block| (block.height(), block)).collect::<BTreeMap<_, _>>();
+
+        // Skip the pending blocks the ledger has already advanced with, e.g. from the CDN.
+        let latest_height = self.canon.latest_block_height();
+        // Ensure the pending blocks extend the latest block, in case the ledger was rolled back.
+        let mut previous_hash = self.canon.get_block_hash(latest_height)?;
+        let mut responses = self.responses.write();
+        let mut num_blocks = 0;
+        for (height, block) in blocks.range(latest_height + 1..) {
+            if *height != latest_height + 1 + num_blocks {
                 break;
             }
-            // Check the next block.
-            if let Err(error) = self.canon.check_next_block(&block) {
-                warn!("The next block ({}) is invalid - {error}", block.height());
+            if block.previous_hash() != previous_hash {
+                warn!("Discarding the pending blocks from block {height}, as they do not extend the ledger");
                 break;
             }
-            // Attempt to advance to the next block.
-            if let Err(error) = self.canon.advance_to_next_block(&block) {
+            if let Err(error) = self.invalid_blocks.check_block(block) {
+                warn!("Discarding the pending blocks from block {height} - {error}");
+                break;
+            }
+            previous_hash = block.hash();
+            responses.insert(*height, block.clone());
+            num_blocks += 1;
+        }
+        drop(responses);
+
+        // Keep only the loaded blocks in the journal, and journal the received blocks from now on.
+        journal.compact(|| self.contiguous_block_responses())?;
+        let _ = self.journal.set(journal);
+
+        // Wake the verification pipeline, as the pending blocks are ready to be verified.
+        if num_blocks > 0 {
+            self.pending_blocks.notify_one();
+        }
+        Ok(num_blocks as usize)
+    }
+
+    /// Returns the block responses that extend the ledger, in ascending height order.
+    fn contiguous_block_responses(&self) -> Vec<Block<N>> {
+        // Note: If the ledger advances meanwhile, the blocks it advanced with are skipped when loading.
+        let start_height = self.canon.latest_block_height() + 1;
+        self.responses
+            .read()
+            .range(start_height..)
+            .zip(start_height..)
+            .take_while(|((height, _), expected_height)| *height == expected_height)
+            .map(|(_, block)| block.clone())
+            .collect()
+    }
+
+    /// Appends the given block to the journal of the received blocks, if enabled, and compacts the journal when due.
+    fn journal_block(&self, block: &Block<N>) {
+        let Some(journal) = self.journal.get() else { return };
+        if let Err(error) = journal.append(block) {
+            warn!("Failed to journal the pending block {} - {error}", block.height());
+        }
+        if journal.is_compaction_due() {
+            if let Err(error) = journal.compact(|| self.contiguous_block_responses()) {
+                warn!("Failed to compact the pending blocks - {error}");
+            }
+        }
+    }
+
+    /// Handles the block responses from the sync pool.
+    fn try_advancing_with_block_responses(&self, mut current_height: u32) {
+        while let Some(block) = self.remove_block_response(current_height + 1) {
+            if let Err(error) = self.try_advancing_with_block(block) {
                 warn!("{error}");
                 break;
             }
@@ -366,6 +612,34 @@ impl<N: Network> BlockSync<N> {
             current_height = self.canon.latest_block_height();
         }
     }
+
+    /// Checks the given block, and advances the ledger with it, if it is the valid next block.
+    fn try_advancing_with_block(&self, block: Block<N>) -> Result<()> {
+        // Ensure the block height matches.
+        let current_height = self.canon.latest_block_height();
+        if block.height() != current_height + 1 {
+            bail!("Block height mismatch: expected {}, found {}", current_height + 1, block.height());
+        }
+        let _span = info_span!("sync_block", height = block.height()).entered();
+        // Check the next block, which is only linked to the latest block if it is covered by the checkpoint.
+        let result = match self.checkpoint.filter(|checkpoint| checkpoint.covers(block.height())) {
+            Some(checkpoint) => self
+                .canon
+                .get_block_hash(current_height)
+                .and_then(|previous_hash| checkpoint.check_block(&block, previous_hash)),
+            None => self.canon.check_next_block(&block),
+        };
+        if let Err(error) = result {
+            bail!("The next block ({}) is invalid - {error}", block.height());
+        }
+        // Attempt to advance to the next block.
+        self.canon.advance_to_next_block(&block)?;
+        // Log when the checkpoint is reached, as the subsequent blocks are fully verified.
+        if self.checkpoint.map_or(false, |checkpoint| checkpoint.height() == block.height()) {
+            info!("Reached the sync checkpoint at block {}", block.height());
+        }
+        Ok(())
+    }
 }
 
 impl<N: Network> BlockSync<N> {
@@ -384,6 +658,39 @@ impl<N: Network> BlockSync<N> {
         }
     }
 
+    /// Returns the block hash at the given height, if the sync peers agree on it in their block locators.
+    pub fn find_sync_peer_hash(&self, height: u32) -> Option<N::BlockHash> {
+        let (sync_peers, _) = self.find_sync_peers_inner()?;
+        let mut hashes = sync_peers.values().filter_map(|locators| locators.get_hash(height));
+        let hash = hashes.next()?;
+        hashes.all(|other| other == hash).then_some(hash)
+    }
+
+    /// Returns the number of peers with block locators.
+    pub fn num_peers_with_locators(&self) -> usize {
+        self.locators.read().len()
+    }
+
+    /// Returns the peers whose block locators disagree with the canonical ledger of this node, i.e. that are on a fork.
+    /// Note: As each block hash commits to the history before it, a peer is only compared at its latest locator
+    /// that this node has a block for.
+    pub fn find_forked_peers(&self) -> Vec<SocketAddr> {
+        let latest_canon_height = self.canon.latest_block_height();
+        self.locators
+            .read()
+            .iter()
+            .filter_map(|(peer_ip, locators)| {
+                let (height, hash) = locators
+                    .clone()
+                    .into_iter()
+                    .filter(|(height, _)| *height <= latest_canon_height)
+                    .max_by_key(|(height, _)| *height)?;
+                let canon_hash = self.canon.get_block_hash(height).ok()?;
+                (canon_hash != hash).then_some(*peer_ip)
+            })
+            .collect()
+    }
+
     /// Updates the block locators and common ancestors for the given peer IP.
     /// This function checks that the given block locators are well-formed, however it does **not** check
     /// that the block locators are consistent the peer's previous block locators or other peers' block locators.
@@ -395,16 +702,30 @@ impl<N: Network> BlockSync<N> {
 
         // Ensure the given block locators are well-formed.
         locators.ensure_is_valid()?;
+        // Ensure the given block locators agree with the trusted checkpoint, if set.
+        if let Some(checkpoint) = &self.checkpoint {
+            checkpoint.check_locators(&locators)?;
+        }
+        // Ensure the given block locators do not include a block marked as invalid.
+        self.invalid_blocks.check_locators(&locators)?;
         // Update the locators entry for the given peer IP.
         self.locators.write().insert(peer_ip, locators.clone());
 
+        // Update the index of the canonical chain, which serves the block hashes at the locator heights.
+        if let Err(error) = self.ancestors.update(&*self.canon) {
+            warn!("Failed to update the index of the canonical chain - {error}");
+        }
+
         // Compute the common ancestor with this node.
         // Attention: Please do not optimize this loop, as it performs fork-detection. In addition,
         // by iterating upwards, it also early-terminates malicious block locators at the *first* point
         // of bifurcation in their ledger history, which is a critical safety guarantee provided here.
         let mut ancestor = 0;
         for (height, hash) in locators.clone().into_iter() {
-            if let Ok(canon_hash) = self.canon.get_block_hash(height) {
+            // Retrieve the canonical block hash from the index, and only fall back to the ledger
+            // for the heights that are not indexed, i.e. the recent blocks of a peer that is behind.
+            if let Some(canon_hash) = self.ancestors.get_hash(height).or_else(|| self.canon.get_block_hash(height).ok())
+            {
                 match canon_hash == hash {
                     true => ancestor = height,
                     false => break, // fork
@@ -437,12 +758,19 @@ impl<N: Network> BlockSync<N> {
         Ok(())
     }
 
+    /// Updates the smoothed round-trip time for the given peer IP.
+    pub fn update_peer_latency(&self, peer_ip: SocketAddr, rtt: Duration) {
+        self.latencies.write().insert(peer_ip, rtt);
+    }
+
     /// TODO (howardwu): Remove the `common_ancestor` entry. But check that this is safe
     ///  (that we don't rely upon it for safety when we re-connect with the same peer).
     /// Removes the peer from the sync pool, if they exist.
     pub fn remove_peer(&self, peer_ip: &SocketAddr) {
         // Remove the locators entry for the given peer IP.
         self.locators.write().swap_remove(peer_ip);
+        // Remove the latency entry for the given peer IP.
+        self.latencies.write().swap_remove(peer_ip);
         // Remove all block requests to the peer.
         self.remove_block_requests_to_peer(peer_ip);
     }
@@ -488,9 +816,15 @@ impl<N: Network> BlockSync<N> {
         self.num_blocks_behind.store(num_blocks_behind, Ordering::SeqCst);
         // Update the sync status.
         self.is_block_synced.store(is_synced, Ordering::SeqCst);
-        // Update the `IS_SYNCED` metric.
+        // Update the `IS_SYNCED` metric, and the sync metrics.
         #[cfg(feature = "metrics")]
-        metrics::gauge(metrics::bft::IS_SYNCED, is_synced);
+        {
+            metrics::gauge(metrics::bft::IS_SYNCED, is_synced);
+            metrics::gauge(metrics::sync::BLOCKS_BEHIND, num_blocks_behind as f64);
+            metrics::gauge(metrics::sync::BLOCK_REQUESTS, self.requests.read().len() as f64);
+            metrics::gauge(metrics::sync::CANON_HEIGHT, canon_height as f64);
+            metrics::gauge(metrics::sync::GREATEST_PEER_HEIGHT, greatest_peer_height as f64);
+        }
     }
 
     /// Inserts a block request for the given height.
@@ -539,7 +873,12 @@ impl<N: Network> BlockSync<N> {
                 self.remove_block_requests_to_peer(&peer_ip);
                 bail!("Candidate block {height} from '{peer_ip}' is malformed");
             }
+            return Ok(());
         }
+        // Drop the write lock on the responses map.
+        drop(responses);
+        // Journal the new candidate block, so that it is not requested again after a restart.
+        self.journal_block(&block);
 
         Ok(())
     }
@@ -569,6 +908,8 @@ impl<N: Network> BlockSync<N> {
     fn check_block_response(&self, peer_ip: &SocketAddr, block: &Block<N>) -> Result<()> {
         // Retrieve the block height.
         let height = block.height();
+        // Ensure the candidate block was not marked as invalid.
+        self.invalid_blocks.check_block(block)?;
 
         // Retrieve the request entry for the candidate block.
         if let Some((expected_hash, expected_previous_hash, sync_ips)) = self.requests.read().get(&height) {
@@ -723,7 +1064,9 @@ impl<N: Network> BlockSync<N> {
             .locators
             .read()
             .iter()
-            .filter(|(_, locators)| locators.latest_locator_height() > latest_canon_height)
+            .filter(|(peer_ip, locators)| {
+                self.is_allowed_sync_peer(peer_ip) && locators.latest_locator_height() > latest_canon_height
+            })
             .sorted_by(|(_, a), (_, b)| b.latest_locator_height().cmp(&a.latest_locator_height()))
             .take(NUM_SYNC_CANDIDATE_PEERS)
             .map(|(peer_ip, locators)| (*peer_ip, locators.clone()))
@@ -791,6 +1134,42 @@ impl<N: Network> BlockSync<N> {
         Some((sync_peers, min_common_ancestor))
     }
 
+    /// Returns up to `num_sync_ips` sync IPs, sampled at random from the least loaded, faster half of the sync peers.
+    /// Ranking the peers by their outstanding block requests first spreads consecutive height ranges across the
+    /// sync peers, so that they are downloaded concurrently; the peers with `MAX_BLOCK_REQUESTS_PER_PEER` outstanding
+    /// requests are skipped. Sampling (rather than always picking the fastest peers) spreads the load, and keeps
+    /// the node from depending on a single low-latency peer. Peers without a measured latency are ranked last.
+    fn select_sync_ips<R: Rng>(
+        &self,
+        sync_peers: &IndexMap<SocketAddr, BlockLocators<N>>,
+        num_sync_ips: usize,
+        rng: &mut R,
+    ) -> IndexSet<SocketAddr> {
+        // Count the outstanding block requests to each sync peer.
+        let blocks_per_request = DataBlocks::<N>::MAXIMUM_NUMBER_OF_BLOCKS as usize;
+        let mut num_requests = HashMap::<SocketAddr, usize>::new();
+        for (_, _, sync_ips) in self.requests.read().values() {
+            for sync_ip in sync_ips {
+                *num_requests.entry(*sync_ip).or_default() += 1;
+            }
+        }
+        let num_requests =
+            |peer_ip: &SocketAddr| num_requests.get(peer_ip).copied().unwrap_or(0).div_ceil(blocks_per_request);
+
+        // Rank the available sync peers by their outstanding block requests, and then by their round-trip time.
+        let latencies = self.latencies.read();
+        let ranked_ips = sync_peers
+            .keys()
+            .copied()
+            .filter(|peer_ip| num_requests(peer_ip) < MAX_BLOCK_REQUESTS_PER_PEER)
+            .sorted_by_key(|peer_ip| (num_requests(peer_ip), latencies.get(peer_ip).copied().unwrap_or(Duration::MAX)))
+            .collect_vec();
+        // Determine the number of least loaded, low-latency peers to sample from.
+        let num_candidates = num_sync_ips.max(ranked_ips.len().div_ceil(2));
+        // Sample the sync IPs from the least loaded, low-latency peers.
+        ranked_ips.into_iter().take(num_candidates).choose_multiple(rng, num_sync_ips).into_iter().collect()
+    }
+
     /// Given the sync peers and their minimum common ancestor, return a list of block requests.
     fn construct_requests(
         &self,
@@ -948,7 +1327,7 @@ mod tests {
         NUM_RECENT_BLOCKS,
     };
     use snarkos_node_bft_ledger_service::MockLedgerService;
-    use snarkvm::prelude::{Field, TestRng};
+    use snarkvm::prelude::{Field, FromBytes, TestRng};
 
     use indexmap::{indexset, IndexSet};
     use snarkvm::ledger::committee::Committee;
@@ -975,7 +1354,7 @@ mod tests {
 
     /// Returns the sync pool, with the canonical ledger initialized to the given height.
     fn sample_sync_at_height(height: u32) -> BlockSync<CurrentNetwork> {
-        BlockSync::<CurrentNetwork>::new(BlockSyncMode::Router, Arc::new(sample_ledger_service(height)))
+        BlockSync::<CurrentNetwork>::new(BlockSyncMode::Router, Arc::new(sample_ledger_service(height)), &[])
     }
 
     /// Checks that the sync pool (starting at genesis) returns the correct requests.
@@ -1318,6 +1697,162 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_select_sync_ips_prefers_low_latency() {
+        let rng = &mut TestRng::default();
+
+        let sync = sample_sync_at_height(0);
+
+        // Add 6 peers, where the peer ID is proportional to its latency.
+        let mut sync_peers = IndexMap::new();
+        for peer_id in 1..=6 {
+            let peer_ip = sample_peer_ip(peer_id);
+            sync.update_peer_locators(peer_ip, sample_block_locators(10)).unwrap();
+            sync.update_peer_latency(peer_ip, Duration::from_millis(peer_id as u64 * 50));
+            sync_peers.insert(peer_ip, sample_block_locators(10));
+        }
+
+        // Ensure the sync IPs are always sampled from the faster half of the peers.
+        let fastest: IndexSet<_> = (1..=3).map(sample_peer_ip).collect();
+        for _ in 0..100 {
+            let sync_ips = sync.select_sync_ips(&sync_peers, 1, rng);
+            assert_eq!(sync_ips.len(), 1);
+            assert!(fastest.is_superset(&sync_ips));
+        }
+
+        // Ensure all peers are selected if requested.
+        assert_eq!(sync.select_sync_ips(&sync_peers, 6, rng).len(), 6);
+
+        // Ensure the latency is removed along with the peer.
+        sync.remove_peer(&sample_peer_ip(1));
+        assert!(!sync.latencies.read().contains_key(&sample_peer_ip(1)));
+    }
+
+    #[test]
+    fn test_select_sync_ips_spreads_load() {
+        let rng = &mut TestRng::default();
+
+        let sync = sample_sync_at_height(0);
+
+        // Add 2 peers, where the first one has the lower latency.
+        let mut sync_peers = IndexMap::new();
+        for peer_id in 1..=2 {
+            let peer_ip = sample_peer_ip(peer_id);
+            sync.update_peer_locators(peer_ip, sample_block_locators(100)).unwrap();
+            sync.update_peer_latency(peer_ip, Duration::from_millis(peer_id as u64 * 50));
+            sync_peers.insert(peer_ip, sample_block_locators(100));
+        }
+
+        // Ensure consecutive block requests alternate between the peers, despite their latencies.
+        let blocks_per_request = DataBlocks::<CurrentNetwork>::MAXIMUM_NUMBER_OF_BLOCKS as u32;
+        for request_id in 0..MAX_BLOCK_REQUESTS_PER_PEER as u32 * 2 {
+            let sync_ips = sync.select_sync_ips(&sync_peers, 1, rng);
+            assert_eq!(sync_ips, indexset![sample_peer_ip(1 + request_id as u16 % 2)]);
+            // Insert the block request.
+            for height in 1 + request_id * blocks_per_request..1 + (request_id + 1) * blocks_per_request {
+                sync.insert_block_request(height, (None, None, sync_ips.clone())).unwrap();
+            }
+        }
+
+        // Ensure no sync IPs are selected once every peer is saturated.
+        assert!(sync.select_sync_ips(&sync_peers, 1, rng).is_empty());
+    }
+
+    #[test]
+    fn test_find_sync_peers_with_trusted_sync_peers() {
+        let trusted_ip = sample_peer_ip(2);
+        let sync =
+            BlockSync::<CurrentNetwork>::new(BlockSyncMode::Router, Arc::new(sample_ledger_service(0)), &[trusted_ip]);
+        assert!(sync.is_allowed_sync_peer(&trusted_ip));
+        assert!(!sync.is_allowed_sync_peer(&sample_peer_ip(1)));
+
+        // Add 3 peers, where the trusted peer is behind the others.
+        sync.update_peer_locators(sample_peer_ip(1), sample_block_locators(20)).unwrap();
+        sync.update_peer_locators(trusted_ip, sample_block_locators(10)).unwrap();
+        sync.update_peer_locators(sample_peer_ip(3), sample_block_locators(20)).unwrap();
+
+        // Ensure only the trusted peer is selected for syncing.
+        let (sync_peers, min_common_ancestor) = sync.find_sync_peers_inner().unwrap();
+        assert_eq!(sync_peers.keys().copied().collect::<Vec<_>>(), vec![trusted_ip]);
+        assert_eq!(min_common_ancestor, 10);
+
+        // Ensure the block requests are only prepared for the trusted peer.
+        let (requests, sync_peers) = sync.prepare_block_requests();
+        assert_eq!(requests.len(), 10);
+        assert_eq!(sync_peers.keys().copied().collect::<Vec<_>>(), vec![trusted_ip]);
+    }
+
+    #[tokio::test]
+    async fn test_execution_stage_discards_stale_blocks() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let sync = sample_sync_at_height(0);
+        let (sender, receiver) = mpsc::channel(MAX_VERIFIED_BLOCKS);
+        let restart = Arc::new(AtomicBool::new(false));
+
+        // Ensure a queued block that does not extend the ledger is discarded, without restarting the verification.
+        sender.send(genesis).await.unwrap();
+        drop(sender);
+        sync.run_execution_stage(receiver, restart.clone()).await;
+        assert_eq!(sync.canon.latest_block_height(), 0);
+        assert!(!restart.load(Ordering::Acquire));
+    }
+
+    #[test]
+    fn test_find_sync_peer_hash() {
+        let sync = sample_sync_at_height(0);
+        assert_eq!(sync.find_sync_peer_hash(10), None);
+
+        // Add 2 peers at the same height.
+        sync.update_peer_locators(sample_peer_ip(1), sample_block_locators(20)).unwrap();
+        sync.update_peer_locators(sample_peer_ip(2), sample_block_locators(20)).unwrap();
+
+        // Ensure the hashes in the recent block locators are found, and the others are not.
+        assert_eq!(sync.find_sync_peer_hash(19), sample_block_locators(20).get_hash(19));
+        assert!(sync.find_sync_peer_hash(19).is_some());
+        assert_eq!(sync.find_sync_peer_hash(21), None);
+    }
+
+    #[test]
+    fn test_find_forked_peers() {
+        let sync = sample_sync_at_height(15);
+        assert!(sync.find_forked_peers().is_empty());
+
+        // Add a peer on the same chain, and a peer that forked at block 10.
+        sync.update_peer_locators(sample_peer_ip(1), sample_block_locators(20)).unwrap();
+        sync.update_peer_locators(sample_peer_ip(2), sample_block_locators_with_fork(20, 10)).unwrap();
+        assert_eq!(sync.num_peers_with_locators(), 2);
+
+        // Ensure only the peer on the fork is found.
+        assert_eq!(sync.find_forked_peers(), vec![sample_peer_ip(2)]);
+
+        // Ensure a peer that forked above the height of this node is not found.
+        sync.update_peer_locators(sample_peer_ip(3), sample_block_locators_with_fork(20, 16)).unwrap();
+        assert_eq!(sync.find_forked_peers(), vec![sample_peer_ip(2)]);
+    }
+
+    #[test]
+    fn test_pending_blocks() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let path = std::env::temp_dir().join(format!("snarkos-sync-pending-{}", rand::thread_rng().gen::<u64>()));
+
+        // Ensure there is nothing to save or load without pending blocks.
+        let sync = sample_sync_at_height(0);
+        assert_eq!(sync.save_pending_blocks().unwrap(), 0);
+        assert_eq!(sync.load_pending_blocks(&path).unwrap(), 0);
+        assert!(sync.load_pending_blocks(&path).is_err());
+
+        // Ensure the received blocks are journaled right away.
+        sync.journal_block(&genesis);
+        assert_eq!(PendingBlocks::<CurrentNetwork>::open(&path).unwrap().1, vec![genesis]);
+
+        // Ensure the pending blocks the ledger already advanced with are skipped, and removed from the journal.
+        let sync = sample_sync_at_height(0);
+        assert_eq!(sync.load_pending_blocks(&path).unwrap(), 0);
+        assert!(sync.responses.read().is_empty());
+        assert!(PendingBlocks::<CurrentNetwork>::open(&path).unwrap().1.is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+
     #[test]
     fn test_remove_peer() {
         let sync = sample_sync_at_height(0);
@@ -0,0 +1,145 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::locators::{BlockLocators, CHECKPOINT_INTERVAL};
+use snarkvm::prelude::{block::Block, Network};
+
+use anyhow::{anyhow, bail, ensure, Error, Result};
+use core::{fmt, str::FromStr};
+
+/// A trusted checkpoint, which lets a new node sync the blocks below it without fully verifying them.
+///
+/// The blocks below the checkpoint are still downloaded and linked by their hashes, and the block
+/// at the checkpoint height must match the trusted hash. Only the peers whose block locators agree
+/// with the checkpoint are synced from, which is why the height must be a locator checkpoint height.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SyncCheckpoint<N: Network> {
+    /// The height of the checkpoint.
+    height: u32,
+    /// The hash of the block at the checkpoint height.
+    hash: N::BlockHash,
+}
+
+impl<N: Network> SyncCheckpoint<N> {
+    /// Initializes a new sync checkpoint.
+    pub fn new(height: u32, hash: N::BlockHash) -> Result<Self> {
+        ensure!(height > 0, "The sync checkpoint must be above the genesis block");
+        ensure!(
+            height % CHECKPOINT_INTERVAL == 0,
+            "The sync checkpoint height must be a multiple of {CHECKPOINT_INTERVAL} (found {height})"
+        );
+        Ok(Self { height, hash })
+    }
+
+    /// Returns the height of the checkpoint.
+    pub const fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the hash of the block at the checkpoint height.
+    pub const fn hash(&self) -> N::BlockHash {
+        self.hash
+    }
+
+    /// Returns `true` if the block at the given height is covered by the checkpoint.
+    pub const fn covers(&self, height: u32) -> bool {
+        height <= self.height
+    }
+
+    /// Ensures the given block extends the block with the given hash, and matches the checkpoint at its height.
+    pub fn check_block(&self, block: &Block<N>, previous_hash: N::BlockHash) -> Result<()> {
+        // Ensure the block links to the latest block.
+        if block.previous_hash() != previous_hash {
+            bail!("Block {} does not extend the latest block ({previous_hash})", block.height())
+        }
+        // Ensure the block at the checkpoint height is the trusted one.
+        if block.height() == self.height && block.hash() != self.hash {
+            bail!("Block {} does not match the sync checkpoint ({self})", block.height())
+        }
+        Ok(())
+    }
+
+    /// Ensures the given block locators agree with the checkpoint.
+    pub fn check_locators(&self, locators: &BlockLocators<N>) -> Result<()> {
+        match locators.get_hash(self.height) {
+            Some(hash) if hash != self.hash => bail!("The block locators conflict with the sync checkpoint ({self})"),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<N: Network> FromStr for SyncCheckpoint<N> {
+    type Err = Error;
+
+    /// Parses a sync checkpoint of the form `<height>:<hash>`.
+    fn from_str(checkpoint: &str) -> Result<Self> {
+        let Some((height, hash)) = checkpoint.split_once(':') else {
+            bail!("The sync checkpoint must be of the form '<height>:<hash>' (found '{checkpoint}')")
+        };
+        let height = height.trim().parse().map_err(|_| anyhow!("Invalid sync checkpoint height '{height}'"))?;
+        let hash = hash.trim().parse().map_err(|_| anyhow!("Invalid sync checkpoint hash '{hash}'"))?;
+        Self::new(height, hash)
+    }
+}
+
+impl<N: Network> fmt::Display for SyncCheckpoint<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.height, self.hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locators::test_helpers::sample_block_locators;
+    use snarkvm::prelude::Field;
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    /// Returns the hash used by `sample_block_locators` at the given height.
+    fn sample_hash(height: u32) -> <CurrentNetwork as Network>::BlockHash {
+        Field::<CurrentNetwork>::from_u32(height).into()
+    }
+
+    #[test]
+    fn test_sync_checkpoint_from_str() {
+        let checkpoint = SyncCheckpoint::<CurrentNetwork>::new(CHECKPOINT_INTERVAL, sample_hash(1)).unwrap();
+        assert_eq!(checkpoint.to_string().parse::<SyncCheckpoint<CurrentNetwork>>().unwrap(), checkpoint);
+
+        // Ensure malformed checkpoints are rejected.
+        let hash = sample_hash(1);
+        assert!(format!("{CHECKPOINT_INTERVAL}").parse::<SyncCheckpoint<CurrentNetwork>>().is_err());
+        assert!(format!("abc:{hash}").parse::<SyncCheckpoint<CurrentNetwork>>().is_err());
+        assert!(format!("{CHECKPOINT_INTERVAL}:abc").parse::<SyncCheckpoint<CurrentNetwork>>().is_err());
+        // Ensure the height must be a locator checkpoint height.
+        assert!(format!("0:{hash}").parse::<SyncCheckpoint<CurrentNetwork>>().is_err());
+        assert!(format!("{}:{hash}", CHECKPOINT_INTERVAL + 1).parse::<SyncCheckpoint<CurrentNetwork>>().is_err());
+    }
+
+    #[test]
+    fn test_sync_checkpoint_locators() {
+        let height = CHECKPOINT_INTERVAL;
+        let checkpoint = SyncCheckpoint::<CurrentNetwork>::new(height, sample_hash(height)).unwrap();
+        assert!(checkpoint.covers(height));
+        assert!(!checkpoint.covers(height + 1));
+
+        // Ensure the locators that agree with the checkpoint, or do not reach it, are accepted.
+        assert!(checkpoint.check_locators(&sample_block_locators(height * 2)).is_ok());
+        assert!(checkpoint.check_locators(&sample_block_locators(height - 1)).is_ok());
+
+        // Ensure the locators that conflict with the checkpoint are rejected.
+        let checkpoint = SyncCheckpoint::<CurrentNetwork>::new(height, sample_hash(height + 1)).unwrap();
+        assert!(checkpoint.check_locators(&sample_block_locators(height * 2)).is_err());
+    }
+}
@@ -0,0 +1,125 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::locators::BlockLocators;
+use snarkvm::prelude::{block::Block, Network};
+
+use anyhow::{bail, Result};
+use indexmap::IndexSet;
+use std::{fs, io::ErrorKind, path::Path};
+
+/// The blocks an operator marked as invalid, which the node refuses to sync.
+///
+/// The peers whose block locators include an invalid block are not synced from,
+/// so that the node follows an alternative branch, if one exists.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidBlocks<N: Network> {
+    /// The hashes of the invalid blocks.
+    hashes: IndexSet<N::BlockHash>,
+}
+
+impl<N: Network> Default for InvalidBlocks<N> {
+    /// Initializes an empty set of invalid blocks.
+    fn default() -> Self {
+        Self { hashes: Default::default() }
+    }
+}
+
+impl<N: Network> InvalidBlocks<N> {
+    /// Loads the invalid blocks from the given file, with one block hash per line.
+    /// If the file does not exist, no block is invalid.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(error) => bail!("Failed to read the invalid blocks from {} - {error}", path.display()),
+        };
+        let mut invalid_blocks = Self::default();
+        for line in contents.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            match line.parse() {
+                Ok(hash) => invalid_blocks.insert(hash),
+                Err(error) => bail!("Invalid block hash '{line}' in {} - {error}", path.display()),
+            };
+        }
+        Ok(invalid_blocks)
+    }
+
+    /// Saves the invalid blocks to the given file, with one block hash per line.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let contents = self.hashes.iter().map(|hash| format!("{hash}\n")).collect::<String>();
+        Ok(fs::write(path, contents)?)
+    }
+
+    /// Marks the block with the given hash as invalid; returns `false` if it already was.
+    pub fn insert(&mut self, hash: N::BlockHash) -> bool {
+        self.hashes.insert(hash)
+    }
+
+    /// Returns `true` if the block with the given hash is invalid.
+    pub fn contains(&self, hash: &N::BlockHash) -> bool {
+        self.hashes.contains(hash)
+    }
+
+    /// Returns `true` if no block is invalid.
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Ensures the given block is not invalid.
+    pub fn check_block(&self, block: &Block<N>) -> Result<()> {
+        match self.contains(&block.hash()) {
+            true => bail!("Block {} ({}) was marked as invalid", block.height(), block.hash()),
+            false => Ok(()),
+        }
+    }
+
+    /// Ensures the given block locators do not include an invalid block.
+    pub fn check_locators(&self, locators: &BlockLocators<N>) -> Result<()> {
+        match locators.clone().into_iter().find(|(_, hash)| self.contains(hash)) {
+            Some((height, hash)) => {
+                bail!("The block locators include block {height} ({hash}), which was marked as invalid")
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{FromBytes, MainnetV0};
+
+    use rand::Rng;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_invalid_blocks() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let path = std::env::temp_dir().join(format!("snarkos-invalid-{}", rand::thread_rng().gen::<u64>()));
+
+        // Ensure a missing file means no block is invalid.
+        let mut invalid_blocks = InvalidBlocks::<CurrentNetwork>::load(&path).unwrap();
+        assert!(invalid_blocks.is_empty());
+        invalid_blocks.check_block(&genesis).unwrap();
+
+        // Ensure an invalid block is rejected, and survives a round trip through the disk.
+        assert!(invalid_blocks.insert(genesis.hash()));
+        assert!(!invalid_blocks.insert(genesis.hash()));
+        assert!(invalid_blocks.check_block(&genesis).is_err());
+        invalid_blocks.save(&path).unwrap();
+        assert_eq!(InvalidBlocks::load(&path).unwrap(), invalid_blocks);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -20,8 +20,20 @@ extern crate tracing;
 pub use snarkos_node_sync_communication_service as communication_service;
 pub use snarkos_node_sync_locators as locators;
 
+mod ancestors;
+pub use ancestors::*;
+
 mod block_sync;
 pub use block_sync::*;
 
+mod checkpoint;
+pub use checkpoint::*;
+
 mod helpers;
 pub use helpers::*;
+
+mod invalid;
+pub use invalid::*;
+
+mod pending;
+pub use pending::*;
@@ -0,0 +1,191 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{block::Block, FromBytes, Network, ToBytes};
+
+use anyhow::{anyhow, bail, Result};
+use parking_lot::Mutex;
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter, ErrorKind, Read, Write},
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// The number of journaled blocks beyond which the journal is compacted to the pending blocks.
+const MAX_JOURNALED_BLOCKS: usize = 1_024;
+
+/// The journal of the blocks the sync pool received, but did not yet advance the ledger with.
+///
+/// Every block is appended to the journal as it is received, so that on restart, even after a crash,
+/// the sync resumes from the journal, instead of requesting the blocks from the peers again.
+/// Each entry is length-prefixed, so that a partially written entry at the end of the journal is discarded.
+pub struct PendingBlocks<N: Network> {
+    /// The path of the journal.
+    path: PathBuf,
+    /// The writer that appends to the journal.
+    writer: Mutex<BufWriter<File>>,
+    /// The number of journaled blocks.
+    num_blocks: AtomicUsize,
+    _phantom: PhantomData<N>,
+}
+
+impl<N: Network> PendingBlocks<N> {
+    /// Opens the journal at the given path, creating it if it does not exist, and returns the journaled blocks.
+    /// The blocks are in the order they were received, so they may be out of order, duplicated, or stale.
+    pub fn open(path: &Path) -> Result<(Self, Vec<Block<N>>)> {
+        let (blocks, length) = match File::open(path) {
+            Ok(file) => read_blocks(BufReader::new(file)),
+            Err(error) if error.kind() == ErrorKind::NotFound => (vec![], 0),
+            Err(error) => bail!("Failed to read the pending blocks from {} - {error}", path.display()),
+        };
+        // Discard a partially written entry, so that the next entries are appended right after the valid ones.
+        let file = open_for_append(path)?;
+        file.set_len(length)?;
+        let journal = Self {
+            path: path.to_path_buf(),
+            writer: Mutex::new(BufWriter::new(file)),
+            num_blocks: AtomicUsize::new(blocks.len()),
+            _phantom: PhantomData,
+        };
+        Ok((journal, blocks))
+    }
+
+    /// Appends the given block to the journal.
+    pub fn append(&self, block: &Block<N>) -> Result<()> {
+        let mut writer = self.writer.lock();
+        write_block(&mut *writer, block)?;
+        // Hand the entry over to the OS, so that it survives the node being killed.
+        writer.flush()?;
+        self.num_blocks.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Returns `true` if the journal holds enough blocks to be compacted.
+    pub fn is_compaction_due(&self) -> bool {
+        self.len() > MAX_JOURNALED_BLOCKS
+    }
+
+    /// Rewrites the journal with the blocks returned by the given function, and returns their number.
+    /// The function is called while no block is appended, so that no block received meanwhile is lost.
+    pub fn compact(&self, blocks: impl FnOnce() -> Vec<Block<N>>) -> Result<usize> {
+        let mut writer = self.writer.lock();
+        let blocks = blocks();
+        // Write to a temporary file first, so that the journal is never truncated.
+        let temp_path = self.path.with_extension("tmp");
+        let mut temp_writer = BufWriter::new(File::create(&temp_path)?);
+        for block in &blocks {
+            write_block(&mut temp_writer, block)?;
+        }
+        temp_writer.into_inner().map_err(|error| anyhow!("{}", error.error()))?.sync_all()?;
+        fs::rename(&temp_path, &self.path)?;
+        *writer = BufWriter::new(open_for_append(&self.path)?);
+        self.num_blocks.store(blocks.len(), Ordering::Relaxed);
+        Ok(blocks.len())
+    }
+
+    /// Returns the number of journaled blocks.
+    pub fn len(&self) -> usize {
+        self.num_blocks.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if there are no journaled blocks.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Opens the journal at the given path for appending, creating it if it does not exist.
+fn open_for_append(path: &Path) -> Result<File> {
+    File::options()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|error| anyhow!("Failed to open the pending blocks in {} - {error}", path.display()))
+}
+
+/// Writes the given block as a length-prefixed entry.
+fn write_block<N: Network>(writer: &mut impl Write, block: &Block<N>) -> Result<()> {
+    let bytes = block.to_bytes_le()?;
+    (bytes.len() as u32).write_le(&mut *writer)?;
+    Ok(writer.write_all(&bytes)?)
+}
+
+/// Reads the journaled blocks, up to the first incomplete or malformed entry, if any.
+/// Returns the blocks, and the length of the entries they were read from.
+fn read_blocks<N: Network>(mut reader: impl Read) -> (Vec<Block<N>>, u64) {
+    let (mut blocks, mut length) = (vec![], 0);
+    loop {
+        let mut prefix = [0u8; 4];
+        if reader.read_exact(&mut prefix).is_err() {
+            break;
+        }
+        let num_bytes = u32::from_le_bytes(prefix) as u64;
+        let mut bytes = Vec::new();
+        let num_read = reader.by_ref().take(num_bytes).read_to_end(&mut bytes).unwrap_or_default();
+        if num_read as u64 != num_bytes {
+            warn!("Discarding a partially written pending block");
+            break;
+        }
+        match Block::from_bytes_le(&bytes) {
+            Ok(block) => blocks.push(block),
+            Err(error) => {
+                warn!("Discarding a malformed pending block - {error}");
+                break;
+            }
+        }
+        length += 4 + num_bytes;
+    }
+    (blocks, length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::MainnetV0;
+
+    use rand::Rng;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_pending_blocks() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let path = std::env::temp_dir().join(format!("snarkos-pending-{}", rand::thread_rng().gen::<u64>()));
+
+        // Ensure a missing journal means there are no pending blocks.
+        let (journal, blocks) = PendingBlocks::<CurrentNetwork>::open(&path).unwrap();
+        assert!(blocks.is_empty());
+
+        // Ensure the appended blocks survive a restart.
+        journal.append(&genesis).unwrap();
+        assert_eq!(journal.len(), 1);
+        drop(journal);
+        assert_eq!(PendingBlocks::<CurrentNetwork>::open(&path).unwrap().1, vec![genesis.clone()]);
+
+        // Ensure a partially written entry is discarded, as if the node was killed while appending.
+        File::options().append(true).open(&path).unwrap().write_all(&[1, 2, 3, 4, 5]).unwrap();
+        let (journal, blocks) = PendingBlocks::<CurrentNetwork>::open(&path).unwrap();
+        assert_eq!(blocks, vec![genesis.clone()]);
+        journal.append(&genesis).unwrap();
+        assert_eq!(PendingBlocks::<CurrentNetwork>::open(&path).unwrap().1, vec![genesis.clone(), genesis]);
+
+        // Ensure the compaction rewrites the journal.
+        assert_eq!(journal.compact(Vec::new).unwrap(), 0);
+        assert!(journal.is_empty());
+        assert!(PendingBlocks::<CurrentNetwork>::open(&path).unwrap().1.is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
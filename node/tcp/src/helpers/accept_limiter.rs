@@ -0,0 +1,324 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+use parking_lot::{Mutex, RwLock};
+
+/// The limits on the rate of inbound connection attempts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AcceptRateLimit {
+    /// The number of inbound connection attempts accepted per second, from all sources combined.
+    pub global_per_sec: u32,
+    /// The number of inbound connection attempts accepted per minute, from a single IP address.
+    pub per_ip_per_min: u32,
+    /// The number of consecutive rejected attempts after which a single IP address is temporarily banned.
+    pub ban_threshold: u32,
+    /// The duration for which an IP address is banned.
+    pub ban_duration: Duration,
+}
+
+impl Default for AcceptRateLimit {
+    fn default() -> Self {
+        Self { global_per_sec: 50, per_ip_per_min: 10, ban_threshold: 30, ban_duration: Duration::from_secs(600) }
+    }
+}
+
+/// The outcome of an inbound connection attempt, as decided by the [`AcceptLimiter`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AcceptDecision {
+    /// The connection attempt is accepted.
+    Accept,
+    /// The connection attempt exceeds the rate limit, and is dropped.
+    Throttle,
+    /// The connection attempt exceeds the rate limit, and the source is now banned.
+    Ban,
+    /// The source is currently banned, and the connection attempt is dropped.
+    Banned,
+}
+
+/// A token bucket, which refills at a constant rate up to its capacity.
+#[derive(Clone, Copy, Debug)]
+struct TokenBucket {
+    /// The number of available tokens.
+    tokens: f64,
+    /// The timestamp of the last refill.
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Initializes a full token bucket with the given capacity.
+    fn new(capacity: f64, now: Instant) -> Self {
+        Self { tokens: capacity, last_refill: now }
+    }
+
+    /// Refills the bucket at the given rate (in tokens per second), up to the given capacity.
+    fn refill(&mut self, rate: f64, capacity: f64, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(capacity);
+        self.last_refill = now;
+    }
+
+    /// Attempts to take a token from the bucket, and returns `true` if one was available.
+    fn try_take(&mut self, rate: f64, capacity: f64, now: Instant) -> bool {
+        self.refill(rate, capacity, now);
+        match self.tokens >= 1.0 {
+            true => {
+                self.tokens -= 1.0;
+                true
+            }
+            false => false,
+        }
+    }
+}
+
+/// The rate-limiting state of a single IP address.
+#[derive(Clone, Copy, Debug)]
+struct Source {
+    /// The token bucket of the source.
+    bucket: TokenBucket,
+    /// The number of consecutive rejected attempts.
+    num_rejected: u32,
+    /// The time until which the source is banned, if any.
+    banned_until: Option<Instant>,
+    /// The time of the last connection attempt of the source.
+    last_seen: Instant,
+}
+
+impl Source {
+    /// Returns `true` if the source is banned at the given time.
+    fn is_banned_at(&self, now: Instant) -> bool {
+        self.banned_until.map_or(false, |until| now < until)
+    }
+}
+
+/// Sheds the inbound connection attempts that exceed the configured [`AcceptRateLimit`], before any handshake.
+#[derive(Debug)]
+pub struct AcceptLimiter {
+    /// The rate limit, if any.
+    limit: RwLock<Option<AcceptRateLimit>>,
+    /// The token bucket shared by all sources.
+    global: Mutex<TokenBucket>,
+    /// The rate-limiting state of each IP address.
+    sources: Mutex<HashMap<IpAddr, Source>>,
+}
+
+impl Default for AcceptLimiter {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl AcceptLimiter {
+    /// The maximum number of IP addresses tracked at any given time.
+    const MAXIMUM_NUMBER_OF_SOURCES: usize = 10_000;
+
+    /// Initializes a new accept limiter with the given rate limit; `None` disables the rate limiting.
+    pub fn new(limit: Option<AcceptRateLimit>) -> Self {
+        let capacity = limit.map_or(0.0, |limit| limit.global_per_sec as f64);
+        Self {
+            limit: RwLock::new(limit),
+            global: Mutex::new(TokenBucket::new(capacity, Instant::now())),
+            sources: Default::default(),
+        }
+    }
+
+    /// Returns the rate limit, if any.
+    pub fn limit(&self) -> Option<AcceptRateLimit> {
+        *self.limit.read()
+    }
+
+    /// Updates the rate limit; `None` disables the rate limiting.
+    pub fn set_limit(&self, limit: Option<AcceptRateLimit>) {
+        *self.global.lock() = TokenBucket::new(limit.map_or(0.0, |limit| limit.global_per_sec as f64), Instant::now());
+        self.sources.lock().clear();
+        *self.limit.write() = limit;
+    }
+
+    /// Returns `true` if the given IP address is currently banned.
+    pub fn is_banned(&self, ip: IpAddr) -> bool {
+        let now = Instant::now();
+        self.sources.lock().get(&ip).and_then(|source| source.banned_until).map_or(false, |until| now < until)
+    }
+
+    /// Decides whether to accept an inbound connection attempt from the given IP address.
+    pub fn check(&self, ip: IpAddr) -> AcceptDecision {
+        self.check_at(ip, Instant::now())
+    }
+
+    /// Decides whether to accept an inbound connection attempt from the given IP address at the given time.
+    fn check_at(&self, ip: IpAddr, now: Instant) -> AcceptDecision {
+        // If there is no rate limit, accept the attempt.
+        let Some(limit) = self.limit() else {
+            return AcceptDecision::Accept;
+        };
+        let per_ip_capacity = limit.per_ip_per_min as f64;
+        let per_ip_rate = per_ip_capacity / 60.0;
+
+        let mut sources = self.sources.lock();
+        // If too many sources are tracked, forget the ones that are neither banned nor throttled.
+        if sources.len() >= Self::MAXIMUM_NUMBER_OF_SOURCES && !sources.contains_key(&ip) {
+            sources.retain(|_, source| {
+                source.bucket.refill(per_ip_rate, per_ip_capacity, now);
+                source.is_banned_at(now) || source.bucket.tokens < per_ip_capacity
+            });
+            // If the sources are still saturated, evict the one that has been idle the longest, preferring
+            // the sources that are not banned, so that new sources are never shed for lack of space.
+            if sources.len() >= Self::MAXIMUM_NUMBER_OF_SOURCES {
+                let evicted = sources
+                    .iter()
+                    .min_by_key(|(_, source)| (source.is_banned_at(now), source.last_seen))
+                    .map(|(evicted_ip, _)| *evicted_ip);
+                if let Some(evicted_ip) = evicted {
+                    sources.remove(&evicted_ip);
+                }
+            }
+        }
+        let source = sources.entry(ip).or_insert_with(|| Source {
+            bucket: TokenBucket::new(per_ip_capacity, now),
+            num_rejected: 0,
+            banned_until: None,
+            last_seen: now,
+        });
+        source.last_seen = now;
+
+        // Drop the attempt if the source is banned.
+        match source.banned_until {
+            Some(until) if now < until => return AcceptDecision::Banned,
+            Some(_) => source.banned_until = None,
+            None => (),
+        }
+        // Apply the per-IP rate limit, and ban the source if it persists.
+        if !source.bucket.try_take(per_ip_rate, per_ip_capacity, now) {
+            source.num_rejected = source.num_rejected.saturating_add(1);
+            if source.num_rejected < limit.ban_threshold {
+                return AcceptDecision::Throttle;
+            }
+            source.num_rejected = 0;
+            source.banned_until = Some(now + limit.ban_duration);
+            return AcceptDecision::Ban;
+        }
+        source.num_rejected = 0;
+        drop(sources);
+
+        // Apply the global rate limit.
+        let global_capacity = limit.global_per_sec as f64;
+        match self.global.lock().try_take(global_capacity, global_capacity, now) {
+            true => AcceptDecision::Accept,
+            false => AcceptDecision::Throttle,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::net::Ipv4Addr;
+
+    fn sample_limit() -> AcceptRateLimit {
+        AcceptRateLimit {
+            global_per_sec: 100,
+            per_ip_per_min: 6,
+            ban_threshold: 3,
+            ban_duration: Duration::from_secs(60),
+        }
+    }
+
+    #[test]
+    fn test_disabled() {
+        let limiter = AcceptLimiter::default();
+        let ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        for _ in 0..1_000 {
+            assert_eq!(limiter.check(ip), AcceptDecision::Accept);
+        }
+    }
+
+    #[test]
+    fn test_per_ip_limit_and_ban() {
+        let limiter = AcceptLimiter::new(Some(sample_limit()));
+        let ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        let other_ip = IpAddr::V4(Ipv4Addr::new(5, 6, 7, 8));
+        let now = Instant::now();
+
+        // The burst is accepted, and the following attempts are throttled.
+        for _ in 0..6 {
+            assert_eq!(limiter.check_at(ip, now), AcceptDecision::Accept);
+        }
+        assert_eq!(limiter.check_at(ip, now), AcceptDecision::Throttle);
+        assert_eq!(limiter.check_at(ip, now), AcceptDecision::Throttle);
+        // Other sources are unaffected.
+        assert_eq!(limiter.check_at(other_ip, now), AcceptDecision::Accept);
+
+        // A token is refilled every 10 seconds.
+        let now = now + Duration::from_secs(10);
+        assert_eq!(limiter.check_at(ip, now), AcceptDecision::Accept);
+
+        // A persistent source is banned.
+        assert_eq!(limiter.check_at(ip, now), AcceptDecision::Throttle);
+        assert_eq!(limiter.check_at(ip, now), AcceptDecision::Throttle);
+        assert_eq!(limiter.check_at(ip, now), AcceptDecision::Ban);
+        let now = now + Duration::from_secs(30);
+        assert_eq!(limiter.check_at(ip, now), AcceptDecision::Banned);
+
+        // The ban expires.
+        let now = now + Duration::from_secs(31);
+        assert_eq!(limiter.check_at(ip, now), AcceptDecision::Accept);
+    }
+
+    #[test]
+    fn test_global_limit() {
+        let limit = AcceptRateLimit { global_per_sec: 3, ..sample_limit() };
+        let limiter = AcceptLimiter::new(Some(limit));
+        let now = Instant::now();
+
+        // The global burst is shared by all sources.
+        for i in 0..3 {
+            assert_eq!(limiter.check_at(IpAddr::V4(Ipv4Addr::new(1, 1, 1, i)), now), AcceptDecision::Accept);
+        }
+        assert_eq!(limiter.check_at(IpAddr::V4(Ipv4Addr::new(1, 1, 1, 3)), now), AcceptDecision::Throttle);
+
+        // The global bucket refills every second.
+        let now = now + Duration::from_secs(1);
+        assert_eq!(limiter.check_at(IpAddr::V4(Ipv4Addr::new(1, 1, 1, 4)), now), AcceptDecision::Accept);
+    }
+
+    #[test]
+    fn test_evicts_idle_sources() {
+        let limit = AcceptRateLimit { global_per_sec: u32::MAX, ..sample_limit() };
+        let limiter = AcceptLimiter::new(Some(limit));
+        let ip = |i: usize| IpAddr::V4(Ipv4Addr::from(0x0100_0000 + i as u32));
+        let now = Instant::now();
+
+        // Saturate the tracked sources, so that none of them can be forgotten.
+        for i in 0..AcceptLimiter::MAXIMUM_NUMBER_OF_SOURCES {
+            let now = now + Duration::from_millis(i as u64);
+            assert_eq!(limiter.check_at(ip(i), now), AcceptDecision::Accept);
+        }
+
+        // A new source is accepted, and the source that has been idle the longest is evicted.
+        let now = now + Duration::from_secs(1);
+        let new_ip = ip(AcceptLimiter::MAXIMUM_NUMBER_OF_SOURCES);
+        assert_eq!(limiter.check_at(new_ip, now), AcceptDecision::Accept);
+        let sources = limiter.sources.lock();
+        assert_eq!(sources.len(), AcceptLimiter::MAXIMUM_NUMBER_OF_SOURCES);
+        assert!(!sources.contains_key(&ip(0)));
+        assert!(sources.contains_key(&ip(1)));
+        assert!(sources.contains_key(&new_ip));
+    }
+}
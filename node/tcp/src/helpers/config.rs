@@ -17,6 +17,8 @@ use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
 };
 
+use crate::AcceptRateLimit;
+
 #[cfg(doc)]
 use crate::protocols::{self, Handshake, Reading, Writing};
 
@@ -54,6 +56,19 @@ pub struct Config {
     pub max_connections: u16,
     /// The maximum time (in milliseconds) allowed to establish a raw (before the [`Handshake`] protocol) TCP connection.
     pub connection_timeout_ms: u16,
+    /// The idle time (in seconds) after which TCP keep-alive probes are sent on a connection.
+    ///
+    /// note: If set to `None`, TCP keep-alive is left at the OS default (usually disabled).
+    pub keepalive_secs: Option<u32>,
+    /// The limits on the rate of inbound connection attempts, which are enforced before the [`Handshake`] protocol.
+    ///
+    /// note: If set to `None`, inbound connection attempts are not rate-limited.
+    pub accept_rate_limit: Option<AcceptRateLimit>,
+    /// If set, the outbound connections are made from the listening port, which is shared with the listener.
+    /// This allows the peers to observe the port mapped by a NAT, which is required for hole punching.
+    ///
+    /// note: The port can only be shared on Unix systems.
+    pub reuse_port: bool,
 }
 
 impl Config {
@@ -90,6 +105,9 @@ impl Default for Config {
             fatal_io_errors: vec![ConnectionReset, ConnectionAborted, BrokenPipe, InvalidData, UnexpectedEof],
             max_connections: 100,
             connection_timeout_ms: 1_000,
+            keepalive_secs: None,
+            accept_rate_limit: None,
+            reuse_port: false,
         }
     }
 }
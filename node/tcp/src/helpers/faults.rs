@@ -0,0 +1,179 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::HashMap, net::SocketAddr, time::Duration};
+
+use parking_lot::{Mutex, RwLock};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// The network faults injected into the outbound traffic to a single peer.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Faults {
+    /// The fixed delay added before every outbound message.
+    pub latency: Duration,
+    /// The upper bound of the random delay added on top of the latency.
+    pub jitter: Duration,
+    /// The probability (between 0 and 1) that an outbound message is silently dropped.
+    pub drop_rate: f64,
+    /// The probability (between 0 and 1) that the connection is severed before an outbound message.
+    pub disconnect_rate: f64,
+}
+
+impl Faults {
+    /// Returns faults that only delay messages by the given latency.
+    pub fn latency(latency: Duration) -> Self {
+        Self { latency, ..Default::default() }
+    }
+
+    /// Returns faults that only drop messages with the given probability.
+    pub fn drop_rate(drop_rate: f64) -> Self {
+        Self { drop_rate, ..Default::default() }
+    }
+
+    /// Returns faults that only sever the connection with the given probability.
+    pub fn disconnect_rate(disconnect_rate: f64) -> Self {
+        Self { disconnect_rate, ..Default::default() }
+    }
+}
+
+/// The outcome of applying the injected faults to an outbound message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FaultAction {
+    /// The message is written to the stream after the given delay.
+    Deliver(Duration),
+    /// The message is discarded, while the sender is told it was delivered.
+    Drop,
+    /// The connection is severed.
+    Disconnect,
+}
+
+/// Injects latency, jitter, message drops, and disconnects into the outbound traffic of a [`Tcp`](crate::Tcp).
+///
+/// The random decisions are drawn from a seeded RNG, so that a test replays the same faults on every run.
+pub struct FaultInjector {
+    /// The faults to inject, keyed by the peer's connection address.
+    faults: RwLock<HashMap<SocketAddr, Faults>>,
+    /// The RNG used to decide whether a fault occurs.
+    rng: Mutex<StdRng>,
+}
+
+impl Default for FaultInjector {
+    fn default() -> Self {
+        Self { faults: Default::default(), rng: Mutex::new(StdRng::seed_from_u64(0)) }
+    }
+}
+
+impl FaultInjector {
+    /// Reseeds the RNG used to decide whether a fault occurs.
+    pub fn set_seed(&self, seed: u64) {
+        *self.rng.lock() = StdRng::seed_from_u64(seed);
+    }
+
+    /// Injects the given faults into the outbound traffic to the given peer, replacing any previous ones.
+    pub fn insert(&self, addr: SocketAddr, faults: Faults) {
+        self.faults.write().insert(addr, faults);
+    }
+
+    /// Returns the faults injected into the outbound traffic to the given peer, if any.
+    pub fn get(&self, addr: SocketAddr) -> Option<Faults> {
+        self.faults.read().get(&addr).cloned()
+    }
+
+    /// Stops injecting faults into the outbound traffic to the given peer.
+    pub fn remove(&self, addr: SocketAddr) -> Option<Faults> {
+        self.faults.write().remove(&addr)
+    }
+
+    /// Stops injecting faults into all outbound traffic.
+    pub fn clear(&self) {
+        self.faults.write().clear();
+    }
+
+    /// Decides the outcome of an outbound message to the given peer.
+    pub fn decide(&self, addr: SocketAddr) -> FaultAction {
+        let Some(faults) = self.get(addr) else {
+            return FaultAction::Deliver(Duration::ZERO);
+        };
+
+        let mut rng = self.rng.lock();
+        if faults.disconnect_rate > 0.0 && rng.gen_bool(faults.disconnect_rate.min(1.0)) {
+            return FaultAction::Disconnect;
+        }
+        if faults.drop_rate > 0.0 && rng.gen_bool(faults.drop_rate.min(1.0)) {
+            return FaultAction::Drop;
+        }
+        let jitter = match faults.jitter.is_zero() {
+            true => Duration::ZERO,
+            false => faults.jitter.mul_f64(rng.gen::<f64>()),
+        };
+        FaultAction::Deliver(faults.latency + jitter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decide_without_faults() {
+        let injector = FaultInjector::default();
+        let addr = "127.0.0.1:4130".parse().unwrap();
+        assert_eq!(injector.decide(addr), FaultAction::Deliver(Duration::ZERO));
+
+        // Faults for other peers do not apply.
+        injector.insert("127.0.0.1:4131".parse().unwrap(), Faults::drop_rate(1.0));
+        assert_eq!(injector.decide(addr), FaultAction::Deliver(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_decide_with_faults() {
+        let injector = FaultInjector::default();
+        let addr = "127.0.0.1:4130".parse().unwrap();
+
+        injector.insert(addr, Faults::disconnect_rate(1.0));
+        assert_eq!(injector.decide(addr), FaultAction::Disconnect);
+
+        injector.insert(addr, Faults::drop_rate(1.0));
+        assert_eq!(injector.decide(addr), FaultAction::Drop);
+
+        let latency = Duration::from_millis(50);
+        let jitter = Duration::from_millis(10);
+        injector.insert(addr, Faults { latency, jitter, ..Default::default() });
+        for _ in 0..100 {
+            match injector.decide(addr) {
+                FaultAction::Deliver(delay) => assert!(delay >= latency && delay <= latency + jitter),
+                action => panic!("Unexpected fault action {action:?}"),
+            }
+        }
+
+        injector.remove(addr);
+        assert_eq!(injector.decide(addr), FaultAction::Deliver(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_decide_is_deterministic() {
+        let addr = "127.0.0.1:4130".parse().unwrap();
+        let faults =
+            Faults { jitter: Duration::from_millis(100), drop_rate: 0.3, disconnect_rate: 0.1, ..Default::default() };
+
+        let sample = |seed| {
+            let injector = FaultInjector::default();
+            injector.set_seed(seed);
+            injector.insert(addr, faults.clone());
+            (0..100).map(|_| injector.decide(addr)).collect::<Vec<_>>()
+        };
+        assert_eq!(sample(7), sample(7));
+        assert_ne!(sample(7), sample(8));
+    }
+}
@@ -12,15 +12,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod accept_limiter;
+pub use accept_limiter::{AcceptDecision, AcceptLimiter, AcceptRateLimit};
+
 mod config;
 pub use config::Config;
 
 pub mod connections;
 pub use connections::{Connection, ConnectionSide};
 
+#[cfg(feature = "test")]
+mod faults;
+#[cfg(feature = "test")]
+pub use faults::{FaultAction, FaultInjector, Faults};
+
 mod known_peers;
 pub use known_peers::KnownPeers;
 
+#[cfg(feature = "quic")]
+mod quic;
+#[cfg(feature = "quic")]
+pub(crate) use quic::Quic;
+#[cfg(feature = "quic")]
+pub use quic::{QuicStream, QuicUpgrade};
+
 mod stats;
 pub use stats::Stats;
 
@@ -0,0 +1,368 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An experimental QUIC transport, used to upgrade connections established over TCP.
+
+use std::{
+    collections::HashMap,
+    io,
+    net::SocketAddr,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{Duration, Instant, SystemTime},
+};
+
+use parking_lot::Mutex;
+use quinn::{ClientConfig, Endpoint, RecvStream, SendStream, ServerConfig, TransportConfig};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    sync::oneshot,
+    time::timeout,
+};
+
+/// The server name used in the QUIC certificates; peers are authenticated by the handshake, not by TLS.
+const SERVER_NAME: &str = "snarkos";
+/// The interval at which keep-alive packets are sent on idle QUIC connections.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(10);
+/// The maximum number of inbound QUIC streams that are being matched with TCP connections at once.
+const MAX_PENDING_STREAMS: usize = 1024;
+/// The byte sent back by the accepting side, once it matched the QUIC stream with its TCP connection.
+const UPGRADE_ACK: u8 = 1;
+
+/// The instructions for upgrading a freshly handshaken connection to QUIC.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuicUpgrade {
+    /// Open a QUIC stream to the given address, identified by the given token.
+    Connect {
+        /// The QUIC address of the peer.
+        addr: SocketAddr,
+        /// The token identifying the connection.
+        token: u64,
+    },
+    /// Await a QUIC stream from the given peer IP, identified by the given token.
+    Accept {
+        /// The (TCP) address of the peer.
+        addr: SocketAddr,
+        /// The token identifying the connection.
+        token: u64,
+    },
+}
+
+/// A bidirectional QUIC stream, used in place of a TCP stream.
+pub struct QuicStream {
+    /// The QUIC connection; kept alive for as long as the stream is in use.
+    _connection: quinn::Connection,
+    /// The sending half of the stream.
+    send: SendStream,
+    /// The receiving half of the stream.
+    recv: RecvStream,
+}
+
+impl AsyncRead for QuicStream {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.recv).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for QuicStream {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.send).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.send).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.send).poll_shutdown(cx)
+    }
+}
+
+/// The state of an inbound QUIC stream that is being matched with a TCP connection.
+enum Pending {
+    /// The token was issued by a TCP handshake, and its QUIC stream is yet to arrive.
+    Expected,
+    /// The TCP connection is awaiting the QUIC stream.
+    Waiting(oneshot::Sender<(SocketAddr, QuicStream)>),
+    /// The QUIC stream arrived before the TCP connection requested it.
+    Arrived(SocketAddr, QuicStream),
+}
+
+/// The QUIC endpoint of the node.
+pub(crate) struct Quic {
+    /// The QUIC endpoint.
+    endpoint: Endpoint,
+    /// The map of the tokens issued by the TCP handshakes to their inbound QUIC streams, with the expiry of each entry.
+    pending: Mutex<HashMap<u64, (Instant, Pending)>>,
+    /// The time after which a pending entry expires.
+    pending_ttl: Duration,
+}
+
+impl Quic {
+    /// Binds a new QUIC endpoint to the given address, whose pending streams expire after the given timeout.
+    pub(crate) fn bind(addr: SocketAddr, timeout_ms: u64) -> io::Result<Self> {
+        // Generate an ephemeral self-signed certificate.
+        let certificate = rcgen::generate_simple_self_signed(vec![SERVER_NAME.to_string()]).map_err(io_error)?;
+        let certificate_der = certificate.serialize_der().map_err(io_error)?;
+        let private_key_der = certificate.serialize_private_key_der();
+
+        // Prepare the transport configuration.
+        let mut transport = TransportConfig::default();
+        transport.keep_alive_interval(Some(KEEP_ALIVE_INTERVAL));
+        let transport = Arc::new(transport);
+
+        // Prepare the server configuration.
+        let mut server_config = ServerConfig::with_single_cert(
+            vec![rustls::Certificate(certificate_der)],
+            rustls::PrivateKey(private_key_der),
+        )
+        .map_err(io_error)?;
+        server_config.transport_config(transport.clone());
+
+        // Prepare the client configuration.
+        let crypto = rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(Arc::new(SkipServerVerification))
+            .with_no_client_auth();
+        let mut client_config = ClientConfig::new(Arc::new(crypto));
+        client_config.transport_config(transport);
+
+        // Initialize the endpoint.
+        let mut endpoint = Endpoint::server(server_config, addr)?;
+        endpoint.set_default_client_config(client_config);
+
+        Ok(Self { endpoint, pending: Default::default(), pending_ttl: Duration::from_millis(timeout_ms) })
+    }
+
+    /// Returns the local address of the endpoint.
+    pub(crate) fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.endpoint.local_addr()
+    }
+
+    /// Registers the given token, issued by a TCP handshake, so that its inbound QUIC stream is accepted.
+    /// Returns `false` if too many streams are already pending.
+    pub(crate) fn expect(&self, token: u64) -> bool {
+        let mut pending = self.pending.lock();
+        Self::remove_expired(&mut pending);
+        if pending.len() >= MAX_PENDING_STREAMS || pending.contains_key(&token) {
+            return false;
+        }
+        pending.insert(token, (Instant::now() + self.pending_ttl, Pending::Expected));
+        true
+    }
+
+    /// Opens a QUIC stream to the given address, identifies it with the given token,
+    /// and awaits the acknowledgement of the peer before returning it.
+    pub(crate) async fn connect(&self, addr: SocketAddr, token: u64, timeout_ms: u64) -> io::Result<QuicStream> {
+        let future = async {
+            let connection = self.endpoint.connect(addr, SERVER_NAME).map_err(io_error)?.await?;
+            let (mut send, mut recv) = connection.open_bi().await?;
+            send.write_all(&token.to_le_bytes()).await?;
+            // Ensure the peer matched the stream with the TCP connection, before switching to it.
+            let mut ack = [0u8; 1];
+            recv.read_exact(&mut ack).await.map_err(io_error)?;
+            if ack[0] != UPGRADE_ACK {
+                return Err(io::ErrorKind::InvalidData.into());
+            }
+            Ok::<_, io::Error>(QuicStream { _connection: connection, send, recv })
+        };
+        timeout(Duration::from_millis(timeout_ms), future)
+            .await
+            .map_err(|_| io::Error::from(io::ErrorKind::TimedOut))?
+    }
+
+    /// Awaits the QUIC stream identified by the given token, from the given peer IP, and acknowledges it.
+    /// The token must have been registered with [`Quic::expect`].
+    pub(crate) async fn accept(&self, addr: SocketAddr, token: u64, timeout_ms: u64) -> io::Result<QuicStream> {
+        // Take the stream if it already arrived, or prepare to await it otherwise.
+        let arrived = {
+            let mut pending = self.pending.lock();
+            Self::remove_expired(&mut pending);
+            match pending.remove(&token) {
+                Some((_, Pending::Arrived(remote_addr, stream))) => Ok((remote_addr, stream)),
+                Some((_, Pending::Expected)) => {
+                    let (sender, receiver) = oneshot::channel();
+                    pending.insert(token, (Instant::now() + self.pending_ttl, Pending::Waiting(sender)));
+                    Err(receiver)
+                }
+                // The token was not issued by a handshake, has expired, or is already awaited.
+                _ => return Err(io::ErrorKind::PermissionDenied.into()),
+            }
+        };
+        let (remote_addr, stream) = match arrived {
+            Ok(arrived) => arrived,
+            Err(receiver) => match timeout(Duration::from_millis(timeout_ms), receiver).await {
+                Ok(Ok(arrived)) => arrived,
+                _ => {
+                    self.pending.lock().remove(&token);
+                    return Err(io::ErrorKind::TimedOut.into());
+                }
+            },
+        };
+        let mut stream = Self::ensure_same_ip(addr, remote_addr, stream)?;
+        // Acknowledge the stream, so that the peer switches to it as well.
+        timeout(Duration::from_millis(timeout_ms), stream.send.write_all(&[UPGRADE_ACK]))
+            .await
+            .map_err(|_| io::Error::from(io::ErrorKind::TimedOut))??;
+        Ok(stream)
+    }
+
+    /// Removes the pending entries that have expired.
+    fn remove_expired(pending: &mut HashMap<u64, (Instant, Pending)>) {
+        let now = Instant::now();
+        pending.retain(|_, (expiry, _)| *expiry > now);
+    }
+
+    /// Accepts the inbound QUIC connections, and matches their streams with the awaiting TCP connections.
+    pub(crate) async fn run(self: Arc<Self>) {
+        while let Some(connecting) = self.endpoint.accept().await {
+            let quic = self.clone();
+            tokio::spawn(async move {
+                let Ok(connection) = connecting.await else { return };
+                let remote_addr = connection.remote_address();
+                let Ok((send, mut recv)) = connection.accept_bi().await else { return };
+                // Read the token identifying the connection.
+                let mut token = [0u8; 8];
+                if !matches!(timeout(quic.pending_ttl, recv.read_exact(&mut token)).await, Ok(Ok(()))) {
+                    return;
+                }
+                let token = u64::from_le_bytes(token);
+                let stream = QuicStream { _connection: connection, send, recv };
+
+                // Hand the stream over to the awaiting TCP connection, or store it until it is requested.
+                let mut pending = quic.pending.lock();
+                Self::remove_expired(&mut pending);
+                match pending.remove(&token) {
+                    Some((_, Pending::Waiting(sender))) => {
+                        let _ = sender.send((remote_addr, stream));
+                    }
+                    Some((expiry, Pending::Expected)) => {
+                        pending.insert(token, (expiry, Pending::Arrived(remote_addr, stream)));
+                    }
+                    // Keep the first stream of a token, and drop this one.
+                    Some(entry) => {
+                        pending.insert(token, entry);
+                    }
+                    // Drop the streams whose token was not issued by a handshake, or has expired.
+                    None => (),
+                }
+            });
+        }
+    }
+
+    /// Ensures the QUIC stream comes from the same IP as the TCP connection.
+    fn ensure_same_ip(addr: SocketAddr, remote_addr: SocketAddr, stream: QuicStream) -> io::Result<QuicStream> {
+        match addr.ip() == remote_addr.ip() {
+            true => Ok(stream),
+            false => Err(io::ErrorKind::PermissionDenied.into()),
+        }
+    }
+
+    /// Closes the endpoint.
+    pub(crate) fn close(&self) {
+        self.endpoint.close(0u32.into(), b"shutdown");
+    }
+}
+
+/// A certificate verifier that accepts any certificate, as the peers are authenticated during the handshake.
+struct SkipServerVerification;
+
+impl rustls::client::ServerCertVerifier for SkipServerVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        Ok(rustls::client::ServerCertVerified::assertion())
+    }
+}
+
+/// Converts the given error into an IO error.
+fn io_error<E: std::error::Error + Send + Sync + 'static>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::net::Ipv4Addr;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Returns a QUIC endpoint bound to localhost, whose pending streams expire after the given timeout.
+    fn sample_quic(timeout_ms: u64) -> Arc<Quic> {
+        Arc::new(Quic::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)), timeout_ms).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_quic_stream() {
+        let node = sample_quic(1_000);
+        let peer = sample_quic(1_000);
+        tokio::spawn(node.clone().run());
+        let (node_addr, peer_addr) = (node.local_addr().unwrap(), peer.local_addr().unwrap());
+
+        // Open a stream from the peer, which completes once the node acknowledges it.
+        assert!(node.expect(42));
+        let peer_clone = peer.clone();
+        let outbound = tokio::spawn(async move { peer_clone.connect(node_addr, 42, 1_000).await });
+
+        // Ensure a token that was not issued by a handshake is not accepted.
+        assert!(node.accept(peer_addr, 43, 100).await.is_err());
+
+        // Accept the stream on the node, and exchange a message.
+        let mut inbound = node.accept(peer_addr, 42, 1_000).await.unwrap();
+        let mut outbound = outbound.await.unwrap().unwrap();
+        outbound.write_all(b"hello").await.unwrap();
+        outbound.flush().await.unwrap();
+        let mut message = [0u8; 5];
+        inbound.read_exact(&mut message).await.unwrap();
+        assert_eq!(&message, b"hello");
+
+        // Ensure the token cannot be used twice.
+        assert!(node.accept(peer_addr, 42, 100).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_quic_unexpected_token() {
+        let node = sample_quic(1_000);
+        let peer = sample_quic(1_000);
+        tokio::spawn(node.clone().run());
+
+        // Ensure a stream whose token was not issued by a handshake is dropped, and never acknowledged.
+        assert!(peer.connect(node.local_addr().unwrap(), 7, 300).await.is_err());
+        assert!(node.pending.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_quic_pending_limits() {
+        let node = sample_quic(100);
+
+        // Ensure the pending tokens expire.
+        assert!(node.expect(1));
+        assert!(!node.expect(1));
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(node.accept(SocketAddr::from((Ipv4Addr::LOCALHOST, 1)), 1, 100).await.is_err());
+
+        // Ensure the number of pending tokens is capped.
+        for token in 0..MAX_PENDING_STREAMS as u64 {
+            assert!(node.expect(token));
+        }
+        assert!(!node.expect(MAX_PENDING_STREAMS as u64));
+    }
+}
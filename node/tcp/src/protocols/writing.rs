@@ -33,7 +33,25 @@ use crate::{
     P2P,
 };
 
-type WritingSenders = Arc<RwLock<HashMap<SocketAddr, mpsc::Sender<WrappedMessage>>>>;
+type WritingSenders = Arc<RwLock<HashMap<SocketAddr, OutboundQueues>>>;
+
+/// The priority class of an outbound message. Each connection has a dedicated queue per class, and the
+/// queues are drained in order of priority, so that critical messages are not delayed by a backlog of
+/// less important ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MessagePriority {
+    /// Messages critical to the progress of the node, e.g. consensus messages and block responses.
+    High = 0,
+    /// Messages without a specific priority.
+    Normal = 1,
+    /// Messages that can tolerate delays, e.g. gossip and pings.
+    Low = 2,
+}
+
+impl MessagePriority {
+    /// The list of priorities, from the highest to the lowest.
+    pub const ALL: [Self; 3] = [Self::High, Self::Normal, Self::Low];
+}
 
 /// Can be used to specify and enable writing, i.e. sending outbound messages. If the [`Handshake`]
 /// protocol is enabled too, it goes into force only after the handshake has been concluded.
@@ -92,6 +110,21 @@ where
     /// The `side` param indicates the connection side **from the node's perspective**.
     fn codec(&self, addr: SocketAddr, side: ConnectionSide) -> Self::Codec;
 
+    /// Returns the priority of the given outbound message, which determines the queue it is sent through.
+    ///
+    /// The default priority is [`MessagePriority::Normal`].
+    fn priority(&self, _message: &Self::Message) -> MessagePriority {
+        MessagePriority::Normal
+    }
+
+    /// Returns the number of outbound messages queued with the given priority, across all connections.
+    fn outbound_queue_depth(&self, priority: MessagePriority) -> usize {
+        match self.tcp().protocols.writing.get() {
+            Some(handler) => handler.senders.read().values().map(|queues| queues.depth(priority)).sum(),
+            None => 0,
+        }
+    }
+
     /// Sends the provided message to the specified [`SocketAddr`]. Returns as soon as the message is queued to
     /// be sent, without waiting for the actual delivery; instead, the caller is provided with a [`oneshot::Receiver`]
     /// which can be used to determine when and whether the message has been delivered.
@@ -106,10 +139,11 @@ where
         // access the protocol handler
         if let Some(handler) = self.tcp().protocols.writing.get() {
             // find the message sender for the given address
-            if let Some(sender) = handler.senders.read().get(&addr).cloned() {
+            if let Some(queues) = handler.senders.read().get(&addr).cloned() {
+                let priority = self.priority(&message);
                 let (msg, delivery) = WrappedMessage::new(Box::new(message));
-                sender
-                    .try_send(msg)
+                queues
+                    .try_send(priority, msg)
                     .map_err(|e| {
                         error!(parent: self.tcp().span(), "can't send a message to {}: {}", addr, e);
                         self.tcp().stats().register_failure();
@@ -139,9 +173,10 @@ where
         // access the protocol handler
         if let Some(handler) = self.tcp().protocols.writing.get() {
             let senders = handler.senders.read().clone();
-            for (addr, message_sender) in senders {
+            let priority = self.priority(&message);
+            for (addr, queues) in senders {
                 let (msg, _delivery) = WrappedMessage::new(Box::new(message.clone()));
-                let _ = message_sender.try_send(msg).map_err(|e| {
+                let _ = queues.try_send(priority, msg).map_err(|e| {
                     error!(parent: self.tcp().span(), "can't send a message to {}: {}", addr, e);
                     self.tcp().stats().register_failure();
                 });
@@ -192,10 +227,12 @@ impl<W: Writing> WritingInternal for W {
         let writer = conn.writer.take().expect("missing connection writer!");
         let mut framed = FramedWrite::new(writer, codec);
 
-        let (outbound_message_sender, mut outbound_message_receiver) = mpsc::channel(Self::MESSAGE_QUEUE_DEPTH);
+        let (high_sender, mut high_receiver) = mpsc::channel(Self::MESSAGE_QUEUE_DEPTH);
+        let (normal_sender, mut normal_receiver) = mpsc::channel(Self::MESSAGE_QUEUE_DEPTH);
+        let (low_sender, mut low_receiver) = mpsc::channel(Self::MESSAGE_QUEUE_DEPTH);
 
-        // register the connection's message sender with the Writing protocol handler
-        conn_senders.write().insert(addr, outbound_message_sender);
+        // register the connection's message queues with the Writing protocol handler
+        conn_senders.write().insert(addr, OutboundQueues([high_sender, normal_sender, low_sender]));
 
         // this will automatically drop the sender upon a disconnect
         let auto_cleanup = SenderCleanup { addr, senders: Arc::clone(conn_senders) };
@@ -213,9 +250,37 @@ impl<W: Writing> WritingInternal for W {
             // move the cleanup into the task that gets aborted on disconnect
             let _auto_cleanup = auto_cleanup;
 
-            while let Some(wrapped_msg) = outbound_message_receiver.recv().await {
+            loop {
+                // drain the queues in order of priority
+                let wrapped_msg = tokio::select! {
+                    biased;
+                    Some(wrapped_msg) = high_receiver.recv() => wrapped_msg,
+                    Some(wrapped_msg) = normal_receiver.recv() => wrapped_msg,
+                    Some(wrapped_msg) = low_receiver.recv() => wrapped_msg,
+                    else => break,
+                };
                 let msg = wrapped_msg.msg.downcast().unwrap();
 
+                // apply the injected network faults, if any
+                #[cfg(feature = "test")]
+                match node.faults().decide(addr) {
+                    crate::FaultAction::Deliver(delay) => {
+                        if !delay.is_zero() {
+                            tokio::time::sleep(delay).await;
+                        }
+                    }
+                    crate::FaultAction::Drop => {
+                        trace!(parent: node.span(), "dropped a message to {} (injected fault)", addr);
+                        let _ = wrapped_msg.delivery_notification.send(Ok(()));
+                        continue;
+                    }
+                    crate::FaultAction::Disconnect => {
+                        debug!(parent: node.span(), "severing the connection to {} (injected fault)", addr);
+                        let _ = wrapped_msg.delivery_notification.send(Err(io::ErrorKind::ConnectionReset.into()));
+                        break;
+                    }
+                }
+
                 match self_clone.write_to_stream(*msg, &mut framed).await {
                     Ok(len) => {
                         let _ = wrapped_msg.delivery_notification.send(Ok(()));
@@ -262,6 +327,27 @@ impl WrappedMessage {
     }
 }
 
+/// The per-connection queues of outbound messages, one per [`MessagePriority`].
+#[derive(Clone)]
+struct OutboundQueues([mpsc::Sender<WrappedMessage>; 3]);
+
+impl OutboundQueues {
+    /// Queues the given message with the given priority.
+    fn try_send(
+        &self,
+        priority: MessagePriority,
+        msg: WrappedMessage,
+    ) -> Result<(), mpsc::error::TrySendError<WrappedMessage>> {
+        self.0[priority as usize].try_send(msg)
+    }
+
+    /// Returns the number of messages queued with the given priority.
+    fn depth(&self, priority: MessagePriority) -> usize {
+        let sender = &self.0[priority as usize];
+        sender.max_capacity() - sender.capacity()
+    }
+}
+
 /// The handler object dedicated to the [`Writing`] protocol.
 pub(crate) struct WritingHandler {
     handler: ProtocolHandler<Connection, io::Result<Connection>>,
@@ -284,3 +370,27 @@ impl Drop for SenderCleanup {
         self.senders.write().remove(&self.addr);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outbound_queue_depth() {
+        let (senders, mut receivers): (Vec<_>, Vec<_>) = (0..3).map(|_| mpsc::channel(4)).unzip();
+        let queues = OutboundQueues(senders.try_into().unwrap());
+
+        // Queue two high-priority messages and one low-priority message.
+        for priority in [MessagePriority::High, MessagePriority::High, MessagePriority::Low] {
+            let (msg, _) = WrappedMessage::new(Box::new(()));
+            queues.try_send(priority, msg).unwrap();
+        }
+        assert_eq!(queues.depth(MessagePriority::High), 2);
+        assert_eq!(queues.depth(MessagePriority::Normal), 0);
+        assert_eq!(queues.depth(MessagePriority::Low), 1);
+
+        // Draining a message reduces the depth of its queue.
+        receivers[MessagePriority::High as usize].try_recv().unwrap();
+        assert_eq!(queues.depth(MessagePriority::High), 1);
+    }
+}
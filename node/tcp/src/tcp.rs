@@ -27,18 +27,29 @@ use std::{
 
 use once_cell::sync::OnceCell;
 use parking_lot::Mutex;
+use socket2::{SockRef, TcpKeepalive};
 use tokio::{
     io::split,
-    net::{TcpListener, TcpStream},
+    net::{TcpListener, TcpSocket, TcpStream},
     sync::oneshot,
     task::JoinHandle,
     time::timeout,
 };
 use tracing::*;
 
+#[cfg(feature = "quic")]
+use crate::{Quic, QuicUpgrade};
+#[cfg(feature = "quic")]
+use std::collections::HashMap;
+
+#[cfg(feature = "test")]
+use crate::FaultInjector;
+
 use crate::{
     connections::{Connection, ConnectionSide, Connections},
     protocols::{Protocol, Protocols},
+    AcceptDecision,
+    AcceptLimiter,
     Config,
     KnownPeers,
     Stats,
@@ -67,6 +78,14 @@ pub struct InnerTcp {
     config: Config,
     /// The node's listening address.
     listening_addr: OnceCell<SocketAddr>,
+    /// The node's additional listening addresses, along with their listening tasks.
+    additional_listeners: Mutex<Vec<(SocketAddr, JoinHandle<()>)>>,
+    /// The node's QUIC endpoint, if enabled.
+    #[cfg(feature = "quic")]
+    quic: OnceCell<Arc<Quic>>,
+    /// The map of connecting addresses to the QUIC upgrades negotiated during their handshake.
+    #[cfg(feature = "quic")]
+    quic_upgrades: Mutex<HashMap<SocketAddr, QuicUpgrade>>,
     /// Contains objects used by the protocols implemented by the node.
     pub(crate) protocols: Protocols,
     /// A set of connections that have not been finalized yet.
@@ -77,6 +96,11 @@ pub struct InnerTcp {
     known_peers: KnownPeers,
     /// Collects statistics related to the node itself.
     stats: Stats,
+    /// Sheds the inbound connection attempts that exceed the rate limit.
+    accept_limiter: AcceptLimiter,
+    /// The network faults injected into the node's outbound traffic.
+    #[cfg(feature = "test")]
+    faults: FaultInjector,
     /// The node's tasks.
     pub(crate) tasks: Mutex<Vec<JoinHandle<()>>>,
 }
@@ -91,17 +115,27 @@ impl Tcp {
 
         // Create a tracing span containing the node's name.
         let span = crate::helpers::create_span(config.name.as_deref().unwrap());
+        // Retrieve the limits on the rate of inbound connection attempts.
+        let accept_rate_limit = config.accept_rate_limit;
 
         // Initialize the Tcp stack.
         let tcp = Tcp(Arc::new(InnerTcp {
             span,
             config,
             listening_addr: Default::default(),
+            additional_listeners: Default::default(),
+            #[cfg(feature = "quic")]
+            quic: Default::default(),
+            #[cfg(feature = "quic")]
+            quic_upgrades: Default::default(),
             protocols: Default::default(),
             connecting: Default::default(),
             connections: Default::default(),
             known_peers: Default::default(),
             stats: Default::default(),
+            accept_limiter: AcceptLimiter::new(accept_rate_limit),
+            #[cfg(feature = "test")]
+            faults: Default::default(),
             tasks: Default::default(),
         }));
 
@@ -129,6 +163,11 @@ impl Tcp {
         self.listening_addr.get().copied().ok_or_else(|| io::ErrorKind::AddrNotAvailable.into())
     }
 
+    /// Returns the additional listening addresses.
+    pub fn additional_listening_addrs(&self) -> Vec<SocketAddr> {
+        self.additional_listeners.lock().iter().map(|(addr, _)| *addr).collect()
+    }
+
     /// Checks whether the provided address is connected.
     pub fn is_connected(&self, addr: SocketAddr) -> bool {
         self.connections.is_connected(addr)
@@ -171,6 +210,19 @@ impl Tcp {
         &self.stats
     }
 
+    /// Returns the limiter of the inbound connection attempts.
+    #[inline]
+    pub fn accept_limiter(&self) -> &AcceptLimiter {
+        &self.accept_limiter
+    }
+
+    /// Returns a reference to the network faults injected into the outbound traffic.
+    #[cfg(feature = "test")]
+    #[inline]
+    pub fn faults(&self) -> &FaultInjector {
+        &self.faults
+    }
+
     /// Returns the tracing [`Span`] associated with Tcp.
     #[inline]
     pub fn span(&self) -> &Span {
@@ -188,6 +240,15 @@ impl Tcp {
         if let Some(listening_task) = tasks.next() {
             listening_task.abort(); // abort the listening task first
         }
+        // Abort the additional listening tasks.
+        for (_, listening_task) in std::mem::take(&mut *self.additional_listeners.lock()) {
+            listening_task.abort();
+        }
+        // Close the QUIC endpoint.
+        #[cfg(feature = "quic")]
+        if let Some(quic) = self.quic.get() {
+            quic.close();
+        }
         // Disconnect from all connected peers.
         for addr in self.connected_addrs() {
             self.disconnect(addr).await;
@@ -202,6 +263,25 @@ impl Tcp {
 impl Tcp {
     /// Connects to the provided `SocketAddr`.
     pub async fn connect(&self, addr: SocketAddr) -> io::Result<()> {
+        let timeout_duration = Duration::from_millis(self.config().connection_timeout_ms.into());
+        self.connect_inner(addr, ConnectionSide::Initiator, timeout_duration).await
+    }
+
+    /// Connects to the provided `SocketAddr` from the listening port, while the peer simultaneously connects back,
+    /// in order to punch through the NATs on both sides. The given `side` must be the opposite of the peer's.
+    ///
+    /// note: [`Config::reuse_port`] must be set in order for it to have any effect.
+    pub async fn punch(&self, addr: SocketAddr, side: ConnectionSide, timeout: Duration) -> io::Result<()> {
+        self.connect_inner(addr, side, timeout).await
+    }
+
+    /// Connects to the provided `SocketAddr`, acting as the given side of the connection.
+    async fn connect_inner(
+        &self,
+        addr: SocketAddr,
+        side: ConnectionSide,
+        timeout_duration: Duration,
+    ) -> io::Result<()> {
         if let Ok(listening_addr) = self.listening_addr() {
             // TODO(nkls): maybe this first check can be dropped; though it might be best to keep just in case.
             if addr == listening_addr || self.is_self_connect(addr) {
@@ -225,20 +305,7 @@ impl Tcp {
             return Err(io::ErrorKind::AlreadyExists.into());
         }
 
-        let timeout_duration = Duration::from_millis(self.config().connection_timeout_ms.into());
-
-        // Bind the tcp socket to the configured listener ip if it's set.
-        // Otherwise default to the system's default interface.
-        let res = if let Some(listen_ip) = self.config().listener_ip {
-            let sock =
-                if listen_ip.is_ipv4() { tokio::net::TcpSocket::new_v4()? } else { tokio::net::TcpSocket::new_v6()? };
-            sock.bind(SocketAddr::new(listen_ip, 0))?;
-            timeout(timeout_duration, sock.connect(addr)).await
-        } else {
-            timeout(timeout_duration, TcpStream::connect(addr)).await
-        };
-
-        let stream = match res {
+        let stream = match timeout(timeout_duration, self.open_stream(addr)).await {
             Ok(Ok(stream)) => Ok(stream),
             Ok(err) => {
                 self.connecting.lock().remove(&addr);
@@ -251,7 +318,7 @@ impl Tcp {
             }
         }?;
 
-        let ret = self.adapt_stream(stream, addr, ConnectionSide::Initiator).await;
+        let ret = self.adapt_stream(stream, addr, side).await;
 
         if let Err(ref e) = ret {
             self.connecting.lock().remove(&addr);
@@ -262,6 +329,27 @@ impl Tcp {
         ret
     }
 
+    /// Opens a TCP stream to the provided `SocketAddr`.
+    async fn open_stream(&self, addr: SocketAddr) -> io::Result<TcpStream> {
+        // Bind the tcp socket to the configured listener ip if it's set.
+        // Otherwise default to the system's default interface.
+        let Some(listen_ip) = self.config().listener_ip else {
+            return TcpStream::connect(addr).await;
+        };
+        let sock = if listen_ip.is_ipv4() { TcpSocket::new_v4()? } else { TcpSocket::new_v6()? };
+        match self.listening_addr() {
+            // If the port is shared, connect from the listening port, so that the peer observes its NAT mapping.
+            Ok(listening_addr) if self.config().reuse_port => {
+                sock.set_reuseaddr(true)?;
+                #[cfg(unix)]
+                sock.set_reuseport(true)?;
+                sock.bind(listening_addr)?;
+            }
+            _ => sock.bind(SocketAddr::new(listen_ip, 0))?,
+        }
+        sock.connect(addr).await
+    }
+
     /// Disconnects from the provided `SocketAddr`.
     pub async fn disconnect(&self, addr: SocketAddr) -> bool {
         if let Some(handler) = self.protocols.disconnect.get() {
@@ -315,6 +403,37 @@ impl Tcp {
         let listening_addr = (listener_ip, port).into();
         self.listening_addr.set(listening_addr).expect("The node's listener was started more than once");
 
+        // Spawn the listening task.
+        let listening_task = self.spawn_listening_task(listener).await;
+        self.tasks.lock().push(listening_task);
+        debug!(parent: self.span(), "Listening on {listening_addr}");
+
+        Ok(listening_addr)
+    }
+
+    /// Spawns a task that listens for incoming connections on the given address, in addition to
+    /// the main listener. This is intended for nodes that are reachable over several interfaces.
+    ///
+    /// note: The main listener must be enabled first, via [`Tcp::enable_listener`].
+    pub async fn enable_additional_listener(&self, addr: SocketAddr) -> io::Result<SocketAddr> {
+        // Ensure the main listener is enabled.
+        self.listening_addr()?;
+
+        // Initialize the TCP listener.
+        let listener = TcpListener::bind(addr).await?;
+        // Discover the port, if it was unspecified.
+        let listening_addr = SocketAddr::new(addr.ip(), listener.local_addr()?.port());
+
+        // Spawn the listening task.
+        let listening_task = self.spawn_listening_task(listener).await;
+        self.additional_listeners.lock().push((listening_addr, listening_task));
+        debug!(parent: self.span(), "Listening on {listening_addr}");
+
+        Ok(listening_addr)
+    }
+
+    /// Spawns a task that accepts the connections on the given listener.
+    async fn spawn_listening_task(&self, listener: TcpListener) -> JoinHandle<()> {
         // Use a channel to know when the listening task is ready.
         let (tx, rx) = oneshot::channel();
 
@@ -331,11 +450,9 @@ impl Tcp {
                 }
             }
         });
-        self.tasks.lock().push(listening_task);
         let _ = rx.await;
-        debug!(parent: self.span(), "Listening on {listening_addr}");
 
-        Ok(listening_addr)
+        listening_task
     }
 
     /// Creates an instance of `TcpListener` based on the node's configuration.
@@ -345,7 +462,7 @@ impl Tcp {
             // Construct the desired listening IP address.
             let desired_listening_addr = SocketAddr::new(listener_ip, port);
             // If a desired listening port is set, try to bind to it.
-            match TcpListener::bind(desired_listening_addr).await {
+            match self.bind_listener(desired_listening_addr) {
                 Ok(listener) => listener,
                 Err(e) => {
                     if self.config().allow_random_port {
@@ -354,7 +471,7 @@ impl Tcp {
                             "Trying any listening port, as the desired port is unavailable: {e}"
                         );
                         let random_available_addr = SocketAddr::new(listener_ip, 0);
-                        TcpListener::bind(random_available_addr).await?
+                        self.bind_listener(random_available_addr)?
                     } else {
                         error!(parent: self.span(), "The desired listening port is unavailable: {e}");
                         return Err(e);
@@ -363,7 +480,7 @@ impl Tcp {
             }
         } else if self.config().allow_random_port {
             let random_available_addr = SocketAddr::new(listener_ip, 0);
-            TcpListener::bind(random_available_addr).await?
+            self.bind_listener(random_available_addr)?
         } else {
             panic!("As 'listener_ip' is set, either 'desired_listening_port' or 'allow_random_port' must be set");
         };
@@ -371,10 +488,36 @@ impl Tcp {
         Ok(listener)
     }
 
+    /// Binds a `TcpListener` to the given address, sharing the port with the outbound connections if configured.
+    fn bind_listener(&self, addr: SocketAddr) -> io::Result<TcpListener> {
+        let socket = if addr.is_ipv4() { TcpSocket::new_v4()? } else { TcpSocket::new_v6()? };
+        #[cfg(not(windows))]
+        socket.set_reuseaddr(true)?;
+        #[cfg(unix)]
+        if self.config().reuse_port {
+            socket.set_reuseport(true)?;
+        }
+        socket.bind(addr)?;
+        socket.listen(1024)
+    }
+
     /// Handles a new inbound connection.
     fn handle_connection(&self, stream: TcpStream, addr: SocketAddr) {
         debug!(parent: self.span(), "Received a connection from {addr}");
 
+        // Shed the connection cheaply if the source exceeds the rate limit.
+        match self.accept_limiter.check(addr.ip()) {
+            AcceptDecision::Accept => (),
+            AcceptDecision::Throttle | AcceptDecision::Banned => {
+                debug!(parent: self.span(), "Dropping the connection from {addr} (rate limited)");
+                return;
+            }
+            AcceptDecision::Ban => {
+                warn!(parent: self.span(), "Temporarily banning {} for flooding connection attempts", addr.ip());
+                return;
+            }
+        }
+
         if !self.can_add_connection() || self.is_self_connect(addr) {
             debug!(parent: self.span(), "Rejecting the connection from {addr}");
             return;
@@ -397,13 +540,15 @@ impl Tcp {
         // SAFETY: if we're opening connections, this should never fail.
         let listening_addr = self.listening_addr().unwrap();
 
-        match listening_addr.ip().is_loopback() {
-            // If localhost, check the ports, this only works on outbound connections, since we
-            // don't know the ephemeral port a peer might be using if they initiate the connection.
-            true => listening_addr.port() == addr.port(),
-            // If it's not localhost, matching IPs indicate a self-connect in both directions.
-            false => listening_addr.ip() == addr.ip(),
-        }
+        std::iter::once(listening_addr).chain(self.additional_listening_addrs()).any(|listening_addr| {
+            match listening_addr.ip().is_loopback() {
+                // If localhost, check the ports, this only works on outbound connections, since we
+                // don't know the ephemeral port a peer might be using if they initiate the connection.
+                true => listening_addr.port() == addr.port(),
+                // If it's not localhost, matching IPs indicate a self-connect in both directions.
+                false => listening_addr.ip() == addr.ip(),
+            }
+        })
     }
 
     /// Checks whether the `Tcp` can handle an additional connection.
@@ -428,6 +573,14 @@ impl Tcp {
     async fn adapt_stream(&self, stream: TcpStream, peer_addr: SocketAddr, own_side: ConnectionSide) -> io::Result<()> {
         self.known_peers.add(peer_addr);
 
+        // Enable TCP keep-alive, if configured.
+        if let Some(keepalive_secs) = self.config.keepalive_secs {
+            let keepalive = TcpKeepalive::new().with_time(Duration::from_secs(keepalive_secs.into()));
+            if let Err(e) = SockRef::from(&stream).set_tcp_keepalive(&keepalive) {
+                warn!(parent: self.span(), "couldn't enable TCP keep-alive for {}: {}", peer_addr, e);
+            }
+        }
+
         // Register the port seen by the peer.
         if own_side == ConnectionSide::Initiator {
             if let Ok(addr) = stream.local_addr() {
@@ -489,6 +642,10 @@ impl Tcp {
 
         let mut conn = enable_protocol!(handshake, self, conn);
 
+        // Upgrade the connection to QUIC, if it was negotiated during the handshake.
+        #[cfg(feature = "quic")]
+        self.upgrade_to_quic(&mut conn).await?;
+
         // Split the stream after the handshake (if not done before).
         if let Some(stream) = conn.stream.take() {
             let (reader, writer) = split(stream);
@@ -503,6 +660,84 @@ impl Tcp {
     }
 }
 
+#[cfg(feature = "quic")]
+impl Tcp {
+    /// Binds a QUIC endpoint to the listening address, and spawns a task that accepts the QUIC streams
+    /// of the connections that are upgraded after their handshake.
+    ///
+    /// note: The main listener must be enabled first, via [`Tcp::enable_listener`].
+    pub async fn enable_quic(&self) -> io::Result<SocketAddr> {
+        // Bind the QUIC endpoint to the listening address.
+        let quic = Arc::new(Quic::bind(self.listening_addr()?, self.config.connection_timeout_ms.into())?);
+        let quic_addr = quic.local_addr()?;
+        if self.quic.set(quic.clone()).is_err() {
+            panic!("The node's QUIC endpoint was started more than once");
+        }
+
+        // Spawn the task accepting the QUIC streams.
+        let quic_task = tokio::spawn(quic.run());
+        self.tasks.lock().push(quic_task);
+        debug!(parent: self.span(), "Listening for QUIC on {quic_addr}");
+
+        Ok(quic_addr)
+    }
+
+    /// Returns the QUIC address; returns an error if QUIC is not enabled.
+    pub fn quic_addr(&self) -> io::Result<SocketAddr> {
+        match self.quic.get() {
+            Some(quic) => quic.local_addr(),
+            None => Err(io::ErrorKind::AddrNotAvailable.into()),
+        }
+    }
+
+    /// Requests the connection with the given address to be upgraded to QUIC once its handshake completes.
+    /// If the upgrade fails on either side, the connection is dropped, so that both sides agree on the transport.
+    pub fn request_quic_upgrade(&self, addr: SocketAddr, upgrade: QuicUpgrade) {
+        // Only accept the inbound QUIC stream whose token was issued by this handshake.
+        if let (QuicUpgrade::Accept { token, .. }, Some(quic)) = (upgrade, self.quic.get()) {
+            if !quic.expect(token) {
+                warn!(parent: self.span(), "Too many pending QUIC streams, the upgrade of {addr} will fail");
+            }
+        }
+        self.quic_upgrades.lock().insert(addr, upgrade);
+    }
+
+    /// Upgrades the given connection to QUIC, if it was requested during its handshake.
+    async fn upgrade_to_quic(&self, conn: &mut Connection) -> io::Result<()> {
+        let Some(upgrade) = self.quic_upgrades.lock().remove(&conn.addr()) else { return Ok(()) };
+        let Some(quic) = self.quic.get() else { return Ok(()) };
+
+        let timeout_ms = self.config.connection_timeout_ms.into();
+        let result = match upgrade {
+            QuicUpgrade::Connect { addr, token } => quic.connect(addr, token, timeout_ms).await,
+            QuicUpgrade::Accept { addr, token } => quic.accept(addr, token, timeout_ms).await,
+        };
+
+        match result {
+            // Replace the TCP stream with the QUIC stream.
+            Ok(stream) => {
+                conn.stream = None;
+                let (reader, writer) = split(stream);
+                conn.reader = Some(Box::new(reader));
+                conn.writer = Some(Box::new(writer));
+                debug!(parent: self.span(), "Upgraded the connection with {} to QUIC", conn.addr());
+                Ok(())
+            }
+            // Drop the connection, as the peer may have already switched to QUIC.
+            Err(e) => {
+                warn!(parent: self.span(), "Unable to upgrade the connection with {} to QUIC: {e}", conn.addr());
+                // Let the higher-level protocols clean up after the peer, as the connection is never registered.
+                if let Some(handler) = self.protocols.disconnect.get() {
+                    let (sender, receiver) = oneshot::channel();
+                    handler.trigger((conn.addr(), sender));
+                    let _ = receiver.await;
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
 impl fmt::Debug for Tcp {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "The TCP stack config: {:?}", self.config)
@@ -560,6 +795,38 @@ mod tests {
         assert!(!tcp.is_connecting(peer_ip));
     }
 
+    #[tokio::test]
+    async fn test_additional_listener() {
+        let tcp = Tcp::new(Config::default());
+
+        // Ensure the main listener must be enabled first.
+        assert!(tcp.enable_additional_listener(SocketAddr::from((Ipv4Addr::LOCALHOST, 0))).await.is_err());
+
+        let node_ip = tcp.enable_listener().await.unwrap();
+        let additional_ip = tcp.enable_additional_listener(SocketAddr::from((Ipv4Addr::LOCALHOST, 0))).await.unwrap();
+        assert_ne!(node_ip.port(), additional_ip.port());
+        assert_eq!(tcp.additional_listening_addrs(), vec![additional_ip]);
+
+        // Ensure self-connecting via the additional listener is not possible.
+        tcp.connect(additional_ip).await.unwrap_err();
+        assert_eq!(tcp.num_connected(), 0);
+
+        // Initialize the peer.
+        let peer = Tcp::new(Config {
+            listener_ip: Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+            desired_listening_port: Some(0),
+            max_connections: 1,
+            ..Default::default()
+        });
+        peer.enable_listener().await.unwrap();
+
+        // Connect to the node via the additional listener.
+        peer.connect(additional_ip).await.unwrap();
+        // Sleep briefly.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(tcp.num_connected(), 1);
+    }
+
     #[tokio::test]
     async fn test_disconnect() {
         let tcp = Tcp::new(Config::default());
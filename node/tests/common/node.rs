@@ -23,10 +23,23 @@ use std::str::FromStr;
 pub async fn client() -> Client<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
     Client::new(
         "127.0.0.1:0".parse().unwrap(),
+        &[],
         None,
         10,
+        0,
         Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
         &[],
+        &[],
+        &[],
+        None,  // No sync checkpoint.
+        None,  // No pruning.
+        None,  // No maximum ledger size.
+        None,  // No cold storage.
+        None,  // No state snapshots.
+        false, // No analytics index.
+        Default::default(),
+        Default::default(),
+        None, // No pool coordinator.
         sample_genesis_block(),
         None, // No CDN.
         StorageMode::Production,
@@ -39,8 +52,17 @@ pub async fn client() -> Client<CurrentNetwork, ConsensusMemory<CurrentNetwork>>
 pub async fn prover() -> Prover<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
     Prover::new(
         "127.0.0.1:0".parse().unwrap(),
+        &[],
         Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
         &[],
+        &[],
+        Default::default(),
+        Default::default(),
+        Default::default(), // Default prover devices.
+        Default::default(), // No prover throttle.
+        None,               // No pool.
+        Default::default(), // No submission endpoints.
+        None,               // No status endpoint.
         sample_genesis_block(),
         StorageMode::Production,
         Default::default(),
@@ -52,12 +74,29 @@ pub async fn prover() -> Prover<CurrentNetwork, ConsensusMemory<CurrentNetwork>>
 pub async fn validator() -> Validator<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
     Validator::new(
         "127.0.0.1:0".parse().unwrap(),
+        &[],
         None,
         None,
         10,
+        0,
         Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
+        None, // No remote signer.
         &[],
         &[],
+        Default::default(),
+        Default::default(),
+        &[],
+        Default::default(),     // The default batch proposals.
+        Default::default(),     // The default round timeouts.
+        Default::default(),     // The default participation alerts.
+        Default::default(),     // The default admission policy.
+        None,                   // No solution rate limit.
+        false,                  // No memory pool persistence.
+        Default::default(),     // No operator lane.
+        None,                   // The default BFT retention.
+        None,                   // No signing journal.
+        None,                   // No failover.
+        None,                   // No audit log.
         sample_genesis_block(), // Should load the current network's genesis block.
         None,                   // No CDN.
         StorageMode::Production,
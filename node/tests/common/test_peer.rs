@@ -15,7 +15,7 @@
 use snarkos_account::Account;
 use snarkos_node_router::{
     expect_message,
-    messages::{ChallengeRequest, ChallengeResponse, Message, MessageCodec, MessageTrait, NodeType},
+    messages::{Capabilities, ChallengeRequest, ChallengeResponse, Message, MessageCodec, MessageTrait, NodeType},
 };
 use snarkvm::{
     ledger::narwhal::Data,
@@ -58,6 +58,7 @@ pub struct TestPeer {
     node: Node,
     node_type: NodeType,
     account: Account<CurrentNetwork>,
+    version: u32,
 }
 
 impl Pea2Pea for TestPeer {
@@ -80,6 +81,10 @@ impl TestPeer {
     }
 
     pub async fn new(node_type: NodeType, account: Account<CurrentNetwork>) -> Self {
+        Self::new_with_version(node_type, account, Message::<CurrentNetwork>::VERSION).await
+    }
+
+    pub async fn new_with_version(node_type: NodeType, account: Account<CurrentNetwork>, version: u32) -> Self {
         let peer = Self {
             node: Node::new(Config {
                 max_connections: 200,
@@ -88,6 +93,7 @@ impl TestPeer {
             }),
             node_type,
             account,
+            version,
         };
 
         peer.enable_handshake().await;
@@ -111,6 +117,21 @@ impl TestPeer {
     pub fn address(&self) -> Address<CurrentNetwork> {
         self.account.address()
     }
+
+    /// Returns a challenge request on the version of the test peer.
+    fn challenge_request(&self, listener_port: u16, nonce: u64) -> ChallengeRequest<CurrentNetwork> {
+        let mut request = ChallengeRequest::new(listener_port, self.node_type(), self.address(), nonce);
+        request.version = self.version;
+        request
+    }
+
+    /// Ensures the peer did not send the capabilities, if the version of the test peer predates them.
+    fn check_capabilities(&self, capabilities: &Capabilities) -> io::Result<()> {
+        if self.version < Message::<CurrentNetwork>::CAPABILITIES_VERSION && !capabilities.is_empty() {
+            return Err(error(format!("Received the capabilities on version {}", self.version)));
+        }
+        Ok(())
+    }
 }
 
 impl Handshake for TestPeer {
@@ -136,12 +157,14 @@ impl Handshake for TestPeer {
         match node_side {
             ConnectionSide::Initiator => {
                 // Send a challenge request to the peer.
-                let our_request = ChallengeRequest::new(local_ip.port(), self.node_type(), self.address(), rng.gen());
+                let our_request = self.challenge_request(local_ip.port(), rng.gen());
                 framed.send(Message::ChallengeRequest(our_request)).await?;
 
                 // Receive the peer's challenge bundle.
-                let _peer_response = expect_message!(Message::ChallengeResponse, framed, peer_addr);
+                let peer_response = expect_message!(Message::ChallengeResponse, framed, peer_addr);
                 let peer_request = expect_message!(Message::ChallengeRequest, framed, peer_addr);
+                self.check_capabilities(&peer_response.capabilities)?;
+                self.check_capabilities(&peer_request.capabilities)?;
 
                 // Sign the nonce.
                 let response_nonce: u64 = rng.gen();
@@ -154,6 +177,7 @@ impl Handshake for TestPeer {
                     restrictions_id,
                     signature: Data::Object(signature),
                     nonce: response_nonce,
+                    capabilities: Default::default(),
                 };
                 framed.send(Message::ChallengeResponse(our_response)).await?;
             }
@@ -172,13 +196,15 @@ impl Handshake for TestPeer {
                     restrictions_id,
                     signature: Data::Object(signature),
                     nonce: response_nonce,
+                    capabilities: Default::default(),
                 };
                 framed.send(Message::ChallengeResponse(our_response)).await?;
-                let our_request = ChallengeRequest::new(local_ip.port(), self.node_type(), self.address(), rng.gen());
+                let our_request = self.challenge_request(local_ip.port(), rng.gen());
                 framed.send(Message::ChallengeRequest(our_request)).await?;
 
                 // Listen for the challenge response.
-                let _peer_response = expect_message!(Message::ChallengeResponse, framed, peer_addr);
+                let peer_response = expect_message!(Message::ChallengeResponse, framed, peer_addr);
+                self.check_capabilities(&peer_response.capabilities)?;
             }
         }
 
@@ -18,10 +18,14 @@
 mod common;
 use common::{node::*, test_peer::TestPeer};
 
+use snarkos_account::Account;
 use snarkos_node::{Client, Prover, Validator};
-use snarkos_node_router::Outbound;
+use snarkos_node_router::{
+    messages::{Message, NodeType},
+    Outbound,
+};
 use snarkos_node_tcp::P2P;
-use snarkvm::prelude::{store::helpers::memory::ConsensusMemory, MainnetV0 as CurrentNetwork};
+use snarkvm::prelude::{store::helpers::memory::ConsensusMemory, MainnetV0 as CurrentNetwork, TestRng};
 
 use pea2pea::Pea2Pea;
 
@@ -286,3 +290,31 @@ async fn duplicate_connection_attempts() {
     assert_eq!(node1.router().number_of_connected_peers(), 1);
     assert_eq!(node2.router().number_of_connected_peers(), 1);
 }
+
+#[tokio::test]
+async fn handshake_with_previous_version() {
+    // Spin up a full node.
+    let node = client().await;
+
+    // Spin up test peers on the previous protocol version, which do not support the capabilities.
+    // Note: The test peers fail their handshake if they are sent the capabilities.
+    let version = Message::<CurrentNetwork>::MIN_VERSION;
+    assert!(version < Message::<CurrentNetwork>::CAPABILITIES_VERSION);
+    let rng = &mut TestRng::default();
+    let initiator = TestPeer::new_with_version(NodeType::Client, Account::new(rng).unwrap(), version).await;
+    let responder = TestPeer::new_with_version(NodeType::Client, Account::new(rng).unwrap(), version).await;
+
+    // Ensure the handshakes succeed in both directions.
+    assert_connect(initiator, node.clone()).await;
+    assert_connect(node.clone(), responder).await;
+    // A small anti-flakiness buffer.
+    sleep(Duration::from_millis(200)).await;
+
+    // Ensure the node recorded the version of the peers, and that they advertised no capabilities.
+    let peers = node.router().get_connected_peers();
+    assert_eq!(peers.len(), 2);
+    for peer in peers {
+        assert_eq!(peer.version(), version);
+        assert!(peer.capabilities().is_empty());
+    }
+}